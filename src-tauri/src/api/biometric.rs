@@ -0,0 +1,100 @@
+//! Touch ID prompt in front of exchange credential reads (macOS LocalAuthentication).
+//!
+//! Off unless the `require_biometric_unlock` setting is on. A successful prompt unlocks
+//! credential reads for a few minutes, so one sync doesn't ask once per key.
+
+use super::error::ApiError;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long credentials stay readable after a successful prompt
+const UNLOCK_GRACE: Duration = Duration::from_secs(300);
+
+static REQUIRED: AtomicBool = AtomicBool::new(false);
+static UNLOCKED_AT: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Whether this build can show the prompt
+pub fn is_supported() -> bool {
+    cfg!(target_os = "macos")
+}
+
+/// Turn the prompt on or off, following the `require_biometric_unlock` setting
+pub fn set_required(required: bool) -> Result<(), ApiError> {
+    if required && !is_supported() {
+        return Err(ApiError::BiometricError(
+            "Biometric unlock is only available on macOS".to_string(),
+        ));
+    }
+
+    REQUIRED.store(required, Ordering::SeqCst);
+    *UNLOCKED_AT.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    Ok(())
+}
+
+/// Ask for Touch ID (or the account password on Macs without it) before a credential is
+/// read. Passes right away when not required or unlocked within the grace period.
+pub fn authorize(reason: &str) -> Result<(), ApiError> {
+    if !REQUIRED.load(Ordering::SeqCst) {
+        return Ok(());
+    }
+
+    // Held across the prompt so concurrent reads wait for the one prompt shown
+    let mut unlocked_at = UNLOCKED_AT.lock().unwrap_or_else(|e| e.into_inner());
+    if unlocked_at.is_some_and(|at| at.elapsed() < UNLOCK_GRACE) {
+        return Ok(());
+    }
+
+    prompt(reason)?;
+    *unlocked_at = Some(Instant::now());
+    Ok(())
+}
+
+/// Show the system authentication sheet and wait for the user's answer
+#[cfg(target_os = "macos")]
+fn prompt(reason: &str) -> Result<(), ApiError> {
+    use block2::RcBlock;
+    use objc2::runtime::Bool;
+    use objc2_foundation::{NSError, NSString};
+    use objc2_local_authentication::{LAContext, LAPolicy};
+    use std::sync::mpsc;
+
+    let policy = LAPolicy::DeviceOwnerAuthentication;
+    let (tx, rx) = mpsc::channel::<Result<(), String>>();
+
+    unsafe {
+        let context = LAContext::new();
+        if let Err(e) = context.canEvaluatePolicy_error(policy) {
+            return Err(ApiError::BiometricError(format!(
+                "Authentication is not available: {}",
+                e.localizedDescription()
+            )));
+        }
+
+        let reply = RcBlock::new(move |success: Bool, error: *mut NSError| {
+            let result = if success.as_bool() {
+                Ok(())
+            } else {
+                Err(error
+                    .as_ref()
+                    .map(|e| e.localizedDescription().to_string())
+                    .unwrap_or_else(|| "Authentication failed".to_string()))
+            };
+            let _ = tx.send(result);
+        });
+        context.evaluatePolicy_localizedReason_reply(policy, &NSString::from_str(reason), &reply);
+    }
+
+    match rx.recv() {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(message)) => Err(ApiError::BiometricError(message)),
+        Err(_) => Err(ApiError::BiometricError("Authentication was interrupted".to_string())),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn prompt(_reason: &str) -> Result<(), ApiError> {
+    Err(ApiError::BiometricError(
+        "Biometric unlock is only available on macOS".to_string(),
+    ))
+}
@@ -3,16 +3,21 @@ use base64::{engine::general_purpose, Engine as _};
 use hmac::{Hmac, Mac};
 use reqwest::header::{HeaderMap, HeaderValue};
 use sha2::Sha256;
+use std::sync::Arc;
 
 use crate::api::{
-    client::{ExchangeClient, FetchTradesRequest, FetchTradesResponse, RateLimitConfig},
+    client::{ApiKeyPermissions, ExchangeClient, ExchangeEnvironment, FetchTradesRequest, FetchTradesResponse, RateLimitConfig, RawFundingPayment},
+    credentials::Secret,
     error::ApiError,
     rate_limiter::RateLimiter,
+    read_only::{ReadOnlyExchangeClient, ReadOnlyHttpClient, BITGET_READ_PATHS},
+    retry::retry_transient,
+    server_time,
 };
 
 use super::{
-    mapper::map_fill_to_raw_trade,
-    types::{BitgetResponse, FillHistoryData, FillHistoryRequest, BitgetPosition, AllPositionsRequest, PendingOrdersData, PendingOrdersRequest},
+    mapper::{map_account_info_to_permissions, map_bill_to_funding_payment, map_fill_to_raw_trade},
+    types::{AccountBillData, AccountBillRequest, BitgetAccountInfo, BitgetResponse, ServerTimeData, FillHistoryData, FillHistoryRequest, BitgetPosition, AllPositionsRequest, PendingOrdersData, PendingOrdersRequest},
 };
 
 type HmacSha256 = Hmac<Sha256>;
@@ -21,18 +26,26 @@ const BASE_URL: &str = "https://api.bitget.com";
 const FILL_HISTORY_ENDPOINT: &str = "/api/v2/mix/order/fill-history";
 const ALL_POSITIONS_ENDPOINT: &str = "/api/v2/mix/position/all-position";
 const PENDING_ORDERS_ENDPOINT: &str = "/api/v2/mix/order/orders-pending";
+const ACCOUNT_BILL_ENDPOINT: &str = "/api/v2/mix/account/bill";
+const SERVER_TIME_ENDPOINT: &str = "/api/v2/public/time";
+const ACCOUNT_INFO_ENDPOINT: &str = "/api/v2/spot/account/info";
+
+/// Bill business type of funding fee settlements
+const FUNDING_FEE_BUSINESS_TYPE: &str = "contract_settle_fee";
 
 pub struct BitgetClient {
-    api_key: String,
-    api_secret: String,
-    passphrase: String,
-    http_client: reqwest::Client,
-    rate_limiter: RateLimiter,
+    api_key: Secret,
+    api_secret: Secret,
+    passphrase: Secret,
+    /// Demo account: requests carry the `paptrading` header
+    demo: bool,
+    http: ReadOnlyHttpClient,
+    rate_limiter: Arc<RateLimiter>,
 }
 
 impl BitgetClient {
-    pub fn new(api_key: String, api_secret: String, passphrase: String) -> Self {
-        let rate_limiter = RateLimiter::new(RateLimitConfig {
+    pub fn new(api_key: Secret, api_secret: Secret, passphrase: Secret) -> Self {
+        let rate_limiter = RateLimiter::shared("bitget", RateLimitConfig {
             requests_per_second: 10,
             burst_size: 10,
         });
@@ -41,11 +54,22 @@ impl BitgetClient {
             api_key,
             api_secret,
             passphrase,
-            http_client: reqwest::Client::new(),
+            demo: false,
+            http: ReadOnlyHttpClient::new(BASE_URL, BITGET_READ_PATHS),
             rate_limiter,
         }
     }
 
+    /// Connect to the credential's environment. BitGet serves demo accounts from the
+    /// production host, told apart by a header.
+    pub fn with_environment(mut self, environment: &ExchangeEnvironment) -> Self {
+        self.demo = environment.demo;
+        if let Some(base_url) = &environment.base_url {
+            self.http = self.http.with_base_url(base_url);
+        }
+        self
+    }
+
     /// Generate HMAC-SHA256 signature for BitGet API
     fn generate_signature(&self, timestamp: &str, method: &str, request_path: &str, body: &str) -> String {
         // Prehash string: timestamp + method + requestPath + body
@@ -86,17 +110,38 @@ impl BitgetClient {
                 .map_err(|e| ApiError::AuthenticationError(format!("Invalid passphrase: {}", e)))?,
         );
         headers.insert("locale", HeaderValue::from_static("en-US"));
+        if self.demo {
+            headers.insert("paptrading", HeaderValue::from_static("1"));
+        }
 
         Ok(headers)
     }
 
+    /// Current time in milliseconds on BitGet's clock, so a drifting local clock doesn't
+    /// get signed requests rejected
+    async fn server_timestamp(&self) -> i64 {
+        server_time::now_millis("bitget", || self.fetch_server_time()).await
+    }
+
+    /// Public server time endpoint, no signature needed
+    async fn fetch_server_time(&self) -> Result<i64, ApiError> {
+        let response_text = self.http().get(SERVER_TIME_ENDPOINT)?.send().await?.text().await?;
+        let api_response: BitgetResponse<ServerTimeData> = serde_json::from_str(&response_text)
+            .map_err(|e| ApiError::ParseError(format!("Failed to parse server time: {} - Body: {}", e, response_text)))?;
+
+        api_response
+            .data
+            .and_then(|data| data.server_time.parse().ok())
+            .ok_or_else(|| ApiError::ParseError(format!("Invalid server time: {}", response_text)))
+    }
+
     /// Fetch fill history with pagination
     async fn fetch_fill_history(&self, request: &FillHistoryRequest) -> Result<FillHistoryData, ApiError> {
         // Rate limit
         self.rate_limiter.acquire().await;
 
-        // Current timestamp in milliseconds
-        let timestamp = chrono::Utc::now().timestamp_millis().to_string();
+        // Current timestamp in milliseconds, on BitGet's clock
+        let timestamp = self.server_timestamp().await.to_string();
 
         // Build query string
         let mut query_params = vec![format!("productType={}", request.product_type)];
@@ -126,10 +171,9 @@ impl BitgetClient {
         let headers = self.build_headers(&timestamp, &signature)?;
 
         // Make request
-        let url = format!("{}{}", BASE_URL, request_path);
         let response = self
-            .http_client
-            .get(&url)
+            .http()
+            .get(&request_path)?
             .headers(headers)
             .send()
             .await?;
@@ -155,6 +199,83 @@ impl BitgetClient {
 
         // Check response code
         if api_response.code != "00000" {
+            server_time::invalidate_on_timestamp_error("bitget", &api_response.msg);
+            return Err(ApiError::ExchangeError {
+                code: api_response.code,
+                message: api_response.msg,
+            });
+        }
+
+        api_response.data.ok_or_else(|| {
+            ApiError::ParseError("Response data is empty".to_string())
+        })
+    }
+
+    /// Fetch account bills (ledger entries) with pagination
+    async fn fetch_account_bills(&self, request: &AccountBillRequest) -> Result<AccountBillData, ApiError> {
+        // Rate limit
+        self.rate_limiter.acquire().await;
+
+        // Current timestamp in milliseconds, on BitGet's clock
+        let timestamp = self.server_timestamp().await.to_string();
+
+        // Build query string
+        let mut query_params = vec![format!("productType={}", request.product_type)];
+        if let Some(ref business_type) = request.business_type {
+            query_params.push(format!("businessType={}", business_type));
+        }
+        if let Some(ref start_time) = request.start_time {
+            query_params.push(format!("startTime={}", start_time));
+        }
+        if let Some(ref end_time) = request.end_time {
+            query_params.push(format!("endTime={}", end_time));
+        }
+        if let Some(ref id_less_than) = request.id_less_than {
+            query_params.push(format!("idLessThan={}", id_less_than));
+        }
+        if let Some(ref limit) = request.limit {
+            query_params.push(format!("limit={}", limit));
+        }
+
+        let query_string = query_params.join("&");
+        let request_path = format!("{}?{}", ACCOUNT_BILL_ENDPOINT, query_string);
+
+        // Generate signature (GET request, empty body)
+        let signature = self.generate_signature(&timestamp, "GET", &request_path, "");
+
+        // Build headers
+        let headers = self.build_headers(&timestamp, &signature)?;
+
+        // Make request
+        let response = self
+            .http()
+            .get(&request_path)?
+            .headers(headers)
+            .send()
+            .await?;
+
+        // Check status code
+        let status = response.status();
+        if status == 429 {
+            return Err(ApiError::RateLimitError(
+                "Rate limit exceeded. Please wait before retrying.".to_string(),
+            ));
+        }
+
+        if status == 401 || status == 403 {
+            return Err(ApiError::AuthenticationError(
+                "Invalid API credentials or permissions".to_string(),
+            ));
+        }
+
+        // Parse response
+        let response_text = response.text().await?;
+        let api_response: BitgetResponse<AccountBillData> = serde_json::from_str(&response_text)
+            .map_err(|e| ApiError::ParseError(format!("Failed to parse response: {} - Body: {}", e, response_text)))?;
+
+        // Check response code
+        if api_response.code != "00000" {
+            server_time::invalidate_on_timestamp_error("bitget", &api_response.msg);
             return Err(ApiError::ExchangeError {
                 code: api_response.code,
                 message: api_response.msg,
@@ -171,8 +292,8 @@ impl BitgetClient {
         // Rate limit
         self.rate_limiter.acquire().await;
 
-        // Current timestamp in milliseconds
-        let timestamp = chrono::Utc::now().timestamp_millis().to_string();
+        // Current timestamp in milliseconds, on BitGet's clock
+        let timestamp = self.server_timestamp().await.to_string();
 
         // Build query string
         let mut query_params = vec![format!("productType={}", request.product_type)];
@@ -190,10 +311,9 @@ impl BitgetClient {
         let headers = self.build_headers(&timestamp, &signature)?;
 
         // Make request
-        let url = format!("{}{}", BASE_URL, request_path);
         let response = self
-            .http_client
-            .get(&url)
+            .http()
+            .get(&request_path)?
             .headers(headers)
             .send()
             .await?;
@@ -219,6 +339,62 @@ impl BitgetClient {
 
         // Check response code
         if api_response.code != "00000" {
+            server_time::invalidate_on_timestamp_error("bitget", &api_response.msg);
+            return Err(ApiError::ExchangeError {
+                code: api_response.code,
+                message: api_response.msg,
+            });
+        }
+
+        api_response.data.ok_or_else(|| {
+            ApiError::ParseError("Response data is empty".to_string())
+        })
+    }
+
+    /// Fetch the account info of the key's user, including the key's authorities
+    async fn fetch_account_info(&self) -> Result<BitgetAccountInfo, ApiError> {
+        // Rate limit
+        self.rate_limiter.acquire().await;
+
+        // Current timestamp in milliseconds, on BitGet's clock
+        let timestamp = self.server_timestamp().await.to_string();
+
+        // Generate signature (GET request, empty body)
+        let signature = self.generate_signature(&timestamp, "GET", ACCOUNT_INFO_ENDPOINT, "");
+
+        // Build headers
+        let headers = self.build_headers(&timestamp, &signature)?;
+
+        // Make request
+        let response = self
+            .http()
+            .get(ACCOUNT_INFO_ENDPOINT)?
+            .headers(headers)
+            .send()
+            .await?;
+
+        // Check status code
+        let status = response.status();
+        if status == 429 {
+            return Err(ApiError::RateLimitError(
+                "Rate limit exceeded. Please wait before retrying.".to_string(),
+            ));
+        }
+
+        if status == 401 || status == 403 {
+            return Err(ApiError::AuthenticationError(
+                "Invalid API credentials or permissions".to_string(),
+            ));
+        }
+
+        // Parse response
+        let response_text = response.text().await?;
+        let api_response: BitgetResponse<BitgetAccountInfo> = serde_json::from_str(&response_text)
+            .map_err(|e| ApiError::ParseError(format!("Failed to parse response: {} - Body: {}", e, response_text)))?;
+
+        // Check response code
+        if api_response.code != "00000" {
+            server_time::invalidate_on_timestamp_error("bitget", &api_response.msg);
             return Err(ApiError::ExchangeError {
                 code: api_response.code,
                 message: api_response.msg,
@@ -235,8 +411,8 @@ impl BitgetClient {
         // Rate limit
         self.rate_limiter.acquire().await;
 
-        // Current timestamp in milliseconds
-        let timestamp = chrono::Utc::now().timestamp_millis().to_string();
+        // Current timestamp in milliseconds, on BitGet's clock
+        let timestamp = self.server_timestamp().await.to_string();
 
         // Build query string
         let mut query_params = vec![format!("productType={}", request.product_type)];
@@ -257,10 +433,9 @@ impl BitgetClient {
         let headers = self.build_headers(&timestamp, &signature)?;
 
         // Make request
-        let url = format!("{}{}", BASE_URL, request_path);
         let response = self
-            .http_client
-            .get(&url)
+            .http()
+            .get(&request_path)?
             .headers(headers)
             .send()
             .await?;
@@ -286,6 +461,7 @@ impl BitgetClient {
 
         // Check response code
         if api_response.code != "00000" {
+            server_time::invalidate_on_timestamp_error("bitget", &api_response.msg);
             return Err(ApiError::ExchangeError {
                 code: api_response.code,
                 message: api_response.msg,
@@ -298,6 +474,12 @@ impl BitgetClient {
     }
 }
 
+impl ReadOnlyExchangeClient for BitgetClient {
+    fn http(&self) -> &ReadOnlyHttpClient {
+        &self.http
+    }
+}
+
 #[async_trait]
 impl ExchangeClient for BitgetClient {
     fn exchange_name(&self) -> &str {
@@ -310,6 +492,10 @@ impl ExchangeClient for BitgetClient {
         let limit = request.limit.unwrap_or(100);
 
         loop {
+            if request.is_cancelled() {
+                return Err(ApiError::Cancelled);
+            }
+
             let bitget_request = FillHistoryRequest {
                 product_type: "USDT-FUTURES".to_string(), // TODO: Make configurable
                 symbol: request.symbol.clone(),
@@ -319,7 +505,7 @@ impl ExchangeClient for BitgetClient {
                 limit: Some("100".to_string()), // Max per request
             };
 
-            let history_data = self.fetch_fill_history(&bitget_request).await?;
+            let history_data = retry_transient(|| self.fetch_fill_history(&bitget_request)).await?;
 
             // Map fills to raw trades (handle null fillList)
             let empty_vec = vec![];
@@ -348,6 +534,46 @@ impl ExchangeClient for BitgetClient {
         }
     }
 
+    async fn fetch_funding_payments(
+        &self,
+        start_time: Option<i64>,
+        end_time: Option<i64>,
+    ) -> Result<Vec<RawFundingPayment>, ApiError> {
+        let mut payments = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let request = AccountBillRequest {
+                product_type: "USDT-FUTURES".to_string(),
+                business_type: Some(FUNDING_FEE_BUSINESS_TYPE.to_string()),
+                start_time: start_time.map(|ts| ts.to_string()),
+                end_time: end_time.map(|ts| ts.to_string()),
+                id_less_than: cursor.clone(),
+                limit: Some("100".to_string()), // Max per request
+            };
+
+            let data = retry_transient(|| self.fetch_account_bills(&request)).await?;
+
+            let empty_vec = vec![];
+            let bills = data.bills.as_ref().unwrap_or(&empty_vec);
+            for bill in bills {
+                // The filter is applied server-side, double-check in case it is ignored
+                if bill.business_type != FUNDING_FEE_BUSINESS_TYPE {
+                    continue;
+                }
+                match map_bill_to_funding_payment(bill) {
+                    Ok(payment) => payments.push(payment),
+                    Err(e) => eprintln!("Warning: Failed to map BitGet funding bill: {}", e),
+                }
+            }
+
+            if data.end_id.is_none() || bills.is_empty() {
+                return Ok(payments);
+            }
+            cursor = data.end_id.clone();
+        }
+    }
+
     async fn test_credentials(&self) -> Result<bool, ApiError> {
         // Test with a minimal request (fetch 1 trade)
         let request = FillHistoryRequest {
@@ -366,6 +592,19 @@ impl ExchangeClient for BitgetClient {
         }
     }
 
+    async fn fetch_server_time_ms(&self) -> Result<i64, ApiError> {
+        self.fetch_server_time().await
+    }
+
+    fn rate_limit_headroom(&self) -> u32 {
+        self.rate_limiter.headroom()
+    }
+
+    async fn fetch_permissions(&self) -> Result<Option<ApiKeyPermissions>, ApiError> {
+        let info = self.fetch_account_info().await?;
+        Ok(Some(map_account_info_to_permissions(&info)))
+    }
+
     fn rate_limit(&self) -> RateLimitConfig {
         RateLimitConfig {
             requests_per_second: 10,
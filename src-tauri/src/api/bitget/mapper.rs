@@ -1,15 +1,9 @@
-use super::types::BitgetFill;
-use crate::api::client::RawTrade;
+use super::types::{BitgetAccountInfo, BitgetBill, BitgetFill};
+use crate::api::client::{ApiKeyPermissions, RawFundingPayment, RawTrade};
 
-/// Map BitGet fill to RawTrade (only for closing positions)
+/// Map BitGet fill to RawTrade. Opening fills are kept so syncs can group them with
+/// the fills that close the position.
 pub fn map_fill_to_raw_trade(fill: &BitgetFill) -> Result<RawTrade, String> {
-    // Skip opening positions - we only want closing trades with actual PnL
-    if let Some(ref trade_side) = fill.trade_side {
-        if trade_side == "open" {
-            return Err("Skipping opening position (no PnL)".to_string());
-        }
-    }
-
     // Parse price
     let entry_price = fill
         .price_avg
@@ -80,6 +74,14 @@ pub fn map_fill_to_raw_trade(fill: &BitgetFill) -> Result<RawTrade, String> {
         }
     };
 
+    // "open"/"close" in hedge mode; one-way fills ("buy_single", ...) are left to inference
+    let reduce_only = match fill.trade_side.as_deref() {
+        Some("open") => Some(false),
+        Some(side) if side.contains("close") => Some(true),
+        _ if fill.profit.is_some() && pnl.abs() > 0.0 => Some(true),
+        _ => None,
+    };
+
     // Serialize raw JSON for audit trail
     let raw_json = serde_json::to_string(&fill)
         .map_err(|e| format!("Failed to serialize fill: {}", e))?;
@@ -98,10 +100,48 @@ pub fn map_fill_to_raw_trade(fill: &BitgetFill) -> Result<RawTrade, String> {
         leverage: None, // BitGet doesn't provide leverage in fill history
         timestamp,
         close_timestamp,
+        reduce_only,
         raw_json,
     })
 }
 
+/// Map a BitGet funding fee bill to RawFundingPayment
+pub fn map_bill_to_funding_payment(bill: &BitgetBill) -> Result<RawFundingPayment, String> {
+    let amount = bill
+        .amount
+        .parse::<f64>()
+        .map_err(|e| format!("Invalid amount: {}", e))?;
+
+    let timestamp = bill
+        .c_time
+        .parse::<i64>()
+        .map_err(|e| format!("Invalid timestamp: {}", e))?;
+
+    Ok(RawFundingPayment {
+        exchange_bill_id: bill.bill_id.clone(),
+        symbol: bill.symbol.clone(),
+        amount,
+        currency: bill.coin.clone(),
+        timestamp,
+    })
+}
+
+/// Map the account info authorities to ApiKeyPermissions. Any valid key can read.
+pub fn map_account_info_to_permissions(info: &BitgetAccountInfo) -> ApiKeyPermissions {
+    let has = |name: &str| {
+        info.authorities
+            .iter()
+            .any(|authority| authority.to_lowercase().contains(name))
+    };
+
+    ApiKeyPermissions {
+        read: true,
+        trade: has("trade"),
+        withdraw: Some(has("withdraw")),
+        raw: info.authorities.clone(),
+    }
+}
+
 /// Generate fingerprint for deduplication
 #[allow(dead_code)]
 pub fn generate_fingerprint(fill: &BitgetFill) -> String {
@@ -165,6 +205,7 @@ mod tests {
         assert_eq!(raw.position_side, "LONG");
         assert_eq!(raw.exit_price, None);
         assert_eq!(raw.close_timestamp, None);
+        assert_eq!(raw.reduce_only, None);
     }
 
     #[test]
@@ -200,6 +241,7 @@ mod tests {
         assert_eq!(raw.pnl, 156.5);
         assert_eq!(raw.exit_price, Some(3500.0));
         assert_eq!(raw.close_timestamp, Some(1704153600000));
+        assert_eq!(raw.reduce_only, Some(true));
     }
 
     #[test]
@@ -232,4 +274,44 @@ mod tests {
         assert!(fingerprint.contains("order123"));
         assert!(fingerprint.contains("btcusdt"));
     }
+
+    #[test]
+    fn test_map_funding_bill() {
+        let bill = BitgetBill {
+            bill_id: "bill1".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            amount: "-0.4213".to_string(),
+            business_type: "contract_settle_fee".to_string(),
+            coin: "USDT".to_string(),
+            c_time: "1704096000000".to_string(),
+        };
+
+        let payment = map_bill_to_funding_payment(&bill).unwrap();
+        assert_eq!(payment.exchange_bill_id, "bill1");
+        assert_eq!(payment.amount, -0.4213);
+        assert_eq!(payment.currency, "USDT");
+        assert_eq!(payment.timestamp, 1704096000000);
+    }
+
+    #[test]
+    fn test_map_account_info_permissions() {
+        let info = BitgetAccountInfo {
+            user_id: "12345".to_string(),
+            authorities: vec!["readonly".to_string()],
+            ips: None,
+        };
+        let permissions = map_account_info_to_permissions(&info);
+        assert!(permissions.read && !permissions.trade);
+        assert_eq!(permissions.withdraw, Some(false));
+        assert!(permissions.warnings().is_empty());
+
+        let info = BitgetAccountInfo {
+            authorities: vec!["Trade".to_string(), "withdraw".to_string()],
+            ..info
+        };
+        let permissions = map_account_info_to_permissions(&info);
+        assert!(permissions.trade);
+        assert_eq!(permissions.withdraw, Some(true));
+        assert_eq!(permissions.warnings().len(), 2);
+    }
 }
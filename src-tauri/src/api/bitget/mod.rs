@@ -1,5 +1,6 @@
 pub mod client;
 pub mod mapper;
+pub mod ticker;
 pub mod types;
 pub mod websocket;
 
@@ -0,0 +1,95 @@
+use super::websocket::{SubscribeArgs, WsMessage, WsResponse};
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::time::{interval, Duration};
+use tokio_tungstenite::tungstenite::Message;
+
+const PUBLIC_WS_URL: &str = "wss://ws.bitget.com/v2/ws/public";
+
+/// Ticker data from the public ticker channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TickerData {
+    #[serde(rename = "instId")]
+    pub inst_id: String,
+    #[serde(rename = "lastPr")]
+    pub last_pr: String,
+}
+
+impl TickerData {
+    pub fn last_price(&self) -> Option<f64> {
+        self.last_pr.parse().ok().filter(|price: &f64| *price > 0.0)
+    }
+}
+
+/// Stream last prices of USDT-M futures symbols; no credentials needed.
+///
+/// Returns once the connection ends or `on_tick` returns false.
+pub async fn stream_tickers<F>(
+    symbols: &[String],
+    mut on_tick: F,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+where
+    F: FnMut(TickerData) -> bool + Send,
+{
+    let (ws_stream, _) = crate::api::proxy::connect_websocket(PUBLIC_WS_URL).await?;
+    let (mut write, mut read) = ws_stream.split();
+
+    let subscribe_msg = WsMessage::Subscribe {
+        args: symbols
+            .iter()
+            .map(|symbol| SubscribeArgs {
+                inst_type: "USDT-FUTURES".to_string(),
+                channel: "ticker".to_string(),
+                inst_id: Some(symbol.clone()),
+            })
+            .collect(),
+    };
+    write.send(Message::Text(serde_json::to_string(&subscribe_msg)?)).await?;
+    println!("Subscribed to tickers of {}", symbols.join(", "));
+
+    let mut ping_interval = interval(Duration::from_secs(30));
+    loop {
+        tokio::select! {
+            _ = ping_interval.tick() => {
+                write.send(Message::Text("ping".to_string())).await?;
+            }
+            msg = read.next() => {
+                match msg {
+                    Some(Ok(Message::Text(text))) => {
+                        if text == "pong" {
+                            continue;
+                        }
+                        let response: WsResponse = match serde_json::from_str(&text) {
+                            Ok(response) => response,
+                            Err(e) => {
+                                eprintln!("Failed to parse ticker message: {} - Text: {}", e, text);
+                                continue;
+                            }
+                        };
+                        // An unknown symbol fails its own subscription only
+                        if response.event == Some("error".to_string()) {
+                            eprintln!("Ticker subscription failed: {:?}", response.msg);
+                            continue;
+                        }
+                        for item in response.data.unwrap_or_default() {
+                            match serde_json::from_value::<TickerData>(item) {
+                                Ok(ticker) => {
+                                    if !on_tick(ticker) {
+                                        return Ok(());
+                                    }
+                                }
+                                Err(e) => eprintln!("Failed to parse ticker data: {}", e),
+                            }
+                        }
+                    }
+                    Some(Ok(Message::Ping(_))) => {
+                        write.send(Message::Pong(vec![])).await?;
+                    }
+                    Some(Ok(Message::Close(_))) | None => return Ok(()),
+                    Some(Err(e)) => return Err(e.into()),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
@@ -144,6 +144,69 @@ pub struct FillHistoryRequest {
     pub limit: Option<String>,
 }
 
+/// BitGet account bill data wrapper
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountBillData {
+    #[serde(default)]
+    pub bills: Option<Vec<BitgetBill>>,
+    #[serde(rename = "endId")]
+    pub end_id: Option<String>,
+}
+
+/// BitGet account bill (ledger entry)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitgetBill {
+    /// Bill ID
+    #[serde(rename = "billId")]
+    pub bill_id: String,
+
+    /// Symbol (e.g., "BTCUSDT", empty for transfers)
+    #[serde(default)]
+    pub symbol: String,
+
+    /// Amount (negative when paid)
+    pub amount: String,
+
+    /// Business type, "contract_settle_fee" for funding
+    #[serde(rename = "businessType")]
+    pub business_type: String,
+
+    /// Coin (e.g., "USDT")
+    pub coin: String,
+
+    /// Creation time (Unix milliseconds)
+    #[serde(rename = "cTime")]
+    pub c_time: String,
+}
+
+/// Request for account bills
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountBillRequest {
+    /// Product type (required)
+    #[serde(rename = "productType")]
+    pub product_type: String,
+
+    /// Business type filter (optional)
+    #[serde(rename = "businessType", skip_serializing_if = "Option::is_none")]
+    pub business_type: Option<String>,
+
+    /// Start time (Unix milliseconds, optional)
+    #[serde(rename = "startTime", skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<String>,
+
+    /// End time (Unix milliseconds, optional)
+    #[serde(rename = "endTime", skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<String>,
+
+    /// Pagination: query bills with IDs less than this value
+    #[serde(rename = "idLessThan", skip_serializing_if = "Option::is_none")]
+    pub id_less_than: Option<String>,
+
+    /// Limit (max 100)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<String>,
+}
+
 /// BitGet all positions data wrapper
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
@@ -228,6 +291,26 @@ pub struct BitgetPosition {
     pub u_time: String,
 }
 
+/// Account information of the API key's user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitgetAccountInfo {
+    #[serde(rename = "userId")]
+    pub user_id: String,
+    /// Permissions of the API key (e.g. "readonly", "trade", "transfer", "withdraw")
+    #[serde(default)]
+    pub authorities: Vec<String>,
+    /// IP whitelist, comma separated
+    #[serde(default)]
+    pub ips: Option<String>,
+}
+
+/// Public server time response data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerTimeData {
+    #[serde(rename = "serverTime")]
+    pub server_time: String,
+}
+
 /// Request for all positions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AllPositionsRequest {
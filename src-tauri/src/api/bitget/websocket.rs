@@ -1,3 +1,6 @@
+use super::types::BitgetPosition;
+use crate::api::client::ExchangeEnvironment;
+use crate::api::credentials::Secret;
 use base64::{engine::general_purpose, Engine as _};
 use futures::{SinkExt, StreamExt};
 use hmac::{Hmac, Mac};
@@ -6,13 +9,15 @@ use sha2::Sha256;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio::time::{interval, Duration};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio_tungstenite::tungstenite::Message;
 
 type HmacSha256 = Hmac<Sha256>;
 
 const WS_URL: &str = "wss://ws.bitget.com/v2/ws/private";
+const DEMO_WS_URL: &str = "wss://wspap.bitget.com/v2/ws/private";
 
-/// WebSocket message types
+/// WebSocket message types. Only login and (un)subscribe can be sent, never the trade op,
+/// keeping the private channel read-only like the HTTP clients.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "op")]
 pub enum WsMessage {
@@ -27,9 +32,9 @@ pub enum WsMessage {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoginArgs {
     #[serde(rename = "apiKey")]
-    pub api_key: String,
+    pub api_key: Secret,
     #[serde(rename = "passphrase")]
-    pub passphrase: String,
+    pub passphrase: Secret,
     #[serde(rename = "timestamp")]
     pub timestamp: String,
     #[serde(rename = "sign")]
@@ -114,32 +119,259 @@ pub struct PositionData {
     pub u_time: String,
 }
 
+/// Identifies a position. In hedge mode a symbol has a long and a short position at once,
+/// so the side is part of the key rather than trusting the position id alone.
+pub type PositionKey = (String, String); // (posId, holdSide)
+
+impl PositionData {
+    pub fn key(&self) -> PositionKey {
+        (self.pos_id.clone(), self.hold_side.clone())
+    }
+
+    /// Position listed by the REST all-positions endpoint; None when it leaves the id out
+    pub fn from_rest(position: &BitgetPosition) -> Option<Self> {
+        let hold_mode = match position.pos_mode.as_deref() {
+            Some("hedge_mode") => "double_hold",
+            _ => "single_hold",
+        };
+        Some(Self {
+            pos_id: position.pos_id.clone()?,
+            inst_id: position.symbol.clone(),
+            inst_type: position.product_type.clone().unwrap_or_else(|| "USDT-FUTURES".to_string()),
+            margin_coin: position.margin_coin.clone(),
+            margin_size: position.margin_size.clone(),
+            margin_mode: position.margin_mode.clone(),
+            hold_side: position.hold_side.clone(),
+            hold_mode: hold_mode.to_string(),
+            total: position.total.clone(),
+            available: position.available.clone(),
+            locked: position.locked.clone(),
+            average_open_price: position.open_avg_price.clone(),
+            leverage: position.leverage.clone(),
+            achieved_profits: position.achieved_profits.clone().unwrap_or_else(|| "0".to_string()),
+            unrealized_pl: position.unrealized_pnl.clone(),
+            unrealized_plr: "0".to_string(),
+            liq_px: position.liquidation_price.clone(),
+            keep_margin_rate: "0".to_string(),
+            market_price: position.mark_price.clone(),
+            c_time: position.c_time.clone(),
+            u_time: position.u_time.clone(),
+        })
+    }
+}
+
+/// Plan order data from the orders-algo channel (TP/SL and trigger orders)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanOrderData {
+    #[serde(rename = "orderId")]
+    pub order_id: String,
+    #[serde(rename = "instId")]
+    pub inst_id: String,
+    #[serde(rename = "planType")]
+    pub plan_type: String, // "pos_profit", "pos_loss", "profit_plan", "loss_plan", ...
+    #[serde(rename = "triggerPrice")]
+    pub trigger_price: String,
+    #[serde(rename = "size", default)]
+    pub size: String,
+    #[serde(rename = "posSide", default)]
+    pub pos_side: String, // "long", "short", "net"
+    #[serde(rename = "status")]
+    pub status: String,
+}
+
+impl PlanOrderData {
+    /// Still waiting for its trigger
+    fn is_live(&self) -> bool {
+        matches!(self.status.as_str(), "live" | "not_trigger")
+    }
+
+    /// Attached to the given position
+    fn belongs_to(&self, position: &PositionData) -> bool {
+        self.inst_id == position.inst_id
+            && (self.pos_side == position.hold_side || self.pos_side == "net")
+    }
+}
+
+/// Stop-loss and take-profits currently set on a position
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PositionTpSl {
+    pub stop_loss: Option<f64>,
+    /// (trigger price, percent of the position), nearest target first
+    pub take_profits: Vec<(f64, f64)>,
+}
+
+impl PositionTpSl {
+    /// Build from the live plan orders of a position. A whole-position TP/SL counts as 100%,
+    /// a partial one as its size over the position total. The whole-position SL wins over
+    /// partial ones.
+    fn from_orders<'a>(
+        position: &PositionData,
+        orders: impl IntoIterator<Item = &'a PlanOrderData>,
+    ) -> Self {
+        let total: f64 = position.total.parse().unwrap_or(0.0);
+        let mut tp_sl = Self::default();
+        let mut partial_sl = None;
+
+        for order in orders {
+            let Ok(price) = order.trigger_price.parse::<f64>() else {
+                continue;
+            };
+            match order.plan_type.as_str() {
+                "pos_loss" => tp_sl.stop_loss = Some(price),
+                "loss_plan" => {
+                    partial_sl.get_or_insert(price);
+                }
+                "pos_profit" => tp_sl.take_profits.push((price, 100.0)),
+                "profit_plan" => {
+                    let size: f64 = order.size.parse().unwrap_or(0.0);
+                    let percent = if total > 0.0 { (size / total * 100.0).min(100.0) } else { 0.0 };
+                    tp_sl.take_profits.push((price, percent));
+                }
+                _ => {}
+            }
+        }
+
+        if tp_sl.stop_loss.is_none() {
+            tp_sl.stop_loss = partial_sl;
+        }
+        if position.hold_side == "short" {
+            tp_sl.take_profits.sort_by(|a, b| b.0.total_cmp(&a.0));
+        } else {
+            tp_sl.take_profits.sort_by(|a, b| a.0.total_cmp(&b.0));
+        }
+        tp_sl
+    }
+}
+
+/// Trade execution from the fill channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FillData {
+    #[serde(rename = "tradeId")]
+    pub trade_id: String,
+    #[serde(rename = "symbol")]
+    pub symbol: String,
+    #[serde(rename = "side")]
+    pub side: String, // "buy", "sell"
+    #[serde(rename = "tradeSide", default)]
+    pub trade_side: String, // "open", "close" in hedge mode, "buy_single"/"sell_single" in one-way mode
+    #[serde(rename = "price")]
+    pub price: String,
+    #[serde(rename = "baseVolume")]
+    pub base_volume: String,
+}
+
+impl FillData {
+    /// Hold side of the position the fill belongs to and whether it opens (or adds to) it.
+    /// One-way mode fills don't say, so `current_side` is the side of the open position on
+    /// the symbol, if any: a fill against it closes, anything else opens.
+    fn position_effect(&self, current_side: Option<&str>) -> (String, bool) {
+        let buy = self.side == "buy";
+        match self.trade_side.as_str() {
+            "open" => ((if buy { "long" } else { "short" }).to_string(), true),
+            "close" => ((if buy { "short" } else { "long" }).to_string(), false),
+            _ => match current_side {
+                Some("long") if !buy => ("long".to_string(), false),
+                Some("short") if buy => ("short".to_string(), false),
+                _ => ((if buy { "long" } else { "short" }).to_string(), true),
+            },
+        }
+    }
+}
+
+/// Executed (price, quantity) of a position's entries and exits, from the fill channel
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PositionFills {
+    pub entries: Vec<(f64, f64)>,
+    pub exits: Vec<(f64, f64)>,
+}
+
+impl PositionFills {
+    /// Volume weighted average price of fills, None without any volume
+    pub fn average_price(fills: &[(f64, f64)]) -> Option<f64> {
+        let quantity: f64 = fills.iter().map(|(_, qty)| qty).sum();
+        if quantity <= 0.0 {
+            return None;
+        }
+        Some(fills.iter().map(|(price, qty)| price * qty).sum::<f64>() / quantity)
+    }
+}
+
 /// Position change event
 #[derive(Debug, Clone)]
 pub enum PositionEvent {
-    Opened(PositionData),
-    Updated(PositionData),
-    Closed(PositionData),
+    /// Carries the entry fills seen so far, they may also arrive after the position
+    Opened(PositionData, PositionTpSl, PositionFills),
+    /// Carries the size closed since the previous update, 0 when the position grew or only
+    /// its PnL moved
+    Updated(PositionData, f64),
+    /// Carries every fill of the position seen over its life
+    Closed(PositionData, PositionFills),
+    /// TP/SL orders of an open position were placed, moved, cancelled or triggered
+    TpSlChanged(PositionData, PositionTpSl),
+}
+
+/// State of a WebSocket connection, for health reporting
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConnectionHealth {
+    pub connected: bool,
+    /// Unix seconds of the last message received, pongs included
+    pub last_message_at: Option<i64>,
+    /// Times the connection was re-established after dropping
+    pub reconnect_count: u32,
+    pub subscribed_channels: Vec<String>,
+    pub last_error: Option<String>,
+}
+
+/// Aborts a spawned task when dropped
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
 }
 
 /// WebSocket client for Bitget
 pub struct BitgetWebSocketClient {
-    api_key: String,
-    api_secret: String,
-    passphrase: String,
-    positions: Arc<Mutex<std::collections::HashMap<String, PositionData>>>,
+    api_key: Secret,
+    api_secret: Secret,
+    passphrase: Secret,
+    ws_url: &'static str,
+    positions: Arc<Mutex<std::collections::HashMap<PositionKey, PositionData>>>,
+    plan_orders: Arc<Mutex<std::collections::HashMap<String, PlanOrderData>>>, // live only
+    fills: Arc<Mutex<std::collections::HashMap<(String, String), PositionFills>>>, // (instId, holdSide)
+    health: Arc<std::sync::Mutex<ConnectionHealth>>,
 }
 
 impl BitgetWebSocketClient {
-    pub fn new(api_key: String, api_secret: String, passphrase: String) -> Self {
+    pub fn new(api_key: Secret, api_secret: Secret, passphrase: Secret) -> Self {
         Self {
             api_key,
             api_secret,
             passphrase,
+            ws_url: WS_URL,
             positions: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            plan_orders: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            fills: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            health: Arc::new(std::sync::Mutex::new(ConnectionHealth::default())),
         }
     }
 
+    /// Connect to the demo servers for a demo account
+    pub fn with_environment(mut self, environment: &ExchangeEnvironment) -> Self {
+        self.ws_url = if environment.demo { DEMO_WS_URL } else { WS_URL };
+        self
+    }
+
+    /// Shared health of this client's connection, updated while connect runs
+    pub fn health(&self) -> Arc<std::sync::Mutex<ConnectionHealth>> {
+        Arc::clone(&self.health)
+    }
+
+    fn update_health(&self, update: impl FnOnce(&mut ConnectionHealth)) {
+        update(&mut self.health.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+
     /// Generate signature for WebSocket login
     fn generate_signature(&self, timestamp: &str) -> String {
         let prehash = format!("{}GET/user/verify", timestamp);
@@ -158,13 +390,19 @@ impl BitgetWebSocketClient {
     where
         F: FnMut(PositionEvent) + Send + 'static,
     {
-        let (ws_stream, _) = connect_async(WS_URL).await?;
-        println!("WebSocket connected to {}", WS_URL);
+        self.update_health(|health| {
+            health.connected = false;
+            health.subscribed_channels.clear();
+        });
+
+        let (ws_stream, _) = crate::api::proxy::connect_websocket(self.ws_url).await?;
+        println!("WebSocket connected to {}", self.ws_url);
 
         let (mut write, mut read) = ws_stream.split();
 
         // Send login message
-        let timestamp = chrono::Utc::now().timestamp().to_string();
+        // Seconds, on BitGet's clock as last measured by the REST client
+        let timestamp = (crate::api::server_time::cached_now_millis("bitget") / 1000).to_string();
         let signature = self.generate_signature(&timestamp);
 
         let login_msg = WsMessage::Login {
@@ -190,6 +428,10 @@ impl BitgetWebSocketClient {
                         && response.code == Some("0".to_string())
                     {
                         println!("Successfully logged in to WebSocket");
+                        self.update_health(|health| {
+                            health.connected = true;
+                            health.last_message_at = Some(chrono::Utc::now().timestamp());
+                        });
                     } else {
                         return Err(format!("Login failed: {:?}", response.msg).into());
                     }
@@ -198,26 +440,40 @@ impl BitgetWebSocketClient {
             }
         }
 
-        // Subscribe to positions channel
+        // Subscribe to positions and plan orders (TP/SL) channels
         let subscribe_msg = WsMessage::Subscribe {
-            args: vec![SubscribeArgs {
-                inst_type: "USDT-FUTURES".to_string(),
-                channel: "positions".to_string(),
-                inst_id: None, // Subscribe to all positions
-            }],
+            args: vec![
+                SubscribeArgs {
+                    inst_type: "USDT-FUTURES".to_string(),
+                    channel: "positions".to_string(),
+                    inst_id: None, // Subscribe to all positions
+                },
+                SubscribeArgs {
+                    inst_type: "USDT-FUTURES".to_string(),
+                    channel: "orders-algo".to_string(),
+                    inst_id: None, // Subscribe to all plan orders
+                },
+                SubscribeArgs {
+                    inst_type: "USDT-FUTURES".to_string(),
+                    channel: "fill".to_string(),
+                    inst_id: None, // Subscribe to all fills
+                },
+            ],
         };
 
         let subscribe_json = serde_json::to_string(&subscribe_msg)?;
         write.send(Message::Text(subscribe_json)).await?;
-        println!("Subscribed to positions channel");
+        println!("Subscribed to positions, orders-algo and fill channels");
 
-        // Clone positions for the reader task
+        // Clone positions, plan orders and fills for the reader task
         let positions = Arc::clone(&self.positions);
+        let plan_orders = Arc::clone(&self.plan_orders);
+        let fills = Arc::clone(&self.fills);
 
-        // Spawn ping task
+        // Spawn ping task, stopped when this connection ends or is dropped by a watchdog
         let write = Arc::new(Mutex::new(write));
         let write_clone = Arc::clone(&write);
-        tokio::spawn(async move {
+        let _ping_task = AbortOnDrop(tokio::spawn(async move {
             let mut ping_interval = interval(Duration::from_secs(30));
             loop {
                 ping_interval.tick().await;
@@ -227,12 +483,15 @@ impl BitgetWebSocketClient {
                     break;
                 }
             }
-        });
+        }));
 
         // Read messages
         while let Some(msg) = read.next().await {
             match msg {
                 Ok(Message::Text(text)) => {
+                    self.update_health(|health| {
+                        health.last_message_at = Some(chrono::Utc::now().timestamp())
+                    });
                     if text == "pong" {
                         continue;
                     }
@@ -243,30 +502,71 @@ impl BitgetWebSocketClient {
                             // Handle subscription confirmation
                             if response.event == Some("subscribe".to_string()) {
                                 println!("Subscription confirmed: {:?}", response.arg);
+                                if let Some(channel) = response.arg.and_then(|arg| arg.channel) {
+                                    self.update_health(|health| {
+                                        if !health.subscribed_channels.contains(&channel) {
+                                            health.subscribed_channels.push(channel);
+                                        }
+                                    });
+                                }
                                 continue;
                             }
 
                             // Handle position updates
-                            if let Some(data) = response.data {
-                                if let Some(arg) = &response.arg {
-                                    if arg.channel == Some("positions".to_string()) {
-                                        for item in data {
-                                            match serde_json::from_value::<PositionData>(item) {
-                                                Ok(position) => {
-                                                    let event =
-                                                        self.process_position_update(position, &positions)
-                                                            .await;
-                                                    if let Some(event) = event {
-                                                        event_handler(event);
-                                                    }
-                                                }
-                                                Err(e) => {
-                                                    eprintln!(
-                                                        "Failed to parse position data: {}",
-                                                        e
-                                                    );
+                            if let Some(data) = response.data
+                                && let Some(arg) = &response.arg
+                            {
+                                if arg.channel == Some("positions".to_string()) {
+                                    for item in data {
+                                        match serde_json::from_value::<PositionData>(item) {
+                                            Ok(position) => {
+                                                let event = self
+                                                    .process_position_update(
+                                                        position,
+                                                        &positions,
+                                                        &plan_orders,
+                                                        &fills,
+                                                    )
+                                                    .await;
+                                                if let Some(event) = event {
+                                                    event_handler(event);
                                                 }
                                             }
+                                            Err(e) => {
+                                                eprintln!(
+                                                    "Failed to parse position data: {}",
+                                                    e
+                                                );
+                                            }
+                                        }
+                                    }
+                                } else if arg.channel == Some("orders-algo".to_string()) {
+                                    let orders: Vec<PlanOrderData> = data
+                                        .into_iter()
+                                        .filter_map(|item| match serde_json::from_value(item) {
+                                            Ok(order) => Some(order),
+                                            Err(e) => {
+                                                eprintln!("Failed to parse plan order data: {}", e);
+                                                None
+                                            }
+                                        })
+                                        .collect();
+                                    for event in self
+                                        .process_plan_order_update(orders, &positions, &plan_orders)
+                                        .await
+                                    {
+                                        event_handler(event);
+                                    }
+                                } else if arg.channel == Some("fill".to_string()) {
+                                    for item in data {
+                                        match serde_json::from_value::<FillData>(item) {
+                                            Ok(fill) => {
+                                                self.process_fill_update(fill, &positions, &fills)
+                                                    .await
+                                            }
+                                            Err(e) => {
+                                                eprintln!("Failed to parse fill data: {}", e);
+                                            }
                                         }
                                     }
                                 }
@@ -293,40 +593,181 @@ impl BitgetWebSocketClient {
             }
         }
 
+        self.update_health(|health| health.connected = false);
         Ok(())
     }
 
+    /// Seed the known positions, e.g. with the snapshot persisted by a previous run
+    pub async fn restore_positions(&self, restored: Vec<PositionData>) {
+        let mut positions = self.positions.lock().await;
+        for position in restored {
+            positions.insert(position.key(), position);
+        }
+    }
+
+    /// Diff a REST snapshot of open positions against the known ones and return the events
+    /// missed while disconnected: Closed for known positions no longer listed, at their last
+    /// known state, and Opened for listed positions not known yet. A listed position without
+    /// an id only vouches for a known position of the same symbol and side.
+    pub async fn replay_snapshot(&self, snapshot: &[BitgetPosition]) -> Vec<PositionEvent> {
+        let is_open = |listed: &BitgetPosition| listed.total.parse::<f64>().unwrap_or(0.0) > 0.0;
+        let known: Vec<PositionData> = self.positions.lock().await.values().cloned().collect();
+        let mut events = Vec::new();
+
+        for position in known {
+            let listed = snapshot.iter().any(|listed| {
+                is_open(listed)
+                    && listed.hold_side == position.hold_side
+                    && match &listed.pos_id {
+                        Some(pos_id) => *pos_id == position.pos_id,
+                        None => listed.symbol == position.inst_id,
+                    }
+            });
+            if !listed {
+                let closed = PositionData {
+                    total: "0".to_string(),
+                    ..position
+                };
+                let event = self
+                    .process_position_update(closed, &self.positions, &self.plan_orders, &self.fills)
+                    .await;
+                events.extend(event);
+            }
+        }
+
+        for listed in snapshot.iter().filter(|listed| is_open(listed)) {
+            let Some(position) = PositionData::from_rest(listed) else {
+                continue;
+            };
+            if self.positions.lock().await.contains_key(&position.key()) {
+                continue;
+            }
+            let event = self
+                .process_position_update(position, &self.positions, &self.plan_orders, &self.fills)
+                .await;
+            events.extend(event);
+        }
+
+        events
+    }
+
     /// Process position update and detect changes
     async fn process_position_update(
         &self,
         position: PositionData,
-        positions: &Arc<Mutex<std::collections::HashMap<String, PositionData>>>,
+        positions: &Arc<Mutex<std::collections::HashMap<PositionKey, PositionData>>>,
+        plan_orders: &Arc<Mutex<std::collections::HashMap<String, PlanOrderData>>>,
+        fills: &Arc<Mutex<std::collections::HashMap<(String, String), PositionFills>>>,
     ) -> Option<PositionEvent> {
         let mut positions_map = positions.lock().await;
-        let pos_id = position.pos_id.clone();
+        let key = position.key();
 
         // Parse total position size
         let total: f64 = position.total.parse().unwrap_or(0.0);
 
         // Check if position is closed (total = 0)
         if total == 0.0 {
-            if let Some(old_position) = positions_map.remove(&pos_id) {
-                return Some(PositionEvent::Closed(old_position));
+            if let Some(old_position) = positions_map.remove(&key) {
+                let key = (old_position.inst_id.clone(), old_position.hold_side.clone());
+                let position_fills = fills.lock().await.remove(&key).unwrap_or_default();
+                return Some(PositionEvent::Closed(old_position, position_fills));
             }
             return None;
         }
 
         // Check if this is a new position
-        if let Some(_old_position) = positions_map.get(&pos_id) {
+        if let Some(old_position) = positions_map.get(&key) {
             // Position exists - this is an update
-            positions_map.insert(pos_id, position.clone());
-            Some(PositionEvent::Updated(position))
+            let old_total: f64 = old_position.total.parse().unwrap_or(0.0);
+            let closed = (old_total - total).max(0.0);
+            positions_map.insert(key, position.clone());
+            Some(PositionEvent::Updated(position, closed))
+        } else {
+            // New position opened, with any TP/SL placed along with the entry order
+            positions_map.insert(key, position.clone());
+            let orders_map = plan_orders.lock().await;
+            let tp_sl = PositionTpSl::from_orders(
+                &position,
+                orders_map.values().filter(|order| order.belongs_to(&position)),
+            );
+
+            // Exits left over from the previous position on this side arrived after its close
+            let key = (position.inst_id.clone(), position.hold_side.clone());
+            let mut fills_map = fills.lock().await;
+            let position_fills = fills_map.entry(key).or_default();
+            position_fills.exits.clear();
+            let position_fills = position_fills.clone();
+
+            Some(PositionEvent::Opened(position, tp_sl, position_fills))
+        }
+    }
+
+    /// Record a fill against the position it opens, adds to or closes
+    async fn process_fill_update(
+        &self,
+        fill: FillData,
+        positions: &Arc<Mutex<std::collections::HashMap<PositionKey, PositionData>>>,
+        fills: &Arc<Mutex<std::collections::HashMap<(String, String), PositionFills>>>,
+    ) {
+        let (Ok(price), Ok(quantity)) = (fill.price.parse::<f64>(), fill.base_volume.parse::<f64>()) else {
+            eprintln!("Invalid fill {}: price {} volume {}", fill.trade_id, fill.price, fill.base_volume);
+            return;
+        };
+
+        let current_side = positions
+            .lock()
+            .await
+            .values()
+            .find(|position| position.inst_id == fill.symbol)
+            .map(|position| position.hold_side.clone());
+        let (hold_side, opens) = fill.position_effect(current_side.as_deref());
+
+        let mut fills_map = fills.lock().await;
+        let position_fills = fills_map.entry((fill.symbol, hold_side)).or_default();
+        if opens {
+            position_fills.entries.push((price, quantity));
         } else {
-            // New position opened
-            positions_map.insert(pos_id, position.clone());
-            Some(PositionEvent::Opened(position))
+            position_fills.exits.push((price, quantity));
         }
     }
+
+    /// Process plan order updates and report the new TP/SL of each affected open position
+    async fn process_plan_order_update(
+        &self,
+        orders: Vec<PlanOrderData>,
+        positions: &Arc<Mutex<std::collections::HashMap<PositionKey, PositionData>>>,
+        plan_orders: &Arc<Mutex<std::collections::HashMap<String, PlanOrderData>>>,
+    ) -> Vec<PositionEvent> {
+        let positions_map = positions.lock().await;
+        let mut orders_map = plan_orders.lock().await;
+
+        let mut affected: Vec<&PositionData> = Vec::new();
+        for order in orders {
+            for position in positions_map.values().filter(|p| order.belongs_to(p)) {
+                if !affected.iter().any(|p| p.key() == position.key()) {
+                    affected.push(position);
+                }
+            }
+
+            // Triggered, cancelled and failed orders no longer protect the position
+            if order.is_live() {
+                orders_map.insert(order.order_id.clone(), order);
+            } else {
+                orders_map.remove(&order.order_id);
+            }
+        }
+
+        affected
+            .into_iter()
+            .map(|position| {
+                let tp_sl = PositionTpSl::from_orders(
+                    position,
+                    orders_map.values().filter(|order| order.belongs_to(position)),
+                );
+                PositionEvent::TpSlChanged(position.clone(), tp_sl)
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -336,12 +777,153 @@ mod tests {
     #[test]
     fn test_generate_signature() {
         let client = BitgetWebSocketClient::new(
-            "test_key".to_string(),
-            "test_secret".to_string(),
-            "test_pass".to_string(),
+            Secret::new("test_key".to_string()),
+            Secret::new("test_secret".to_string()),
+            Secret::new("test_pass".to_string()),
         );
         let timestamp = "1234567890";
         let signature = client.generate_signature(timestamp);
         assert!(!signature.is_empty());
     }
+
+    fn position(hold_side: &str, total: &str) -> PositionData {
+        serde_json::from_value(serde_json::json!({
+            "posId": "1", "instId": "BTCUSDT", "instType": "USDT-FUTURES", "marginCoin": "USDT",
+            "marginSize": "100", "marginMode": "crossed", "holdSide": hold_side,
+            "holdMode": "double_hold", "total": total, "available": total, "locked": "0",
+            "averageOpenPrice": "100", "leverage": "10", "achievedProfits": "0",
+            "unrealizedPL": "0", "unrealizedPLR": "0", "liqPx": "0", "keepMarginRate": "0",
+            "marketPrice": "100", "cTime": "0", "uTime": "0"
+        }))
+        .unwrap()
+    }
+
+    fn fill(side: &str, trade_side: &str) -> FillData {
+        FillData {
+            trade_id: "1".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            side: side.to_string(),
+            trade_side: trade_side.to_string(),
+            price: "100".to_string(),
+            base_volume: "1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_fill_position_effect() {
+        // Hedge mode says it
+        assert_eq!(fill("buy", "open").position_effect(None), ("long".to_string(), true));
+        assert_eq!(fill("buy", "close").position_effect(Some("long")), ("short".to_string(), false));
+        assert_eq!(fill("sell", "close").position_effect(None), ("long".to_string(), false));
+
+        // One-way mode goes by the open position
+        assert_eq!(fill("sell", "sell_single").position_effect(Some("long")), ("long".to_string(), false));
+        assert_eq!(fill("buy", "buy_single").position_effect(Some("long")), ("long".to_string(), true));
+        assert_eq!(fill("sell", "sell_single").position_effect(None), ("short".to_string(), true));
+
+        assert_eq!(PositionFills::average_price(&[(100.0, 1.0), (110.0, 3.0)]), Some(107.5));
+        assert_eq!(PositionFills::average_price(&[]), None);
+    }
+
+    fn plan_order(plan_type: &str, trigger_price: &str, size: &str) -> PlanOrderData {
+        PlanOrderData {
+            order_id: format!("{}-{}", plan_type, trigger_price),
+            inst_id: "BTCUSDT".to_string(),
+            plan_type: plan_type.to_string(),
+            trigger_price: trigger_price.to_string(),
+            size: size.to_string(),
+            pos_side: "long".to_string(),
+            status: "live".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_tp_sl_from_orders() {
+        let long = position("long", "2");
+        let orders = [
+            plan_order("profit_plan", "120", "1"),
+            plan_order("loss_plan", "92", "1"),
+            plan_order("profit_plan", "110", "1"),
+            plan_order("pos_loss", "90", ""),
+            plan_order("normal_plan", "105", "1"),
+        ];
+        let tp_sl = PositionTpSl::from_orders(&long, &orders);
+        assert_eq!(tp_sl.stop_loss, Some(90.0));
+        assert_eq!(tp_sl.take_profits, vec![(110.0, 50.0), (120.0, 50.0)]);
+
+        // Shorts take profit below entry, nearest target first
+        let short = position("short", "1");
+        let orders = [plan_order("profit_plan", "80", "0.5"), plan_order("pos_profit", "90", "")];
+        let tp_sl = PositionTpSl::from_orders(&short, &orders);
+        assert_eq!(tp_sl.stop_loss, None);
+        assert_eq!(tp_sl.take_profits, vec![(90.0, 100.0), (80.0, 50.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_hedge_mode_positions_tracked_per_side() {
+        let client = BitgetWebSocketClient::new(
+            Secret::new("test_key".to_string()),
+            Secret::new("test_secret".to_string()),
+            Secret::new("test_pass".to_string()),
+        );
+        let positions = Arc::new(Mutex::new(std::collections::HashMap::new()));
+        let plan_orders = Arc::new(Mutex::new(std::collections::HashMap::new()));
+        let fills = Arc::new(Mutex::new(std::collections::HashMap::new()));
+
+        // Both sides of the same symbol open independently
+        for side in ["long", "short"] {
+            let event = client
+                .process_position_update(position(side, "1"), &positions, &plan_orders, &fills)
+                .await;
+            assert!(matches!(event, Some(PositionEvent::Opened(..))));
+        }
+
+        // Closing the short leaves the long tracked
+        let event = client
+            .process_position_update(position("short", "0"), &positions, &plan_orders, &fills)
+            .await;
+        assert!(matches!(event, Some(PositionEvent::Closed(p, _)) if p.hold_side == "short"));
+        let event = client
+            .process_position_update(position("long", "2"), &positions, &plan_orders, &fills)
+            .await;
+        assert!(matches!(event, Some(PositionEvent::Updated(p, closed)) if p.hold_side == "long" && closed == 0.0));
+
+        // Partial close of the long
+        let event = client
+            .process_position_update(position("long", "1.5"), &positions, &plan_orders, &fills)
+            .await;
+        assert!(matches!(event, Some(PositionEvent::Updated(_, closed)) if closed == 0.5));
+    }
+
+    #[tokio::test]
+    async fn test_replay_snapshot_synthesizes_missed_events() {
+        let client = BitgetWebSocketClient::new(
+            Secret::new("test_key".to_string()),
+            Secret::new("test_secret".to_string()),
+            Secret::new("test_pass".to_string()),
+        );
+        client
+            .restore_positions(vec![position("long", "1"), position("short", "1")])
+            .await;
+
+        let listed = |pos_id: Option<&str>, symbol: &str, hold_side: &str| -> BitgetPosition {
+            serde_json::from_value(serde_json::json!({
+                "posId": pos_id, "symbol": symbol, "marginCoin": "USDT", "marginSize": "100",
+                "holdSide": hold_side, "openPriceAvg": "100", "total": "1", "available": "1",
+                "locked": "0", "leverage": "10", "liquidationPrice": "0", "markPrice": "100",
+                "unrealizedPL": "0", "marginMode": "crossed", "cTime": "0", "uTime": "0"
+            }))
+            .unwrap()
+        };
+
+        // The short closed and an ETH long opened while disconnected
+        let snapshot = [listed(None, "BTCUSDT", "long"), listed(Some("2"), "ETHUSDT", "long")];
+        let events = client.replay_snapshot(&snapshot).await;
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[0], PositionEvent::Closed(p, _) if p.hold_side == "short" && p.total == "1"));
+        assert!(matches!(&events[1], PositionEvent::Opened(p, ..) if p.inst_id == "ETHUSDT"));
+
+        // Nothing missed the second time
+        assert!(client.replay_snapshot(&snapshot).await.is_empty());
+    }
 }
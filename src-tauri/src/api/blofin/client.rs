@@ -3,36 +3,46 @@ use base64::{engine::general_purpose, Engine as _};
 use hmac::{Hmac, Mac};
 use reqwest::header::{HeaderMap, HeaderValue};
 use sha2::Sha256;
+use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::api::{
-    client::{ExchangeClient, FetchTradesRequest, FetchTradesResponse, RateLimitConfig},
+    client::{ApiKeyPermissions, ExchangeClient, ExchangeEnvironment, FetchTradesRequest, FetchTradesResponse, RateLimitConfig, RawClosedPosition},
+    credentials::Secret,
     error::ApiError,
     rate_limiter::RateLimiter,
+    read_only::{ReadOnlyExchangeClient, ReadOnlyHttpClient, BLOFIN_READ_PATHS},
+    retry::retry_transient,
+    server_time,
 };
 
 use super::{
-    mapper::map_trade_to_raw_trade,
-    types::{BlofinResponse, BlofinTrade, TradeHistoryRequest},
+    mapper::{map_api_key_info_to_permissions, map_position_history, map_trade_to_raw_trade},
+    types::{BlofinApiKeyInfo, BlofinPositionHistory, BlofinResponse, BlofinTrade, PositionsHistoryRequest, TradeHistoryRequest},
 };
 
 type HmacSha256 = Hmac<Sha256>;
 
 const BASE_URL: &str = "https://openapi.blofin.com";
+const DEMO_BASE_URL: &str = "https://demo-trading-openapi.blofin.com";
 const TRADE_HISTORY_ENDPOINT: &str = "/api/v1/trade/trade-history";
+const POSITIONS_HISTORY_ENDPOINT: &str = "/api/v1/account/positions-history";
+const API_KEY_INFO_ENDPOINT: &str = "/api/v1/user/query-apikey";
+/// Light public endpoint whose Date header gives BloFin's clock
+const SERVER_TIME_ENDPOINT: &str = "/api/v1/market/tickers?instId=BTC-USDT";
 
 pub struct BlofinClient {
-    api_key: String,
-    api_secret: String,
-    passphrase: String,
-    http_client: reqwest::Client,
-    rate_limiter: RateLimiter,
+    api_key: Secret,
+    api_secret: Secret,
+    passphrase: Secret,
+    http: ReadOnlyHttpClient,
+    rate_limiter: Arc<RateLimiter>,
 }
 
 impl BlofinClient {
-    pub fn new(api_key: String, api_secret: String, passphrase: String) -> Self {
+    pub fn new(api_key: Secret, api_secret: Secret, passphrase: Secret) -> Self {
         // BloFin: 30 req/10s = 3 req/s
-        let rate_limiter = RateLimiter::new(RateLimitConfig {
+        let rate_limiter = RateLimiter::shared("blofin", RateLimitConfig {
             requests_per_second: 3,
             burst_size: 5,
         });
@@ -41,11 +51,22 @@ impl BlofinClient {
             api_key,
             api_secret,
             passphrase,
-            http_client: reqwest::Client::new(),
+            http: ReadOnlyHttpClient::new(BASE_URL, BLOFIN_READ_PATHS),
             rate_limiter,
         }
     }
 
+    /// Connect to the credential's environment; BloFin demo accounts have their own host
+    pub fn with_environment(mut self, environment: &ExchangeEnvironment) -> Self {
+        let base_url = match &environment.base_url {
+            Some(base_url) => base_url.as_str(),
+            None if environment.demo => DEMO_BASE_URL,
+            None => return self,
+        };
+        self.http = self.http.with_base_url(base_url);
+        self
+    }
+
     /// Generate HMAC-SHA256 signature for BloFin API
     fn generate_signature(
         &self,
@@ -102,15 +123,6 @@ impl BlofinClient {
 
     /// Fetch trade history with pagination
     async fn fetch_trade_history(&self, request: &TradeHistoryRequest) -> Result<Vec<BlofinTrade>, ApiError> {
-        // Rate limit
-        self.rate_limiter.acquire().await;
-
-        // Current timestamp in ISO 8601 format
-        let timestamp = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
-
-        // Generate nonce (UUID v4)
-        let nonce = Uuid::new_v4().to_string();
-
         // Build query string
         let mut query_params = vec![];
         if let Some(ref inst_type) = request.inst_type {
@@ -138,13 +150,76 @@ impl BlofinClient {
             query_params.push(format!("limit={}", limit));
         }
 
+        self.signed_get(TRADE_HISTORY_ENDPOINT, &query_params).await
+    }
+
+    /// Fetch closed positions with pagination
+    async fn fetch_positions_history(
+        &self,
+        request: &PositionsHistoryRequest,
+    ) -> Result<Vec<BlofinPositionHistory>, ApiError> {
+        let mut query_params = vec![];
+        if let Some(ref inst_id) = request.inst_id {
+            query_params.push(format!("instId={}", inst_id));
+        }
+        if let Some(ref after) = request.after {
+            query_params.push(format!("after={}", after));
+        }
+        if let Some(ref limit) = request.limit {
+            query_params.push(format!("limit={}", limit));
+        }
+
+        self.signed_get(POSITIONS_HISTORY_ENDPOINT, &query_params).await
+    }
+
+    /// Fetch the details of the API key in use
+    async fn fetch_api_key_info(&self) -> Result<BlofinApiKeyInfo, ApiError> {
+        let info: Option<BlofinApiKeyInfo> = self.signed_get(API_KEY_INFO_ENDPOINT, &[]).await?;
+        info.ok_or_else(|| ApiError::ParseError("Response data is empty".to_string()))
+    }
+
+    /// BloFin's clock in milliseconds. There is no server time endpoint, so the HTTP Date
+    /// header of a public request is used. It only has whole seconds, the middle of the
+    /// second is assumed.
+    async fn fetch_server_time(&self) -> Result<i64, ApiError> {
+        let response = self.http().get(SERVER_TIME_ENDPOINT)?.send().await?;
+        let date = response
+            .headers()
+            .get(reqwest::header::DATE)
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| ApiError::ParseError("Server time response has no Date header".to_string()))?;
+
+        chrono::DateTime::parse_from_rfc2822(date)
+            .map(|date| date.timestamp_millis() + 500)
+            .map_err(|e| ApiError::ParseError(format!("Invalid Date header {}: {}", date, e)))
+    }
+
+    /// Authenticated GET returning the response's data (a list, or an Option for endpoints
+    /// returning a single object); missing data gives the default
+    async fn signed_get<T: serde::de::DeserializeOwned + Default>(
+        &self,
+        endpoint: &str,
+        query_params: &[String],
+    ) -> Result<T, ApiError> {
+        // Rate limit
+        self.rate_limiter.acquire().await;
+
+        // Current timestamp in ISO 8601 format, on BloFin's clock
+        let server_ms = server_time::now_millis("blofin", || self.fetch_server_time()).await;
+        let timestamp = chrono::DateTime::from_timestamp_millis(server_ms)
+            .unwrap_or_else(chrono::Utc::now)
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+
+        // Generate nonce (UUID v4)
+        let nonce = Uuid::new_v4().to_string();
+
         let query_string = if query_params.is_empty() {
             String::new()
         } else {
             format!("?{}", query_params.join("&"))
         };
 
-        let request_path = format!("{}{}", TRADE_HISTORY_ENDPOINT, query_string);
+        let request_path = format!("{}{}", endpoint, query_string);
 
         // Generate signature (GET request, empty body)
         let signature = self.generate_signature(&timestamp, "GET", &request_path, "");
@@ -153,10 +228,9 @@ impl BlofinClient {
         let headers = self.build_headers(&timestamp, &signature, &nonce)?;
 
         // Make request
-        let url = format!("{}{}", BASE_URL, request_path);
         let response = self
-            .http_client
-            .get(&url)
+            .http()
+            .get(&request_path)?
             .headers(headers)
             .send()
             .await?;
@@ -177,11 +251,12 @@ impl BlofinClient {
 
         // Parse response
         let response_text = response.text().await?;
-        let api_response: BlofinResponse<BlofinTrade> = serde_json::from_str(&response_text)
+        let api_response: BlofinResponse<T> = serde_json::from_str(&response_text)
             .map_err(|e| ApiError::ParseError(format!("Failed to parse response: {} - Body: {}", e, response_text)))?;
 
         // Check response code
         if api_response.code != "0" {
+            server_time::invalidate_on_timestamp_error("blofin", &api_response.msg);
             return Err(ApiError::ExchangeError {
                 code: api_response.code,
                 message: api_response.msg,
@@ -192,6 +267,12 @@ impl BlofinClient {
     }
 }
 
+impl ReadOnlyExchangeClient for BlofinClient {
+    fn http(&self) -> &ReadOnlyHttpClient {
+        &self.http
+    }
+}
+
 #[async_trait]
 impl ExchangeClient for BlofinClient {
     fn exchange_name(&self) -> &str {
@@ -204,6 +285,10 @@ impl ExchangeClient for BlofinClient {
         let limit = request.limit.unwrap_or(100);
 
         loop {
+            if request.is_cancelled() {
+                return Err(ApiError::Cancelled);
+            }
+
             let blofin_request = TradeHistoryRequest {
                 inst_type: Some("SWAP".to_string()), // Perpetual futures
                 inst_id: request.symbol.clone(),
@@ -215,7 +300,7 @@ impl ExchangeClient for BlofinClient {
                 limit: Some("100".to_string()), // Max per request
             };
 
-            let trades = self.fetch_trade_history(&blofin_request).await?;
+            let trades = retry_transient(|| self.fetch_trade_history(&blofin_request)).await?;
 
             // Map trades to raw trades
             for trade in &trades {
@@ -243,6 +328,46 @@ impl ExchangeClient for BlofinClient {
         }
     }
 
+    async fn fetch_closed_positions(
+        &self,
+        start_time: Option<i64>,
+        end_time: Option<i64>,
+    ) -> Result<Vec<RawClosedPosition>, ApiError> {
+        let mut closed = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let request = PositionsHistoryRequest {
+                inst_id: None,
+                after: cursor.clone(),
+                limit: Some("100".to_string()), // Max per request
+            };
+
+            let positions = retry_transient(|| self.fetch_positions_history(&request)).await?;
+
+            let mut reached_start = false;
+            for position in &positions {
+                match map_position_history(position) {
+                    Ok(p) => {
+                        // Newest first: once past the start of the range, older pages are not needed
+                        reached_start |= start_time.is_some_and(|start| p.close_timestamp < start);
+                        let in_range = start_time.is_none_or(|start| p.close_timestamp >= start)
+                            && end_time.is_none_or(|end| p.close_timestamp <= end);
+                        if in_range {
+                            closed.push(p);
+                        }
+                    }
+                    Err(e) => eprintln!("Warning: Failed to map BloFin position: {}", e),
+                }
+            }
+
+            if positions.len() < 100 || reached_start {
+                return Ok(closed);
+            }
+            cursor = positions.last().map(|p| p.position_id.clone());
+        }
+    }
+
     async fn test_credentials(&self) -> Result<bool, ApiError> {
         // Test with a minimal request (fetch 1 trade)
         let request = TradeHistoryRequest {
@@ -263,6 +388,19 @@ impl ExchangeClient for BlofinClient {
         }
     }
 
+    async fn fetch_server_time_ms(&self) -> Result<i64, ApiError> {
+        self.fetch_server_time().await
+    }
+
+    fn rate_limit_headroom(&self) -> u32 {
+        self.rate_limiter.headroom()
+    }
+
+    async fn fetch_permissions(&self) -> Result<Option<ApiKeyPermissions>, ApiError> {
+        let info = self.fetch_api_key_info().await?;
+        Ok(Some(map_api_key_info_to_permissions(&info)))
+    }
+
     fn rate_limit(&self) -> RateLimitConfig {
         RateLimitConfig {
             requests_per_second: 3,
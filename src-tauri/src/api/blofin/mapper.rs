@@ -1,5 +1,5 @@
-use super::types::BlofinTrade;
-use crate::api::client::RawTrade;
+use super::types::{BlofinApiKeyInfo, BlofinPositionHistory, BlofinTrade};
+use crate::api::client::{ApiKeyPermissions, RawClosedPosition, RawTrade};
 
 /// Map BloFin trade to RawTrade
 pub fn map_trade_to_raw_trade(trade: &BlofinTrade) -> Result<RawTrade, String> {
@@ -53,6 +53,13 @@ pub fn map_trade_to_raw_trade(trade: &BlofinTrade) -> Result<RawTrade, String> {
         _ => "LONG", // Default
     };
 
+    // In hedge mode a fill against the position side closes it; one-way ("net") is inferred
+    let reduce_only = match (trade.pos_side.as_str(), trade.side.as_str()) {
+        ("long", side) => Some(side == "sell"),
+        ("short", side) => Some(side == "buy"),
+        _ => None,
+    };
+
     // Serialize raw JSON for audit trail
     let raw_json = serde_json::to_string(&trade)
         .map_err(|e| format!("Failed to serialize trade: {}", e))?;
@@ -71,10 +78,55 @@ pub fn map_trade_to_raw_trade(trade: &BlofinTrade) -> Result<RawTrade, String> {
         leverage: None, // BloFin doesn't provide leverage in trade history
         timestamp,
         close_timestamp,
+        reduce_only,
         raw_json,
     })
 }
 
+/// Map a BloFin positions-history record to RawClosedPosition
+pub fn map_position_history(position: &BlofinPositionHistory) -> Result<RawClosedPosition, String> {
+    let realized_pnl = position
+        .realized_pnl
+        .parse::<f64>()
+        .map_err(|e| format!("Invalid realized PnL: {}", e))?;
+    let open_timestamp = position
+        .create_time
+        .parse::<i64>()
+        .map_err(|e| format!("Invalid create time: {}", e))?;
+    let close_timestamp = position
+        .update_time
+        .parse::<i64>()
+        .map_err(|e| format!("Invalid update time: {}", e))?;
+
+    let position_side = match position.position_side.as_str() {
+        "long" => Some("LONG".to_string()),
+        "short" => Some("SHORT".to_string()),
+        _ => None,
+    };
+
+    Ok(RawClosedPosition {
+        exchange_position_id: position.position_id.clone(),
+        symbol: position.inst_id.clone(),
+        position_side,
+        realized_pnl,
+        open_timestamp,
+        close_timestamp,
+    })
+}
+
+/// Map the API key details to ApiKeyPermissions. BloFin only says whether the key is
+/// read-only, so withdrawal rights are unknown.
+pub fn map_api_key_info_to_permissions(info: &BlofinApiKeyInfo) -> ApiKeyPermissions {
+    let read_only = info.read_only == 1;
+
+    ApiKeyPermissions {
+        read: true,
+        trade: !read_only,
+        withdraw: None,
+        raw: vec![if read_only { "read_only" } else { "read_write" }.to_string()],
+    }
+}
+
 /// Generate fingerprint for deduplication
 #[allow(dead_code)]
 pub fn generate_fingerprint(trade: &BlofinTrade) -> String {
@@ -117,6 +169,7 @@ mod tests {
         assert_eq!(raw.fee, 2.5); // Absolute value
         assert_eq!(raw.position_side, "LONG");
         assert_eq!(raw.timestamp, 1704067200000);
+        assert_eq!(raw.reduce_only, Some(false)); // Buying into a long opens it
     }
 
     #[test]
@@ -164,5 +217,25 @@ mod tests {
 
         let raw = map_trade_to_raw_trade(&trade).unwrap();
         assert_eq!(raw.position_side, "SHORT"); // Inferred from sell
+        assert_eq!(raw.reduce_only, None); // Left to position tracking
+    }
+
+    #[test]
+    fn test_map_position_history() {
+        let position = BlofinPositionHistory {
+            position_id: "pos1".to_string(),
+            inst_id: "BTC-USDT".to_string(),
+            position_side: "net".to_string(),
+            open_average_price: Some("50000".to_string()),
+            close_average_price: Some("51000".to_string()),
+            realized_pnl: "98.5".to_string(),
+            create_time: "1704067200000".to_string(),
+            update_time: "1704070800000".to_string(),
+        };
+
+        let closed = map_position_history(&position).unwrap();
+        assert_eq!(closed.realized_pnl, 98.5);
+        assert_eq!(closed.position_side, None); // One-way mode doesn't say
+        assert_eq!((closed.open_timestamp, closed.close_timestamp), (1704067200000, 1704070800000));
     }
 }
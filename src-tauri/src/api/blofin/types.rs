@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 pub struct BlofinResponse<T> {
     pub code: String,
     pub msg: String,
-    pub data: Option<Vec<T>>,
+    pub data: Option<T>,
 }
 
 /// BloFin trade record
@@ -62,6 +62,73 @@ pub struct BlofinTrade {
     pub ts: String,
 }
 
+/// BloFin closed position from positions history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlofinPositionHistory {
+    /// Position ID
+    #[serde(rename = "positionId")]
+    pub position_id: String,
+
+    /// Instrument ID (e.g., "BTC-USDT")
+    #[serde(rename = "instId")]
+    pub inst_id: String,
+
+    /// Position side: "long", "short", "net"
+    #[serde(rename = "positionSide")]
+    pub position_side: String,
+
+    /// Average open price
+    #[serde(rename = "openAveragePrice", default, skip_serializing_if = "Option::is_none")]
+    pub open_average_price: Option<String>,
+
+    /// Average close price
+    #[serde(rename = "closeAveragePrice", default, skip_serializing_if = "Option::is_none")]
+    pub close_average_price: Option<String>,
+
+    /// Realized PnL of the position
+    #[serde(rename = "realizedPnl")]
+    pub realized_pnl: String,
+
+    /// Opened at (Unix milliseconds)
+    #[serde(rename = "createTime")]
+    pub create_time: String,
+
+    /// Closed at (Unix milliseconds)
+    #[serde(rename = "updateTime")]
+    pub update_time: String,
+}
+
+/// The API key's own details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlofinApiKeyInfo {
+    #[serde(rename = "apiName", default)]
+    pub api_name: String,
+
+    /// 1 for a read-only key, 0 when it can also trade
+    #[serde(rename = "readOnly")]
+    pub read_only: i32,
+
+    /// IP whitelist
+    #[serde(default)]
+    pub ips: Vec<String>,
+}
+
+/// Request for positions history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionsHistoryRequest {
+    /// Instrument ID (optional)
+    #[serde(rename = "instId", skip_serializing_if = "Option::is_none")]
+    pub inst_id: Option<String>,
+
+    /// Pagination: query positions with ID < after
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+
+    /// Limit (max 100)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<String>,
+}
+
 /// Request for trade history
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeHistoryRequest {
@@ -1,7 +1,10 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use super::error::ApiError;
+use super::read_only::ReadOnlyExchangeClient;
 
 /// Configuration for rate limiting
 #[derive(Debug, Clone)]
@@ -10,6 +13,48 @@ pub struct RateLimitConfig {
     pub burst_size: u32,
 }
 
+/// Servers a credential's clients connect to: the production ones unless the credential
+/// belongs to a demo (paper trading) account or overrides the REST base URL
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExchangeEnvironment {
+    pub demo: bool,
+    /// Replaces the exchange's REST base URL; the WebSocket still follows `demo`
+    pub base_url: Option<String>,
+}
+
+impl ExchangeEnvironment {
+    /// Environment from the `environment` and `base_url` columns of `api_credentials`
+    pub fn from_columns(environment: &str, base_url: Option<String>) -> Self {
+        Self {
+            demo: environment == "demo",
+            base_url,
+        }
+    }
+
+    /// Check a base URL given for a credential, without the trailing slash. Signed requests
+    /// carry the API key, so only https is accepted, except for a local test server.
+    pub fn normalize_base_url(url: &str) -> Result<String, String> {
+        let url = url.trim().trim_end_matches('/');
+        let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid base URL '{}': {}", url, e))?;
+        let is_local = matches!(parsed.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+        let secure = match parsed.scheme() {
+            "https" => true,
+            "http" => is_local,
+            _ => false,
+        };
+        if !secure || parsed.host_str().is_none_or(str::is_empty) {
+            return Err(format!("Base URL '{}' must be an https address", url));
+        }
+        if parsed.query().is_some() || parsed.fragment().is_some() {
+            return Err(format!("Base URL '{}' must not have a query string", url));
+        }
+        Ok(url.to_string())
+    }
+}
+
+/// Set from another task to stop a running fetch at the next page
+pub type CancelFlag = Arc<AtomicBool>;
+
 /// Request parameters for fetching trades
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FetchTradesRequest {
@@ -23,6 +68,15 @@ pub struct FetchTradesRequest {
     pub limit: Option<u32>,
     /// Pagination cursor (exchange-specific)
     pub cursor: Option<String>,
+    /// Checked before each page; the fetch fails with ApiError::Cancelled once set
+    #[serde(skip)]
+    pub cancel: Option<CancelFlag>,
+}
+
+impl FetchTradesRequest {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|flag| flag.load(Ordering::Relaxed))
+    }
 }
 
 /// Raw trade data from exchange API (before mapping to Trade model)
@@ -41,10 +95,71 @@ pub struct RawTrade {
     pub leverage: Option<u32>,
     pub timestamp: i64, // Unix milliseconds
     pub close_timestamp: Option<i64>,
+    /// Whether the fill closes (part of) a position; None when the exchange doesn't say,
+    /// as in one-way mode, and it is inferred from the open position
+    #[serde(default)]
+    pub reduce_only: Option<bool>,
     /// Raw JSON from exchange (for debugging/auditing)
     pub raw_json: String,
 }
 
+/// Funding fee settlement from the exchange's account history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawFundingPayment {
+    /// Exchange bill/ledger ID (unique per exchange)
+    pub exchange_bill_id: String,
+    pub symbol: String,
+    /// Positive when received, negative when paid
+    pub amount: f64,
+    pub currency: String,
+    pub timestamp: i64, // Unix milliseconds
+}
+
+/// Closed position from the exchange's position history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawClosedPosition {
+    pub exchange_position_id: String,
+    pub symbol: String,
+    /// "LONG" or "SHORT"; None in one-way mode
+    pub position_side: Option<String>,
+    pub realized_pnl: f64,
+    pub open_timestamp: i64,  // Unix milliseconds
+    pub close_timestamp: i64, // Unix milliseconds
+}
+
+/// What an API key may do, as reported by the exchange
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyPermissions {
+    pub read: bool,
+    pub trade: bool,
+    /// None when the exchange doesn't report withdrawal rights
+    pub withdraw: Option<bool>,
+    /// Permission names as the exchange returned them
+    pub raw: Vec<String>,
+}
+
+impl ApiKeyPermissions {
+    /// Warnings for rights a journaling key doesn't need; it only ever reads
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.withdraw == Some(true) {
+            warnings.push(
+                "WARNING: this API key can WITHDRAW funds. The journal only reads history; \
+                 create a read-only key and delete this one."
+                    .to_string(),
+            );
+        }
+        if self.trade {
+            warnings.push(
+                "WARNING: this API key can place and cancel orders. The journal only reads \
+                 history; a read-only key is safer."
+                    .to_string(),
+            );
+        }
+        warnings
+    }
+}
+
 /// Response from fetching trades
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FetchTradesResponse {
@@ -55,9 +170,10 @@ pub struct FetchTradesResponse {
     pub has_more: bool,
 }
 
-/// Core trait that all exchange clients must implement
+/// Core trait that all exchange clients must implement. Clients only read; their HTTP
+/// access goes through the read-only guard (see `read_only`).
 #[async_trait]
-pub trait ExchangeClient: Send + Sync {
+pub trait ExchangeClient: ReadOnlyExchangeClient + Send + Sync {
     /// Get the exchange name (e.g., "bitget", "blofin")
     #[allow(dead_code)]
     fn exchange_name(&self) -> &str;
@@ -68,10 +184,40 @@ pub trait ExchangeClient: Send + Sync {
         request: FetchTradesRequest,
     ) -> Result<FetchTradesResponse, ApiError>;
 
+    /// Fetch funding fee payments between two timestamps (Unix milliseconds).
+    /// Exchanges without a supported funding endpoint return an empty list.
+    async fn fetch_funding_payments(
+        &self,
+        _start_time: Option<i64>,
+        _end_time: Option<i64>,
+    ) -> Result<Vec<RawFundingPayment>, ApiError> {
+        Ok(Vec::new())
+    }
+
+    /// Fetch positions closed between two timestamps (Unix milliseconds), for exchanges
+    /// whose fills carry no realized PnL. Others return an empty list.
+    async fn fetch_closed_positions(
+        &self,
+        _start_time: Option<i64>,
+        _end_time: Option<i64>,
+    ) -> Result<Vec<RawClosedPosition>, ApiError> {
+        Ok(Vec::new())
+    }
+
     /// Test API credentials by making a lightweight API call
     async fn test_credentials(&self) -> Result<bool, ApiError>;
 
+    /// Current time on the exchange's clock (Unix milliseconds), from a public endpoint
+    async fn fetch_server_time_ms(&self) -> Result<i64, ApiError>;
+
+    /// Requests the exchange's shared rate limiter lets through right now
+    fn rate_limit_headroom(&self) -> u32;
+
+    /// Permissions of the API key. None when the exchange has no endpoint to tell.
+    async fn fetch_permissions(&self) -> Result<Option<ApiKeyPermissions>, ApiError> {
+        Ok(None)
+    }
+
     /// Get rate limit configuration for this exchange
-    #[allow(dead_code)]
     fn rate_limit(&self) -> RateLimitConfig;
 }
@@ -0,0 +1,109 @@
+//! Short-lived cache of what the credential list shows from the keychain.
+//!
+//! Listing credentials reads every API key to show its preview, and macOS may prompt for
+//! each read. The cache keeps the previews (never the secrets) for a few minutes, so
+//! reopening the settings page doesn't read the keychain again. Saving, rotating or
+//! deleting a credential drops its entry.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::credentials::retrieve_api_key;
+use crate::models::ApiCredential;
+
+/// How long a preview is served without reading the keychain again
+const PREVIEW_TTL: Duration = Duration::from_secs(300);
+
+/// API key previews per credential, managed as app state
+pub struct CredentialCache {
+    previews: Mutex<HashMap<String, (String, Instant)>>,
+    ttl: Duration,
+}
+
+impl Default for CredentialCache {
+    fn default() -> Self {
+        Self::new(PREVIEW_TTL)
+    }
+}
+
+impl CredentialCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            previews: Mutex::new(HashMap::new()),
+            ttl,
+        }
+    }
+
+    /// Preview of the credential's API key, read from the keychain when not cached. A key
+    /// that can't be read shows as an empty preview and isn't cached, so the next refresh
+    /// tries again.
+    pub fn api_key_preview(&self, credential_id: &str, accessed_by: &str) -> String {
+        self.preview_or_load(credential_id, || {
+            retrieve_api_key(credential_id, accessed_by).map(|api_key| ApiCredential::create_preview(&api_key))
+        })
+    }
+
+    fn preview_or_load<E>(&self, credential_id: &str, load: impl FnOnce() -> Result<String, E>) -> String {
+        if let Some((preview, cached_at)) = self.lock().get(credential_id)
+            && cached_at.elapsed() < self.ttl
+        {
+            return preview.clone();
+        }
+
+        // Read without holding the lock: the keychain may show a prompt
+        let Ok(preview) = load() else {
+            return String::new();
+        };
+        self.lock()
+            .insert(credential_id.to_string(), (preview.clone(), Instant::now()));
+        preview
+    }
+
+    /// Forget a credential after its secrets changed or it was deleted
+    pub fn invalidate(&self, credential_id: &str) {
+        self.lock().remove(credential_id);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, (String, Instant)>> {
+        self.previews.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_previews_cached_until_invalidated_or_expired() {
+        let cache = CredentialCache::default();
+        let reads = Cell::new(0);
+        let load = || {
+            reads.set(reads.get() + 1);
+            Ok::<_, String>("****abcd".to_string())
+        };
+
+        assert_eq!(cache.preview_or_load("C1", load), "****abcd");
+        assert_eq!(cache.preview_or_load("C1", load), "****abcd");
+        assert_eq!(reads.get(), 1);
+
+        cache.invalidate("C1");
+        cache.preview_or_load("C1", load);
+        assert_eq!(reads.get(), 2);
+
+        let expired = CredentialCache::new(Duration::ZERO);
+        expired.preview_or_load("C1", load);
+        expired.preview_or_load("C1", load);
+        assert_eq!(reads.get(), 4);
+
+        // A failed read isn't cached
+        let failed = || {
+            reads.set(reads.get() + 1);
+            Err("keychain locked".to_string())
+        };
+        cache.preview_or_load("C2", failed);
+        cache.preview_or_load("C2", failed);
+        assert_eq!(reads.get(), 6);
+    }
+}
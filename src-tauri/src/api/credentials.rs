@@ -1,11 +1,54 @@
+use super::biometric;
 use super::error::ApiError;
 use super::secure_storage::SecureStorage;
+use crate::db::Database;
+use chrono::Utc;
+use rusqlite::Connection;
 use std::sync::{Mutex, OnceLock};
 use std::path::PathBuf;
+use zeroize::Zeroizing;
+
+/// Keychain service the app's secrets are stored under
+const KEYCHAIN_SERVICE: &str = "com.nemesis.trading-journal";
+
+/// Shown in the Touch ID prompt: "<app> is trying to ..."
+const BIOMETRIC_REASON: &str = "read your exchange API keys";
+
+/// Entry suffixes of an API credential's secrets
+const CREDENTIAL_ENTRIES: &[&str] = &["api-key", "api-secret", "passphrase"];
+
+/// Days credential_access_log entries are kept
+const ACCESS_LOG_RETENTION_DAYS: i64 = 90;
 
 static STORAGE: OnceLock<Mutex<SecureStorage>> = OnceLock::new();
+static ACCESS_LOG: OnceLock<Database> = OnceLock::new();
+
+/// An API key, secret or passphrase read from secure storage. The memory holding it is
+/// wiped when it is dropped.
+pub type Secret = Zeroizing<String>;
+
+/// Where a secret is kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageBackend {
+    /// The system keychain (macOS Keychain, Windows Credential Manager)
+    Keychain,
+    /// The AES-GCM file store (credentials.enc), used when the keychain can't be written
+    EncryptedFile,
+}
+
+impl StorageBackend {
+    /// Value of the `storage_backend` column of api_credentials
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StorageBackend::Keychain => "keychain",
+            StorageBackend::EncryptedFile => "encrypted_file",
+        }
+    }
+}
 
-/// Initialize secure storage with app data directory
+/// Initialize the encrypted file store (credentials.enc) with the app data directory.
+/// Secrets live in the system keychain; the file holds entries not migrated from it yet
+/// and the ones stored while the keychain was unavailable.
 pub fn init_storage(app_data_dir: PathBuf) -> Result<(), ApiError> {
     let storage = SecureStorage::new(app_data_dir)?;
     STORAGE.set(Mutex::new(storage)).map_err(|_| {
@@ -15,6 +58,67 @@ pub fn init_storage(app_data_dir: PathBuf) -> Result<(), ApiError> {
     Ok(())
 }
 
+/// Log every credential read into credential_access_log from now on, pruning entries
+/// older than ACCESS_LOG_RETENTION_DAYS
+pub fn init_access_log(db: Database) -> Result<(), String> {
+    {
+        let conn = db.pool.get().map_err(|e| e.to_string())?;
+        prune_access_log(&conn, Utc::now().timestamp()).map_err(|e| e.to_string())?;
+    }
+    ACCESS_LOG
+        .set(db)
+        .map_err(|_| "Credential access log already initialized".to_string())
+}
+
+fn prune_access_log(conn: &Connection, now: i64) -> Result<usize, rusqlite::Error> {
+    conn.execute(
+        "DELETE FROM credential_access_log WHERE accessed_at < ?",
+        [now - ACCESS_LOG_RETENTION_DAYS * 86_400],
+    )
+}
+
+fn insert_access(
+    conn: &Connection,
+    credential_id: &str,
+    entry: &str,
+    accessed_by: &str,
+    success: bool,
+    now: i64,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO credential_access_log (id, credential_id, entry, accessed_by, success, accessed_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+        rusqlite::params![
+            uuid::Uuid::new_v4().to_string(), credential_id, entry, accessed_by,
+            success as i32, now
+        ],
+    )?;
+    Ok(())
+}
+
+/// Record a credential read. A failed write is reported but never fails the read.
+fn log_access(credential_id: &str, entry: &str, accessed_by: &str, success: bool) {
+    let Some(db) = ACCESS_LOG.get() else {
+        return;
+    };
+    let result = db.pool.get().map_err(|e| e.to_string()).and_then(|conn| {
+        insert_access(&conn, credential_id, entry, accessed_by, success, Utc::now().timestamp())
+            .map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        eprintln!("Failed to log credential access: {}", e);
+    }
+}
+
+/// Read one of a credential's secrets on behalf of `accessed_by`, logging the read
+fn retrieve_credential_entry(credential_id: &str, entry: &str, accessed_by: &str) -> Result<Secret, ApiError> {
+    let result = biometric::authorize(BIOMETRIC_REASON)
+        .and_then(|()| retrieve_secret(&format!("{}-{}", credential_id, entry)))
+        .map(Zeroizing::new);
+    log_access(credential_id, entry, accessed_by, result.is_ok());
+    result
+}
+
 /// Get the storage instance
 fn get_storage() -> Result<std::sync::MutexGuard<'static, SecureStorage>, ApiError> {
     STORAGE
@@ -24,80 +128,337 @@ fn get_storage() -> Result<std::sync::MutexGuard<'static, SecureStorage>, ApiErr
         .map_err(|e| ApiError::EncryptionError(format!("Failed to lock storage: {}", e)))
 }
 
-/// Store an API key in secure storage
-pub fn store_api_key(credential_id: &str, api_key: &str) -> Result<(), ApiError> {
-    let key = format!("{}-api-key", credential_id);
-    println!("Storing API key: {}", key);
+fn keychain_entry(key: &str) -> Result<keyring::Entry, ApiError> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, key)
+        .map_err(|e| ApiError::KeychainError(format!("Invalid keychain entry '{}': {}", key, e)))
+}
+
+/// Write a secret to the keychain and read it back, so a keychain that silently drops
+/// writes is caught here rather than at the next sync
+fn keychain_store(key: &str, value: &str) -> Result<(), ApiError> {
+    let entry = keychain_entry(key)?;
+    entry
+        .set_password(value)
+        .map_err(|e| ApiError::KeychainError(format!("Failed to store '{}': {}", key, e)))?;
+
+    match keychain_entry(key)?.get_password() {
+        Ok(stored) if stored == value => Ok(()),
+        Ok(_) => Err(ApiError::KeychainError(format!("'{}' reads back differently than stored", key))),
+        Err(e) => Err(ApiError::KeychainError(format!("Stored '{}' but cannot read it back: {}", key, e))),
+    }
+}
+
+/// Store a secret in the keychain, or in the encrypted file store when the keychain can't
+/// be written (no keyring on Linux, a locked keychain). Returns where it went.
+fn store_secret(key: &str, value: &str) -> Result<StorageBackend, ApiError> {
+    match keychain_store(key, value) {
+        Ok(()) => {
+            // A copy from an earlier fallback is no longer read, don't leave it on disk
+            let storage = get_storage()?;
+            if storage.retrieve(key).is_ok() {
+                storage.delete(key)?;
+            }
+            Ok(StorageBackend::Keychain)
+        }
+        Err(e) => {
+            eprintln!("✗ WARNING: {}; storing '{}' in the encrypted file store instead", e, key);
+            // The keychain must not shadow the file copy with a half-written value
+            if let Ok(entry) = keychain_entry(key) {
+                let _ = entry.delete_credential();
+            }
+            get_storage()?.store(key, value)?;
+            Ok(StorageBackend::EncryptedFile)
+        }
+    }
+}
+
+/// Read a secret and where it was found: the keychain first, then the encrypted file store
+/// for entries not migrated yet or stored there as a fallback
+fn locate_secret(key: &str) -> Result<(String, StorageBackend), ApiError> {
+    let keychain_error = match keychain_entry(key).map(|entry| entry.get_password()) {
+        Ok(Ok(value)) => return Ok((value, StorageBackend::Keychain)),
+        Ok(Err(keyring::Error::NoEntry)) => None,
+        Ok(Err(e)) => Some(ApiError::KeychainError(format!("Failed to read '{}': {}", key, e))),
+        Err(e) => Some(e),
+    };
+
+    match get_storage()?.retrieve(key) {
+        Ok(value) => Ok((value, StorageBackend::EncryptedFile)),
+        Err(_) => Err(keychain_error.unwrap_or_else(|| {
+            ApiError::KeychainError(format!("Credential '{}' not found", key))
+        })),
+    }
+}
+
+fn retrieve_secret(key: &str) -> Result<String, ApiError> {
+    locate_secret(key).map(|(value, _)| value)
+}
+
+/// Remove a secret from the keychain and the encrypted file store
+fn delete_secret(key: &str) -> Result<(), ApiError> {
+    let keychain_result = keychain_entry(key).and_then(|entry| match entry.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(ApiError::KeychainError(format!("Failed to delete '{}': {}", key, e))),
+    });
 
     let storage = get_storage()?;
-    storage.store(&key, api_key)?;
+    let in_file = storage.retrieve(key).is_ok();
+    if in_file {
+        storage.delete(key)?;
+    }
 
-    // Verify it was stored
-    match storage.retrieve(&key) {
-        Ok(_) => {
-            println!("✓ Verified API key stored successfully");
+    match keychain_result {
+        // Stored in the file because the keychain was unavailable, which it still is
+        Err(e) if in_file => {
+            eprintln!("✗ WARNING: {}", e);
             Ok(())
         }
+        result => result,
+    }
+}
+
+/// Move every entry of the legacy file store into the keychain, then mark the file migrated
+/// and empty it. Does nothing once migrated; returns the number of entries moved. On error
+/// the file is left as it is and the migration runs again at the next startup.
+pub fn migrate_legacy_storage() -> Result<usize, ApiError> {
+    let storage = get_storage()?;
+    migrate_entries(&storage, keychain_store)
+}
+
+fn migrate_entries(
+    storage: &SecureStorage,
+    mut store: impl FnMut(&str, &str) -> Result<(), ApiError>,
+) -> Result<usize, ApiError> {
+    if storage.is_migrated()? {
+        return Ok(0);
+    }
+
+    let keys = storage.keys()?;
+    for key in &keys {
+        let value = storage.retrieve(key)?;
+        store(key, &value)?;
+    }
+
+    storage.mark_migrated()?;
+    Ok(keys.len())
+}
+
+/// Store an API key in the keychain, or the encrypted file store as a fallback
+pub fn store_api_key(credential_id: &str, api_key: &str) -> Result<StorageBackend, ApiError> {
+    let key = format!("{}-api-key", credential_id);
+    println!("Storing API key: {}", key);
+
+    match store_secret(&key, api_key) {
+        Ok(backend) => {
+            println!("✓ Verified API key stored successfully ({})", backend.as_str());
+            Ok(backend)
+        }
         Err(e) => {
-            eprintln!("✗ WARNING: Stored but cannot retrieve immediately: {}", e);
+            eprintln!("✗ WARNING: {}", e);
             Err(e)
         }
     }
 }
 
-/// Retrieve an API key from secure storage
-pub fn retrieve_api_key(credential_id: &str) -> Result<String, ApiError> {
+/// Retrieve an API key from the keychain. `accessed_by` names the feature reading it in
+/// the credential access log.
+pub fn retrieve_api_key(credential_id: &str, accessed_by: &str) -> Result<Secret, ApiError> {
     let key = format!("{}-api-key", credential_id);
     println!("Retrieving API key: {}", key);
 
-    let storage = get_storage()?;
-    storage.retrieve(&key).map_err(|e| {
+    retrieve_credential_entry(credential_id, "api-key", accessed_by).map_err(|e| {
         eprintln!("✗ Failed to retrieve API key for {}: {}", key, e);
         e
     })
 }
 
-/// Store an API secret in secure storage
-pub fn store_api_secret(credential_id: &str, api_secret: &str) -> Result<(), ApiError> {
-    let key = format!("{}-api-secret", credential_id);
-    let storage = get_storage()?;
-    storage.store(&key, api_secret)
+/// Where the credential's API key is stored, None without one. The key isn't handed out,
+/// so this neither prompts nor counts as an access.
+pub fn api_key_backend(credential_id: &str) -> Option<StorageBackend> {
+    locate_secret(&format!("{}-api-key", credential_id))
+        .ok()
+        .map(|(_, backend)| backend)
 }
 
-/// Retrieve an API secret from secure storage
-pub fn retrieve_api_secret(credential_id: &str) -> Result<String, ApiError> {
+/// Store an API secret in the keychain, or the encrypted file store as a fallback
+pub fn store_api_secret(credential_id: &str, api_secret: &str) -> Result<StorageBackend, ApiError> {
     let key = format!("{}-api-secret", credential_id);
-    let storage = get_storage()?;
-    storage.retrieve(&key)
+    store_secret(&key, api_secret)
 }
 
-/// Store an API passphrase in secure storage
-pub fn store_passphrase(credential_id: &str, passphrase: &str) -> Result<(), ApiError> {
-    let key = format!("{}-passphrase", credential_id);
-    let storage = get_storage()?;
-    storage.store(&key, passphrase)
+/// Retrieve an API secret from the keychain
+pub fn retrieve_api_secret(credential_id: &str, accessed_by: &str) -> Result<Secret, ApiError> {
+    retrieve_credential_entry(credential_id, "api-secret", accessed_by)
 }
 
-/// Retrieve an API passphrase from secure storage
-pub fn retrieve_passphrase(credential_id: &str) -> Result<String, ApiError> {
+/// Store an API passphrase in the keychain, or the encrypted file store as a fallback
+pub fn store_passphrase(credential_id: &str, passphrase: &str) -> Result<StorageBackend, ApiError> {
     let key = format!("{}-passphrase", credential_id);
-    let storage = get_storage()?;
-    storage.retrieve(&key)
+    store_secret(&key, passphrase)
+}
+
+/// Retrieve an API passphrase from the keychain
+pub fn retrieve_passphrase(credential_id: &str, accessed_by: &str) -> Result<Secret, ApiError> {
+    retrieve_credential_entry(credential_id, "passphrase", accessed_by)
+}
+
+/// Replace the secrets of a credential with those of a rotated key; a None passphrase
+/// removes the stored one. When a secret can't be stored, the secrets stored before are put
+/// back so the credential keeps its old key. Returns where the new secrets went.
+pub fn replace_credentials(
+    credential_id: &str,
+    api_key: &str,
+    api_secret: &str,
+    passphrase: Option<&str>,
+) -> Result<StorageBackend, ApiError> {
+    let keys: Vec<String> = CREDENTIAL_ENTRIES
+        .iter()
+        .map(|entry| format!("{}-{}", credential_id, entry))
+        .collect();
+    let previous: Vec<Option<Secret>> = keys
+        .iter()
+        .map(|key| retrieve_secret(key).ok().map(Zeroizing::new))
+        .collect();
+
+    let mut backend = StorageBackend::Keychain;
+    for (key, value) in keys.iter().zip([Some(api_key), Some(api_secret), passphrase]) {
+        let result = match value {
+            Some(value) => store_secret(key, value).map(Some),
+            None => delete_secret(key).map(|()| None),
+        };
+        match result {
+            Ok(Some(StorageBackend::EncryptedFile)) => backend = StorageBackend::EncryptedFile,
+            Ok(_) => {}
+            Err(e) => {
+                restore_secrets(&keys, &previous);
+                return Err(e);
+            }
+        }
+    }
+    Ok(backend)
+}
+
+/// Put back the secrets read before a failed replace_credentials
+fn restore_secrets(keys: &[String], previous: &[Option<Secret>]) {
+    for (key, value) in keys.iter().zip(previous) {
+        let result = match value {
+            Some(value) => store_secret(key, value).map(|_| ()),
+            None => delete_secret(key),
+        };
+        if let Err(e) = result {
+            eprintln!("✗ Failed to restore '{}' after a failed rotation: {}", key, e);
+        }
+    }
+}
+
+/// Keychain entry holding the SQLCipher key of the journal database
+const DATABASE_KEY_ENTRY: &str = "database-encryption-key";
+
+/// Store the database encryption key in the keychain, or the encrypted file store as a
+/// fallback
+pub fn store_database_key(key: &str) -> Result<StorageBackend, ApiError> {
+    store_secret(DATABASE_KEY_ENTRY, key)
+}
+
+/// Retrieve the database encryption key
+pub fn retrieve_database_key() -> Result<String, ApiError> {
+    retrieve_secret(DATABASE_KEY_ENTRY)
+}
+
+/// Delete the database encryption key
+pub fn delete_database_key() -> Result<(), ApiError> {
+    delete_secret(DATABASE_KEY_ENTRY)
 }
 
 /// Delete all credentials for a given credential_id
 pub fn delete_credentials(credential_id: &str) -> Result<(), ApiError> {
-    let storage = get_storage()?;
-    storage.delete_all_with_prefix(credential_id)?;
+    for entry in CREDENTIAL_ENTRIES {
+        delete_secret(&format!("{}-{}", credential_id, entry))?;
+    }
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::commands::test_support::test_conn;
+    use keyring::credential::{Credential, CredentialApi, CredentialBuilderApi};
+    use std::any::Any;
+    use std::collections::HashMap;
     use std::env;
+    use std::sync::Once;
+
+    /// In-memory keychain, as tests can't use the system one
+    static MEMORY_KEYCHAIN: Mutex<Option<HashMap<String, Vec<u8>>>> = Mutex::new(None);
+
+    fn with_memory_keychain<T>(f: impl FnOnce(&mut HashMap<String, Vec<u8>>) -> T) -> T {
+        f(MEMORY_KEYCHAIN.lock().unwrap().get_or_insert_with(HashMap::new))
+    }
+
+    struct MemoryCredential {
+        key: String,
+    }
+
+    impl MemoryCredential {
+        /// Entries of credentials named "locked-..." act like a locked keychain
+        fn check_unlocked(&self) -> keyring::Result<()> {
+            if self.key.contains("/locked-") {
+                return Err(keyring::Error::PlatformFailure("keychain locked".into()));
+            }
+            Ok(())
+        }
+    }
+
+    impl CredentialApi for MemoryCredential {
+        fn set_secret(&self, secret: &[u8]) -> keyring::Result<()> {
+            self.check_unlocked()?;
+            with_memory_keychain(|keychain| keychain.insert(self.key.clone(), secret.to_vec()));
+            Ok(())
+        }
+
+        fn get_secret(&self) -> keyring::Result<Vec<u8>> {
+            self.check_unlocked()?;
+            with_memory_keychain(|keychain| keychain.get(&self.key).cloned())
+                .ok_or(keyring::Error::NoEntry)
+        }
+
+        fn delete_credential(&self) -> keyring::Result<()> {
+            self.check_unlocked()?;
+            with_memory_keychain(|keychain| keychain.remove(&self.key))
+                .map(|_| ())
+                .ok_or(keyring::Error::NoEntry)
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    struct MemoryCredentialBuilder;
+
+    impl CredentialBuilderApi for MemoryCredentialBuilder {
+        fn build(
+            &self,
+            _target: Option<&str>,
+            service: &str,
+            user: &str,
+        ) -> keyring::Result<Box<Credential>> {
+            Ok(Box::new(MemoryCredential {
+                key: format!("{}/{}", service, user),
+            }))
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
 
     fn setup_test_storage() {
+        static KEYCHAIN: Once = Once::new();
+        KEYCHAIN.call_once(|| {
+            keyring::set_default_credential_builder(Box::new(MemoryCredentialBuilder))
+        });
+
         let temp_dir = env::temp_dir().join("trading-journal-test-creds");
         let _ = init_storage(temp_dir);
     }
@@ -109,8 +470,8 @@ mod tests {
         let test_key = "my-test-api-key-12345";
 
         store_api_key(test_id, test_key).unwrap();
-        let retrieved = retrieve_api_key(test_id).unwrap();
-        assert_eq!(retrieved, test_key);
+        let retrieved = retrieve_api_key(test_id, "test").unwrap();
+        assert_eq!(*retrieved, test_key);
 
         delete_credentials(test_id).unwrap();
     }
@@ -122,8 +483,8 @@ mod tests {
         let test_secret = "my-secret-value-xyz";
 
         store_api_secret(test_id, test_secret).unwrap();
-        let retrieved = retrieve_api_secret(test_id).unwrap();
-        assert_eq!(retrieved, test_secret);
+        let retrieved = retrieve_api_secret(test_id, "test").unwrap();
+        assert_eq!(*retrieved, test_secret);
 
         delete_credentials(test_id).unwrap();
     }
@@ -139,8 +500,97 @@ mod tests {
 
         delete_credentials(test_id).unwrap();
 
-        assert!(retrieve_api_key(test_id).is_err());
-        assert!(retrieve_api_secret(test_id).is_err());
-        assert!(retrieve_passphrase(test_id).is_err());
+        assert!(retrieve_api_key(test_id, "test").is_err());
+        assert!(retrieve_api_secret(test_id, "test").is_err());
+        assert!(retrieve_passphrase(test_id, "test").is_err());
+    }
+
+    #[test]
+    fn test_fallback_to_file_store_when_keychain_locked() {
+        setup_test_storage();
+        let test_id = "locked-credential-004";
+
+        assert_eq!(store_api_key(test_id, "key123").unwrap(), StorageBackend::EncryptedFile);
+        assert_eq!(store_api_secret(test_id, "secret456").unwrap(), StorageBackend::EncryptedFile);
+        assert_eq!(store_passphrase(test_id, "pass789").unwrap(), StorageBackend::EncryptedFile);
+        assert_eq!(*retrieve_api_key(test_id, "test").unwrap(), "key123");
+        assert_eq!(api_key_backend(test_id), Some(StorageBackend::EncryptedFile));
+
+        delete_credentials(test_id).unwrap();
+        assert!(retrieve_api_key(test_id, "test").is_err());
+        assert_eq!(api_key_backend(test_id), None);
+
+        let test_id = "test-credential-005";
+        assert_eq!(store_api_key(test_id, "key456").unwrap(), StorageBackend::Keychain);
+        assert_eq!(api_key_backend(test_id), Some(StorageBackend::Keychain));
+        delete_credentials(test_id).unwrap();
+    }
+
+    #[test]
+    fn test_replace_credentials() {
+        setup_test_storage();
+        let test_id = "test-credential-006";
+        store_api_key(test_id, "old-key").unwrap();
+        store_api_secret(test_id, "old-secret").unwrap();
+        store_passphrase(test_id, "old-pass").unwrap();
+
+        let backend = replace_credentials(test_id, "new-key", "new-secret", None).unwrap();
+        assert_eq!(backend, StorageBackend::Keychain);
+        assert_eq!(*retrieve_api_key(test_id, "test").unwrap(), "new-key");
+        assert_eq!(*retrieve_api_secret(test_id, "test").unwrap(), "new-secret");
+        assert!(retrieve_passphrase(test_id, "test").is_err());
+
+        replace_credentials(test_id, "newer-key", "newer-secret", Some("newer-pass")).unwrap();
+        delete_credentials(test_id).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_legacy_storage() {
+        let temp_dir = env::temp_dir()
+            .join(format!("trading-journal-test-migrate-{}", uuid::Uuid::new_v4()));
+        let storage = SecureStorage::new(temp_dir.clone()).unwrap();
+        storage.store("cred-1-api-key", "key123").unwrap();
+        storage.store("cred-1-api-secret", "secret456").unwrap();
+
+        // A failed write leaves the file store as it was
+        let failed = migrate_entries(&storage, |_, _| Err(ApiError::KeychainError("locked".to_string())));
+        assert!(failed.is_err());
+        assert!(!storage.is_migrated().unwrap());
+        assert_eq!(storage.keys().unwrap().len(), 2);
+
+        let mut keychain = HashMap::new();
+        let moved = migrate_entries(&storage, |key, value| {
+            keychain.insert(key.to_string(), value.to_string());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(moved, 2);
+        assert_eq!(keychain["cred-1-api-secret"], "secret456");
+        assert!(storage.is_migrated().unwrap());
+        assert!(storage.keys().unwrap().is_empty());
+
+        // Runs only once
+        assert_eq!(migrate_entries(&storage, |_, _| panic!("migrated twice")).unwrap(), 0);
+
+        let _ = std::fs::remove_dir_all(temp_dir);
+    }
+
+    #[test]
+    fn test_access_log_pruned_after_retention() {
+        let conn = test_conn();
+        let now = 1_700_000_000;
+        insert_access(&conn, "C1", "api-key", "sync", true, now - 91 * 86_400).unwrap();
+        insert_access(&conn, "C1", "api-secret", "sync", true, now - 89 * 86_400).unwrap();
+        insert_access(&conn, "C2", "passphrase", "positions", false, now).unwrap();
+
+        assert_eq!(prune_access_log(&conn, now).unwrap(), 1);
+        let entries: Vec<(String, bool)> = conn
+            .prepare("SELECT entry, success FROM credential_access_log ORDER BY accessed_at")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(entries, vec![("api-secret".to_string(), true), ("passphrase".to_string(), false)]);
     }
 }
@@ -17,6 +17,15 @@ pub enum ApiError {
     #[error("Encryption error: {0}")]
     EncryptionError(String),
 
+    #[error("Keychain error: {0}")]
+    KeychainError(String),
+
+    #[error("Biometric authentication failed: {0}")]
+    BiometricError(String),
+
+    #[error("Blocked request to {0}: the app only calls read-only exchange endpoints")]
+    BlockedEndpoint(String),
+
     #[error("Database error: {0}")]
     DatabaseError(String),
 
@@ -31,6 +40,12 @@ pub enum ApiError {
     #[allow(dead_code)]
     NetworkError(String),
 
+    #[error("Proxy error: {0}")]
+    ProxyError(String),
+
+    #[error("Sync cancelled")]
+    Cancelled,
+
     #[error("Timeout: {0}")]
     #[allow(dead_code)]
     TimeoutError(String),
@@ -40,6 +55,17 @@ pub enum ApiError {
     Unknown(String),
 }
 
+impl ApiError {
+    /// Errors worth retrying: rate limits and network trouble, not rejected requests
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ApiError::RateLimitError(_) | ApiError::NetworkError(_) | ApiError::TimeoutError(_) => true,
+            ApiError::HttpError(e) => e.is_timeout() || e.is_connect() || e.status().is_some_and(|s| s.is_server_error()),
+            _ => false,
+        }
+    }
+}
+
 impl From<rusqlite::Error> for ApiError {
     fn from(err: rusqlite::Error) -> Self {
         ApiError::DatabaseError(err.to_string())
@@ -1,19 +1,92 @@
-use crate::api::bitget::websocket::{BitgetWebSocketClient, PositionData, PositionEvent};
+use crate::api::bitget::BitgetClient;
+use crate::api::bitget::types::{AllPositionsRequest, BitgetPosition};
+use crate::api::bitget::websocket::{
+    BitgetWebSocketClient, ConnectionHealth, PositionData, PositionEvent, PositionFills,
+    PositionKey, PositionTpSl,
+};
+use crate::api::client::ExchangeClient;
 use crate::api::credentials::{retrieve_api_key, retrieve_api_secret, retrieve_passphrase};
+use crate::commands::api_sync::{load_environment, load_symbol_filters};
+use crate::commands::app_lock::{emit_unless_locked, notification_body};
+use crate::commands::error::CommandError;
+use crate::commands::funding::{link_funding_payments, store_funding_payments};
+use crate::commands::trade_metrics::{breakeven_threshold, closed_status};
 use crate::db::Database;
 use crate::models::Trade;
 use chrono::Utc;
-use rusqlite::Connection;
+use rusqlite::{Connection, OptionalExtension};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
+/// Longest exchange payload snippet kept per event log entry
+const EVENT_PAYLOAD_MAX_CHARS: usize = 2000;
+/// Event log entries older than this are pruned when mirroring starts
+const EVENT_RETENTION_DAYS: i64 = 30;
+/// Minimum time between two live-positions-update events of a credential
+const PNL_UPDATE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+/// Wait before reconnecting a dropped connection, doubled on each quick failure
+const RECONNECT_MIN_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+const RECONNECT_MAX_DELAY: std::time::Duration = std::time::Duration::from_secs(120);
+/// How often funding payments of open positions are fetched while mirroring
+const FUNDING_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+/// How far back each funding poll looks, so a missed poll or a late bill is caught up
+const FUNDING_LOOKBACK_MS: i64 = 24 * 60 * 60 * 1000;
+/// How often the watchdog looks at a connection's last message time
+const WATCHDOG_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+/// Shortest staleness window honoured; pings go out every 30s so less would flap
+const MIN_STALE_TIMEOUT_SECS: i64 = 45;
+/// A close this close to the liquidation price (as a fraction of it) counts as liquidated
+const LIQUIDATION_TOLERANCE: f64 = 0.005;
+
+/// Health of one credential's mirror connection, returned by get_mirror_health
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MirrorHealth {
+    pub credential_id: String,
+    #[serde(flatten)]
+    pub connection: ConnectionHealth,
+}
+
+/// Open PnL of a mirrored position, streamed in live-positions-update events
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct LivePositionPnl {
+    pub pos_id: String,
+    pub trade_id: Option<String>,
+    pub pair: String,
+    pub hold_side: String,
+    pub unrealized_pnl: f64,
+    pub market_price: f64,
+}
+
+/// Payload of the live-positions-update event: every open position of a credential
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct LivePositionsUpdate {
+    pub credential_id: String,
+    pub positions: Vec<LivePositionPnl>,
+}
+
+/// Latest open positions of a connection, and whether they changed since the last emit
+#[derive(Default)]
+struct OpenPnl {
+    positions: HashMap<PositionKey, PositionData>,
+    dirty: bool,
+}
+
+/// OPEN trade mirroring a position
+#[derive(Debug, Clone)]
+struct TrackedPosition {
+    trade_id: String,
+    credential_id: String,
+    inst_id: String,
+}
+
 /// Live trade mirror manager
 pub struct LiveMirrorManager {
     active_connections: Arc<Mutex<HashMap<String, tokio::task::JoinHandle<()>>>>,
-    tracked_positions: Arc<Mutex<HashMap<String, String>>>, // pos_id -> trade_id mapping
+    tracked_positions: Arc<Mutex<HashMap<PositionKey, TrackedPosition>>>, // (pos_id, hold_side) -> trade
+    health: Arc<Mutex<HashMap<String, Arc<std::sync::Mutex<ConnectionHealth>>>>>,
 }
 
 impl LiveMirrorManager {
@@ -21,6 +94,7 @@ impl LiveMirrorManager {
         Self {
             active_connections: Arc::new(Mutex::new(HashMap::new())),
             tracked_positions: Arc::new(Mutex::new(HashMap::new())),
+            health: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -29,99 +103,238 @@ impl LiveMirrorManager {
         &self,
         credential_id: String,
         app_handle: AppHandle,
-        db: Arc<Database>,
-    ) -> Result<(), String> {
+        db: Database,
+    ) -> Result<(), CommandError> {
         // Check if already running
         let mut connections = self.active_connections.lock().await;
         if connections.contains_key(&credential_id) {
-            return Err("Live mirroring already active for this credential".to_string());
+            return Err(CommandError::Validation("Live mirroring already active for this credential".to_string()));
         }
 
         // Retrieve credentials from keychain
-        let api_key = retrieve_api_key(&credential_id).map_err(|e| e.to_string())?;
-        let api_secret = retrieve_api_secret(&credential_id).map_err(|e| e.to_string())?;
-        let passphrase = retrieve_passphrase(&credential_id).unwrap_or_default();
-
-        // Get exchange type from database
-        let exchange = {
-            let conn = db.conn.lock().map_err(|e| e.to_string())?;
-            conn.query_row(
-                "SELECT exchange FROM api_credentials WHERE id = ?",
-                [&credential_id],
-                |row| row.get::<_, String>(0),
-            )
-            .map_err(|e| format!("Failed to get exchange: {}", e))?
+        let api_key = retrieve_api_key(&credential_id, "live_mirror")?;
+        let api_secret = retrieve_api_secret(&credential_id, "live_mirror")?;
+        let passphrase = retrieve_passphrase(&credential_id, "live_mirror").unwrap_or_default();
+
+        // Get exchange type and environment from database
+        let (exchange, environment) = {
+            let conn = db.pool.get()?;
+            if let Err(e) = prune_events(&conn, &credential_id, Utc::now().timestamp()) {
+                eprintln!("Failed to prune live mirror events: {}", e);
+            }
+            let exchange = conn
+                .query_row(
+                    "SELECT exchange FROM api_credentials WHERE id = ?",
+                    [&credential_id],
+                    |row| row.get::<_, String>(0),
+                )
+                .optional()?
+                .ok_or_else(|| CommandError::NotFound(format!("Credential {} not found", credential_id)))?;
+            (exchange, load_environment(&conn, &credential_id)?)
         };
 
         if exchange != "bitget" {
-            return Err(format!("Live mirroring not supported for {}", exchange));
+            return Err(CommandError::Validation(format!("Live mirroring not supported for {}", exchange)));
         }
 
-        // Create WebSocket client
-        let ws_client = BitgetWebSocketClient::new(api_key, api_secret, passphrase);
+        // Create WebSocket client, and a REST client for funding payments
+        let rest_client = Arc::new(
+            BitgetClient::new(api_key.clone(), api_secret.clone(), passphrase.clone()).with_environment(&environment),
+        );
+        let ws_client = BitgetWebSocketClient::new(api_key, api_secret, passphrase).with_environment(&environment);
+        let health = ws_client.health();
+
+        // Positions known when this credential was last mirrored, to diff against the exchange
+        let snapshots = {
+            let conn = db.pool.get()?;
+            load_position_snapshots(&conn, &credential_id)?
+        };
+        ws_client.restore_positions(snapshots).await;
+        self.health.lock().await.insert(credential_id.clone(), Arc::clone(&health));
 
         // Clone for the task
         let tracked_positions = Arc::clone(&self.tracked_positions);
         let app_handle_clone = app_handle.clone();
         let app_handle_for_error = app_handle.clone();
-        let db_clone = Arc::clone(&db);
+        let db_clone = db.clone();
         let credential_id_clone = credential_id.clone();
         let credential_id_for_error = credential_id.clone();
+        let db_for_error = db.clone();
+        let open_pnl = Arc::new(std::sync::Mutex::new(OpenPnl::default()));
+        let open_pnl_for_events = Arc::clone(&open_pnl);
+        let pnl_stream = stream_open_pnl(
+            open_pnl,
+            Arc::clone(&self.tracked_positions),
+            app_handle.clone(),
+            credential_id.clone(),
+        );
+        let funding_poll = poll_funding(Arc::clone(&rest_client), db.clone(), credential_id.clone());
+        let resume = resume_inflight(
+            Arc::clone(&rest_client),
+            app_handle.clone(),
+            db.clone(),
+            Arc::clone(&self.tracked_positions),
+            credential_id.clone(),
+        );
+
+        let rest_client_for_replay = Arc::clone(&rest_client);
 
-        // Spawn WebSocket connection task
+        let handler = move |event: PositionEvent| {
+            track_open_pnl(&open_pnl_for_events, &event);
+            // Written in event order, before handling, so a replay sees the latest state
+            let stored = db_clone.pool.get().map_err(|e| e.to_string()).and_then(|conn| {
+                store_position_snapshot(&conn, &credential_id_clone, &event, Utc::now().timestamp())
+                    .map_err(|e| e.to_string())
+            });
+            if let Err(e) = stored {
+                eprintln!("Failed to store position snapshot: {}", e);
+            }
+
+            let app_handle = app_handle_clone.clone();
+            let db = db_clone.clone();
+            let tracked_positions = Arc::clone(&tracked_positions);
+            let credential_id = credential_id_clone.clone();
+            let rest_client = Arc::clone(&rest_client);
+
+            tokio::spawn(async move {
+                let position = event_position(&event).clone();
+                if let Err(e) = handle_position_event(
+                    event,
+                    &app_handle,
+                    &db,
+                    &tracked_positions,
+                    &credential_id,
+                    &rest_client,
+                )
+                .await
+                {
+                    eprintln!("Error handling position event: {}", e);
+                    record_event(
+                        &db,
+                        &credential_id,
+                        "ERROR",
+                        Some(&position),
+                        None,
+                        &format!("Error processing position: {}", e),
+                    );
+                    let _ = emit_unless_locked(
+                        &app_handle,
+                        "live-mirror-error",
+                        format!("Error processing position: {}", e),
+                    );
+                }
+            });
+        };
+
+        // Spawn WebSocket connection task, reconnecting whenever the connection drops.
+        // The PnL stream stops along with it.
         let handle = tokio::spawn(async move {
-            let result = ws_client
-                .connect(move |event| {
-                    let app_handle = app_handle_clone.clone();
-                    let db = Arc::clone(&db_clone);
-                    let tracked_positions = Arc::clone(&tracked_positions);
-                    let credential_id = credential_id_clone.clone();
-
-                    tokio::spawn(async move {
-                        if let Err(e) = handle_position_event(
-                            event,
-                            &app_handle,
-                            &db,
-                            &tracked_positions,
-                            &credential_id,
-                        )
-                        .await
-                        {
-                            eprintln!("Error handling position event: {}", e);
-                            let _ = app_handle.emit(
-                                "live-mirror-error",
-                                format!("Error processing position: {}", e),
-                            );
+            // Settle trades left open on exit before the position snapshot arrives
+            resume.await;
+
+            let mirror = async {
+                let mut delay = RECONNECT_MIN_DELAY;
+                loop {
+                    replay_missed_events(&ws_client, &rest_client_for_replay, handler.clone()).await;
+
+                    let connected_at = std::time::Instant::now();
+                    let stale_timeout = load_stale_timeout(&db_for_error);
+                    let message = tokio::select! {
+                        result = ws_client.connect(handler.clone()) => match result {
+                            Ok(()) => "WebSocket connection closed".to_string(),
+                            Err(e) => format!("WebSocket connection error: {}", e),
+                        },
+                        _ = watch_staleness(&health, Utc::now().timestamp(), stale_timeout) => {
+                            if let Err(e) = send_stalled_notification(&app_handle_for_error, &db_for_error, &credential_id_for_error) {
+                                eprintln!("Failed to send notification: {}", e);
+                            }
+                            let _ = app_handle_for_error.emit("live-mirror-stalled", &credential_id_for_error);
+                            format!("No message for {}s, connection stalled", stale_timeout.unwrap_or_default())
                         }
-                    });
-                })
-                .await;
+                    };
+
+                    eprintln!("{} - reconnecting in {:?}", message, delay);
+                    record_event(&db_for_error, &credential_id_for_error, "ERROR", None, None, &message);
+                    {
+                        let mut health = health.lock().unwrap_or_else(|e| e.into_inner());
+                        health.connected = false;
+                        health.last_error = Some(message);
+                    }
+                    let _ = app_handle_for_error.emit("live-mirror-disconnected", &credential_id_for_error);
 
-            if let Err(e) = result {
-                eprintln!("WebSocket connection error: {}", e);
-                let _ = app_handle_for_error.emit("live-mirror-disconnected", credential_id_for_error);
+                    // A connection that held for a while starts the backoff over
+                    if connected_at.elapsed() > RECONNECT_MAX_DELAY {
+                        delay = RECONNECT_MIN_DELAY;
+                    }
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+                    health.lock().unwrap_or_else(|e| e.into_inner()).reconnect_count += 1;
+                }
+            };
+
+            tokio::select! {
+                _ = mirror => {}
+                _ = pnl_stream => {}
+                _ = funding_poll => {}
             }
         });
 
         connections.insert(credential_id.clone(), handle);
 
         // Emit event to frontend
-        app_handle
-            .emit("live-mirror-started", credential_id)
-            .map_err(|e| e.to_string())?;
+        app_handle.emit("live-mirror-started", credential_id)?;
 
         Ok(())
     }
 
+    /// Start mirroring for every active credential with live_mirror_enabled set, as on app
+    /// launch. A credential that fails to start is logged and skipped.
+    pub async fn start_enabled(&self, app_handle: AppHandle, db: Database) {
+        let credential_ids = {
+            let conn = match db.pool.get() {
+                Ok(conn) => conn,
+                Err(e) => {
+                    eprintln!("Failed to load live mirroring credentials: {}", e);
+                    return;
+                }
+            };
+            match enabled_credential_ids(&conn) {
+                Ok(ids) => ids,
+                Err(e) => {
+                    eprintln!("Failed to load live mirroring credentials: {}", e);
+                    return;
+                }
+            }
+        }; // conn is dropped here
+
+        for credential_id in credential_ids {
+            if let Err(e) = self
+                .start_mirroring(credential_id.clone(), app_handle.clone(), db.clone())
+                .await
+            {
+                eprintln!("Failed to start live mirroring for {}: {}", credential_id, e);
+                record_event(
+                    &db,
+                    &credential_id,
+                    "ERROR",
+                    None,
+                    None,
+                    &format!("Failed to start on launch: {}", e),
+                );
+            }
+        }
+    }
+
     /// Stop live mirroring for a credential
-    pub async fn stop_mirroring(&self, credential_id: &str) -> Result<(), String> {
+    pub async fn stop_mirroring(&self, credential_id: &str) -> Result<(), CommandError> {
         let mut connections = self.active_connections.lock().await;
 
         if let Some(handle) = connections.remove(credential_id) {
             handle.abort();
+            self.health.lock().await.remove(credential_id);
             Ok(())
         } else {
-            Err("No active mirroring found for this credential".to_string())
+            Err(CommandError::NotFound("No active mirroring found for this credential".to_string()))
         }
     }
 
@@ -138,73 +351,730 @@ impl LiveMirrorManager {
         for (_, handle) in connections.drain() {
             handle.abort();
         }
+        self.health.lock().await.clear();
     }
+
+    /// Stop all mirroring on exit, recording the trades still open so the next start can
+    /// check whether their positions closed while the app was off
+    pub async fn shutdown(&self, db: &Database) {
+        self.stop_all().await;
+
+        let tracked = self.tracked_positions.lock().await;
+        if tracked.is_empty() {
+            return;
+        }
+        let result = db
+            .pool
+            .get()
+            .map_err(|e| e.to_string())
+            .and_then(|conn| save_inflight(&conn, &tracked, Utc::now().timestamp()).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => println!("Recorded {} open live trade(s) for the next start", tracked.len()),
+            Err(e) => eprintln!("Failed to record open live trades: {}", e),
+        }
+    }
+
+    /// Connection health of every running mirror
+    pub async fn health(&self) -> Vec<MirrorHealth> {
+        let health = self.health.lock().await;
+        let mut statuses: Vec<MirrorHealth> = health
+            .iter()
+            .map(|(credential_id, connection)| MirrorHealth {
+                credential_id: credential_id.clone(),
+                connection: connection.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.credential_id.cmp(&b.credential_id));
+        statuses
+    }
+}
+
+/// Credentials that should be mirrored on launch
+fn enabled_credential_ids(conn: &Connection) -> Result<Vec<String>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT id FROM api_credentials WHERE is_active = 1 AND live_mirror_enabled = 1",
+    )?;
+    let ids = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(ids)
 }
 
 /// Handle position event and create/update trades
 async fn handle_position_event(
     event: PositionEvent,
     app_handle: &AppHandle,
-    db: &Arc<Database>,
-    tracked_positions: &Arc<Mutex<HashMap<String, String>>>,
+    db: &Database,
+    tracked_positions: &Arc<Mutex<HashMap<PositionKey, TrackedPosition>>>,
     credential_id: &str,
+    rest_client: &BitgetClient,
 ) -> Result<(), String> {
     match event {
-        PositionEvent::Opened(position) => {
+        PositionEvent::Opened(position, tp_sl, fills) => {
+            // A trade resumed after a restart shows up again in the first position snapshot
+            let resumed = tracked_positions
+                .lock()
+                .await
+                .get(&position.key())
+                .map(|tracked| tracked.trade_id.clone());
+            if let Some(trade_id) = resumed {
+                update_live_trade(&trade_id, &position, db).await?;
+                update_live_trade_tp_sl(&trade_id, &tp_sl, db).await?;
+                emit_unless_locked(app_handle, "live-trade-updated", trade_id)
+                    .map_err(|e| e.to_string())?;
+                return Ok(());
+            }
+
+            // Leave pairs filtered out of the credential, e.g. traded by a bot, to themselves
+            let filters = {
+                let conn = db.pool.get().map_err(|e| e.to_string())?;
+                load_symbol_filters(&conn, credential_id).map_err(|e| e.to_string())?
+            };
+            if !filters.allows(&position.inst_id) {
+                println!("Not mirroring {}: excluded by the symbol filters", position.inst_id);
+                return Ok(());
+            }
+
             // Create new trade
-            let trade_id = create_live_trade(&position, db, credential_id).await?;
+            let trade_id = create_live_trade(&position, &tp_sl, &fills, db, credential_id).await?;
 
             // Track position
             let mut positions = tracked_positions.lock().await;
-            positions.insert(position.pos_id.clone(), trade_id.clone());
+            positions.insert(
+                position.key(),
+                TrackedPosition {
+                    trade_id: trade_id.clone(),
+                    credential_id: credential_id.to_string(),
+                    inst_id: position.inst_id.clone(),
+                },
+            );
+
+            record_event(
+                db,
+                credential_id,
+                "OPENED",
+                Some(&position),
+                Some(&trade_id),
+                &format!(
+                    "Opened {} {} {} at {}",
+                    position.hold_side, position.total, position.inst_id, position.average_open_price
+                ),
+            );
 
-            // Emit to frontend
-            app_handle
-                .emit("live-trade-opened", &trade_id)
+            // Emit to frontend, and notify in case the window is closed or minimized
+            emit_unless_locked(app_handle, "live-trade-opened", &trade_id)
                 .map_err(|e| e.to_string())?;
+            let body = format!(
+                "{} {} {} at {} ({}x)",
+                position.inst_id, position.hold_side, position.total, position.average_open_price, position.leverage
+            );
+            if let Err(e) = send_notification(app_handle, "Position Opened", &body) {
+                eprintln!("Failed to send notification: {}", e);
+            }
 
             println!("Live trade opened: {} for position {}", trade_id, position.pos_id);
         }
-        PositionEvent::Updated(position) => {
+        PositionEvent::Updated(position, closed) => {
             // Update existing trade
             let positions = tracked_positions.lock().await;
-            if let Some(trade_id) = positions.get(&position.pos_id) {
+            if let Some(TrackedPosition { trade_id, .. }) = positions.get(&position.key()) {
                 update_live_trade(trade_id, &position, db).await?;
+                let message = if closed > 0.0 {
+                    format!(
+                        "Partially closed {} at {}, {} left, realized PnL {}",
+                        closed, position.market_price, position.total, position.achieved_profits
+                    )
+                } else {
+                    format!("Unrealized PnL {} at {}", position.unrealized_pl, position.market_price)
+                };
+                record_event(db, credential_id, "UPDATED", Some(&position), Some(trade_id), &message);
+
+                if closed > 0.0 {
+                    let body = format!("{} {}: {}", position.inst_id, position.hold_side, message);
+                    if let Err(e) = send_notification(app_handle, "Position Partially Closed", &body) {
+                        eprintln!("Failed to send notification: {}", e);
+                    }
+                }
 
                 // Emit to frontend
-                app_handle
-                    .emit("live-trade-updated", trade_id.clone())
+                emit_unless_locked(app_handle, "live-trade-updated", trade_id.clone())
                     .map_err(|e| e.to_string())?;
 
                 println!("Live trade updated: {}", trade_id);
             }
         }
-        PositionEvent::Closed(position) => {
+        PositionEvent::Closed(position, fills) => {
             // Close trade
             let mut positions = tracked_positions.lock().await;
-            if let Some(trade_id) = positions.remove(&position.pos_id) {
-                close_live_trade(&trade_id, &position, db).await?;
+            if let Some(TrackedPosition { trade_id, .. }) = positions.remove(&position.key()) {
+                let (exit_price, liquidated) = close_live_trade(&trade_id, &position, &fills, db).await?;
+                // Settle the funding paid over the trade's life so its net PnL is complete
+                if let Err(e) = capture_trade_funding(rest_client, db, credential_id, &trade_id).await {
+                    eprintln!("Failed to capture funding for {}: {}", trade_id, e);
+                }
+
+                let verb = if liquidated { "Liquidated" } else { "Closed" };
+                record_event(
+                    db,
+                    credential_id,
+                    "CLOSED",
+                    Some(&position),
+                    Some(&trade_id),
+                    &format!("{} at {} with PnL {}", verb, exit_price, position.achieved_profits),
+                );
+
+                if liquidated {
+                    emit_unless_locked(app_handle, "live-trade-liquidated", trade_id.clone())
+                        .map_err(|e| e.to_string())?;
+                    if let Err(e) = send_liquidation_notification(app_handle, &position, exit_price) {
+                        eprintln!("Failed to send notification: {}", e);
+                    }
+                } else {
+                    let body = format!(
+                        "{} {} closed at {} with PnL {}",
+                        position.inst_id, position.hold_side, exit_price, position.achieved_profits
+                    );
+                    if let Err(e) = send_notification(app_handle, "Position Closed", &body) {
+                        eprintln!("Failed to send notification: {}", e);
+                    }
+                }
 
                 // Emit to frontend
-                app_handle
-                    .emit("live-trade-closed", trade_id.clone())
+                emit_unless_locked(app_handle, "live-trade-closed", trade_id.clone())
                     .map_err(|e| e.to_string())?;
 
                 println!("Live trade closed: {}", trade_id);
             }
         }
+        PositionEvent::TpSlChanged(position, tp_sl) => {
+            // Replace the trade's plan with the orders actually on the position
+            let positions = tracked_positions.lock().await;
+            if let Some(TrackedPosition { trade_id, .. }) = positions.get(&position.key()) {
+                update_live_trade_tp_sl(trade_id, &tp_sl, db).await?;
+                record_event(
+                    db,
+                    credential_id,
+                    "TP_SL",
+                    Some(&position),
+                    Some(trade_id),
+                    &format!(
+                        "Stop loss {}, {} take profit(s)",
+                        tp_sl.stop_loss.map_or("none".to_string(), |sl| sl.to_string()),
+                        tp_sl.take_profits.len()
+                    ),
+                );
+
+                // Emit to frontend
+                emit_unless_locked(app_handle, "live-trade-updated", trade_id.clone())
+                    .map_err(|e| e.to_string())?;
+
+                println!("Live trade TP/SL updated: {}", trade_id);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Fetch funding payments settled since `since_ms`, store the new ones and link them to
+/// the trades open at the time. Returns how many were new.
+async fn capture_funding(
+    client: &BitgetClient,
+    db: &Database,
+    credential_id: &str,
+    since_ms: i64,
+) -> Result<usize, String> {
+    let payments = client
+        .fetch_funding_payments(Some(since_ms), Some(Utc::now().timestamp_millis()))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let conn = db.pool.get().map_err(|e| e.to_string())?;
+    let stored = store_funding_payments(&conn, credential_id, "bitget", &payments).map_err(|e| e.to_string())?;
+    link_funding_payments(&conn).map_err(|e| e.to_string())?;
+    Ok(stored)
+}
+
+/// Capture the funding paid or received over a trade's life, from its open time
+async fn capture_trade_funding(
+    client: &BitgetClient,
+    db: &Database,
+    credential_id: &str,
+    trade_id: &str,
+) -> Result<usize, String> {
+    let trade_date: i64 = {
+        let conn = db.pool.get().map_err(|e| e.to_string())?;
+        conn.query_row("SELECT trade_date FROM trades WHERE id = ?", [trade_id], |row| row.get(0))
+            .map_err(|e| format!("Failed to get trade: {}", e))?
+    };
+    capture_funding(client, db, credential_id, trade_date * 1000).await
+}
+
+/// Capture funding of open positions every FUNDING_POLL_INTERVAL. Runs until aborted.
+async fn poll_funding(client: Arc<BitgetClient>, db: Database, credential_id: String) {
+    let mut ticker = tokio::time::interval(FUNDING_POLL_INTERVAL);
+    loop {
+        ticker.tick().await;
+        let since = Utc::now().timestamp_millis() - FUNDING_LOOKBACK_MS;
+        match capture_funding(&client, &db, &credential_id, since).await {
+            Ok(0) => {}
+            Ok(stored) => println!("Captured {} funding payment(s) for {}", stored, credential_id),
+            Err(e) => eprintln!("Failed to capture funding for {}: {}", credential_id, e),
+        }
+    }
+}
+
+/// Staleness window from settings, None when the watchdog is off
+fn load_stale_timeout(db: &Database) -> Option<i64> {
+    let timeout: i64 = db
+        .pool
+        .get()
+        .ok()
+        .and_then(|conn| {
+            conn.query_row("SELECT mirror_stale_timeout_secs FROM settings WHERE id = 1", [], |row| row.get(0))
+                .ok()
+        })
+        .unwrap_or(90);
+    (timeout > 0).then(|| timeout.max(MIN_STALE_TIMEOUT_SECS))
+}
+
+/// Resolves once the connection went `timeout_secs` without a message, counting from
+/// `started_at` until the first one. Never resolves when `timeout_secs` is None.
+async fn watch_staleness(
+    health: &std::sync::Mutex<ConnectionHealth>,
+    started_at: i64,
+    timeout_secs: Option<i64>,
+) {
+    let Some(timeout_secs) = timeout_secs else {
+        return std::future::pending().await;
+    };
+
+    let mut ticker = tokio::time::interval(WATCHDOG_CHECK_INTERVAL);
+    loop {
+        ticker.tick().await;
+        let last_message_at = health.lock().unwrap_or_else(|e| e.into_inner()).last_message_at;
+        let last_activity = last_message_at.unwrap_or(started_at).max(started_at);
+        if is_stale(last_activity, Utc::now().timestamp(), timeout_secs) {
+            return;
+        }
+    }
+}
+
+fn is_stale(last_activity: i64, now: i64, timeout_secs: i64) -> bool {
+    now - last_activity > timeout_secs
+}
+
+/// Desktop notification that a mirror went silent and is being reconnected
+fn send_stalled_notification(app_handle: &AppHandle, db: &Database, credential_id: &str) -> Result<(), String> {
+    let label = db
+        .pool
+        .get()
+        .ok()
+        .and_then(|conn| {
+            conn.query_row("SELECT label FROM api_credentials WHERE id = ?", [credential_id], |row| {
+                row.get::<_, String>(0)
+            })
+            .ok()
+        })
+        .unwrap_or_else(|| credential_id.to_string());
+
+    let body = format!("{} stopped receiving updates and is reconnecting", label);
+    send_notification(app_handle, "Live Mirror Stalled", &body)
+}
+
+/// Keep the latest data of each open position for the PnL stream
+fn track_open_pnl(open_pnl: &std::sync::Mutex<OpenPnl>, event: &PositionEvent) {
+    let mut open_pnl = open_pnl.lock().unwrap_or_else(|e| e.into_inner());
+    match event {
+        PositionEvent::Opened(position, ..) | PositionEvent::Updated(position, _) => {
+            open_pnl.positions.insert(position.key(), position.clone());
+        }
+        PositionEvent::Closed(position, _) => {
+            open_pnl.positions.remove(&position.key());
+        }
+        PositionEvent::TpSlChanged(..) => return,
+    }
+    open_pnl.dirty = true;
+}
+
+/// Emit live-positions-update with the open positions' PnL whenever they changed, at most
+/// once per PNL_UPDATE_INTERVAL, so the frontend doesn't have to poll. Runs until aborted.
+async fn stream_open_pnl(
+    open_pnl: Arc<std::sync::Mutex<OpenPnl>>,
+    tracked_positions: Arc<Mutex<HashMap<PositionKey, TrackedPosition>>>,
+    app_handle: AppHandle,
+    credential_id: String,
+) {
+    let mut ticker = tokio::time::interval(PNL_UPDATE_INTERVAL);
+    loop {
+        ticker.tick().await;
+
+        let positions: Vec<PositionData> = {
+            let mut open_pnl = open_pnl.lock().unwrap_or_else(|e| e.into_inner());
+            if !open_pnl.dirty {
+                continue;
+            }
+            open_pnl.dirty = false;
+            open_pnl.positions.values().cloned().collect()
+        };
+
+        let tracked = tracked_positions.lock().await;
+        let positions = positions
+            .into_iter()
+            .map(|position| LivePositionPnl {
+                trade_id: tracked.get(&position.key()).map(|tracked| tracked.trade_id.clone()),
+                unrealized_pnl: position.unrealized_pl.parse().unwrap_or(0.0),
+                market_price: position.market_price.parse().unwrap_or(0.0),
+                pos_id: position.pos_id,
+                pair: position.inst_id,
+                hold_side: position.hold_side,
+            })
+            .collect();
+        drop(tracked);
+
+        let _ = emit_unless_locked(
+            &app_handle,
+            "live-positions-update",
+            LivePositionsUpdate { credential_id: credential_id.clone(), positions },
+        );
+    }
+}
+
+/// Keep the last state of a credential's open position, or forget it once closed
+fn store_position_snapshot(
+    conn: &Connection,
+    credential_id: &str,
+    event: &PositionEvent,
+    now: i64,
+) -> Result<(), rusqlite::Error> {
+    let position = event_position(event);
+    if let PositionEvent::Closed(..) = event {
+        conn.execute(
+            "DELETE FROM live_mirror_positions WHERE credential_id = ? AND pos_id = ? AND hold_side = ?",
+            rusqlite::params![credential_id, position.pos_id, position.hold_side],
+        )?;
+        return Ok(());
+    }
+
+    let data = serde_json::to_string(position).unwrap_or_default();
+    conn.execute(
+        "INSERT OR REPLACE INTO live_mirror_positions (credential_id, pos_id, hold_side, data, updated_at)
+         VALUES (?, ?, ?, ?, ?)",
+        rusqlite::params![credential_id, position.pos_id, position.hold_side, data, now],
+    )?;
+    Ok(())
+}
+
+fn load_position_snapshots(conn: &Connection, credential_id: &str) -> Result<Vec<PositionData>, rusqlite::Error> {
+    let mut stmt = conn.prepare("SELECT data FROM live_mirror_positions WHERE credential_id = ?")?;
+    let rows = stmt
+        .query_map([credential_id], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows
+        .iter()
+        .filter_map(|data| match serde_json::from_str(data) {
+            Ok(position) => Some(position),
+            Err(e) => {
+                eprintln!("Skipping unreadable position snapshot: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// Replay the position events missed while disconnected, found by diffing the exchange's
+/// open positions against the last known ones. When the fetch fails, the connection's own
+/// snapshot still reports the positions that are open.
+async fn replay_missed_events<F>(ws_client: &BitgetWebSocketClient, rest_client: &BitgetClient, mut handler: F)
+where
+    F: FnMut(PositionEvent),
+{
+    let request = AllPositionsRequest {
+        product_type: "USDT-FUTURES".to_string(),
+        margin_coin: Some("USDT".to_string()),
+    };
+    match rest_client.fetch_all_positions(&request).await {
+        Ok(snapshot) => {
+            let events = ws_client.replay_snapshot(&snapshot).await;
+            if !events.is_empty() {
+                println!("Replaying {} missed position event(s)", events.len());
+            }
+            for event in events {
+                handler(event);
+            }
+        }
+        Err(e) => eprintln!("Failed to fetch positions to replay missed events: {}", e),
+    }
+}
+
+/// Trade a mirror was tracking when the app exited
+#[derive(Debug, Clone, PartialEq)]
+struct InflightTrade {
+    trade_id: String,
+    pos_id: String,
+    hold_side: String,
+    inst_id: String,
+}
+
+fn save_inflight(
+    conn: &Connection,
+    tracked: &HashMap<PositionKey, TrackedPosition>,
+    now: i64,
+) -> Result<(), rusqlite::Error> {
+    for ((pos_id, hold_side), position) in tracked {
+        conn.execute(
+            "INSERT OR REPLACE INTO live_mirror_inflight (trade_id, credential_id, pos_id, hold_side, inst_id, stopped_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![position.trade_id, position.credential_id, pos_id, hold_side, position.inst_id, now],
+        )?;
+    }
+    Ok(())
+}
+
+fn load_inflight(conn: &Connection, credential_id: &str) -> Result<Vec<InflightTrade>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT i.trade_id, i.pos_id, i.hold_side, i.inst_id
+         FROM live_mirror_inflight i
+         JOIN trades t ON t.id = i.trade_id
+         WHERE i.credential_id = ? AND t.status = 'OPEN' AND t.deleted_at IS NULL",
+    )?;
+    let trades = stmt
+        .query_map([credential_id], |row| {
+            Ok(InflightTrade {
+                trade_id: row.get(0)?,
+                pos_id: row.get(1)?,
+                hold_side: row.get(2)?,
+                inst_id: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(trades)
+}
+
+/// Open position an in-flight trade still mirrors, by position id, or by symbol and side
+/// when the exchange leaves the id out
+fn find_open_position<'a>(
+    trade: &InflightTrade,
+    positions: &'a [BitgetPosition],
+) -> Option<&'a BitgetPosition> {
+    positions.iter().find(|position| {
+        position.total.parse::<f64>().unwrap_or(0.0) > 0.0
+            && position.hold_side == trade.hold_side
+            && match &position.pos_id {
+                Some(pos_id) => *pos_id == trade.pos_id,
+                None => position.symbol == trade.inst_id,
+            }
+    })
+}
+
+/// Close a trade whose position closed while the app was off. The exit is unknown, so the
+/// trade keeps the last PnL mirrored, as reconciliation does.
+fn close_inflight_trade(conn: &Connection, trade_id: &str, now: i64) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "UPDATE trades SET
+            status = CASE WHEN COALESCE(total_pnl, 0) > 1 THEN 'WIN'
+                          WHEN COALESCE(total_pnl, 0) < -1 THEN 'LOSS'
+                          ELSE 'BE' END,
+            close_date = ?,
+            pnl_in_r = CASE WHEN one_r > 0 THEN COALESCE(total_pnl, 0) / one_r ELSE 0 END,
+            updated_at = ?
+         WHERE id = ? AND status = 'OPEN'",
+        rusqlite::params![now, now, trade_id],
+    )?;
+    Ok(())
+}
+
+/// Check the trades a credential's mirror left open on exit against its positions over
+/// REST: resume tracking the ones still open and close the others. Without positions,
+/// every trade is tracked again so the snapshot cannot duplicate it, and the check is
+/// retried on the next start.
+async fn resume_inflight(
+    rest_client: Arc<BitgetClient>,
+    app_handle: AppHandle,
+    db: Database,
+    tracked_positions: Arc<Mutex<HashMap<PositionKey, TrackedPosition>>>,
+    credential_id: String,
+) {
+    let inflight = match db.pool.get().map_err(|e| e.to_string()).and_then(|conn| {
+        load_inflight(&conn, &credential_id).map_err(|e| e.to_string())
+    }) {
+        Ok(inflight) => inflight,
+        Err(e) => {
+            eprintln!("Failed to load open live trades: {}", e);
+            return;
+        }
+    };
+    if inflight.is_empty() {
+        // Rows of trades closed or deleted by other means since
+        if let Ok(conn) = db.pool.get() {
+            let _ = conn.execute("DELETE FROM live_mirror_inflight WHERE credential_id = ?", [&credential_id]);
+        }
+        return;
+    }
+
+    let request = AllPositionsRequest {
+        product_type: "USDT-FUTURES".to_string(),
+        margin_coin: Some("USDT".to_string()),
+    };
+    let positions = rest_client.fetch_all_positions(&request).await;
+
+    let mut tracked = tracked_positions.lock().await;
+    for trade in &inflight {
+        let open = match &positions {
+            Ok(positions) => find_open_position(trade, positions),
+            Err(_) => None,
+        };
+        if positions.is_err() || open.is_some() {
+            tracked.insert(
+                (trade.pos_id.clone(), trade.hold_side.clone()),
+                TrackedPosition {
+                    trade_id: trade.trade_id.clone(),
+                    credential_id: credential_id.clone(),
+                    inst_id: trade.inst_id.clone(),
+                },
+            );
+        }
+    }
+    drop(tracked);
+
+    let positions = match positions {
+        Ok(positions) => positions,
+        Err(e) => {
+            record_event(
+                &db,
+                &credential_id,
+                "ERROR",
+                None,
+                None,
+                &format!("Failed to check {} trade(s) left open on exit: {}", inflight.len(), e),
+            );
+            return;
+        }
+    };
+
+    let Ok(conn) = db.pool.get() else {
+        return;
+    };
+    let now = Utc::now().timestamp();
+    for trade in &inflight {
+        match find_open_position(trade, &positions) {
+            Some(position) => {
+                if let Ok(unrealized_pnl) = position.unrealized_pnl.parse::<f64>() {
+                    let _ = conn.execute(
+                        "UPDATE trades SET total_pnl = ?, updated_at = ? WHERE id = ?",
+                        rusqlite::params![unrealized_pnl, now, trade.trade_id],
+                    );
+                }
+                let _ = insert_event(
+                    &conn,
+                    &credential_id,
+                    "UPDATED",
+                    None,
+                    Some(&trade.trade_id),
+                    &format!("Resumed after restart, position {} still open", trade.pos_id),
+                    now,
+                );
+            }
+            None => {
+                if let Err(e) = close_inflight_trade(&conn, &trade.trade_id, now) {
+                    eprintln!("Failed to close live trade {}: {}", trade.trade_id, e);
+                    continue;
+                }
+                let _ = insert_event(
+                    &conn,
+                    &credential_id,
+                    "CLOSED",
+                    None,
+                    Some(&trade.trade_id),
+                    &format!("Position {} closed while the app was off, keeping the last mirrored PnL", trade.pos_id),
+                    now,
+                );
+                let _ = emit_unless_locked(&app_handle, "live-trade-closed", trade.trade_id.clone());
+            }
+        }
+    }
+
+    if let Err(e) = conn.execute("DELETE FROM live_mirror_inflight WHERE credential_id = ?", [&credential_id]) {
+        eprintln!("Failed to clear open live trades: {}", e);
+    }
+}
+
+/// Position an event is about
+fn event_position(event: &PositionEvent) -> &PositionData {
+    match event {
+        PositionEvent::Opened(position, ..)
+        | PositionEvent::Updated(position, _)
+        | PositionEvent::Closed(position, _)
+        | PositionEvent::TpSlChanged(position, _) => position,
+    }
+}
+
+/// Append an entry to the event log. Failing to log is only printed, it never stops the
+/// mirror.
+fn record_event(
+    db: &Database,
+    credential_id: &str,
+    event_type: &str,
+    position: Option<&PositionData>,
+    trade_id: Option<&str>,
+    message: &str,
+) {
+    let result = db.pool.get().map_err(|e| e.to_string()).and_then(|conn| {
+        insert_event(&conn, credential_id, event_type, position, trade_id, message, Utc::now().timestamp())
+            .map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        eprintln!("Failed to record live mirror event: {}", e);
     }
+}
 
+fn insert_event(
+    conn: &Connection,
+    credential_id: &str,
+    event_type: &str,
+    position: Option<&PositionData>,
+    trade_id: Option<&str>,
+    message: &str,
+    now: i64,
+) -> Result<(), rusqlite::Error> {
+    let payload = position
+        .and_then(|position| serde_json::to_string(position).ok())
+        .map(|json| json.chars().take(EVENT_PAYLOAD_MAX_CHARS).collect::<String>());
+
+    conn.execute(
+        "INSERT INTO live_mirror_events (id, credential_id, event_type, pos_id, trade_id, message, payload, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        rusqlite::params![
+            Uuid::new_v4().to_string(),
+            credential_id,
+            event_type,
+            position.map(|position| &position.pos_id),
+            trade_id,
+            message,
+            payload,
+            now
+        ],
+    )?;
     Ok(())
 }
 
+/// Drop a credential's event log entries older than EVENT_RETENTION_DAYS
+fn prune_events(conn: &Connection, credential_id: &str, now: i64) -> Result<usize, rusqlite::Error> {
+    conn.execute(
+        "DELETE FROM live_mirror_events WHERE credential_id = ? AND created_at < ?",
+        rusqlite::params![credential_id, now - EVENT_RETENTION_DAYS * 86_400],
+    )
+}
+
 /// Create a new live trade from position data
 async fn create_live_trade(
     position: &PositionData,
-    db: &Arc<Database>,
+    tp_sl: &PositionTpSl,
+    fills: &PositionFills,
+    db: &Database,
     credential_id: &str,
 ) -> Result<String, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let conn = db.pool.get().map_err(|e| e.to_string())?;
 
     // Get portfolio settings
     let (portfolio_value, r_percent): (f64, f64) = conn
@@ -243,7 +1113,7 @@ async fn create_live_trade(
     // Calculate 1R
     let one_r = portfolio_value * r_percent;
 
-    // Estimate stop loss
+    // Use the position's stop loss order, or estimate one from 1R
     let sl_distance = one_r / quantity;
     let estimated_sl = if position_type == "LONG" {
         entry_price - sl_distance
@@ -253,14 +1123,22 @@ async fn create_live_trade(
 
     let position_size = entry_price * quantity;
 
+    // Entries at the executed fill prices when the fills were seen
+    let entries = if fills.entries.is_empty() {
+        serde_json::to_string(&vec![serde_json::json!({"price": entry_price, "percent": 100})])
+            .unwrap_or_default()
+    } else {
+        entries_json(&fills.entries)
+    };
+
     // Create trade
     let trade_id = Uuid::new_v4().to_string();
     let now = Utc::now().timestamp();
 
     // Create fingerprint for deduplication
     let fingerprint = format!(
-        "live|bitget|{}|{}|{}",
-        position.pos_id, position.inst_id, position.c_time
+        "live|bitget|{}|{}|{}|{}",
+        position.pos_id, position.inst_id, position.hold_side, position.c_time
     );
 
     let trade = Trade {
@@ -274,13 +1152,10 @@ async fn create_live_trade(
         r_percent,
         min_rr: 0.0, // Not applicable for live trades
         planned_pe: entry_price,
-        planned_sl: estimated_sl,
+        planned_sl: tp_sl.stop_loss.unwrap_or(estimated_sl),
         leverage,
-        planned_tps: "[]".to_string(),
-        planned_entries: Some(
-            serde_json::to_string(&vec![serde_json::json!({"price": entry_price, "percent": 100})])
-                .unwrap_or_default(),
-        ),
+        planned_tps: planned_tps_json(tp_sl),
+        planned_entries: Some(entries.clone()),
         position_type: position_type.to_string(),
         one_r,
         margin,
@@ -288,10 +1163,7 @@ async fn create_live_trade(
         quantity,
         planned_weighted_rr: 0.0,
         effective_pe: Some(entry_price),
-        effective_entries: Some(
-            serde_json::to_string(&vec![serde_json::json!({"price": entry_price, "percent": 100})])
-                .unwrap_or_default(),
-        ),
+        effective_entries: Some(entries),
         close_date: None,
         exits: None,
         effective_weighted_rr: None,
@@ -307,6 +1179,12 @@ async fn create_live_trade(
         execution_potential_profit: None,
         import_fingerprint: Some(fingerprint),
         import_source: "LIVE_MIRROR".to_string(),
+        account_id: None,
+        grade: None,
+        pre_emotion: None,
+        post_emotion: None,
+        liquidated: false,
+        funding_pnl: None,
         created_at: now,
         updated_at: now,
     };
@@ -320,9 +1198,9 @@ async fn create_live_trade(
 async fn update_live_trade(
     trade_id: &str,
     position: &PositionData,
-    db: &Arc<Database>,
+    db: &Database,
 ) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let conn = db.pool.get().map_err(|e| e.to_string())?;
 
     let unrealized_pl: f64 = position
         .unrealized_pl
@@ -345,16 +1223,115 @@ async fn update_live_trade(
     Ok(())
 }
 
-/// Close a live trade
-async fn close_live_trade(
+/// Update a live trade's planned TP/SL from the position's orders. A removed stop loss
+/// keeps the previous planned_sl, as a trade always has one.
+async fn update_live_trade_tp_sl(
     trade_id: &str,
+    tp_sl: &PositionTpSl,
+    db: &Database,
+) -> Result<(), String> {
+    let conn = db.pool.get().map_err(|e| e.to_string())?;
+
+    let now = Utc::now().timestamp();
+
+    conn.execute(
+        "UPDATE trades SET planned_sl = COALESCE(?, planned_sl), planned_tps = ?, updated_at = ? WHERE id = ?",
+        rusqlite::params![tp_sl.stop_loss, planned_tps_json(tp_sl), now, trade_id],
+    )
+    .map_err(|e| format!("Failed to update trade TP/SL: {}", e))?;
+
+    Ok(())
+}
+
+/// planned_tps JSON: [{price, percent}]
+fn planned_tps_json(tp_sl: &PositionTpSl) -> String {
+    let tps: Vec<serde_json::Value> = tp_sl
+        .take_profits
+        .iter()
+        .map(|(price, percent)| serde_json::json!({"price": price, "percent": percent}))
+        .collect();
+    serde_json::to_string(&tps).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// entries JSON: [{price, percent}] where percent is integer 0-100 of the filled quantity
+fn entries_json(fills: &[(f64, f64)]) -> String {
+    let total: f64 = fills.iter().map(|(_, qty)| qty).sum();
+    let entries: Vec<serde_json::Value> = fills
+        .iter()
+        .map(|(price, qty)| {
+            let pct = if total > 0.0 { (qty / total * 100.0).round() as i64 } else { 0 };
+            serde_json::json!({"price": price, "percent": pct})
+        })
+        .collect();
+    serde_json::to_string(&entries).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// exits JSON: [{price, percent}] where percent is 0-100 of the trade quantity, or of the
+/// exited quantity when more was exited (the position was added to)
+fn exits_json(fills: &[(f64, f64)], quantity: f64) -> String {
+    let exited: f64 = fills.iter().map(|(_, qty)| qty).sum();
+    let total = quantity.max(exited);
+    let exits: Vec<serde_json::Value> = fills
+        .iter()
+        .map(|(price, qty)| {
+            let pct = if total > 0.0 { qty / total * 100.0 } else { 100.0 };
+            serde_json::json!({"price": price, "percent": pct})
+        })
+        .collect();
+    serde_json::to_string(&exits).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Whether a position closed at `exit_price` was liquidated: at or past its liquidation
+/// price, give or take LIQUIDATION_TOLERANCE
+fn is_liquidation(position_type: &str, exit_price: f64, liquidation_price: f64) -> bool {
+    if liquidation_price <= 0.0 {
+        return false;
+    }
+    if position_type == "LONG" {
+        exit_price <= liquidation_price * (1.0 + LIQUIDATION_TOLERANCE)
+    } else {
+        exit_price >= liquidation_price * (1.0 - LIQUIDATION_TOLERANCE)
+    }
+}
+
+/// Desktop notification, seen even while the window is closed or minimized
+fn send_notification(app_handle: &AppHandle, title: &str, body: &str) -> Result<(), String> {
+    use tauri_plugin_notification::NotificationExt;
+
+    app_handle
+        .notification()
+        .builder()
+        .title(title)
+        .body(notification_body(app_handle, body))
+        .show()
+        .map_err(|e| e.to_string())
+}
+
+/// Desktop notification for a liquidated position, which deserves more than the usual
+/// close event
+fn send_liquidation_notification(
+    app_handle: &AppHandle,
     position: &PositionData,
-    db: &Arc<Database>,
+    exit_price: f64,
 ) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let body = format!(
+        "{} {} was liquidated at {} (PnL {})",
+        position.inst_id, position.hold_side, exit_price, position.achieved_profits
+    );
+    send_notification(app_handle, "Position Liquidated", &body)
+}
+
+/// Close a live trade, returning its average exit price and whether it was liquidated
+async fn close_live_trade(
+    trade_id: &str,
+    position: &PositionData,
+    fills: &PositionFills,
+    db: &Database,
+) -> Result<(f64, bool), String> {
+    let conn = db.pool.get().map_err(|e| e.to_string())?;
 
     // Get trade data
-    let (entry_price, _quantity, position_type, one_r): (f64, f64, String, f64) = conn
+    let (entry_price, quantity, position_type, one_r): (f64, f64, String, f64) = conn
         .query_row(
             "SELECT planned_pe, quantity, position_type, one_r FROM trades WHERE id = ?",
             [trade_id],
@@ -372,8 +1349,17 @@ async fn close_live_trade(
         .parse()
         .map_err(|e| format!("Invalid market price: {}", e))?;
 
-    let exit_price = market_price;
+    // Exit at the executed fill prices. Quantity the fills don't cover, e.g. closed while
+    // disconnected, is assumed to have closed at the last market price.
+    let mut exit_fills = fills.exits.clone();
+    let filled: f64 = exit_fills.iter().map(|(_, qty)| qty).sum();
+    if filled < quantity * 0.999 || exit_fills.is_empty() {
+        exit_fills.push((market_price, (quantity - filled).max(0.0)));
+    }
+    let exit_price = PositionFills::average_price(&exit_fills).unwrap_or(market_price);
     let total_pnl = achieved_profits;
+    let liquidation_price: f64 = position.liq_px.parse().unwrap_or(0.0);
+    let liquidated = is_liquidation(&position_type, exit_price, liquidation_price);
 
     // Calculate PnL in R
     let pnl_in_r = if one_r > 0.0 {
@@ -382,23 +1368,12 @@ async fn close_live_trade(
         0.0
     };
 
-    // Determine status
-    let status = if total_pnl > 1.0 {
-        "WIN"
-    } else if total_pnl < -1.0 {
-        "LOSS"
-    } else {
-        "BE"
-    };
+    let status = closed_status(total_pnl, breakeven_threshold(&conn).map_err(|e| e.to_string())?);
 
     let now = Utc::now().timestamp();
 
     // Create exits JSON
-    let exits = serde_json::to_string(&vec![serde_json::json!({
-        "price": exit_price,
-        "percent": 100
-    })])
-    .unwrap_or_else(|_| "[]".to_string());
+    let exits = exits_json(&exit_fills, quantity);
 
     // Calculate effective weighted RR
     let sl_distance = (entry_price - exit_price).abs();
@@ -422,6 +1397,7 @@ async fn close_live_trade(
             total_pnl = ?,
             pnl_in_r = ?,
             effective_weighted_rr = ?,
+            liquidated = ?,
             updated_at = ?
          WHERE id = ?",
         rusqlite::params![
@@ -431,13 +1407,14 @@ async fn close_live_trade(
             total_pnl,
             pnl_in_r,
             effective_weighted_rr,
+            liquidated,
             now,
             trade_id
         ],
     )
     .map_err(|e| format!("Failed to close trade: {}", e))?;
 
-    Ok(())
+    Ok((exit_price, liquidated))
 }
 
 /// Insert trade into database
@@ -497,3 +1474,139 @@ fn insert_trade(conn: &Connection, trade: &Trade) -> Result<(), rusqlite::Error>
     )?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::{insert_trade, test_conn, trade};
+
+    #[test]
+    fn test_event_log_records_and_prunes() {
+        let conn = test_conn();
+        conn.execute(
+            "INSERT INTO api_credentials (id, exchange, label, api_key, api_secret, created_at, updated_at)
+             VALUES ('C1', 'bitget', 'Main', 'key', 'secret', 0, 0)",
+            [],
+        )
+        .unwrap();
+
+        let now = 1_700_000_000;
+        insert_event(&conn, "C1", "ERROR", None, None, "stale", now - 31 * 86_400).unwrap();
+        insert_event(&conn, "C1", "ERROR", None, None, "recent", now - 86_400).unwrap();
+        assert!(insert_event(&conn, "C1", "UNKNOWN", None, None, "bad type", now).is_err());
+
+        assert_eq!(prune_events(&conn, "C1", now).unwrap(), 1);
+        let message: String = conn
+            .query_row("SELECT message FROM live_mirror_events WHERE credential_id = 'C1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(message, "recent");
+    }
+
+    #[test]
+    fn test_is_stale_after_timeout() {
+        assert!(!is_stale(1_000, 1_090, 90));
+        assert!(is_stale(1_000, 1_091, 90));
+    }
+
+    #[test]
+    fn test_is_liquidation_near_liquidation_price() {
+        assert!(is_liquidation("LONG", 80.3, 80.0));
+        assert!(is_liquidation("LONG", 79.0, 80.0));
+        assert!(!is_liquidation("LONG", 81.0, 80.0));
+        assert!(is_liquidation("SHORT", 119.5, 120.0));
+        assert!(!is_liquidation("SHORT", 118.0, 120.0));
+        // No liquidation price reported
+        assert!(!is_liquidation("LONG", 10.0, 0.0));
+    }
+
+    #[test]
+    fn test_exits_json_shares_of_trade_quantity() {
+        assert_eq!(
+            exits_json(&[(110.0, 1.0), (120.0, 3.0)], 4.0),
+            r#"[{"percent":25.0,"price":110.0},{"percent":75.0,"price":120.0}]"#
+        );
+        // Added to after opening: shares of what was exited
+        assert_eq!(exits_json(&[(110.0, 4.0)], 2.0), r#"[{"percent":100.0,"price":110.0}]"#);
+        assert_eq!(entries_json(&[(100.0, 1.0), (90.0, 1.0)]), r#"[{"percent":50,"price":100.0},{"percent":50,"price":90.0}]"#);
+    }
+
+    #[test]
+    fn test_inflight_trades_saved_and_closed() {
+        let conn = test_conn();
+        conn.execute(
+            "INSERT INTO api_credentials (id, exchange, label, api_key, api_secret, created_at, updated_at)
+             VALUES ('C1', 'bitget', 'Main', 'key', 'secret', 0, 0)",
+            [],
+        )
+        .unwrap();
+        insert_trade(&conn, &Trade { pair: "BTCUSDT".to_string(), total_pnl: Some(-150.0), ..trade("T1") });
+
+        let tracked = HashMap::from([(
+            ("P1".to_string(), "long".to_string()),
+            TrackedPosition {
+                trade_id: "T1".to_string(),
+                credential_id: "C1".to_string(),
+                inst_id: "BTCUSDT".to_string(),
+            },
+        )]);
+        save_inflight(&conn, &tracked, 1_700_000_000).unwrap();
+        let inflight = load_inflight(&conn, "C1").unwrap();
+        assert_eq!(
+            inflight,
+            vec![InflightTrade {
+                trade_id: "T1".to_string(),
+                pos_id: "P1".to_string(),
+                hold_side: "long".to_string(),
+                inst_id: "BTCUSDT".to_string(),
+            }]
+        );
+
+        close_inflight_trade(&conn, "T1", 1_700_000_100).unwrap();
+        let (status, close_date, pnl_in_r): (String, i64, f64) = conn
+            .query_row("SELECT status, close_date, pnl_in_r FROM trades WHERE id = 'T1'", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .unwrap();
+        assert_eq!((status.as_str(), close_date, pnl_in_r), ("LOSS", 1_700_000_100, -1.5));
+        // Only trades still open are checked on the next start
+        assert!(load_inflight(&conn, "C1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_position_snapshots_follow_events() {
+        let conn = test_conn();
+        conn.execute(
+            "INSERT INTO api_credentials (id, exchange, label, api_key, api_secret, created_at, updated_at)
+             VALUES ('C1', 'bitget', 'Main', 'key', 'secret', 0, 0)",
+            [],
+        )
+        .unwrap();
+
+        let position = |hold_side: &str, total: &str| -> PositionData {
+            serde_json::from_value(serde_json::json!({
+                "posId": "1", "instId": "BTCUSDT", "instType": "USDT-FUTURES", "marginCoin": "USDT",
+                "marginSize": "100", "marginMode": "crossed", "holdSide": hold_side,
+                "holdMode": "double_hold", "total": total, "available": total, "locked": "0",
+                "averageOpenPrice": "100", "leverage": "10", "achievedProfits": "0",
+                "unrealizedPL": "0", "unrealizedPLR": "0", "liqPx": "0", "keepMarginRate": "0",
+                "marketPrice": "100", "cTime": "0", "uTime": "0"
+            }))
+            .unwrap()
+        };
+
+        let opened = PositionEvent::Opened(position("long", "1"), PositionTpSl::default(), PositionFills::default());
+        store_position_snapshot(&conn, "C1", &opened, 0).unwrap();
+        store_position_snapshot(&conn, "C1", &PositionEvent::Updated(position("long", "2"), 0.0), 1).unwrap();
+        store_position_snapshot(&conn, "C1", &PositionEvent::Updated(position("short", "1"), 0.0), 1).unwrap();
+        let mut stored = load_position_snapshots(&conn, "C1").unwrap();
+        stored.sort_by(|a, b| a.hold_side.cmp(&b.hold_side));
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0].total, "2");
+
+        let closed = PositionEvent::Closed(position("short", "0"), PositionFills::default());
+        store_position_snapshot(&conn, "C1", &closed, 2).unwrap();
+        let stored = load_position_snapshots(&conn, "C1").unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].hold_side, "long");
+    }
+}
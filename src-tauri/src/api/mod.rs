@@ -1,11 +1,19 @@
+pub mod biometric;
 pub mod bitget;
 pub mod blofin;
 pub mod client;
+pub mod credential_cache;
 pub mod credentials;
 pub mod secure_storage;
 pub mod error;
 pub mod live_mirror;
+pub mod price_alerts;
+pub mod proxy;
 pub mod rate_limiter;
+pub mod read_only;
+pub mod retry;
+pub mod server_time;
 
-pub use client::RawTrade;
+pub use client::{RawClosedPosition, RawFundingPayment, RawTrade};
 pub use live_mirror::LiveMirrorManager;
+pub use price_alerts::PriceAlertManager;
@@ -0,0 +1,309 @@
+use crate::api::bitget::ticker::stream_tickers;
+use crate::commands::app_lock::{emit_unless_locked, notification_body};
+use crate::commands::price_alerts::map_row_to_price_alert;
+use crate::db::Database;
+use crate::models::PriceAlert;
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension};
+use std::sync::Arc;
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+use tokio::time::Duration;
+
+/// Delay before the ticker feed is reopened after it drops; doubles up to the maximum
+const RECONNECT_MIN_DELAY: Duration = Duration::from_secs(5);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(120);
+
+/// Watches active price alerts on the public ticker feed
+pub struct PriceAlertManager {
+    task: Mutex<Option<tokio::task::JoinHandle<()>>>,
+}
+
+impl PriceAlertManager {
+    pub fn new() -> Self {
+        Self {
+            task: Mutex::new(None),
+        }
+    }
+
+    /// Reload active alerts and resubscribe to their symbols. Call after alerts change;
+    /// the feed is closed when no alert is left to watch.
+    pub async fn refresh(&self, app_handle: AppHandle, db: Database) -> Result<(), String> {
+        let alerts = {
+            let conn = db.pool.get().map_err(|e| e.to_string())?;
+            load_active_alerts(&conn).map_err(|e| e.to_string())?
+        };
+
+        let mut task = self.task.lock().await;
+        if let Some(task) = task.take() {
+            task.abort();
+        }
+        if alerts.is_empty() {
+            return Ok(());
+        }
+
+        println!("Watching {} price alert(s)", alerts.len());
+        *task = Some(tokio::spawn(watch_alerts(alerts, app_handle, db)));
+        Ok(())
+    }
+
+    /// Close the ticker feed
+    pub async fn stop(&self) {
+        if let Some(task) = self.task.lock().await.take() {
+            task.abort();
+        }
+    }
+}
+
+fn load_active_alerts(conn: &Connection) -> Result<Vec<PriceAlert>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT * FROM alerts WHERE status = 'ACTIVE' AND exchange = 'bitget' ORDER BY created_at",
+    )?;
+    let alerts = stmt
+        .query_map([], map_row_to_price_alert)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(alerts)
+}
+
+/// Stream tickers of the alerts' symbols, reconnecting with backoff, until every alert triggered
+async fn watch_alerts(alerts: Vec<PriceAlert>, app_handle: AppHandle, db: Database) {
+    let alerts = Arc::new(std::sync::Mutex::new(alerts));
+    let mut delay = RECONNECT_MIN_DELAY;
+
+    loop {
+        let mut symbols: Vec<String> = alerts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|alert| alert.symbol.clone())
+            .collect();
+        symbols.sort();
+        symbols.dedup();
+        if symbols.is_empty() {
+            return;
+        }
+
+        let result = stream_tickers(&symbols, |ticker| {
+            let Some(price) = ticker.last_price() else {
+                return true;
+            };
+
+            // Taken out of the watch list right away so later ticks cannot fire it twice
+            let mut alerts = alerts.lock().unwrap_or_else(|e| e.into_inner());
+            let (triggered, remaining): (Vec<_>, Vec<_>) = alerts.drain(..).partition(|alert| {
+                alert.symbol == ticker.inst_id
+                    && is_triggered(&alert.condition, alert.target_price, price)
+            });
+            *alerts = remaining;
+
+            for alert in triggered {
+                let app_handle = app_handle.clone();
+                let db = db.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_triggered(&alert, price, &app_handle, &db) {
+                        eprintln!("Failed to handle price alert {}: {}", alert.id, e);
+                    }
+                });
+            }
+            !alerts.is_empty()
+        })
+        .await;
+
+        match result {
+            Ok(()) => delay = RECONNECT_MIN_DELAY,
+            Err(e) => eprintln!("Price alert feed error: {}", e),
+        }
+        if alerts.lock().unwrap_or_else(|e| e.into_inner()).is_empty() {
+            return;
+        }
+
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+    }
+}
+
+/// Whether `price` reached the alert's level
+fn is_triggered(condition: &str, target_price: f64, price: f64) -> bool {
+    match condition {
+        "ABOVE" => price >= target_price,
+        "BELOW" => price <= target_price,
+        _ => false,
+    }
+}
+
+fn handle_triggered(
+    alert: &PriceAlert,
+    price: f64,
+    app_handle: &AppHandle,
+    db: &Database,
+) -> Result<(), String> {
+    let mut conn = db.pool.get().map_err(|e| e.to_string())?;
+    let Some(alert) = trigger_alert(&mut conn, &alert.id, price, Utc::now().timestamp())
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(()); // Deleted or already triggered meanwhile
+    };
+
+    if let Err(e) = send_alert_notification(app_handle, &alert, price) {
+        eprintln!("Failed to send price alert notification: {}", e);
+    }
+    let _ = emit_unless_locked(app_handle, "price-alert-triggered", &alert);
+    Ok(())
+}
+
+/// Mark an active alert triggered at `price`, creating its planned trade if it asks for one.
+/// Returns None when the alert is no longer active.
+fn trigger_alert(
+    conn: &mut Connection,
+    id: &str,
+    price: f64,
+    now: i64,
+) -> Result<Option<PriceAlert>, rusqlite::Error> {
+    let tx = conn.transaction()?;
+
+    let Some(alert) = tx
+        .query_row(
+            "SELECT * FROM alerts WHERE id = ? AND status = 'ACTIVE'",
+            [id],
+            map_row_to_price_alert,
+        )
+        .optional()?
+    else {
+        return Ok(None);
+    };
+
+    let planned_trade_id = match &alert.plan_position_type {
+        Some(position_type) => Some(insert_alert_plan(&tx, &alert, position_type, price, now)?),
+        None => None,
+    };
+
+    tx.execute(
+        "UPDATE alerts SET status = 'TRIGGERED', triggered_at = ?, triggered_price = ?,
+            planned_trade_id = ?, updated_at = ?
+         WHERE id = ?",
+        rusqlite::params![now, price, planned_trade_id, now, id],
+    )?;
+    let alert = tx.query_row("SELECT * FROM alerts WHERE id = ?", [id], map_row_to_price_alert)?;
+
+    tx.commit()?;
+    Ok(Some(alert))
+}
+
+/// Watchlist entry at the trigger price, sized from the default settings. Stop loss and
+/// targets are left for the user to fill in.
+fn insert_alert_plan(
+    conn: &Connection,
+    alert: &PriceAlert,
+    position_type: &str,
+    price: f64,
+    now: i64,
+) -> Result<String, rusqlite::Error> {
+    let (portfolio_value, r_percent, min_rr, leverage): (f64, f64, f64, i32) = conn.query_row(
+        "SELECT initial_capital, current_r_percent, default_min_rr, default_leverage
+         FROM settings WHERE id = 1",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+    )?;
+
+    let id = format!("PLAN-{}-{}", Utc::now().timestamp_millis(), uuid::Uuid::new_v4());
+    let direction = if alert.condition == "ABOVE" { "above" } else { "below" };
+    let mut notes = format!("Price alert: {} {} {}", alert.symbol, direction, alert.target_price);
+    if !alert.note.is_empty() {
+        notes.push_str("\n\n");
+        notes.push_str(&alert.note);
+    }
+
+    conn.execute(
+        "INSERT INTO planned_trades (
+            id, pair, exchange, analysis_date, portfolio_value, r_percent, min_rr,
+            planned_pe, planned_sl, leverage, planned_tps, planned_entries, position_type,
+            one_r, margin, position_size, quantity, planned_weighted_rr, notes, account_id,
+            created_at, updated_at
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, 0, ?, '[]', NULL, ?, ?, 0, 0, 0, 0, ?, NULL, ?, ?)",
+        rusqlite::params![
+            id, alert.symbol, alert.exchange, now, portfolio_value, r_percent, min_rr,
+            price, leverage, position_type,
+            portfolio_value * r_percent, notes,
+            now, now
+        ],
+    )?;
+
+    Ok(id)
+}
+
+fn send_alert_notification(
+    app_handle: &AppHandle,
+    alert: &PriceAlert,
+    price: f64,
+) -> Result<(), String> {
+    use tauri_plugin_notification::NotificationExt;
+
+    let direction = if alert.condition == "ABOVE" { "rose above" } else { "fell below" };
+    let mut body = format!("{} {} {} (last {})", alert.symbol, direction, alert.target_price, price);
+    if !alert.note.is_empty() {
+        body.push_str(&format!(" - {}", alert.note));
+    }
+    if alert.planned_trade_id.is_some() {
+        body.push_str(". Planned trade added to the watchlist.");
+    }
+
+    app_handle
+        .notification()
+        .builder()
+        .title("Price Alert")
+        .body(notification_body(app_handle, &body))
+        .show()
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::test_conn;
+
+    #[test]
+    fn test_is_triggered() {
+        assert!(is_triggered("ABOVE", 100.0, 100.0));
+        assert!(is_triggered("ABOVE", 100.0, 101.0));
+        assert!(!is_triggered("ABOVE", 100.0, 99.0));
+        assert!(is_triggered("BELOW", 100.0, 99.0));
+        assert!(!is_triggered("BELOW", 100.0, 101.0));
+        assert!(!is_triggered("CROSS", 100.0, 100.0));
+    }
+
+    #[test]
+    fn test_trigger_alert_creates_plan_once() {
+        let mut conn = test_conn();
+        conn.execute(
+            "INSERT INTO alerts (id, symbol, condition, target_price, note, plan_position_type, created_at, updated_at)
+             VALUES ('A1', 'BTCUSDT', 'ABOVE', 100000, 'breakout', 'LONG', 0, 0),
+                    ('A2', 'ETHUSDT', 'BELOW', 2000, '', NULL, 0, 0)",
+            [],
+        )
+        .unwrap();
+
+        let alert = trigger_alert(&mut conn, "A1", 100_050.0, 1_700_000_000).unwrap().unwrap();
+        assert_eq!(alert.status, "TRIGGERED");
+        assert_eq!(alert.triggered_price, Some(100_050.0));
+        let (pe, position_type, notes): (f64, String, String) = conn
+            .query_row(
+                "SELECT planned_pe, position_type, notes FROM planned_trades WHERE id = ?",
+                [alert.planned_trade_id.unwrap()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(pe, 100_050.0);
+        assert_eq!(position_type, "LONG");
+        assert!(notes.ends_with("breakout"));
+
+        // Already triggered
+        assert!(trigger_alert(&mut conn, "A1", 100_100.0, 1_700_000_001).unwrap().is_none());
+
+        let alert = trigger_alert(&mut conn, "A2", 1_990.0, 1_700_000_000).unwrap().unwrap();
+        assert_eq!(alert.planned_trade_id, None);
+        let plans: i64 = conn
+            .query_row("SELECT COUNT(*) FROM planned_trades", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(plans, 1);
+    }
+}
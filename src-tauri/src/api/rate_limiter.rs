@@ -1,16 +1,22 @@
 use governor::{
     clock::DefaultClock,
+    middleware::StateInformationMiddleware,
     state::{InMemoryState, NotKeyed},
     Quota, RateLimiter as GovernorRateLimiter,
 };
+use std::collections::HashMap;
 use std::num::NonZeroU32;
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::Duration;
 
 use super::client::RateLimitConfig;
 
+/// One limiter per exchange, shared by every client of that exchange
+static SHARED_LIMITERS: OnceLock<Mutex<HashMap<String, Arc<RateLimiter>>>> = OnceLock::new();
+
 /// Rate limiter wrapper using token bucket algorithm
 pub struct RateLimiter {
-    limiter: GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>,
+    limiter: GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock, StateInformationMiddleware>,
 }
 
 impl RateLimiter {
@@ -22,11 +28,23 @@ impl RateLimiter {
             .unwrap_or(NonZeroU32::new(config.requests_per_second).unwrap());
 
         let quota = Quota::per_second(per_second).allow_burst(burst);
-        let limiter = GovernorRateLimiter::direct(quota);
+        let limiter = GovernorRateLimiter::direct(quota).with_middleware::<StateInformationMiddleware>();
 
         Self { limiter }
     }
 
+    /// Limiter shared by all clients of `exchange`, so manual syncs, auto-syncs and position
+    /// fetches stay under the exchange's limit together. `config` is only used the first
+    /// time an exchange is seen.
+    pub fn shared(exchange: &str, config: RateLimitConfig) -> Arc<Self> {
+        let limiters = SHARED_LIMITERS.get_or_init(|| Mutex::new(HashMap::new()));
+        let mut limiters = limiters.lock().unwrap_or_else(|e| e.into_inner());
+        limiters
+            .entry(exchange.to_lowercase())
+            .or_insert_with(|| Arc::new(Self::new(config)))
+            .clone()
+    }
+
     /// Wait until a request can be made (blocking)
     pub async fn acquire(&self) {
         while self.limiter.check().is_err() {
@@ -34,6 +52,14 @@ impl RateLimiter {
         }
     }
 
+    /// Requests that can go out right now without waiting. Measuring takes one of them, as
+    /// the request it precedes would.
+    pub fn headroom(&self) -> u32 {
+        self.limiter
+            .check()
+            .map_or(0, |snapshot| snapshot.remaining_burst_capacity() + 1)
+    }
+
     /// Try to acquire a token without blocking
     #[allow(dead_code)]
     pub fn try_acquire(&self) -> bool {
@@ -73,6 +99,33 @@ mod tests {
 
         // Next should fail (no blocking)
         assert!(!limiter.try_acquire());
+        assert_eq!(limiter.headroom(), 0);
+
+        let limiter = RateLimiter::new(RateLimitConfig {
+            requests_per_second: 1,
+            burst_size: 3,
+        });
+        assert_eq!(limiter.headroom(), 3);
+        assert_eq!(limiter.headroom(), 2);
+    }
+
+    #[test]
+    fn test_shared_rate_limiter_is_per_exchange() {
+        let config = || RateLimitConfig {
+            requests_per_second: 1,
+            burst_size: 1,
+        };
+        let a = RateLimiter::shared("test-exchange-a", config());
+        let b = RateLimiter::shared("Test-Exchange-A", config());
+        let other = RateLimiter::shared("test-exchange-b", config());
+
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &other));
+
+        // A token taken through one handle is gone for the other
+        assert!(a.try_acquire());
+        assert!(!b.try_acquire());
+        assert!(other.try_acquire());
     }
 
     #[tokio::test]
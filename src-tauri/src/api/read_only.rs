@@ -0,0 +1,102 @@
+//! Read-only guardrail: the journal never trades on the user's behalf.
+//!
+//! Exchange clients reach the exchanges only through `ReadOnlyHttpClient`, which can send
+//! GET requests and nothing else, and only to the read endpoints allowlisted per exchange.
+//! Order placement, cancellation, transfers and withdrawals are POST endpoints on every
+//! supported exchange, so they cannot be expressed at all; a GET to a path missing from
+//! the allowlist fails before anything leaves the machine.
+
+use super::error::ApiError;
+use super::proxy;
+
+/// Read endpoints BitGet clients may call
+pub const BITGET_READ_PATHS: &[&str] = &[
+    "/api/v2/public/time",
+    "/api/v2/mix/order/fill-history",
+    "/api/v2/mix/position/all-position",
+    "/api/v2/mix/order/orders-pending",
+    "/api/v2/mix/account/bill",
+    "/api/v2/spot/account/info",
+];
+
+/// Read endpoints BloFin clients may call
+pub const BLOFIN_READ_PATHS: &[&str] = &[
+    "/api/v1/market/tickers",
+    "/api/v1/trade/trade-history",
+    "/api/v1/account/positions-history",
+    "/api/v1/user/query-apikey",
+];
+
+/// HTTP client that only sends GET requests to allowlisted paths of one exchange
+pub struct ReadOnlyHttpClient {
+    /// Err when the configured proxy can't be used; every request then fails
+    inner: Result<reqwest::Client, String>,
+    base_url: String,
+    allowed_paths: &'static [&'static str],
+}
+
+impl ReadOnlyHttpClient {
+    pub fn new(base_url: &str, allowed_paths: &'static [&'static str]) -> Self {
+        Self {
+            inner: proxy::client_builder()
+                .map(|builder| builder.build().expect("Failed to build the HTTP client")),
+            base_url: base_url.to_string(),
+            allowed_paths,
+        }
+    }
+
+    /// Send requests to another host of the exchange, e.g. its demo trading servers. The
+    /// allowlist still applies.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// GET request to `request_path` (path plus query string)
+    pub fn get(&self, request_path: &str) -> Result<reqwest::RequestBuilder, ApiError> {
+        if !is_allowed(self.allowed_paths, request_path) {
+            eprintln!("ERROR: Blocked request to non-allowlisted endpoint {}", request_path);
+            return Err(ApiError::BlockedEndpoint(request_path.to_string()));
+        }
+        let inner = self.inner.as_ref().map_err(|e| ApiError::ProxyError(e.clone()))?;
+        Ok(inner.get(format!("{}{}", self.base_url, request_path)))
+    }
+}
+
+/// Whether the path of `request_path`, query string aside, is one of `allowed_paths`
+fn is_allowed(allowed_paths: &[&str], request_path: &str) -> bool {
+    let path = request_path.split(['?', '#']).next().unwrap_or_default();
+    allowed_paths.contains(&path)
+}
+
+/// Exchange clients hold their HTTP access as a `ReadOnlyHttpClient`. `ExchangeClient`
+/// requires this trait, so no exchange client can be written without the guard.
+pub trait ReadOnlyExchangeClient {
+    fn http(&self) -> &ReadOnlyHttpClient;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_allowlisted_read_paths_pass() {
+        assert!(is_allowed(BITGET_READ_PATHS, "/api/v2/mix/order/fill-history?productType=USDT-FUTURES&limit=1"));
+        assert!(is_allowed(BLOFIN_READ_PATHS, "/api/v1/user/query-apikey"));
+
+        // Trading and withdrawal endpoints
+        assert!(!is_allowed(BITGET_READ_PATHS, "/api/v2/mix/order/place-order"));
+        assert!(!is_allowed(BITGET_READ_PATHS, "/api/v2/spot/wallet/withdrawal"));
+        assert!(!is_allowed(BLOFIN_READ_PATHS, "/api/v1/trade/order"));
+        // No prefix or lookalike matches
+        assert!(!is_allowed(BITGET_READ_PATHS, "/api/v2/mix/order/fill-history/../place-order"));
+        assert!(!is_allowed(BITGET_READ_PATHS, "/api/v2/mix/order"));
+
+        let client = ReadOnlyHttpClient::new("https://api.bitget.com", BITGET_READ_PATHS);
+        assert!(matches!(
+            client.get("/api/v2/mix/order/cancel-order?symbol=BTCUSDT"),
+            Err(ApiError::BlockedEndpoint(_))
+        ));
+        assert!(client.get("/api/v2/public/time").is_ok());
+    }
+}
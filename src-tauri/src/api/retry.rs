@@ -0,0 +1,94 @@
+use rand::Rng;
+use std::future::Future;
+use std::time::Duration;
+
+use super::error::ApiError;
+
+/// Attempts made for a request that keeps failing with a transient error
+const MAX_ATTEMPTS: u32 = 4;
+const BASE_DELAY: Duration = Duration::from_millis(500);
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Exponential backoff with jitter for the given retry (0 = first retry): half of
+/// `base * 2^attempt` plus a random share of the other half, capped at `max`. The jitter
+/// keeps clients that failed together from retrying in lockstep.
+pub fn backoff_delay(attempt: u32, base: Duration, max: Duration) -> Duration {
+    let exponential = base.saturating_mul(2u32.saturating_pow(attempt)).min(max);
+    let half = exponential / 2;
+    half + half.mul_f64(rand::thread_rng().gen_range(0.0..=1.0))
+}
+
+/// Run a request, retrying rate limit and network errors with backoff. Other errors
+/// (bad credentials, exchange rejections) are returned immediately.
+pub async fn retry_transient<T, F, Fut>(request: F) -> Result<T, ApiError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ApiError>>,
+{
+    retry_with_backoff(request, MAX_ATTEMPTS, BASE_DELAY).await
+}
+
+async fn retry_with_backoff<T, F, Fut>(mut request: F, max_attempts: u32, base: Duration) -> Result<T, ApiError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ApiError>>,
+{
+    let mut attempt = 0;
+    loop {
+        match request().await {
+            Err(e) if e.is_transient() && attempt + 1 < max_attempts => {
+                let delay = backoff_delay(attempt, base, MAX_DELAY);
+                eprintln!("Transient API error ({}), retrying in {:?}", e, delay);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_backoff_delay_grows_and_is_capped() {
+        for attempt in 0..4 {
+            let full = BASE_DELAY * 2u32.pow(attempt);
+            let delay = backoff_delay(attempt, BASE_DELAY, MAX_DELAY);
+            assert!(delay >= full / 2 && delay <= full);
+        }
+        assert!(backoff_delay(20, BASE_DELAY, MAX_DELAY) <= MAX_DELAY);
+    }
+
+    #[tokio::test]
+    async fn test_retry_only_retries_transient_errors() {
+        let base = Duration::from_millis(1);
+        let calls = Cell::new(0);
+        let result = retry_with_backoff(
+            || {
+                calls.set(calls.get() + 1);
+                async { Err::<(), _>(ApiError::RateLimitError("slow down".to_string())) }
+            },
+            3,
+            base,
+        )
+        .await;
+        assert!(matches!(result, Err(ApiError::RateLimitError(_))));
+        assert_eq!(calls.get(), 3);
+
+        calls.set(0);
+        let result = retry_with_backoff(
+            || {
+                calls.set(calls.get() + 1);
+                async { Err::<(), _>(ApiError::AuthenticationError("bad key".to_string())) }
+            },
+            3,
+            base,
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+}
@@ -19,11 +19,96 @@ struct EncryptedCredential {
     ciphertext: String, // Base64 encoded encrypted data
 }
 
+/// Data encrypted with a key derived from a password, carrying what decryption needs
+/// except the password
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PasswordEncrypted {
+    pub version: u8,
+    pub salt: String,       // Base64 encoded salt for key derivation
+    pub nonce: String,      // Base64 encoded nonce
+    pub ciphertext: String, // Base64 encoded encrypted data
+}
+
+/// Encrypt with AES-256-GCM under a key derived from `password` with Argon2id
+pub fn encrypt_with_password(plaintext: &[u8], password: &str) -> Result<PasswordEncrypted, ApiError> {
+    let salt = new_salt();
+    let key = SecureStorage::derive_key(password, &salt)?;
+    let encrypted = encrypt(&key, plaintext)?;
+
+    Ok(PasswordEncrypted {
+        version: ENCRYPTION_VERSION,
+        salt,
+        nonce: encrypted.nonce,
+        ciphertext: encrypted.ciphertext,
+    })
+}
+
+/// Decrypt data from `encrypt_with_password`. A wrong password fails authentication.
+pub fn decrypt_with_password(data: &PasswordEncrypted, password: &str) -> Result<Vec<u8>, ApiError> {
+    let key = SecureStorage::derive_key(password, &data.salt)?;
+    decrypt(
+        &key,
+        &EncryptedCredential {
+            nonce: data.nonce.clone(),
+            ciphertext: data.ciphertext.clone(),
+        },
+    )
+}
+
+/// Fresh random salt, Base64 encoded
+fn new_salt() -> String {
+    let mut salt_bytes = vec![0u8; 16];
+    OsRng.fill_bytes(&mut salt_bytes);
+    BASE64.encode(&salt_bytes)
+}
+
+fn encrypt(key: &[u8], plaintext: &[u8]) -> Result<EncryptedCredential, ApiError> {
+    let cipher = Aes256Gcm::new_from_slice(key)
+        .map_err(|e| ApiError::EncryptionError(format!("Failed to create cipher: {}", e)))?;
+
+    // Generate random nonce
+    let mut nonce_bytes = [0u8; 12];
+    OsRng.fill_bytes(&mut nonce_bytes);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let ciphertext = cipher
+        .encrypt(nonce, plaintext)
+        .map_err(|e| ApiError::EncryptionError(format!("Encryption failed: {}", e)))?;
+
+    Ok(EncryptedCredential {
+        nonce: BASE64.encode(nonce_bytes),
+        ciphertext: BASE64.encode(&ciphertext),
+    })
+}
+
+fn decrypt(key: &[u8], encrypted: &EncryptedCredential) -> Result<Vec<u8>, ApiError> {
+    let cipher = Aes256Gcm::new_from_slice(key)
+        .map_err(|e| ApiError::EncryptionError(format!("Failed to create cipher: {}", e)))?;
+
+    // Decode nonce and ciphertext
+    let nonce_bytes = BASE64.decode(&encrypted.nonce)
+        .map_err(|e| ApiError::EncryptionError(format!("Invalid nonce: {}", e)))?;
+    if nonce_bytes.len() != 12 {
+        return Err(ApiError::EncryptionError("Invalid nonce length".to_string()));
+    }
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let ciphertext = BASE64.decode(&encrypted.ciphertext)
+        .map_err(|e| ApiError::EncryptionError(format!("Invalid ciphertext: {}", e)))?;
+
+    cipher
+        .decrypt(nonce, ciphertext.as_ref())
+        .map_err(|e| ApiError::EncryptionError(format!("Decryption failed: {}", e)))
+}
+
 #[derive(Serialize, Deserialize)]
 struct CredentialStore {
     version: u8,
     salt: String,              // Base64 encoded salt for key derivation
     credentials: HashMap<String, EncryptedCredential>,
+    /// When the entries were moved to the system keychain (Unix seconds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    migrated_at: Option<i64>,
 }
 
 pub struct SecureStorage {
@@ -70,13 +155,11 @@ impl SecureStorage {
                 .map_err(|e| ApiError::EncryptionError(format!("Failed to parse store: {}", e)))
         } else {
             // Create new store with fresh salt
-            let mut salt_bytes = vec![0u8; 16];
-            OsRng.fill_bytes(&mut salt_bytes);
-
             Ok(CredentialStore {
                 version: ENCRYPTION_VERSION,
-                salt: BASE64.encode(&salt_bytes),
+                salt: new_salt(),
                 credentials: HashMap::new(),
+                migrated_at: None,
             })
         }
     }
@@ -99,8 +182,8 @@ impl SecureStorage {
         format!("trading-journal-{}-{}", hostname, username)
     }
 
-    /// Derive encryption key from machine ID using persistent salt
-    fn derive_key(machine_id: &str, salt_b64: &str) -> Result<Vec<u8>, ApiError> {
+    /// Derive encryption key from a secret (the machine ID, or a password) and salt
+    fn derive_key(secret: &str, salt_b64: &str) -> Result<Vec<u8>, ApiError> {
         use argon2::{Algorithm, Params, Version};
 
         let salt_bytes = BASE64.decode(salt_b64)
@@ -114,7 +197,7 @@ impl SecureStorage {
 
         let mut output_key = [0u8; 32]; // 32 bytes for AES-256
         argon2
-            .hash_password_into(machine_id.as_bytes(), &salt_bytes, &mut output_key)
+            .hash_password_into(secret.as_bytes(), &salt_bytes, &mut output_key)
             .map_err(|e| ApiError::EncryptionError(format!("Key derivation failed: {}", e)))?;
 
         Ok(output_key.to_vec())
@@ -142,28 +225,12 @@ impl SecureStorage {
 
     /// Encrypt and store a credential
     pub fn store(&self, key: &str, value: &str) -> Result<(), ApiError> {
-        let cipher = Aes256Gcm::new_from_slice(&self.master_key)
-            .map_err(|e| ApiError::EncryptionError(format!("Failed to create cipher: {}", e)))?;
-
-        // Generate random nonce
-        let mut nonce_bytes = [0u8; 12];
-        OsRng.fill_bytes(&mut nonce_bytes);
-        let nonce = Nonce::from_slice(&nonce_bytes);
-
         // Encrypt the value
-        let ciphertext = cipher
-            .encrypt(nonce, value.as_bytes())
-            .map_err(|e| ApiError::EncryptionError(format!("Encryption failed: {}", e)))?;
+        let encrypted = encrypt(&self.master_key, value.as_bytes())?;
 
         // Store encrypted credential
         let mut store = self.load_store()?;
-        store.credentials.insert(
-            key.to_string(),
-            EncryptedCredential {
-                nonce: BASE64.encode(nonce_bytes),
-                ciphertext: BASE64.encode(&ciphertext),
-            },
-        );
+        store.credentials.insert(key.to_string(), encrypted);
 
         self.save_store(&store)?;
         println!("✓ Credential '{}' stored and encrypted successfully", key);
@@ -177,28 +244,14 @@ impl SecureStorage {
         let encrypted = store.credentials.get(key)
             .ok_or_else(|| ApiError::EncryptionError(format!("Credential '{}' not found", key)))?;
 
-        let cipher = Aes256Gcm::new_from_slice(&self.master_key)
-            .map_err(|e| ApiError::EncryptionError(format!("Failed to create cipher: {}", e)))?;
-
-        // Decode nonce and ciphertext
-        let nonce_bytes = BASE64.decode(&encrypted.nonce)
-            .map_err(|e| ApiError::EncryptionError(format!("Invalid nonce: {}", e)))?;
-        let nonce = Nonce::from_slice(&nonce_bytes);
-
-        let ciphertext = BASE64.decode(&encrypted.ciphertext)
-            .map_err(|e| ApiError::EncryptionError(format!("Invalid ciphertext: {}", e)))?;
-
         // Decrypt
-        let plaintext = cipher
-            .decrypt(nonce, ciphertext.as_ref())
-            .map_err(|e| ApiError::EncryptionError(format!("Decryption failed: {}", e)))?;
+        let plaintext = decrypt(&self.master_key, encrypted)?;
 
         String::from_utf8(plaintext)
             .map_err(|e| ApiError::EncryptionError(format!("Invalid UTF-8: {}", e)))
     }
 
     /// Delete a credential
-    #[allow(dead_code)]
     pub fn delete(&self, key: &str) -> Result<(), ApiError> {
         let mut store = self.load_store()?;
         store.credentials.remove(key);
@@ -206,12 +259,23 @@ impl SecureStorage {
         Ok(())
     }
 
-    /// Delete all credentials for a given prefix
-    pub fn delete_all_with_prefix(&self, prefix: &str) -> Result<(), ApiError> {
+    /// Names of all stored credentials
+    pub fn keys(&self) -> Result<Vec<String>, ApiError> {
+        let store = self.load_store()?;
+        Ok(store.credentials.into_keys().collect())
+    }
+
+    /// Whether the entries were moved to the system keychain
+    pub fn is_migrated(&self) -> Result<bool, ApiError> {
+        Ok(self.load_store()?.migrated_at.is_some())
+    }
+
+    /// Record that the entries now live in the system keychain and drop them from the file
+    pub fn mark_migrated(&self) -> Result<(), ApiError> {
         let mut store = self.load_store()?;
-        store.credentials.retain(|k, _| !k.starts_with(prefix));
-        self.save_store(&store)?;
-        Ok(())
+        store.credentials.clear();
+        store.migrated_at = Some(chrono::Utc::now().timestamp());
+        self.save_store(&store)
     }
 }
 
@@ -247,6 +311,18 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_password_encryption_roundtrip() {
+        let encrypted = encrypt_with_password(b"full trading history", "hunter22").unwrap();
+        assert_eq!(decrypt_with_password(&encrypted, "hunter22").unwrap(), b"full trading history");
+        assert!(decrypt_with_password(&encrypted, "hunter23").is_err());
+
+        // A fresh salt and nonce every time
+        let again = encrypt_with_password(b"full trading history", "hunter22").unwrap();
+        assert_ne!(encrypted.salt, again.salt);
+        assert_ne!(encrypted.ciphertext, again.ciphertext);
+    }
+
     #[test]
     fn test_delete() {
         let storage = get_test_storage();
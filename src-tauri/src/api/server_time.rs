@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use super::error::ApiError;
+
+/// How long a measured clock offset is trusted before the server time is fetched again
+const OFFSET_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Server clock minus local clock in milliseconds, per exchange
+static OFFSETS: OnceLock<Mutex<HashMap<String, (i64, Instant)>>> = OnceLock::new();
+
+fn offsets() -> std::sync::MutexGuard<'static, HashMap<String, (i64, Instant)>> {
+    OFFSETS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Cached offset for `exchange`, if it was measured less than OFFSET_TTL ago
+fn cached_offset(exchange: &str) -> Option<i64> {
+    offsets()
+        .get(exchange)
+        .filter(|(_, measured_at)| measured_at.elapsed() < OFFSET_TTL)
+        .map(|(offset, _)| *offset)
+}
+
+/// Offset between a server time and the local clock around the request that returned it.
+/// The midpoint of the round trip stands in for when the server read its clock.
+pub fn measure_offset(server_ms: i64, sent_ms: i64, received_ms: i64) -> i64 {
+    server_ms - (sent_ms + (received_ms - sent_ms) / 2)
+}
+
+/// Forget the offset of `exchange` so the next signed request measures it again
+fn invalidate(exchange: &str) {
+    offsets().remove(exchange);
+}
+
+/// Call with an exchange error message: a rejected timestamp means the offset is stale
+pub fn invalidate_on_timestamp_error(exchange: &str, message: &str) {
+    if message.to_lowercase().contains("timestamp") {
+        invalidate(exchange);
+    }
+}
+
+/// Like now_millis without fetching: the local clock corrected by whatever offset the
+/// REST client last measured, for signers that can't make a request first
+pub fn cached_now_millis(exchange: &str) -> i64 {
+    let offset = offsets().get(exchange).map(|(offset, _)| *offset).unwrap_or(0);
+    chrono::Utc::now().timestamp_millis() + offset
+}
+
+/// Current time in milliseconds on `exchange`'s clock, for signing requests.
+/// `fetch_server_ms` is only called when no fresh offset is cached. When it fails the
+/// last known offset is kept (none means the local clock is used) and the next request
+/// tries again.
+pub async fn now_millis<F, Fut>(exchange: &str, fetch_server_ms: F) -> i64
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<i64, ApiError>>,
+{
+    if let Some(offset) = cached_offset(exchange) {
+        return chrono::Utc::now().timestamp_millis() + offset;
+    }
+
+    let sent_ms = chrono::Utc::now().timestamp_millis();
+    match fetch_server_ms().await {
+        Ok(server_ms) => {
+            let received_ms = chrono::Utc::now().timestamp_millis();
+            let offset = measure_offset(server_ms, sent_ms, received_ms);
+            offsets().insert(exchange.to_string(), (offset, Instant::now()));
+            received_ms + offset
+        }
+        Err(e) => {
+            eprintln!("Failed to fetch {} server time, signing with the local clock: {}", exchange, e);
+            cached_now_millis(exchange)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure_offset_uses_round_trip_midpoint() {
+        // Server is 5s ahead, request took 200ms
+        assert_eq!(measure_offset(1_000_005_100, 1_000_000_000, 1_000_000_200), 5_000);
+        // Server is behind
+        assert_eq!(measure_offset(999_998_000, 1_000_000_000, 1_000_000_000), -2_000);
+    }
+
+    #[tokio::test]
+    async fn test_now_millis_caches_offset_per_exchange() {
+        let ahead = chrono::Utc::now().timestamp_millis() + 60_000;
+        let now = now_millis("test-clock-ahead", || async move { Ok(ahead) }).await;
+        assert!((now - ahead).abs() < 1_000);
+
+        // Cached: the fetch is not called again
+        let now = now_millis("test-clock-ahead", || async { panic!("offset should be cached") }).await;
+        assert!(now - chrono::Utc::now().timestamp_millis() > 59_000);
+
+        // A failed fetch falls back to the local clock
+        let now = now_millis("test-clock-down", || async {
+            Err(ApiError::NetworkError("unreachable".to_string()))
+        })
+        .await;
+        assert!((now - chrono::Utc::now().timestamp_millis()).abs() < 1_000);
+
+        invalidate_on_timestamp_error("test-clock-ahead", "Insufficient balance");
+        assert!(cached_offset("test-clock-ahead").is_some());
+        invalidate_on_timestamp_error("test-clock-ahead", "Request timestamp expired");
+        assert!(cached_offset("test-clock-ahead").is_none());
+    }
+}
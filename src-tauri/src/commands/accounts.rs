@@ -0,0 +1,183 @@
+use tauri::State;
+use crate::db::Database;
+use crate::commands::error::CommandError;
+use crate::models::{Account, CreateAccountInput, UpdateAccountInput};
+use chrono::Utc;
+use rusqlite::OptionalExtension;
+
+fn map_row_to_account(row: &rusqlite::Row) -> rusqlite::Result<Account> {
+    Ok(Account {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        exchange: row.get("exchange")?,
+        is_paper: row.get::<_, i32>("is_paper")? == 1,
+        initial_capital: row.get("initial_capital")?,
+        current_r_percent: row.get("current_r_percent")?,
+        currency: row.get("currency")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+#[tauri::command]
+pub async fn get_accounts(db: State<'_, Database>) -> Result<Vec<Account>, CommandError> {
+    let conn = db.pool.get()?;
+
+    let mut stmt = conn
+        .prepare("SELECT * FROM accounts ORDER BY name COLLATE NOCASE")?;
+
+    let accounts = stmt
+        .query_map([], map_row_to_account)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(accounts)
+}
+
+#[tauri::command]
+pub async fn get_account(
+    db: State<'_, Database>,
+    id: String,
+) -> Result<Account, CommandError> {
+    let conn = db.pool.get()?;
+
+    conn.query_row("SELECT * FROM accounts WHERE id = ?", [&id], map_row_to_account)
+        .map_err(CommandError::from)
+}
+
+/// Create an account. Capital settings default to the current global settings.
+#[tauri::command]
+pub async fn create_account(
+    db: State<'_, Database>,
+    account: CreateAccountInput,
+) -> Result<Account, CommandError> {
+    let name = account.name.trim().to_string();
+    if name.is_empty() {
+        return Err(CommandError::Validation("Account name cannot be empty".to_string()));
+    }
+
+    let id = {
+        let conn = db.pool.get()?;
+
+        let (initial_capital, current_r_percent, currency): (f64, f64, String) = conn
+            .query_row(
+                "SELECT initial_capital, current_r_percent, currency FROM settings WHERE id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )?;
+
+        let id = format!("ACCOUNT-{}-{}", Utc::now().timestamp_millis(), uuid::Uuid::new_v4());
+        let now = Utc::now().timestamp();
+
+        conn.execute(
+            "INSERT INTO accounts (id, name, exchange, is_paper, initial_capital, current_r_percent, currency, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                id,
+                name,
+                account.exchange,
+                account.is_paper.unwrap_or(false) as i32,
+                account.initial_capital.unwrap_or(initial_capital),
+                account.current_r_percent.unwrap_or(current_r_percent),
+                account.currency.unwrap_or(currency),
+                now,
+                now
+            ],
+        )
+        .map_err(|e| {
+            if e.to_string().contains("UNIQUE constraint failed") {
+                CommandError::Validation(format!("An account named {} already exists", name))
+            } else {
+                CommandError::from(e)
+            }
+        })?;
+
+        id
+    };
+
+    get_account(db, id).await
+}
+
+#[tauri::command]
+pub async fn update_account(
+    db: State<'_, Database>,
+    id: String,
+    account: UpdateAccountInput,
+) -> Result<Account, CommandError> {
+    {
+        let conn = db.pool.get()?;
+
+        let mut updates = vec!["updated_at = ?"];
+        let mut values: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(Utc::now().timestamp())];
+
+        if let Some(val) = account.name {
+            updates.push("name = ?");
+            values.push(Box::new(val.trim().to_string()));
+        }
+        if let Some(val) = account.exchange {
+            updates.push("exchange = ?");
+            values.push(Box::new(val));
+        }
+        if let Some(val) = account.is_paper {
+            updates.push("is_paper = ?");
+            values.push(Box::new(val as i32));
+        }
+        if let Some(val) = account.initial_capital {
+            updates.push("initial_capital = ?");
+            values.push(Box::new(val));
+        }
+        if let Some(val) = account.current_r_percent {
+            updates.push("current_r_percent = ?");
+            values.push(Box::new(val));
+        }
+        if let Some(val) = account.currency {
+            updates.push("currency = ?");
+            values.push(Box::new(val));
+        }
+
+        let query = format!("UPDATE accounts SET {} WHERE id = ?", updates.join(", "));
+        values.push(Box::new(id.clone()));
+
+        let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+        conn.execute(&query, params.as_slice())?;
+    }
+
+    get_account(db, id).await
+}
+
+/// Delete an account. Its trades and capital transactions are kept and become unassigned.
+#[tauri::command]
+pub async fn delete_account(
+    db: State<'_, Database>,
+    id: String,
+) -> Result<(), CommandError> {
+    let conn = db.pool.get()?;
+    conn.execute("UPDATE trades SET account_id = NULL WHERE account_id = ?", [&id])?;
+    conn.execute("UPDATE capital_transactions SET account_id = NULL WHERE account_id = ?", [&id])?;
+    conn.execute("DELETE FROM accounts WHERE id = ?", [&id])?;
+    Ok(())
+}
+
+/// Capital settings for stats: the account's when one is given, the global settings otherwise.
+/// Returns (initial_capital, current_r_percent).
+pub(crate) fn capital_settings(
+    conn: &rusqlite::Connection,
+    account_id: Option<&str>,
+) -> Result<(f64, f64), CommandError> {
+    match account_id {
+        Some(id) => conn
+            .query_row(
+                "SELECT initial_capital, current_r_percent FROM accounts WHERE id = ?",
+                [id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .ok_or_else(|| CommandError::NotFound(format!("Account {} not found", id))),
+        None => conn
+            .query_row(
+                "SELECT initial_capital, current_r_percent FROM settings WHERE id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(CommandError::from),
+    }
+}
@@ -1,31 +1,66 @@
 use tauri::State;
 use crate::db::Database;
+use crate::commands::error::CommandError;
 use crate::models::{
-    ApiCredential, ApiCredentialInput, ApiCredentialSafe, ApiSyncHistory,
-    SyncConfig, SyncResult, Trade,
+    ApiCredential, ApiCredentialInput, ApiCredentialSafe, ApiSyncHistory, CredentialAccess, CredentialDiagnosis, CredentialRotation,
+    CredentialTestResult, DiagnosticCheck,
+    SymbolFilters, SyncConfig, SyncPreview, SyncPreviewTrade, SyncResult, Trade, TradeLinkSuggestion,
 };
 use crate::api::{
     bitget::BitgetClient,
     blofin::BlofinClient,
-    client::ExchangeClient,
-    credentials::{store_api_key, store_api_secret, store_passphrase, retrieve_api_key, retrieve_api_secret, retrieve_passphrase, delete_credentials},
+    client::{CancelFlag, ExchangeClient, ExchangeEnvironment, RawClosedPosition, RawTrade},
+    credential_cache::CredentialCache,
+    error::ApiError,
+    credentials::{store_api_key, store_api_secret, store_passphrase, retrieve_api_key, retrieve_api_secret, retrieve_passphrase, delete_credentials, replace_credentials, Secret, StorageBackend},
 };
+use crate::commands::funding::{link_funding_payments, store_funding_payments};
+use crate::commands::positions::replace_reconciled_trade;
+use crate::commands::trade_metrics::closed_status;
+use crate::sync::{SyncCancellations, SyncScheduler, position_builder::{backfill_realized_pnl, group_fills_into_positions}};
+use std::sync::atomic::Ordering;
 use chrono::Utc;
+use rusqlite::OptionalExtension;
 use uuid::Uuid;
 
+/// Incremental syncs start this far before the last sync so fills the exchange
+/// reported late are still picked up (already-imported trades are skipped as duplicates)
+const SYNC_OVERLAP_SECS: i64 = 3600;
+
+/// Start of an incremental sync in milliseconds, from the credential's
+/// last_sync_timestamp (seconds). None (full history) when it never synced.
+pub(crate) fn incremental_start_time(last_sync_timestamp: Option<i64>) -> Option<i64> {
+    last_sync_timestamp.map(|ts| (ts - SYNC_OVERLAP_SECS).max(0) * 1000)
+}
+
+/// Start of an automatic sync in milliseconds: incremental from the last sync, but never
+/// further back than the credential's look-back window from `now` (seconds)
+pub(crate) fn auto_sync_start_time(
+    last_sync_timestamp: Option<i64>,
+    lookback_days: Option<i64>,
+    now: i64,
+) -> Option<i64> {
+    let window_start = lookback_days.map(|days| (now - days * 86_400).max(0) * 1000);
+    match (incremental_start_time(last_sync_timestamp), window_start) {
+        (Some(incremental), Some(window)) => Some(incremental.max(window)),
+        (incremental, window) => incremental.or(window),
+    }
+}
+
 /// Save or update API credentials
 #[tauri::command]
 pub async fn save_api_credentials(
     db: State<'_, Database>,
+    cache: State<'_, CredentialCache>,
     input: ApiCredentialInput,
-) -> Result<ApiCredentialSafe, String> {
+) -> Result<ApiCredentialSafe, CommandError> {
     println!("=== Saving API credentials ===");
     println!("Exchange: {}, Label: {}", input.exchange, input.label);
 
-    let conn = db.conn.lock().map_err(|e| {
-        let error_msg = format!("Failed to lock database: {}", e);
+    let conn = db.pool.get().map_err(|e| {
+        let error_msg = format!("Failed to get database connection: {}", e);
         eprintln!("ERROR: {}", error_msg);
-        error_msg
+        CommandError::Database(error_msg)
     })?;
 
     let now = Utc::now().timestamp();
@@ -33,34 +68,60 @@ pub async fn save_api_credentials(
     let is_active = input.is_active.unwrap_or(true);
     let auto_sync_enabled = input.auto_sync_enabled.unwrap_or(false);
     let auto_sync_interval = input.auto_sync_interval.unwrap_or(3600); // Default 1 hour
+    let auto_sync_lookback_days = input.auto_sync_lookback_days;
+    if let Some(days) = auto_sync_lookback_days
+        && days <= 0
+    {
+        return Err(CommandError::Validation(format!("Auto-sync look-back must be at least 1 day, got {}", days)));
+    }
     let live_mirror_enabled = input.live_mirror_enabled.unwrap_or(false);
+    let environment = input.environment.unwrap_or_else(|| "production".to_string());
+    if !matches!(environment.as_str(), "production" | "demo") {
+        return Err(CommandError::Validation(format!(
+            "Environment must be 'production' or 'demo', got '{}'",
+            environment
+        )));
+    }
+    let base_url = match input.base_url.as_deref().map(str::trim) {
+        Some(url) if !url.is_empty() => {
+            Some(ExchangeEnvironment::normalize_base_url(url).map_err(CommandError::Validation)?)
+        }
+        _ => None,
+    };
 
     println!("Generated credential ID: {}", id);
 
     // Store credentials in system keychain
     println!("Storing API key in keychain...");
-    store_api_key(&id, &input.api_key).map_err(|e| {
+    let mut backends = vec![store_api_key(&id, &input.api_key).map_err(|e| {
         let error_msg = format!("Failed to store API key: {}", e);
         eprintln!("ERROR: {}", error_msg);
-        error_msg
-    })?;
+        CommandError::Auth(error_msg)
+    })?];
 
     println!("Storing API secret in keychain...");
-    store_api_secret(&id, &input.api_secret).map_err(|e| {
+    backends.push(store_api_secret(&id, &input.api_secret).map_err(|e| {
         let error_msg = format!("Failed to store API secret: {}", e);
         eprintln!("ERROR: {}", error_msg);
-        error_msg
-    })?;
+        CommandError::Auth(error_msg)
+    })?);
 
     if let Some(ref passphrase) = input.passphrase {
         println!("Storing passphrase in keychain...");
-        store_passphrase(&id, passphrase).map_err(|e| {
+        backends.push(store_passphrase(&id, passphrase).map_err(|e| {
             let error_msg = format!("Failed to store passphrase: {}", e);
             eprintln!("ERROR: {}", error_msg);
-            error_msg
-        })?;
+            CommandError::Auth(error_msg)
+        })?);
     }
 
+    // Falling back for any secret means the credential isn't fully in the keychain
+    let storage_backend = if backends.contains(&StorageBackend::EncryptedFile) {
+        StorageBackend::EncryptedFile
+    } else {
+        StorageBackend::Keychain
+    };
+
     // Store placeholder in database to maintain schema compatibility
     let placeholder_key = format!("KEYCHAIN:{}", id);
     let placeholder_secret = format!("KEYCHAIN:{}", id);
@@ -81,10 +142,14 @@ pub async fn save_api_credentials(
         println!("Updating existing credential in database...");
         conn.execute(
             "UPDATE api_credentials SET
+                is_default = CASE WHEN exchange = ? THEN is_default
+                    ELSE NOT EXISTS (SELECT 1 FROM api_credentials WHERE exchange = ? AND is_default = 1) END,
                 exchange = ?, label = ?, api_key = ?, api_secret = ?,
-                passphrase = ?, is_active = ?, auto_sync_enabled = ?, auto_sync_interval = ?, live_mirror_enabled = ?, updated_at = ?
+                passphrase = ?, is_active = ?, auto_sync_enabled = ?, auto_sync_interval = ?, auto_sync_lookback_days = ?, live_mirror_enabled = ?, storage_backend = ?, environment = ?, base_url = ?, updated_at = ?
              WHERE id = ?",
             rusqlite::params![
+                &input.exchange,
+                &input.exchange,
                 &input.exchange,
                 &input.label,
                 &placeholder_key,
@@ -93,7 +158,11 @@ pub async fn save_api_credentials(
                 is_active as i32,
                 auto_sync_enabled as i32,
                 auto_sync_interval,
+                auto_sync_lookback_days,
                 live_mirror_enabled as i32,
+                storage_backend.as_str(),
+                &environment,
+                &base_url,
                 now,
                 &id,
             ],
@@ -101,15 +170,16 @@ pub async fn save_api_credentials(
         .map_err(|e| {
             let error_msg = format!("Failed to update credential in database: {}", e);
             eprintln!("ERROR: {}", error_msg);
-            error_msg
+            CommandError::Database(error_msg)
         })?;
     } else {
         // Insert
         println!("Inserting new credential into database...");
         conn.execute(
             "INSERT INTO api_credentials
-                (id, exchange, label, api_key, api_secret, passphrase, is_active, auto_sync_enabled, auto_sync_interval, live_mirror_enabled, created_at, updated_at)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                (id, exchange, label, api_key, api_secret, passphrase, is_active, auto_sync_enabled, auto_sync_interval, auto_sync_lookback_days, live_mirror_enabled, is_default, storage_backend, environment, base_url, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+                NOT EXISTS (SELECT 1 FROM api_credentials WHERE exchange = ? AND is_default = 1), ?, ?, ?, ?, ?)",
             rusqlite::params![
                 &id,
                 &input.exchange,
@@ -120,7 +190,12 @@ pub async fn save_api_credentials(
                 is_active as i32,
                 auto_sync_enabled as i32,
                 auto_sync_interval,
+                auto_sync_lookback_days,
                 live_mirror_enabled as i32,
+                &input.exchange,
+                storage_backend.as_str(),
+                &environment,
+                &base_url,
                 now,
                 now,
             ],
@@ -128,11 +203,16 @@ pub async fn save_api_credentials(
         .map_err(|e| {
             let error_msg = format!("Failed to insert credential into database: {}", e);
             eprintln!("ERROR: {}", error_msg);
-            error_msg
+            CommandError::Database(error_msg)
         })?;
     }
 
     println!("Database operation successful!");
+    cache.invalidate(&id);
+
+    // The first credential of an exchange becomes its default
+    let is_default: bool = conn
+        .query_row("SELECT is_default FROM api_credentials WHERE id = ?", [&id], |row| row.get(0))?;
 
     // Return safe version
     let credential = ApiCredential {
@@ -147,7 +227,12 @@ pub async fn save_api_credentials(
         last_sync_timestamp: None,
         auto_sync_enabled,
         auto_sync_interval,
+        auto_sync_lookback_days,
         live_mirror_enabled,
+        is_default,
+        storage_backend: storage_backend.as_str().to_string(),
+        environment,
+        base_url,
         created_at: now,
         updated_at: now,
     };
@@ -160,54 +245,69 @@ pub async fn save_api_credentials(
 #[tauri::command]
 pub async fn list_api_credentials(
     db: State<'_, Database>,
-) -> Result<Vec<ApiCredentialSafe>, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    scheduler: State<'_, SyncScheduler>,
+    cache: State<'_, CredentialCache>,
+) -> Result<Vec<ApiCredentialSafe>, CommandError> {
+    let mut credentials = query_api_credentials(&db, &cache)?;
+    for credential in &mut credentials {
+        credential.auto_sync_paused = scheduler.is_paused(&credential.id).await;
+    }
+    Ok(credentials)
+}
+
+fn query_api_credentials(db: &Database, cache: &CredentialCache) -> Result<Vec<ApiCredentialSafe>, CommandError> {
+    let conn = db.pool.get()?;
 
     let mut stmt = conn
-        .prepare("SELECT id, exchange, label, api_key, is_active, last_sync_timestamp, auto_sync_enabled, auto_sync_interval, live_mirror_enabled, created_at, updated_at FROM api_credentials ORDER BY created_at DESC")
-        .map_err(|e| e.to_string())?;
+        .prepare("SELECT id, exchange, label, api_key, is_active, last_sync_timestamp, auto_sync_enabled, auto_sync_interval, live_mirror_enabled, created_at, updated_at, auto_sync_lookback_days, auto_sync_disabled_reason, is_default, storage_backend, environment, base_url FROM api_credentials ORDER BY created_at DESC")?;
 
     let credentials_iter = stmt
         .query_map([], |row| {
             let id: String = row.get(0)?;
-            // Retrieve from keychain instead of decrypting from database
-            let api_key = retrieve_api_key(&id).unwrap_or_default();
+            // Preview comes from the keychain (cached) instead of decrypting from database
+            let api_key_preview = cache.api_key_preview(&id, "credential_list");
 
             Ok(ApiCredentialSafe {
                 id,
                 exchange: row.get(1)?,
                 label: row.get(2)?,
-                api_key_preview: ApiCredential::create_preview(&api_key),
+                api_key_preview,
                 is_active: row.get::<_, i32>(4)? == 1,
                 last_sync_timestamp: row.get(5)?,
                 auto_sync_enabled: row.get::<_, i32>(6)? == 1,
                 auto_sync_interval: row.get(7)?,
+                auto_sync_lookback_days: row.get(11)?,
                 live_mirror_enabled: row.get::<_, i32>(8)? == 1,
+                is_default: row.get::<_, i32>(13)? == 1,
+                storage_backend: row.get(14)?,
+                environment: row.get(15)?,
+                base_url: row.get(16)?,
+                auto_sync_paused: false,
+                auto_sync_disabled_reason: row.get(12)?,
                 created_at: row.get(9)?,
                 updated_at: row.get(10)?,
             })
-        })
-        .map_err(|e| e.to_string())?;
+        })?;
 
     let credentials: Result<Vec<ApiCredentialSafe>, _> = credentials_iter.collect();
-    credentials.map_err(|e| e.to_string())
+    credentials.map_err(CommandError::from)
 }
 
-/// Test API credentials
+/// Test API credentials and audit the key's permissions
 #[tauri::command]
 pub async fn test_api_credentials(
     db: State<'_, Database>,
     credential_id: String,
-) -> Result<bool, String> {
+) -> Result<CredentialTestResult, CommandError> {
     println!("=== Testing API credentials ===");
     println!("Credential ID: {}", credential_id);
 
     // Fetch and decrypt credentials (in scope block to drop conn before await)
-    let (exchange, api_key, api_secret, passphrase) = {
-        let conn = db.conn.lock().map_err(|e| {
-            let error_msg = format!("Failed to lock database: {}", e);
+    let (exchange, environment, api_key, api_secret, passphrase) = {
+        let conn = db.pool.get().map_err(|e| {
+            let error_msg = format!("Failed to get database connection: {}", e);
             eprintln!("ERROR: {}", error_msg);
-            error_msg
+            CommandError::Database(error_msg)
         })?;
 
         // Fetch exchange type
@@ -218,78 +318,501 @@ pub async fn test_api_credentials(
                 [&credential_id],
                 |row| row.get(0),
             )
-            .map_err(|e| {
-                let error_msg = format!("Credential not found: {}", e);
+            .optional()?
+            .ok_or_else(|| {
+                let error_msg = format!("Credential not found: {}", credential_id);
                 eprintln!("ERROR: {}", error_msg);
-                error_msg
+                CommandError::NotFound(error_msg)
             })?;
 
+        let environment = load_environment(&conn, &credential_id)?;
+
         println!("Exchange: {}", exchange);
         println!("Retrieving credentials from keychain...");
 
         // Retrieve credentials from system keychain
-        let api_key = retrieve_api_key(&credential_id).map_err(|e| {
+        let api_key = retrieve_api_key(&credential_id, "credential_test").map_err(|e| {
             let error_msg = format!("Failed to retrieve API key: {}", e);
             eprintln!("ERROR: {}", error_msg);
-            error_msg
+            CommandError::Auth(error_msg)
         })?;
-        let api_secret = retrieve_api_secret(&credential_id).map_err(|e| {
+        let api_secret = retrieve_api_secret(&credential_id, "credential_test").map_err(|e| {
             let error_msg = format!("Failed to retrieve API secret: {}", e);
             eprintln!("ERROR: {}", error_msg);
-            error_msg
+            CommandError::Auth(error_msg)
         })?;
-        let passphrase = retrieve_passphrase(&credential_id).unwrap_or_default();
+        let passphrase = retrieve_passphrase(&credential_id, "credential_test").unwrap_or_default();
 
         println!("Successfully retrieved credentials from keychain");
-        (exchange, api_key, api_secret, passphrase)
+        (exchange, environment, api_key, api_secret, passphrase)
     }; // conn is dropped here
 
     // Create client and test
     println!("Creating {} client and testing credentials...", exchange);
-    let result = match exchange.as_str() {
-        "bitget" => {
-            let client = BitgetClient::new(api_key, api_secret, passphrase);
-            client.test_credentials().await
-        }
-        "blofin" => {
-            let client = BlofinClient::new(api_key, api_secret, passphrase);
-            client.test_credentials().await
-        }
+    let client: Box<dyn ExchangeClient> = match exchange.as_str() {
+        "bitget" => Box::new(BitgetClient::new(api_key, api_secret, passphrase).with_environment(&environment)),
+        "blofin" => Box::new(BlofinClient::new(api_key, api_secret, passphrase).with_environment(&environment)),
         _ => {
             let error_msg = format!("Unsupported exchange: {}", exchange);
             eprintln!("ERROR: {}", error_msg);
-            return Err(error_msg);
+            return Err(CommandError::Validation(error_msg));
+        }
+    };
+
+    let result = diagnose_credentials(client.as_ref(), &exchange).await;
+    if result.valid {
+        println!("=== Credentials test PASSED ===\n");
+    } else {
+        println!("=== Credentials test FAILED: {} ===\n", result.diagnosis.signature.message);
+    }
+    for warning in &result.warnings {
+        eprintln!("{}", warning);
+    }
+
+    Ok(result)
+}
+
+/// Clock difference the test warns about. Requests are signed on the exchange's clock
+/// anyway, so a larger one only breaks signing when the offset can't be measured.
+const CLOCK_SKEW_WARNING_MS: i64 = 5_000;
+
+/// Test a credential step by step: reach the exchange, compare clocks, send a signed
+/// request, audit the key's rights and look at the rate limit headroom
+async fn diagnose_credentials(client: &dyn ExchangeClient, exchange: &str) -> CredentialTestResult {
+    let mut rate_limited = false;
+
+    let sent_ms = Utc::now().timestamp_millis();
+    let server_time = client.fetch_server_time_ms().await;
+    let received_ms = Utc::now().timestamp_millis();
+    let (connectivity, clock_skew, latency_ms, clock_skew_ms) = match server_time {
+        Ok(server_ms) => {
+            let latency = received_ms - sent_ms;
+            let skew = crate::api::server_time::measure_offset(server_ms, sent_ms, received_ms);
+            let seconds = skew.abs() as f64 / 1000.0;
+            let clock_skew = if skew.abs() > CLOCK_SKEW_WARNING_MS {
+                DiagnosticCheck::warning(format!(
+                    "This computer's clock is {:.1}s {} {}'s. Requests are signed on the exchange's clock, \
+                     but set the date and time automatically if requests get rejected.",
+                    seconds,
+                    if skew > 0 { "behind" } else { "ahead of" },
+                    exchange
+                ))
+            } else {
+                DiagnosticCheck::ok(format!("Clock within {:.1}s of {}'s", seconds, exchange))
+            };
+            (
+                DiagnosticCheck::ok(format!("Reached {} in {} ms", exchange, latency)),
+                clock_skew,
+                Some(latency),
+                Some(skew),
+            )
+        }
+        Err(e) => {
+            rate_limited |= matches!(e, ApiError::RateLimitError(_));
+            (
+                DiagnosticCheck::failed(format!("Could not reach {}: {}", exchange, e)),
+                DiagnosticCheck::skipped("Needs a connection to the exchange"),
+                None,
+                None,
+            )
+        }
+    };
+
+    let signature = if connectivity.status == "failed" {
+        DiagnosticCheck::skipped("Needs a connection to the exchange")
+    } else {
+        match client.test_credentials().await {
+            Ok(true) => DiagnosticCheck::ok(format!("{} accepted a signed request", exchange)),
+            Ok(false) => DiagnosticCheck::failed(format!(
+                "{} rejected the API key, secret or passphrase. Check they were copied whole and \
+                 that the key belongs to this environment (demo or production).",
+                exchange
+            )),
+            Err(ApiError::RateLimitError(_)) => {
+                rate_limited = true;
+                DiagnosticCheck::warning("Rate limited before the signed request could be checked")
+            }
+            Err(ApiError::ExchangeError { code, message }) if message.to_lowercase().contains("timestamp") => {
+                DiagnosticCheck::failed(format!(
+                    "{} rejected the request time ({}: {}). Set the date and time automatically.",
+                    exchange, code, message
+                ))
+            }
+            Err(ApiError::ExchangeError { code, message }) if message.to_lowercase().contains("sign") => {
+                DiagnosticCheck::failed(format!(
+                    "{} rejected the signature ({}: {}). Check the API secret.",
+                    exchange, code, message
+                ))
+            }
+            Err(e) => DiagnosticCheck::failed(format!("Signed request failed: {}", e)),
+        }
+    };
+    let valid = signature.status == "ok";
+
+    let mut warnings = Vec::new();
+    let mut permissions = None;
+    let permission_check = if !valid {
+        DiagnosticCheck::skipped("Needs a working API key")
+    } else {
+        match client.fetch_permissions().await {
+            Ok(Some(key_permissions)) => {
+                warnings = key_permissions.warnings();
+                let check = if !key_permissions.read {
+                    DiagnosticCheck::failed("The key cannot read account data; enable read access for it")
+                } else if !warnings.is_empty() {
+                    DiagnosticCheck::warning(warnings.join(" "))
+                } else {
+                    DiagnosticCheck::ok("Read-only key")
+                };
+                permissions = Some(key_permissions);
+                check
+            }
+            Ok(None) => {
+                let message = format!("{} does not report API key permissions", exchange);
+                warnings.push(message.clone());
+                DiagnosticCheck::skipped(message)
+            }
+            Err(e) => {
+                eprintln!("ERROR: Failed to fetch API key permissions: {}", e);
+                rate_limited |= matches!(e, ApiError::RateLimitError(_));
+                let message = format!("Could not check API key permissions: {}", e);
+                warnings.push(message.clone());
+                DiagnosticCheck::warning(message)
+            }
         }
     };
 
-    match &result {
-        Ok(true) => println!("=== Credentials test PASSED ===\n"),
-        Ok(false) => println!("=== Credentials test FAILED (invalid credentials) ===\n"),
-        Err(e) => eprintln!("ERROR: Credentials test failed with error: {}\n", e),
+    let config = client.rate_limit();
+    let headroom = client.rate_limit_headroom();
+    let rate_limit = if rate_limited {
+        DiagnosticCheck::warning(format!(
+            "{} rate limited a test request. Wait a minute before syncing; if it keeps happening, \
+             sync fewer credentials of this exchange at once.",
+            exchange
+        ))
+    } else if headroom == 0 {
+        DiagnosticCheck::warning(format!(
+            "The app is using all of its {} requests per second to {}; further requests wait their turn",
+            config.requests_per_second, exchange
+        ))
+    } else {
+        DiagnosticCheck::ok(format!(
+            "{} of {} requests available right now ({} per second)",
+            headroom, config.burst_size, config.requests_per_second
+        ))
+    };
+
+    CredentialTestResult {
+        valid,
+        permissions,
+        warnings,
+        diagnosis: CredentialDiagnosis {
+            connectivity,
+            clock_skew,
+            signature,
+            permissions: permission_check,
+            rate_limit,
+            latency_ms,
+            clock_skew_ms,
+            rate_limit_headroom: headroom,
+        },
     }
+}
+
+/// Swap a credential's key for a new one, e.g. before the old key expires. The new key is
+/// tested against the exchange first and the old one stays in place unless the test passes.
+/// Every attempt is recorded in the rotation history.
+#[tauri::command]
+pub async fn rotate_api_credentials(
+    db: State<'_, Database>,
+    cache: State<'_, CredentialCache>,
+    credential_id: String,
+    api_key: Secret,
+    api_secret: Secret,
+    passphrase: Option<Secret>,
+) -> Result<ApiCredentialSafe, CommandError> {
+    let (exchange, environment, old_key_preview) = {
+        let conn = db.pool.get()?;
+        let exchange: String = conn
+            .query_row("SELECT exchange FROM api_credentials WHERE id = ?", [&credential_id], |row| row.get(0))
+            .optional()?
+            .ok_or_else(|| CommandError::NotFound(format!("Credential {} not found", credential_id)))?;
+        let environment = load_environment(&conn, &credential_id)?;
+        let old_key_preview = retrieve_api_key(&credential_id, "credential_rotation")
+            .ok()
+            .map(|key| ApiCredential::create_preview(&key[..]));
+        (exchange, environment, old_key_preview)
+    };
+
+    let new_key_preview = ApiCredential::create_preview(&api_key);
+    let result = swap_credentials(&db, &credential_id, &exchange, &environment, api_key, api_secret, passphrase).await;
+
+    let conn = db.pool.get()?;
+    record_rotation(
+        &conn,
+        &credential_id,
+        old_key_preview.as_deref(),
+        &new_key_preview,
+        result.as_ref().err().map(|e| e.to_string()).as_deref(),
+        Utc::now().timestamp(),
+    )?;
+    result?;
+
+    cache.invalidate(&credential_id);
+    query_api_credentials(&db, &cache)?
+        .into_iter()
+        .find(|credential| credential.id == credential_id)
+        .ok_or_else(|| CommandError::NotFound(format!("Credential {} not found", credential_id)))
+}
+
+/// Test the new key, then store it in place of the old one
+async fn swap_credentials(
+    db: &Database,
+    credential_id: &str,
+    exchange: &str,
+    environment: &ExchangeEnvironment,
+    api_key: Secret,
+    api_secret: Secret,
+    passphrase: Option<Secret>,
+) -> Result<(), CommandError> {
+    let client: Box<dyn ExchangeClient> = match exchange {
+        "bitget" => Box::new(BitgetClient::new(
+            api_key.clone(),
+            api_secret.clone(),
+            passphrase.clone().unwrap_or_default(),
+        ).with_environment(environment)),
+        "blofin" => Box::new(BlofinClient::new(
+            api_key.clone(),
+            api_secret.clone(),
+            passphrase.clone().unwrap_or_default(),
+        ).with_environment(environment)),
+        _ => return Err(CommandError::Validation(format!("Unsupported exchange: {}", exchange))),
+    };
+
+    if !client.test_credentials().await? {
+        return Err(CommandError::Auth("The exchange rejected the new API credentials".to_string()));
+    }
+
+    let storage_backend = replace_credentials(
+        credential_id,
+        &api_key,
+        &api_secret,
+        passphrase.as_ref().map(|passphrase| passphrase.as_str()),
+    )?;
+
+    let conn = db.pool.get()?;
+    conn.execute(
+        "UPDATE api_credentials SET passphrase = ?, storage_backend = ?, updated_at = ? WHERE id = ?",
+        rusqlite::params![
+            passphrase.as_ref().map(|_| format!("KEYCHAIN:{}", credential_id)),
+            storage_backend.as_str(),
+            Utc::now().timestamp(),
+            credential_id,
+        ],
+    )?;
+    Ok(())
+}
+
+fn record_rotation(
+    conn: &rusqlite::Connection,
+    credential_id: &str,
+    old_key_preview: Option<&str>,
+    new_key_preview: &str,
+    error_message: Option<&str>,
+    now: i64,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO credential_rotations (id, credential_id, old_key_preview, new_key_preview, status, error_message, rotated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+        rusqlite::params![
+            Uuid::new_v4().to_string(),
+            credential_id,
+            old_key_preview,
+            new_key_preview,
+            if error_message.is_some() { "failed" } else { "success" },
+            error_message,
+            now,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Rotation history of a credential, newest first
+#[tauri::command]
+pub async fn get_credential_rotations(
+    db: State<'_, Database>,
+    credential_id: String,
+) -> Result<Vec<CredentialRotation>, CommandError> {
+    let conn = db.pool.get()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, credential_id, old_key_preview, new_key_preview, status, error_message, rotated_at
+         FROM credential_rotations
+         WHERE credential_id = ?
+         ORDER BY rotated_at DESC, rowid DESC",
+    )?;
+
+    let rotations = stmt
+        .query_map([&credential_id], |row| {
+            Ok(CredentialRotation {
+                id: row.get(0)?,
+                credential_id: row.get(1)?,
+                old_key_preview: row.get(2)?,
+                new_key_preview: row.get(3)?,
+                status: row.get(4)?,
+                error_message: row.get(5)?,
+                rotated_at: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
 
-    result.map_err(|e| e.to_string())
+    Ok(rotations)
 }
 
 /// Delete API credentials
 #[tauri::command]
 pub async fn delete_api_credentials(
     db: State<'_, Database>,
+    cache: State<'_, CredentialCache>,
     credential_id: String,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     // Delete from system keychain first
-    delete_credentials(&credential_id).map_err(|e| e.to_string())?;
+    delete_credentials(&credential_id)?;
+    cache.invalidate(&credential_id);
 
     // Then delete from database
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let mut conn = db.pool.get()?;
+    let tx = conn.transaction()?;
 
-    conn.execute(
+    let exchange: Option<String> = tx
+        .query_row(
+            "SELECT exchange FROM api_credentials WHERE id = ? AND is_default = 1",
+            [&credential_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    tx.execute(
         "DELETE FROM api_credentials WHERE id = ?",
         [&credential_id],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
 
-    Ok(())
+    // The oldest remaining credential of the exchange takes over as its default
+    if let Some(exchange) = exchange {
+        tx.execute(
+            "UPDATE api_credentials SET is_default = 1
+             WHERE id = (SELECT id FROM api_credentials WHERE exchange = ? ORDER BY created_at LIMIT 1)",
+            [&exchange],
+        )?;
+    }
+
+    tx.commit().map_err(CommandError::from)
+}
+
+/// Make a credential the default of its exchange, used by quick actions given no credential
+#[tauri::command]
+pub async fn set_default_api_credential(
+    db: State<'_, Database>,
+    credential_id: String,
+) -> Result<(), CommandError> {
+    let mut conn = db.pool.get()?;
+    let tx = conn.transaction()?;
+
+    let exchange: String = tx
+        .query_row(
+            "SELECT exchange FROM api_credentials WHERE id = ?",
+            [&credential_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| CommandError::NotFound(format!("Credential {} not found", credential_id)))?;
+
+    let now = Utc::now().timestamp();
+    tx.execute(
+        "UPDATE api_credentials SET is_default = 0, updated_at = ? WHERE exchange = ? AND is_default = 1",
+        rusqlite::params![now, &exchange],
+    )?;
+    tx.execute(
+        "UPDATE api_credentials SET is_default = 1, updated_at = ? WHERE id = ?",
+        rusqlite::params![now, &credential_id],
+    )?;
+
+    tx.commit().map_err(CommandError::from)
+}
+
+/// Credential access log, newest first: every keychain read of an API key, secret or
+/// passphrase. Covers all credentials when `credential_id` is None; `limit` defaults to
+/// 500 entries.
+#[tauri::command]
+pub async fn get_credential_access_log(
+    db: State<'_, Database>,
+    credential_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<CredentialAccess>, CommandError> {
+    let conn = db.pool.get()?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, credential_id, entry, accessed_by, success, accessed_at
+             FROM credential_access_log
+             WHERE ?1 IS NULL OR credential_id = ?1
+             ORDER BY accessed_at DESC, rowid DESC
+             LIMIT ?2",
+        )?;
+
+    let entries = stmt
+        .query_map(rusqlite::params![credential_id, limit.unwrap_or(500)], |row| {
+            Ok(CredentialAccess {
+                id: row.get(0)?,
+                credential_id: row.get(1)?,
+                entry: row.get(2)?,
+                accessed_by: row.get(3)?,
+                success: row.get::<_, i32>(4)? == 1,
+                accessed_at: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(entries)
+}
+
+/// The credential a command should use: the given one, else the default of `exchange`, else
+/// the only default credential there is
+pub(crate) fn default_credential_id(
+    db: &Database,
+    credential_id: Option<String>,
+    exchange: Option<String>,
+) -> Result<String, CommandError> {
+    let conn = db.pool.get()?;
+    resolve_credential_id(&conn, credential_id, exchange.as_deref())
+}
+
+fn resolve_credential_id(
+    conn: &rusqlite::Connection,
+    credential_id: Option<String>,
+    exchange: Option<&str>,
+) -> Result<String, CommandError> {
+    if let Some(credential_id) = credential_id {
+        return Ok(credential_id);
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT id FROM api_credentials
+         WHERE is_default = 1 AND (?1 IS NULL OR exchange = ?1)
+         ORDER BY created_at",
+    )?;
+    let defaults = stmt
+        .query_map([exchange], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match (defaults.as_slice(), exchange) {
+        ([id], _) => Ok(id.clone()),
+        ([], Some(exchange)) => Err(CommandError::NotFound(format!("No default credential for {}", exchange))),
+        ([], None) => Err(CommandError::Validation("No default credential; choose a credential".to_string())),
+        (_, _) => Err(CommandError::Validation(
+            "Several exchanges have a default credential; choose an exchange".to_string(),
+        )),
+    }
 }
 
 /// Update API credentials active status
@@ -298,16 +821,15 @@ pub async fn update_api_credentials_status(
     db: State<'_, Database>,
     credential_id: String,
     is_active: bool,
-) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+) -> Result<(), CommandError> {
+    let conn = db.pool.get()?;
 
     let now = Utc::now().timestamp();
 
     conn.execute(
         "UPDATE api_credentials SET is_active = ?, updated_at = ? WHERE id = ?",
         rusqlite::params![is_active as i32, now, &credential_id],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
 
     Ok(())
 }
@@ -319,17 +841,104 @@ pub async fn update_auto_sync_settings(
     credential_id: String,
     auto_sync_enabled: bool,
     auto_sync_interval: i64,
-) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+) -> Result<(), CommandError> {
+    let conn = db.pool.get()?;
 
     let now = Utc::now().timestamp();
 
     conn.execute(
         "UPDATE api_credentials SET auto_sync_enabled = ?, auto_sync_interval = ?, updated_at = ? WHERE id = ?",
         rusqlite::params![auto_sync_enabled as i32, auto_sync_interval, now, &credential_id],
+    )?;
+
+    // Turning auto-sync on by hand also clears a tripped circuit breaker
+    if auto_sync_enabled {
+        crate::sync::scheduler::reset_sync_failures(&conn, &credential_id)?;
+    }
+
+    Ok(())
+}
+
+/// Symbol filters applied to a credential's syncs and live mirroring
+#[tauri::command]
+pub async fn get_sync_symbol_filters(
+    db: State<'_, Database>,
+    credential_id: String,
+) -> Result<SymbolFilters, CommandError> {
+    let conn = db.pool.get()?;
+    load_symbol_filters(&conn, &credential_id)
+}
+
+/// Replace a credential's symbol filters. Already imported trades are left alone, a running
+/// mirror applies them to positions opened from now on.
+#[tauri::command]
+pub async fn set_sync_symbol_filters(
+    db: State<'_, Database>,
+    credential_id: String,
+    filters: SymbolFilters,
+) -> Result<SymbolFilters, CommandError> {
+    let mut conn = db.pool.get()?;
+    save_symbol_filters(&mut conn, &credential_id, &filters)?;
+    load_symbol_filters(&conn, &credential_id)
+}
+
+/// Servers the credential's clients connect to
+pub(crate) fn load_environment(conn: &rusqlite::Connection, credential_id: &str) -> Result<ExchangeEnvironment, CommandError> {
+    conn.query_row(
+        "SELECT environment, base_url FROM api_credentials WHERE id = ?",
+        [credential_id],
+        |row| Ok(ExchangeEnvironment::from_columns(&row.get::<_, String>(0)?, row.get(1)?)),
     )
-    .map_err(|e| e.to_string())?;
+    .optional()?
+    .ok_or_else(|| CommandError::NotFound(format!("Credential {} not found", credential_id)))
+}
+
+pub(crate) fn load_symbol_filters(conn: &rusqlite::Connection, credential_id: &str) -> Result<SymbolFilters, CommandError> {
+    let mut stmt = conn.prepare("SELECT symbol, mode FROM sync_symbol_filters WHERE credential_id = ? ORDER BY symbol")?;
+
+    let rows = stmt
+        .query_map([credential_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut filters = SymbolFilters::default();
+    for (symbol, mode) in rows {
+        if mode == "INCLUDE" {
+            filters.include.push(symbol);
+        } else {
+            filters.exclude.push(symbol);
+        }
+    }
+    Ok(filters)
+}
+
+fn save_symbol_filters(
+    conn: &mut rusqlite::Connection,
+    credential_id: &str,
+    filters: &SymbolFilters,
+) -> Result<(), CommandError> {
+    if let Some(symbol) = filters.include.iter().find(|s| !filters.allows(s)) {
+        return Err(CommandError::Validation(format!("{} is both included and excluded", symbol)));
+    }
+
+    let tx = conn.transaction()?;
 
+    tx.execute("DELETE FROM sync_symbol_filters WHERE credential_id = ?", [credential_id])?;
+
+    for (symbols, mode) in [(&filters.include, "INCLUDE"), (&filters.exclude, "EXCLUDE")] {
+        for symbol in symbols {
+            let symbol = SymbolFilters::normalize(symbol);
+            if symbol.is_empty() {
+                continue;
+            }
+            // The same symbol listed twice is stored once
+            tx.execute(
+                "INSERT OR IGNORE INTO sync_symbol_filters (credential_id, symbol, mode) VALUES (?, ?, ?)",
+                rusqlite::params![credential_id, symbol, mode],
+            )?;
+        }
+    }
+
+    tx.commit()?;
     Ok(())
 }
 
@@ -338,8 +947,8 @@ pub async fn update_auto_sync_settings(
 pub async fn get_sync_history(
     db: State<'_, Database>,
     credential_id: String,
-) -> Result<Vec<ApiSyncHistory>, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+) -> Result<Vec<ApiSyncHistory>, CommandError> {
+    let conn = db.pool.get()?;
 
     let mut stmt = conn
         .prepare(
@@ -348,8 +957,7 @@ pub async fn get_sync_history(
              FROM api_sync_history
              WHERE credential_id = ?
              ORDER BY created_at DESC",
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
 
     let history_iter = stmt
         .query_map([&credential_id], |row| {
@@ -366,24 +974,349 @@ pub async fn get_sync_history(
                 error_message: row.get(9)?,
                 created_at: row.get(10)?,
             })
-        })
-        .map_err(|e| e.to_string())?;
+        })?;
 
     let history: Result<Vec<ApiSyncHistory>, _> = history_iter.collect();
-    history.map_err(|e| e.to_string())
+    history.map_err(CommandError::from)
+}
+
+/// Delete sync history for one credential, or for all of them when `credential_id` is None.
+/// Returns the number of entries removed.
+#[tauri::command]
+pub async fn clear_sync_history(
+    db: State<'_, Database>,
+    credential_id: Option<String>,
+) -> Result<usize, CommandError> {
+    let conn = db.pool.get()?;
+
+    conn.execute(
+        "DELETE FROM api_sync_history WHERE ?1 IS NULL OR credential_id = ?1",
+        [&credential_id],
+    )
+    .map_err(CommandError::from)
+}
+
+/// Apply the retention settings to a credential's sync history: drop entries older than
+/// sync_history_retention_days, then keep only the newest sync_history_max_entries.
+fn prune_sync_history(conn: &rusqlite::Connection, credential_id: &str, now: i64) -> rusqlite::Result<usize> {
+    let (retention_days, max_entries): (i64, i64) = conn.query_row(
+        "SELECT sync_history_retention_days, sync_history_max_entries FROM settings WHERE id = 1",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    let mut removed = 0;
+    if retention_days > 0 {
+        removed += conn.execute(
+            "DELETE FROM api_sync_history WHERE credential_id = ? AND created_at < ?",
+            rusqlite::params![credential_id, now - retention_days * 86_400],
+        )?;
+    }
+    if max_entries > 0 {
+        removed += conn.execute(
+            "DELETE FROM api_sync_history WHERE credential_id = ?1 AND id NOT IN (
+                SELECT id FROM api_sync_history WHERE credential_id = ?1
+                ORDER BY created_at DESC, rowid DESC LIMIT ?2
+             )",
+            rusqlite::params![credential_id, max_entries],
+        )?;
+    }
+
+    Ok(removed)
 }
 
-/// Sync trades from exchange
+/// Sync trades from exchange. Waits for a sync of the same credential that is already
+/// running (manual or automatic) to finish first. A running or queued sync can be
+/// stopped with cancel_sync.
 #[tauri::command]
 pub async fn sync_exchange_trades(
     db: State<'_, Database>,
+    cancellations: State<'_, SyncCancellations>,
+    scheduler: State<'_, SyncScheduler>,
     config: SyncConfig,
-) -> Result<SyncResult, String> {
+) -> Result<SyncResult, CommandError> {
+    let credential_id = config.credential_id.clone();
+    let cancel = cancellations.register(&credential_id);
+    let result = {
+        let _turn = scheduler.queue().acquire(&credential_id).await;
+        run_sync(&db, config, &cancel).await
+    };
+    cancellations.finish(&credential_id, &cancel);
+    result
+}
+
+/// Stop the running sync(s) of a credential. Pages already fetched are discarded and
+/// nothing is imported. Returns false when no sync was running.
+#[tauri::command]
+pub async fn cancel_sync(
+    cancellations: State<'_, SyncCancellations>,
+    credential_id: String,
+) -> Result<bool, CommandError> {
+    Ok(cancellations.cancel(&credential_id))
+}
+
+/// Fetch trades for a sync without importing them: each mapped trade with whether a
+/// sync would skip it as a duplicate. Like the CSV previews, nothing is written.
+#[tauri::command]
+pub async fn preview_exchange_sync(
+    db: State<'_, Database>,
+    cancellations: State<'_, SyncCancellations>,
+    config: SyncConfig,
+) -> Result<SyncPreview, CommandError> {
+    let credential_id = config.credential_id.clone();
+    let cancel = cancellations.register(&credential_id);
+    let result = preview_sync(&db, &config, &cancel).await;
+    cancellations.finish(&credential_id, &cancel);
+    result
+}
+
+async fn preview_sync(db: &Database, config: &SyncConfig, cancel: &CancelFlag) -> Result<SyncPreview, CommandError> {
+    let fetched = fetch_sync_trades(db, config, cancel).await?;
+    let conn = db.pool.get()?;
+
+    let mut trades = Vec::new();
+    let mut errors = fetched.warnings;
+    let planned = plan_sync_trades(
+        &conn,
+        &fetched.exchange,
+        fetched.raw_trades,
+        &fetched.closed_positions,
+        fetched.portfolio_value,
+        fetched.r_percent,
+        fetched.breakeven_threshold,
+    );
+    for result in planned {
+        match result {
+            Ok(trade) => trades.push(trade),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    let duplicates = trades.iter().filter(|t| t.is_duplicate).count() as i32;
+    Ok(SyncPreview {
+        new_trades: trades.len() as i32 - duplicates,
+        duplicates,
+        trades,
+        errors,
+    })
+}
+
+/// Trades fetched for a sync, with what is needed to map them
+struct FetchedSync {
+    exchange: String,
+    portfolio_value: f64,
+    r_percent: f64,
+    breakeven_threshold: f64,
+    start_time: Option<i64>,
+    client: Box<dyn ExchangeClient>,
+    raw_trades: Vec<RawTrade>,
+    /// Realized PnL for exchanges whose fills carry none
+    closed_positions: Vec<RawClosedPosition>,
+    symbol_filters: SymbolFilters,
+    /// Secondary data that could not be fetched, reported without failing the sync
+    warnings: Vec<String>,
+}
+
+/// Group fetched fills into the trades a sync imports, each flagged when already imported.
+/// A position some of whose fills were imported one by one, before fills were grouped,
+/// only imports its remaining fills, still one by one.
+fn plan_sync_trades(
+    conn: &rusqlite::Connection,
+    exchange: &str,
+    raw_trades: Vec<RawTrade>,
+    closed_positions: &[RawClosedPosition],
+    portfolio_value: f64,
+    r_percent: f64,
+    breakeven_threshold: f64,
+) -> Vec<Result<SyncPreviewTrade, String>> {
+    let imported = |fingerprint: &str| -> bool {
+        conn.query_row(
+            "SELECT COUNT(*) > 0 FROM trades WHERE import_fingerprint = ?",
+            [fingerprint],
+            |row| row.get(0),
+        )
+        .unwrap_or(false)
+    };
+    let map_fill = |raw_trade: &RawTrade| {
+        let fingerprint = sync_fingerprint(exchange, raw_trade);
+        map_raw_trade_to_trade(raw_trade, exchange, portfolio_value, r_percent, breakeven_threshold, &fingerprint)
+            .map(|trade| planned_trade(conn, trade, imported(&fingerprint)))
+            .map_err(|e| format!("Failed to map trade {}: {}", raw_trade.exchange_trade_id, e))
+    };
+
+    let mut groups = group_fills_into_positions(raw_trades);
+    backfill_realized_pnl(&mut groups, closed_positions);
+
+    let mut planned = Vec::new();
+    for group in groups {
+        let fills_imported = group
+            .exit_fills
+            .iter()
+            .any(|fill| imported(&sync_fingerprint(exchange, fill)));
+        if !group.is_position() || fills_imported {
+            planned.extend(group.exit_fills.iter().map(map_fill));
+            continue;
+        }
+
+        let fingerprint = position_fingerprint(exchange, &group.trade);
+        let result = map_raw_trade_to_trade(&group.trade, exchange, portfolio_value, r_percent, breakeven_threshold, &fingerprint)
+            .map(|mut trade| {
+                trade.planned_entries = Some(group.entries_json());
+                trade.effective_entries = Some(group.entries_json());
+                trade.exits = Some(group.exits_json());
+                planned_trade(conn, trade, imported(&fingerprint))
+            })
+            .map_err(|e| format!("Failed to map position {}: {}", group.trade.exchange_trade_id, e));
+        planned.push(result);
+    }
+    planned
+}
+
+fn planned_trade(conn: &rusqlite::Connection, trade: Trade, is_duplicate: bool) -> SyncPreviewTrade {
+    let matching_trade_id = if is_duplicate { None } else { find_matching_user_trade(conn, &trade) };
+    SyncPreviewTrade { trade, is_duplicate, matching_trade_id }
+}
+
+/// Hand-entered times are approximate
+const LINK_TIME_TOLERANCE_SECS: i64 = 3600;
+/// Quantities within this ratio of each other count as the same size
+const LINK_SIZE_TOLERANCE: f64 = 1.25;
+
+/// The unlinked USER_CREATED trade, if any, that an imported trade looks like: same pair
+/// and side, overlapping time, similar size. The closest by entry time wins.
+fn find_matching_user_trade(conn: &rusqlite::Connection, trade: &Trade) -> Option<String> {
+    let closed_at = trade.close_date.unwrap_or(trade.trade_date);
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, pair FROM trades
+             WHERE import_source = 'USER_CREATED' AND import_fingerprint IS NULL AND deleted_at IS NULL
+               AND position_type = ?1
+               AND trade_date <= ?2 + ?4
+               AND COALESCE(close_date, ?5) >= ?3 - ?4
+               AND quantity BETWEEN ?6 / ?7 AND ?6 * ?7
+             ORDER BY ABS(trade_date - ?3)",
+        )
+        .ok()?;
+
+    let candidates = stmt
+        .query_map(
+            rusqlite::params![
+                trade.position_type,
+                closed_at,
+                trade.trade_date,
+                LINK_TIME_TOLERANCE_SECS,
+                Utc::now().timestamp(),
+                trade.quantity,
+                LINK_SIZE_TOLERANCE
+            ],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        )
+        .ok()?
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+
+    let pair = SymbolFilters::normalize(&trade.pair);
+    candidates
+        .into_iter()
+        .find(|(_, candidate)| SymbolFilters::normalize(candidate) == pair)
+        .map(|(id, _)| id)
+}
+
+/// Attach the fills of an imported trade to a hand-entered one instead of keeping both:
+/// the trade keeps its plan and takes the actual entries, exits and PnL, and the imported
+/// trade goes to the trash. Later syncs recognise the fills on the linked trade.
+#[tauri::command]
+pub async fn link_trade_to_fill(
+    db: State<'_, Database>,
+    trade_id: String,
+    fill_trade_id: String,
+) -> Result<Trade, CommandError> {
+    {
+        let mut conn = db.pool.get()?;
+        link_fill(&mut conn, &trade_id, &fill_trade_id)?;
+    }
+
+    crate::commands::trades::get_trade(db, trade_id).await
+}
+
+fn link_fill(conn: &mut rusqlite::Connection, trade_id: &str, fill_trade_id: &str) -> Result<(), CommandError> {
+    let tx = conn.transaction()?;
+
+    let one_r: f64 = tx
+        .query_row(
+            "SELECT one_r FROM trades
+             WHERE id = ? AND import_source = 'USER_CREATED' AND import_fingerprint IS NULL AND deleted_at IS NULL",
+            [trade_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| CommandError::NotFound(format!("Trade {} not found or already linked", trade_id)))?;
+    let fill = tx
+        .query_row(
+            &format!("{} WHERE id = ? AND import_source = 'API_IMPORT' AND deleted_at IS NULL", crate::commands::trades::TRADE_SELECT),
+            [fill_trade_id],
+            crate::commands::trades::map_row_to_trade,
+        )
+        .optional()?
+        .ok_or_else(|| CommandError::NotFound(format!("Imported trade {} not found", fill_trade_id)))?;
+
+    let pnl_in_r = fill.total_pnl.filter(|_| one_r > 0.0).map(|pnl| pnl / one_r);
+    let now = Utc::now().timestamp();
+
+    tx.execute(
+        "UPDATE trades SET
+            status = ?, effective_pe = ?, effective_entries = ?, close_date = ?, exits = ?,
+            total_pnl = ?, pnl_in_r = ?, import_fingerprint = ?, updated_at = ?
+         WHERE id = ?",
+        rusqlite::params![
+            fill.status, fill.effective_pe, fill.effective_entries, fill.close_date, fill.exits,
+            fill.total_pnl, pnl_in_r, fill.import_fingerprint, now, trade_id
+        ],
+    )?;
+    tx.execute("UPDATE trades SET deleted_at = ? WHERE id = ?", rusqlite::params![now, fill_trade_id])?;
+    tx.execute(
+        "UPDATE funding_payments SET trade_id = ? WHERE trade_id = ?",
+        rusqlite::params![trade_id, fill_trade_id],
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+/// Identifies a position grouped from fills. PnL is left out so a position whose PnL is
+/// filled in later is still recognised.
+fn position_fingerprint(exchange: &str, position: &RawTrade) -> String {
+    format!(
+        "api|{}|position|{}|{}|{}|{:.8}|{}",
+        exchange,
+        position.exchange_trade_id,
+        position.symbol.to_lowercase(),
+        position.position_side.to_lowercase(),
+        position.quantity,
+        position.close_timestamp.unwrap_or_default()
+    )
+}
+
+/// Identifies an API-imported fill so later syncs skip it
+fn sync_fingerprint(exchange: &str, raw_trade: &RawTrade) -> String {
+    format!(
+        "api|{}|{}|{}|{}|{:.8}|{:.8}|{}",
+        exchange,
+        raw_trade.exchange_trade_id,
+        raw_trade.exchange_order_id,
+        raw_trade.symbol.to_lowercase(),
+        raw_trade.quantity,
+        raw_trade.pnl,
+        raw_trade.timestamp
+    )
+}
+
+async fn fetch_sync_trades(db: &Database, config: &SyncConfig, cancel: &CancelFlag) -> Result<FetchedSync, CommandError> {
     use crate::api::client::FetchTradesRequest;
 
     // Fetch and decrypt credentials
-    let (exchange, api_key, api_secret, passphrase, portfolio_value, r_percent, last_sync) = {
-        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let (exchange, environment, api_key, api_secret, passphrase, portfolio_value, r_percent, breakeven_threshold, last_sync, symbol_filters) = {
+        let conn = db.pool.get()?;
 
         // Get credential and last sync timestamp
         let (exchange, last_sync_timestamp): (String, Option<i64>) = conn
@@ -392,30 +1325,31 @@ pub async fn sync_exchange_trades(
                 [&config.credential_id],
                 |row| Ok((row.get(0)?, row.get(1)?)),
             )
-            .map_err(|e| format!("Credential not found: {}", e))?;
+            .map_err(|e| CommandError::NotFound(format!("Credential not found: {}", e)))?;
 
-        // Get current settings for portfolio value and r_percent
-        let (portfolio, r): (f64, f64) = conn
+        // Get current settings for portfolio value, r_percent and the break-even threshold
+        let (portfolio, r, breakeven): (f64, f64, f64) = conn
             .query_row(
-                "SELECT initial_capital, current_r_percent FROM settings WHERE id = 1",
+                "SELECT initial_capital, current_r_percent, breakeven_threshold FROM settings WHERE id = 1",
                 [],
-                |row| Ok((row.get(0)?, row.get(1)?)),
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
             )
-            .map_err(|e| format!("Failed to load settings: {}", e))?;
+            .map_err(|e| CommandError::Database(format!("Failed to load settings: {}", e)))?;
 
         // Retrieve credentials from system keychain
-        let api_key = retrieve_api_key(&config.credential_id).map_err(|e| e.to_string())?;
-        let api_secret = retrieve_api_secret(&config.credential_id).map_err(|e| e.to_string())?;
-        let passphrase = retrieve_passphrase(&config.credential_id).unwrap_or_default();
+        let api_key = retrieve_api_key(&config.credential_id, "sync")?;
+        let api_secret = retrieve_api_secret(&config.credential_id, "sync")?;
+        let passphrase = retrieve_passphrase(&config.credential_id, "sync").unwrap_or_default();
 
-        (exchange, api_key, api_secret, passphrase, portfolio, r, last_sync_timestamp)
+        let symbol_filters = load_symbol_filters(&conn, &config.credential_id)?;
+        let environment = load_environment(&conn, &config.credential_id)?;
+
+        (exchange, environment, api_key, api_secret, passphrase, portfolio, r, breakeven, last_sync_timestamp, symbol_filters)
     };
 
     // Create exchange client
     // Smart sync: use last_sync_timestamp if no start_date specified and last_sync exists
-    let start_time = config.start_date.or_else(|| {
-        last_sync.map(|ts| ts * 1000) // Convert seconds to milliseconds
-    });
+    let start_time = config.start_date.or_else(|| incremental_start_time(last_sync));
 
     let fetch_request = FetchTradesRequest {
         start_time,
@@ -423,71 +1357,113 @@ pub async fn sync_exchange_trades(
         symbol: None,
         limit: None,
         cursor: None,
+        cancel: Some(cancel.clone()),
+    };
+
+    let client: Box<dyn ExchangeClient> = match exchange.as_str() {
+        "bitget" => Box::new(BitgetClient::new(api_key, api_secret, passphrase).with_environment(&environment)),
+        "blofin" => Box::new(BlofinClient::new(api_key, api_secret, passphrase).with_environment(&environment)),
+        _ => return Err(CommandError::Validation(format!("Unsupported exchange: {}", exchange))),
     };
 
-    let response = match exchange.as_str() {
-        "bitget" => {
-            let client = BitgetClient::new(api_key, api_secret, passphrase);
-            client.fetch_trades(fetch_request).await
+    let mut raw_trades = client.fetch_trades(fetch_request).await?.trades;
+    raw_trades.retain(|t| symbol_filters.allows(&t.symbol));
+
+    if cancel.load(Ordering::Relaxed) {
+        return Err(ApiError::Cancelled.into());
+    }
+
+    let mut warnings = Vec::new();
+    let closed_positions = match client.fetch_closed_positions(start_time, config.end_date).await {
+        Ok(mut closed) => {
+            closed.retain(|p| symbol_filters.allows(&p.symbol));
+            closed
         }
-        "blofin" => {
-            let client = BlofinClient::new(api_key, api_secret, passphrase);
-            client.fetch_trades(fetch_request).await
+        Err(e) => {
+            warnings.push(format!("Position history unavailable, PnL may be missing: {}", e));
+            Vec::new()
         }
-        _ => return Err(format!("Unsupported exchange: {}", exchange)),
     };
 
-    let raw_trades = response.map_err(|e| e.to_string())?.trades;
+    Ok(FetchedSync {
+        exchange,
+        portfolio_value,
+        r_percent,
+        breakeven_threshold,
+        start_time,
+        client,
+        raw_trades,
+        closed_positions,
+        symbol_filters,
+        warnings,
+    })
+}
+
+async fn run_sync(db: &Database, config: SyncConfig, cancel: &CancelFlag) -> Result<SyncResult, CommandError> {
+    let FetchedSync {
+        exchange,
+        portfolio_value,
+        r_percent,
+        breakeven_threshold,
+        start_time,
+        client,
+        raw_trades,
+        closed_positions,
+        symbol_filters,
+        warnings,
+    } = fetch_sync_trades(db, &config, cancel).await?;
+
+    // Funding is a secondary source: a failure is reported but does not fail the sync
+    let (funding_payments, funding_error) = match client
+        .fetch_funding_payments(start_time, config.end_date)
+        .await
+    {
+        Ok(mut payments) => {
+            payments.retain(|p| symbol_filters.allows(&p.symbol));
+            (payments, None)
+        }
+        Err(e) => (Vec::new(), Some(format!("Funding history unavailable: {}", e))),
+    };
 
     // Process trades
     let mut imported = 0;
     let mut duplicates = 0;
-    let mut errors = Vec::new();
+    let mut errors = warnings;
     let mut total_pnl = 0.0;
+    let mut link_suggestions = Vec::new();
 
-    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let mut conn = db.pool.get()?;
 
     // Wrap the entire sync operation in a transaction
-    let tx = conn.transaction().map_err(|e| e.to_string())?;
-
-    for raw_trade in raw_trades {
-        // Generate fingerprint
-        let fingerprint = format!(
-            "api|{}|{}|{}|{}|{:.8}|{:.8}|{}",
-            exchange,
-            raw_trade.exchange_trade_id,
-            raw_trade.exchange_order_id,
-            raw_trade.symbol.to_lowercase(),
-            raw_trade.quantity,
-            raw_trade.pnl,
-            raw_trade.timestamp
-        );
+    let tx = conn.transaction()?;
+
+    // Fills are grouped into positions before they become trades
+    let planned = plan_sync_trades(&tx, &exchange, raw_trades, &closed_positions, portfolio_value, r_percent, breakeven_threshold);
+
+    for result in planned {
+        // Dropping the transaction rolls back the trades inserted so far
+        if cancel.load(Ordering::Relaxed) {
+            return Err(ApiError::Cancelled.into());
+        }
 
-        // Check for duplicate
-        if config.skip_duplicates {
-            let exists: bool = tx
-                .query_row(
-                    "SELECT COUNT(*) > 0 FROM trades WHERE import_fingerprint = ?",
-                    [&fingerprint],
-                    |row| row.get(0),
-                )
-                .unwrap_or(false);
-
-            if exists {
+        match result {
+            // Check for duplicate
+            Ok(SyncPreviewTrade { is_duplicate: true, .. }) if config.skip_duplicates => {
                 duplicates += 1;
-                continue;
             }
-        }
+            Ok(SyncPreviewTrade { trade, matching_trade_id, .. }) => {
+                if let Some(trade_id) = matching_trade_id {
+                    link_suggestions.push(TradeLinkSuggestion { trade_id, fill_trade_id: trade.id.clone() });
+                }
 
-        // Map to Trade model
-        match map_raw_trade_to_trade(&raw_trade, &exchange, portfolio_value, r_percent, &fingerprint) {
-            Ok(trade) => {
-                // Insert trade using transaction
-                if let Err(e) = insert_trade_in_tx(&tx, &trade) {
-                    errors.push(format!("Failed to insert trade {}: {}", raw_trade.exchange_trade_id, e));
+                // Insert trade using transaction, it takes over a trade reconciliation opened
+                let inserted = insert_trade_in_tx(&tx, &trade)
+                    .and_then(|_| replace_reconciled_trade(&tx, &config.credential_id, &trade));
+                if let Err(e) = inserted {
+                    errors.push(format!("Failed to insert trade {}: {}", trade.pair, e));
                     // Rollback transaction on any insertion error
                     drop(tx); // Drop transaction to rollback
-                    return Err(format!("Sync failed - no trades imported. Error: {}", errors.join("; ")));
+                    return Err(CommandError::Database(format!("Sync failed - no trades imported. Error: {}", errors.join("; "))));
                 } else {
                     imported += 1;
                     if let Some(pnl) = trade.total_pnl {
@@ -496,14 +1472,19 @@ pub async fn sync_exchange_trades(
                 }
             }
             Err(e) => {
-                errors.push(format!("Failed to map trade {}: {}", raw_trade.exchange_trade_id, e));
+                errors.push(e);
                 // Rollback transaction on any mapping error
                 drop(tx); // Drop transaction to rollback
-                return Err(format!("Sync failed - no trades imported. Error: {}", errors.join("; ")));
+                return Err(CommandError::Exchange(format!("Sync failed - no trades imported. Error: {}", errors.join("; "))));
             }
         }
     }
 
+    let funding_imported = store_funding_payments(&tx, &config.credential_id, &exchange, &funding_payments)
+        .map_err(|e| CommandError::Database(format!("Failed to store funding payments: {}", e)))?;
+    link_funding_payments(&tx).map_err(|e| CommandError::Database(format!("Failed to link funding payments: {}", e)))?;
+    errors.extend(funding_error);
+
     // Create sync history record
     let now = Utc::now().timestamp();
     let sync_id = Uuid::new_v4().to_string();
@@ -526,24 +1507,25 @@ pub async fn sync_exchange_trades(
             if errors.is_empty() { None } else { Some(errors.join("; ")) },
             now,
         ],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
+    prune_sync_history(&tx, &config.credential_id, now)?;
 
     // Update last_sync_timestamp on credential
     tx.execute(
         "UPDATE api_credentials SET last_sync_timestamp = ?, updated_at = ? WHERE id = ?",
         rusqlite::params![now, now, &config.credential_id],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
 
     // Commit the transaction
-    tx.commit().map_err(|e| e.to_string())?;
+    tx.commit()?;
 
     Ok(SyncResult {
         imported,
         duplicates,
         errors,
         total_pnl: Some(total_pnl),
+        funding_imported: funding_imported as i32,
+        link_suggestions,
     })
 }
 
@@ -553,6 +1535,7 @@ fn map_raw_trade_to_trade(
     exchange: &str,
     portfolio_value: f64,
     r_percent: f64,
+    breakeven_threshold: f64,
     fingerprint: &str,
 ) -> Result<Trade, String> {
     use uuid::Uuid;
@@ -578,7 +1561,7 @@ fn map_raw_trade_to_trade(
     // Estimate leverage based on SL distance
     let sl_distance_pct = sl_distance / entry_price;
     let max_leverage = (1.0 / sl_distance_pct).floor() as i32;
-    let leverage = max_leverage.max(1).min(20);
+    let leverage = max_leverage.clamp(1, 20);
 
     // Calculate margin and position size
     let position_size = entry_price * quantity;
@@ -586,13 +1569,7 @@ fn map_raw_trade_to_trade(
 
     // Determine trade status
     let status = if raw.close_timestamp.is_some() {
-        if raw.pnl > 1.0 {
-            "WIN"
-        } else if raw.pnl < -1.0 {
-            "LOSS"
-        } else {
-            "BE"
-        }
+        closed_status(raw.pnl, breakeven_threshold)
     } else {
         "OPEN"
     };
@@ -610,17 +1587,13 @@ fn map_raw_trade_to_trade(
     };
 
     // Create exits JSON if closed
-    let exits = if let Some(exit_price) = raw.exit_price {
-        Some(
-            serde_json::to_string(&vec![serde_json::json!({
-                "price": exit_price,
-                "percent": 100
-            })])
-            .unwrap_or_else(|_| "[]".to_string()),
-        )
-    } else {
-        None
-    };
+    let exits = raw.exit_price.map(|exit_price| {
+        serde_json::to_string(&vec![serde_json::json!({
+            "price": exit_price,
+            "percent": 100
+        })])
+        .unwrap_or_else(|_| "[]".to_string())
+    });
 
     // Calculate RR
     let planned_weighted_rr = if position_type == "LONG" {
@@ -677,14 +1650,19 @@ fn map_raw_trade_to_trade(
         execution_potential_profit: None,
         import_fingerprint: Some(fingerprint.to_string()),
         import_source: "API_IMPORT".to_string(),
+        account_id: None,
+        grade: None,
+        pre_emotion: None,
+        post_emotion: None,
+        liquidated: false,
+        funding_pnl: None,
         created_at: now,
         updated_at: now,
     })
 }
 
 /// Insert trade into database
-#[allow(dead_code)]
-fn insert_trade(conn: &rusqlite::Connection, trade: &Trade) -> Result<(), rusqlite::Error> {
+pub(crate) fn insert_trade(conn: &rusqlite::Connection, trade: &Trade) -> Result<(), rusqlite::Error> {
     conn.execute(
         "INSERT INTO trades (
             id, pair, exchange, analysis_date, trade_date, status,
@@ -818,3 +1796,437 @@ fn insert_trade_in_tx(tx: &rusqlite::Transaction, trade: &Trade) -> Result<(), r
     )?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::test_conn;
+
+    #[test]
+    fn test_resolve_credential_id_uses_defaults() {
+        let conn = test_conn();
+
+        assert!(resolve_credential_id(&conn, None, None).is_err());
+        assert_eq!(resolve_credential_id(&conn, Some("C9".to_string()), None).unwrap(), "C9");
+
+        conn.execute(
+            "INSERT INTO api_credentials (id, exchange, label, api_key, api_secret, is_default, created_at, updated_at)
+             VALUES ('C1', 'bitget', 'Main', 'KEYCHAIN:C1', 'KEYCHAIN:C1', 1, 0, 0),
+                    ('C2', 'bitget', 'Alt', 'KEYCHAIN:C2', 'KEYCHAIN:C2', 0, 1, 1)",
+            [],
+        )
+        .unwrap();
+        assert_eq!(resolve_credential_id(&conn, None, None).unwrap(), "C1");
+        assert_eq!(resolve_credential_id(&conn, None, Some("bitget")).unwrap(), "C1");
+        assert!(resolve_credential_id(&conn, None, Some("blofin")).is_err());
+
+        conn.execute(
+            "INSERT INTO api_credentials (id, exchange, label, api_key, api_secret, is_default, created_at, updated_at)
+             VALUES ('C3', 'blofin', 'Main', 'KEYCHAIN:C3', 'KEYCHAIN:C3', 1, 2, 2)",
+            [],
+        )
+        .unwrap();
+        // Ambiguous without an exchange
+        assert!(resolve_credential_id(&conn, None, None).is_err());
+        assert_eq!(resolve_credential_id(&conn, None, Some("blofin")).unwrap(), "C3");
+
+        // One default per exchange
+        assert!(conn
+            .execute("UPDATE api_credentials SET is_default = 1 WHERE id = 'C2'", [])
+            .is_err());
+    }
+
+    #[test]
+    fn test_incremental_start_time_overlaps_last_sync() {
+        assert_eq!(incremental_start_time(None), None);
+        assert_eq!(incremental_start_time(Some(1_767_603_600)), Some((1_767_603_600 - 3600) * 1000));
+        assert_eq!(incremental_start_time(Some(60)), Some(0));
+    }
+
+    #[test]
+    fn test_auto_sync_start_time_respects_lookback() {
+        let now = 100 * 86_400;
+        // Never synced: the window bounds the first sync instead of full history
+        assert_eq!(auto_sync_start_time(None, Some(7), now), Some(93 * 86_400 * 1000));
+        assert_eq!(auto_sync_start_time(None, None, now), None);
+        // Synced long ago: the window wins over the stale last sync
+        assert_eq!(auto_sync_start_time(Some(10 * 86_400), Some(7), now), Some(93 * 86_400 * 1000));
+        // Synced recently: incremental wins
+        let recent = now - 600;
+        assert_eq!(auto_sync_start_time(Some(recent), Some(7), now), incremental_start_time(Some(recent)));
+    }
+
+    #[test]
+    fn test_sync_fingerprint_is_stable() {
+        let raw = RawTrade {
+            exchange_trade_id: "T1".to_string(),
+            exchange_order_id: "O1".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            side: "sell".to_string(),
+            position_side: "long".to_string(),
+            quantity: 0.5,
+            entry_price: 60000.0,
+            exit_price: Some(61000.0),
+            pnl: 500.0,
+            fee: 1.2,
+            leverage: Some(10),
+            timestamp: 1_767_603_600_000,
+            close_timestamp: None,
+            reduce_only: Some(true),
+            raw_json: "{}".to_string(),
+        };
+        // Previews and syncs must agree on the fingerprint for duplicates to line up
+        assert_eq!(
+            sync_fingerprint("bitget", &raw),
+            "api|bitget|T1|O1|btcusdt|0.50000000|500.00000000|1767603600000"
+        );
+        let mut other = raw.clone();
+        other.raw_json = "{\"changed\": true}".to_string();
+        assert_eq!(sync_fingerprint("bitget", &raw), sync_fingerprint("bitget", &other));
+    }
+
+    #[test]
+    fn test_plan_sync_trades_groups_fills() {
+        let conn = test_conn();
+
+        let fill = |id: &str, side: &str, reduce_only: bool, price: f64, pnl: f64, timestamp: i64| RawTrade {
+            exchange_trade_id: id.to_string(),
+            exchange_order_id: format!("O{}", id),
+            symbol: "BTCUSDT".to_string(),
+            side: side.to_string(),
+            position_side: "LONG".to_string(),
+            quantity: 1.0,
+            entry_price: price,
+            exit_price: None,
+            pnl,
+            fee: 0.5,
+            leverage: None,
+            timestamp,
+            close_timestamp: None,
+            reduce_only: Some(reduce_only),
+            raw_json: "{}".to_string(),
+        };
+        let fills = vec![
+            fill("1", "buy", false, 100.0, 0.0, 1_000),
+            fill("2", "sell", true, 105.0, 5.0, 2_000),
+            fill("3", "buy", false, 100.0, 0.0, 3_000),
+            fill("4", "buy", false, 110.0, 0.0, 4_000),
+            fill("5", "sell", true, 120.0, 15.0, 5_000),
+            fill("6", "sell", true, 120.0, 5.0, 6_000),
+        ];
+
+        // An older sync imported fill 5 on its own
+        let legacy = map_raw_trade_to_trade(&fills[4], "bitget", 10_000.0, 0.01, 0.5, &sync_fingerprint("bitget", &fills[4])).unwrap();
+        insert_trade(&conn, &legacy).unwrap();
+
+        let planned: Vec<SyncPreviewTrade> = plan_sync_trades(&conn, "bitget", fills, &[], 10_000.0, 0.01, 0.5)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        // First position grouped; second falls back to its fills, one already imported
+        assert_eq!(planned.len(), 3);
+        assert_eq!(planned[0].trade.status, "WIN");
+        assert_eq!(planned[0].trade.total_pnl, Some(5.0));
+        assert!(planned[0].trade.import_fingerprint.as_deref().unwrap().starts_with("api|bitget|position|1|"));
+        assert!(!planned[0].is_duplicate);
+        assert_eq!(planned.iter().filter(|p| p.is_duplicate).count(), 1);
+        assert_eq!(planned[2].trade.total_pnl, Some(5.0));
+    }
+
+    #[test]
+    fn test_link_fill_to_user_trade() {
+        let mut conn = test_conn();
+
+        let raw = RawTrade {
+            exchange_trade_id: "T1".to_string(),
+            exchange_order_id: "O1".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            side: "sell".to_string(),
+            position_side: "LONG".to_string(),
+            quantity: 0.5,
+            entry_price: 60000.0,
+            exit_price: Some(61000.0),
+            pnl: 500.0,
+            fee: 1.2,
+            leverage: None,
+            timestamp: 1_767_603_600_000,
+            close_timestamp: Some(1_767_610_800_000),
+            reduce_only: Some(true),
+            raw_json: "{}".to_string(),
+        };
+        let fill = map_raw_trade_to_trade(&raw, "bitget", 10_000.0, 0.01, 0.5, &sync_fingerprint("bitget", &raw)).unwrap();
+
+        // Entered by hand half an hour off, slightly different size, with a 250 risk
+        let manual = Trade {
+            id: "MANUAL".to_string(),
+            pair: "BTC/USDT".to_string(),
+            trade_date: 1_767_603_600 + 1800,
+            status: "OPEN".to_string(),
+            quantity: 0.45,
+            one_r: 250.0,
+            close_date: None,
+            exits: None,
+            total_pnl: None,
+            pnl_in_r: None,
+            import_fingerprint: None,
+            import_source: "USER_CREATED".to_string(),
+            ..fill.clone()
+        };
+        insert_trade(&conn, &manual).unwrap();
+        assert_eq!(find_matching_user_trade(&conn, &fill), Some("MANUAL".to_string()));
+        assert_eq!(find_matching_user_trade(&conn, &Trade { quantity: 1.0, ..fill.clone() }), None);
+        assert_eq!(find_matching_user_trade(&conn, &Trade { position_type: "SHORT".to_string(), ..fill.clone() }), None);
+
+        insert_trade(&conn, &fill).unwrap();
+        link_fill(&mut conn, "MANUAL", &fill.id).unwrap();
+
+        let (status, pnl_in_r, fingerprint): (String, f64, String) = conn
+            .query_row(
+                "SELECT status, pnl_in_r, import_fingerprint FROM trades WHERE id = 'MANUAL'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!((status.as_str(), pnl_in_r), ("WIN", 2.0));
+        assert_eq!(Some(fingerprint), fill.import_fingerprint);
+        let trashed: bool = conn
+            .query_row("SELECT deleted_at IS NOT NULL FROM trades WHERE id = ?", [&fill.id], |row| row.get(0))
+            .unwrap();
+        assert!(trashed);
+
+        // Linked trades are neither suggested nor linked again
+        assert_eq!(find_matching_user_trade(&conn, &fill), None);
+        assert!(link_fill(&mut conn, "MANUAL", &fill.id).is_err());
+    }
+
+    #[test]
+    fn test_symbol_filters() {
+        let mut conn = test_conn();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        conn.execute(
+            "INSERT INTO api_credentials (id, exchange, label, api_key, api_secret, created_at, updated_at)
+             VALUES ('C1', 'blofin', 'Main', 'key', 'secret', 0, 0)",
+            [],
+        )
+        .unwrap();
+
+        let filters = SymbolFilters {
+            include: vec![],
+            exclude: vec!["sol-usdt".to_string(), "SOLUSDT".to_string(), " ".to_string()],
+        };
+        save_symbol_filters(&mut conn, "C1", &filters).unwrap();
+        let saved = load_symbol_filters(&conn, "C1").unwrap();
+        assert_eq!(saved.exclude, vec!["SOLUSDT".to_string()]);
+        assert!(!saved.allows("SOL-USDT"));
+        assert!(saved.allows("BTC-USDT"));
+
+        // An include list restricts syncs to the listed symbols
+        let include_only = SymbolFilters { include: vec!["BTCUSDT".to_string()], exclude: vec![] };
+        assert!(include_only.allows("btc-usdt"));
+        assert!(!include_only.allows("ETHUSDT"));
+
+        let conflicting = SymbolFilters {
+            include: vec!["ETH-USDT".to_string()],
+            exclude: vec!["ethusdt".to_string()],
+        };
+        assert!(save_symbol_filters(&mut conn, "C1", &conflicting).is_err());
+        assert_eq!(load_symbol_filters(&conn, "C1").unwrap().exclude, vec!["SOLUSDT".to_string()]);
+
+        // Filters go with their credential
+        conn.execute("DELETE FROM api_credentials WHERE id = 'C1'", []).unwrap();
+        assert!(load_symbol_filters(&conn, "C1").unwrap().exclude.is_empty());
+    }
+
+    struct FakeClient {
+        http: crate::api::read_only::ReadOnlyHttpClient,
+        /// Exchange clock minus local clock; None when unreachable
+        server_offset_ms: Option<i64>,
+        test_result: fn() -> Result<bool, ApiError>,
+        headroom: u32,
+    }
+
+    impl FakeClient {
+        fn new(server_offset_ms: Option<i64>, test_result: fn() -> Result<bool, ApiError>) -> Self {
+            Self {
+                http: crate::api::read_only::ReadOnlyHttpClient::new("https://exchange.invalid", &[]),
+                server_offset_ms,
+                test_result,
+                headroom: 5,
+            }
+        }
+    }
+
+    impl crate::api::read_only::ReadOnlyExchangeClient for FakeClient {
+        fn http(&self) -> &crate::api::read_only::ReadOnlyHttpClient {
+            &self.http
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ExchangeClient for FakeClient {
+        fn exchange_name(&self) -> &str {
+            "fake"
+        }
+
+        async fn fetch_trades(
+            &self,
+            _request: crate::api::client::FetchTradesRequest,
+        ) -> Result<crate::api::client::FetchTradesResponse, ApiError> {
+            unimplemented!()
+        }
+
+        async fn test_credentials(&self) -> Result<bool, ApiError> {
+            (self.test_result)()
+        }
+
+        async fn fetch_server_time_ms(&self) -> Result<i64, ApiError> {
+            self.server_offset_ms
+                .map(|offset| Utc::now().timestamp_millis() + offset)
+                .ok_or_else(|| ApiError::NetworkError("connection refused".to_string()))
+        }
+
+        async fn fetch_permissions(&self) -> Result<Option<crate::api::client::ApiKeyPermissions>, ApiError> {
+            Ok(Some(crate::api::client::ApiKeyPermissions {
+                read: true,
+                trade: true,
+                withdraw: Some(false),
+                raw: vec![],
+            }))
+        }
+
+        fn rate_limit_headroom(&self) -> u32 {
+            self.headroom
+        }
+
+        fn rate_limit(&self) -> crate::api::client::RateLimitConfig {
+            crate::api::client::RateLimitConfig { requests_per_second: 10, burst_size: 10 }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_diagnose_credentials() {
+        // Valid key on a skewed clock, with trading rights
+        let result = diagnose_credentials(&FakeClient::new(Some(60_000), || Ok(true)), "fake").await;
+        assert!(result.valid);
+        let diagnosis = &result.diagnosis;
+        assert_eq!(diagnosis.connectivity.status, "ok");
+        assert_eq!(diagnosis.clock_skew.status, "warning");
+        assert!((diagnosis.clock_skew_ms.unwrap() - 60_000).abs() < 1_000);
+        assert_eq!(diagnosis.signature.status, "ok");
+        assert_eq!(diagnosis.permissions.status, "warning");
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(diagnosis.rate_limit.status, "ok");
+        assert_eq!(diagnosis.rate_limit_headroom, 5);
+
+        // Unreachable: the signed steps are skipped
+        let result = diagnose_credentials(&FakeClient::new(None, || Ok(true)), "fake").await;
+        assert!(!result.valid);
+        assert_eq!(result.diagnosis.connectivity.status, "failed");
+        assert_eq!(result.diagnosis.signature.status, "skipped");
+        assert_eq!(result.diagnosis.permissions.status, "skipped");
+
+        // A rejected timestamp is told apart from a wrong key
+        let result = diagnose_credentials(
+            &FakeClient::new(Some(0), || {
+                Err(ApiError::ExchangeError { code: "40008".to_string(), message: "Request timestamp expired".to_string() })
+            }),
+            "fake",
+        )
+        .await;
+        assert!(!result.valid);
+        assert_eq!(result.diagnosis.clock_skew.status, "ok");
+        assert!(result.diagnosis.signature.message.contains("request time"));
+        let result = diagnose_credentials(&FakeClient::new(Some(0), || Ok(false)), "fake").await;
+        assert!(result.diagnosis.signature.message.contains("rejected the API key"));
+
+        let result = diagnose_credentials(
+            &FakeClient::new(Some(0), || Err(ApiError::RateLimitError("slow down".to_string()))),
+            "fake",
+        )
+        .await;
+        assert_eq!(result.diagnosis.rate_limit.status, "warning");
+    }
+
+    #[test]
+    fn test_load_environment() {
+        let conn = test_conn();
+        conn.execute(
+            "INSERT INTO api_credentials (id, exchange, label, api_key, api_secret, created_at, updated_at)
+             VALUES ('C1', 'bitget', 'Main', 'key', 'secret', 0, 0)",
+            [],
+        )
+        .unwrap();
+
+        // Existing credentials stay on production
+        assert_eq!(load_environment(&conn, "C1").unwrap(), ExchangeEnvironment::default());
+
+        conn.execute(
+            "UPDATE api_credentials SET environment = 'demo', base_url = 'https://demo.example.com' WHERE id = 'C1'",
+            [],
+        )
+        .unwrap();
+        let environment = load_environment(&conn, "C1").unwrap();
+        assert!(environment.demo);
+        assert_eq!(environment.base_url.as_deref(), Some("https://demo.example.com"));
+
+        assert!(conn
+            .execute("UPDATE api_credentials SET environment = 'testnet' WHERE id = 'C1'", [])
+            .is_err());
+        assert!(load_environment(&conn, "C9").is_err());
+
+        assert_eq!(
+            ExchangeEnvironment::normalize_base_url(" https://openapi.blofin.com/ ").unwrap(),
+            "https://openapi.blofin.com"
+        );
+        assert!(ExchangeEnvironment::normalize_base_url("ftp://openapi.blofin.com").is_err());
+        assert!(ExchangeEnvironment::normalize_base_url("http://openapi.blofin.com").is_err());
+        assert!(ExchangeEnvironment::normalize_base_url("http://127.0.0.1:8080").is_ok());
+        assert!(ExchangeEnvironment::normalize_base_url("https://openapi.blofin.com?x=1").is_err());
+    }
+
+    #[test]
+    fn test_prune_sync_history_applies_retention() {
+        let conn = test_conn();
+        conn.execute(
+            "INSERT INTO api_credentials (id, exchange, label, api_key, api_secret, created_at, updated_at)
+             VALUES ('C1', 'blofin', 'Main', 'key', 'secret', 0, 0)",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE settings SET sync_history_retention_days = 30, sync_history_max_entries = 3 WHERE id = 1",
+            [],
+        )
+        .unwrap();
+
+        let now = 100 * 86_400;
+        // One entry per day for the last 40 days
+        for day in 0..40 {
+            conn.execute(
+                "INSERT INTO api_sync_history (id, credential_id, exchange, sync_type, last_sync_timestamp, trades_imported, trades_duplicated, status, created_at)
+                 VALUES (?, 'C1', 'blofin', 'automatic', ?, 0, 0, 'success', ?)",
+                rusqlite::params![format!("H{}", day), now - day * 86_400, now - day * 86_400],
+            )
+            .unwrap();
+        }
+
+        assert_eq!(prune_sync_history(&conn, "C1", now).unwrap(), 37);
+        let kept: Vec<String> = conn
+            .prepare("SELECT id FROM api_sync_history ORDER BY created_at DESC")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(kept, vec!["H0", "H1", "H2"]);
+
+        // Zero disables both limits
+        conn.execute(
+            "UPDATE settings SET sync_history_retention_days = 0, sync_history_max_entries = 0 WHERE id = 1",
+            [],
+        )
+        .unwrap();
+        assert_eq!(prune_sync_history(&conn, "C1", now + 365 * 86_400).unwrap(), 0);
+    }
+}
@@ -0,0 +1,405 @@
+use tauri::{ipc::Invoke, AppHandle, Emitter, Runtime, State};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::db::Database;
+use crate::commands::error::CommandError;
+use chrono::Utc;
+
+/// Commands that still answer while the app is locked
+const LOCKED_COMMANDS: &[&str] = &["get_app_lock_status", "unlock_app", "lock_app"];
+
+/// Body of desktop notifications raised while locked, in place of the trade details
+const LOCKED_NOTIFICATION_BODY: &str = "Unlock Trading Journal to see the details.";
+
+/// How often the auto-lock checks for inactivity
+const AUTO_LOCK_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Wrong passwords accepted before each further attempt has to wait
+const FREE_PASSWORD_ATTEMPTS: u32 = 3;
+
+/// Longest wait between password attempts; the wait doubles per failure up to this
+const MAX_PASSWORD_DELAY: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppLockStatus {
+    /// A master password is set
+    pub enabled: bool,
+    pub locked: bool,
+    /// Minutes without user activity before the app locks itself (0 disables auto-lock)
+    pub auto_lock_minutes: i64,
+}
+
+/// Lock state of the app, checked before every command is dispatched
+pub struct AppLock {
+    enabled: AtomicBool,
+    locked: AtomicBool,
+    auto_lock_minutes: AtomicI64,
+    last_activity: Mutex<Instant>,
+    /// Wrong passwords in a row and when the last one was entered
+    failed_attempts: Mutex<(u32, Instant)>,
+}
+
+impl AppLock {
+    /// Lock settings from the database; a set password locks the app from launch
+    pub fn load(conn: &rusqlite::Connection) -> Result<Self, rusqlite::Error> {
+        let (password_hash, auto_lock_minutes): (Option<String>, i64) = conn.query_row(
+            "SELECT password_hash, auto_lock_minutes FROM app_lock WHERE id = 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let enabled = password_hash.is_some();
+
+        Ok(Self {
+            enabled: AtomicBool::new(enabled),
+            locked: AtomicBool::new(enabled),
+            auto_lock_minutes: AtomicI64::new(auto_lock_minutes),
+            last_activity: Mutex::new(Instant::now()),
+            failed_attempts: Mutex::new((0, Instant::now())),
+        })
+    }
+
+    /// Whether a command may run now. Commands don't count as activity: the window polls
+    /// some of them on a timer, which would keep an unattended app unlocked.
+    pub fn allows(&self, command: &str) -> bool {
+        LOCKED_COMMANDS.contains(&command) || !self.check(Instant::now())
+    }
+
+    /// Lock when idle past the auto-lock delay. Returns whether the app is locked.
+    fn check(&self, now: Instant) -> bool {
+        self.lock_if_idle(now) || self.locked.load(Ordering::SeqCst)
+    }
+
+    /// Push the auto-lock deadline out after user input. Ignored while locked.
+    fn record_activity(&self, now: Instant) {
+        if !self.check(now) {
+            *self.last_activity.lock().unwrap_or_else(|e| e.into_inner()) = now;
+        }
+    }
+
+    /// Lock once nothing ran for the auto-lock delay. Returns true when it just locked.
+    fn lock_if_idle(&self, now: Instant) -> bool {
+        let minutes = self.auto_lock_minutes.load(Ordering::SeqCst);
+        if !self.enabled.load(Ordering::SeqCst) || minutes <= 0 || self.locked.load(Ordering::SeqCst) {
+            return false;
+        }
+        let last_activity = *self.last_activity.lock().unwrap_or_else(|e| e.into_inner());
+        if now.duration_since(last_activity) < Duration::from_secs(minutes as u64 * 60) {
+            return false;
+        }
+        self.locked.store(true, Ordering::SeqCst);
+        true
+    }
+
+    fn status(&self) -> AppLockStatus {
+        AppLockStatus {
+            enabled: self.enabled.load(Ordering::SeqCst),
+            locked: self.locked.load(Ordering::SeqCst),
+            auto_lock_minutes: self.auto_lock_minutes.load(Ordering::SeqCst),
+        }
+    }
+
+    fn unlock(&self) {
+        self.locked.store(false, Ordering::SeqCst);
+        *self.last_activity.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+    }
+
+    /// Check a password against the stored hash, counting wrong ones towards the throttle
+    fn verify_throttled(&self, password: &str, hash: &str) -> Result<(), CommandError> {
+        // Held through the check so parallel attempts wait their turn
+        let mut failed_attempts = self.failed_attempts.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        if let Some(wait) = retry_in(*failed_attempts, now) {
+            return Err(CommandError::RateLimited(format!(
+                "Too many wrong passwords, try again in {} s",
+                wait.as_secs().max(1)
+            )));
+        }
+
+        if verify_password(password, hash) {
+            *failed_attempts = (0, now);
+            Ok(())
+        } else {
+            *failed_attempts = (failed_attempts.0.saturating_add(1), now);
+            Err(CommandError::Auth("Incorrect password".to_string()))
+        }
+    }
+}
+
+/// Wait left after `failures` wrong passwords in a row, the last at `last_failure`
+fn retry_in((failures, last_failure): (u32, Instant), now: Instant) -> Option<Duration> {
+    let throttled = failures.checked_sub(FREE_PASSWORD_ATTEMPTS)?;
+    let delay = Duration::from_secs(1u64 << throttled.min(16)).min(MAX_PASSWORD_DELAY);
+    delay.checked_sub(now.duration_since(last_failure)).filter(|wait| !wait.is_zero())
+}
+
+/// Wrap the command handler so that, while locked, everything but unlocking is refused.
+/// Without the lock state (not loaded yet) nothing runs.
+pub fn with_app_lock<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    use tauri::Manager;
+
+    move |invoke| {
+        let allowed = invoke
+            .message
+            .webview()
+            .try_state::<AppLock>()
+            .is_some_and(|app_lock| app_lock.allows(invoke.message.command()));
+        if !allowed {
+            invoke.resolver.reject(CommandError::Locked("App is locked".to_string()));
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+/// Whether the app is locked, or its lock state is not loaded yet. Background tasks check
+/// it before handing trade data to the window or to desktop notifications.
+pub(crate) fn is_locked<R: Runtime>(app_handle: &AppHandle<R>) -> bool {
+    use tauri::Manager;
+
+    app_handle
+        .try_state::<AppLock>()
+        .is_none_or(|app_lock| app_lock.check(Instant::now()))
+}
+
+/// Emit an event carrying trade data, dropped while the app is locked. The window reloads
+/// its data after unlocking, so nothing is held back for later.
+pub(crate) fn emit_unless_locked<R: Runtime, S: Serialize + Clone>(
+    app_handle: &AppHandle<R>,
+    event: &str,
+    payload: S,
+) -> tauri::Result<()> {
+    if is_locked(app_handle) {
+        return Ok(());
+    }
+    app_handle.emit(event, payload)
+}
+
+/// Body for a desktop notification: `body` itself, or a generic line while locked
+pub(crate) fn notification_body<'a, R: Runtime>(app_handle: &AppHandle<R>, body: &'a str) -> &'a str {
+    if is_locked(app_handle) {
+        LOCKED_NOTIFICATION_BODY
+    } else {
+        body
+    }
+}
+
+/// Lock the app after the inactivity delay, telling the window with an `app-locked` event
+pub async fn run_auto_lock(app_handle: AppHandle) {
+    use tauri::Manager;
+
+    let mut interval = tokio::time::interval(AUTO_LOCK_CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        let app_lock = app_handle.state::<AppLock>();
+        if app_lock.lock_if_idle(Instant::now()) {
+            println!("App locked after inactivity");
+            let _ = app_handle.emit("app-locked", ());
+        }
+    }
+}
+
+fn hash_password(password: &str) -> Result<String, CommandError> {
+    let salt = SaltString::generate(&mut rand::rngs::OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| CommandError::Internal(format!("Failed to hash password: {}", e)))
+}
+
+fn verify_password(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .map(|parsed| Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+        .unwrap_or(false)
+}
+
+fn load_password_hash(db: &Database) -> Result<Option<String>, CommandError> {
+    let conn = db.pool.get()?;
+    conn.query_row("SELECT password_hash FROM app_lock WHERE id = 1", [], |row| row.get(0))
+        .map_err(|e| CommandError::Database(e.to_string()))
+}
+
+#[tauri::command]
+pub async fn get_app_lock_status(app_lock: State<'_, AppLock>) -> Result<AppLockStatus, CommandError> {
+    Ok(app_lock.status())
+}
+
+/// Unlock with the master password
+#[tauri::command]
+pub async fn unlock_app(
+    db: State<'_, Database>,
+    app_lock: State<'_, AppLock>,
+    password: String,
+) -> Result<AppLockStatus, CommandError> {
+    let Some(hash) = load_password_hash(&db)? else {
+        app_lock.unlock();
+        return Ok(app_lock.status());
+    };
+    app_lock.verify_throttled(&password, &hash)?;
+    app_lock.unlock();
+    Ok(app_lock.status())
+}
+
+/// Signal user input (keys, clicks, scrolling) from the window; only this keeps auto-lock
+/// from locking the app
+#[tauri::command]
+pub async fn record_app_activity(app_lock: State<'_, AppLock>) -> Result<(), CommandError> {
+    app_lock.record_activity(Instant::now());
+    Ok(())
+}
+
+/// Lock right away. Does nothing while no master password is set.
+#[tauri::command]
+pub async fn lock_app(app_lock: State<'_, AppLock>) -> Result<AppLockStatus, CommandError> {
+    if app_lock.enabled.load(Ordering::SeqCst) {
+        app_lock.locked.store(true, Ordering::SeqCst);
+    }
+    Ok(app_lock.status())
+}
+
+/// Set, change or remove (`new_password` None) the master password. `current_password` is
+/// required once one is set.
+#[tauri::command]
+pub async fn set_app_lock_password(
+    db: State<'_, Database>,
+    app_lock: State<'_, AppLock>,
+    current_password: Option<String>,
+    new_password: Option<String>,
+) -> Result<AppLockStatus, CommandError> {
+    if let Some(hash) = load_password_hash(&db)? {
+        let current = current_password.unwrap_or_default();
+        app_lock.verify_throttled(&current, &hash)?;
+    }
+
+    let new_hash = match new_password.as_deref() {
+        Some(password) if password.chars().count() < 8 => {
+            return Err(CommandError::Validation("Password must be at least 8 characters".to_string()));
+        }
+        Some(password) => Some(hash_password(password)?),
+        None => None,
+    };
+
+    let conn = db.pool.get()?;
+    conn.execute(
+        "UPDATE app_lock SET password_hash = ?, updated_at = ? WHERE id = 1",
+        rusqlite::params![new_hash, Utc::now().timestamp()],
+    )?;
+
+    app_lock.enabled.store(new_hash.is_some(), Ordering::SeqCst);
+    app_lock.unlock();
+    Ok(app_lock.status())
+}
+
+/// Minutes without user activity before the app locks itself; 0 disables auto-lock
+#[tauri::command]
+pub async fn set_auto_lock_minutes(
+    db: State<'_, Database>,
+    app_lock: State<'_, AppLock>,
+    minutes: i64,
+) -> Result<AppLockStatus, CommandError> {
+    if !(0..=1440).contains(&minutes) {
+        return Err(CommandError::Validation("Auto-lock must be between 0 and 1440 minutes".to_string()));
+    }
+
+    let conn = db.pool.get()?;
+    conn.execute(
+        "UPDATE app_lock SET auto_lock_minutes = ?, updated_at = ? WHERE id = 1",
+        rusqlite::params![minutes, Utc::now().timestamp()],
+    )?;
+
+    app_lock.auto_lock_minutes.store(minutes, Ordering::SeqCst);
+    Ok(app_lock.status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app_lock(enabled: bool, auto_lock_minutes: i64) -> AppLock {
+        AppLock {
+            enabled: AtomicBool::new(enabled),
+            locked: AtomicBool::new(enabled),
+            auto_lock_minutes: AtomicI64::new(auto_lock_minutes),
+            last_activity: Mutex::new(Instant::now()),
+            failed_attempts: Mutex::new((0, Instant::now())),
+        }
+    }
+
+    #[test]
+    fn test_locked_app_only_allows_unlocking() {
+        let lock = app_lock(true, 15);
+        assert!(!lock.allows("get_trades"));
+        assert!(lock.allows("unlock_app"));
+
+        lock.unlock();
+        assert!(lock.allows("get_trades"));
+        assert!(app_lock(false, 15).allows("get_trades"));
+    }
+
+    #[test]
+    fn test_auto_lock_after_inactivity() {
+        let lock = app_lock(true, 1);
+        lock.unlock();
+        let start = Instant::now();
+
+        // Commands alone (a polling window) don't keep the app unlocked
+        assert!(lock.allows("get_trades"));
+        assert!(!lock.check(start + Duration::from_secs(30)));
+        assert!(lock.check(start + Duration::from_secs(61)));
+
+        // User activity at 30s pushes the deadline out
+        let lock = app_lock(true, 1);
+        lock.unlock();
+        let start = Instant::now();
+        lock.record_activity(start + Duration::from_secs(30));
+        assert!(!lock.check(start + Duration::from_secs(80)));
+        assert!(lock.check(start + Duration::from_secs(91)));
+        // Activity reported while locked doesn't unlock
+        lock.record_activity(start + Duration::from_secs(92));
+        assert!(lock.check(start + Duration::from_secs(93)));
+
+        // Disabled auto-lock never locks
+        let lock = app_lock(true, 0);
+        lock.unlock();
+        assert!(!lock.check(Instant::now() + Duration::from_secs(86_400)));
+    }
+
+    #[test]
+    fn test_wrong_passwords_throttled() {
+        let lock = app_lock(true, 15);
+        let hash = hash_password("correct horse").unwrap();
+
+        for _ in 0..FREE_PASSWORD_ATTEMPTS {
+            assert!(matches!(lock.verify_throttled("wrong", &hash), Err(CommandError::Auth(_))));
+        }
+        // Even the right password waits once throttled
+        assert!(matches!(lock.verify_throttled("correct horse", &hash), Err(CommandError::RateLimited(_))));
+
+        let (failures, last_failure) = *lock.failed_attempts.lock().unwrap();
+        let after = |millis| last_failure + Duration::from_millis(millis);
+        assert_eq!(retry_in((failures, last_failure), after(500)), Some(Duration::from_millis(500)));
+        assert_eq!(retry_in((failures, last_failure), after(1000)), None);
+
+        // The wait doubles and stops at the maximum
+        assert_eq!(retry_in((FREE_PASSWORD_ATTEMPTS + 2, last_failure), after(0)), Some(Duration::from_secs(4)));
+        assert_eq!(retry_in((FREE_PASSWORD_ATTEMPTS + 40, last_failure), after(0)), Some(MAX_PASSWORD_DELAY));
+
+        // A right password resets the count
+        *lock.failed_attempts.lock().unwrap() = (1, last_failure);
+        lock.verify_throttled("correct horse", &hash).unwrap();
+        assert_eq!(lock.failed_attempts.lock().unwrap().0, 0);
+    }
+
+    #[test]
+    fn test_password_hash_roundtrip() {
+        let hash = hash_password("correct horse").unwrap();
+        assert!(verify_password("correct horse", &hash));
+        assert!(!verify_password("wrong horse", &hash));
+        assert!(!verify_password("correct horse", "not a hash"));
+    }
+}
@@ -0,0 +1,230 @@
+use tauri::{AppHandle, Manager, State};
+use crate::db::encryption;
+use crate::db::migration_runner::MigrationRunner;
+use crate::db::Database;
+use crate::commands::error::CommandError;
+use rusqlite::{Connection, OpenFlags, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub size_bytes: u64,
+    /// File modification time (unix seconds)
+    pub created_at: i64,
+    /// Schema version recorded in the backup, if it has a schema_migrations table
+    pub schema_version: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreBackupResult {
+    pub restored_from: String,
+    /// Copy of the database as it was before the restore
+    pub safety_backup: String,
+    /// The app must restart so migrations run on the restored data and in-memory state reloads
+    pub restart_required: bool,
+}
+
+pub(crate) fn backup_dir(app_handle: &AppHandle) -> Result<PathBuf, CommandError> {
+    Ok(app_handle.path().app_data_dir()?.join("backups"))
+}
+
+/// Database backups (pre-migration and pre-restore copies), newest first
+#[tauri::command]
+pub async fn list_backups(app_handle: AppHandle) -> Result<Vec<BackupInfo>, CommandError> {
+    let dir = backup_dir(&app_handle)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("db") {
+            continue;
+        }
+        let Some(file_name) = path.file_name().and_then(|s| s.to_str()).map(str::to_string) else {
+            continue;
+        };
+        let metadata = std::fs::metadata(&path)?;
+        let created_at = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+
+        backups.push(BackupInfo {
+            file_name,
+            size_bytes: metadata.len(),
+            created_at,
+            schema_version: backup_schema_version(&path).unwrap_or(None),
+        });
+    }
+
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+    Ok(backups)
+}
+
+/// Replace the current database with a backup from the backups folder. The backup is
+/// integrity-checked first and the current database is saved as a pre_restore_ backup.
+/// The copy runs through SQLite's backup API in a single step, so the live database is
+/// either fully replaced or left untouched.
+#[tauri::command]
+pub async fn restore_backup(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    file_name: String,
+) -> Result<RestoreBackupResult, CommandError> {
+    // Only plain file names from the backups folder, never arbitrary paths
+    if file_name.is_empty() || Path::new(&file_name).file_name().and_then(|s| s.to_str()) != Some(file_name.as_str()) {
+        return Err(CommandError::Validation(format!("Invalid backup file name: {}", file_name)));
+    }
+    let dir = backup_dir(&app_handle)?;
+    let source_path = dir.join(&file_name);
+    if !source_path.is_file() {
+        return Err(CommandError::NotFound(format!("Backup not found: {}", file_name)));
+    }
+
+    let source = validate_backup(&source_path)?;
+
+    let mut conn = db.pool.get()?;
+
+    let safety_name = format!("pre_restore_{}.db", chrono::Utc::now().format("%Y%m%d_%H%M%S"));
+    let safety_path = dir.join(&safety_name);
+    {
+        let mut safety = Connection::open(&safety_path)?;
+        encryption::apply_key(&safety)?;
+        let backup = rusqlite::backup::Backup::new(&conn, &mut safety)?;
+        backup
+            .run_to_completion(-1, std::time::Duration::from_millis(0), None)
+            .map_err(|e| CommandError::Database(format!("Failed to back up the current database: {}", e)))?;
+    }
+
+    {
+        let backup = rusqlite::backup::Backup::new(&source, &mut conn)?;
+        backup
+            .run_to_completion(-1, std::time::Duration::from_millis(0), None)
+            .map_err(|e| CommandError::Database(format!("Failed to restore backup: {}", e)))?;
+    }
+
+    Ok(RestoreBackupResult {
+        restored_from: file_name,
+        safety_backup: safety_name,
+        restart_required: true,
+    })
+}
+
+/// Revert the schema to `target_version` with the migrations' down SQL, ahead of
+/// installing an older release. Starting this build again re-applies the migrations.
+/// Returns the number of migrations reverted.
+#[tauri::command]
+pub async fn rollback_database(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    target_version: u32,
+) -> Result<usize, CommandError> {
+    let db_path = app_handle
+        .path()
+        .app_data_dir()?
+        .join("trading_journal.db");
+
+    let conn = db.pool.get()?;
+    MigrationRunner::new()
+        .rollback_to(&conn, &db_path.to_string_lossy(), target_version)
+        .map_err(CommandError::from)
+}
+
+/// Restart the app, e.g. after restore_backup
+#[tauri::command]
+pub async fn restart_app(app_handle: AppHandle) -> Result<(), CommandError> {
+    app_handle.restart()
+}
+
+/// Open a backup read-only and check it is an intact journal database this build can migrate
+fn validate_backup(path: &Path) -> Result<Connection, CommandError> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| CommandError::Validation(format!("Cannot open backup: {}", e)))?;
+    encryption::apply_key(&conn)?;
+
+    let integrity: String = conn
+        .pragma_query_value(None, "integrity_check", |row| row.get(0))
+        .map_err(|e| CommandError::Validation(format!("Backup is not a valid database: {}", e)))?;
+    if integrity != "ok" {
+        return Err(CommandError::Validation(format!("Backup integrity check failed: {}", integrity)));
+    }
+
+    let has_trades: i32 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'trades'",
+        [],
+        |row| row.get(0),
+    )?;
+    if has_trades == 0 {
+        return Err(CommandError::Validation("Backup does not contain a trades table".to_string()));
+    }
+
+    let latest = MigrationRunner::new().latest_version();
+    if let Some(version) = schema_version(&conn)?
+        && version > latest
+    {
+        return Err(CommandError::Validation(format!(
+            "Backup has schema version {} but this version of the app supports up to {}",
+            version, latest
+        )));
+    }
+
+    Ok(conn)
+}
+
+fn backup_schema_version(path: &Path) -> rusqlite::Result<Option<u32>> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    encryption::apply_key(&conn)?;
+    schema_version(&conn)
+}
+
+fn schema_version(conn: &Connection) -> rusqlite::Result<Option<u32>> {
+    let has_table: i32 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'schema_migrations'",
+        [],
+        |row| row.get(0),
+    )?;
+    if has_table == 0 {
+        return Ok(None);
+    }
+
+    conn.query_row("SELECT MAX(version) FROM schema_migrations", [], |row| row.get(0))
+        .optional()
+        .map(Option::flatten)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_backup() {
+        let dir = std::env::temp_dir().join(format!("backup-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let good = dir.join("good.db");
+        {
+            let conn = Connection::open(&good).unwrap();
+            MigrationRunner::new()
+                .run_pending_migrations(&conn, good.to_str().unwrap())
+                .unwrap();
+        }
+        let conn = validate_backup(&good).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), Some(MigrationRunner::new().latest_version()));
+
+        let garbage = dir.join("garbage.db");
+        std::fs::write(&garbage, b"definitely not sqlite").unwrap();
+        assert!(validate_backup(&garbage).is_err());
+
+        let empty = dir.join("empty.db");
+        Connection::open(&empty).unwrap().execute_batch("CREATE TABLE x (id INTEGER)").unwrap();
+        assert!(validate_backup(&empty).is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,158 @@
+use tauri::State;
+use crate::db::Database;
+use crate::commands::error::CommandError;
+use crate::models::{CapitalTransaction, CreateCapitalTransactionInput};
+use chrono::Utc;
+
+fn map_row_to_capital_transaction(row: &rusqlite::Row) -> rusqlite::Result<CapitalTransaction> {
+    Ok(CapitalTransaction {
+        id: row.get("id")?,
+        transaction_type: row.get("transaction_type")?,
+        amount: row.get("amount")?,
+        transaction_date: row.get("transaction_date")?,
+        notes: row.get("notes")?,
+        account_id: row.get("account_id")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+/// Deposits and withdrawals, newest first, optionally for one account only
+#[tauri::command]
+pub async fn get_capital_transactions(
+    db: State<'_, Database>,
+    account_id: Option<String>,
+) -> Result<Vec<CapitalTransaction>, CommandError> {
+    let conn = db.pool.get()?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM capital_transactions
+             WHERE (?1 IS NULL OR account_id = ?1)
+             ORDER BY transaction_date DESC",
+        )?;
+
+    let transactions = stmt
+        .query_map([account_id], map_row_to_capital_transaction)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(transactions)
+}
+
+#[tauri::command]
+pub async fn create_capital_transaction(
+    db: State<'_, Database>,
+    transaction: CreateCapitalTransactionInput,
+) -> Result<CapitalTransaction, CommandError> {
+    let transaction_type = transaction.transaction_type.to_uppercase();
+    if transaction_type != "DEPOSIT" && transaction_type != "WITHDRAWAL" {
+        return Err(CommandError::Validation(format!("Unknown transaction type: {}", transaction.transaction_type)));
+    }
+    if !transaction.amount.is_finite() || transaction.amount <= 0.0 {
+        return Err(CommandError::Validation("Amount must be greater than 0".to_string()));
+    }
+
+    let conn = db.pool.get()?;
+
+    let id = format!("CAPITAL-{}-{}", Utc::now().timestamp_millis(), uuid::Uuid::new_v4());
+    conn.execute(
+        "INSERT INTO capital_transactions (id, transaction_type, amount, transaction_date, notes, account_id, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+        rusqlite::params![
+            id,
+            transaction_type,
+            transaction.amount,
+            transaction.transaction_date,
+            transaction.notes,
+            transaction.account_id,
+            Utc::now().timestamp()
+        ],
+    )?;
+
+    conn.query_row(
+        "SELECT * FROM capital_transactions WHERE id = ?",
+        [&id],
+        map_row_to_capital_transaction,
+    )
+    .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn delete_capital_transaction(
+    db: State<'_, Database>,
+    id: String,
+) -> Result<(), CommandError> {
+    let conn = db.pool.get()?;
+    conn.execute("DELETE FROM capital_transactions WHERE id = ?", [&id])?;
+    Ok(())
+}
+
+/// Net deposits minus withdrawals dated before `before` (all of them when None),
+/// for one account or, when `account_id` is None, across all of them
+pub(crate) fn net_capital_flow(
+    conn: &rusqlite::Connection,
+    before: Option<i64>,
+    account_id: Option<&str>,
+) -> rusqlite::Result<f64> {
+    conn.query_row(
+        "SELECT COALESCE(SUM(CASE WHEN transaction_type = 'DEPOSIT' THEN amount ELSE -amount END), 0.0)
+         FROM capital_transactions
+         WHERE transaction_date < COALESCE(?1, 9223372036854775807)
+         AND (?2 IS NULL OR account_id = ?2)",
+        rusqlite::params![before, account_id],
+        |row| row.get(0),
+    )
+}
+
+/// Net capital flow per UTC day (YYYY-MM-DD) from `from` onwards, oldest first
+pub(crate) fn daily_capital_flows(
+    conn: &rusqlite::Connection,
+    from: Option<i64>,
+    account_id: Option<&str>,
+) -> rusqlite::Result<Vec<(String, f64)>> {
+    let mut stmt = conn.prepare(
+        "SELECT strftime('%Y-%m-%d', transaction_date, 'unixepoch') AS day,
+                SUM(CASE WHEN transaction_type = 'DEPOSIT' THEN amount ELSE -amount END)
+         FROM capital_transactions
+         WHERE transaction_date >= COALESCE(?1, 0)
+         AND (?2 IS NULL OR account_id = ?2)
+         GROUP BY day
+         ORDER BY day",
+    )?;
+
+    let flows = stmt
+        .query_map(rusqlite::params![from, account_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(flows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::test_conn;
+
+    #[test]
+    fn test_capital_flows_net_withdrawals() {
+        let conn = test_conn();
+
+        for (id, kind, amount, date) in [
+            ("C1", "DEPOSIT", 5_000.0, 1_767_603_600),
+            ("C2", "WITHDRAWAL", 1_000.0, 1_767_690_000),
+            ("C3", "DEPOSIT", 500.0, 1_767_693_600),
+        ] {
+            conn.execute(
+                "INSERT INTO capital_transactions (id, transaction_type, amount, transaction_date, created_at)
+                 VALUES (?, ?, ?, ?, 0)",
+                rusqlite::params![id, kind, amount, date],
+            )
+            .unwrap();
+        }
+
+        assert_eq!(net_capital_flow(&conn, None, None).unwrap(), 4_500.0);
+        assert_eq!(net_capital_flow(&conn, Some(1_767_690_000), None).unwrap(), 5_000.0);
+        assert_eq!(net_capital_flow(&conn, None, Some("ACCOUNT-1")).unwrap(), 0.0);
+
+        let daily = daily_capital_flows(&conn, Some(1_767_690_000), None).unwrap();
+        assert_eq!(daily, vec![("2026-01-06".to_string(), -500.0)]);
+    }
+}
@@ -0,0 +1,374 @@
+use tauri::State;
+use crate::db::Database;
+use crate::commands::error::CommandError;
+use crate::models::Trade;
+use chrono::Utc;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// import_source of generated trades; clear_demo_data deletes exactly these
+const DEMO_IMPORT_SOURCE: &str = "DEMO";
+
+const DEFAULT_TRADES: usize = 300;
+const MAX_TRADES: usize = 2_000;
+const DEFAULT_MONTHS: u32 = 6;
+const MAX_MONTHS: u32 = 36;
+
+/// Latest trades are left open, so open-position views have something to show
+const OPEN_TRADES: usize = 3;
+
+/// Taker fee per side, as a fraction of the position size
+const FEE_RATE: f64 = 0.0006;
+
+/// Pairs traded, with the price the random walk starts from
+const DEMO_PAIRS: &[(&str, f64)] = &[
+    ("BTC/USDT", 62_000.0),
+    ("ETH/USDT", 3_100.0),
+    ("SOL/USDT", 145.0),
+    ("BNB/USDT", 560.0),
+    ("XRP/USDT", 0.52),
+    ("DOGE/USDT", 0.14),
+];
+
+const DEMO_EXCHANGES: &[&str] = &["BitGet", "BloFin"];
+
+const DEMO_SETUPS: &[&str] = &[
+    "Breakout retest",
+    "Range low bounce",
+    "Trend continuation",
+    "Failed breakdown",
+    "Liquidity sweep reversal",
+];
+
+const CALM_EMOTIONS: &[&str] = &["CALM", "CONFIDENT", "SATISFIED"];
+const TENSE_EMOTIONS: &[&str] = &["ANXIOUS", "FOMO", "FRUSTRATED", "REGRETFUL", "TIRED"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DemoDataInput {
+    /// Number of trades (default 300, max 2000)
+    pub trades: Option<usize>,
+    /// Months of history, ending now (default 6, max 36)
+    pub months: Option<u32>,
+    /// Fixed seed for the same data on every run, e.g. for screenshots
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DemoDataSummary {
+    pub trades_created: usize,
+    /// Trade date of the first and last generated trade
+    pub start_date: i64,
+    pub end_date: i64,
+}
+
+/// Fill the journal with sample trades to explore the dashboards without real data.
+/// Refused while demo trades exist; clear_demo_data removes them.
+#[tauri::command]
+pub async fn generate_demo_data(
+    db: State<'_, Database>,
+    input: Option<DemoDataInput>,
+) -> Result<DemoDataSummary, CommandError> {
+    let input = input.unwrap_or_default();
+    let count = input.trades.unwrap_or(DEFAULT_TRADES);
+    if !(1..=MAX_TRADES).contains(&count) {
+        return Err(CommandError::Validation(format!("Trades must be between 1 and {}", MAX_TRADES)));
+    }
+    let months = input.months.unwrap_or(DEFAULT_MONTHS);
+    if !(1..=MAX_MONTHS).contains(&months) {
+        return Err(CommandError::Validation(format!("Months must be between 1 and {}", MAX_MONTHS)));
+    }
+
+    let mut rng = match input.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let mut conn = db.pool.get()?;
+    if count_demo_trades(&conn)? > 0 {
+        return Err(CommandError::Validation(
+            "Demo data already exists, clear it before generating more".to_string(),
+        ));
+    }
+    let (initial_capital, r_percent): (f64, f64) = conn.query_row(
+        "SELECT initial_capital, current_r_percent FROM settings WHERE id = 1",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    let trades = demo_trades(&mut rng, count, months, Utc::now().timestamp(), initial_capital, r_percent);
+    insert_demo_trades(&mut conn, &trades)?;
+
+    Ok(DemoDataSummary {
+        trades_created: trades.len(),
+        start_date: trades.first().map_or(0, |trade| trade.trade_date),
+        end_date: trades.last().map_or(0, |trade| trade.trade_date),
+    })
+}
+
+/// Permanently delete every generated trade, trashed ones included. Returns how many.
+#[tauri::command]
+pub async fn clear_demo_data(db: State<'_, Database>) -> Result<usize, CommandError> {
+    let conn = db.pool.get()?;
+    conn.execute("DELETE FROM trades WHERE import_source = ?", [DEMO_IMPORT_SOURCE])
+        .map_err(CommandError::from)
+}
+
+fn count_demo_trades(conn: &rusqlite::Connection) -> Result<i64, rusqlite::Error> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM trades WHERE import_source = ?",
+        [DEMO_IMPORT_SOURCE],
+        |row| row.get(0),
+    )
+}
+
+/// `count` trades spread over the last `months` months, oldest first. Outcomes come in
+/// streaks, prices follow a random walk per pair, PnL is net of fees and the account
+/// compounds from `initial_capital`.
+fn demo_trades(
+    rng: &mut StdRng,
+    count: usize,
+    months: u32,
+    now: i64,
+    initial_capital: f64,
+    r_percent: f64,
+) -> Vec<Trade> {
+    let start = now - months as i64 * 30 * 86_400;
+    let mut trade_dates: Vec<i64> = (0..count).map(|_| rng.gen_range(start..now - 86_400)).collect();
+    trade_dates.sort_unstable();
+
+    let mut prices: Vec<f64> = DEMO_PAIRS.iter().map(|(_, price)| *price).collect();
+    let mut equity = initial_capital;
+    let mut last_win: Option<bool> = None;
+
+    let mut trades = Vec::with_capacity(count);
+    for (index, trade_date) in trade_dates.into_iter().enumerate() {
+        let pair_index = rng.gen_range(0..DEMO_PAIRS.len());
+        prices[pair_index] *= 1.0 + rng.gen_range(-0.05..0.05);
+        let entry_price = prices[pair_index];
+        let is_long = rng.gen_bool(0.6);
+        let direction = if is_long { 1.0 } else { -1.0 };
+
+        let one_r = equity * r_percent;
+        let sl_pct = rng.gen_range(0.004..0.03);
+        let sl_distance = entry_price * sl_pct;
+        let quantity = one_r / sl_distance;
+        let position_size = entry_price * quantity;
+        let leverage = (1.0 / sl_pct).floor().clamp(1.0, 20.0) as i32;
+        let planned_rr = rng.gen_range(1.5..4.0);
+        let take_profit = entry_price + direction * planned_rr * sl_distance;
+
+        // A win makes the next one likelier, so wins and losses come in streaks
+        let win_chance = match last_win {
+            Some(true) => 0.6,
+            Some(false) => 0.38,
+            None => 0.5,
+        };
+        let is_open = index + OPEN_TRADES >= count;
+        let r_multiple = if rng.gen_bool(0.07) {
+            0.0
+        } else if rng.gen_bool(win_chance) {
+            rng.gen_range(0.5..planned_rr)
+        } else {
+            -rng.gen_range(0.6..1.05)
+        };
+
+        let pre_emotions = if rng.gen_bool(0.7) { CALM_EMOTIONS } else { TENSE_EMOTIONS };
+        let mut trade = Trade {
+            id: Uuid::new_v4().to_string(),
+            pair: DEMO_PAIRS[pair_index].0.to_string(),
+            exchange: DEMO_EXCHANGES[rng.gen_range(0..DEMO_EXCHANGES.len())].to_string(),
+            analysis_date: trade_date - rng.gen_range(0..6 * 3600),
+            trade_date,
+            status: "OPEN".to_string(),
+            portfolio_value: equity,
+            r_percent,
+            min_rr: 1.5,
+            planned_pe: entry_price,
+            planned_sl: entry_price - direction * sl_distance,
+            leverage,
+            planned_tps: serde_json::json!([{ "price": take_profit, "percent": 100, "rr": planned_rr }]).to_string(),
+            planned_entries: Some(serde_json::json!([{ "price": entry_price, "percent": 100 }]).to_string()),
+            position_type: if is_long { "LONG" } else { "SHORT" }.to_string(),
+            one_r,
+            margin: position_size / leverage as f64,
+            position_size,
+            quantity,
+            planned_weighted_rr: planned_rr,
+            effective_pe: Some(entry_price),
+            effective_entries: Some(serde_json::json!([{ "price": entry_price, "percent": 100 }]).to_string()),
+            close_date: None,
+            exits: None,
+            effective_weighted_rr: None,
+            total_pnl: None,
+            pnl_in_r: None,
+            notes: DEMO_SETUPS[rng.gen_range(0..DEMO_SETUPS.len())].to_string(),
+            execution_portfolio: None,
+            execution_r_percent: None,
+            execution_margin: None,
+            execution_position_size: None,
+            execution_quantity: None,
+            execution_one_r: None,
+            execution_potential_profit: None,
+            import_fingerprint: None,
+            import_source: DEMO_IMPORT_SOURCE.to_string(),
+            account_id: None,
+            grade: None,
+            pre_emotion: Some(pick(rng, pre_emotions)),
+            post_emotion: None,
+            liquidated: false,
+            funding_pnl: None,
+            created_at: trade_date,
+            updated_at: trade_date,
+        };
+
+        if !is_open {
+            let exit_price = entry_price + direction * r_multiple * sl_distance;
+            let fees = (position_size + exit_price * quantity) * FEE_RATE;
+            let total_pnl = r_multiple * one_r - fees;
+            let close_date = (trade_date + rng.gen_range(15 * 60..72 * 3600)).min(now);
+
+            trade.status = if r_multiple == 0.0 {
+                "BE"
+            } else if total_pnl > 0.0 {
+                "WIN"
+            } else {
+                "LOSS"
+            }
+            .to_string();
+            trade.close_date = Some(close_date);
+            trade.exits = Some(serde_json::json!([{ "price": exit_price, "percent": 100 }]).to_string());
+            trade.effective_weighted_rr = Some(r_multiple);
+            trade.total_pnl = Some(total_pnl);
+            trade.pnl_in_r = Some(total_pnl / one_r);
+            trade.notes = format!("{}. Fees: {:.2} USDT", trade.notes, fees);
+            trade.grade = Some(demo_grade(rng, r_multiple));
+            trade.post_emotion = Some(pick(rng, if r_multiple >= 0.0 { CALM_EMOTIONS } else { TENSE_EMOTIONS }));
+            trade.updated_at = close_date;
+
+            equity += total_pnl;
+            if r_multiple != 0.0 {
+                last_win = Some(r_multiple > 0.0);
+            }
+        }
+
+        trades.push(trade);
+    }
+    trades
+}
+
+/// Execution grades lean on the outcome, with room for well-executed losses
+fn demo_grade(rng: &mut StdRng, r_multiple: f64) -> String {
+    let grades: &[&str] = if r_multiple > 0.0 { &["A", "A", "B", "B", "C"] } else { &["B", "C", "C", "D"] };
+    pick(rng, grades)
+}
+
+fn pick(rng: &mut StdRng, values: &[&str]) -> String {
+    values[rng.gen_range(0..values.len())].to_string()
+}
+
+fn insert_demo_trades(conn: &mut rusqlite::Connection, trades: &[Trade]) -> Result<(), rusqlite::Error> {
+    let tx = conn.transaction()?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO trades (
+                id, pair, exchange, analysis_date, trade_date, status,
+                portfolio_value, r_percent, min_rr,
+                planned_pe, planned_sl, leverage, planned_tps, planned_entries,
+                position_type, one_r, margin, position_size, quantity, planned_weighted_rr,
+                effective_pe, effective_entries, close_date, exits,
+                effective_weighted_rr, total_pnl, pnl_in_r, notes,
+                import_source, grade, pre_emotion, post_emotion, created_at, updated_at
+            ) VALUES (
+                ?, ?, ?, ?, ?, ?,
+                ?, ?, ?,
+                ?, ?, ?, ?, ?,
+                ?, ?, ?, ?, ?, ?,
+                ?, ?, ?, ?,
+                ?, ?, ?, ?,
+                ?, ?, ?, ?, ?, ?
+            )",
+        )?;
+        for trade in trades {
+            stmt.execute(rusqlite::params![
+                trade.id,
+                trade.pair,
+                trade.exchange,
+                trade.analysis_date,
+                trade.trade_date,
+                trade.status,
+                trade.portfolio_value,
+                trade.r_percent,
+                trade.min_rr,
+                trade.planned_pe,
+                trade.planned_sl,
+                trade.leverage,
+                trade.planned_tps,
+                trade.planned_entries,
+                trade.position_type,
+                trade.one_r,
+                trade.margin,
+                trade.position_size,
+                trade.quantity,
+                trade.planned_weighted_rr,
+                trade.effective_pe,
+                trade.effective_entries,
+                trade.close_date,
+                trade.exits,
+                trade.effective_weighted_rr,
+                trade.total_pnl,
+                trade.pnl_in_r,
+                trade.notes,
+                trade.import_source,
+                trade.grade,
+                trade.pre_emotion,
+                trade.post_emotion,
+                trade.created_at,
+                trade.updated_at,
+            ])?;
+        }
+    }
+    tx.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::{insert_trade, test_conn, trade};
+
+    #[test]
+    fn test_demo_trades_are_realistic_and_removable() {
+        let mut conn = test_conn();
+
+        let now = 1_790_000_000;
+        let trades = demo_trades(&mut StdRng::seed_from_u64(7), 300, 6, now, 10_000.0, 0.01);
+        assert_eq!(trades.len(), 300);
+        assert!(trades.windows(2).all(|pair| pair[0].trade_date <= pair[1].trade_date));
+        assert!(trades.iter().all(|trade| trade.trade_date >= now - 6 * 30 * 86_400 && trade.trade_date < now));
+        assert_eq!(trades.iter().filter(|trade| trade.status == "OPEN").count(), OPEN_TRADES);
+
+        let closed: Vec<&Trade> = trades.iter().filter(|trade| trade.status != "OPEN").collect();
+        let wins = closed.iter().filter(|trade| trade.status == "WIN").count();
+        assert!(wins > closed.len() / 4 && wins < closed.len() * 3 / 4);
+        // Fees come off every closed trade
+        assert!(closed
+            .iter()
+            .all(|trade| trade.total_pnl.unwrap() < trade.effective_weighted_rr.unwrap() * trade.one_r));
+        let pairs: std::collections::HashSet<&str> = trades.iter().map(|trade| trade.pair.as_str()).collect();
+        assert!(pairs.len() > 3);
+
+        // Same seed, same data
+        let again = demo_trades(&mut StdRng::seed_from_u64(7), 300, 6, now, 10_000.0, 0.01);
+        assert_eq!(again[42].total_pnl, trades[42].total_pnl);
+
+        insert_trade(&conn, &trade("REAL"));
+        insert_demo_trades(&mut conn, &trades).unwrap();
+        assert_eq!(count_demo_trades(&conn).unwrap(), 300);
+
+        conn.execute("DELETE FROM trades WHERE import_source = ?", [DEMO_IMPORT_SOURCE]).unwrap();
+        assert_eq!(count_demo_trades(&conn).unwrap(), 0);
+        let remaining: i64 = conn.query_row("SELECT COUNT(*) FROM trades", [], |row| row.get(0)).unwrap();
+        assert_eq!(remaining, 1);
+    }
+}
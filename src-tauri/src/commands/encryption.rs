@@ -0,0 +1,43 @@
+use tauri::{AppHandle, Manager};
+use crate::db::encryption;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use crate::commands::error::CommandError;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseEncryptionStatus {
+    /// This build links SQLCipher
+    pub supported: bool,
+    /// The open database is encrypted
+    pub encrypted: bool,
+    /// Encryption was requested and runs at the next restart
+    pub pending: bool,
+}
+
+fn database_path(app_handle: &AppHandle) -> Result<PathBuf, CommandError> {
+    Ok(app_handle.path().app_data_dir()?.join("trading_journal.db"))
+}
+
+#[tauri::command]
+pub async fn get_database_encryption_status(
+    app_handle: AppHandle,
+) -> Result<DatabaseEncryptionStatus, CommandError> {
+    let db_path = database_path(&app_handle)?;
+    Ok(DatabaseEncryptionStatus {
+        supported: encryption::is_supported(),
+        encrypted: encryption::is_active(),
+        pending: encryption::is_pending(&db_path),
+    })
+}
+
+/// Encrypt the database with SQLCipher at the next restart, using `passphrase` or a random
+/// key when none is given. Existing backups in the backups folder stay unencrypted.
+#[tauri::command]
+pub async fn enable_database_encryption(
+    app_handle: AppHandle,
+    passphrase: Option<String>,
+) -> Result<DatabaseEncryptionStatus, CommandError> {
+    let db_path = database_path(&app_handle)?;
+    encryption::schedule_encryption(&db_path, passphrase)?;
+    get_database_encryption_status(app_handle).await
+}
@@ -0,0 +1,124 @@
+use serde::Serialize;
+use thiserror::Error;
+use crate::api::error::ApiError;
+
+/// Error every command returns. Serialized as `{ "kind": "NotFound", "message": "..." }`
+/// so the frontend can react to the kind instead of matching on the message.
+#[derive(Error, Debug, Serialize)]
+#[serde(tag = "kind", content = "message")]
+pub enum CommandError {
+    /// The record asked for doesn't exist
+    #[error("{0}")]
+    NotFound(String),
+
+    /// The input was rejected
+    #[error("{0}")]
+    Validation(String),
+
+    /// The exchange rate limited the request, or password attempts are throttled
+    #[error("{0}")]
+    RateLimited(String),
+
+    /// The exchange rejected the API key, or the keychain or Touch ID refused access
+    #[error("{0}")]
+    Auth(String),
+
+    /// The app is locked until the master password is entered
+    #[error("{0}")]
+    Locked(String),
+
+    #[error("{0}")]
+    Database(String),
+
+    /// The exchange could not be reached
+    #[error("{0}")]
+    Network(String),
+
+    /// The exchange answered with an error or a response that couldn't be read
+    #[error("{0}")]
+    Exchange(String),
+
+    /// The user cancelled the operation
+    #[error("{0}")]
+    Cancelled(String),
+
+    /// Anything else
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl From<ApiError> for CommandError {
+    fn from(err: ApiError) -> Self {
+        let message = err.to_string();
+        match err {
+            ApiError::HttpError(e) => match e.status().map(|status| status.as_u16()) {
+                Some(429) => CommandError::RateLimited(message),
+                Some(401 | 403) => CommandError::Auth(message),
+                Some(_) => CommandError::Exchange(message),
+                None => CommandError::Network(message),
+            },
+            ApiError::AuthenticationError(_)
+            | ApiError::InvalidCredentials
+            | ApiError::KeychainError(_)
+            | ApiError::BiometricError(_) => CommandError::Auth(message),
+            ApiError::RateLimitError(_) => CommandError::RateLimited(message),
+            ApiError::ParseError(_) | ApiError::ExchangeError { .. } => CommandError::Exchange(message),
+            ApiError::NetworkError(_) | ApiError::TimeoutError(_) | ApiError::ProxyError(_) => {
+                CommandError::Network(message)
+            }
+            ApiError::DatabaseError(_) => CommandError::Database(message),
+            ApiError::Cancelled => CommandError::Cancelled(message),
+            ApiError::EncryptionError(_) | ApiError::BlockedEndpoint(_) | ApiError::Unknown(_) => {
+                CommandError::Internal(message)
+            }
+        }
+    }
+}
+
+impl From<rusqlite::Error> for CommandError {
+    fn from(err: rusqlite::Error) -> Self {
+        match err {
+            rusqlite::Error::QueryReturnedNoRows => CommandError::NotFound("Record not found".to_string()),
+            err => CommandError::Database(err.to_string()),
+        }
+    }
+}
+
+impl From<r2d2::Error> for CommandError {
+    fn from(err: r2d2::Error) -> Self {
+        CommandError::Database(err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for CommandError {
+    fn from(err: serde_json::Error) -> Self {
+        CommandError::Internal(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for CommandError {
+    fn from(err: std::io::Error) -> Self {
+        CommandError::Internal(err.to_string())
+    }
+}
+
+impl From<tauri::Error> for CommandError {
+    fn from(err: tauri::Error) -> Self {
+        CommandError::Internal(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialized_with_kind_and_message() {
+        let json = serde_json::to_value(CommandError::from(ApiError::RateLimitError("slow down".to_string()))).unwrap();
+        assert_eq!(json, serde_json::json!({ "kind": "RateLimited", "message": "Rate limit exceeded: slow down" }));
+
+        let json = serde_json::to_value(CommandError::from(rusqlite::Error::QueryReturnedNoRows)).unwrap();
+        assert_eq!(json["kind"], "NotFound");
+        assert_eq!(CommandError::from(ApiError::Cancelled).to_string(), "Sync cancelled");
+    }
+}
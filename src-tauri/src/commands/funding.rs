@@ -0,0 +1,159 @@
+use tauri::State;
+use crate::api::RawFundingPayment;
+use crate::db::Database;
+use crate::commands::error::CommandError;
+use crate::models::FundingPayment;
+use chrono::Utc;
+
+/// Symbols are written differently per source ("BTCUSDT", "BTC/USDT", "BTC-USDT")
+const NORMALIZED_TRADE_PAIR: &str = "REPLACE(REPLACE(UPPER(t.pair), '/', ''), '-', '')";
+const NORMALIZED_FUNDING_SYMBOL: &str =
+    "REPLACE(REPLACE(UPPER(funding_payments.symbol), '/', ''), '-', '')";
+
+fn map_row_to_funding_payment(row: &rusqlite::Row) -> rusqlite::Result<FundingPayment> {
+    Ok(FundingPayment {
+        id: row.get("id")?,
+        credential_id: row.get("credential_id")?,
+        exchange: row.get("exchange")?,
+        symbol: row.get("symbol")?,
+        amount: row.get("amount")?,
+        currency: row.get("currency")?,
+        funding_time: row.get("funding_time")?,
+        trade_id: row.get("trade_id")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+/// Funding payments, newest first; only those linked to `trade_id` when given
+#[tauri::command]
+pub async fn get_funding_payments(
+    db: State<'_, Database>,
+    trade_id: Option<String>,
+) -> Result<Vec<FundingPayment>, CommandError> {
+    let conn = db.pool.get()?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM funding_payments
+             WHERE ?1 IS NULL OR trade_id = ?1
+             ORDER BY funding_time DESC",
+        )?;
+
+    let payments = stmt
+        .query_map([&trade_id], map_row_to_funding_payment)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(payments)
+}
+
+/// Store payments from an exchange sync, skipping ones already stored. Returns how many
+/// were new.
+pub(crate) fn store_funding_payments(
+    conn: &rusqlite::Connection,
+    credential_id: &str,
+    exchange: &str,
+    payments: &[RawFundingPayment],
+) -> rusqlite::Result<usize> {
+    let now = Utc::now().timestamp();
+    let mut stored = 0;
+
+    for payment in payments {
+        let id = format!("FUND-{}-{}", Utc::now().timestamp_millis(), uuid::Uuid::new_v4());
+        stored += conn.execute(
+            "INSERT OR IGNORE INTO funding_payments
+                (id, credential_id, exchange, exchange_bill_id, symbol, amount, currency, funding_time, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                id,
+                credential_id,
+                exchange,
+                payment.exchange_bill_id,
+                payment.symbol,
+                payment.amount,
+                payment.currency,
+                payment.timestamp / 1000, // Convert ms to seconds
+                now,
+            ],
+        )?;
+    }
+
+    Ok(stored)
+}
+
+/// Attach unlinked payments to the trade that was open on the same exchange and symbol
+/// when the funding settled. Returns how many were linked.
+pub(crate) fn link_funding_payments(conn: &rusqlite::Connection) -> rusqlite::Result<usize> {
+    let open_trade = format!(
+        "SELECT t.id FROM trades t
+         WHERE t.deleted_at IS NULL
+           AND LOWER(t.exchange) = LOWER(funding_payments.exchange)
+           AND {} = {}
+           AND t.trade_date <= funding_payments.funding_time
+           AND (t.close_date IS NULL OR t.close_date >= funding_payments.funding_time)
+         ORDER BY t.trade_date DESC LIMIT 1",
+        NORMALIZED_TRADE_PAIR, NORMALIZED_FUNDING_SYMBOL
+    );
+
+    conn.execute(
+        &format!(
+            "UPDATE funding_payments SET trade_id = ({0})
+             WHERE trade_id IS NULL AND ({0}) IS NOT NULL",
+            open_trade
+        ),
+        [],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::{insert_trade, test_conn, trade};
+    use crate::models::Trade;
+
+    #[test]
+    fn test_store_and_link_funding_payments() {
+        let conn = test_conn();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+
+        conn.execute(
+            "INSERT INTO api_credentials (id, exchange, label, api_key, api_secret, created_at, updated_at)
+             VALUES ('CRED', 'bitget', 'Main', 'KEYCHAIN:CRED', 'KEYCHAIN:CRED', 0, 0)",
+            [],
+        )
+        .unwrap();
+
+        // Held from t=1000 to t=5000
+        insert_trade(&conn, &Trade {
+            analysis_date: 1000,
+            trade_date: 1000,
+            close_date: Some(5000),
+            status: "WIN".to_string(),
+            total_pnl: Some(50.0),
+            ..trade("T1")
+        });
+
+        let payment = |id: &str, amount: f64, time_ms: i64| RawFundingPayment {
+            exchange_bill_id: id.to_string(),
+            symbol: "BTCUSDT".to_string(),
+            amount,
+            currency: "USDT".to_string(),
+            timestamp: time_ms,
+        };
+        let payments = vec![
+            payment("b1", -1.5, 2_000_000),
+            payment("b2", 0.5, 3_000_000),
+            payment("b3", -2.0, 9_000_000), // After the trade closed
+        ];
+
+        assert_eq!(store_funding_payments(&conn, "CRED", "bitget", &payments).unwrap(), 3);
+        // A repeated sync does not duplicate payments
+        assert_eq!(store_funding_payments(&conn, "CRED", "bitget", &payments).unwrap(), 0);
+
+        assert_eq!(link_funding_payments(&conn).unwrap(), 2);
+
+        let funding: f64 = conn
+            .query_row("SELECT SUM(amount) FROM funding_payments WHERE trade_id = 'T1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(funding, -1.0);
+    }
+}
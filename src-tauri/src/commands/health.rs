@@ -0,0 +1,230 @@
+use tauri::{AppHandle, State};
+use crate::commands::backups::backup_dir;
+use crate::db::migration_runner::MigrationRunner;
+use crate::db::Database;
+use crate::commands::error::CommandError;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Warn when the newest backup is older than this
+const STALE_BACKUP_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForeignKeyViolation {
+    pub table: String,
+    pub rowid: Option<i64>,
+    pub parent: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceCount {
+    pub import_source: String,
+    pub active: i64,
+    pub deleted: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseHealth {
+    pub integrity_ok: bool,
+    /// Output of PRAGMA integrity_check ("ok" when healthy)
+    pub integrity_messages: Vec<String>,
+    pub foreign_key_violations: Vec<ForeignKeyViolation>,
+    pub schema_version: Option<u32>,
+    pub size_bytes: i64,
+    /// Unused pages that a VACUUM would reclaim
+    pub free_bytes: i64,
+    pub trades_by_source: Vec<SourceCount>,
+    /// Fingerprints only held by trades in the trash; re-importing those trades is skipped
+    /// as duplicate until the trash is emptied
+    pub orphan_fingerprints: i64,
+    /// Fingerprints shared by more than one trade
+    pub duplicate_fingerprints: i64,
+    /// Modification time of the newest file in the backups folder (unix seconds)
+    pub last_backup_at: Option<i64>,
+    pub last_backup_age_days: Option<i64>,
+    /// Human-readable summary of everything that needs attention
+    pub warnings: Vec<String>,
+}
+
+/// Check the journal database for corruption, broken references and stale backups
+#[tauri::command]
+pub async fn check_database_health(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+) -> Result<DatabaseHealth, CommandError> {
+    let dir = backup_dir(&app_handle)?;
+    let conn = db.pool.get()?;
+    database_health(&conn, &dir).map_err(CommandError::from)
+}
+
+fn database_health(conn: &rusqlite::Connection, backup_dir: &Path) -> rusqlite::Result<DatabaseHealth> {
+    let integrity_messages = conn
+        .prepare("PRAGMA integrity_check")?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    let integrity_ok = integrity_messages.len() == 1 && integrity_messages[0] == "ok";
+
+    let foreign_key_violations = conn
+        .prepare("PRAGMA foreign_key_check")?
+        .query_map([], |row| {
+            Ok(ForeignKeyViolation {
+                table: row.get(0)?,
+                rowid: row.get(1)?,
+                parent: row.get(2)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let schema_version = MigrationRunner::new().get_current_version(conn)?;
+
+    let page_size: i64 = conn.pragma_query_value(None, "page_size", |row| row.get(0))?;
+    let page_count: i64 = conn.pragma_query_value(None, "page_count", |row| row.get(0))?;
+    let freelist_count: i64 = conn.pragma_query_value(None, "freelist_count", |row| row.get(0))?;
+
+    let trades_by_source = conn
+        .prepare(
+            "SELECT COALESCE(import_source, 'USER_CREATED') AS source,
+                    SUM(CASE WHEN deleted_at IS NULL THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN deleted_at IS NOT NULL THEN 1 ELSE 0 END)
+             FROM trades
+             GROUP BY source
+             ORDER BY source",
+        )?
+        .query_map([], |row| {
+            Ok(SourceCount {
+                import_source: row.get(0)?,
+                active: row.get(1)?,
+                deleted: row.get(2)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let orphan_fingerprints: i64 = conn.query_row(
+        "SELECT COUNT(DISTINCT import_fingerprint) FROM trades t
+         WHERE import_fingerprint IS NOT NULL AND deleted_at IS NOT NULL
+           AND NOT EXISTS (
+               SELECT 1 FROM trades a
+               WHERE a.import_fingerprint = t.import_fingerprint AND a.deleted_at IS NULL
+           )",
+        [],
+        |row| row.get(0),
+    )?;
+
+    let duplicate_fingerprints: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM (
+             SELECT import_fingerprint FROM trades
+             WHERE import_fingerprint IS NOT NULL
+             GROUP BY import_fingerprint
+             HAVING COUNT(*) > 1
+         )",
+        [],
+        |row| row.get(0),
+    )?;
+
+    let last_backup_at = newest_backup_time(backup_dir);
+    let last_backup_age_days =
+        last_backup_at.map(|t| (chrono::Utc::now().timestamp() - t).max(0) / 86_400);
+
+    let mut warnings = Vec::new();
+    if !integrity_ok {
+        warnings.push(format!(
+            "Integrity check failed ({} problem(s)), restore a backup before making changes",
+            integrity_messages.len()
+        ));
+    }
+    if !foreign_key_violations.is_empty() {
+        warnings.push(format!(
+            "{} row(s) reference missing records",
+            foreign_key_violations.len()
+        ));
+    }
+    if orphan_fingerprints > 0 {
+        warnings.push(format!(
+            "{} trashed import(s) will be skipped on re-import until the trash is emptied",
+            orphan_fingerprints
+        ));
+    }
+    if duplicate_fingerprints > 0 {
+        warnings.push(format!(
+            "{} imported trade(s) exist more than once",
+            duplicate_fingerprints
+        ));
+    }
+    match last_backup_age_days {
+        None => warnings.push("No backups found".to_string()),
+        Some(days) if days > STALE_BACKUP_DAYS => {
+            warnings.push(format!("Last backup is {} days old", days))
+        }
+        Some(_) => {}
+    }
+
+    Ok(DatabaseHealth {
+        integrity_ok,
+        integrity_messages,
+        foreign_key_violations,
+        schema_version,
+        size_bytes: page_size * page_count,
+        free_bytes: page_size * freelist_count,
+        trades_by_source,
+        orphan_fingerprints,
+        duplicate_fingerprints,
+        last_backup_at,
+        last_backup_age_days,
+        warnings,
+    })
+}
+
+fn newest_backup_time(backup_dir: &Path) -> Option<i64> {
+    std::fs::read_dir(backup_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().and_then(|s| s.to_str()) == Some("db"))
+        .filter_map(|entry| entry.metadata().ok()?.modified().ok())
+        .filter_map(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::{insert_trashed_trade, test_conn, trade};
+    use crate::models::Trade;
+
+    #[test]
+    fn test_database_health_report() {
+        let conn = test_conn();
+
+        for (id, fingerprint, deleted_at) in [
+            ("T1", Some("fp-1"), None),
+            ("T2", Some("fp-2"), Some(100)),
+            ("T3", Some("fp-3"), None),
+            ("T4", Some("fp-3"), None),
+            ("T5", None, None),
+        ] {
+            let imported = Trade {
+                import_fingerprint: fingerprint.map(str::to_string),
+                import_source: if fingerprint.is_some() { "CSV_IMPORT" } else { "USER_CREATED" }.to_string(),
+                ..trade(id)
+            };
+            insert_trashed_trade(&conn, &imported, deleted_at);
+        }
+
+        let empty_dir = tempfile::tempdir().unwrap();
+        let health = database_health(&conn, empty_dir.path()).unwrap();
+
+        assert!(health.integrity_ok);
+        assert!(health.foreign_key_violations.is_empty());
+        assert_eq!(health.orphan_fingerprints, 1);
+        assert_eq!(health.duplicate_fingerprints, 1);
+        assert_eq!(health.last_backup_at, None);
+        assert!(health.warnings.iter().any(|w| w == "No backups found"));
+
+        let csv = health
+            .trades_by_source
+            .iter()
+            .find(|s| s.import_source == "CSV_IMPORT")
+            .unwrap();
+        assert_eq!((csv.active, csv.deleted), (3, 1));
+    }
+}
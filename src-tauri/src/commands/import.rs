@@ -1,9 +1,18 @@
 use tauri::State;
 use crate::db::Database;
-use crate::models::{Trade, Settings};
+use crate::commands::error::CommandError;
+use crate::commands::trade_metrics::{breakeven_threshold, closed_status, recalculate_trade};
+use crate::api::credentials::{api_key_backend, retrieve_api_key, StorageBackend};
+use crate::api::secure_storage::{decrypt_with_password, encrypt_with_password, PasswordEncrypted};
+use crate::models::{
+    Account, ApiCredential, ApiCredentialSafe, ApiSyncHistory, CapitalTransaction, JournalEntry,
+    Settings, Tag, Trade,
+};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::LazyLock;
+use regex::Regex;
 use calamine::{open_workbook, Data, Reader, Xlsx};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -18,11 +27,17 @@ pub struct ImportPreview {
     pub closing_time: String,
     pub total_fees: f64,
     pub fingerprint: String,
+    /// Position still open at the end of the export (only with `include_open`)
+    #[serde(default)]
+    pub is_open: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ImportResult {
     pub imported: usize,
+    /// Existing trades refreshed in merge mode (see `merge_existing`)
+    #[serde(default)]
+    pub updated: usize,
     pub duplicates: usize,
     pub errors: Vec<String>,
 }
@@ -33,9 +48,11 @@ pub async fn preview_bitget_import(
     csv_content: String,
     _portfolio: f64,
     _r_percent: f64,
-) -> Result<Vec<ImportPreview>, String> {
+    csv_format: Option<CsvFormat>,
+) -> Result<Vec<ImportPreview>, CommandError> {
     let mut previews = Vec::new();
     let lines: Vec<&str> = csv_content.lines().collect();
+    let dialect = detect_csv_dialect(&csv_content, csv_format);
 
     // Skip header
     for (line_num, line) in lines.iter().enumerate().skip(1) {
@@ -43,7 +60,7 @@ pub async fn preview_bitget_import(
             continue;
         }
 
-        match parse_bitget_line(line) {
+        match parse_bitget_line(line, dialect) {
             Ok(trade_data) => {
                 let fingerprint = generate_fingerprint(&trade_data);
                 previews.push(ImportPreview {
@@ -57,6 +74,7 @@ pub async fn preview_bitget_import(
                     closing_time: trade_data.closing_time,
                     total_fees: trade_data.total_fees,
                     fingerprint,
+                    is_open: false,
                 });
             }
             Err(e) => {
@@ -75,35 +93,44 @@ pub async fn import_bitget_csv(
     csv_content: String,
     portfolio: f64,
     r_percent: f64,
-) -> Result<ImportResult, String> {
+    merge_existing: Option<bool>,
+    csv_format: Option<CsvFormat>,
+) -> Result<ImportResult, CommandError> {
+    let conn = db.pool.get()?;
+    import_bitget_content(&conn, &csv_content, portfolio, r_percent, merge_existing.unwrap_or(false), csv_format)
+}
+
+fn import_bitget_content(
+    conn: &rusqlite::Connection,
+    csv_content: &str,
+    portfolio: f64,
+    r_percent: f64,
+    merge_existing: bool,
+    csv_format: Option<CsvFormat>,
+) -> Result<ImportResult, CommandError> {
     let lines: Vec<&str> = csv_content.lines().collect();
     let mut imported = 0;
+    let mut updated = 0;
     let mut duplicates = 0;
     let mut errors = Vec::new();
+    let dialect = detect_csv_dialect(csv_content, csv_format);
+    let breakeven_threshold = breakeven_threshold(conn)?;
 
     {
-        let conn = db.conn.lock().map_err(|e| e.to_string())?;
-
         // Skip header
         for (line_num, line) in lines.iter().enumerate().skip(1) {
             if line.trim().is_empty() {
                 continue;
             }
 
-            match parse_bitget_line(line) {
+            match parse_bitget_line(line, dialect) {
                 Ok(trade_data) => {
                     let fingerprint = generate_fingerprint(&trade_data);
 
-                    // Check for duplicate
-                    let exists: bool = conn
-                        .query_row(
-                            "SELECT EXISTS(SELECT 1 FROM trades WHERE import_fingerprint = ?)",
-                            [&fingerprint],
-                            |row| row.get(0),
-                        )
-                        .unwrap_or(false);
+                    // Check for duplicate (or the trade to refresh in merge mode)
+                    let existing = find_imported_trade(conn, &fingerprint, merge_existing);
 
-                    if exists {
+                    if existing == Some(ExistingImport::Duplicate) {
                         duplicates += 1;
                         continue;
                     }
@@ -113,7 +140,7 @@ pub async fn import_bitget_csv(
                         "TRADE-{}-{}",
                         Utc::now().timestamp_millis(),
                         uuid::Uuid::new_v4().to_string().split('-').next()
-                            .ok_or("Failed to generate trade ID from UUID")?
+                            .ok_or_else(|| CommandError::Internal("Failed to generate trade ID from UUID".to_string()))?
                     );
                     let now = Utc::now().timestamp();
 
@@ -131,18 +158,11 @@ pub async fn import_bitget_csv(
 
                     // Calculate leverage (capped at 125x, standard exchange maximum)
                     let sl_distance_pct = (trade_data.entry_price - estimated_sl).abs() / trade_data.entry_price;
-                    let max_leverage = (1.0 / sl_distance_pct).floor().max(1.0).min(125.0) as i64;
+                    let max_leverage = (1.0 / sl_distance_pct).floor().clamp(1.0, 125.0) as i64;
                     let leverage = max_leverage.min(125);
                     let margin = position_size / leverage as f64;
 
-                    // Determine status (using $0.50 threshold to match UI logic)
-                    let status = if trade_data.realized_pnl > 0.5 {
-                        "WIN"
-                    } else if trade_data.realized_pnl < -0.5 {
-                        "LOSS"
-                    } else {
-                        "BE"
-                    };
+                    let status = closed_status(trade_data.realized_pnl, breakeven_threshold);
 
                     let planned_tps = serde_json::json!([{
                         "price": trade_data.exit_price,
@@ -171,7 +191,27 @@ pub async fn import_bitget_csv(
                         .map(|dt| dt.timestamp())
                         .unwrap_or(now);
 
-                    conn.execute(
+                    if let Some(ExistingImport::Refresh(existing_id)) = existing {
+                        let effective_entries = serde_json::json!([{"price": trade_data.entry_price, "percent": 100}]).to_string();
+                        match merge_imported_trade(conn, &existing_id, &MergedExecution {
+                            fingerprint: &fingerprint,
+                            status,
+                            close_date: Some(closing_timestamp),
+                            quantity: trade_data.quantity,
+                            effective_pe: trade_data.entry_price,
+                            effective_entries: Some(&effective_entries),
+                            exits: &exits,
+                            total_pnl: Some(trade_data.realized_pnl),
+                            notes: &notes,
+                        }, breakeven_threshold) {
+                            Ok(true) => updated += 1,
+                            Ok(false) => duplicates += 1,
+                            Err(e) => errors.push(format!("Failed to merge {}: {}", trade_data.pair, e)),
+                        }
+                        continue;
+                    }
+
+                    match conn.execute(
                         "INSERT INTO trades (
                             id, pair, exchange, analysis_date, trade_date, close_date, status,
                             portfolio_value, r_percent, min_rr,
@@ -212,10 +252,10 @@ pub async fn import_bitget_csv(
                             now,
                             now,
                         ],
-                    )
-                    .map_err(|e| e.to_string())?;
-
-                    imported += 1;
+                    ) {
+                        Ok(_) => imported += 1,
+                        Err(e) => errors.push(format!("Failed to import {}: {}", trade_data.pair, e)),
+                    }
                 }
                 Err(e) => {
                     errors.push(format!("Line {}: {}", line_num + 1, e));
@@ -226,24 +266,155 @@ pub async fn import_bitget_csv(
 
     Ok(ImportResult {
         imported,
+        updated,
         duplicates,
         errors,
     })
 }
 
-/// Delete all BitGet imported trades (both CSV and API imports)
+/// Delete all BitGet imported trades (futures CSV, spot CSV and API imports)
 #[tauri::command]
-pub async fn delete_bitget_trades(db: State<'_, Database>) -> Result<usize, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+pub async fn delete_bitget_trades(db: State<'_, Database>) -> Result<usize, CommandError> {
+    let conn = db.pool.get()?;
     let count = conn
         .execute(
-            "DELETE FROM trades WHERE import_fingerprint LIKE 'csv|bitget|%' OR import_fingerprint LIKE 'api|bitget|%'",
+            "DELETE FROM trades WHERE import_fingerprint LIKE 'csv|bitget|%' OR import_fingerprint LIKE 'csv|bitget-spot|%' OR import_fingerprint LIKE 'api|bitget|%'",
             [],
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
     Ok(count)
 }
 
+// ─── CSV dialect ──────────────────────────────────────────────────────────────
+// Exchange exports follow the user's locale: besides plain "a,b,1.5" we see
+// "a;b;1,5" (EU locales, decimal comma) and tab-separated files. The dialect is
+// sniffed once per file and every CSV parser splits lines through it. Callers
+// can force either part with a `CsvFormat` when a file is sniffed wrong.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CsvDialect {
+    delimiter: u8,
+    /// "1.234,56" style numbers: '.' groups thousands, ',' marks decimals
+    decimal_comma: bool,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        CsvDialect { delimiter: b',', decimal_comma: false }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CsvDelimiter {
+    #[serde(rename = ",")]
+    Comma,
+    #[serde(rename = ";")]
+    Semicolon,
+    #[serde(rename = "\t")]
+    Tab,
+}
+
+impl CsvDelimiter {
+    fn byte(self) -> u8 {
+        match self {
+            CsvDelimiter::Comma => b',',
+            CsvDelimiter::Semicolon => b';',
+            CsvDelimiter::Tab => b'\t',
+        }
+    }
+}
+
+/// User override of the sniffed dialect; unset parts are still detected
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CsvFormat {
+    pub delimiter: Option<CsvDelimiter>,
+    pub decimal_comma: Option<bool>,
+}
+
+/// Sniff delimiter from the header and decimal mark from the first data rows
+fn detect_csv_dialect(content: &str, format: Option<CsvFormat>) -> CsvDialect {
+    let format = format.unwrap_or_default();
+    let mut lines = content
+        .lines()
+        .map(|l| l.trim_start_matches('\u{feff}'))
+        .filter(|l| !l.trim().is_empty());
+
+    let Some(header) = lines.next() else {
+        return CsvDialect {
+            delimiter: format.delimiter.map_or(b',', CsvDelimiter::byte),
+            decimal_comma: format.decimal_comma.unwrap_or(false),
+        };
+    };
+
+    // max_by_key keeps the last maximum, so ',' wins ties (including no delimiter at all)
+    let delimiter = format.delimiter.map(CsvDelimiter::byte).unwrap_or_else(|| {
+        [b'\t', b';', b',']
+            .into_iter()
+            .max_by_key(|d| header.bytes().filter(|b| b == d).count())
+            .unwrap_or(b',')
+    });
+
+    // A comma can only be a decimal mark when it is not the delimiter. Each row votes for
+    // the mark its numbers use, so one odd field (a "1,5" note, say) can't flip the file.
+    let decimal_comma = format.decimal_comma.unwrap_or_else(|| {
+        if delimiter == b',' {
+            return false;
+        }
+        let (mut comma_rows, mut dot_rows) = (0, 0);
+        for line in lines.take(10) {
+            let fields = split_csv_line(line, CsvDialect { delimiter, decimal_comma: false });
+            if fields.iter().any(|f| is_decimal_comma_number(f)) {
+                comma_rows += 1;
+            } else if fields.iter().any(|f| DECIMAL_DOT_NUMBER.is_match(f)) {
+                dot_rows += 1;
+            }
+        }
+        comma_rows > dot_rows
+    });
+
+    CsvDialect { delimiter, decimal_comma }
+}
+
+/// "1,5", "-90,354USDT", "1.645,2 INJ" (but not "1,234.5" or "1,5,2")
+static DECIMAL_COMMA_NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^-?(\d{1,3}(\.\d{3})+|\d+),\d+ ?[A-Za-z%]*$").expect("valid regex")
+});
+
+/// "1.5", "-90.354USDT"
+static DECIMAL_DOT_NUMBER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^-?\d+\.\d+ ?[A-Za-z%]*$").expect("valid regex"));
+
+fn is_decimal_comma_number(field: &str) -> bool {
+    DECIMAL_COMMA_NUMBER.is_match(field)
+}
+
+/// Split one CSV line (quotes respected, fields trimmed) and normalize
+/// decimal-comma numbers to '.' so the field parsers stay locale-agnostic
+fn split_csv_line(line: &str, dialect: CsvDialect) -> Vec<String> {
+    let clean = line.trim_start_matches('\u{feff}');
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(dialect.delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(clean.as_bytes());
+
+    let mut record = csv::StringRecord::new();
+    if !reader.read_record(&mut record).unwrap_or(false) {
+        return Vec::new();
+    }
+
+    record
+        .iter()
+        .map(|field| {
+            let field = field.trim();
+            if dialect.decimal_comma && is_decimal_comma_number(field) {
+                field.replace('.', "").replace(',', ".")
+            } else {
+                field.to_string()
+            }
+        })
+        .collect()
+}
+
 // Helper structures and functions
 
 struct BitGetTradeData {
@@ -258,10 +429,9 @@ struct BitGetTradeData {
     total_fees: f64,
 }
 
-fn parse_bitget_line(line: &str) -> Result<BitGetTradeData, String> {
-    // Remove BOM if present
-    let clean_line = line.trim_start_matches('\u{feff}');
-    let fields: Vec<&str> = clean_line.split(',').map(|f| f.trim()).collect();
+fn parse_bitget_line(line: &str, dialect: CsvDialect) -> Result<BitGetTradeData, String> {
+    let owned = split_csv_line(line, dialect);
+    let fields: Vec<&str> = owned.iter().map(String::as_str).collect();
 
     if fields.len() < 12 {
         return Err(format!("Invalid CSV line: expected 12 fields, got {}", fields.len()));
@@ -333,6 +503,566 @@ fn generate_fingerprint(trade: &BitGetTradeData) -> String {
     )
 }
 
+// ─── Merge-on-reimport ────────────────────────────────────────────────────────
+// Fingerprints are "source|exchange|pair|side|open|close|qty|pnl". When an
+// exchange corrects a position or adds fills after the last export, the tail of
+// the fingerprint changes while the position itself is the same. In merge mode
+// we match on the identity prefix (up to the opening time) and refresh the
+// execution fields of the existing trade instead of inserting a second one.
+
+/// Identity prefix of an import fingerprint, e.g. "csv|bitget|btc/usdt|long|2026-01-01 10:00:00|"
+fn fingerprint_identity(fingerprint: &str) -> String {
+    let parts: Vec<&str> = fingerprint.splitn(6, '|').collect();
+    if parts.len() < 6 {
+        return fingerprint.to_string();
+    }
+    format!("{}|", parts[..5].join("|"))
+}
+
+/// How a parsed position relates to trades already in the journal
+#[derive(Debug, PartialEq)]
+enum ExistingImport {
+    /// Already imported and merge mode is off — count as a duplicate
+    Duplicate,
+    /// Refresh this trade in place (merge mode, or an OPEN import that has since moved on)
+    Refresh(String),
+}
+
+/// Find the trade a freshly parsed position corresponds to.
+/// Exact fingerprint matches always count. Identity matches count in merge mode,
+/// and always for trades imported as OPEN so they are reconciled once they close.
+fn find_imported_trade(
+    conn: &rusqlite::Connection,
+    fingerprint: &str,
+    merge_existing: bool,
+) -> Option<ExistingImport> {
+    let exact: Option<String> = conn
+        .query_row(
+            "SELECT id FROM trades WHERE import_fingerprint = ? LIMIT 1",
+            [fingerprint],
+            |row| row.get(0),
+        )
+        .ok();
+
+    if let Some(id) = exact {
+        return Some(if merge_existing { ExistingImport::Refresh(id) } else { ExistingImport::Duplicate });
+    }
+
+    let identity = fingerprint_identity(fingerprint);
+    let (id, status): (String, String) = conn
+        .query_row(
+            // Prefix match written as a range so it can use idx_trades_import_fingerprint
+            "SELECT id, status FROM trades
+             WHERE import_fingerprint >= ?1 AND import_fingerprint < ?1 || char(1114111)
+             ORDER BY updated_at DESC LIMIT 1",
+            [&identity],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .ok()?;
+
+    if merge_existing || status == "OPEN" {
+        Some(ExistingImport::Refresh(id))
+    } else {
+        None
+    }
+}
+
+/// Execution fields refreshed on an existing trade during merge-on-reimport
+struct MergedExecution<'a> {
+    fingerprint: &'a str,
+    status: &'a str,
+    close_date: Option<i64>,
+    quantity: f64,
+    effective_pe: f64,
+    effective_entries: Option<&'a str>,
+    exits: &'a str,
+    total_pnl: Option<f64>,
+    notes: &'a str,
+}
+
+/// Refresh an existing imported trade and the values derived from it (pnl_in_r, weighted RR,
+/// margin, status). Returns false when nothing changed, so identical re-imports are still
+/// reported as duplicates.
+/// Notes are only replaced while they are still the auto-generated import note.
+fn merge_imported_trade(
+    conn: &rusqlite::Connection,
+    trade_id: &str,
+    exec: &MergedExecution,
+    breakeven_threshold: f64,
+) -> rusqlite::Result<bool> {
+    let changed = conn.execute(
+        "UPDATE trades SET
+            import_fingerprint = ?1, status = ?2, close_date = ?3, quantity = ?4,
+            effective_pe = ?5, effective_entries = COALESCE(?6, effective_entries),
+            exits = ?7, total_pnl = ?8,
+            notes = CASE WHEN notes LIKE 'Imported from %' THEN ?9 ELSE notes END,
+            updated_at = ?10
+         WHERE id = ?11
+           AND (import_fingerprint IS NOT ?1 OR status IS NOT ?2 OR close_date IS NOT ?3
+                OR quantity IS NOT ?4 OR effective_pe IS NOT ?5
+                OR effective_entries IS NOT COALESCE(?6, effective_entries)
+                OR exits IS NOT ?7 OR total_pnl IS NOT ?8
+                OR (notes LIKE 'Imported from %' AND notes IS NOT ?9))",
+        rusqlite::params![
+            exec.fingerprint,
+            exec.status,
+            exec.close_date,
+            exec.quantity,
+            exec.effective_pe,
+            exec.effective_entries,
+            exec.exits,
+            exec.total_pnl,
+            exec.notes,
+            Utc::now().timestamp(),
+            trade_id,
+        ],
+    )?;
+    if changed > 0 {
+        recalculate_trade(conn, trade_id, breakeven_threshold)?;
+    }
+    Ok(changed > 0)
+}
+
+// ─── BitGet Spot CSV Import ───────────────────────────────────────────────────
+// BitGet's spot order history has one row per order and no position concept.
+// Column order differs between export versions, so columns are located by
+// header name. Spot is long-only: buys open/add, sells reduce, and a position
+// closes once the sold quantity covers the bought quantity.
+
+/// Column indices of a BitGet spot export, resolved from its header row
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SpotColumns {
+    time: usize,
+    pair: usize,
+    side: usize,
+    price: usize,
+    quantity: usize,
+    fee: Option<usize>,
+    fee_currency: Option<usize>,
+    status: Option<usize>,
+}
+
+impl SpotColumns {
+    fn from_header(header: &[&str]) -> Option<Self> {
+        // Aliases in priority order (average fill price beats order price)
+        let find = |aliases: &[&str]| {
+            aliases.iter().find_map(|alias| {
+                header.iter().position(|h| h.trim_start_matches('\u{feff}').eq_ignore_ascii_case(alias))
+            })
+        };
+
+        Some(SpotColumns {
+            time: find(&["Order Time", "Time", "Date", "Created Time", "Transaction Time"])?,
+            pair: find(&["Trading pair", "Symbol", "Pair", "Coin pair"])?,
+            side: find(&["Direction", "Side"])?,
+            price: find(&["Average Price", "Avg. price", "Average", "Filled price", "Price"])?,
+            quantity: find(&["Filled amount", "Filled", "Executed", "Amount", "Quantity"])?,
+            fee: find(&["Fee", "Trading fee", "Fees"]),
+            fee_currency: find(&["Fee Coin", "Fee Currency", "Fee Asset"]),
+            status: find(&["Status", "Order status"]),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SpotOrder {
+    pair: String,
+    order_time: String,
+    is_buy: bool,
+    price: f64,
+    quantity: f64,
+    fee: f64,
+}
+
+struct OpenSpotPosition {
+    pair: String,
+    entry_qty: f64,
+    exit_qty: f64,
+    entry_price_sum: f64,
+    exit_price_sum: f64,
+    total_fees: f64,
+    opening_time: String,
+    closing_time: String,
+    entry_orders: Vec<(f64, f64)>,
+    exit_orders: Vec<(f64, f64)>,
+}
+
+struct SpotPositionData {
+    pair: String,
+    entry_price: f64,
+    exit_price: f64,
+    quantity: f64,
+    realized_pnl: f64,
+    total_fees: f64,
+    opening_time: String,
+    closing_time: String,
+    entries_json: String,
+    exits_json: String,
+}
+
+fn spot_symbol_to_pair(symbol: &str) -> String {
+    // "BTCUSDT" | "BTC_USDT" | "BTC-USDT" | "BTC/USDT" → "BTC/USDT"
+    let symbol = symbol.to_uppercase().replace(['_', '-'], "/");
+    if symbol.contains('/') {
+        symbol
+    } else {
+        asset_to_pair(&symbol)
+    }
+}
+
+fn normalize_spot_time(s: &str) -> String {
+    // Some exports use epoch milliseconds instead of "YYYY-MM-DD HH:MM:SS"
+    if let Ok(millis) = s.parse::<i64>()
+        && let Some(dt) = chrono::DateTime::from_timestamp_millis(millis)
+    {
+        return dt.format("%Y-%m-%d %H:%M:%S").to_string();
+    }
+    s.replace('/', "-")
+}
+
+/// "0.1", "0.1USDT", "0.001 BTC" → (amount, currency suffix)
+static FEE_WITH_CURRENCY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^-?(\d+\.?\d*(?:[eE]-?\d+)?) ?([A-Za-z]*)$").expect("valid regex"));
+
+/// Fee of a spot fill in the quote currency. Buys are usually charged in the base coin,
+/// which converts at the fill price; fees in any other coin (BGB discounts) can't be priced.
+fn spot_fee_in_quote(fee: &str, currency: &str, pair: &str, price: f64) -> Result<f64, String> {
+    if fee.is_empty() {
+        return Ok(0.0);
+    }
+    let caps = FEE_WITH_CURRENCY.captures(fee).ok_or_else(|| format!("Invalid fee: {}", fee))?;
+    let amount: f64 = caps[1].parse().map_err(|_| format!("Invalid fee: {}", fee))?;
+    let currency = if currency.is_empty() { &caps[2] } else { currency }.to_uppercase();
+
+    let (base, quote) = pair.split_once('/').unwrap_or((pair, ""));
+    if currency.is_empty() || currency == quote {
+        Ok(amount)
+    } else if currency == base {
+        Ok(amount * price)
+    } else {
+        Err(format!("Fee paid in {} can't be converted to {}", currency, quote))
+    }
+}
+
+/// Ok(None) for rows that are not fills (cancelled or unfilled orders)
+fn parse_spot_line(line: &str, columns: &SpotColumns, dialect: CsvDialect) -> Result<Option<SpotOrder>, String> {
+    let owned = split_csv_line(line, dialect);
+    let fields: Vec<&str> = owned.iter().map(String::as_str).collect();
+    let field = |idx: usize| fields.get(idx).copied().unwrap_or("");
+
+    if let Some(status) = columns.status {
+        let status = field(status).to_lowercase();
+        if status.contains("cancel") || status.contains("fail") {
+            return Ok(None);
+        }
+    }
+
+    let is_buy = match field(columns.side).to_lowercase().as_str() {
+        "buy" => true,
+        "sell" => false,
+        other => return Err(format!("Unknown direction: {}", other)),
+    };
+
+    let quantity = parse_numeric_value(field(columns.quantity))
+        .map_err(|_| format!("Invalid amount: {}", field(columns.quantity)))?;
+    let price = parse_numeric_value(field(columns.price))
+        .map_err(|_| format!("Invalid price: {}", field(columns.price)))?;
+    if quantity <= 0.0 || price <= 0.0 {
+        return Ok(None);
+    }
+
+    let pair = spot_symbol_to_pair(field(columns.pair));
+    let fee = match columns.fee {
+        Some(idx) => spot_fee_in_quote(field(idx), columns.fee_currency.map_or("", field), &pair, price)?,
+        None => 0.0,
+    };
+
+    Ok(Some(SpotOrder {
+        pair,
+        order_time: normalize_spot_time(field(columns.time)),
+        is_buy,
+        price,
+        quantity,
+        fee,
+    }))
+}
+
+/// Fills of the export, plus the rows that could not be read as "Line N: reason"
+fn parse_spot_orders_from_csv(
+    csv_content: &str,
+    csv_format: Option<CsvFormat>,
+) -> Result<(Vec<SpotOrder>, Vec<String>), String> {
+    let dialect = detect_csv_dialect(csv_content, csv_format);
+    let mut lines = csv_content.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+
+    let (_, header_line) = lines.next().ok_or("File is empty")?;
+    let header_owned = split_csv_line(header_line, dialect);
+    let header: Vec<&str> = header_owned.iter().map(String::as_str).collect();
+    let columns = SpotColumns::from_header(&header)
+        .ok_or("Not a BitGet spot export: missing time, pair, direction, price or amount column")?;
+
+    let mut orders = Vec::new();
+    let mut errors = Vec::new();
+    for (line_num, line) in lines {
+        match parse_spot_line(line, &columns, dialect) {
+            Ok(Some(order)) => orders.push(order),
+            Ok(None) => {}
+            Err(e) => errors.push(format!("Line {}: {}", line_num + 1, e)),
+        }
+    }
+
+    // Process chronologically so position grouping works correctly
+    orders.sort_by(|a, b| a.order_time.cmp(&b.order_time));
+    Ok((orders, errors))
+}
+
+fn group_spot_orders_into_positions(orders: Vec<SpotOrder>) -> Vec<SpotPositionData> {
+    let mut open: HashMap<String, OpenSpotPosition> = HashMap::new();
+    let mut closed: Vec<SpotPositionData> = Vec::new();
+
+    for order in orders {
+        if order.is_buy {
+            let pos = open.entry(order.pair.clone()).or_insert_with(|| OpenSpotPosition {
+                pair: order.pair.clone(),
+                entry_qty: 0.0,
+                exit_qty: 0.0,
+                entry_price_sum: 0.0,
+                exit_price_sum: 0.0,
+                total_fees: 0.0,
+                opening_time: order.order_time.clone(),
+                closing_time: String::new(),
+                entry_orders: Vec::new(),
+                exit_orders: Vec::new(),
+            });
+            pos.entry_qty += order.quantity;
+            pos.entry_price_sum += order.price * order.quantity;
+            pos.total_fees += order.fee;
+            pos.entry_orders.push((order.price, order.quantity));
+        } else if let Some(pos) = open.get_mut(&order.pair) {
+            pos.exit_qty += order.quantity;
+            pos.exit_price_sum += order.price * order.quantity;
+            pos.total_fees += order.fee;
+            pos.closing_time = order.order_time.clone();
+            pos.exit_orders.push((order.price, order.quantity));
+
+            // Fully closed when exit qty >= entry qty (with 0.1% tolerance)
+            if pos.exit_qty >= pos.entry_qty * 0.999
+                && let Some(pos) = open.remove(&order.pair)
+            {
+                closed.push(finalize_spot_position(pos));
+            }
+        }
+        // Sells without a tracked buy are coins acquired before the export window — skip
+    }
+
+    closed
+}
+
+fn finalize_spot_position(pos: OpenSpotPosition) -> SpotPositionData {
+    let entry_price = if pos.entry_qty > 0.0 { pos.entry_price_sum / pos.entry_qty } else { 0.0 };
+    let exit_price = if pos.exit_qty > 0.0 { pos.exit_price_sum / pos.exit_qty } else { 0.0 };
+
+    // Spot has no exchange-side PnL: (exit - entry) × sold qty, net of fees
+    let realized_pnl = (exit_price - entry_price) * pos.exit_qty.min(pos.entry_qty) - pos.total_fees;
+
+    let entries: Vec<serde_json::Value> = pos.entry_orders.iter().map(|(price, qty)| {
+        let pct = if pos.entry_qty > 0.0 { (qty / pos.entry_qty * 100.0).round() as i64 } else { 0 };
+        serde_json::json!({"price": price, "percent": pct})
+    }).collect();
+
+    let exits: Vec<serde_json::Value> = pos.exit_orders.iter().map(|(price, qty)| {
+        let pct = if pos.entry_qty > 0.0 { qty / pos.entry_qty * 100.0 } else { 0.0 };
+        serde_json::json!({"price": price, "percent": pct})
+    }).collect();
+
+    SpotPositionData {
+        pair: pos.pair,
+        entry_price,
+        exit_price,
+        quantity: pos.entry_qty,
+        realized_pnl,
+        total_fees: pos.total_fees,
+        opening_time: pos.opening_time,
+        closing_time: pos.closing_time,
+        entries_json: serde_json::to_string(&entries).unwrap_or_else(|_| "[]".to_string()),
+        exits_json: serde_json::to_string(&exits).unwrap_or_else(|_| "[]".to_string()),
+    }
+}
+
+fn generate_spot_fingerprint(pos: &SpotPositionData) -> String {
+    format!(
+        "csv|bitget-spot|{}|long|{}|{}|{:.8}|{:.8}",
+        pos.pair.to_lowercase(),
+        pos.opening_time,
+        pos.closing_time,
+        pos.quantity,
+        pos.realized_pnl
+    )
+}
+
+/// Parse BitGet spot order history CSV and return preview of paired positions
+#[tauri::command]
+pub async fn preview_bitget_spot_import(
+    csv_content: String,
+    _portfolio: f64,
+    _r_percent: f64,
+    csv_format: Option<CsvFormat>,
+) -> Result<Vec<ImportPreview>, CommandError> {
+    let (orders, _) = parse_spot_orders_from_csv(&csv_content, csv_format).map_err(CommandError::Validation)?;
+    let positions = group_spot_orders_into_positions(orders);
+
+    let previews = positions.iter().map(|pos| {
+        ImportPreview {
+            pair: pos.pair.clone(),
+            position_type: "LONG".to_string(),
+            entry_price: pos.entry_price,
+            exit_price: pos.exit_price,
+            quantity: pos.quantity,
+            realized_pnl: pos.realized_pnl,
+            opening_time: pos.opening_time.clone(),
+            closing_time: pos.closing_time.clone(),
+            total_fees: pos.total_fees,
+            fingerprint: generate_spot_fingerprint(pos),
+            is_open: false,
+        }
+    }).collect();
+
+    Ok(previews)
+}
+
+/// Import BitGet spot order history CSV — pairs buys/sells per symbol then inserts
+#[tauri::command]
+pub async fn import_bitget_spot_csv(
+    db: State<'_, Database>,
+    csv_content: String,
+    portfolio: f64,
+    r_percent: f64,
+    merge_existing: Option<bool>,
+    csv_format: Option<CsvFormat>,
+) -> Result<ImportResult, CommandError> {
+    let conn = db.pool.get()?;
+    import_bitget_spot_content(&conn, &csv_content, portfolio, r_percent, merge_existing.unwrap_or(false), csv_format)
+}
+
+fn import_bitget_spot_content(
+    conn: &rusqlite::Connection,
+    csv_content: &str,
+    portfolio: f64,
+    r_percent: f64,
+    merge_existing: bool,
+    csv_format: Option<CsvFormat>,
+) -> Result<ImportResult, CommandError> {
+    let (orders, mut errors) = parse_spot_orders_from_csv(csv_content, csv_format).map_err(CommandError::Validation)?;
+    let positions = group_spot_orders_into_positions(orders);
+
+    let mut imported = 0;
+    let mut updated = 0;
+    let mut duplicates = 0;
+    let breakeven_threshold = breakeven_threshold(conn)?;
+
+    for pos in positions {
+        let fingerprint = generate_spot_fingerprint(&pos);
+
+        let existing = find_imported_trade(conn, &fingerprint, merge_existing);
+
+        if existing == Some(ExistingImport::Duplicate) {
+            duplicates += 1;
+            continue;
+        }
+
+        let id = format!(
+            "TRADE-{}-{}",
+            Utc::now().timestamp_millis(),
+            uuid::Uuid::new_v4()
+                .to_string()
+                .split('-')
+                .next()
+                .ok_or_else(|| CommandError::Internal("Failed to generate trade ID".to_string()))?
+        );
+        let now = Utc::now().timestamp();
+
+        // Spot trades are unleveraged: margin equals the position size
+        let one_r = portfolio * r_percent;
+        let position_size = pos.quantity * pos.entry_price;
+        let leverage = 1;
+        let margin = position_size;
+
+        let target_sl_distance = if pos.quantity > 0.0 {
+            one_r / pos.quantity
+        } else {
+            pos.entry_price * 0.01
+        };
+        let estimated_sl = (pos.entry_price - target_sl_distance).max(0.0);
+
+        let status = closed_status(pos.realized_pnl, breakeven_threshold);
+
+        let planned_tps = serde_json::json!([{
+            "price": pos.exit_price, "percent": 1.0, "rr": 0.0
+        }]).to_string();
+
+        let notes = format!(
+            "Imported from BitGet Spot | Fees: ${:.2} | Note: RR metrics unavailable (no SL data from BitGet)",
+            pos.total_fees
+        );
+
+        let opening_ts = chrono::DateTime::parse_from_rfc3339(
+            &format!("{}Z", pos.opening_time.replace(' ', "T"))
+        ).map(|dt| dt.timestamp()).unwrap_or(now);
+
+        let closing_ts = chrono::DateTime::parse_from_rfc3339(
+            &format!("{}Z", pos.closing_time.replace(' ', "T"))
+        ).map(|dt| dt.timestamp()).unwrap_or(now);
+
+        if let Some(ExistingImport::Refresh(existing_id)) = existing {
+            match merge_imported_trade(conn, &existing_id, &MergedExecution {
+                fingerprint: &fingerprint,
+                status,
+                close_date: Some(closing_ts),
+                quantity: pos.quantity,
+                effective_pe: pos.entry_price,
+                effective_entries: Some(&pos.entries_json),
+                exits: &pos.exits_json,
+                total_pnl: Some(pos.realized_pnl),
+                notes: &notes,
+            }, breakeven_threshold) {
+                Ok(true) => updated += 1,
+                Ok(false) => duplicates += 1,
+                Err(e) => errors.push(format!("Failed to merge {}: {}", pos.pair, e)),
+            }
+            continue;
+        }
+
+        match conn.execute(
+            "INSERT INTO trades (
+                id, pair, exchange, analysis_date, trade_date, close_date, status,
+                portfolio_value, r_percent, min_rr,
+                planned_pe, planned_sl, leverage, planned_tps, planned_entries,
+                position_type, one_r, margin, position_size, quantity,
+                planned_weighted_rr, effective_pe, effective_entries, exits, total_pnl,
+                notes, import_fingerprint, import_source, created_at, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                id, pos.pair, "BitGet",
+                opening_ts, opening_ts, closing_ts,
+                status, portfolio, r_percent, 0.0,
+                pos.entry_price, estimated_sl, leverage,
+                planned_tps, pos.entries_json,
+                "LONG",
+                one_r, margin, position_size, pos.quantity,
+                0.0,
+                pos.entry_price, pos.entries_json, pos.exits_json,
+                pos.realized_pnl, notes, fingerprint, "CSV_IMPORT",
+                now, now,
+            ],
+        ) {
+            Ok(_) => imported += 1,
+            Err(e) => errors.push(format!("Failed to import {}: {}", pos.pair, e)),
+        }
+    }
+
+    Ok(ImportResult { imported, updated, duplicates, errors })
+}
+
 // ─── BloFin CSV Import ────────────────────────────────────────────────────────
 
 /// A single filled order row from BloFin order history CSV
@@ -365,6 +1095,8 @@ struct BlofinPositionData {
     closing_time: String,
     entries_json: String,
     exits_json: String,
+    /// Not fully closed by the end of the export window
+    is_open: bool,
 }
 
 struct OpenBlofinPosition {
@@ -437,9 +1169,9 @@ fn asset_to_pair(asset: &str) -> String {
     }
 }
 
-fn parse_blofin_line(line: &str) -> Result<BlofinOrder, String> {
-    let clean = line.trim_start_matches('\u{feff}');
-    let fields: Vec<&str> = clean.split(',').map(|f| f.trim()).collect();
+fn parse_blofin_line(line: &str, dialect: CsvDialect) -> Result<BlofinOrder, String> {
+    let owned = split_csv_line(line, dialect);
+    let fields: Vec<&str> = owned.iter().map(String::as_str).collect();
 
     if fields.len() < 15 {
         return Err(format!("Expected ≥15 fields, got {}", fields.len()));
@@ -476,7 +1208,8 @@ fn parse_blofin_line(line: &str) -> Result<BlofinOrder, String> {
     })
 }
 
-fn parse_blofin_orders_from_csv(csv_content: &str) -> Vec<BlofinOrder> {
+fn parse_blofin_orders_from_csv(csv_content: &str, csv_format: Option<CsvFormat>) -> Vec<BlofinOrder> {
+    let dialect = detect_csv_dialect(csv_content, csv_format);
     let mut orders: Vec<BlofinOrder> = csv_content
         .lines()
         .skip(1)
@@ -485,7 +1218,7 @@ fn parse_blofin_orders_from_csv(csv_content: &str) -> Vec<BlofinOrder> {
             if clean.trim().is_empty() {
                 return None;
             }
-            parse_blofin_line(clean).ok()
+            parse_blofin_line(clean, dialect).ok()
         })
         .collect();
 
@@ -494,7 +1227,9 @@ fn parse_blofin_orders_from_csv(csv_content: &str) -> Vec<BlofinOrder> {
     orders
 }
 
-fn group_blofin_orders_into_positions(orders: Vec<BlofinOrder>) -> Vec<BlofinPositionData> {
+/// Group orders into positions. Positions still open at the end of the export are
+/// dropped unless `include_open` is set, in which case they are returned with `is_open`.
+fn group_blofin_orders_into_positions(orders: Vec<BlofinOrder>, include_open: bool) -> Vec<BlofinPositionData> {
     let mut open: HashMap<String, OpenBlofinPosition> = HashMap::new();
     let mut closed: Vec<BlofinPositionData> = Vec::new();
 
@@ -551,7 +1286,13 @@ fn group_blofin_orders_into_positions(orders: Vec<BlofinOrder>) -> Vec<BlofinPos
             }
         }
     }
-    // Any remaining open positions are unclosed — skip them
+    // Any remaining open positions are unclosed — skip them unless requested
+    if include_open {
+        closed.extend(open.into_values().map(|pos| BlofinPositionData {
+            is_open: true,
+            ..finalize_blofin_position(pos)
+        }));
+    }
 
     closed
 }
@@ -610,6 +1351,7 @@ fn finalize_blofin_position(pos: OpenBlofinPosition) -> BlofinPositionData {
         closing_time: pos.closing_time,
         entries_json: serde_json::to_string(&entries).unwrap_or_else(|_| "[]".to_string()),
         exits_json: serde_json::to_string(&exits).unwrap_or_else(|_| "[]".to_string()),
+        is_open: false,
     }
 }
 
@@ -631,9 +1373,11 @@ pub async fn preview_blofin_import(
     csv_content: String,
     _portfolio: f64,
     _r_percent: f64,
-) -> Result<Vec<ImportPreview>, String> {
-    let orders = parse_blofin_orders_from_csv(&csv_content);
-    let positions = group_blofin_orders_into_positions(orders);
+    include_open: Option<bool>,
+    csv_format: Option<CsvFormat>,
+) -> Result<Vec<ImportPreview>, CommandError> {
+    let orders = parse_blofin_orders_from_csv(&csv_content, csv_format);
+    let positions = group_blofin_orders_into_positions(orders, include_open.unwrap_or(false));
 
     let previews = positions
         .iter()
@@ -650,6 +1394,7 @@ pub async fn preview_blofin_import(
                 closing_time: pos.closing_time.clone(),
                 total_fees: pos.total_fees,
                 fingerprint,
+                is_open: pos.is_open,
             }
         })
         .collect();
@@ -664,29 +1409,47 @@ pub async fn import_blofin_csv(
     csv_content: String,
     portfolio: f64,
     r_percent: f64,
-) -> Result<ImportResult, String> {
-    let orders = parse_blofin_orders_from_csv(&csv_content);
-    let positions = group_blofin_orders_into_positions(orders);
+    merge_existing: Option<bool>,
+    include_open: Option<bool>,
+    csv_format: Option<CsvFormat>,
+) -> Result<ImportResult, CommandError> {
+    let conn = db.pool.get()?;
+    import_blofin_content(
+        &conn,
+        &csv_content,
+        portfolio,
+        r_percent,
+        merge_existing.unwrap_or(false),
+        include_open.unwrap_or(false),
+        csv_format,
+    )
+}
+
+fn import_blofin_content(
+    conn: &rusqlite::Connection,
+    csv_content: &str,
+    portfolio: f64,
+    r_percent: f64,
+    merge_existing: bool,
+    include_open: bool,
+    csv_format: Option<CsvFormat>,
+) -> Result<ImportResult, CommandError> {
+    let orders = parse_blofin_orders_from_csv(csv_content, csv_format);
+    let positions = group_blofin_orders_into_positions(orders, include_open);
 
     let mut imported = 0;
+    let mut updated = 0;
     let mut duplicates = 0;
     let mut errors: Vec<String> = Vec::new();
+    let breakeven_threshold = breakeven_threshold(conn)?;
 
     {
-        let conn = db.conn.lock().map_err(|e| e.to_string())?;
-
         for pos in positions {
             let fingerprint = generate_blofin_fingerprint(&pos);
 
-            let exists: bool = conn
-                .query_row(
-                    "SELECT EXISTS(SELECT 1 FROM trades WHERE import_fingerprint = ?)",
-                    [&fingerprint],
-                    |row| row.get(0),
-                )
-                .unwrap_or(false);
+            let existing = find_imported_trade(conn, &fingerprint, merge_existing);
 
-            if exists {
+            if existing == Some(ExistingImport::Duplicate) {
                 duplicates += 1;
                 continue;
             }
@@ -698,7 +1461,7 @@ pub async fn import_blofin_csv(
                     .to_string()
                     .split('-')
                     .next()
-                    .ok_or("Failed to generate trade ID")?
+                    .ok_or_else(|| CommandError::Internal("Failed to generate trade ID".to_string()))?
             );
             let now = Utc::now().timestamp();
 
@@ -720,12 +1483,10 @@ pub async fn import_blofin_csv(
                 pos.entry_price + target_sl_distance
             };
 
-            let status = if pos.realized_pnl > 0.5 {
-                "WIN"
-            } else if pos.realized_pnl < -0.5 {
-                "LOSS"
+            let status = if pos.is_open {
+                "OPEN"
             } else {
-                "BE"
+                closed_status(pos.realized_pnl, breakeven_threshold)
             };
 
             let planned_tps = serde_json::json!([{
@@ -739,6 +1500,11 @@ pub async fn import_blofin_csv(
                 "Imported from BloFin | {}x {} | Fees: ${:.2} | Note: RR metrics unavailable (no SL data from BloFin)",
                 leverage, pos.margin_mode, pos.total_fees
             );
+            let notes = if pos.is_open {
+                format!("{} | Open at import (realized so far: ${:.2})", notes, pos.realized_pnl)
+            } else {
+                notes
+            };
 
             let opening_ts = chrono::DateTime::parse_from_rfc3339(&format!(
                 "{}Z",
@@ -754,6 +1520,32 @@ pub async fn import_blofin_csv(
             .map(|dt| dt.timestamp())
             .unwrap_or(now);
 
+            // Open positions have no close yet; realized PnL of partial exits stays in the notes
+            let (closing_ts, total_pnl) = if pos.is_open {
+                (None, None)
+            } else {
+                (Some(closing_ts), Some(pos.realized_pnl))
+            };
+
+            if let Some(ExistingImport::Refresh(existing_id)) = existing {
+                match merge_imported_trade(conn, &existing_id, &MergedExecution {
+                    fingerprint: &fingerprint,
+                    status,
+                    close_date: closing_ts,
+                    quantity: pos.quantity,
+                    effective_pe: pos.entry_price,
+                    effective_entries: Some(&pos.entries_json),
+                    exits: &pos.exits_json,
+                    total_pnl,
+                    notes: &notes,
+                }, breakeven_threshold) {
+                    Ok(true) => updated += 1,
+                    Ok(false) => duplicates += 1,
+                    Err(e) => errors.push(format!("Failed to merge {}: {}", pos.pair, e)),
+                }
+                continue;
+            }
+
             match conn.execute(
                 "INSERT INTO trades (
                     id, pair, exchange, analysis_date, trade_date, close_date, status,
@@ -788,7 +1580,7 @@ pub async fn import_blofin_csv(
                     pos.entry_price,
                     pos.entries_json,
                     pos.exits_json,
-                    pos.realized_pnl,
+                    total_pnl,
                     notes,
                     fingerprint,
                     "CSV_IMPORT",
@@ -804,6 +1596,7 @@ pub async fn import_blofin_csv(
 
     Ok(ImportResult {
         imported,
+        updated,
         duplicates,
         errors,
     })
@@ -811,14 +1604,13 @@ pub async fn import_blofin_csv(
 
 /// Delete all BloFin CSV-imported trades
 #[tauri::command]
-pub async fn delete_blofin_trades(db: State<'_, Database>) -> Result<usize, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+pub async fn delete_blofin_trades(db: State<'_, Database>) -> Result<usize, CommandError> {
+    let conn = db.pool.get()?;
     let count = conn
         .execute(
             "DELETE FROM trades WHERE import_fingerprint LIKE 'csv|blofin|%'",
             [],
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
     Ok(count)
 }
 
@@ -829,6 +1621,8 @@ pub async fn delete_blofin_trades(db: State<'_, Database>) -> Result<usize, Stri
 //                Quote Asset | Order Type | AvgPrice
 //
 // Type values: "Open Long", "Close Long", "Open Short", "Close Short"
+// Columns are resolved by header name (English or Chinese locale, e.g.
+// 时间 | 交易对 | 类型 "开多/平多/开空/平空"), falling back to this layout.
 // Grouping key: pair + direction (handles hedge mode)
 
 #[derive(Debug, Clone)]
@@ -873,6 +1667,8 @@ struct BingxPositionData {
     closing_time: String,
     entries_json: String,
     exits_json: String,
+    /// Not fully closed by the end of the export window
+    is_open: bool,
 }
 
 /// Extract a string from a calamine Data cell
@@ -915,19 +1711,109 @@ fn normalize_bingx_time(s: &str) -> String {
     s
 }
 
-fn parse_bingx_row(row: &[Data]) -> Result<BingxOrder, String> {
-    if row.len() < 12 {
-        return Err(format!("Expected ≥12 columns, got {}", row.len()));
+/// Column indices of a BingX order sheet, resolved from its header row
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BingxColumns {
+    time: usize,
+    pair: usize,
+    kind: usize,
+    leverage: Option<usize>,
+    price: usize,
+    quantity: usize,
+    fee: Option<usize>,
+    pnl: Option<usize>,
+}
+
+impl BingxColumns {
+    /// The fixed English layout documented above, used for a headerless first sheet
+    const LEGACY: BingxColumns = BingxColumns {
+        time: 2,
+        pair: 3,
+        kind: 4,
+        leverage: Some(5),
+        price: 6,
+        quantity: 7,
+        fee: Some(9),
+        pnl: Some(11),
+    };
+
+    /// Resolve columns by (English or Chinese) header name
+    fn from_header(row: &[Data]) -> Option<Self> {
+        // "Time(UTC+8)" / "时间（UTC+8）" → "time" / "时间"
+        let headers: Vec<String> = row
+            .iter()
+            .map(|cell| {
+                let text = data_str(cell);
+                let text = text.split(['(', '（']).next().unwrap_or("");
+                text.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase()
+            })
+            .collect();
+
+        // Aliases in priority order
+        let find = |aliases: &[&str]| {
+            aliases
+                .iter()
+                .find_map(|alias| headers.iter().position(|h| h == alias))
+        };
+
+        Some(BingxColumns {
+            time: find(&["time", "时间", "成交时间", "订单时间"])?,
+            pair: find(&["pair", "symbol", "交易对", "币对"])?,
+            kind: find(&["type", "direction", "类型", "方向"])?,
+            leverage: find(&["leverage", "杠杆", "杠杆倍数"]),
+            price: find(&["dealprice", "avgprice", "price", "成交价", "成交价格", "成交均价", "价格"])?,
+            quantity: find(&["quantity", "qty", "数量", "成交数量", "成交量"])?,
+            fee: find(&["fee", "手续费"]),
+            pnl: find(&["realizedpnl", "已实现盈亏", "实现盈亏", "盈亏"]),
+        })
+    }
+}
+
+/// "Open Long" / "开多" → ("LONG", is_entry = true)
+fn parse_bingx_order_type(s: &str) -> Option<(&'static str, bool)> {
+    match s.to_lowercase().as_str() {
+        "open long" => return Some(("LONG", true)),
+        "close long" => return Some(("LONG", false)),
+        "open short" => return Some(("SHORT", true)),
+        "close short" => return Some(("SHORT", false)),
+        _ => {}
     }
 
-    let time_str = normalize_bingx_time(&data_str(&row[2]));
-    let pair_raw = data_str(&row[3]);
-    let type_str = data_str(&row[4]);
-    let leverage = data_f64(&row[5]) as i64;
-    let deal_price = data_f64(&row[6]);
-    let quantity = data_f64(&row[7]);
-    let fee = data_f64(&row[9]).abs();
-    let realized_pnl = data_f64(&row[11]);
+    // Chinese locale: 开 = open, 平 = close, 多 = long, 空 = short
+    let is_entry = if s.contains('开') {
+        true
+    } else if s.contains('平') {
+        false
+    } else {
+        return None;
+    };
+    let direction = if s.contains('多') {
+        "LONG"
+    } else if s.contains('空') {
+        "SHORT"
+    } else {
+        return None;
+    };
+    Some((direction, is_entry))
+}
+
+fn parse_bingx_row(row: &[Data], columns: &BingxColumns) -> Result<BingxOrder, String> {
+    let required = [columns.time, columns.pair, columns.kind, columns.price, columns.quantity];
+    let needed = required.iter().max().copied().unwrap_or(0) + 1;
+    if row.len() < needed {
+        return Err(format!("Expected ≥{} columns, got {}", needed, row.len()));
+    }
+
+    let optional = |idx: Option<usize>| idx.and_then(|i| row.get(i)).map(data_f64).unwrap_or(0.0);
+
+    let time_str = normalize_bingx_time(&data_str(&row[columns.time]));
+    let pair_raw = data_str(&row[columns.pair]);
+    let type_str = data_str(&row[columns.kind]);
+    let leverage = optional(columns.leverage) as i64;
+    let deal_price = data_f64(&row[columns.price]);
+    let quantity = data_f64(&row[columns.quantity]);
+    let fee = optional(columns.fee).abs();
+    let realized_pnl = optional(columns.pnl);
 
     if pair_raw.is_empty() || type_str.is_empty() || quantity <= 0.0 {
         return Err("Empty or zero-quantity row".to_string());
@@ -935,13 +1821,8 @@ fn parse_bingx_row(row: &[Data]) -> Result<BingxOrder, String> {
 
     let pair = bingx_pair_to_standard(&pair_raw);
 
-    let (direction, is_entry) = match type_str.as_str() {
-        "Open Long"   => ("LONG",  true),
-        "Close Long"  => ("LONG",  false),
-        "Open Short"  => ("SHORT", true),
-        "Close Short" => ("SHORT", false),
-        other => return Err(format!("Unknown order type: {}", other)),
-    };
+    let (direction, is_entry) = parse_bingx_order_type(&type_str)
+        .ok_or_else(|| format!("Unknown order type: {}", type_str))?;
 
     Ok(BingxOrder {
         order_time: time_str,
@@ -956,32 +1837,53 @@ fn parse_bingx_row(row: &[Data]) -> Result<BingxOrder, String> {
     })
 }
 
+/// Parse the order rows of one sheet. The header may sit below a title/summary
+/// block, so the first few rows are scanned for it.
+fn parse_bingx_sheet(rows: &[&[Data]], is_first_sheet: bool) -> Vec<BingxOrder> {
+    let header = rows
+        .iter()
+        .take(10)
+        .enumerate()
+        .find_map(|(idx, row)| BingxColumns::from_header(row).map(|columns| (idx, columns)));
+
+    let (data_start, columns) = match header {
+        Some((idx, columns)) => (idx + 1, columns),
+        // Older exports without a recognizable header: fixed layout, first sheet only
+        None if is_first_sheet => (1, BingxColumns::LEGACY),
+        None => return Vec::new(),
+    };
+
+    rows.iter()
+        .skip(data_start)
+        .filter_map(|row| parse_bingx_row(row, &columns).ok())
+        .collect()
+}
+
 fn parse_bingx_xlsx(file_path: &str) -> Result<Vec<BingxOrder>, String> {
     let mut workbook: Xlsx<_> = open_workbook(file_path)
         .map_err(|e| format!("Failed to open xlsx: {}", e))?;
 
-    let sheet_name = workbook
-        .sheet_names()
-        .first()
-        .cloned()
-        .ok_or("No sheets found in workbook")?;
-
-    let sheet = workbook
-        .worksheet_range(&sheet_name)
-        .map_err(|e| format!("Failed to read sheet '{}': {}", sheet_name, e))?;
+    let sheet_names = workbook.sheet_names();
+    if sheet_names.is_empty() {
+        return Err("No sheets found in workbook".to_string());
+    }
 
-    let mut orders: Vec<BingxOrder> = sheet
-        .rows()
-        .skip(1) // skip header
-        .filter_map(|row| parse_bingx_row(row).ok())
-        .collect();
+    // Larger histories are split across several sheets — scan them all
+    let mut orders: Vec<BingxOrder> = Vec::new();
+    for (idx, sheet_name) in sheet_names.iter().enumerate() {
+        let sheet = workbook
+            .worksheet_range(sheet_name)
+            .map_err(|e| format!("Failed to read sheet '{}': {}", sheet_name, e))?;
+        let rows: Vec<&[Data]> = sheet.rows().collect();
+        orders.extend(parse_bingx_sheet(&rows, idx == 0));
+    }
 
     // Process chronologically
     orders.sort_by(|a, b| a.order_time.cmp(&b.order_time));
     Ok(orders)
 }
 
-fn group_bingx_orders_into_positions(orders: Vec<BingxOrder>) -> Vec<BingxPositionData> {
+fn group_bingx_orders_into_positions(orders: Vec<BingxOrder>, include_open: bool) -> Vec<BingxPositionData> {
     // Key = "PAIR-DIRECTION" (e.g., "BTC/USDT-LONG") to support hedge mode
     let mut open: HashMap<String, OpenBingxPosition> = HashMap::new();
     let mut closed: Vec<BingxPositionData> = Vec::new();
@@ -1034,6 +1936,14 @@ fn group_bingx_orders_into_positions(orders: Vec<BingxOrder>) -> Vec<BingxPositi
         }
     }
 
+    // Positions not fully closed within the export window
+    if include_open {
+        closed.extend(open.into_values().map(|pos| BingxPositionData {
+            is_open: true,
+            ..finalize_bingx_position(pos)
+        }));
+    }
+
     closed
 }
 
@@ -1072,6 +1982,7 @@ fn finalize_bingx_position(pos: OpenBingxPosition) -> BingxPositionData {
         closing_time: pos.closing_time,
         entries_json: serde_json::to_string(&entries).unwrap_or_else(|_| "[]".to_string()),
         exits_json:   serde_json::to_string(&exits).unwrap_or_else(|_| "[]".to_string()),
+        is_open: false,
     }
 }
 
@@ -1094,9 +2005,10 @@ pub async fn preview_bingx_import(
     file_path: String,
     _portfolio: f64,
     _r_percent: f64,
-) -> Result<Vec<ImportPreview>, String> {
-    let orders = parse_bingx_xlsx(&file_path)?;
-    let positions = group_bingx_orders_into_positions(orders);
+    include_open: Option<bool>,
+) -> Result<Vec<ImportPreview>, CommandError> {
+    let orders = parse_bingx_xlsx(&file_path).map_err(CommandError::Validation)?;
+    let positions = group_bingx_orders_into_positions(orders, include_open.unwrap_or(false));
 
     let previews = positions.iter().map(|pos| {
         ImportPreview {
@@ -1110,6 +2022,7 @@ pub async fn preview_bingx_import(
             closing_time: pos.closing_time.clone(),
             total_fees: pos.total_fees,
             fingerprint: generate_bingx_fingerprint(pos),
+            is_open: pos.is_open,
         }
     }).collect();
 
@@ -1123,29 +2036,44 @@ pub async fn import_bingx_file(
     file_path: String,
     portfolio: f64,
     r_percent: f64,
-) -> Result<ImportResult, String> {
-    let orders = parse_bingx_xlsx(&file_path)?;
-    let positions = group_bingx_orders_into_positions(orders);
+    merge_existing: Option<bool>,
+    include_open: Option<bool>,
+) -> Result<ImportResult, CommandError> {
+    let conn = db.pool.get()?;
+    import_bingx_content(
+        &conn,
+        &file_path,
+        portfolio,
+        r_percent,
+        merge_existing.unwrap_or(false),
+        include_open.unwrap_or(false),
+    )
+}
+
+fn import_bingx_content(
+    conn: &rusqlite::Connection,
+    file_path: &str,
+    portfolio: f64,
+    r_percent: f64,
+    merge_existing: bool,
+    include_open: bool,
+) -> Result<ImportResult, CommandError> {
+    let orders = parse_bingx_xlsx(file_path).map_err(CommandError::Validation)?;
+    let positions = group_bingx_orders_into_positions(orders, include_open);
 
     let mut imported = 0;
+    let mut updated = 0;
     let mut duplicates = 0;
     let mut errors: Vec<String> = Vec::new();
+    let breakeven_threshold = breakeven_threshold(conn)?;
 
     {
-        let conn = db.conn.lock().map_err(|e| e.to_string())?;
-
         for pos in positions {
             let fingerprint = generate_bingx_fingerprint(&pos);
 
-            let exists: bool = conn
-                .query_row(
-                    "SELECT EXISTS(SELECT 1 FROM trades WHERE import_fingerprint = ?)",
-                    [&fingerprint],
-                    |row| row.get(0),
-                )
-                .unwrap_or(false);
+            let existing = find_imported_trade(conn, &fingerprint, merge_existing);
 
-            if exists {
+            if existing == Some(ExistingImport::Duplicate) {
                 duplicates += 1;
                 continue;
             }
@@ -1157,7 +2085,7 @@ pub async fn import_bingx_file(
                     .to_string()
                     .split('-')
                     .next()
-                    .ok_or("Failed to generate ID")?
+                    .ok_or_else(|| CommandError::Internal("Failed to generate ID".to_string()))?
             );
             let now = Utc::now().timestamp();
 
@@ -1177,12 +2105,10 @@ pub async fn import_bingx_file(
                 pos.entry_price + target_sl_distance
             };
 
-            let status = if pos.realized_pnl > 0.5 {
-                "WIN"
-            } else if pos.realized_pnl < -0.5 {
-                "LOSS"
+            let status = if pos.is_open {
+                "OPEN"
             } else {
-                "BE"
+                closed_status(pos.realized_pnl, breakeven_threshold)
             };
 
             let planned_tps = serde_json::json!([{
@@ -1193,6 +2119,11 @@ pub async fn import_bingx_file(
                 "Imported from BingX | {}x | Fees: ${:.2} | Note: RR metrics unavailable (no SL data from BingX)",
                 leverage, pos.total_fees
             );
+            let notes = if pos.is_open {
+                format!("{} | Open at import (realized so far: ${:.2})", notes, pos.realized_pnl)
+            } else {
+                notes
+            };
 
             let opening_ts = chrono::DateTime::parse_from_rfc3339(
                 &format!("{}Z", pos.opening_time.replace(' ', "T"))
@@ -1202,6 +2133,32 @@ pub async fn import_bingx_file(
                 &format!("{}Z", pos.closing_time.replace(' ', "T"))
             ).map(|dt| dt.timestamp()).unwrap_or(now);
 
+            // Open positions have no close yet; realized PnL of partial exits stays in the notes
+            let (closing_ts, total_pnl) = if pos.is_open {
+                (None, None)
+            } else {
+                (Some(closing_ts), Some(pos.realized_pnl))
+            };
+
+            if let Some(ExistingImport::Refresh(existing_id)) = existing {
+                match merge_imported_trade(conn, &existing_id, &MergedExecution {
+                    fingerprint: &fingerprint,
+                    status,
+                    close_date: closing_ts,
+                    quantity: pos.quantity,
+                    effective_pe: pos.entry_price,
+                    effective_entries: Some(&pos.entries_json),
+                    exits: &pos.exits_json,
+                    total_pnl,
+                    notes: &notes,
+                }, breakeven_threshold) {
+                    Ok(true) => updated += 1,
+                    Ok(false) => duplicates += 1,
+                    Err(e) => errors.push(format!("Failed to merge {}: {}", pos.pair, e)),
+                }
+                continue;
+            }
+
             match conn.execute(
                 "INSERT INTO trades (
                     id, pair, exchange, analysis_date, trade_date, close_date, status,
@@ -1221,7 +2178,7 @@ pub async fn import_bingx_file(
                     one_r, margin, position_size, pos.quantity,
                     0.0,
                     pos.entry_price, pos.entries_json, pos.exits_json,
-                    pos.realized_pnl, notes, fingerprint, "CSV_IMPORT",
+                    total_pnl, notes, fingerprint, "CSV_IMPORT",
                     now, now,
                 ],
             ) {
@@ -1231,38 +2188,251 @@ pub async fn import_bingx_file(
         }
     }
 
-    Ok(ImportResult { imported, duplicates, errors })
+    Ok(ImportResult { imported, updated, duplicates, errors })
 }
 
 /// Delete all BingX imported trades
 #[tauri::command]
-pub async fn delete_bingx_trades(db: State<'_, Database>) -> Result<usize, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+pub async fn delete_bingx_trades(db: State<'_, Database>) -> Result<usize, CommandError> {
+    let conn = db.pool.get()?;
     let count = conn
-        .execute("DELETE FROM trades WHERE import_fingerprint LIKE 'xlsx|bingx|%'", [])
-        .map_err(|e| e.to_string())?;
+        .execute("DELETE FROM trades WHERE import_fingerprint LIKE 'xlsx|bingx|%'", [])?;
     Ok(count)
 }
 
+// ─── Batch Import ─────────────────────────────────────────────────────────────
+
+/// Per-file outcome of `import_files`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileImportResult {
+    pub file_path: String,
+    /// Detected format ("bitget_csv" | "bitget_spot_csv" | "blofin_csv" | "bingx_xlsx"), None if unrecognized
+    pub format: Option<String>,
+    pub result: ImportResult,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ImportFormat {
+    BitgetCsv,
+    BitgetSpotCsv,
+    BlofinCsv,
+    BingxXlsx,
+}
+
+impl ImportFormat {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ImportFormat::BitgetCsv => "bitget_csv",
+            ImportFormat::BitgetSpotCsv => "bitget_spot_csv",
+            ImportFormat::BlofinCsv => "blofin_csv",
+            ImportFormat::BingxXlsx => "bingx_xlsx",
+        }
+    }
+}
+
+/// Detect the export format from the raw file bytes.
+/// BingX ships xlsx (a zip archive) even when the file is named .csv, so the
+/// extension is ignored and the zip magic bytes are checked instead.
+fn detect_import_format(bytes: &[u8], csv_format: Option<CsvFormat>) -> Result<ImportFormat, String> {
+    if bytes.starts_with(b"PK\x03\x04") {
+        return Ok(ImportFormat::BingxXlsx);
+    }
+
+    let content = String::from_utf8_lossy(bytes);
+    let dialect = detect_csv_dialect(&content, csv_format);
+    let mut lines = content.lines().filter(|l| !l.trim().is_empty());
+    let header = lines.next().ok_or("File is empty")?;
+
+    // BitGet rows start with "BTCUSDT Long·Isolated", which no BloFin row matches
+    if lines.clone().take(5).any(|l| parse_bitget_line(l, dialect).is_ok()) {
+        return Ok(ImportFormat::BitgetCsv);
+    }
+
+    let header_fields = split_csv_line(header, dialect);
+    let header_fields: Vec<&str> = header_fields.iter().map(String::as_str).collect();
+
+    // BloFin has no trading-pair column ("Underlying Asset"), so check spot first
+    if SpotColumns::from_header(&header_fields).is_some() {
+        return Ok(ImportFormat::BitgetSpotCsv);
+    }
+
+    if is_blofin_header(&header_fields) {
+        return Ok(ImportFormat::BlofinCsv);
+    }
+
+    Err("Unrecognized file format (expected BitGet futures/spot CSV, BloFin CSV or BingX xlsx)".to_string())
+}
+
+/// Columns of BloFin's order history export that `parse_blofin_line` reads by position
+const BLOFIN_HEADER_COLUMNS: &[(usize, &str)] = &[
+    (0, "Underlying Asset"),
+    (2, "Leverage"),
+    (3, "Order Time"),
+    (7, "Filled"),
+    (9, "PNL"),
+    (14, "Status"),
+];
+
+fn is_blofin_header(fields: &[&str]) -> bool {
+    BLOFIN_HEADER_COLUMNS
+        .iter()
+        .all(|(i, name)| fields.get(*i).is_some_and(|f| f.trim().eq_ignore_ascii_case(name)))
+}
+
+/// Import several exchange exports at once, auto-detecting each file's format.
+/// All files are imported in a single transaction; a database error rolls back
+/// every file, while unreadable or unrecognized files are reported per file.
+#[tauri::command]
+pub async fn import_files(
+    db: State<'_, Database>,
+    file_paths: Vec<String>,
+    portfolio: f64,
+    r_percent: f64,
+    merge_existing: Option<bool>,
+    include_open: Option<bool>,
+    csv_format: Option<CsvFormat>,
+) -> Result<Vec<FileImportResult>, CommandError> {
+    let merge_existing = merge_existing.unwrap_or(false);
+    let include_open = include_open.unwrap_or(false);
+    let mut results = Vec::with_capacity(file_paths.len());
+
+    let mut conn = db.pool.get()?;
+    let tx = conn.transaction()?;
+
+    for file_path in file_paths {
+        let detected = std::fs::read(&file_path)
+            .map_err(|e| format!("Failed to read file: {}", e))
+            .and_then(|bytes| detect_import_format(&bytes, csv_format).map(|format| (format, bytes)));
+
+        let (format, bytes) = match detected {
+            Ok(detected) => detected,
+            Err(e) => {
+                results.push(FileImportResult {
+                    file_path,
+                    format: None,
+                    result: ImportResult { imported: 0, updated: 0, duplicates: 0, errors: vec![e] },
+                });
+                continue;
+            }
+        };
+
+        let result = match format {
+            ImportFormat::BitgetCsv => {
+                import_bitget_content(&tx, &String::from_utf8_lossy(&bytes), portfolio, r_percent, merge_existing, csv_format)?
+            }
+            ImportFormat::BitgetSpotCsv => {
+                import_bitget_spot_content(
+                    &tx,
+                    &String::from_utf8_lossy(&bytes),
+                    portfolio,
+                    r_percent,
+                    merge_existing,
+                    csv_format,
+                )?
+            }
+            ImportFormat::BlofinCsv => {
+                import_blofin_content(
+                    &tx,
+                    &String::from_utf8_lossy(&bytes),
+                    portfolio,
+                    r_percent,
+                    merge_existing,
+                    include_open,
+                    csv_format,
+                )?
+            }
+            ImportFormat::BingxXlsx => {
+                match import_bingx_content(&tx, &file_path, portfolio, r_percent, merge_existing, include_open) {
+                    Ok(result) => result,
+                    // Workbook parse failures are per-file problems, not database errors
+                    Err(CommandError::Validation(e)) => {
+                        ImportResult { imported: 0, updated: 0, duplicates: 0, errors: vec![e] }
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        };
+
+        results.push(FileImportResult {
+            file_path,
+            format: Some(format.as_str().to_string()),
+            result,
+        });
+    }
+
+    tx.commit()?;
+
+    Ok(results)
+}
+
 // Data Export/Import
 
+/// Backup format written by export_all_data. Bump the major version when a backup can no
+/// longer be read by older builds; sections added later must be `#[serde(default)]` so
+/// older backups still import.
+const BACKUP_FORMAT_VERSION: &str = "2.0.0";
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BackupData {
     pub settings: Settings,
+    #[serde(default)]
+    pub accounts: Vec<Account>,
     pub trades: Vec<Trade>,
+    #[serde(default)]
+    pub capital_transactions: Vec<CapitalTransaction>,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    #[serde(default)]
+    pub trade_tags: Vec<TradeTagLink>,
+    #[serde(default)]
+    pub journal_entries: Vec<JournalEntry>,
+    #[serde(default)]
+    pub journal_entry_trades: Vec<JournalTradeLink>,
+    /// Credential metadata only, the keys stay in the keychain
+    #[serde(default)]
+    pub api_credentials: Vec<ApiCredentialSafe>,
+    #[serde(default)]
+    pub api_sync_history: Vec<ApiSyncHistory>,
     pub export_date: String,
     pub version: String,
 }
 
-/// Export all data to JSON
+/// Scheme of encrypted backups: AES-256-GCM under an Argon2id key derived from the password
+const BACKUP_ENCRYPTION: &str = "aes-256-gcm/argon2id";
+
+/// Backup written by export_all_data when given a password, wrapping the JSON of a BackupData
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedBackup {
+    encryption: String,
+    #[serde(flatten)]
+    data: PasswordEncrypted,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TradeTagLink {
+    pub trade_id: String,
+    pub tag_id: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JournalTradeLink {
+    pub entry_id: String,
+    pub trade_id: String,
+}
+
+/// Export all data to JSON, encrypted when a password is given
 #[tauri::command]
-pub async fn export_all_data(db: State<'_, Database>) -> Result<String, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+pub async fn export_all_data(
+    db: State<'_, Database>,
+    password: Option<String>,
+) -> Result<String, CommandError> {
+    let conn = db.pool.get()?;
 
     // Get settings
     let settings = conn
         .query_row(
-            "SELECT id, initial_capital, current_r_percent, default_min_rr, default_leverage, currency, enable_position_monitor, enable_api_connections, created_at, updated_at FROM settings WHERE id = 1",
+            "SELECT id, initial_capital, current_r_percent, default_min_rr, default_leverage, currency, enable_position_monitor, enable_api_connections, max_consecutive_losses, daily_loss_limit_r, trash_retention_days, sync_history_retention_days, sync_history_max_entries, sync_on_startup, mirror_stale_timeout_secs, require_biometric_unlock, breakeven_threshold, created_at, updated_at FROM settings WHERE id = 1",
             [],
             |row| {
                 Ok(Settings {
@@ -1274,17 +2444,46 @@ pub async fn export_all_data(db: State<'_, Database>) -> Result<String, String>
                     currency: row.get(5)?,
                     enable_position_monitor: row.get::<_, i32>(6)? == 1,
                     enable_api_connections: row.get::<_, i32>(7)? == 1,
-                    created_at: row.get(8)?,
-                    updated_at: row.get(9)?,
+                    max_consecutive_losses: row.get(8)?,
+                    daily_loss_limit_r: row.get(9)?,
+                    trash_retention_days: row.get(10)?,
+                    sync_history_retention_days: row.get(11)?,
+                    sync_history_max_entries: row.get(12)?,
+                    sync_on_startup: row.get::<_, i32>(13)? == 1,
+                    mirror_stale_timeout_secs: row.get(14)?,
+                    require_biometric_unlock: row.get::<_, i32>(15)? == 1,
+                    // A setting of this machine, whose URL may hold the proxy's password
+                    proxy_url: None,
+                    breakeven_threshold: row.get(16)?,
+                    created_at: row.get(17)?,
+                    updated_at: row.get(18)?,
                 })
             },
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
+
+    // Get all accounts
+    let mut stmt = conn
+        .prepare("SELECT * FROM accounts ORDER BY name")?;
+
+    let accounts = stmt
+        .query_map([], |row| {
+            Ok(Account {
+                id: row.get("id")?,
+                name: row.get("name")?,
+                exchange: row.get("exchange")?,
+                is_paper: row.get::<_, i32>("is_paper")? == 1,
+                initial_capital: row.get("initial_capital")?,
+                current_r_percent: row.get("current_r_percent")?,
+                currency: row.get("currency")?,
+                created_at: row.get("created_at")?,
+                updated_at: row.get("updated_at")?,
+            })
+        })?
+        .collect::<Result<Vec<Account>, _>>()?;
 
     // Get all trades
     let mut stmt = conn
-        .prepare("SELECT * FROM trades ORDER BY trade_date DESC")
-        .map_err(|e| e.to_string())?;
+        .prepare("SELECT * FROM trades ORDER BY trade_date DESC")?;
 
     let trades = stmt
         .query_map([], |row| {
@@ -1326,54 +2525,276 @@ pub async fn export_all_data(db: State<'_, Database>) -> Result<String, String>
                 execution_potential_profit: row.get("execution_potential_profit").ok(),
                 import_fingerprint: row.get("import_fingerprint").ok(),
                 import_source: row.get("import_source")?,
+                account_id: row.get("account_id").ok(),
+                grade: row.get("grade").ok(),
+                pre_emotion: row.get("pre_emotion").ok(),
+                post_emotion: row.get("post_emotion").ok(),
+                liquidated: row.get("liquidated").unwrap_or(false),
+                funding_pnl: None,
                 created_at: row.get("created_at")?,
                 updated_at: row.get("updated_at")?,
             })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<Trade>, _>>()
-        .map_err(|e| e.to_string())?;
+        })?
+        .collect::<Result<Vec<Trade>, _>>()?;
+
+    let capital_transactions = query_backup_rows(
+        &conn,
+        "SELECT id, transaction_type, amount, transaction_date, notes, account_id, created_at FROM capital_transactions ORDER BY transaction_date",
+        |row| {
+            Ok(CapitalTransaction {
+                id: row.get(0)?,
+                transaction_type: row.get(1)?,
+                amount: row.get(2)?,
+                transaction_date: row.get(3)?,
+                notes: row.get(4)?,
+                account_id: row.get(5)?,
+                created_at: row.get(6)?,
+            })
+        },
+    )?;
+
+    let tags = query_backup_rows(
+        &conn,
+        "SELECT id, name, color, created_at FROM tags ORDER BY name",
+        |row| {
+            Ok(Tag {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                color: row.get(2)?,
+                created_at: row.get(3)?,
+            })
+        },
+    )?;
+
+    let trade_tags = query_backup_rows(
+        &conn,
+        "SELECT trade_id, tag_id, created_at FROM trade_tags",
+        |row| {
+            Ok(TradeTagLink {
+                trade_id: row.get(0)?,
+                tag_id: row.get(1)?,
+                created_at: row.get(2)?,
+            })
+        },
+    )?;
+
+    let journal_entries = query_backup_rows(
+        &conn,
+        "SELECT id, entry_date, pre_market_plan, post_market_review, mood, created_at, updated_at FROM journal_entries ORDER BY entry_date",
+        |row| {
+            Ok(JournalEntry {
+                id: row.get(0)?,
+                entry_date: row.get(1)?,
+                pre_market_plan: row.get(2)?,
+                post_market_review: row.get(3)?,
+                mood: row.get(4)?,
+                created_at: row.get(5)?,
+                updated_at: row.get(6)?,
+            })
+        },
+    )?;
+
+    let journal_entry_trades = query_backup_rows(
+        &conn,
+        "SELECT entry_id, trade_id FROM journal_entry_trades",
+        |row| {
+            Ok(JournalTradeLink {
+                entry_id: row.get(0)?,
+                trade_id: row.get(1)?,
+            })
+        },
+    )?;
+
+    let api_credentials = query_backup_rows(
+        &conn,
+        "SELECT id, exchange, label, is_active, last_sync_timestamp, auto_sync_enabled, auto_sync_interval, live_mirror_enabled, created_at, updated_at, auto_sync_lookback_days, is_default, storage_backend, environment, base_url FROM api_credentials ORDER BY created_at",
+        |row| {
+            let id: String = row.get(0)?;
+            let api_key = retrieve_api_key(&id, "backup_export").unwrap_or_default();
+
+            Ok(ApiCredentialSafe {
+                id,
+                exchange: row.get(1)?,
+                label: row.get(2)?,
+                api_key_preview: ApiCredential::create_preview(&api_key),
+                is_active: row.get::<_, i32>(3)? == 1,
+                last_sync_timestamp: row.get(4)?,
+                auto_sync_enabled: row.get::<_, i32>(5)? == 1,
+                auto_sync_interval: row.get(6)?,
+                auto_sync_lookback_days: row.get(10)?,
+                live_mirror_enabled: row.get::<_, i32>(7)? == 1,
+                is_default: row.get::<_, i32>(11)? == 1,
+                storage_backend: row.get(12)?,
+                environment: row.get(13)?,
+                base_url: row.get(14)?,
+                auto_sync_paused: false,
+                auto_sync_disabled_reason: None,
+                created_at: row.get(8)?,
+                updated_at: row.get(9)?,
+            })
+        },
+    )?;
+
+    let api_sync_history = query_backup_rows(
+        &conn,
+        "SELECT id, credential_id, exchange, sync_type, last_sync_timestamp, trades_imported, trades_duplicated, last_trade_id, status, error_message, created_at FROM api_sync_history ORDER BY created_at",
+        |row| {
+            Ok(ApiSyncHistory {
+                id: row.get(0)?,
+                credential_id: row.get(1)?,
+                exchange: row.get(2)?,
+                sync_type: row.get(3)?,
+                last_sync_timestamp: row.get(4)?,
+                trades_imported: row.get(5)?,
+                trades_duplicated: row.get(6)?,
+                last_trade_id: row.get(7)?,
+                status: row.get(8)?,
+                error_message: row.get(9)?,
+                created_at: row.get(10)?,
+            })
+        },
+    )?;
 
     let backup = BackupData {
         settings,
+        accounts,
         trades,
+        capital_transactions,
+        tags,
+        trade_tags,
+        journal_entries,
+        journal_entry_trades,
+        api_credentials,
+        api_sync_history,
         export_date: Utc::now().to_rfc3339(),
-        version: "1.0.0".to_string(),
+        version: BACKUP_FORMAT_VERSION.to_string(),
     };
 
-    serde_json::to_string_pretty(&backup).map_err(|e| e.to_string())
+    let json = serde_json::to_string_pretty(&backup)?;
+    match password.filter(|password| !password.is_empty()) {
+        Some(password) => Ok(encrypt_backup(&json, &password)?),
+        None => Ok(json),
+    }
+}
+
+fn encrypt_backup(json: &str, password: &str) -> Result<String, CommandError> {
+    let data = encrypt_with_password(json.as_bytes(), password)
+        .map_err(|e| CommandError::Internal(e.to_string()))?;
+    let encrypted = EncryptedBackup {
+        encryption: BACKUP_ENCRYPTION.to_string(),
+        data,
+    };
+    Ok(serde_json::to_string_pretty(&encrypted)?)
+}
+
+/// Read a backup, decrypting it with `password` when it is encrypted
+fn parse_backup(json_data: &str, password: Option<&str>) -> Result<BackupData, CommandError> {
+    let invalid = |e: serde_json::Error| CommandError::Validation(format!("Invalid backup file: {}", e));
+    let encrypted = serde_json::from_str::<EncryptedBackup>(json_data)
+        .ok()
+        .filter(|encrypted| encrypted.encryption == BACKUP_ENCRYPTION);
+    let Some(encrypted) = encrypted else {
+        return serde_json::from_str(json_data).map_err(invalid);
+    };
+
+    let password = password
+        .filter(|password| !password.is_empty())
+        .ok_or_else(|| {
+            CommandError::Validation("This backup is encrypted, enter its password to import it".to_string())
+        })?;
+    let json = decrypt_with_password(&encrypted.data, password)
+        .map_err(|_| CommandError::Auth("Wrong password, or the backup is damaged".to_string()))?;
+    serde_json::from_slice(&json).map_err(invalid)
 }
 
-/// Import data from JSON backup
+fn query_backup_rows<T>(
+    conn: &rusqlite::Connection,
+    sql: &str,
+    map: impl FnMut(&rusqlite::Row) -> rusqlite::Result<T>,
+) -> rusqlite::Result<Vec<T>> {
+    let mut stmt = conn.prepare(sql)?;
+    stmt.query_map([], map)?.collect()
+}
+
+/// Import data from JSON backup; `password` decrypts an encrypted one
 #[tauri::command]
 pub async fn import_all_data(
     db: State<'_, Database>,
     json_data: String,
-) -> Result<(usize, usize), String> {
-    let backup: BackupData = serde_json::from_str(&json_data).map_err(|e| e.to_string())?;
+    password: Option<String>,
+) -> Result<(usize, usize), CommandError> {
+    let backup = parse_backup(&json_data, password.as_deref())?;
+    check_backup_version(&backup.version)?;
+
+    let mut conn = db.pool.get()?;
+
+    // All or nothing, a failing row must not leave a half-restored journal
+    let tx = conn.transaction()?;
+    let imported_trades = import_backup(&tx, backup)?;
+    tx.commit()?;
+
+    Ok((1, imported_trades)) // (settings_updated, trades_imported)
+}
 
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+/// Reject backups written in a newer format than this build understands
+fn check_backup_version(version: &str) -> Result<(), CommandError> {
+    let major = |v: &str| v.split('.').next().and_then(|m| m.parse::<u32>().ok());
+    match (major(version), major(BACKUP_FORMAT_VERSION)) {
+        (Some(found), Some(supported)) if found <= supported => Ok(()),
+        _ => Err(CommandError::Validation(format!(
+            "Backup format {} is not supported by this version (supports up to {}), please update the app",
+            version, BACKUP_FORMAT_VERSION
+        ))),
+    }
+}
 
-    // Update settings
+fn import_backup(conn: &rusqlite::Connection, backup: BackupData) -> rusqlite::Result<usize> {
+
+    // Update settings. require_biometric_unlock and proxy_url stay as they are on this machine.
     conn.execute(
-        "UPDATE settings SET initial_capital = ?, current_r_percent = ?, default_min_rr = ?, default_leverage = ?, currency = ?, updated_at = ? WHERE id = 1",
+        "UPDATE settings SET initial_capital = ?, current_r_percent = ?, default_min_rr = ?, default_leverage = ?, currency = ?, max_consecutive_losses = ?, daily_loss_limit_r = ?, trash_retention_days = ?, sync_history_retention_days = ?, sync_history_max_entries = ?, sync_on_startup = ?, mirror_stale_timeout_secs = ?, breakeven_threshold = ?, updated_at = ? WHERE id = 1",
         rusqlite::params![
             backup.settings.initial_capital,
             backup.settings.current_r_percent,
             backup.settings.default_min_rr,
             backup.settings.default_leverage,
             backup.settings.currency,
+            backup.settings.max_consecutive_losses,
+            backup.settings.daily_loss_limit_r,
+            backup.settings.trash_retention_days,
+            backup.settings.sync_history_retention_days,
+            backup.settings.sync_history_max_entries,
+            backup.settings.sync_on_startup as i32,
+            backup.settings.mirror_stale_timeout_secs,
+            backup.settings.breakeven_threshold,
             Utc::now().timestamp(),
         ],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
+
+    // Accounts go first so restored trades can reference them
+    for account in &backup.accounts {
+        conn.execute(
+            "REPLACE INTO accounts (id, name, exchange, is_paper, initial_capital, current_r_percent, currency, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                account.id,
+                account.name,
+                account.exchange,
+                account.is_paper as i32,
+                account.initial_capital,
+                account.current_r_percent,
+                account.currency,
+                account.created_at,
+                account.updated_at,
+            ],
+        )?;
+    }
 
     let mut imported_trades = 0;
 
     // Import trades (use REPLACE to overwrite existing trades)
     for trade in backup.trades {
         conn.execute(
-            "REPLACE INTO trades (id, pair, exchange, analysis_date, trade_date, close_date, status, portfolio_value, r_percent, min_rr, planned_pe, planned_sl, leverage, planned_tps, planned_entries, position_type, one_r, margin, position_size, quantity, planned_weighted_rr, effective_pe, effective_entries, exits, effective_weighted_rr, total_pnl, pnl_in_r, notes, import_fingerprint, import_source, execution_portfolio, execution_r_percent, execution_margin, execution_position_size, execution_quantity, execution_one_r, execution_potential_profit, created_at, updated_at, deleted_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            "REPLACE INTO trades (id, pair, exchange, analysis_date, trade_date, close_date, status, portfolio_value, r_percent, min_rr, planned_pe, planned_sl, leverage, planned_tps, planned_entries, position_type, one_r, margin, position_size, quantity, planned_weighted_rr, effective_pe, effective_entries, exits, effective_weighted_rr, total_pnl, pnl_in_r, notes, import_fingerprint, import_source, execution_portfolio, execution_r_percent, execution_margin, execution_position_size, execution_quantity, execution_one_r, execution_potential_profit, account_id, grade, pre_emotion, post_emotion, liquidated, created_at, updated_at, deleted_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             rusqlite::params![
                 trade.id,
                 trade.pair,
@@ -1412,22 +2833,132 @@ pub async fn import_all_data(
                 trade.execution_quantity,
                 trade.execution_one_r,
                 trade.execution_potential_profit,
+                trade.account_id,
+                trade.grade,
+                trade.pre_emotion,
+                trade.post_emotion,
+                trade.liquidated,
                 trade.created_at,
                 trade.updated_at,
                 None::<i64>, // deleted_at is NULL for imported trades
             ],
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
 
         imported_trades += 1;
     }
 
-    Ok((1, imported_trades)) // (settings_updated, trades_imported)
+    for transaction in &backup.capital_transactions {
+        conn.execute(
+            "REPLACE INTO capital_transactions (id, transaction_type, amount, transaction_date, notes, account_id, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                transaction.id,
+                transaction.transaction_type,
+                transaction.amount,
+                transaction.transaction_date,
+                transaction.notes,
+                transaction.account_id,
+                transaction.created_at,
+            ],
+        )?;
+    }
+
+    for tag in &backup.tags {
+        conn.execute(
+            "REPLACE INTO tags (id, name, color, created_at) VALUES (?, ?, ?, ?)",
+            rusqlite::params![tag.id, tag.name, tag.color, tag.created_at],
+        )?;
+    }
+
+    for link in &backup.trade_tags {
+        conn.execute(
+            "INSERT OR IGNORE INTO trade_tags (trade_id, tag_id, created_at) VALUES (?, ?, ?)",
+            rusqlite::params![link.trade_id, link.tag_id, link.created_at],
+        )?;
+    }
+
+    for entry in &backup.journal_entries {
+        conn.execute(
+            "REPLACE INTO journal_entries (id, entry_date, pre_market_plan, post_market_review, mood, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                entry.id,
+                entry.entry_date,
+                entry.pre_market_plan,
+                entry.post_market_review,
+                entry.mood,
+                entry.created_at,
+                entry.updated_at,
+            ],
+        )?;
+    }
+
+    for link in &backup.journal_entry_trades {
+        conn.execute(
+            "INSERT OR IGNORE INTO journal_entry_trades (entry_id, trade_id) VALUES (?, ?)",
+            rusqlite::params![link.entry_id, link.trade_id],
+        )?;
+    }
+
+    // Existing credentials are kept as they are. Restored ones point at the keychain like
+    // save_api_credentials does; without keys on this machine they stay inactive until
+    // the user re-enters them.
+    for credential in &backup.api_credentials {
+        let backend = api_key_backend(&credential.id);
+        let has_keys = backend.is_some();
+        let placeholder = format!("KEYCHAIN:{}", credential.id);
+        conn.execute(
+            "INSERT OR IGNORE INTO api_credentials
+                (id, exchange, label, api_key, api_secret, passphrase, is_active, last_sync_timestamp, auto_sync_enabled, auto_sync_interval, auto_sync_lookback_days, live_mirror_enabled, is_default, storage_backend, environment, base_url, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, NULL, ?, ?, ?, ?, ?, ?,
+                ? AND NOT EXISTS (SELECT 1 FROM api_credentials WHERE exchange = ? AND is_default = 1), ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                credential.id,
+                credential.exchange,
+                credential.label,
+                placeholder,
+                placeholder,
+                (credential.is_active && has_keys) as i32,
+                credential.last_sync_timestamp,
+                (credential.auto_sync_enabled && has_keys) as i32,
+                credential.auto_sync_interval,
+                credential.auto_sync_lookback_days,
+                (credential.live_mirror_enabled && has_keys) as i32,
+                credential.is_default as i32,
+                credential.exchange,
+                backend.unwrap_or(StorageBackend::Keychain).as_str(),
+                credential.environment,
+                credential.base_url,
+                credential.created_at,
+                credential.updated_at,
+            ],
+        )?;
+    }
+
+    for history in &backup.api_sync_history {
+        conn.execute(
+            "INSERT OR IGNORE INTO api_sync_history (id, credential_id, exchange, sync_type, last_sync_timestamp, trades_imported, trades_duplicated, last_trade_id, status, error_message, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                history.id,
+                history.credential_id,
+                history.exchange,
+                history.sync_type,
+                history.last_sync_timestamp,
+                history.trades_imported,
+                history.trades_duplicated,
+                history.last_trade_id,
+                history.status,
+                history.error_message,
+                history.created_at,
+            ],
+        )?;
+    }
+
+    Ok(imported_trades)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::commands::test_support::{insert_trade, test_conn, trade};
 
     #[test]
     fn test_backward_compatibility_import_source() {
@@ -1503,4 +3034,324 @@ mod tests {
         assert_eq!(trade.import_source, "CSV_IMPORT", "Import source should be preserved from JSON");
         assert_eq!(trade.pair, "ETH/USDT");
     }
+
+    #[test]
+    fn test_backup_version_check() {
+        assert!(check_backup_version("1.0.0").is_ok());
+        assert!(check_backup_version(BACKUP_FORMAT_VERSION).is_ok());
+        assert!(check_backup_version("3.0.0").is_err());
+        assert!(check_backup_version("garbage").is_err());
+    }
+
+    #[test]
+    fn test_encrypted_backup_roundtrip() {
+        let json = r#"{"settings": {"id": 1, "initial_capital": 5000.0, "current_r_percent": 0.01,
+            "default_min_rr": 2.0, "default_leverage": 5, "currency": "USD",
+            "created_at": 0, "updated_at": 0},
+            "trades": [], "export_date": "2026-01-01T00:00:00Z", "version": "2.0.0"}"#;
+        let encrypted = encrypt_backup(json, "s3cret-pass").unwrap();
+        assert!(!encrypted.contains("initial_capital"));
+
+        let backup = parse_backup(&encrypted, Some("s3cret-pass")).unwrap();
+        assert_eq!(backup.settings.initial_capital, 5000.0);
+        assert!(parse_backup(&encrypted, Some("wrong-pass")).is_err());
+        assert!(parse_backup(&encrypted, None).unwrap_err().to_string().contains("encrypted"));
+
+        // Plain backups ignore the password
+        assert!(parse_backup(json, Some("s3cret-pass")).is_ok());
+    }
+
+    #[test]
+    fn test_import_backup_restores_related_tables() {
+        let conn = test_conn();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+
+        // A 1.0.0 backup has no related sections and still deserializes
+        let old: BackupData = serde_json::from_str(
+            r#"{"settings": {"id": 1, "initial_capital": 5000.0, "current_r_percent": 0.01,
+                "default_min_rr": 2.0, "default_leverage": 5, "currency": "USD",
+                "created_at": 0, "updated_at": 0},
+                "trades": [], "export_date": "2026-01-01T00:00:00Z", "version": "1.0.0"}"#,
+        )
+        .unwrap();
+        assert!(old.tags.is_empty() && old.api_credentials.is_empty());
+
+        let mut backup = old;
+        backup.tags.push(Tag {
+            id: "TAG-1".to_string(),
+            name: "breakout".to_string(),
+            color: None,
+            created_at: 0,
+        });
+        backup.journal_entries.push(JournalEntry {
+            id: "JRN-1".to_string(),
+            entry_date: "2026-01-01".to_string(),
+            pre_market_plan: Some("Wait for the retest".to_string()),
+            post_market_review: None,
+            mood: Some(4),
+            created_at: 0,
+            updated_at: 0,
+        });
+        backup.api_credentials.push(ApiCredentialSafe {
+            id: "CRED-missing-keys".to_string(),
+            exchange: "bitget".to_string(),
+            label: "Main".to_string(),
+            api_key_preview: "****abcd".to_string(),
+            is_active: true,
+            last_sync_timestamp: Some(100),
+            auto_sync_enabled: true,
+            auto_sync_interval: 3600,
+            auto_sync_lookback_days: Some(30),
+            live_mirror_enabled: false,
+            is_default: true,
+            storage_backend: "keychain".to_string(),
+            environment: "demo".to_string(),
+            base_url: None,
+            auto_sync_paused: false,
+            auto_sync_disabled_reason: None,
+            created_at: 0,
+            updated_at: 0,
+        });
+        backup.api_sync_history.push(ApiSyncHistory {
+            id: "SYNC-1".to_string(),
+            credential_id: "CRED-missing-keys".to_string(),
+            exchange: "bitget".to_string(),
+            sync_type: "MANUAL".to_string(),
+            last_sync_timestamp: 100,
+            trades_imported: 3,
+            trades_duplicated: 0,
+            last_trade_id: None,
+            status: "SUCCESS".to_string(),
+            error_message: None,
+            created_at: 100,
+        });
+
+        import_backup(&conn, backup).unwrap();
+
+        let count = |table: &str| -> i64 {
+            conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(count("tags"), 1);
+        assert_eq!(count("journal_entries"), 1);
+        assert_eq!(count("api_sync_history"), 1);
+
+        // No keys in the keychain for this credential, so it must not start syncing
+        let (active, auto_sync, environment): (i32, i32, String) = conn
+            .query_row(
+                "SELECT is_active, auto_sync_enabled, environment FROM api_credentials WHERE id = 'CRED-missing-keys'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!((active, auto_sync), (0, 0));
+        assert_eq!(environment, "demo");
+    }
+
+    #[test]
+    fn test_fingerprint_identity_strips_execution_tail() {
+        let fp = "csv|bitget|inj/usdt|short|2026-01-05 10:00:00|2026-01-05 12:00:00|1645.20000000|-90.35400000";
+        assert_eq!(fingerprint_identity(fp), "csv|bitget|inj/usdt|short|2026-01-05 10:00:00|");
+        assert_eq!(fingerprint_identity("csv|bitget|short"), "csv|bitget|short");
+    }
+
+    #[test]
+    fn test_merge_existing_refreshes_corrected_trade() {
+        let conn = test_conn();
+
+        let old_fp = "csv|bitget|btc/usdt|long|2026-01-05 10:00:00|2026-01-05 12:00:00|1.00000000|50.00000000";
+        insert_trade(&conn, &Trade {
+            close_date: Some(0),
+            status: "WIN".to_string(),
+            min_rr: 0.0,
+            planned_weighted_rr: 0.0,
+            exits: Some("[]".to_string()),
+            total_pnl: Some(50.0),
+            notes: "Imported from BitGet | Fees: $1.00".to_string(),
+            import_fingerprint: Some(old_fp.to_string()),
+            import_source: "CSV_IMPORT".to_string(),
+            ..trade("TRADE-1")
+        });
+
+        let new_fp = "csv|bitget|btc/usdt|long|2026-01-05 10:00:00|2026-01-05 12:00:00|1.00000000|45.00000000";
+        assert_eq!(find_imported_trade(&conn, new_fp, false), None);
+        let Some(ExistingImport::Refresh(id)) = find_imported_trade(&conn, new_fp, true) else {
+            panic!("expected identity match in merge mode");
+        };
+
+        let exec = MergedExecution {
+            fingerprint: new_fp,
+            status: "WIN",
+            close_date: Some(0),
+            quantity: 2.0,
+            effective_pe: 100.0,
+            effective_entries: None,
+            exits: "[]",
+            total_pnl: Some(45.0),
+            notes: "Imported from BitGet | Fees: $6.00",
+        };
+        assert!(merge_imported_trade(&conn, &id, &exec, 0.5).unwrap());
+        // Same data again is a no-op, reported as a duplicate by the callers
+        assert!(!merge_imported_trade(&conn, &id, &exec, 0.5).unwrap());
+
+        let (pnl, fp, pnl_in_r, margin): (f64, String, f64, f64) = conn
+            .query_row(
+                "SELECT total_pnl, import_fingerprint, pnl_in_r, margin FROM trades WHERE id = 'TRADE-1'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(pnl, 45.0);
+        assert_eq!(fp, new_fp);
+        // Derived values follow the merged execution
+        assert_eq!((pnl_in_r, margin), (0.45, 200.0));
+    }
+
+    #[test]
+    fn test_detect_import_format() {
+        let bitget = "Futures,Opening time,Average entry price,Average closing price,Closed amount,x,x,Realized PnL,x,Opening fee,Closing fee,Closing time\n\
+            INJUSDT Short·Isolated,2026-01-05 10:00:00,20.1,19.5,1645.2INJ,x,x,-90.354USDT,x,-1.2USDT,-1.1USDT,2026-01-05 12:00:00";
+        assert_eq!(detect_import_format(bitget.as_bytes(), None), Ok(ImportFormat::BitgetCsv));
+
+        let blofin_header = "Underlying Asset,Margin Mode,Leverage,Order Time,Side,Avg Fill,Price,Filled,Total,PNL,PNL%,Fee,Order Options,Reduce-only,Status";
+        assert_eq!(detect_import_format(blofin_header.as_bytes(), None), Ok(ImportFormat::BlofinCsv));
+        // Any other 15-column export isn't taken for BloFin
+        let other = (0..15).map(|i| format!("c{}", i)).collect::<Vec<_>>().join(",");
+        assert!(detect_import_format(other.as_bytes(), None).is_err());
+
+        assert_eq!(detect_import_format(b"PK\x03\x04rest-of-zip", None), Ok(ImportFormat::BingxXlsx));
+        assert!(detect_import_format(b"a,b,c\n1,2,3", None).is_err());
+    }
+
+    #[test]
+    fn test_csv_dialect_detection() {
+        let eu = "Futures;Opening time;Price\nINJUSDT Short·Isolated;2026-01-05 10:00:00;1.645,2INJ";
+        let dialect = detect_csv_dialect(eu, None);
+        assert_eq!(dialect, CsvDialect { delimiter: b';', decimal_comma: true });
+        assert_eq!(
+            split_csv_line("INJUSDT Short·Isolated;2026-01-05 10:00:00;1.645,2INJ", dialect),
+            vec!["INJUSDT Short·Isolated", "2026-01-05 10:00:00", "1645.2INJ"]
+        );
+
+        let tabs = "a\tb\tc\n1.5\t2\t3";
+        assert_eq!(detect_csv_dialect(tabs, None), CsvDialect { delimiter: b'\t', decimal_comma: false });
+
+        let plain = "a,b,c\n\"1,5\",2,3";
+        let dialect = detect_csv_dialect(plain, None);
+        assert_eq!(dialect, CsvDialect::default());
+        assert_eq!(split_csv_line("\"1,5\",2,3", dialect), vec!["1,5", "2", "3"]);
+
+        // One comma-looking note doesn't outvote rows of dot decimals, and US-grouped
+        // "1,234.5" is not a decimal-comma number
+        let mixed = "a;b;c\n1.5;note 1,5;x\n2.5;3;x\n1,234.5;4.5;x";
+        assert!(!detect_csv_dialect(mixed, None).decimal_comma);
+        assert!(!is_decimal_comma_number("1,234.5"));
+        assert!(!is_decimal_comma_number("1,5,2"));
+        assert!(is_decimal_comma_number("-90,354 USDT"));
+
+        let forced = CsvFormat { delimiter: Some(CsvDelimiter::Semicolon), decimal_comma: Some(true) };
+        assert_eq!(detect_csv_dialect("a,b;c\n1,5;2", Some(forced)), CsvDialect { delimiter: b';', decimal_comma: true });
+    }
+
+    #[test]
+    fn test_bitget_spot_pairs_buys_and_sells() {
+        let csv = "Order Time,Trading pair,Direction,Average Price,Filled amount,Fee,Status\n\
+            2026-01-05 10:00:00,BTCUSDT,Buy,100,1,0.1,Filled\n\
+            2026-01-05 11:00:00,BTCUSDT,Buy,110,1,0.1,Filled\n\
+            2026-01-05 11:30:00,ETHUSDT,Buy,10,1,0,Cancelled\n\
+            2026-01-05 12:00:00,BTCUSDT,Sell,120,2,0.2,Filled\n\
+            2026-01-05 13:00:00,SOLUSDT,Sell,50,1,0,Filled";
+
+        assert_eq!(detect_import_format(csv.as_bytes(), None), Ok(ImportFormat::BitgetSpotCsv));
+
+        let (orders, errors) = parse_spot_orders_from_csv(csv, None).unwrap();
+        assert_eq!(orders.len(), 4, "cancelled order is skipped");
+        assert!(errors.is_empty());
+
+        let positions = group_spot_orders_into_positions(orders);
+        assert_eq!(positions.len(), 1, "orphaned SOL sell does not open a position");
+
+        let pos = &positions[0];
+        assert_eq!(pos.pair, "BTC/USDT");
+        assert!((pos.entry_price - 105.0).abs() < 1e-9);
+        assert!((pos.realized_pnl - (30.0 - 0.4)).abs() < 1e-9);
+        assert_eq!(pos.closing_time, "2026-01-05 12:00:00");
+    }
+
+    #[test]
+    fn test_bitget_spot_fee_currencies_and_bad_rows() {
+        let csv = "Order Time,Trading pair,Direction,Average Price,Filled amount,Fee\n\
+            2026-01-05 10:00:00,BTCUSDT,Buy,100,1,0.001BTC\n\
+            2026-01-05 11:00:00,BTCUSDT,Sell,120,1,0.12USDT\n\
+            2026-01-05 12:00:00,ETHUSDT,Buy,10,1,0.5BGB\n\
+            2026-01-05 13:00:00,ETHUSDT,Buy,n/a,1,0";
+
+        let (orders, errors) = parse_spot_orders_from_csv(csv, None).unwrap();
+        assert_eq!(orders.len(), 2);
+        assert!((orders[0].fee - 0.1).abs() < 1e-9, "base-coin fee converts at the fill price");
+        assert!((orders[1].fee - 0.12).abs() < 1e-9);
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("Line 4: Fee paid in BGB"));
+        assert!(errors[1].starts_with("Line 5: Invalid price"));
+    }
+
+    #[test]
+    fn test_bingx_sheet_with_chinese_headers() {
+        let s = |v: &str| Data::String(v.to_string());
+        let title = vec![s("订单历史")];
+        let header = vec![
+            s("时间（UTC+8）"), s("交易对"), s("类型"), s("杠杆"), s("成交价"),
+            s("数量"), s("手续费"), s("已实现盈亏"),
+        ];
+        let open = vec![
+            s("2026/02/06 00:24:54"), s("BTC-USDT"), s("开多"), Data::Float(10.0),
+            Data::Float(100.0), Data::Float(2.0), Data::Float(-0.1), Data::Float(0.0),
+        ];
+        let close = vec![
+            s("2026/02/06 01:00:00"), s("BTC-USDT"), s("平多"), Data::Float(10.0),
+            Data::Float(110.0), Data::Float(2.0), Data::Float(-0.1), Data::Float(20.0),
+        ];
+        let rows: Vec<&[Data]> = vec![&title, &header, &open, &close];
+
+        let orders = parse_bingx_sheet(&rows, false);
+        assert_eq!(orders.len(), 2);
+        assert_eq!(orders[0].pair, "BTC/USDT");
+        assert_eq!(orders[0].order_time, "2026-02-06 00:24:54");
+        assert!(orders[0].is_entry);
+        assert!(!orders[1].is_entry);
+        assert_eq!(orders[1].direction, "LONG");
+        assert_eq!(orders[1].realized_pnl, 20.0);
+
+        // Headerless sheets only fall back to the fixed layout on the first sheet
+        let headerless: Vec<&[Data]> = vec![&open, &close];
+        assert!(parse_bingx_sheet(&headerless, false).is_empty());
+    }
+
+    #[test]
+    fn test_blofin_unclosed_position_kept_as_open() {
+        let order = |time: &str, side: &str, qty: f64, reduce_only: bool| BlofinOrder {
+            asset: "BTCUSDT".to_string(),
+            margin_mode: "Cross".to_string(),
+            leverage: 10,
+            order_time: time.to_string(),
+            side: side.to_string(),
+            avg_fill: 100.0,
+            filled_qty: qty,
+            pnl: if reduce_only { 5.0 } else { 0.0 },
+            fee: 0.1,
+            is_reduce_only: reduce_only,
+        };
+        let orders = vec![
+            order("2026-01-05 10:00:00", "Buy", 2.0, false),
+            order("2026-01-05 11:00:00", "Sell", 1.0, true),
+        ];
+
+        assert!(group_blofin_orders_into_positions(orders.clone(), false).is_empty());
+
+        let positions = group_blofin_orders_into_positions(orders, true);
+        assert_eq!(positions.len(), 1);
+        assert!(positions[0].is_open);
+        assert_eq!(positions[0].position_type, "LONG");
+        assert_eq!(positions[0].realized_pnl, 5.0);
+    }
 }
@@ -0,0 +1,268 @@
+use tauri::State;
+use crate::db::Database;
+use crate::commands::error::CommandError;
+use crate::models::{CreateJournalEntryInput, JournalEntry, Trade, UpdateJournalEntryInput};
+use crate::commands::trades::map_row_to_trade;
+use chrono::Utc;
+use rusqlite::OptionalExtension;
+
+fn map_row_to_journal_entry(row: &rusqlite::Row) -> rusqlite::Result<JournalEntry> {
+    Ok(JournalEntry {
+        id: row.get("id")?,
+        entry_date: row.get("entry_date")?,
+        pre_market_plan: row.get("pre_market_plan")?,
+        post_market_review: row.get("post_market_review")?,
+        mood: row.get("mood")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+fn validate_mood(mood: Option<i32>) -> Result<(), CommandError> {
+    match mood {
+        Some(m) if !(1..=5).contains(&m) => {
+            Err(CommandError::Validation(format!("Mood must be between 1 and 5, got {}", m)))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Journal entries, newest first, optionally between two YYYY-MM-DD dates (inclusive)
+#[tauri::command]
+pub async fn get_journal_entries(
+    db: State<'_, Database>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<Vec<JournalEntry>, CommandError> {
+    let conn = db.pool.get()?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM journal_entries
+             WHERE entry_date >= COALESCE(?1, entry_date) AND entry_date <= COALESCE(?2, entry_date)
+             ORDER BY entry_date DESC",
+        )?;
+
+    let entries = stmt
+        .query_map(rusqlite::params![start_date, end_date], map_row_to_journal_entry)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(entries)
+}
+
+#[tauri::command]
+pub async fn get_journal_entry(
+    db: State<'_, Database>,
+    id: String,
+) -> Result<JournalEntry, CommandError> {
+    let conn = db.pool.get()?;
+
+    conn.query_row("SELECT * FROM journal_entries WHERE id = ?", [&id], map_row_to_journal_entry)
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn create_journal_entry(
+    db: State<'_, Database>,
+    entry: CreateJournalEntryInput,
+) -> Result<JournalEntry, CommandError> {
+    chrono::NaiveDate::parse_from_str(&entry.entry_date, "%Y-%m-%d")
+        .map_err(|_| CommandError::Validation(format!("Invalid date: {}", entry.entry_date)))?;
+    validate_mood(entry.mood)?;
+
+    let id = {
+        let conn = db.pool.get()?;
+
+        let id = format!("JOURNAL-{}-{}", Utc::now().timestamp_millis(), uuid::Uuid::new_v4());
+        let now = Utc::now().timestamp();
+
+        conn.execute(
+            "INSERT INTO journal_entries (id, entry_date, pre_market_plan, post_market_review, mood, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                id,
+                entry.entry_date,
+                entry.pre_market_plan,
+                entry.post_market_review,
+                entry.mood,
+                now,
+                now
+            ],
+        )
+        .map_err(|e| {
+            if e.to_string().contains("UNIQUE constraint failed") {
+                CommandError::Validation(format!("A journal entry for {} already exists", entry.entry_date))
+            } else {
+                CommandError::from(e)
+            }
+        })?;
+
+        id
+    };
+
+    get_journal_entry(db, id).await
+}
+
+#[tauri::command]
+pub async fn update_journal_entry(
+    db: State<'_, Database>,
+    id: String,
+    entry: UpdateJournalEntryInput,
+) -> Result<JournalEntry, CommandError> {
+    validate_mood(entry.mood)?;
+
+    {
+        let conn = db.pool.get()?;
+
+        let mut updates = vec!["updated_at = ?"];
+        let mut values: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(Utc::now().timestamp())];
+
+        if let Some(val) = entry.pre_market_plan {
+            updates.push("pre_market_plan = ?");
+            values.push(Box::new(val));
+        }
+        if let Some(val) = entry.post_market_review {
+            updates.push("post_market_review = ?");
+            values.push(Box::new(val));
+        }
+        if let Some(val) = entry.mood {
+            updates.push("mood = ?");
+            values.push(Box::new(val));
+        }
+
+        let query = format!("UPDATE journal_entries SET {} WHERE id = ?", updates.join(", "));
+        values.push(Box::new(id.clone()));
+
+        let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+        conn.execute(&query, params.as_slice())?;
+    }
+
+    get_journal_entry(db, id).await
+}
+
+#[tauri::command]
+pub async fn delete_journal_entry(
+    db: State<'_, Database>,
+    id: String,
+) -> Result<(), CommandError> {
+    let conn = db.pool.get()?;
+    conn.execute("DELETE FROM journal_entry_trades WHERE entry_id = ?", [&id])?;
+    conn.execute("DELETE FROM journal_entries WHERE id = ?", [&id])?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn link_journal_trade(
+    db: State<'_, Database>,
+    entry_id: String,
+    trade_id: String,
+) -> Result<(), CommandError> {
+    let conn = db.pool.get()?;
+    conn.execute(
+        "INSERT OR IGNORE INTO journal_entry_trades (entry_id, trade_id) VALUES (?, ?)",
+        rusqlite::params![entry_id, trade_id],
+    )?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn unlink_journal_trade(
+    db: State<'_, Database>,
+    entry_id: String,
+    trade_id: String,
+) -> Result<(), CommandError> {
+    let conn = db.pool.get()?;
+    conn.execute(
+        "DELETE FROM journal_entry_trades WHERE entry_id = ? AND trade_id = ?",
+        rusqlite::params![entry_id, trade_id],
+    )?;
+    Ok(())
+}
+
+/// Link every trade opened on the entry's day (in the user's local time) to the entry.
+/// Returns the number of newly linked trades.
+#[tauri::command]
+pub async fn link_journal_day_trades(
+    db: State<'_, Database>,
+    entry_id: String,
+    utc_offset_minutes: Option<i32>,
+) -> Result<usize, CommandError> {
+    let conn = db.pool.get()?;
+    link_day_trades(&conn, &entry_id, utc_offset_minutes.unwrap_or(0))
+}
+
+fn link_day_trades(conn: &rusqlite::Connection, entry_id: &str, utc_offset_minutes: i32) -> Result<usize, CommandError> {
+    let entry_date: String = conn
+        .query_row("SELECT entry_date FROM journal_entries WHERE id = ?", [entry_id], |row| row.get(0))
+        .optional()?
+        .ok_or_else(|| CommandError::NotFound(format!("Journal entry {} not found", entry_id)))?;
+
+    let invalid_date = || CommandError::Internal(format!("Invalid date: {}", entry_date));
+    let day = chrono::NaiveDate::parse_from_str(&entry_date, "%Y-%m-%d").map_err(|_| invalid_date())?;
+    let day_start = day
+        .and_hms_opt(0, 0, 0)
+        .ok_or_else(invalid_date)?
+        .and_utc()
+        .timestamp()
+        - utc_offset_minutes as i64 * 60;
+
+    conn.execute(
+        "INSERT OR IGNORE INTO journal_entry_trades (entry_id, trade_id)
+         SELECT ?, id FROM trades
+         WHERE deleted_at IS NULL AND trade_date >= ? AND trade_date < ?",
+        rusqlite::params![entry_id, day_start, day_start + 86_400],
+    )
+    .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn get_journal_entry_trades(
+    db: State<'_, Database>,
+    entry_id: String,
+) -> Result<Vec<Trade>, CommandError> {
+    let conn = db.pool.get()?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.* FROM trades t
+             JOIN journal_entry_trades j ON j.trade_id = t.id
+             WHERE j.entry_id = ? AND t.deleted_at IS NULL
+             ORDER BY t.trade_date ASC",
+        )?;
+
+    let trades = stmt
+        .query_map([&entry_id], map_row_to_trade)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(trades)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::{insert_trade, test_conn, trade};
+
+    #[test]
+    fn test_link_day_trades_uses_local_day() {
+        let conn = test_conn();
+
+        // 2026-01-05 23:30 UTC is already 2026-01-06 in UTC+2
+        for (id, trade_date) in [("T1", 1_767_655_800_i64), ("T2", 1_767_603_600)] {
+            insert_trade(&conn, &Trade { analysis_date: trade_date, trade_date, ..trade(id) });
+        }
+        conn.execute(
+            "INSERT INTO journal_entries (id, entry_date, created_at, updated_at) VALUES ('J1', '2026-01-06', 0, 0)",
+            [],
+        )
+        .unwrap();
+
+        assert_eq!(link_day_trades(&conn, "J1", 120).unwrap(), 1);
+        let linked: String = conn
+            .query_row("SELECT trade_id FROM journal_entry_trades WHERE entry_id = 'J1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(linked, "T1");
+
+        // Re-linking is idempotent
+        assert_eq!(link_day_trades(&conn, "J1", 120).unwrap(), 0);
+    }
+}
@@ -1,7 +1,10 @@
-use tauri::{AppHandle, Manager, State};
+use tauri::{AppHandle, State};
 use std::sync::Arc;
+use crate::api::live_mirror::MirrorHealth;
 use crate::api::LiveMirrorManager;
 use crate::db::Database;
+use crate::commands::error::CommandError;
+use crate::models::LiveMirrorEvent;
 
 /// Start live trade mirroring for a credential
 #[tauri::command]
@@ -10,42 +13,26 @@ pub async fn start_live_mirroring(
     db: State<'_, Database>,
     mirror_manager: State<'_, Arc<LiveMirrorManager>>,
     credential_id: String,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     // Check if position monitor feature is enabled
     let enabled = {
-        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        let conn = db.pool.get()?;
         let enabled: i32 = conn
             .query_row(
                 "SELECT enable_position_monitor FROM settings WHERE id = 1",
                 [],
                 |row| row.get(0),
-            )
-            .map_err(|e| e.to_string())?;
+            )?;
         enabled
     }; // conn is dropped here
 
     if enabled == 0 {
-        return Err("Position monitoring feature is currently disabled".to_string());
+        return Err(CommandError::Validation("Position monitoring feature is currently disabled".to_string()));
     }
 
-    // Create Arc wrapper for database
-    // Note: This is a simplified approach. In production, consider restructuring
-    // to share the database connection more efficiently
-    let db_arc = Arc::new(Database {
-        conn: std::sync::Mutex::new(
-            // This creates a connection to the same database file
-            rusqlite::Connection::open(
-                app_handle.path()
-                    .app_data_dir()
-                    .expect("Failed to resolve app data directory")
-                    .join("trading_journal.db")
-            )
-            .map_err(|e| e.to_string())?
-        ),
-    });
-
+    // The mirror task shares the app's connection pool; cloning the handle is cheap
     mirror_manager
-        .start_mirroring(credential_id, app_handle, db_arc)
+        .start_mirroring(credential_id, app_handle, db.inner().clone())
         .await
 }
 
@@ -54,7 +41,7 @@ pub async fn start_live_mirroring(
 pub async fn stop_live_mirroring(
     mirror_manager: State<'_, Arc<LiveMirrorManager>>,
     credential_id: String,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     mirror_manager.stop_mirroring(&credential_id).await
 }
 
@@ -63,30 +50,37 @@ pub async fn stop_live_mirroring(
 pub async fn is_live_mirroring_active(
     mirror_manager: State<'_, Arc<LiveMirrorManager>>,
     credential_id: String,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     Ok(mirror_manager.is_active(&credential_id).await)
 }
 
+/// Connection health of every running mirror, to spot one that silently stopped
+#[tauri::command]
+pub async fn get_mirror_health(
+    mirror_manager: State<'_, Arc<LiveMirrorManager>>,
+) -> Result<Vec<MirrorHealth>, CommandError> {
+    Ok(mirror_manager.health().await)
+}
+
 /// Toggle live mirroring setting for a credential
 #[tauri::command]
 pub async fn toggle_live_mirroring(
     db: State<'_, Database>,
     credential_id: String,
     enabled: bool,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     // Check if position monitor feature is enabled
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let conn = db.pool.get()?;
     let feature_enabled: i32 = conn
         .query_row(
             "SELECT enable_position_monitor FROM settings WHERE id = 1",
             [],
             |row| row.get(0),
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
 
     if feature_enabled == 0 {
         drop(conn);
-        return Err("Position monitoring feature is currently disabled".to_string());
+        return Err(CommandError::Validation("Position monitoring feature is currently disabled".to_string()));
     }
 
     let now = chrono::Utc::now().timestamp();
@@ -94,8 +88,7 @@ pub async fn toggle_live_mirroring(
     conn.execute(
         "UPDATE api_credentials SET live_mirror_enabled = ?, updated_at = ? WHERE id = ?",
         rusqlite::params![enabled as i32, now, &credential_id],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
 
     Ok(())
 }
@@ -104,12 +97,11 @@ pub async fn toggle_live_mirroring(
 #[tauri::command]
 pub async fn get_live_mirroring_status(
     db: State<'_, Database>,
-) -> Result<Vec<LiveMirrorStatus>, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+) -> Result<Vec<LiveMirrorStatus>, CommandError> {
+    let conn = db.pool.get()?;
 
     let mut stmt = conn
-        .prepare("SELECT id, exchange, label, live_mirror_enabled FROM api_credentials WHERE is_active = 1")
-        .map_err(|e| e.to_string())?;
+        .prepare("SELECT id, exchange, label, live_mirror_enabled FROM api_credentials WHERE is_active = 1")?;
 
     let statuses = stmt
         .query_map([], |row| {
@@ -119,14 +111,49 @@ pub async fn get_live_mirroring_status(
                 label: row.get(2)?,
                 enabled: row.get::<_, i32>(3)? == 1,
             })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
 
     Ok(statuses)
 }
 
+/// Live mirror event log, newest first. Covers all credentials when `credential_id` is
+/// None; `limit` defaults to 500 entries.
+#[tauri::command]
+pub async fn get_live_mirror_events(
+    db: State<'_, Database>,
+    credential_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<LiveMirrorEvent>, CommandError> {
+    let conn = db.pool.get()?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, credential_id, event_type, pos_id, trade_id, message, payload, created_at
+             FROM live_mirror_events
+             WHERE ?1 IS NULL OR credential_id = ?1
+             ORDER BY created_at DESC, rowid DESC
+             LIMIT ?2",
+        )?;
+
+    let events = stmt
+        .query_map(rusqlite::params![credential_id, limit.unwrap_or(500)], |row| {
+            Ok(LiveMirrorEvent {
+                id: row.get(0)?,
+                credential_id: row.get(1)?,
+                event_type: row.get(2)?,
+                pos_id: row.get(3)?,
+                trade_id: row.get(4)?,
+                message: row.get(5)?,
+                payload: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(events)
+}
+
 #[derive(serde::Serialize)]
 pub struct LiveMirrorStatus {
     pub credential_id: String,
@@ -1,21 +1,60 @@
+pub mod accounts;
+pub mod app_lock;
 pub mod api_sync;
+pub mod backups;
+pub mod capital;
 pub mod debug;
+pub mod demo_data;
+pub mod encryption;
+pub mod error;
+pub mod funding;
+pub mod health;
 pub mod import;
+pub mod journal;
 pub mod live_mirror;
 pub mod open_orders;
+pub mod planned_trades;
 pub mod positions;
+pub mod price_alerts;
+pub mod public_ip;
+pub mod risk;
 pub mod settings;
+pub mod simulation;
 pub mod stats;
 pub mod sync_scheduler;
+pub mod tags;
+#[cfg(test)]
+pub(crate) mod test_support;
+pub mod trade_links;
+pub mod trade_metrics;
+pub mod trade_revisions;
 pub mod trades;
 
+pub use accounts::*;
+pub use app_lock::*;
 pub use api_sync::*;
+pub use backups::*;
+pub use capital::*;
 pub use debug::*;
+pub use demo_data::*;
+pub use encryption::*;
+pub use funding::*;
+pub use health::*;
 pub use import::*;
+pub use journal::*;
 pub use live_mirror::*;
 pub use open_orders::*;
+pub use planned_trades::*;
 pub use positions::*;
+pub use price_alerts::*;
+pub use public_ip::*;
+pub use risk::*;
 pub use settings::*;
+pub use simulation::*;
 pub use stats::*;
 pub use sync_scheduler::*;
+pub use tags::*;
+pub use trade_links::*;
+pub use trade_metrics::*;
+pub use trade_revisions::*;
 pub use trades::*;
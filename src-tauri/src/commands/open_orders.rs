@@ -1,6 +1,9 @@
 use tauri::State;
 use serde::{Deserialize, Serialize};
+use crate::commands::api_sync::{default_credential_id, load_environment};
 use crate::db::Database;
+use crate::commands::error::CommandError;
+use rusqlite::OptionalExtension;
 use crate::api::{
     bitget::{BitgetClient, types::{PendingOrdersRequest, BitgetPendingOrder}},
     credentials::{retrieve_api_key, retrieve_api_secret, retrieve_passphrase},
@@ -27,7 +30,11 @@ pub struct OpenOrder {
 /// Request for fetching open orders
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FetchOpenOrdersRequest {
-    pub credential_id: String,
+    /// None uses the default credential of `exchange` (or the only default credential)
+    #[serde(default)]
+    pub credential_id: Option<String>,
+    #[serde(default)]
+    pub exchange: Option<String>,
     pub symbol: Option<String>,
 }
 
@@ -60,35 +67,39 @@ impl From<BitgetPendingOrder> for OpenOrder {
 pub async fn fetch_open_orders(
     db: State<'_, Database>,
     request: FetchOpenOrdersRequest,
-) -> Result<Vec<OpenOrder>, String> {
+) -> Result<Vec<OpenOrder>, CommandError> {
+    let credential_id = default_credential_id(&db, request.credential_id, request.exchange)?;
+
     // Fetch and decrypt credentials
-    let (exchange, api_key, api_secret, passphrase) = {
-        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let (exchange, environment, api_key, api_secret, passphrase) = {
+        let conn = db.pool.get()?;
 
         // Get credential
         let exchange: String = conn
             .query_row(
                 "SELECT exchange FROM api_credentials WHERE id = ?",
-                [&request.credential_id],
+                [&credential_id],
                 |row| row.get(0),
             )
-            .map_err(|e| format!("Credential not found: {}", e))?;
+            .optional()?
+            .ok_or_else(|| CommandError::NotFound(format!("Credential {} not found", credential_id)))?;
+        let environment = load_environment(&conn, &credential_id)?;
 
         // Retrieve credentials from system keychain
-        let api_key = retrieve_api_key(&request.credential_id).map_err(|e| e.to_string())?;
-        let api_secret = retrieve_api_secret(&request.credential_id).map_err(|e| e.to_string())?;
-        let passphrase = retrieve_passphrase(&request.credential_id).unwrap_or_default();
+        let api_key = retrieve_api_key(&credential_id, "open_orders")?;
+        let api_secret = retrieve_api_secret(&credential_id, "open_orders")?;
+        let passphrase = retrieve_passphrase(&credential_id, "open_orders").unwrap_or_default();
 
-        (exchange, api_key, api_secret, passphrase)
+        (exchange, environment, api_key, api_secret, passphrase)
     };
 
     // Currently only Bitget is supported
     if exchange != "bitget" {
-        return Err(format!("Exchange '{}' not supported for open orders", exchange));
+        return Err(CommandError::Validation(format!("Exchange '{}' not supported for open orders", exchange)));
     }
 
     // Create Bitget client
-    let client = BitgetClient::new(api_key, api_secret, passphrase);
+    let client = BitgetClient::new(api_key, api_secret, passphrase).with_environment(&environment);
 
     // Fetch pending orders
     let pending_orders_request = PendingOrdersRequest {
@@ -98,8 +109,7 @@ pub async fn fetch_open_orders(
     };
 
     let pending_data = client.fetch_pending_orders(&pending_orders_request)
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
 
     // Convert to frontend-friendly format
     let orders: Vec<OpenOrder> = pending_data
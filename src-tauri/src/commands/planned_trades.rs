@@ -0,0 +1,252 @@
+use tauri::State;
+use crate::commands::trades::get_trade;
+use crate::db::Database;
+use crate::commands::error::CommandError;
+use crate::models::{PlannedTrade, PlannedTradeInput, Trade};
+use chrono::Utc;
+use rusqlite::OptionalExtension;
+
+fn map_row_to_planned_trade(row: &rusqlite::Row) -> rusqlite::Result<PlannedTrade> {
+    Ok(PlannedTrade {
+        id: row.get("id")?,
+        pair: row.get("pair")?,
+        exchange: row.get("exchange")?,
+        analysis_date: row.get("analysis_date")?,
+        portfolio_value: row.get("portfolio_value")?,
+        r_percent: row.get("r_percent")?,
+        min_rr: row.get("min_rr")?,
+        planned_pe: row.get("planned_pe")?,
+        planned_sl: row.get("planned_sl")?,
+        leverage: row.get("leverage")?,
+        planned_tps: row.get("planned_tps")?,
+        planned_entries: row.get("planned_entries")?,
+        position_type: row.get("position_type")?,
+        one_r: row.get("one_r")?,
+        margin: row.get("margin")?,
+        position_size: row.get("position_size")?,
+        quantity: row.get("quantity")?,
+        planned_weighted_rr: row.get("planned_weighted_rr")?,
+        notes: row.get("notes")?,
+        account_id: row.get("account_id")?,
+        converted_trade_id: row.get("converted_trade_id")?,
+        converted_at: row.get("converted_at")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+/// Watchlist, newest first. Converted plans are left out unless `include_converted` is set.
+#[tauri::command]
+pub async fn get_planned_trades(
+    db: State<'_, Database>,
+    include_converted: Option<bool>,
+) -> Result<Vec<PlannedTrade>, CommandError> {
+    let conn = db.pool.get()?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM planned_trades
+             WHERE ?1 OR converted_at IS NULL
+             ORDER BY analysis_date DESC, created_at DESC",
+        )?;
+
+    let planned = stmt
+        .query_map([include_converted.unwrap_or(false)], map_row_to_planned_trade)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(planned)
+}
+
+#[tauri::command]
+pub async fn get_planned_trade(
+    db: State<'_, Database>,
+    id: String,
+) -> Result<PlannedTrade, CommandError> {
+    let conn = db.pool.get()?;
+
+    conn.query_row("SELECT * FROM planned_trades WHERE id = ?", [&id], map_row_to_planned_trade)
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn create_planned_trade(
+    db: State<'_, Database>,
+    planned_trade: PlannedTradeInput,
+) -> Result<PlannedTrade, CommandError> {
+    let id = {
+        let conn = db.pool.get()?;
+
+        let id = format!("PLAN-{}-{}", Utc::now().timestamp_millis(), uuid::Uuid::new_v4());
+        let now = Utc::now().timestamp();
+        let p = &planned_trade;
+
+        conn.execute(
+            "INSERT INTO planned_trades (
+                id, pair, exchange, analysis_date, portfolio_value, r_percent, min_rr,
+                planned_pe, planned_sl, leverage, planned_tps, planned_entries, position_type,
+                one_r, margin, position_size, quantity, planned_weighted_rr, notes, account_id,
+                created_at, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                id, p.pair, p.exchange, p.analysis_date, p.portfolio_value, p.r_percent, p.min_rr,
+                p.planned_pe, p.planned_sl, p.leverage, p.planned_tps, p.planned_entries, p.position_type,
+                p.one_r, p.margin, p.position_size, p.quantity, p.planned_weighted_rr, p.notes, p.account_id,
+                now, now
+            ],
+        )?;
+
+        id
+    };
+
+    get_planned_trade(db, id).await
+}
+
+/// Replace the plan. Plans already converted are read-only; edit the trade instead.
+#[tauri::command]
+pub async fn update_planned_trade(
+    db: State<'_, Database>,
+    id: String,
+    planned_trade: PlannedTradeInput,
+) -> Result<PlannedTrade, CommandError> {
+    {
+        let conn = db.pool.get()?;
+        let p = &planned_trade;
+
+        let updated = conn
+            .execute(
+                "UPDATE planned_trades SET
+                    pair = ?, exchange = ?, analysis_date = ?, portfolio_value = ?, r_percent = ?, min_rr = ?,
+                    planned_pe = ?, planned_sl = ?, leverage = ?, planned_tps = ?, planned_entries = ?,
+                    position_type = ?, one_r = ?, margin = ?, position_size = ?, quantity = ?,
+                    planned_weighted_rr = ?, notes = ?, account_id = ?, updated_at = ?
+                 WHERE id = ? AND converted_at IS NULL",
+                rusqlite::params![
+                    p.pair, p.exchange, p.analysis_date, p.portfolio_value, p.r_percent, p.min_rr,
+                    p.planned_pe, p.planned_sl, p.leverage, p.planned_tps, p.planned_entries,
+                    p.position_type, p.one_r, p.margin, p.position_size, p.quantity,
+                    p.planned_weighted_rr, p.notes, p.account_id, Utc::now().timestamp(),
+                    id
+                ],
+            )?;
+
+        if updated == 0 {
+            return Err(CommandError::NotFound(format!("Planned trade {} not found or already converted", id)));
+        }
+    }
+
+    get_planned_trade(db, id).await
+}
+
+#[tauri::command]
+pub async fn delete_planned_trade(
+    db: State<'_, Database>,
+    id: String,
+) -> Result<(), CommandError> {
+    let conn = db.pool.get()?;
+    conn.execute("DELETE FROM planned_trades WHERE id = ?", [&id])?;
+    Ok(())
+}
+
+/// Take a planned trade: create an OPEN trade from the plan and mark the plan converted.
+/// `trade_date` defaults to now.
+#[tauri::command]
+pub async fn convert_to_trade(
+    db: State<'_, Database>,
+    id: String,
+    trade_date: Option<i64>,
+) -> Result<Trade, CommandError> {
+    let trade_id = {
+        let mut conn = db.pool.get()?;
+        convert_planned_trade(&mut conn, &id, trade_date.unwrap_or_else(|| Utc::now().timestamp()))?
+    };
+
+    get_trade(db, trade_id).await
+}
+
+fn convert_planned_trade(
+    conn: &mut rusqlite::Connection,
+    id: &str,
+    trade_date: i64,
+) -> Result<String, CommandError> {
+    let tx = conn.transaction()?;
+
+    let plan = tx
+        .query_row("SELECT * FROM planned_trades WHERE id = ?", [id], map_row_to_planned_trade)
+        .optional()?
+        .ok_or_else(|| CommandError::NotFound(format!("Planned trade {} not found", id)))?;
+    if plan.converted_at.is_some() {
+        return Err(CommandError::Validation(format!("Planned trade {} was already converted", id)));
+    }
+
+    let trade_id = format!("TRADE-{}-{}", Utc::now().timestamp_millis(), uuid::Uuid::new_v4());
+    let now = Utc::now().timestamp();
+
+    tx.execute(
+        "INSERT INTO trades (
+            id, pair, exchange, analysis_date, trade_date, status,
+            portfolio_value, r_percent, min_rr, planned_pe, planned_sl, leverage,
+            planned_tps, planned_entries, position_type, one_r, margin, position_size, quantity,
+            planned_weighted_rr, notes, import_source, account_id, created_at, updated_at
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        rusqlite::params![
+            trade_id, plan.pair, plan.exchange, plan.analysis_date, trade_date, "OPEN",
+            plan.portfolio_value, plan.r_percent, plan.min_rr, plan.planned_pe, plan.planned_sl, plan.leverage,
+            plan.planned_tps, plan.planned_entries, plan.position_type, plan.one_r, plan.margin,
+            plan.position_size, plan.quantity, plan.planned_weighted_rr, plan.notes, "USER_CREATED",
+            plan.account_id, now, now
+        ],
+    )?;
+
+    tx.execute(
+        "UPDATE planned_trades SET converted_trade_id = ?, converted_at = ?, updated_at = ? WHERE id = ?",
+        rusqlite::params![trade_id, now, now, id],
+    )?;
+
+    tx.commit()?;
+
+    Ok(trade_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::test_conn;
+
+    #[test]
+    fn test_convert_planned_trade() {
+        let mut conn = test_conn();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+
+        conn.execute(
+            "INSERT INTO planned_trades (id, pair, exchange, analysis_date, portfolio_value, r_percent,
+                min_rr, planned_pe, planned_sl, leverage, planned_tps, position_type, one_r, margin,
+                position_size, quantity, planned_weighted_rr, notes, created_at, updated_at)
+             VALUES ('PLAN1', 'ETH/USDT', 'BitGet', 500, 10000, 0.01, 2, 2000, 1900, 5, '[{\"price\": 2300}]',
+                'LONG', 100, 400, 2000, 1, 3, 'breakout retest', 500, 500)",
+            [],
+        )
+        .unwrap();
+
+        let trade_id = convert_planned_trade(&mut conn, "PLAN1", 1_000).unwrap();
+
+        let (status, trade_date, planned_sl, notes): (String, i64, f64, String) = conn
+            .query_row(
+                "SELECT status, trade_date, planned_sl, notes FROM trades WHERE id = ?",
+                [&trade_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!((status.as_str(), trade_date, planned_sl), ("OPEN", 1_000, 1900.0));
+        assert_eq!(notes, "breakout retest");
+
+        let plan = conn
+            .query_row("SELECT * FROM planned_trades WHERE id = 'PLAN1'", [], map_row_to_planned_trade)
+            .unwrap();
+        assert_eq!(plan.converted_trade_id, Some(trade_id));
+        assert!(plan.converted_at.is_some());
+
+        // A plan can only be taken once
+        assert!(convert_planned_trade(&mut conn, "PLAN1", 2_000).is_err());
+        assert!(convert_planned_trade(&mut conn, "MISSING", 2_000).is_err());
+    }
+}
@@ -1,6 +1,11 @@
 use tauri::State;
 use serde::{Deserialize, Serialize};
+use chrono::Utc;
+use crate::commands::api_sync::{default_credential_id, insert_trade, load_environment};
 use crate::db::Database;
+use crate::commands::error::CommandError;
+use crate::models::{SymbolFilters, Trade};
+use rusqlite::OptionalExtension;
 use crate::api::{
     bitget::{BitgetClient, types::{AllPositionsRequest, BitgetPosition}},
     credentials::{retrieve_api_key, retrieve_api_secret, retrieve_passphrase},
@@ -96,57 +101,396 @@ impl Position {
     }
 }
 
-/// Fetch current open positions from exchange
+/// Outcome of reconciling a credential's open positions with OPEN trades
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReconcileResult {
+    /// OPEN trades created for positions the journal was missing
+    pub opened: Vec<String>,
+    /// Reconciled trades closed because their position is gone
+    pub closed: Vec<String>,
+    /// Positions already covered by an OPEN trade
+    pub unchanged: i32,
+}
+
+/// Exchanges `fetch_current_positions` can read positions from
+pub(crate) fn supports_positions(exchange: &str) -> bool {
+    exchange == "bitget"
+}
+
+/// Fetch current open positions from exchange. Without a credential, the default one of
+/// `exchange` (or the only default credential) is used.
 #[tauri::command]
 pub async fn fetch_current_positions(
     db: State<'_, Database>,
-    credential_id: String,
-) -> Result<Vec<Position>, String> {
+    credential_id: Option<String>,
+    exchange: Option<String>,
+) -> Result<Vec<Position>, CommandError> {
+    let credential_id = default_credential_id(&db, credential_id, exchange)?;
+    load_positions(&db, &credential_id).await
+}
+
+/// Bring OPEN trades in line with the exchange: create trades for open positions the journal
+/// is missing and close the ones this reconciliation opened whose position is gone.
+/// The exit of a vanished position is unknown, so its trade is closed without a PnL (as
+/// break-even, counted in no PnL totals) until the next sync imports the position's fills
+/// and replaces it.
+#[tauri::command]
+pub async fn reconcile_positions(
+    db: State<'_, Database>,
+    credential_id: Option<String>,
+    exchange: Option<String>,
+) -> Result<ReconcileResult, CommandError> {
+    let credential_id = default_credential_id(&db, credential_id, exchange)?;
+    reconcile_credential_positions(&db, &credential_id).await
+}
+
+pub(crate) async fn reconcile_credential_positions(
+    db: &Database,
+    credential_id: &str,
+) -> Result<ReconcileResult, CommandError> {
+    let positions = load_positions(db, credential_id).await?;
+
+    let mut conn = db.pool.get()?;
+    let exchange: String = conn
+        .query_row("SELECT exchange FROM api_credentials WHERE id = ?", [credential_id], |row| row.get(0))
+        .optional()?
+        .ok_or_else(|| CommandError::NotFound(format!("Credential {} not found", credential_id)))?;
+    let (portfolio_value, r_percent): (f64, f64) = conn
+        .query_row(
+            "SELECT initial_capital, current_r_percent FROM settings WHERE id = 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| CommandError::Database(format!("Failed to load settings: {}", e)))?;
+
+    reconcile_open_trades(&mut conn, credential_id, &exchange, &positions, portfolio_value, r_percent)
+}
+
+/// Prefix of the fingerprint of trades opened by reconciliation for a credential
+fn reconcile_fingerprint_prefix(credential_id: &str) -> String {
+    format!("position|{}|", credential_id)
+}
+
+fn reconcile_open_trades(
+    conn: &mut rusqlite::Connection,
+    credential_id: &str,
+    exchange: &str,
+    positions: &[Position],
+    portfolio_value: f64,
+    r_percent: f64,
+) -> Result<ReconcileResult, CommandError> {
+    let tx = conn.transaction()?;
+    let prefix = reconcile_fingerprint_prefix(credential_id);
+    let now = Utc::now().timestamp();
+    let mut result = ReconcileResult::default();
+
+    // (id, pair, position_type, opened by this reconciliation)
+    let open_trades: Vec<(String, String, String, bool)> = {
+        let mut stmt = tx
+            .prepare(
+                "SELECT id, pair, position_type, COALESCE(substr(import_fingerprint, 1, length(?1)) = ?1, 0)
+                 FROM trades
+                 WHERE status = 'OPEN' AND deleted_at IS NULL AND LOWER(exchange) = LOWER(?2)",
+            )?;
+        stmt.query_map(rusqlite::params![prefix, exchange], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?
+    };
+    let covers = |pair: &str, position_type: &str, position: &Position| {
+        SymbolFilters::normalize(pair) == SymbolFilters::normalize(&position.symbol)
+            && position_type == position.position_side
+    };
+
+    for position in positions {
+        let covering = open_trades.iter().find(|(_, pair, side, _)| covers(pair, side, position));
+        match covering {
+            Some((trade_id, _, _, true)) => {
+                // Show the current unrealized PnL; an unchanged value is not written, so it
+                // records no revision and keeps the stats cache
+                tx.execute(
+                    "UPDATE trades SET total_pnl = ?1, updated_at = ?2 WHERE id = ?3 AND total_pnl IS NOT ?1",
+                    rusqlite::params![position.unrealized_pnl, now, trade_id],
+                )?;
+                result.unchanged += 1;
+            }
+            Some(_) => result.unchanged += 1,
+            None => {
+                let trade = position_to_trade(position, credential_id, portfolio_value, r_percent);
+                insert_trade(&tx, &trade)
+                    .map_err(|e| CommandError::Database(format!("Failed to insert trade: {}", e)))?;
+                result.opened.push(trade.id);
+            }
+        }
+    }
+
+    let stale = open_trades
+        .iter()
+        .filter(|(_, pair, side, reconciled)| {
+            *reconciled && !positions.iter().any(|p| covers(pair, side, p))
+        });
+    for (trade_id, _, _, _) in stale {
+        tx.execute(
+            "UPDATE trades SET status = 'BE', close_date = ?1, total_pnl = NULL, pnl_in_r = NULL, updated_at = ?1
+             WHERE id = ?2",
+            rusqlite::params![now, trade_id],
+        )?;
+        result.closed.push(trade_id.clone());
+    }
+
+    tx.commit()?;
+    Ok(result)
+}
+
+/// Trash the trade reconciliation opened for the position an imported trade covers, so
+/// the position is not counted twice. Its funding payments move to the imported trade.
+pub(crate) fn replace_reconciled_trade(
+    conn: &rusqlite::Connection,
+    credential_id: &str,
+    trade: &Trade,
+) -> rusqlite::Result<Option<String>> {
+    let now = Utc::now().timestamp();
+    let candidates = {
+        let mut stmt = conn.prepare(
+            "SELECT id, pair FROM trades
+             WHERE substr(import_fingerprint, 1, length(?1)) = ?1 AND deleted_at IS NULL
+               AND position_type = ?2
+               AND trade_date <= ?3
+               AND COALESCE(close_date, ?4) >= ?5
+             ORDER BY ABS(trade_date - ?5)",
+        )?;
+        stmt.query_map(
+            rusqlite::params![
+                reconcile_fingerprint_prefix(credential_id),
+                trade.position_type,
+                trade.close_date.unwrap_or(trade.trade_date),
+                now,
+                trade.trade_date
+            ],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        )?
+        .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let pair = SymbolFilters::normalize(&trade.pair);
+    let Some((reconciled_id, _)) = candidates
+        .into_iter()
+        .find(|(_, candidate)| SymbolFilters::normalize(candidate) == pair)
+    else {
+        return Ok(None);
+    };
+
+    conn.execute("UPDATE trades SET deleted_at = ? WHERE id = ?", rusqlite::params![now, reconciled_id])?;
+    conn.execute(
+        "UPDATE funding_payments SET trade_id = ? WHERE trade_id = ?",
+        rusqlite::params![trade.id, reconciled_id],
+    )?;
+    Ok(Some(reconciled_id))
+}
+
+/// OPEN trade for a position, with the stop estimated at 1R like live mirroring
+fn position_to_trade(position: &Position, credential_id: &str, portfolio_value: f64, r_percent: f64) -> Trade {
+    let one_r = portfolio_value * r_percent;
+    let sl_distance = if position.quantity > 0.0 { one_r / position.quantity } else { 0.0 };
+    let estimated_sl = if position.position_side == "SHORT" {
+        position.entry_price + sl_distance
+    } else {
+        position.entry_price - sl_distance
+    };
+    let entries = serde_json::to_string(&vec![serde_json::json!({"price": position.entry_price, "percent": 100})])
+        .unwrap_or_default();
+    let now = Utc::now().timestamp();
+    let opened_at = position.created_at / 1000; // Exchange times are in milliseconds
+
+    Trade {
+        id: uuid::Uuid::new_v4().to_string(),
+        pair: position.symbol.clone(),
+        exchange: position.exchange.clone(),
+        analysis_date: opened_at,
+        trade_date: opened_at,
+        status: "OPEN".to_string(),
+        portfolio_value,
+        r_percent,
+        min_rr: 0.0,
+        planned_pe: position.entry_price,
+        planned_sl: estimated_sl,
+        leverage: position.leverage,
+        planned_tps: "[]".to_string(),
+        planned_entries: Some(entries.clone()),
+        position_type: position.position_side.clone(),
+        one_r,
+        margin: position.margin,
+        position_size: position.entry_price * position.quantity,
+        quantity: position.quantity,
+        planned_weighted_rr: 0.0,
+        effective_pe: Some(position.entry_price),
+        effective_entries: Some(entries),
+        close_date: None,
+        exits: None,
+        effective_weighted_rr: None,
+        total_pnl: Some(position.unrealized_pnl),
+        pnl_in_r: None,
+        notes: format!("Open position reconciled from {} (Credential: {})", position.exchange, credential_id),
+        execution_portfolio: None,
+        execution_r_percent: None,
+        execution_margin: None,
+        execution_position_size: None,
+        execution_quantity: None,
+        execution_one_r: None,
+        execution_potential_profit: None,
+        import_fingerprint: Some(format!(
+            "{}{}|{}",
+            reconcile_fingerprint_prefix(credential_id),
+            position.position_id,
+            position.created_at
+        )),
+        import_source: "POSITION_SYNC".to_string(),
+        account_id: None,
+        grade: None,
+        pre_emotion: None,
+        post_emotion: None,
+        liquidated: false,
+        funding_pnl: None,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+async fn load_positions(db: &Database, credential_id: &str) -> Result<Vec<Position>, CommandError> {
     // Fetch credentials
-    let (exchange, api_key, api_secret, passphrase) = {
-        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let (exchange, environment, api_key, api_secret, passphrase) = {
+        let conn = db.pool.get()?;
 
         // Fetch exchange type
         let exchange: String = conn
             .query_row(
                 "SELECT exchange FROM api_credentials WHERE id = ?",
-                [&credential_id],
+                [credential_id],
                 |row| row.get(0),
             )
-            .map_err(|e| format!("Credential not found: {}", e))?;
+            .optional()?
+            .ok_or_else(|| CommandError::NotFound(format!("Credential {} not found", credential_id)))?;
+        let environment = load_environment(&conn, credential_id)?;
 
         // Retrieve credentials from system keychain
-        let api_key = retrieve_api_key(&credential_id).map_err(|e| e.to_string())?;
-        let api_secret = retrieve_api_secret(&credential_id).map_err(|e| e.to_string())?;
-        let passphrase = retrieve_passphrase(&credential_id).unwrap_or_default();
+        let api_key = retrieve_api_key(credential_id, "positions")?;
+        let api_secret = retrieve_api_secret(credential_id, "positions")?;
+        let passphrase = retrieve_passphrase(credential_id, "positions").unwrap_or_default();
 
-        (exchange, api_key, api_secret, passphrase)
+        (exchange, environment, api_key, api_secret, passphrase)
     }; // conn is dropped here
 
     // Fetch positions based on exchange
     match exchange.as_str() {
         "bitget" => {
-            let client = BitgetClient::new(api_key, api_secret, passphrase);
+            let client = BitgetClient::new(api_key, api_secret, passphrase).with_environment(&environment);
             let request = AllPositionsRequest {
                 product_type: "USDT-FUTURES".to_string(),
                 margin_coin: Some("USDT".to_string()),
             };
 
-            let positions_data = client.fetch_all_positions(&request).await
-                .map_err(|e| e.to_string())?;
+            let positions_data = client.fetch_all_positions(&request).await?;
 
             // Convert Bitget positions to generic Position format
-            let positions: Result<Vec<Position>, String> = positions_data
+            positions_data
                 .iter()
                 .map(|bitget_pos| Position::from_bitget(bitget_pos, &exchange))
-                .collect();
-
-            positions
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(CommandError::Exchange)
         }
         "blofin" => {
             // TODO: Implement BloFin position fetching when needed
-            Err("BloFin position monitoring not yet implemented".to_string())
+            Err(CommandError::Validation("BloFin position monitoring not yet implemented".to_string()))
+        }
+        _ => Err(CommandError::Validation(format!("Unsupported exchange: {}", exchange))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::test_conn;
+
+    fn position(symbol: &str, side: &str, unrealized_pnl: f64) -> Position {
+        Position {
+            position_id: format!("{}_{}", symbol, side),
+            symbol: symbol.to_string(),
+            exchange: "bitget".to_string(),
+            position_side: side.to_string(),
+            entry_price: 100.0,
+            current_price: 105.0,
+            quantity: 2.0,
+            leverage: 10,
+            unrealized_pnl,
+            unrealized_pnl_percent: 0.0,
+            liquidation_price: 50.0,
+            margin: 20.0,
+            margin_mode: "crossed".to_string(),
+            price_distance_to_liquidation_percent: 0.0,
+            created_at: 1_767_603_600_000,
+            updated_at: 1_767_603_600_000,
         }
-        _ => Err(format!("Unsupported exchange: {}", exchange)),
+    }
+
+    #[test]
+    fn test_reconcile_open_trades() {
+        let mut conn = test_conn();
+
+        // A hand-entered OPEN trade already covers the ETH long
+        let manual = position_to_trade(&position("ETHUSDT", "LONG", 0.0), "C1", 10_000.0, 0.01);
+        insert_trade(&conn, &Trade { import_fingerprint: None, import_source: "USER_CREATED".to_string(), ..manual })
+            .unwrap();
+
+        let open = [position("BTCUSDT", "LONG", 10.0), position("ETHUSDT", "LONG", 5.0)];
+        let first = reconcile_open_trades(&mut conn, "C1", "bitget", &open, 10_000.0, 0.01).unwrap();
+        assert_eq!((first.opened.len(), first.closed.len(), first.unchanged), (1, 0, 1));
+
+        // Running again is a no-op apart from refreshing the unrealized PnL
+        let open = [position("BTCUSDT", "LONG", 25.0), position("ETHUSDT", "LONG", 5.0)];
+        let second = reconcile_open_trades(&mut conn, "C1", "bitget", &open, 10_000.0, 0.01).unwrap();
+        assert_eq!((second.opened.len(), second.closed.len(), second.unchanged), (0, 0, 2));
+        let revisions = |conn: &rusqlite::Connection| -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM trade_revisions", [], |row| row.get(0)).unwrap()
+        };
+        let before = revisions(&conn);
+        reconcile_open_trades(&mut conn, "C1", "bitget", &open, 10_000.0, 0.01).unwrap();
+        assert_eq!(revisions(&conn), before, "an unchanged PnL is not rewritten");
+
+        // Another credential on the same exchange never closes C1's trades
+        let other = reconcile_open_trades(&mut conn, "C2", "bitget", &[], 10_000.0, 0.01).unwrap();
+        assert!(other.closed.is_empty());
+
+        // The BTC position closed: its trade is closed, the manual ETH trade is left alone
+        let third = reconcile_open_trades(&mut conn, "C1", "bitget", &[], 10_000.0, 0.01).unwrap();
+        assert_eq!(third.closed, first.opened);
+        let (status, total_pnl, pnl_in_r): (String, Option<f64>, Option<f64>) = conn
+            .query_row(
+                "SELECT status, total_pnl, pnl_in_r FROM trades WHERE id = ?",
+                [&first.opened[0]],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!((status.as_str(), total_pnl, pnl_in_r), ("BE", None, None));
+        let still_open: i64 = conn
+            .query_row("SELECT COUNT(*) FROM trades WHERE status = 'OPEN'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(still_open, 1);
+
+        // The next sync imports the BTC position from its fills and takes over the closed trade
+        let fills = Trade {
+            id: "FILLS".to_string(),
+            status: "WIN".to_string(),
+            close_date: Some(Utc::now().timestamp()),
+            import_fingerprint: Some("api|bitget|position|1|btcusdt".to_string()),
+            import_source: "API_IMPORT".to_string(),
+            ..position_to_trade(&position("BTC/USDT", "LONG", 0.0), "C1", 10_000.0, 0.01)
+        };
+        insert_trade(&conn, &fills).unwrap();
+        assert_eq!(replace_reconciled_trade(&conn, "C2", &fills).unwrap(), None);
+        assert_eq!(replace_reconciled_trade(&conn, "C1", &fills).unwrap().as_ref(), first.opened.first());
+        let active: i64 = conn
+            .query_row("SELECT COUNT(*) FROM trades WHERE pair LIKE 'BTC%' AND deleted_at IS NULL", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(active, 1);
     }
 }
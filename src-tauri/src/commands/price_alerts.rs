@@ -0,0 +1,97 @@
+use tauri::{AppHandle, State};
+use std::sync::Arc;
+use crate::api::PriceAlertManager;
+use crate::db::Database;
+use crate::commands::error::CommandError;
+use crate::models::{PriceAlert, PriceAlertInput};
+use chrono::Utc;
+
+pub(crate) fn map_row_to_price_alert(row: &rusqlite::Row) -> rusqlite::Result<PriceAlert> {
+    Ok(PriceAlert {
+        id: row.get("id")?,
+        exchange: row.get("exchange")?,
+        symbol: row.get("symbol")?,
+        condition: row.get("condition")?,
+        target_price: row.get("target_price")?,
+        note: row.get("note")?,
+        plan_position_type: row.get("plan_position_type")?,
+        status: row.get("status")?,
+        triggered_at: row.get("triggered_at")?,
+        triggered_price: row.get("triggered_price")?,
+        planned_trade_id: row.get("planned_trade_id")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+/// Price alerts, active ones first, newest first within each group
+#[tauri::command]
+pub async fn get_price_alerts(db: State<'_, Database>) -> Result<Vec<PriceAlert>, CommandError> {
+    let conn = db.pool.get()?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM alerts
+             ORDER BY status = 'TRIGGERED', COALESCE(triggered_at, created_at) DESC",
+        )?;
+
+    let alerts = stmt
+        .query_map([], map_row_to_price_alert)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(alerts)
+}
+
+/// Create an alert on a Bitget USDT-M futures symbol and start watching it
+#[tauri::command]
+pub async fn create_price_alert(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    alert_manager: State<'_, Arc<PriceAlertManager>>,
+    alert: PriceAlertInput,
+) -> Result<PriceAlert, CommandError> {
+    let symbol = alert.symbol.trim().to_uppercase();
+    if symbol.is_empty() {
+        return Err(CommandError::Validation("Symbol is required".to_string()));
+    }
+    if alert.target_price.is_nan() || alert.target_price <= 0.0 {
+        return Err(CommandError::Validation("Target price must be positive".to_string()));
+    }
+
+    let created = {
+        let conn = db.pool.get()?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now().timestamp();
+
+        conn.execute(
+            "INSERT INTO alerts (id, symbol, condition, target_price, note, plan_position_type, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                id, symbol, alert.condition, alert.target_price, alert.note.trim(),
+                alert.plan_position_type, now, now
+            ],
+        )?;
+
+        conn.query_row("SELECT * FROM alerts WHERE id = ?", [&id], map_row_to_price_alert)?
+    };
+
+    alert_manager.refresh(app_handle, db.inner().clone()).await.map_err(CommandError::Database)?;
+    Ok(created)
+}
+
+/// Delete an alert, triggered or not. Planned trades it created are kept.
+#[tauri::command]
+pub async fn delete_price_alert(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    alert_manager: State<'_, Arc<PriceAlertManager>>,
+    id: String,
+) -> Result<(), CommandError> {
+    {
+        let conn = db.pool.get()?;
+        conn.execute("DELETE FROM alerts WHERE id = ?", [&id])?;
+    }
+
+    alert_manager.refresh(app_handle, db.inner().clone()).await.map_err(CommandError::Database)
+}
@@ -0,0 +1,146 @@
+use tauri::State;
+use serde::{Deserialize, Serialize};
+use crate::db::Database;
+use crate::commands::error::CommandError;
+use chrono::Utc;
+use rusqlite::Connection;
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// Services answering with the caller's public IPv4 address as plain text, tried in order
+const IP_ECHO_URLS: &[&str] = &[
+    "https://api.ipify.org",
+    "https://ipv4.icanhazip.com",
+    "https://checkip.amazonaws.com",
+];
+
+const IP_ECHO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Addresses kept in public_ip_history
+const RECENT_IP_LIMIT: i64 = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicIpSighting {
+    pub ip: String,
+    pub first_seen_at: i64,
+    pub last_seen_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicIpInfo {
+    pub current: String,
+    /// Recently seen addresses, the current one included, most recent first
+    pub recent: Vec<PublicIpSighting>,
+}
+
+/// The machine's public IP, to whitelist on exchange API keys, with the addresses it
+/// recently had
+#[tauri::command]
+pub async fn get_public_ip(db: State<'_, Database>) -> Result<PublicIpInfo, CommandError> {
+    let current = fetch_public_ip().await?;
+
+    let conn = db.pool.get()?;
+    record_public_ip(&conn, &current, Utc::now().timestamp())?;
+    let recent = load_recent_ips(&conn)?;
+
+    Ok(PublicIpInfo { current, recent })
+}
+
+/// Ask the echo services in turn until one answers with an address
+async fn fetch_public_ip() -> Result<String, CommandError> {
+    // Through the proxy, if any: its address is the one exchanges see
+    let client = crate::api::proxy::client_builder()
+        .map_err(CommandError::Network)?
+        .timeout(IP_ECHO_TIMEOUT)
+        .build()
+        .map_err(|e| CommandError::Internal(e.to_string()))?;
+
+    let mut last_error = String::new();
+    for url in IP_ECHO_URLS {
+        let body = match client.get(*url).send().await.and_then(|r| r.error_for_status()) {
+            Ok(response) => response.text().await,
+            Err(e) => Err(e),
+        };
+        match body.map_err(|e| e.to_string()).and_then(|body| parse_ip(&body)) {
+            Ok(ip) => return Ok(ip),
+            Err(e) => {
+                eprintln!("Public IP lookup via {} failed: {}", url, e);
+                last_error = e;
+            }
+        }
+    }
+    Err(CommandError::Network(format!("Failed to look up the public IP: {}", last_error)))
+}
+
+fn parse_ip(body: &str) -> Result<String, String> {
+    body.trim()
+        .parse::<IpAddr>()
+        .map(|ip| ip.to_string())
+        .map_err(|_| format!("Unexpected answer '{}'", body.trim()))
+}
+
+/// Note `ip` as seen at `now`, keeping only the RECENT_IP_LIMIT most recent addresses
+fn record_public_ip(conn: &Connection, ip: &str, now: i64) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO public_ip_history (ip, first_seen_at, last_seen_at) VALUES (?1, ?2, ?2)
+         ON CONFLICT(ip) DO UPDATE SET last_seen_at = excluded.last_seen_at",
+        rusqlite::params![ip, now],
+    )?;
+    conn.execute(
+        "DELETE FROM public_ip_history WHERE ip NOT IN (
+            SELECT ip FROM public_ip_history ORDER BY last_seen_at DESC LIMIT ?
+         )",
+        [RECENT_IP_LIMIT],
+    )?;
+    Ok(())
+}
+
+fn load_recent_ips(conn: &Connection) -> Result<Vec<PublicIpSighting>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT ip, first_seen_at, last_seen_at FROM public_ip_history ORDER BY last_seen_at DESC",
+    )?;
+    let sightings = stmt
+        .query_map([], |row| {
+            Ok(PublicIpSighting {
+                ip: row.get(0)?,
+                first_seen_at: row.get(1)?,
+                last_seen_at: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(sightings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::test_conn;
+
+    #[test]
+    fn test_parse_ip() {
+        assert_eq!(parse_ip("203.0.113.7\n").unwrap(), "203.0.113.7");
+        assert!(parse_ip("<html>rate limited</html>").is_err());
+        assert!(parse_ip("").is_err());
+    }
+
+    #[test]
+    fn test_recent_ips_keep_first_seen_and_limit() {
+        let conn = test_conn();
+
+        record_public_ip(&conn, "203.0.113.7", 100).unwrap();
+        record_public_ip(&conn, "198.51.100.2", 200).unwrap();
+        record_public_ip(&conn, "203.0.113.7", 300).unwrap();
+
+        let recent = load_recent_ips(&conn).unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].ip, "203.0.113.7");
+        assert_eq!((recent[0].first_seen_at, recent[0].last_seen_at), (100, 300));
+
+        for i in 0..RECENT_IP_LIMIT {
+            record_public_ip(&conn, &format!("192.0.2.{}", i), 400 + i).unwrap();
+        }
+        let recent = load_recent_ips(&conn).unwrap();
+        assert_eq!(recent.len() as i64, RECENT_IP_LIMIT);
+        assert!(recent.iter().all(|sighting| sighting.ip.starts_with("192.0.2.")));
+    }
+}
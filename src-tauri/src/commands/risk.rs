@@ -0,0 +1,342 @@
+use tauri::{AppHandle, State};
+use crate::db::Database;
+use crate::commands::error::CommandError;
+use crate::commands::accounts::capital_settings;
+use crate::commands::app_lock::notification_body;
+use crate::commands::stats::{closed_r_outcomes, compute_kelly, StatsScope};
+use serde::{Deserialize, Serialize};
+
+/// Share of the daily loss limit at which a warning is raised before the stop alert
+const DAILY_LOSS_WARNING_RATIO: f64 = 0.75;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RiskAlert {
+    /// "loss_streak" or "daily_loss_limit"
+    pub rule: String,
+    /// "warning" (reduce size) or "stop" (stop trading for the day)
+    pub severity: String,
+    pub message: String,
+    pub value: f64,
+    pub limit: f64,
+    /// Suggested risk per trade as a fraction, when the advice is to reduce size
+    pub suggested_r_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskOfRuinInput {
+    /// Win rate in percent; defaults to the historical value
+    pub win_rate: Option<f64>,
+    /// Average win / loss in R (both positive); default to historical values
+    pub avg_win_r: Option<f64>,
+    pub avg_loss_r: Option<f64>,
+    /// Fraction of equity risked per 1R (default: the account's or settings' current_r_percent)
+    pub risk_per_trade: Option<f64>,
+    /// Drawdown that counts as ruin, as a fraction (default 0.5)
+    pub ruin_threshold: Option<f64>,
+    /// Window for the historical defaults
+    pub date_range: Option<String>,
+    pub start_ts: Option<i64>,
+    pub end_ts: Option<i64>,
+    /// Use one account's history and capital settings (default: all accounts)
+    pub account_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskOfRuinResult {
+    pub win_rate: f64,
+    pub avg_win_r: f64,
+    pub avg_loss_r: f64,
+    pub risk_per_trade: f64,
+    pub ruin_threshold: f64,
+    /// Closed trades with an R value behind the historical defaults
+    pub sample_size: i32,
+    pub expectancy_r: f64,
+    /// Net losing R that takes equity down to the ruin threshold at this risk per trade
+    pub units_to_ruin: f64,
+    pub risk_of_ruin_pct: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RiskRules {
+    max_consecutive_losses: i32,
+    daily_loss_limit_r: f64,
+    current_r_percent: f64,
+}
+
+/// Evaluate the loss-streak and daily-loss rules from settings against recent closed trades.
+/// `utc_offset_minutes` defines the user's trading day; `notify` also raises a system notification.
+/// With `account_id` only that account's trades count and its risk per trade is used.
+#[tauri::command]
+pub async fn get_risk_alerts(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    utc_offset_minutes: Option<i32>,
+    notify: Option<bool>,
+    account_id: Option<String>,
+) -> Result<Vec<RiskAlert>, CommandError> {
+    let alerts = {
+        let conn = db.pool.get()?;
+
+        let (_, current_r_percent) = capital_settings(&conn, account_id.as_deref())?;
+        let rules = conn
+            .query_row(
+                "SELECT max_consecutive_losses, daily_loss_limit_r FROM settings WHERE id = 1",
+                [],
+                |row| {
+                    Ok(RiskRules {
+                        max_consecutive_losses: row.get(0)?,
+                        daily_loss_limit_r: row.get(1)?,
+                        current_r_percent,
+                    })
+                },
+            )?;
+
+        let streak = current_loss_streak(&conn, account_id.as_deref())?;
+        let today_r = realized_r_today(&conn, utc_offset_minutes.unwrap_or(0), account_id.as_deref())?;
+
+        evaluate_risk_rules(rules, streak, today_r)
+        // conn is dropped here
+    };
+
+    if notify.unwrap_or(false) && !alerts.is_empty() {
+        send_risk_notification(&app_handle, &alerts)?;
+    }
+
+    Ok(alerts)
+}
+
+/// Analytical risk of ruin from win rate and average win/loss in R. Any assumption left
+/// unset is taken from closed-trade history, so the UI can tweak one input at a time.
+#[tauri::command]
+pub async fn get_risk_of_ruin(
+    db: State<'_, Database>,
+    input: RiskOfRuinInput,
+) -> Result<RiskOfRuinResult, CommandError> {
+    let scope = StatsScope::resolve(
+        input.date_range.as_deref(),
+        input.start_ts,
+        input.end_ts,
+        input.account_id.clone(),
+    )?;
+    let conn = db.pool.get()?;
+
+    let (_, current_r_percent) = capital_settings(&conn, scope.account_id.as_deref())?;
+    let history = compute_kelly(&closed_r_outcomes(&conn, &scope)?, current_r_percent);
+
+    let win_rate = input.win_rate.unwrap_or(history.win_rate);
+    let avg_win_r = input.avg_win_r.unwrap_or(history.avg_win_r);
+    let avg_loss_r = input.avg_loss_r.unwrap_or(history.avg_loss_r);
+    let risk_per_trade = input.risk_per_trade.unwrap_or(current_r_percent);
+    let ruin_threshold = input.ruin_threshold.unwrap_or(0.5);
+
+    if !(0.0..=100.0).contains(&win_rate) {
+        return Err(CommandError::Validation("win_rate must be between 0 and 100".to_string()));
+    }
+    if avg_win_r < 0.0 || avg_loss_r <= 0.0 {
+        return Err(CommandError::Validation("avg_win_r must be >= 0 and avg_loss_r > 0 (not enough losing trades in history?)".to_string()));
+    }
+    if !(0.0..1.0).contains(&risk_per_trade) || risk_per_trade == 0.0 {
+        return Err(CommandError::Validation("risk_per_trade must be between 0 and 1 (e.g. 0.02 for 2%)".to_string()));
+    }
+    if !(0.0..1.0).contains(&ruin_threshold) || ruin_threshold == 0.0 {
+        return Err(CommandError::Validation("ruin_threshold must be between 0 and 1 (e.g. 0.5 for a 50% drawdown)".to_string()));
+    }
+
+    let p = win_rate / 100.0;
+    let expectancy_r = p * avg_win_r - (1.0 - p) * avg_loss_r;
+    let units_to_ruin = (1.0 - ruin_threshold).ln() / (1.0 - risk_per_trade).ln();
+
+    Ok(RiskOfRuinResult {
+        win_rate,
+        avg_win_r,
+        avg_loss_r,
+        risk_per_trade,
+        ruin_threshold,
+        sample_size: history.trade_count,
+        expectancy_r,
+        units_to_ruin,
+        risk_of_ruin_pct: risk_of_ruin(p, avg_win_r, avg_loss_r, units_to_ruin) * 100.0,
+    })
+}
+
+/// Probability that a random walk with these R outcomes ever falls `units` R below its start,
+/// using the diffusion approximation exp(-2μD/σ²). Without a positive edge ruin is certain.
+fn risk_of_ruin(win_prob: f64, avg_win_r: f64, avg_loss_r: f64, units: f64) -> f64 {
+    let mean = win_prob * avg_win_r - (1.0 - win_prob) * avg_loss_r;
+    if mean <= 0.0 {
+        return 1.0;
+    }
+    let variance = win_prob * avg_win_r.powi(2) + (1.0 - win_prob) * avg_loss_r.powi(2) - mean.powi(2);
+    if variance <= 0.0 {
+        return 0.0;
+    }
+    (-2.0 * mean * units / variance).exp().min(1.0)
+}
+
+/// Consecutive LOSS trades at the end of the closed-trade history (by close time)
+fn current_loss_streak(conn: &rusqlite::Connection, account_id: Option<&str>) -> rusqlite::Result<i32> {
+    let mut stmt = conn.prepare(
+        "SELECT status FROM trades
+         WHERE deleted_at IS NULL AND status IN ('WIN', 'LOSS', 'BE') AND close_date IS NOT NULL
+         AND (?1 IS NULL OR account_id = ?1)
+         ORDER BY close_date DESC",
+    )?;
+
+    let mut rows = stmt.query([account_id])?;
+    let mut streak = 0;
+    while let Some(row) = rows.next()? {
+        let status: String = row.get(0)?;
+        if status != "LOSS" {
+            break;
+        }
+        streak += 1;
+    }
+
+    Ok(streak)
+}
+
+/// Realized R of trades closed since local midnight. Imported trades have no pnl_in_r,
+/// so fall back to PnL / 1R.
+fn realized_r_today(
+    conn: &rusqlite::Connection,
+    utc_offset_minutes: i32,
+    account_id: Option<&str>,
+) -> rusqlite::Result<f64> {
+    let offset_seconds = utc_offset_minutes as i64 * 60;
+    let now_local = chrono::Utc::now().timestamp() + offset_seconds;
+    let day_start = now_local - now_local.rem_euclid(86_400) - offset_seconds;
+
+    conn.query_row(
+        "SELECT COALESCE(SUM(COALESCE(pnl_in_r, total_pnl / NULLIF(one_r, 0))), 0.0) FROM trades
+         WHERE deleted_at IS NULL AND status IN ('WIN', 'LOSS', 'BE') AND close_date >= ?1
+         AND (?2 IS NULL OR account_id = ?2)",
+        rusqlite::params![day_start, account_id],
+        |row| row.get(0),
+    )
+}
+
+fn evaluate_risk_rules(rules: RiskRules, loss_streak: i32, today_r: f64) -> Vec<RiskAlert> {
+    let mut alerts = Vec::new();
+
+    if rules.max_consecutive_losses > 0 && loss_streak >= rules.max_consecutive_losses {
+        let suggested = rules.current_r_percent / 2.0;
+        alerts.push(RiskAlert {
+            rule: "loss_streak".to_string(),
+            severity: "warning".to_string(),
+            message: format!(
+                "{} consecutive losses. Consider halving risk to {:.2}% until the next win.",
+                loss_streak,
+                suggested * 100.0
+            ),
+            value: loss_streak as f64,
+            limit: rules.max_consecutive_losses as f64,
+            suggested_r_percent: Some(suggested),
+        });
+    }
+
+    if rules.daily_loss_limit_r > 0.0 {
+        let loss_r = -today_r;
+        if loss_r >= rules.daily_loss_limit_r {
+            alerts.push(RiskAlert {
+                rule: "daily_loss_limit".to_string(),
+                severity: "stop".to_string(),
+                message: format!(
+                    "Daily loss limit reached ({:.2}R of {:.2}R). Stop trading for the day.",
+                    loss_r, rules.daily_loss_limit_r
+                ),
+                value: loss_r,
+                limit: rules.daily_loss_limit_r,
+                suggested_r_percent: None,
+            });
+        } else if loss_r >= rules.daily_loss_limit_r * DAILY_LOSS_WARNING_RATIO {
+            let suggested = rules.current_r_percent / 2.0;
+            alerts.push(RiskAlert {
+                rule: "daily_loss_limit".to_string(),
+                severity: "warning".to_string(),
+                message: format!(
+                    "Down {:.2}R today, close to the {:.2}R daily limit. Reduce size or stop.",
+                    loss_r, rules.daily_loss_limit_r
+                ),
+                value: loss_r,
+                limit: rules.daily_loss_limit_r,
+                suggested_r_percent: Some(suggested),
+            });
+        }
+    }
+
+    alerts
+}
+
+fn send_risk_notification(app_handle: &AppHandle, alerts: &[RiskAlert]) -> Result<(), CommandError> {
+    use tauri_plugin_notification::NotificationExt;
+
+    let title = if alerts.iter().any(|a| a.severity == "stop") {
+        "Stop Trading for Today"
+    } else {
+        "Risk Warning"
+    };
+    let body = alerts
+        .iter()
+        .map(|a| a.message.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    app_handle
+        .notification()
+        .builder()
+        .title(title)
+        .body(notification_body(app_handle, &body))
+        .show()
+        .map_err(|e| CommandError::Internal(e.to_string()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: RiskRules = RiskRules {
+        max_consecutive_losses: 3,
+        daily_loss_limit_r: 3.0,
+        current_r_percent: 0.02,
+    };
+
+    #[test]
+    fn test_risk_of_ruin() {
+        // μ = 0.5R, σ² = 2.25 → exp(-2 × 0.5 × 10 / 2.25)
+        let ror = risk_of_ruin(0.5, 2.0, 1.0, 10.0);
+        assert!((ror - (-10.0_f64 / 2.25).exp()).abs() < 1e-12);
+
+        // More room before ruin means lower risk
+        assert!(risk_of_ruin(0.5, 2.0, 1.0, 30.0) < ror);
+        // No edge, certain ruin
+        assert_eq!(risk_of_ruin(0.4, 1.0, 1.0, 50.0), 1.0);
+    }
+
+    #[test]
+    fn test_no_alerts_within_limits() {
+        assert!(evaluate_risk_rules(RULES, 2, -1.0).is_empty());
+    }
+
+    #[test]
+    fn test_loss_streak_suggests_half_size() {
+        let alerts = evaluate_risk_rules(RULES, 3, 0.0);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].rule, "loss_streak");
+        assert_eq!(alerts[0].suggested_r_percent, Some(0.01));
+    }
+
+    #[test]
+    fn test_daily_loss_warning_then_stop() {
+        let warning = evaluate_risk_rules(RULES, 0, -2.5);
+        assert_eq!(warning[0].severity, "warning");
+
+        let stop = evaluate_risk_rules(RULES, 0, -3.2);
+        assert_eq!(stop[0].severity, "stop");
+        assert_eq!(stop[0].suggested_r_percent, None);
+
+        let disabled = RiskRules { daily_loss_limit_r: 0.0, max_consecutive_losses: 0, ..RULES };
+        assert!(evaluate_risk_rules(disabled, 10, -10.0).is_empty());
+    }
+}
@@ -1,13 +1,15 @@
 use tauri::State;
+use crate::api::{biometric, proxy};
 use crate::db::Database;
+use crate::commands::error::CommandError;
 use crate::models::{Settings, UpdateSettingsInput};
 
 #[tauri::command]
-pub async fn get_settings(db: State<'_, Database>) -> Result<Settings, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+pub async fn get_settings(db: State<'_, Database>) -> Result<Settings, CommandError> {
+    let conn = db.pool.get()?;
 
     let settings = conn.query_row(
-        "SELECT id, initial_capital, current_r_percent, default_min_rr, default_leverage, currency, enable_position_monitor, enable_api_connections, created_at, updated_at FROM settings WHERE id = 1",
+        "SELECT id, initial_capital, current_r_percent, default_min_rr, default_leverage, currency, enable_position_monitor, enable_api_connections, max_consecutive_losses, daily_loss_limit_r, trash_retention_days, sync_history_retention_days, sync_history_max_entries, sync_on_startup, mirror_stale_timeout_secs, require_biometric_unlock, proxy_url, breakeven_threshold, created_at, updated_at FROM settings WHERE id = 1",
         [],
         |row| {
             Ok(Settings {
@@ -19,11 +21,21 @@ pub async fn get_settings(db: State<'_, Database>) -> Result<Settings, String> {
                 currency: row.get(5)?,
                 enable_position_monitor: row.get::<_, i32>(6)? == 1,
                 enable_api_connections: row.get::<_, i32>(7)? == 1,
-                created_at: row.get(8)?,
-                updated_at: row.get(9)?,
+                max_consecutive_losses: row.get(8)?,
+                daily_loss_limit_r: row.get(9)?,
+                trash_retention_days: row.get(10)?,
+                sync_history_retention_days: row.get(11)?,
+                sync_history_max_entries: row.get(12)?,
+                sync_on_startup: row.get::<_, i32>(13)? == 1,
+                mirror_stale_timeout_secs: row.get(14)?,
+                require_biometric_unlock: row.get::<_, i32>(15)? == 1,
+                proxy_url: row.get(16)?,
+                breakeven_threshold: row.get(17)?,
+                created_at: row.get(18)?,
+                updated_at: row.get(19)?,
             })
         },
-    ).map_err(|e| e.to_string())?;
+    )?;
 
     Ok(settings)
 }
@@ -32,9 +44,9 @@ pub async fn get_settings(db: State<'_, Database>) -> Result<Settings, String> {
 pub async fn update_settings(
     db: State<'_, Database>,
     settings: UpdateSettingsInput,
-) -> Result<Settings, String> {
+) -> Result<Settings, CommandError> {
     {
-        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        let conn = db.pool.get()?;
 
         // Build dynamic UPDATE query
         let mut updates = Vec::new();
@@ -68,13 +80,61 @@ pub async fn update_settings(
             updates.push("enable_api_connections = ?");
             values.push(Box::new(val as i32));
         }
+        if let Some(val) = settings.max_consecutive_losses {
+            updates.push("max_consecutive_losses = ?");
+            values.push(Box::new(val.max(0)));
+        }
+        if let Some(val) = settings.daily_loss_limit_r {
+            updates.push("daily_loss_limit_r = ?");
+            values.push(Box::new(val.max(0.0)));
+        }
+        if let Some(val) = settings.trash_retention_days {
+            updates.push("trash_retention_days = ?");
+            values.push(Box::new(val.max(0)));
+        }
+        if let Some(val) = settings.sync_history_retention_days {
+            updates.push("sync_history_retention_days = ?");
+            values.push(Box::new(val.max(0)));
+        }
+        if let Some(val) = settings.sync_history_max_entries {
+            updates.push("sync_history_max_entries = ?");
+            values.push(Box::new(val.max(0)));
+        }
+        if let Some(val) = settings.sync_on_startup {
+            updates.push("sync_on_startup = ?");
+            values.push(Box::new(val as i32));
+        }
+        if let Some(val) = settings.mirror_stale_timeout_secs {
+            updates.push("mirror_stale_timeout_secs = ?");
+            values.push(Box::new(val.max(0)));
+        }
+        if let Some(val) = settings.require_biometric_unlock {
+            biometric::set_required(val)?;
+            updates.push("require_biometric_unlock = ?");
+            values.push(Box::new(val as i32));
+        }
+        if let Some(val) = settings.proxy_url {
+            let val = Some(val.trim().to_string()).filter(|url| !url.is_empty());
+            // Validate first: a rejected URL must not replace the proxy in use
+            if let Some(url) = &val {
+                proxy::parse_proxy_url(url).map_err(CommandError::Validation)?;
+            }
+            proxy::set_proxy(val.as_deref()).map_err(CommandError::Validation)?;
+            updates.push("proxy_url = ?");
+            values.push(Box::new(val));
+        }
+
+        if let Some(val) = settings.breakeven_threshold {
+            updates.push("breakeven_threshold = ?");
+            values.push(Box::new(val.max(0.0)));
+        }
 
         updates.push("updated_at = strftime('%s', 'now')");
 
         let query = format!("UPDATE settings SET {} WHERE id = 1", updates.join(", "));
         let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
 
-        conn.execute(&query, params.as_slice()).map_err(|e| e.to_string())?;
+        conn.execute(&query, params.as_slice())?;
     }
 
     get_settings(db).await
@@ -0,0 +1,226 @@
+use tauri::State;
+use crate::db::Database;
+use crate::commands::error::CommandError;
+use crate::commands::accounts::capital_settings;
+use crate::commands::stats::{closed_r_outcomes, StatsScope};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+const MAX_SIMULATIONS: usize = 10_000;
+const MAX_TRADES_PER_SIMULATION: usize = 1_000;
+const PERCENTILES: [u8; 5] = [5, 25, 50, 75, 95];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonteCarloInput {
+    /// Number of simulated equity paths (default 1000, max 10 000)
+    pub simulations: Option<usize>,
+    /// Trades per path (default 100, max 1000)
+    pub trades_per_simulation: Option<usize>,
+    /// Fraction of equity risked per 1R (default: the account's or settings' current_r_percent)
+    pub risk_per_trade: Option<f64>,
+    /// Drawdown from the starting equity that counts as ruin, as a fraction (default 0.5)
+    pub ruin_threshold: Option<f64>,
+    /// Restrict the sampled history to a dashboard date range
+    pub date_range: Option<String>,
+    /// Explicit close-date bounds (unix seconds), overriding `date_range`
+    pub start_ts: Option<i64>,
+    pub end_ts: Option<i64>,
+    /// Sample one account's history and use its capital settings (default: all accounts)
+    pub account_id: Option<String>,
+    /// Fixed seed for reproducible runs
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PercentilePath {
+    pub percentile: u8,
+    /// Equity after each trade, starting with the initial equity (trades_per_simulation + 1 points)
+    pub equity: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonteCarloResult {
+    pub simulations: usize,
+    pub trades_per_simulation: usize,
+    /// Historical R outcomes the simulation resampled from
+    pub sample_size: usize,
+    pub starting_equity: f64,
+    pub risk_per_trade: f64,
+    pub ruin_threshold: f64,
+    pub percentile_paths: Vec<PercentilePath>,
+    /// Probability (0-100) of hitting the ruin threshold at any point in a path
+    pub risk_of_ruin_pct: f64,
+    pub median_max_drawdown_pct: f64,
+}
+
+/// Resample historical R outcomes (with replacement) into many compounded equity paths
+#[tauri::command]
+pub async fn run_monte_carlo(
+    db: State<'_, Database>,
+    input: MonteCarloInput,
+) -> Result<MonteCarloResult, CommandError> {
+    let scope = StatsScope::resolve(
+        input.date_range.as_deref(),
+        input.start_ts,
+        input.end_ts,
+        input.account_id.clone(),
+    )?;
+
+    let (r_values, starting_equity, default_risk) = {
+        let conn = db.pool.get()?;
+
+        let (initial_capital, current_r_percent) = capital_settings(&conn, scope.account_id.as_deref())?;
+
+        let r_values = closed_r_outcomes(&conn, &scope)?;
+        (r_values, initial_capital, current_r_percent)
+        // conn is dropped here
+    };
+
+    if r_values.is_empty() {
+        return Err(CommandError::Validation("No closed trades with R data to simulate".to_string()));
+    }
+
+    let simulations = input.simulations.unwrap_or(1_000).clamp(1, MAX_SIMULATIONS);
+    let trades = input.trades_per_simulation.unwrap_or(100).clamp(1, MAX_TRADES_PER_SIMULATION);
+    let risk_per_trade = input.risk_per_trade.unwrap_or(default_risk);
+    let ruin_threshold = input.ruin_threshold.unwrap_or(0.5);
+
+    if !(0.0..1.0).contains(&risk_per_trade) || risk_per_trade == 0.0 {
+        return Err(CommandError::Validation("risk_per_trade must be between 0 and 1 (e.g. 0.02 for 2%)".to_string()));
+    }
+    if !(0.0..=1.0).contains(&ruin_threshold) || ruin_threshold == 0.0 {
+        return Err(CommandError::Validation("ruin_threshold must be between 0 and 1 (e.g. 0.5 for a 50% drawdown)".to_string()));
+    }
+
+    let seed = input.seed;
+    let sample_size = r_values.len();
+
+    // CPU-bound: keep it off the async runtime's worker threads
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let outcome = simulate_paths(&r_values, simulations, trades, risk_per_trade, ruin_threshold, &mut rng);
+
+        MonteCarloResult {
+            simulations,
+            trades_per_simulation: trades,
+            sample_size,
+            starting_equity,
+            risk_per_trade,
+            ruin_threshold,
+            percentile_paths: outcome
+                .percentile_paths
+                .into_iter()
+                .map(|(percentile, path)| PercentilePath {
+                    percentile,
+                    equity: path.into_iter().map(|m| m * starting_equity).collect(),
+                })
+                .collect(),
+            risk_of_ruin_pct: outcome.ruin_probability * 100.0,
+            median_max_drawdown_pct: outcome.median_max_drawdown * 100.0,
+        }
+    })
+    .await
+    .map_err(CommandError::from)
+}
+
+struct SimulationOutcome {
+    /// (percentile, equity multiple of the start after each trade)
+    percentile_paths: Vec<(u8, Vec<f64>)>,
+    ruin_probability: f64,
+    median_max_drawdown: f64,
+}
+
+fn simulate_paths(
+    r_values: &[f64],
+    simulations: usize,
+    trades: usize,
+    risk_per_trade: f64,
+    ruin_threshold: f64,
+    rng: &mut StdRng,
+) -> SimulationOutcome {
+    // steps[t][s] = equity multiple of simulation s after t trades
+    let mut steps: Vec<Vec<f64>> = vec![Vec::with_capacity(simulations); trades + 1];
+    let mut ruined = 0usize;
+    let mut max_drawdowns = Vec::with_capacity(simulations);
+
+    for _ in 0..simulations {
+        let mut equity = 1.0_f64;
+        let mut peak = 1.0_f64;
+        let mut max_drawdown = 0.0_f64;
+        let mut hit_ruin = false;
+        steps[0].push(equity);
+
+        for step in steps.iter_mut().skip(1) {
+            let r = r_values[rng.gen_range(0..r_values.len())];
+            equity = (equity * (1.0 + r * risk_per_trade)).max(0.0);
+            peak = peak.max(equity);
+            max_drawdown = max_drawdown.max((peak - equity) / peak);
+            if equity <= 1.0 - ruin_threshold {
+                hit_ruin = true;
+            }
+            step.push(equity);
+        }
+
+        if hit_ruin {
+            ruined += 1;
+        }
+        max_drawdowns.push(max_drawdown);
+    }
+
+    for values in steps.iter_mut() {
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    }
+    let percentile_paths = PERCENTILES
+        .iter()
+        .map(|&p| (p, steps.iter().map(|values| percentile_of_sorted(values, p as f64)).collect()))
+        .collect();
+
+    max_drawdowns.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    SimulationOutcome {
+        percentile_paths,
+        ruin_probability: ruined as f64 / simulations as f64,
+        median_max_drawdown: percentile_of_sorted(&max_drawdowns, 50.0),
+    }
+}
+
+/// Nearest-rank percentile of an ascending slice
+fn percentile_of_sorted(sorted: &[f64], percentile: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((percentile / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+    sorted[rank.min(sorted.len() - 1)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_outcomes_compound_deterministically() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let outcome = simulate_paths(&[1.0], 50, 10, 0.1, 0.5, &mut rng);
+
+        assert_eq!(outcome.ruin_probability, 0.0);
+        assert_eq!(outcome.median_max_drawdown, 0.0);
+        for (_, path) in &outcome.percentile_paths {
+            assert_eq!(path.len(), 11);
+            assert!((path[10] - 1.1_f64.powi(10)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_losing_outcomes_hit_ruin() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let outcome = simulate_paths(&[-1.0], 20, 30, 0.05, 0.5, &mut rng);
+
+        // 0.95^14 < 0.5, so every path is ruined within 30 trades
+        assert_eq!(outcome.ruin_probability, 1.0);
+        assert!(outcome.median_max_drawdown > 0.5);
+    }
+}
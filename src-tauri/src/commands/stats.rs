@@ -1,6 +1,14 @@
 use tauri::State;
 use crate::db::Database;
+use crate::commands::error::CommandError;
 use serde::{Deserialize, Serialize};
+use chrono::Datelike;
+use rusqlite::types::Value;
+use crate::commands::accounts::capital_settings;
+use crate::commands::capital::{daily_capital_flows, net_capital_flow};
+use crate::commands::trade_links::load_campaigns;
+use crate::models::TradeCampaign;
+use std::sync::{LazyLock, Mutex, TryLockError};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DashboardStats {
@@ -17,6 +25,13 @@ pub struct DashboardStats {
     pub avg_effective_rr: f64,
     pub best_trade: f64,
     pub worst_trade: f64,
+    /// Average R per closed trade (from pnl_in_r)
+    pub expectancy_r: f64,
+    pub r_std_dev: f64,
+    /// Van Tharp's System Quality Number: √min(n, 100) × expectancy / σ(R)
+    pub sqn: f64,
+    /// Closed trades with an R value, i.e. the sample behind the three metrics above
+    pub r_trade_count: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,76 +40,272 @@ pub struct EquityCurvePoint {
     pub cumulative_pnl: f64,
     pub daily_pnl: f64,
     pub trade_count: i32,
+    /// Cumulative value in the requested mode: dollars, R, or time-weighted percent return
+    pub cumulative_value: f64,
+    pub daily_value: f64,
+    /// Account balance: starting capital plus closed PnL and deposits minus withdrawals
+    pub equity: f64,
 }
 
+/// Unit of `get_equity_curve` values
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EquityCurveMode {
+    Usd,
+    R,
+    Percent,
+}
+
+impl EquityCurveMode {
+    fn parse(mode: Option<&str>) -> Result<Self, CommandError> {
+        match mode {
+            None | Some("usd") => Ok(EquityCurveMode::Usd),
+            Some("r") => Ok(EquityCurveMode::R),
+            Some("percent") => Ok(EquityCurveMode::Percent),
+            Some(other) => Err(CommandError::Validation(format!(
+                "Unknown equity curve mode: {} (expected usd, r or percent)",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrawdownPoint {
+    pub date: String,
+    pub equity: f64,
+    pub peak: f64,
+    pub drawdown: f64,
+    pub drawdown_pct: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrawdownStats {
+    pub max_drawdown: f64,
+    pub max_drawdown_pct: f64,
+    /// Longest peak-to-recovery stretch in days (an unrecovered drawdown counts up to the last trade day)
+    pub max_drawdown_duration_days: i64,
+    pub current_drawdown: f64,
+    pub current_drawdown_pct: f64,
+    pub curve: Vec<DrawdownPoint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdvancedStats {
+    pub sharpe_ratio: f64,
+    pub sortino_ratio: f64,
+    pub calmar_ratio: f64,
+    pub annualized_return_pct: f64,
+    pub annualized_volatility_pct: f64,
+    pub max_drawdown_pct: f64,
+    /// Annual risk-free rate used, as a fraction (0.04 = 4%)
+    pub risk_free_rate: f64,
+    /// Calendar days covered by the daily return series
+    pub days: i64,
+}
+
+/// Aggregated closed-trade performance for one bucket (weekday, hour, exchange, ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BucketStats {
+    pub bucket: String,
+    pub trade_count: i32,
+    pub wins: i32,
+    pub losses: i32,
+    pub win_rate: f64,
+    pub total_pnl: f64,
+    pub avg_pnl: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeBreakdown {
+    /// Sunday = "0" … Saturday = "6" (always 7 entries)
+    pub by_weekday: Vec<BucketStats>,
+    /// "0" … "23" (always 24 entries)
+    pub by_hour: Vec<BucketStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmotionStats {
+    /// One of TRADE_EMOTIONS, or "Unrecorded"
+    pub emotion: String,
+    pub trade_count: i32,
+    pub wins: i32,
+    pub losses: i32,
+    pub win_rate: f64,
+    pub total_pnl: f64,
+    /// Average R outcome (pnl_in_r, else PnL / 1R) over the trades where R is known
+    pub avg_r: f64,
+    pub r_trade_count: i32,
+}
+
+/// Closed-campaign performance, where trades connected through links count as one
+/// campaign and every other trade as a campaign of its own
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignStats {
+    pub campaign_count: i32,
+    /// Campaigns of more than one trade
+    pub linked_campaign_count: i32,
+    pub wins: i32,
+    pub losses: i32,
+    pub breakevens: i32,
+    pub win_rate: f64,
+    pub total_pnl: f64,
+    pub avg_pnl: f64,
+    /// Average of the campaigns' summed R over those where R is known
+    pub avg_r: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmotionBreakdown {
+    pub pre_trade: Vec<EmotionStats>,
+    pub post_trade: Vec<EmotionStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundingSymbolStats {
+    pub symbol: String,
+    pub net_funding: f64,
+    pub payments: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundingStats {
+    /// Funding paid out, as a positive amount
+    pub total_paid: f64,
+    pub total_received: f64,
+    pub net_funding: f64,
+    pub payments: i64,
+    /// Payments with no trade open on the symbol when they settled
+    pub unlinked_payments: i64,
+    /// Realized PnL of the trades in scope, without and with their linked funding
+    pub gross_pnl: f64,
+    pub net_pnl: f64,
+    pub by_symbol: Vec<FundingSymbolStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceBreakdown {
+    /// Lower-cased exchange name ("bitget", "blofin", ...); "unknown" when empty
+    pub by_exchange: Vec<BucketStats>,
+    /// USER_CREATED | CSV_IMPORT | API_IMPORT | LIVE_MIRROR
+    pub by_import_source: Vec<BucketStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyStats {
+    pub strategy: String,
+    pub trade_count: i32,
+    pub wins: i32,
+    pub losses: i32,
+    pub win_rate: f64,
+    pub total_pnl: f64,
+    pub avg_pnl: f64,
+    /// Average R over the trades that have pnl_in_r
+    pub expectancy_r: f64,
+    pub r_trade_count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarDay {
+    /// YYYY-MM-DD in the requested UTC offset
+    pub date: String,
+    pub total_pnl: f64,
+    pub trade_count: i32,
+    pub wins: i32,
+    pub losses: i32,
+    pub win_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarWeek {
+    /// Monday of the week (YYYY-MM-DD)
+    pub week_start: String,
+    pub total_pnl: f64,
+    pub trade_count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PnlCalendar {
+    pub year: i32,
+    pub month: u32,
+    /// Only days with closed trades
+    pub days: Vec<CalendarDay>,
+    pub weeks: Vec<CalendarWeek>,
+    pub total_pnl: f64,
+    pub trade_count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KellySuggestion {
+    /// Closed trades with an R value in the window
+    pub trade_count: i32,
+    pub win_rate: f64,
+    /// Average winning / losing trade in R (avg_loss_r is positive)
+    pub avg_win_r: f64,
+    pub avg_loss_r: f64,
+    pub payoff_ratio: f64,
+    /// Full Kelly fraction of equity; negative means the sample has no edge
+    pub kelly_fraction: f64,
+    pub half_kelly_fraction: f64,
+    /// Half Kelly floored at 0, as a fraction like settings.current_r_percent
+    pub suggested_r_percent: f64,
+    pub current_r_percent: f64,
+}
+
+/// Rolling presets ("week", "month", ...) move with the clock, so cached results for them expire
+const ROLLING_CACHE_TTL_SECS: i64 = 60;
+
 #[tauri::command]
 pub async fn get_dashboard_stats(
     db: State<'_, Database>,
     date_range: Option<String>,
-) -> Result<DashboardStats, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-
-    // Calculate date threshold based on range
-    let date_threshold = match date_range.as_deref() {
-        Some("today") => {
-            Some(chrono::Utc::now().date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp())
-        },
-        Some("week") => {
-            Some(chrono::Utc::now().timestamp() - (7 * 24 * 60 * 60))
-        },
-        Some("month") => {
-            Some(chrono::Utc::now().timestamp() - (30 * 24 * 60 * 60))
-        },
-        Some("3months") => {
-            Some(chrono::Utc::now().timestamp() - (90 * 24 * 60 * 60))
-        },
-        Some("6months") => {
-            Some(chrono::Utc::now().timestamp() - (180 * 24 * 60 * 60))
-        },
-        Some("year") => {
-            Some(chrono::Utc::now().timestamp() - (365 * 24 * 60 * 60))
-        },
-        _ => None,
-    };
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    account_id: Option<String>,
+) -> Result<DashboardStats, CommandError> {
+    let conn = db.pool.get()?;
+
+    let scope = &StatsScope::resolve(date_range.as_deref(), start_ts, end_ts, account_id.clone())?;
+    let cache_key = format!("dashboard|{:?}|{:?}|{:?}|{:?}", date_range, start_ts, end_ts, account_id);
 
+    cached_stats(&conn, &cache_key, scope, || compute_dashboard_stats(&conn, scope))
+}
+
+fn compute_dashboard_stats(conn: &rusqlite::Connection, scope: &StatsScope) -> Result<DashboardStats, CommandError> {
     // Build filter clause and params
-    // SAFETY: date_filter is always a compile-time constant string ("AND close_date >= ?" or ""),
-    // never user-provided input. This pattern is safe from SQL injection as long as date_filter
-    // remains a hardcoded string. All dynamic values are passed through parameterized queries.
-    let (date_filter, date_params): (&str, Vec<i64>) = match date_threshold {
-        Some(threshold) => ("AND close_date >= ?", vec![threshold]),
-        None => ("", vec![]),
-    };
+    // SAFETY: scope_filter is assembled from compile-time constant fragments only, never
+    // user-provided input. All dynamic values are passed through parameterized queries.
+    let (scope_filter, scope_params) = scope.sql_filter();
 
     // Total trades
     let total_trades: i32 = conn.query_row(
-        &format!("SELECT COUNT(*) FROM trades WHERE deleted_at IS NULL {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
+        &format!("SELECT COUNT(*) FROM trades WHERE deleted_at IS NULL {}", scope_filter),
+        rusqlite::params_from_iter(scope_params.iter()),
         |row| row.get(0),
     ).unwrap_or(0);
 
     // Status counts
     let wins: i32 = conn.query_row(
-        &format!("SELECT COUNT(*) FROM trades WHERE deleted_at IS NULL AND status = 'WIN' {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
+        &format!("SELECT COUNT(*) FROM trades WHERE deleted_at IS NULL AND status = 'WIN' {}", scope_filter),
+        rusqlite::params_from_iter(scope_params.iter()),
         |row| row.get(0),
     ).unwrap_or(0);
 
     let losses: i32 = conn.query_row(
-        &format!("SELECT COUNT(*) FROM trades WHERE deleted_at IS NULL AND status = 'LOSS' {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
+        &format!("SELECT COUNT(*) FROM trades WHERE deleted_at IS NULL AND status = 'LOSS' {}", scope_filter),
+        rusqlite::params_from_iter(scope_params.iter()),
         |row| row.get(0),
     ).unwrap_or(0);
 
     let breakevens: i32 = conn.query_row(
-        &format!("SELECT COUNT(*) FROM trades WHERE deleted_at IS NULL AND status = 'BE' {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
+        &format!("SELECT COUNT(*) FROM trades WHERE deleted_at IS NULL AND status = 'BE' {}", scope_filter),
+        rusqlite::params_from_iter(scope_params.iter()),
         |row| row.get(0),
     ).unwrap_or(0);
 
+    let (account_filter, account_params) = scope.account_filter();
     let open_trades: i32 = conn.query_row(
-        "SELECT COUNT(*) FROM trades WHERE deleted_at IS NULL AND status = 'OPEN'",
-        [],
+        &format!("SELECT COUNT(*) FROM trades WHERE deleted_at IS NULL AND status = 'OPEN' {}", account_filter),
+        rusqlite::params_from_iter(account_params.iter()),
         |row| row.get(0),
     ).unwrap_or(0);
 
@@ -108,22 +319,22 @@ pub async fn get_dashboard_stats(
 
     // Total P&L
     let total_pnl: f64 = conn.query_row(
-        &format!("SELECT COALESCE(SUM(total_pnl), 0.0) FROM trades WHERE deleted_at IS NULL AND total_pnl IS NOT NULL {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
+        &format!("SELECT COALESCE(SUM(total_pnl), 0.0) FROM trades WHERE deleted_at IS NULL AND total_pnl IS NOT NULL {}", scope_filter),
+        rusqlite::params_from_iter(scope_params.iter()),
         |row| row.get(0),
     ).unwrap_or(0.0);
 
     // Gross profit
     let gross_profit: f64 = conn.query_row(
-        &format!("SELECT COALESCE(SUM(total_pnl), 0.0) FROM trades WHERE deleted_at IS NULL AND total_pnl > 0 {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
+        &format!("SELECT COALESCE(SUM(total_pnl), 0.0) FROM trades WHERE deleted_at IS NULL AND total_pnl > 0 {}", scope_filter),
+        rusqlite::params_from_iter(scope_params.iter()),
         |row| row.get(0),
     ).unwrap_or(0.0);
 
     // Gross loss
     let gross_loss: f64 = conn.query_row(
-        &format!("SELECT COALESCE(ABS(SUM(total_pnl)), 0.0) FROM trades WHERE deleted_at IS NULL AND total_pnl < 0 {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
+        &format!("SELECT COALESCE(ABS(SUM(total_pnl)), 0.0) FROM trades WHERE deleted_at IS NULL AND total_pnl < 0 {}", scope_filter),
+        rusqlite::params_from_iter(scope_params.iter()),
         |row| row.get(0),
     ).unwrap_or(0.0);
 
@@ -138,25 +349,35 @@ pub async fn get_dashboard_stats(
 
     // Average effective RR
     let avg_effective_rr: f64 = conn.query_row(
-        &format!("SELECT COALESCE(AVG(effective_weighted_rr), 0.0) FROM trades WHERE deleted_at IS NULL AND effective_weighted_rr IS NOT NULL {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
+        &format!("SELECT COALESCE(AVG(effective_weighted_rr), 0.0) FROM trades WHERE deleted_at IS NULL AND effective_weighted_rr IS NOT NULL {}", scope_filter),
+        rusqlite::params_from_iter(scope_params.iter()),
         |row| row.get(0),
     ).unwrap_or(0.0);
 
     // Best trade
     let best_trade: f64 = conn.query_row(
-        &format!("SELECT COALESCE(MAX(total_pnl), 0.0) FROM trades WHERE deleted_at IS NULL AND total_pnl IS NOT NULL {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
+        &format!("SELECT COALESCE(MAX(total_pnl), 0.0) FROM trades WHERE deleted_at IS NULL AND total_pnl IS NOT NULL {}", scope_filter),
+        rusqlite::params_from_iter(scope_params.iter()),
         |row| row.get(0),
     ).unwrap_or(0.0);
 
     // Worst trade
     let worst_trade: f64 = conn.query_row(
-        &format!("SELECT COALESCE(MIN(total_pnl), 0.0) FROM trades WHERE deleted_at IS NULL AND total_pnl IS NOT NULL {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
+        &format!("SELECT COALESCE(MIN(total_pnl), 0.0) FROM trades WHERE deleted_at IS NULL AND total_pnl IS NOT NULL {}", scope_filter),
+        rusqlite::params_from_iter(scope_params.iter()),
         |row| row.get(0),
     ).unwrap_or(0.0);
 
+    // Expectancy / SQN from R-multiples
+    let mut stmt = conn.prepare(&format!(
+        "SELECT pnl_in_r FROM trades WHERE deleted_at IS NULL AND pnl_in_r IS NOT NULL AND status IN ('WIN', 'LOSS', 'BE') {}",
+        scope_filter
+    ))?;
+    let r_values = stmt
+        .query_map(rusqlite::params_from_iter(scope_params.iter()), |row| row.get::<_, f64>(0))?
+        .collect::<Result<Vec<f64>, _>>()?;
+    let (expectancy_r, r_std_dev, sqn) = r_multiple_stats(&r_values);
+
     Ok(DashboardStats {
         total_trades,
         wins,
@@ -171,6 +392,10 @@ pub async fn get_dashboard_stats(
         avg_effective_rr,
         best_trade,
         worst_trade,
+        expectancy_r,
+        r_std_dev,
+        sqn,
+        r_trade_count: r_values.len() as i32,
     })
 }
 
@@ -178,69 +403,1184 @@ pub async fn get_dashboard_stats(
 pub async fn get_equity_curve(
     db: State<'_, Database>,
     date_range: Option<String>,
-) -> Result<Vec<EquityCurvePoint>, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-
-    // Calculate date threshold based on range
-    let date_threshold = match date_range.as_deref() {
-        Some("today") => {
-            Some(chrono::Utc::now().date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp())
-        },
-        Some("week") => {
-            Some(chrono::Utc::now().timestamp() - (7 * 24 * 60 * 60))
-        },
-        Some("month") => {
-            Some(chrono::Utc::now().timestamp() - (30 * 24 * 60 * 60))
-        },
-        Some("3months") => {
-            Some(chrono::Utc::now().timestamp() - (90 * 24 * 60 * 60))
-        },
-        Some("6months") => {
-            Some(chrono::Utc::now().timestamp() - (180 * 24 * 60 * 60))
-        },
-        Some("year") => {
-            Some(chrono::Utc::now().timestamp() - (365 * 24 * 60 * 60))
-        },
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    account_id: Option<String>,
+    mode: Option<String>,
+) -> Result<Vec<EquityCurvePoint>, CommandError> {
+    let mode = EquityCurveMode::parse(mode.as_deref())?;
+    let conn = db.pool.get()?;
+
+    let scope = &StatsScope::resolve(date_range.as_deref(), start_ts, end_ts, account_id.clone())?;
+    let cache_key = format!("equity_curve|{:?}|{:?}|{:?}|{:?}|{:?}", date_range, start_ts, end_ts, account_id, mode);
+
+    cached_stats(&conn, &cache_key, scope, || {
+        let daily = daily_pnl_series(&conn, scope)?;
+        let daily_r = match mode {
+            EquityCurveMode::R => daily_r_series(&conn, scope)?,
+            _ => std::collections::HashMap::new(),
+        };
+        let starting_balance = starting_equity(&conn, scope)?;
+        let flows = daily_capital_flows(&conn, scope.start, scope.account_id.as_deref())?;
+
+        Ok(build_equity_curve(mode, &daily, &daily_r, starting_balance, &flows))
+    })
+}
+
+/// Percent mode is a time-weighted return: each day's PnL is measured against the balance
+/// after that day's deposits/withdrawals, so capital changes never count as performance.
+fn build_equity_curve(
+    mode: EquityCurveMode,
+    daily: &[(String, f64, i32)],
+    daily_r: &std::collections::HashMap<String, f64>,
+    starting_balance: f64,
+    flows: &[(String, f64)],
+) -> Vec<EquityCurvePoint> {
+    let mut cumulative_pnl = 0.0;
+    let mut cumulative_value = 0.0;
+    let mut balance = starting_balance;
+    let mut growth = 1.0;
+    let mut pending_flows = flows.iter().peekable();
+    let mut result: Vec<EquityCurvePoint> = Vec::new();
+
+    for (date, daily_pnl, trade_count) in daily {
+        // Days are YYYY-MM-DD strings, so they compare chronologically
+        while let Some((_, amount)) = pending_flows.next_if(|(flow_date, _)| flow_date <= date) {
+            balance += amount;
+        }
+
+        let daily_value = match mode {
+            EquityCurveMode::Usd => *daily_pnl,
+            EquityCurveMode::R => daily_r.get(date).copied().unwrap_or(0.0),
+            EquityCurveMode::Percent if balance > 0.0 => daily_pnl / balance * 100.0,
+            EquityCurveMode::Percent => 0.0,
+        };
+        cumulative_pnl += daily_pnl;
+        balance += daily_pnl;
+        cumulative_value = match mode {
+            EquityCurveMode::Percent => {
+                growth *= 1.0 + daily_value / 100.0;
+                (growth - 1.0) * 100.0
+            }
+            _ => cumulative_value + daily_value,
+        };
+        result.push(EquityCurvePoint {
+            date: date.clone(),
+            cumulative_pnl,
+            daily_pnl: *daily_pnl,
+            trade_count: *trade_count,
+            cumulative_value,
+            daily_value,
+            equity: balance,
+        });
+    }
+
+    result
+}
+
+/// Drawdown from the running equity peak, based on initial capital plus closed-trade PnL.
+/// Deposits and withdrawals move equity and peak alike, so they never show as drawdowns.
+#[tauri::command]
+pub async fn get_drawdown(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    account_id: Option<String>,
+) -> Result<DrawdownStats, CommandError> {
+    let conn = db.pool.get()?;
+
+    let scope = &StatsScope::resolve(date_range.as_deref(), start_ts, end_ts, account_id.clone())?;
+    let cache_key = format!("drawdown|{:?}|{:?}|{:?}|{:?}", date_range, start_ts, end_ts, account_id);
+
+    cached_stats(&conn, &cache_key, scope, || {
+        let starting_equity = starting_equity(&conn, scope)?;
+        let daily = daily_pnl_series(&conn, scope)?;
+        let flows = daily_capital_flows(&conn, scope.start, scope.account_id.as_deref())?;
+        Ok(compute_drawdown(starting_equity, &daily, &flows))
+    })
+}
+
+fn compute_drawdown(starting_equity: f64, daily: &[(String, f64, i32)], flows: &[(String, f64)]) -> DrawdownStats {
+    let parse_day = |date: &str| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok();
+
+    let mut equity = starting_equity;
+    let mut peak = starting_equity;
+    let mut peak_day = daily.first().and_then(|(date, _, _)| parse_day(date));
+    let mut in_drawdown = false;
+    let mut pending_flows = flows.iter().peekable();
+
+    let mut max_drawdown = 0.0_f64;
+    let mut max_drawdown_pct = 0.0_f64;
+    let mut max_duration = 0_i64;
+    let mut curve = Vec::with_capacity(daily.len());
+
+    for (date, pnl, _) in daily {
+        // Days are YYYY-MM-DD strings, so they compare chronologically
+        while let Some((_, amount)) = pending_flows.next_if(|(flow_date, _)| flow_date <= date) {
+            equity += amount;
+            peak += amount;
+        }
+        equity += pnl;
+        let day = parse_day(date);
+
+        if equity >= peak {
+            // New high (or full recovery) closes any open drawdown period
+            if in_drawdown && let (Some(start), Some(end)) = (peak_day, day) {
+                max_duration = max_duration.max((end - start).num_days());
+            }
+            in_drawdown = false;
+            peak = equity;
+            peak_day = day;
+        } else {
+            in_drawdown = true;
+        }
+
+        let drawdown = peak - equity;
+        let drawdown_pct = if peak > 0.0 { drawdown / peak * 100.0 } else { 0.0 };
+        max_drawdown = max_drawdown.max(drawdown);
+        max_drawdown_pct = max_drawdown_pct.max(drawdown_pct);
+
+        curve.push(DrawdownPoint {
+            date: date.clone(),
+            equity,
+            peak,
+            drawdown,
+            drawdown_pct,
+        });
+    }
+
+    // A drawdown still open at the end lasts until the last trade day
+    if in_drawdown
+        && let (Some(start), Some(end)) = (peak_day, daily.last().and_then(|(date, _, _)| parse_day(date)))
+    {
+        max_duration = max_duration.max((end - start).num_days());
+    }
+
+    let (current_drawdown, current_drawdown_pct) = curve
+        .last()
+        .map(|p| (p.drawdown, p.drawdown_pct))
+        .unwrap_or((0.0, 0.0));
+
+    DrawdownStats {
+        max_drawdown,
+        max_drawdown_pct,
+        max_drawdown_duration_days: max_duration,
+        current_drawdown,
+        current_drawdown_pct,
+        curve,
+    }
+}
+
+/// Risk-adjusted return metrics from daily returns on equity.
+/// Crypto trades every day, so ratios are annualized over 365 days.
+#[tauri::command]
+pub async fn get_advanced_stats(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    account_id: Option<String>,
+    risk_free_rate: Option<f64>,
+) -> Result<AdvancedStats, CommandError> {
+    let conn = db.pool.get()?;
+
+    let scope = &StatsScope::resolve(date_range.as_deref(), start_ts, end_ts, account_id.clone())?;
+    let risk_free_rate = risk_free_rate.unwrap_or(0.0);
+    let cache_key = format!("advanced|{:?}|{:?}|{:?}|{:?}|{}", date_range, start_ts, end_ts, account_id, risk_free_rate);
+
+    cached_stats(&conn, &cache_key, scope, || {
+        let starting_equity = starting_equity(&conn, scope)?;
+        let daily = daily_pnl_series(&conn, scope)?;
+        let flows = daily_capital_flows(&conn, scope.start, scope.account_id.as_deref())?;
+        Ok(compute_advanced_stats(starting_equity, &daily, &flows, risk_free_rate))
+    })
+}
+
+/// Returns are time-weighted like the percent equity curve: each day's PnL against the
+/// equity after that day's deposits and withdrawals
+fn compute_advanced_stats(
+    starting_equity: f64,
+    daily: &[(String, f64, i32)],
+    flows: &[(String, f64)],
+    risk_free_rate: f64,
+) -> AdvancedStats {
+    let mut stats = AdvancedStats {
+        sharpe_ratio: 0.0,
+        sortino_ratio: 0.0,
+        calmar_ratio: 0.0,
+        annualized_return_pct: 0.0,
+        annualized_volatility_pct: 0.0,
+        max_drawdown_pct: compute_drawdown(starting_equity, daily, flows).max_drawdown_pct,
+        risk_free_rate,
+        days: 0,
+    };
+
+    let parse_day = |date: &str| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok();
+    let (Some(first), Some(last)) = (
+        daily.first().and_then(|(date, _, _)| parse_day(date)),
+        daily.last().and_then(|(date, _, _)| parse_day(date)),
+    ) else {
+        return stats;
+    };
+    if starting_equity <= 0.0 {
+        return stats;
+    }
+
+    // Daily returns over every calendar day in the range (no-trade days return 0)
+    let pnl_by_day: std::collections::HashMap<&str, f64> =
+        daily.iter().map(|(date, pnl, _)| (date.as_str(), *pnl)).collect();
+    let mut returns = Vec::new();
+    let mut equity = starting_equity;
+    let mut growth = 1.0;
+    let mut pending_flows = flows.iter().peekable();
+    let mut day = first;
+    while day <= last {
+        let date = day.format("%Y-%m-%d").to_string();
+        while let Some((_, amount)) = pending_flows.next_if(|(flow_date, _)| *flow_date <= date) {
+            equity += amount;
+        }
+        let pnl = pnl_by_day.get(date.as_str()).copied().unwrap_or(0.0);
+        if equity > 0.0 {
+            returns.push(pnl / equity);
+            growth *= 1.0 + pnl / equity;
+        }
+        equity += pnl;
+        day += chrono::Duration::days(1);
+    }
+
+    let n = returns.len() as f64;
+    stats.days = returns.len() as i64;
+    if returns.len() < 2 {
+        return stats;
+    }
+
+    let daily_rf = risk_free_rate / 365.0;
+    let mean = returns.iter().sum::<f64>() / n;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    let std_dev = variance.sqrt();
+    let downside_dev = (returns.iter().map(|r| (r - daily_rf).min(0.0).powi(2)).sum::<f64>() / n).sqrt();
+    let annualization = 365.0_f64.sqrt();
+
+    if std_dev > 0.0 {
+        stats.sharpe_ratio = (mean - daily_rf) / std_dev * annualization;
+    }
+    if downside_dev > 0.0 {
+        stats.sortino_ratio = (mean - daily_rf) / downside_dev * annualization;
+    }
+    stats.annualized_volatility_pct = std_dev * annualization * 100.0;
+
+    // CAGR; a wiped-out account has no meaningful annualized figure
+    if growth > 0.0 {
+        stats.annualized_return_pct = (growth.powf(365.0 / n) - 1.0) * 100.0;
+    } else {
+        stats.annualized_return_pct = -100.0;
+    }
+    if stats.max_drawdown_pct > 0.0 {
+        stats.calmar_ratio = stats.annualized_return_pct / stats.max_drawdown_pct;
+    }
+
+    stats
+}
+
+/// Performance by weekday and hour of the trade open time.
+/// `utc_offset_minutes` shifts buckets into the user's local time (e.g. 120 for UTC+2).
+#[tauri::command]
+pub async fn get_time_breakdown(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    account_id: Option<String>,
+    utc_offset_minutes: Option<i32>,
+) -> Result<TimeBreakdown, CommandError> {
+    let conn = db.pool.get()?;
+
+    let scope = &StatsScope::resolve(date_range.as_deref(), start_ts, end_ts, account_id.clone())?;
+    let offset_seconds = utc_offset_minutes.unwrap_or(0) as i64 * 60;
+    let cache_key = format!("time_breakdown|{:?}|{:?}|{:?}|{:?}|{}", date_range, start_ts, end_ts, account_id, offset_seconds);
+
+    cached_stats(&conn, &cache_key, scope, || {
+        let by_weekday = query_bucket_stats(
+            &conn,
+            "CAST(strftime('%w', trade_date + ?, 'unixepoch') AS INTEGER)",
+            Some(offset_seconds),
+            scope,
+        )?;
+        let by_hour = query_bucket_stats(
+            &conn,
+            "CAST(strftime('%H', trade_date + ?, 'unixepoch') AS INTEGER)",
+            Some(offset_seconds),
+            scope,
+        )?;
+
+        Ok(TimeBreakdown {
+            by_weekday: fill_numeric_buckets(by_weekday, 7),
+            by_hour: fill_numeric_buckets(by_hour, 24),
+        })
+    })
+}
+
+/// Performance per exchange and per import source, to compare accounts and
+/// cross-check imported data against manual entries
+#[tauri::command]
+pub async fn get_source_breakdown(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    account_id: Option<String>,
+) -> Result<SourceBreakdown, CommandError> {
+    let conn = db.pool.get()?;
+
+    let scope = &StatsScope::resolve(date_range.as_deref(), start_ts, end_ts, account_id.clone())?;
+    let cache_key = format!("source_breakdown|{:?}|{:?}|{:?}|{:?}", date_range, start_ts, end_ts, account_id);
+
+    cached_stats(&conn, &cache_key, scope, || {
+        // Exchange names are not consistently cased across importers ("BitGet" vs "bitget")
+        let by_exchange = query_bucket_stats(
+            &conn,
+            "COALESCE(NULLIF(LOWER(TRIM(exchange)), ''), 'unknown')",
+            None,
+            scope,
+        )?;
+        let by_import_source = query_bucket_stats(
+            &conn,
+            "COALESCE(import_source, 'USER_CREATED')",
+            None,
+            scope,
+        )?;
+
+        Ok(SourceBreakdown {
+            by_exchange,
+            by_import_source,
+        })
+    })
+}
+
+/// Funding fees paid and received. Payments are windowed by when they settled; with an
+/// account, only payments linked to that account's trades count.
+#[tauri::command]
+pub async fn get_funding_stats(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    account_id: Option<String>,
+) -> Result<FundingStats, CommandError> {
+    let conn = db.pool.get()?;
+
+    let scope = &StatsScope::resolve(date_range.as_deref(), start_ts, end_ts, account_id.clone())?;
+    let cache_key = format!("funding|{:?}|{:?}|{:?}|{:?}", date_range, start_ts, end_ts, account_id);
+
+    cached_stats(&conn, &cache_key, scope, || compute_funding_stats(&conn, scope))
+}
+
+fn compute_funding_stats(conn: &rusqlite::Connection, scope: &StatsScope) -> Result<FundingStats, CommandError> {
+    let mut funding_filter = String::new();
+    let mut funding_params = Vec::new();
+    if let Some(start) = scope.start {
+        funding_filter.push_str("AND funding_time >= ? ");
+        funding_params.push(Value::Integer(start));
+    }
+    if let Some(end) = scope.end {
+        funding_filter.push_str("AND funding_time <= ? ");
+        funding_params.push(Value::Integer(end));
+    }
+    if let Some(id) = &scope.account_id {
+        funding_filter.push_str("AND trade_id IN (SELECT id FROM trades WHERE account_id = ?) ");
+        funding_params.push(Value::Text(id.clone()));
+    }
+
+    let (total_paid, total_received, payments, unlinked_payments): (f64, f64, i64, i64) = conn
+        .query_row(
+            &format!(
+                "SELECT COALESCE(-SUM(CASE WHEN amount < 0 THEN amount ELSE 0 END), 0.0),
+                        COALESCE(SUM(CASE WHEN amount > 0 THEN amount ELSE 0 END), 0.0),
+                        COUNT(*),
+                        COALESCE(SUM(CASE WHEN trade_id IS NULL THEN 1 ELSE 0 END), 0)
+                 FROM funding_payments WHERE 1 = 1 {}",
+                funding_filter
+            ),
+            rusqlite::params_from_iter(funding_params.iter()),
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT symbol, SUM(amount), COUNT(*) FROM funding_payments WHERE 1 = 1 {}
+             GROUP BY UPPER(symbol) ORDER BY SUM(amount)",
+            funding_filter
+        ))?;
+    let by_symbol = stmt
+        .query_map(rusqlite::params_from_iter(funding_params.iter()), |row| {
+            Ok(FundingSymbolStats {
+                symbol: row.get(0)?,
+                net_funding: row.get(1)?,
+                payments: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Trades are windowed by close date like the other stats, with all of their funding
+    let (scope_filter, scope_params) = scope.sql_filter();
+    let (gross_pnl, trade_funding): (f64, f64) = conn
+        .query_row(
+            &format!(
+                "SELECT COALESCE(SUM(total_pnl), 0.0),
+                        COALESCE(SUM((SELECT SUM(amount) FROM funding_payments f WHERE f.trade_id = trades.id)), 0.0)
+                 FROM trades WHERE deleted_at IS NULL AND total_pnl IS NOT NULL {}",
+                scope_filter
+            ),
+            rusqlite::params_from_iter(scope_params.iter()),
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+    Ok(FundingStats {
+        total_paid,
+        total_received,
+        net_funding: total_received - total_paid,
+        payments,
+        unlinked_payments,
+        gross_pnl,
+        net_pnl: gross_pnl + trade_funding,
+        by_symbol,
+    })
+}
+
+/// Performance per tag. Trades with several tags count toward each of them;
+/// untagged trades are grouped as "Untagged".
+#[tauri::command]
+pub async fn get_tag_stats(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    account_id: Option<String>,
+) -> Result<Vec<BucketStats>, CommandError> {
+    let conn = db.pool.get()?;
+
+    let scope = &StatsScope::resolve(date_range.as_deref(), start_ts, end_ts, account_id.clone())?;
+    let cache_key = format!("tag_stats|{:?}|{:?}|{:?}|{:?}", date_range, start_ts, end_ts, account_id);
+
+    cached_stats(&conn, &cache_key, scope, || {
+        let mut stats = query_bucket_stats_from(
+            &conn,
+            "trades LEFT JOIN trade_tags ON trade_tags.trade_id = trades.id
+             LEFT JOIN tags ON tags.id = trade_tags.tag_id",
+            "COALESCE(tags.name, 'Untagged')",
+            None,
+            scope,
+        )?;
+        stats.sort_by(|a, b| b.total_pnl.partial_cmp(&a.total_pnl).unwrap_or(std::cmp::Ordering::Equal));
+        Ok(stats)
+    })
+}
+
+/// Performance per execution grade (A-D, then "Ungraded"), to compare execution quality
+/// against outcome
+#[tauri::command]
+pub async fn get_grade_stats(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    account_id: Option<String>,
+) -> Result<Vec<BucketStats>, CommandError> {
+    let conn = db.pool.get()?;
+
+    let scope = &StatsScope::resolve(date_range.as_deref(), start_ts, end_ts, account_id.clone())?;
+    let cache_key = format!("grade_stats|{:?}|{:?}|{:?}|{:?}", date_range, start_ts, end_ts, account_id);
+
+    cached_stats(&conn, &cache_key, scope, || {
+        query_bucket_stats(&conn, "COALESCE(grade, 'Ungraded')", None, scope)
+    })
+}
+
+/// Outcomes per campaign instead of per trade, so a setup re-entered or scaled into across
+/// several trades counts once. A campaign is in range when its last trade closed in it.
+#[tauri::command]
+pub async fn get_campaign_stats(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    account_id: Option<String>,
+) -> Result<CampaignStats, CommandError> {
+    let conn = db.pool.get()?;
+
+    let scope = &StatsScope::resolve(date_range.as_deref(), start_ts, end_ts, account_id.clone())?;
+    let cache_key = format!("campaign_stats|{:?}|{:?}|{:?}|{:?}", date_range, start_ts, end_ts, account_id);
+
+    cached_stats(&conn, &cache_key, scope, || {
+        let campaigns = load_campaigns(&conn, scope.account_filter())?;
+        Ok(compute_campaign_stats(&campaigns, scope))
+    })
+}
+
+fn compute_campaign_stats(campaigns: &[TradeCampaign], scope: &StatsScope) -> CampaignStats {
+    let closed: Vec<&TradeCampaign> = campaigns
+        .iter()
+        .filter(|c| {
+            c.end_date.is_some_and(|end| {
+                scope.start.is_none_or(|start| end >= start) && scope.end.is_none_or(|scope_end| end <= scope_end)
+            })
+        })
+        .collect();
+
+    let count = |status: &str| closed.iter().filter(|c| c.status == status).count() as i32;
+    let (wins, losses) = (count("WIN"), count("LOSS"));
+    let total_pnl: f64 = closed.iter().map(|c| c.total_pnl).sum();
+    let r_values: Vec<f64> = closed.iter().filter_map(|c| c.pnl_in_r).collect();
+
+    CampaignStats {
+        campaign_count: closed.len() as i32,
+        linked_campaign_count: closed.iter().filter(|c| c.trades.len() > 1).count() as i32,
+        wins,
+        losses,
+        breakevens: count("BE"),
+        win_rate: if wins + losses > 0 { wins as f64 / (wins + losses) as f64 * 100.0 } else { 0.0 },
+        total_pnl,
+        avg_pnl: if closed.is_empty() { 0.0 } else { total_pnl / closed.len() as f64 },
+        avg_r: if r_values.is_empty() { 0.0 } else { r_values.iter().sum::<f64>() / r_values.len() as f64 },
+    }
+}
+
+/// Closed-trade outcomes per recorded emotion, before entry and after close, sorted by
+/// average R (best first)
+#[tauri::command]
+pub async fn get_emotion_stats(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    account_id: Option<String>,
+) -> Result<EmotionBreakdown, CommandError> {
+    let conn = db.pool.get()?;
+
+    let scope = &StatsScope::resolve(date_range.as_deref(), start_ts, end_ts, account_id.clone())?;
+    let cache_key = format!("emotion_stats|{:?}|{:?}|{:?}|{:?}", date_range, start_ts, end_ts, account_id);
+
+    cached_stats(&conn, &cache_key, scope, || {
+        Ok(EmotionBreakdown {
+            pre_trade: query_emotion_stats(&conn, "pre_emotion", scope)?,
+            post_trade: query_emotion_stats(&conn, "post_emotion", scope)?,
+        })
+    })
+}
+
+/// `column` must be a hardcoded emotion column name
+fn query_emotion_stats(
+    conn: &rusqlite::Connection,
+    column: &str,
+    scope: &StatsScope,
+) -> Result<Vec<EmotionStats>, CommandError> {
+    // SAFETY: scope_filter is built from hardcoded fragments, values are bound as parameters
+    let (scope_filter, scope_params) = scope.sql_filter();
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT COALESCE({0}, 'Unrecorded') AS emotion,
+                    COUNT(*),
+                    SUM(CASE WHEN status = 'WIN' THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN status = 'LOSS' THEN 1 ELSE 0 END),
+                    COALESCE(SUM(total_pnl), 0.0),
+                    AVG(COALESCE(pnl_in_r, total_pnl / NULLIF(one_r, 0))),
+                    COUNT(COALESCE(pnl_in_r, total_pnl / NULLIF(one_r, 0)))
+             FROM trades
+             WHERE deleted_at IS NULL AND status IN ('WIN', 'LOSS', 'BE') {1}
+             GROUP BY emotion",
+            column, scope_filter
+        ))?;
+
+    let mut stats = stmt
+        .query_map(rusqlite::params_from_iter(scope_params.iter()), |row| {
+            let trade_count: i32 = row.get(1)?;
+            let wins: i32 = row.get(2)?;
+            let losses: i32 = row.get(3)?;
+            let decided = wins + losses;
+            Ok(EmotionStats {
+                emotion: row.get(0)?,
+                trade_count,
+                wins,
+                losses,
+                win_rate: if decided > 0 { wins as f64 / decided as f64 * 100.0 } else { 0.0 },
+                total_pnl: row.get(4)?,
+                avg_r: row.get::<_, Option<f64>>(5)?.unwrap_or(0.0),
+                r_trade_count: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    stats.sort_by(|a, b| b.avg_r.partial_cmp(&a.avg_r).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(stats)
+}
+
+/// Per-strategy performance for playbook review. The strategy is the bracketed
+/// prefix of the notes ("[Breakout] retest of ..."); trades without one are
+/// grouped as "Unassigned". See get_tag_stats for tag-based grouping.
+#[tauri::command]
+pub async fn get_strategy_stats(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    account_id: Option<String>,
+) -> Result<Vec<StrategyStats>, CommandError> {
+    let conn = db.pool.get()?;
+
+    let scope = &StatsScope::resolve(date_range.as_deref(), start_ts, end_ts, account_id.clone())?;
+    // SAFETY: scope_filter is built from hardcoded fragments, values are bound as parameters
+    let (scope_filter, scope_params) = scope.sql_filter();
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT notes, status, total_pnl, pnl_in_r FROM trades
+         WHERE deleted_at IS NULL AND status IN ('WIN', 'LOSS', 'BE') {}",
+        scope_filter
+    ))?;
+
+    let rows = stmt.query_map(rusqlite::params_from_iter(scope_params.iter()), |row| {
+        Ok((
+            row.get::<_, Option<String>>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<f64>>(2)?,
+            row.get::<_, Option<f64>>(3)?,
+        ))
+    })?;
+
+    let mut by_strategy: std::collections::HashMap<String, StrategyStats> = std::collections::HashMap::new();
+    let mut r_sums: std::collections::HashMap<String, f64> = std::collections::HashMap::new();
+
+    for row in rows {
+        let (notes, status, pnl, pnl_in_r) = row?;
+        let strategy = parse_strategy_prefix(notes.as_deref().unwrap_or(""))
+            .unwrap_or_else(|| "Unassigned".to_string());
+
+        let entry = by_strategy.entry(strategy.clone()).or_insert_with(|| StrategyStats {
+            strategy: strategy.clone(),
+            trade_count: 0,
+            wins: 0,
+            losses: 0,
+            win_rate: 0.0,
+            total_pnl: 0.0,
+            avg_pnl: 0.0,
+            expectancy_r: 0.0,
+            r_trade_count: 0,
+        });
+        entry.trade_count += 1;
+        match status.as_str() {
+            "WIN" => entry.wins += 1,
+            "LOSS" => entry.losses += 1,
+            _ => {}
+        }
+        entry.total_pnl += pnl.unwrap_or(0.0);
+        if let Some(r) = pnl_in_r {
+            entry.r_trade_count += 1;
+            *r_sums.entry(strategy).or_insert(0.0) += r;
+        }
+    }
+
+    let mut result: Vec<StrategyStats> = by_strategy
+        .into_values()
+        .map(|mut stats| {
+            let decided = stats.wins + stats.losses;
+            stats.win_rate = if decided > 0 { stats.wins as f64 / decided as f64 * 100.0 } else { 0.0 };
+            stats.avg_pnl = stats.total_pnl / stats.trade_count as f64;
+            if stats.r_trade_count > 0 {
+                stats.expectancy_r = r_sums.get(&stats.strategy).copied().unwrap_or(0.0) / stats.r_trade_count as f64;
+            }
+            stats
+        })
+        .collect();
+
+    result.sort_by(|a, b| b.total_pnl.partial_cmp(&a.total_pnl).unwrap_or(std::cmp::Ordering::Equal));
+
+    Ok(result)
+}
+
+/// "[Breakout] retest of range high" → Some("Breakout")
+fn parse_strategy_prefix(notes: &str) -> Option<String> {
+    let rest = notes.trim_start().strip_prefix('[')?;
+    let (name, _) = rest.split_once(']')?;
+    let name = name.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Per-day PnL for one month (the data behind the calendar heatmap), plus weekly totals.
+/// Days are bucketed on close time shifted by `utc_offset_minutes`.
+#[tauri::command]
+pub async fn get_pnl_calendar(
+    db: State<'_, Database>,
+    year: i32,
+    month: u32,
+    utc_offset_minutes: Option<i32>,
+    account_id: Option<String>,
+) -> Result<PnlCalendar, CommandError> {
+    let first_day = chrono::NaiveDate::from_ymd_opt(year, month, 1)
+        .ok_or_else(|| CommandError::Validation(format!("Invalid month: {}-{}", year, month)))?;
+    let next_month = if month == 12 {
+        chrono::NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    } else {
+        chrono::NaiveDate::from_ymd_opt(year, month + 1, 1)
+    }
+    .ok_or_else(|| CommandError::Validation(format!("Invalid month: {}-{}", year, month)))?;
+
+    let offset_seconds = utc_offset_minutes.unwrap_or(0) as i64 * 60;
+    let to_utc = |date: chrono::NaiveDate| -> Result<i64, CommandError> {
+        Ok(date.and_hms_opt(0, 0, 0).ok_or_else(|| CommandError::Validation("Invalid date".to_string()))?.and_utc().timestamp() - offset_seconds)
+    };
+    let (start, end) = (to_utc(first_day)?, to_utc(next_month)?);
+
+    // SAFETY: account_filter is a hardcoded fragment, the account id is bound as a parameter
+    let (account_filter, account_params) = StatsScope { account_id, ..Default::default() }.account_filter();
+    let mut params = vec![Value::Integer(offset_seconds), Value::Integer(start), Value::Integer(end)];
+    params.extend(account_params);
+
+    let conn = db.pool.get()?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT strftime('%Y-%m-%d', close_date + ?1, 'unixepoch') AS day,
+                COALESCE(SUM(total_pnl), 0.0),
+                COUNT(*),
+                SUM(CASE WHEN status = 'WIN' THEN 1 ELSE 0 END),
+                SUM(CASE WHEN status = 'LOSS' THEN 1 ELSE 0 END)
+         FROM trades
+         WHERE deleted_at IS NULL
+         AND status IN ('WIN', 'LOSS', 'BE')
+         AND close_date >= ?2 AND close_date < ?3
+         {}
+         GROUP BY day
+         ORDER BY day",
+        account_filter
+    ))?;
+
+    let days = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
+        let wins: i32 = row.get(3)?;
+        let losses: i32 = row.get(4)?;
+        Ok(CalendarDay {
+            date: row.get(0)?,
+            total_pnl: row.get(1)?,
+            trade_count: row.get(2)?,
+            wins,
+            losses,
+            win_rate: if wins + losses > 0 { wins as f64 / (wins + losses) as f64 * 100.0 } else { 0.0 },
+        })
+    })?
+    .collect::<Result<Vec<CalendarDay>, _>>()?;
+
+    // Weekly totals (Monday-based), in calendar order
+    let mut weeks: Vec<CalendarWeek> = Vec::new();
+    for day in &days {
+        let Ok(date) = chrono::NaiveDate::parse_from_str(&day.date, "%Y-%m-%d") else {
+            continue;
+        };
+        let monday = date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64);
+        let week_start = monday.format("%Y-%m-%d").to_string();
+        match weeks.last_mut() {
+            Some(week) if week.week_start == week_start => {
+                week.total_pnl += day.total_pnl;
+                week.trade_count += day.trade_count;
+            }
+            _ => weeks.push(CalendarWeek {
+                week_start,
+                total_pnl: day.total_pnl,
+                trade_count: day.trade_count,
+            }),
+        }
+    }
+
+    Ok(PnlCalendar {
+        year,
+        month,
+        total_pnl: days.iter().map(|d| d.total_pnl).sum(),
+        trade_count: days.iter().map(|d| d.trade_count).sum(),
+        days,
+        weeks,
+    })
+}
+
+/// Kelly fraction from win rate and payoff ratio (W - (1 - W) / R), with half Kelly as
+/// the suggested risk per trade since full Kelly is too volatile for real use
+#[tauri::command]
+pub async fn get_kelly_suggestion(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    account_id: Option<String>,
+) -> Result<KellySuggestion, CommandError> {
+    let conn = db.pool.get()?;
+
+    let scope = &StatsScope::resolve(date_range.as_deref(), start_ts, end_ts, account_id.clone())?;
+    let (_, current_r_percent) = capital_settings(&conn, scope.account_id.as_deref())?;
+    let r_values = closed_r_outcomes(&conn, scope)?;
+
+    Ok(compute_kelly(&r_values, current_r_percent))
+}
+
+pub(crate) fn compute_kelly(r_values: &[f64], current_r_percent: f64) -> KellySuggestion {
+    let wins: Vec<f64> = r_values.iter().copied().filter(|r| *r > 0.0).collect();
+    let losses: Vec<f64> = r_values.iter().copied().filter(|r| *r < 0.0).map(f64::abs).collect();
+    let average = |values: &[f64]| if values.is_empty() { 0.0 } else { values.iter().sum::<f64>() / values.len() as f64 };
+
+    let win_rate = if r_values.is_empty() { 0.0 } else { wins.len() as f64 / r_values.len() as f64 };
+    let avg_win_r = average(&wins);
+    let avg_loss_r = average(&losses);
+    let payoff_ratio = if avg_loss_r > 0.0 { avg_win_r / avg_loss_r } else { 0.0 };
+
+    // Without both wins and losses the payoff ratio is undefined, so no suggestion
+    let kelly_fraction = if payoff_ratio > 0.0 { win_rate - (1.0 - win_rate) / payoff_ratio } else { 0.0 };
+    let half_kelly_fraction = kelly_fraction / 2.0;
+
+    KellySuggestion {
+        trade_count: r_values.len() as i32,
+        win_rate: win_rate * 100.0,
+        avg_win_r,
+        avg_loss_r,
+        payoff_ratio,
+        kelly_fraction,
+        half_kelly_fraction,
+        suggested_r_percent: half_kelly_fraction.max(0.0),
+        current_r_percent,
+    }
+}
+
+/// Group closed trades by an SQL expression and aggregate count / win rate / PnL.
+/// SAFETY: `bucket_expr` must be a hardcoded SQL expression, never user input; its
+/// optional `?` placeholder is bound to `expr_param`.
+fn query_bucket_stats(
+    conn: &rusqlite::Connection,
+    bucket_expr: &str,
+    expr_param: Option<i64>,
+    scope: &StatsScope,
+) -> Result<Vec<BucketStats>, CommandError> {
+    query_bucket_stats_from(conn, "trades", bucket_expr, expr_param, scope)
+}
+
+/// `query_bucket_stats` over a hardcoded FROM clause joining other tables onto trades.
+/// A trade counts once per joined row, e.g. once per tag.
+fn query_bucket_stats_from(
+    conn: &rusqlite::Connection,
+    from_clause: &str,
+    bucket_expr: &str,
+    expr_param: Option<i64>,
+    scope: &StatsScope,
+) -> Result<Vec<BucketStats>, CommandError> {
+    let (scope_filter, mut params) = scope.sql_filter();
+    if let Some(param) = expr_param {
+        params.insert(0, Value::Integer(param));
+    }
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} AS bucket,
+                COUNT(*),
+                SUM(CASE WHEN status = 'WIN' THEN 1 ELSE 0 END),
+                SUM(CASE WHEN status = 'LOSS' THEN 1 ELSE 0 END),
+                COALESCE(SUM(total_pnl), 0.0)
+         FROM {}
+         WHERE deleted_at IS NULL
+         AND status IN ('WIN', 'LOSS', 'BE')
+         {}
+         GROUP BY bucket
+         ORDER BY bucket",
+        bucket_expr, from_clause, scope_filter
+    ))?;
+
+    let rows = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
+        // Buckets may be integers (hour, weekday) or text (exchange, source)
+        let bucket = match row.get_ref(0)? {
+            rusqlite::types::ValueRef::Integer(i) => i.to_string(),
+            rusqlite::types::ValueRef::Text(t) => String::from_utf8_lossy(t).to_string(),
+            _ => String::new(),
+        };
+        let trade_count: i32 = row.get(1)?;
+        let wins: i32 = row.get(2)?;
+        let losses: i32 = row.get(3)?;
+        let total_pnl: f64 = row.get(4)?;
+        Ok(BucketStats {
+            bucket,
+            trade_count,
+            wins,
+            losses,
+            win_rate: if wins + losses > 0 { wins as f64 / (wins + losses) as f64 * 100.0 } else { 0.0 },
+            total_pnl,
+            avg_pnl: if trade_count > 0 { total_pnl / trade_count as f64 } else { 0.0 },
+        })
+    })?;
+
+    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+}
+
+/// Ensure buckets "0".."count-1" all exist (empty ones zeroed), in numeric order
+fn fill_numeric_buckets(buckets: Vec<BucketStats>, count: usize) -> Vec<BucketStats> {
+    (0..count)
+        .map(|i| {
+            let key = i.to_string();
+            buckets.iter().find(|b| b.bucket == key).cloned().unwrap_or(BucketStats {
+                bucket: key,
+                trade_count: 0,
+                wins: 0,
+                losses: 0,
+                win_rate: 0.0,
+                total_pnl: 0.0,
+                avg_pnl: 0.0,
+            })
+        })
+        .collect()
+}
+
+/// (expectancy, sample standard deviation, SQN) of a set of R-multiples
+fn r_multiple_stats(r_values: &[f64]) -> (f64, f64, f64) {
+    if r_values.is_empty() {
+        return (0.0, 0.0, 0.0);
+    }
+
+    let n = r_values.len() as f64;
+    let expectancy = r_values.iter().sum::<f64>() / n;
+    if r_values.len() < 2 {
+        return (expectancy, 0.0, 0.0);
+    }
+
+    let std_dev = (r_values.iter().map(|r| (r - expectancy).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+    // SQN caps the sample at 100 trades so large samples don't inflate the score
+    let sqn = if std_dev > 0.0 {
+        n.min(100.0).sqrt() * expectancy / std_dev
+    } else {
+        0.0
+    };
+
+    (expectancy, std_dev, sqn)
+}
+
+/// Which trades a stats query covers: a close-date window and optionally one account.
+/// Explicit timestamps take precedence over a preset range.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct StatsScope {
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    /// Start follows the clock (preset range), so results go stale without any trade change
+    pub rolling: bool,
+    /// None pools all accounts (and unassigned trades)
+    pub account_id: Option<String>,
+}
+
+impl StatsScope {
+    /// `start_ts`/`end_ts` are inclusive unix seconds; either may be omitted for an open-ended range
+    pub(crate) fn resolve(
+        date_range: Option<&str>,
+        start_ts: Option<i64>,
+        end_ts: Option<i64>,
+        account_id: Option<String>,
+    ) -> Result<Self, CommandError> {
+        if let (Some(start), Some(end)) = (start_ts, end_ts)
+            && start > end
+        {
+            return Err(CommandError::Validation(format!("start_ts ({}) must not be after end_ts ({})", start, end)));
+        }
+        if start_ts.is_some() || end_ts.is_some() {
+            return Ok(StatsScope { start: start_ts, end: end_ts, rolling: false, account_id });
+        }
+        let start = date_range_threshold(date_range);
+        Ok(StatsScope { start, end: None, rolling: start.is_some(), account_id })
+    }
+
+    /// SQL clause ("AND close_date >= ? AND close_date <= ? AND account_id = ?", each part
+    /// optional) and its params
+    pub(crate) fn sql_filter(&self) -> (String, Vec<Value>) {
+        let (account_filter, account_params) = self.account_filter();
+        let mut filter = String::new();
+        let mut params = Vec::new();
+        if let Some(start) = self.start {
+            filter.push_str("AND close_date >= ? ");
+            params.push(Value::Integer(start));
+        }
+        if let Some(end) = self.end {
+            filter.push_str("AND close_date <= ? ");
+            params.push(Value::Integer(end));
+        }
+        filter.push_str(&account_filter);
+        params.extend(account_params);
+        (filter, params)
+    }
+
+    /// Only the account part of `sql_filter`, for queries that ignore the date window
+    pub(crate) fn account_filter(&self) -> (String, Vec<Value>) {
+        match &self.account_id {
+            Some(id) => ("AND account_id = ? ".to_string(), vec![Value::Text(id.clone())]),
+            None => (String::new(), Vec::new()),
+        }
+    }
+}
+
+/// Serve a stats result from stats_cache while no trade or settings write happened since it
+/// was computed (the triggers from migration 010 bump stats_data_version); otherwise compute
+/// and store it. Entries for rolling windows also expire after ROLLING_CACHE_TTL_SECS.
+/// Payloads with non-finite floats serialize to null and fail to load, so they are recomputed.
+fn cached_stats<T, F>(
+    conn: &rusqlite::Connection,
+    cache_key: &str,
+    scope: &StatsScope,
+    compute: F,
+) -> Result<T, CommandError>
+where
+    T: Serialize + serde::de::DeserializeOwned,
+    F: FnOnce() -> Result<T, CommandError>,
+{
+    let now = chrono::Utc::now().timestamp();
+    let version: i64 = conn
+        .query_row("SELECT version FROM stats_data_version WHERE id = 1", [], |row| row.get(0))?;
+
+    let cached: Option<(i64, String, i64)> = conn
+        .query_row(
+            "SELECT data_version, payload, computed_at FROM stats_cache WHERE cache_key = ?",
+            [cache_key],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .ok();
+
+    if let Some((cached_version, payload, computed_at)) = cached
+        && cached_version == version
+        && (!scope.rolling || now - computed_at < ROLLING_CACHE_TTL_SECS)
+        && let Ok(value) = serde_json::from_str(&payload)
+    {
+        return Ok(value);
+    }
+
+    let value = compute()?;
+    let payload = serde_json::to_string(&value)?;
+    store_cached_stats(conn, cache_key, version, &payload, now);
+
+    Ok(value)
+}
+
+/// Connections that only write stats_cache, one per database file. Unlike pooled connections
+/// they never wait for a lock, so the write is skipped while another connection is writing.
+static CACHE_WRITERS: LazyLock<Mutex<std::collections::HashMap<String, rusqlite::Connection>>> =
+    LazyLock::new(|| Mutex::new(std::collections::HashMap::new()));
+
+/// Best-effort cache write that never holds up the read it follows: it gives up at once
+/// when another connection (or another cache write) is writing, and a failed write only
+/// costs the next call a recompute
+fn store_cached_stats(conn: &rusqlite::Connection, cache_key: &str, version: i64, payload: &str, now: i64) {
+    let write = |conn: &rusqlite::Connection| -> rusqlite::Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM stats_cache WHERE data_version <> ?", [version])?;
+        tx.execute(
+            "INSERT OR REPLACE INTO stats_cache (cache_key, data_version, payload, computed_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![cache_key, version, payload, now],
+        )?;
+        tx.commit()
+    };
+
+    let stored = match conn.path().filter(|path| !path.is_empty()) {
+        // An in-memory database has no other connection to wait for
+        None => write(conn),
+        Some(path) => {
+            let mut writers = match CACHE_WRITERS.try_lock() {
+                Ok(writers) => writers,
+                Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+                Err(TryLockError::WouldBlock) => return,
+            };
+            let writer = match writers.entry(path.to_string()) {
+                std::collections::hash_map::Entry::Occupied(entry) => Ok(entry.into_mut()),
+                std::collections::hash_map::Entry::Vacant(entry) => Database::open_connection(path)
+                    .and_then(|writer| writer.busy_timeout(std::time::Duration::ZERO).map(|_| writer))
+                    .map(|writer| entry.insert(writer)),
+            };
+            writer.and_then(|writer| write(writer))
+        }
+    };
+
+    if let Err(e) = stored {
+        eprintln!("Skipped storing stats cache entry {}: {}", cache_key, e);
+    }
+}
+
+/// Start timestamp for a dashboard date range ("today", "week", "month", "3months",
+/// "6months", "year"); None means all time
+fn date_range_threshold(date_range: Option<&str>) -> Option<i64> {
+    let now = chrono::Utc::now();
+    match date_range {
+        Some("today") => Some(now.date_naive().and_hms_opt(0, 0, 0)?.and_utc().timestamp()),
+        Some("week") => Some(now.timestamp() - (7 * 24 * 60 * 60)),
+        Some("month") => Some(now.timestamp() - (30 * 24 * 60 * 60)),
+        Some("3months") => Some(now.timestamp() - (90 * 24 * 60 * 60)),
+        Some("6months") => Some(now.timestamp() - (180 * 24 * 60 * 60)),
+        Some("year") => Some(now.timestamp() - (365 * 24 * 60 * 60)),
         _ => None,
+    }
+}
+
+/// Account equity at the start of a range: initial capital plus everything closed and
+/// deposited (net of withdrawals) before it. Scoped to an account, its own capital is used.
+fn starting_equity(conn: &rusqlite::Connection, scope: &StatsScope) -> Result<f64, CommandError> {
+    let (initial_capital, _) = capital_settings(conn, scope.account_id.as_deref())?;
+
+    let pnl_before_range: f64 = match scope.start {
+        Some(threshold) => {
+            let (account_filter, mut params) = scope.account_filter();
+            params.insert(0, Value::Integer(threshold));
+            conn.query_row(
+                &format!(
+                    "SELECT COALESCE(SUM(total_pnl), 0.0) FROM trades
+                     WHERE deleted_at IS NULL AND status IN ('WIN', 'LOSS', 'BE') AND close_date < ? {}",
+                    account_filter
+                ),
+                rusqlite::params_from_iter(params.iter()),
+                |row| row.get(0),
+            )
+            .unwrap_or(0.0)
+        }
+        None => 0.0,
+    };
+    let flows_before_range = match scope.start {
+        Some(start) => net_capital_flow(conn, Some(start), scope.account_id.as_deref())?,
+        None => 0.0,
     };
 
+    Ok(initial_capital + pnl_before_range + flows_before_range)
+}
+
+/// Closed-trade PnL grouped by UTC close day: (YYYY-MM-DD, pnl, trade_count), oldest first
+fn daily_pnl_series(
+    conn: &rusqlite::Connection,
+    scope: &StatsScope,
+) -> Result<Vec<(String, f64, i32)>, CommandError> {
     // Build filter clause and params
-    // SAFETY: date_filter is always a compile-time constant string ("AND close_date >= ?" or ""),
-    // never user-provided input. This pattern is safe from SQL injection as long as date_filter
-    // remains a hardcoded string. All dynamic values are passed through parameterized queries.
-    let (date_filter, date_params): (&str, Vec<i64>) = match date_threshold {
-        Some(threshold) => ("AND close_date >= ?", vec![threshold]),
-        None => ("", vec![]),
-    };
+    // SAFETY: scope_filter is assembled from compile-time constant fragments only, never
+    // user-provided input. All dynamic values are passed through parameterized queries.
+    let (scope_filter, scope_params) = scope.sql_filter();
 
     // Query all closed trades with close_date
     let mut stmt = conn.prepare(&format!(
         "SELECT close_date, total_pnl
          FROM trades
-         WHERE close_date IS NOT NULL
+         WHERE deleted_at IS NULL
+         AND close_date IS NOT NULL
          AND total_pnl IS NOT NULL
          AND status IN ('WIN', 'LOSS', 'BE')
          {}
          ORDER BY close_date ASC",
-        date_filter
-    )).map_err(|e| e.to_string())?;
+        scope_filter
+    ))?;
 
-    let trades = stmt.query_map(rusqlite::params_from_iter(date_params.iter()), |row| {
+    let trades = stmt.query_map(rusqlite::params_from_iter(scope_params.iter()), |row| {
         Ok((
             row.get::<_, i64>(0)?,
             row.get::<_, f64>(1)?,
         ))
-    }).map_err(|e| e.to_string())?;
+    })?;
 
-    // Group by date and calculate cumulative P&L
+    // Group by date
     let mut daily_map: std::collections::HashMap<String, (f64, i32)> = std::collections::HashMap::new();
 
     for trade in trades {
-        let (close_timestamp, pnl) = trade.map_err(|e| e.to_string())?;
+        let (close_timestamp, pnl) = trade?;
 
         // Convert timestamp to date string (YYYY-MM-DD)
         let date = chrono::DateTime::from_timestamp(close_timestamp, 0)
-            .ok_or(format!("Invalid timestamp: {} for trade with close_date {}", close_timestamp, close_timestamp))?
+            .ok_or_else(|| CommandError::Internal(format!("Invalid timestamp: {}", close_timestamp)))?
             .format("%Y-%m-%d")
             .to_string();
 
@@ -249,22 +1589,462 @@ pub async fn get_equity_curve(
         entry.1 += 1;
     }
 
-    // Sort by date and calculate cumulative
+    // Sort by date
     let mut sorted_dates: Vec<_> = daily_map.into_iter().collect();
     sorted_dates.sort_by(|a, b| a.0.cmp(&b.0));
 
-    let mut cumulative_pnl = 0.0;
-    let mut result: Vec<EquityCurvePoint> = Vec::new();
+    Ok(sorted_dates
+        .into_iter()
+        .map(|(date, (pnl, count))| (date, pnl, count))
+        .collect())
+}
 
-    for (date, (daily_pnl, trade_count)) in sorted_dates {
-        cumulative_pnl += daily_pnl;
-        result.push(EquityCurvePoint {
-            date,
-            cumulative_pnl,
-            daily_pnl,
-            trade_count,
+/// Closed-trade R outcomes. Imported trades have no pnl_in_r, so fall back to PnL / 1R.
+pub(crate) fn closed_r_outcomes(conn: &rusqlite::Connection, scope: &StatsScope) -> Result<Vec<f64>, CommandError> {
+    // SAFETY: scope_filter is built from hardcoded fragments, values are bound as parameters
+    let (scope_filter, scope_params) = scope.sql_filter();
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT COALESCE(pnl_in_r, total_pnl / NULLIF(one_r, 0)) AS r
+             FROM trades
+             WHERE deleted_at IS NULL AND status IN ('WIN', 'LOSS', 'BE') {}",
+            scope_filter
+        ))?;
+
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(scope_params.iter()), |row| row.get::<_, Option<f64>>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(rows.into_iter().flatten().filter(|r| r.is_finite()).collect())
+}
+
+/// Closed-trade R grouped by UTC close day. Trades without pnl_in_r fall back to PnL / 1R.
+fn daily_r_series(
+    conn: &rusqlite::Connection,
+    scope: &StatsScope,
+) -> Result<std::collections::HashMap<String, f64>, CommandError> {
+    // SAFETY: scope_filter is built from hardcoded fragments, values are bound as parameters
+    let (scope_filter, scope_params) = scope.sql_filter();
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT close_date, COALESCE(pnl_in_r, total_pnl / NULLIF(one_r, 0))
+         FROM trades
+         WHERE deleted_at IS NULL
+         AND close_date IS NOT NULL
+         AND status IN ('WIN', 'LOSS', 'BE')
+         {}",
+        scope_filter
+    ))?;
+
+    let trades = stmt.query_map(rusqlite::params_from_iter(scope_params.iter()), |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, Option<f64>>(1)?))
+    })?;
+
+    let mut daily_map: std::collections::HashMap<String, f64> = std::collections::HashMap::new();
+    for trade in trades {
+        let (close_timestamp, r) = trade?;
+        let Some(r) = r.filter(|r| r.is_finite()) else { continue };
+
+        let date = chrono::DateTime::from_timestamp(close_timestamp, 0)
+            .ok_or_else(|| CommandError::Internal(format!("Invalid timestamp: {}", close_timestamp)))?
+            .format("%Y-%m-%d")
+            .to_string();
+        *daily_map.entry(date).or_insert(0.0) += r;
+    }
+
+    Ok(daily_map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::{insert_trade, test_conn, trade};
+    use crate::models::Trade;
+
+    fn day(date: &str, pnl: f64) -> (String, f64, i32) {
+        (date.to_string(), pnl, 1)
+    }
+
+    #[test]
+    fn test_drawdown_peak_to_recovery() {
+        let daily = vec![
+            day("2026-01-01", 100.0),  // 1100 peak
+            day("2026-01-03", -220.0), // 880, 20% down
+            day("2026-01-05", 120.0),  // 1000
+            day("2026-01-08", 150.0),  // 1150 recovered after 7 days
+            day("2026-01-09", -50.0),  // 1100, still open at the end
+        ];
+
+        let dd = compute_drawdown(1000.0, &daily, &[]);
+        assert!((dd.max_drawdown - 220.0).abs() < 1e-9);
+        assert!((dd.max_drawdown_pct - 20.0).abs() < 1e-9);
+        assert_eq!(dd.max_drawdown_duration_days, 7);
+        assert!((dd.current_drawdown - 50.0).abs() < 1e-9);
+        assert_eq!(dd.curve.len(), 5);
+        assert_eq!(dd.curve[3].peak, 1150.0);
+    }
+
+    #[test]
+    fn test_drawdown_ignores_capital_flows() {
+        let daily = vec![
+            day("2026-01-01", 100.0),  // 1100 peak
+            day("2026-01-03", -100.0), // 1000 after a 500 deposit that day: 1500 vs 1600 peak
+            day("2026-01-05", 50.0),   // 1050 after a 1000 withdrawal: 550 vs 600 peak
+        ];
+        let flows = vec![("2026-01-03".to_string(), 500.0), ("2026-01-05".to_string(), -1000.0)];
+
+        let dd = compute_drawdown(1000.0, &daily, &flows);
+        // Only the trading loss counts: the deposit doesn't hide it, the withdrawal isn't one
+        assert!((dd.max_drawdown - 100.0).abs() < 1e-9);
+        assert!((dd.current_drawdown - 50.0).abs() < 1e-9);
+        assert_eq!(dd.curve[1].equity, 1500.0);
+        assert_eq!(dd.curve[2].peak, 600.0);
+    }
+
+    #[test]
+    fn test_drawdown_empty_series() {
+        let dd = compute_drawdown(1000.0, &[], &[]);
+        assert_eq!(dd.max_drawdown, 0.0);
+        assert_eq!(dd.max_drawdown_duration_days, 0);
+        assert!(dd.curve.is_empty());
+    }
+
+    #[test]
+    fn test_advanced_stats_ratios() {
+        let daily = vec![
+            day("2026-01-01", 100.0),
+            day("2026-01-02", -50.0),
+            day("2026-01-04", 80.0),
+        ];
+
+        let stats = compute_advanced_stats(10_000.0, &daily, &[], 0.0);
+        assert_eq!(stats.days, 4, "gap day counts as a zero return");
+        assert!(stats.sharpe_ratio > 0.0);
+        assert!(stats.sortino_ratio > stats.sharpe_ratio, "only one losing day");
+        assert!(stats.calmar_ratio > 0.0);
+
+        let flat = compute_advanced_stats(10_000.0, &[day("2026-01-01", 10.0)], &[], 0.0);
+        assert_eq!(flat.sharpe_ratio, 0.0);
+    }
+
+    #[test]
+    fn test_r_multiple_stats() {
+        let (expectancy, std_dev, sqn) = r_multiple_stats(&[2.0, -1.0, 2.0, -1.0]);
+        assert!((expectancy - 0.5).abs() < 1e-9);
+        assert!((std_dev - 3.0_f64.sqrt()).abs() < 1e-9);
+        assert!((sqn - 2.0 * 0.5 / 3.0_f64.sqrt()).abs() < 1e-9);
+
+        assert_eq!(r_multiple_stats(&[]), (0.0, 0.0, 0.0));
+        assert_eq!(r_multiple_stats(&[1.5]), (1.5, 0.0, 0.0));
+    }
+
+    fn insert_closed_trade(conn: &rusqlite::Connection, id: &str, status: &str, pnl: f64, trade_date: i64) {
+        insert_trade(conn, &Trade {
+            analysis_date: trade_date,
+            trade_date,
+            close_date: Some(trade_date + 3600),
+            status: status.to_string(),
+            total_pnl: Some(pnl),
+            ..trade(id)
         });
     }
 
-    Ok(result)
+    #[test]
+    fn test_campaign_stats_count_linked_trades_once() {
+        let conn = test_conn();
+        insert_closed_trade(&conn, "T1", "LOSS", -100.0, 1_767_603_600);
+        insert_closed_trade(&conn, "T2", "WIN", 250.0, 1_767_690_000);
+        insert_closed_trade(&conn, "T3", "LOSS", -20.0, 1_767_776_400);
+        conn.execute(
+            "INSERT INTO trade_links (trade_id, related_trade_id, relation, created_at) VALUES ('T1', 'T2', 'RE_ENTRY', 0)",
+            [],
+        )
+        .unwrap();
+
+        let campaigns = load_campaigns(&conn, (String::new(), Vec::new())).unwrap();
+        let stats = compute_campaign_stats(&campaigns, &StatsScope::default());
+        assert_eq!(stats.campaign_count, 2);
+        assert_eq!(stats.linked_campaign_count, 1);
+        assert_eq!((stats.wins, stats.losses), (1, 1));
+        assert!((stats.total_pnl - 130.0).abs() < 1e-9);
+
+        // The campaign's last close (T2) decides whether it is in range
+        let scope = StatsScope { start: Some(1_767_690_000), ..Default::default() };
+        let stats = compute_campaign_stats(&campaigns, &scope);
+        assert_eq!(stats.campaign_count, 2);
+        let scope = StatsScope { end: Some(1_767_690_000), ..Default::default() };
+        assert_eq!(compute_campaign_stats(&campaigns, &scope).campaign_count, 0);
+    }
+
+    #[test]
+    fn test_bucket_stats_by_hour() {
+        let conn = test_conn();
+        // 2026-01-05 is a Monday; 09:00 and 09:30 UTC, then 15:00 UTC
+        insert_closed_trade(&conn, "T1", "WIN", 50.0, 1_767_603_600);
+        insert_closed_trade(&conn, "T2", "LOSS", -20.0, 1_767_605_400);
+        insert_closed_trade(&conn, "T3", "WIN", 10.0, 1_767_625_200);
+
+        let by_hour = query_bucket_stats(
+            &conn,
+            "CAST(strftime('%H', trade_date + ?, 'unixepoch') AS INTEGER)",
+            Some(0),
+            &StatsScope::default(),
+        )
+        .unwrap();
+        let by_hour = fill_numeric_buckets(by_hour, 24);
+
+        assert_eq!(by_hour.len(), 24);
+        assert_eq!(by_hour[9].trade_count, 2);
+        assert_eq!(by_hour[9].win_rate, 50.0);
+        assert_eq!(by_hour[9].total_pnl, 30.0);
+        assert_eq!(by_hour[15].wins, 1);
+        assert_eq!(by_hour[0].trade_count, 0);
+    }
+
+    #[test]
+    fn test_funding_stats() {
+        let conn = test_conn();
+        insert_closed_trade(&conn, "T1", "WIN", 50.0, 1_767_603_600);
+        for (bill, amount, trade_id) in [("b1", -3.0, Some("T1")), ("b2", 1.0, Some("T1")), ("b3", -0.5, None)] {
+            conn.execute(
+                "INSERT INTO funding_payments (id, exchange, exchange_bill_id, symbol, amount, currency,
+                    funding_time, trade_id, created_at)
+                 VALUES (?1, 'bitget', ?1, 'BTCUSDT', ?2, 'USDT', 1767604000, ?3, 0)",
+                rusqlite::params![bill, amount, trade_id],
+            )
+            .unwrap();
+        }
+
+        let stats = compute_funding_stats(&conn, &StatsScope::default()).unwrap();
+        assert_eq!(stats.payments, 3);
+        assert_eq!(stats.unlinked_payments, 1);
+        assert_eq!(stats.total_paid, 3.5);
+        assert_eq!(stats.total_received, 1.0);
+        assert_eq!(stats.net_funding, -2.5);
+        assert_eq!(stats.gross_pnl, 50.0);
+        // Only funding linked to the trade counts toward its net PnL
+        assert_eq!(stats.net_pnl, 48.0);
+        assert_eq!(stats.by_symbol.len(), 1);
+    }
+
+    #[test]
+    fn test_grade_buckets() {
+        let conn = test_conn();
+        insert_closed_trade(&conn, "T1", "WIN", 50.0, 1_767_603_600);
+        insert_closed_trade(&conn, "T2", "LOSS", -20.0, 1_767_605_400);
+        insert_closed_trade(&conn, "T3", "WIN", 10.0, 1_767_625_200);
+        conn.execute("UPDATE trades SET grade = 'A' WHERE id IN ('T1', 'T2')", []).unwrap();
+        assert!(conn.execute("UPDATE trades SET grade = 'E' WHERE id = 'T3'", []).is_err());
+
+        let stats = query_bucket_stats(&conn, "COALESCE(grade, 'Ungraded')", None, &StatsScope::default()).unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!((stats[0].bucket.as_str(), stats[0].trade_count, stats[0].total_pnl), ("A", 2, 30.0));
+        assert_eq!((stats[1].bucket.as_str(), stats[1].trade_count), ("Ungraded", 1));
+    }
+
+    #[test]
+    fn test_emotion_stats() {
+        let conn = test_conn();
+        insert_closed_trade(&conn, "T1", "WIN", 200.0, 1_767_603_600);
+        insert_closed_trade(&conn, "T2", "LOSS", -100.0, 1_767_605_400);
+        insert_closed_trade(&conn, "T3", "LOSS", -50.0, 1_767_625_200);
+        conn.execute("UPDATE trades SET pre_emotion = 'CALM' WHERE id = 'T1'", []).unwrap();
+        conn.execute("UPDATE trades SET pre_emotion = 'FOMO', post_emotion = 'REGRETFUL' WHERE id IN ('T2', 'T3')", [])
+            .unwrap();
+
+        let pre = query_emotion_stats(&conn, "pre_emotion", &StatsScope::default()).unwrap();
+        assert_eq!(pre.len(), 2);
+        assert_eq!((pre[0].emotion.as_str(), pre[0].avg_r), ("CALM", 2.0));
+        assert_eq!((pre[1].emotion.as_str(), pre[1].trade_count, pre[1].win_rate), ("FOMO", 2, 0.0));
+        assert!((pre[1].avg_r + 0.75).abs() < 1e-9);
+
+        let post = query_emotion_stats(&conn, "post_emotion", &StatsScope::default()).unwrap();
+        assert_eq!(post[0].emotion, "Unrecorded");
+        assert_eq!(post[1].emotion, "REGRETFUL");
+    }
+
+    #[test]
+    fn test_parse_strategy_prefix() {
+        assert_eq!(parse_strategy_prefix("[Breakout] retest"), Some("Breakout".to_string()));
+        assert_eq!(parse_strategy_prefix("  [ Range Fade ]"), Some("Range Fade".to_string()));
+        assert_eq!(parse_strategy_prefix("Imported from BitGet"), None);
+        assert_eq!(parse_strategy_prefix("[] empty"), None);
+        assert_eq!(parse_strategy_prefix("[unterminated"), None);
+    }
+
+    #[test]
+    fn test_equity_curve_modes() {
+        let conn = test_conn();
+        // one_r is 100 and pnl_in_r is unset, so R falls back to PnL / 100
+        insert_closed_trade(&conn, "T1", "WIN", 50.0, 1_767_603_600);
+        insert_closed_trade(&conn, "T2", "LOSS", -20.0, 1_767_605_400);
+        insert_closed_trade(&conn, "T3", "WIN", 200.0, 1_767_690_000);
+
+        let daily = daily_pnl_series(&conn, &StatsScope::default()).unwrap();
+        let daily_r = daily_r_series(&conn, &StatsScope::default()).unwrap();
+
+        let r_curve = build_equity_curve(EquityCurveMode::R, &daily, &daily_r, 10_000.0, &[]);
+        assert_eq!(r_curve.len(), 2);
+        assert!((r_curve[0].daily_value - 0.3).abs() < 1e-9);
+        assert!((r_curve[1].cumulative_value - 2.3).abs() < 1e-9);
+        assert_eq!(r_curve[1].cumulative_pnl, 230.0);
+
+        // Time-weighted: +0.3% on 10 000, then +200 on 10 030
+        let pct_curve = build_equity_curve(EquityCurveMode::Percent, &daily, &daily_r, 10_000.0, &[]);
+        let expected = (1.003 * (1.0 + 200.0 / 10_030.0) - 1.0) * 100.0;
+        assert!((pct_curve[1].cumulative_value - expected).abs() < 1e-9);
+        assert_eq!(pct_curve[1].equity, 10_230.0);
+
+        // A deposit before the second day raises the base without counting as return
+        let flows = vec![(daily[1].0.clone(), 10_000.0)];
+        let with_deposit = build_equity_curve(EquityCurveMode::Percent, &daily, &daily_r, 10_000.0, &flows);
+        assert!((with_deposit[1].daily_value - 200.0 / 20_030.0 * 100.0).abs() < 1e-9);
+        assert_eq!(with_deposit[1].equity, 20_230.0);
+
+        assert!(EquityCurveMode::parse(Some("pips")).is_err());
+    }
+
+    #[test]
+    fn test_custom_date_window() {
+        let conn = test_conn();
+        insert_closed_trade(&conn, "T1", "WIN", 50.0, 1_767_603_600);
+        insert_closed_trade(&conn, "T2", "LOSS", -20.0, 1_767_690_000);
+        insert_closed_trade(&conn, "T3", "WIN", 200.0, 1_767_776_400);
+
+        // Close dates are trade_date + 1h; keep only the middle trade
+        let window = &StatsScope::resolve(Some("year"), Some(1_767_690_000), Some(1_767_700_000), None).unwrap();
+        assert_eq!(window.start, Some(1_767_690_000));
+        let daily = daily_pnl_series(&conn, window).unwrap();
+        assert_eq!(daily.len(), 1);
+        assert_eq!(daily[0].1, -20.0);
+
+        // Equity at the window start includes the trade closed before it
+        assert_eq!(starting_equity(&conn, window).unwrap(), 10_050.0);
+
+        assert!(StatsScope::resolve(None, Some(10), Some(5), None).is_err());
+        assert_eq!(StatsScope::resolve(None, None, None, None).unwrap(), StatsScope::default());
+    }
+
+    #[test]
+    fn test_kelly_from_r_outcomes() {
+        // 50% winners at 2R vs 1R losers: K = 0.5 - 0.5 / 2 = 0.25
+        let kelly = compute_kelly(&[2.0, -1.0, 2.0, -1.0], 0.02);
+        assert_eq!(kelly.win_rate, 50.0);
+        assert_eq!(kelly.payoff_ratio, 2.0);
+        assert!((kelly.kelly_fraction - 0.25).abs() < 1e-9);
+        assert!((kelly.suggested_r_percent - 0.125).abs() < 1e-9);
+
+        // Negative edge never suggests risking anything
+        let kelly = compute_kelly(&[1.0, -1.0, -1.0], 0.02);
+        assert!(kelly.kelly_fraction < 0.0);
+        assert_eq!(kelly.suggested_r_percent, 0.0);
+
+        assert_eq!(compute_kelly(&[], 0.02).kelly_fraction, 0.0);
+    }
+
+    #[test]
+    fn test_stats_cache_invalidated_by_trade_writes() {
+        let conn = test_conn();
+        insert_closed_trade(&conn, "T1", "WIN", 50.0, 1_767_603_600);
+        insert_closed_trade(&conn, "T2", "LOSS", -20.0, 1_767_690_000);
+
+        let window = &StatsScope::default();
+        let first = cached_stats(&conn, "test", window, || compute_dashboard_stats(&conn, window)).unwrap();
+        assert_eq!(first.total_pnl, 30.0);
+
+        // Served from the cache without recomputing
+        let cached: DashboardStats = cached_stats(&conn, "test", window, || Err(CommandError::Internal("recomputed".to_string()))).unwrap();
+        assert_eq!(cached.total_pnl, 30.0);
+
+        // Any write to trades bumps the data version through the triggers
+        conn.execute("UPDATE trades SET total_pnl = 80 WHERE id = 'T1'", []).unwrap();
+        let fresh = cached_stats(&conn, "test", window, || compute_dashboard_stats(&conn, window)).unwrap();
+        assert_eq!(fresh.total_pnl, 60.0);
+    }
+
+    #[test]
+    fn test_stats_read_not_held_up_by_a_writer() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(dir.path().join("stats.db").to_str().unwrap()).unwrap();
+        let (writer, reader) = (db.pool.get().unwrap(), db.pool.get().unwrap());
+
+        // An import holding the write lock
+        writer.execute_batch("BEGIN IMMEDIATE").unwrap();
+        let started = std::time::Instant::now();
+        let window = &StatsScope::default();
+        let stats = cached_stats(&reader, "test", window, || compute_dashboard_stats(&reader, window)).unwrap();
+        assert_eq!(stats.total_trades, 0);
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+        writer.execute_batch("COMMIT").unwrap();
+
+        // Nothing was cached, and the pooled connection still waits for locks as usual
+        let cached: i64 = reader.query_row("SELECT COUNT(*) FROM stats_cache", [], |row| row.get(0)).unwrap();
+        assert_eq!(cached, 0);
+        let busy_timeout: i64 = reader.query_row("PRAGMA busy_timeout", [], |row| row.get(0)).unwrap();
+        assert_eq!(busy_timeout, 5000);
+
+        // Once the writer is done the dedicated connection stores the entry
+        cached_stats(&reader, "test", window, || compute_dashboard_stats(&reader, window)).unwrap();
+        let cached: i64 = reader.query_row("SELECT COUNT(*) FROM stats_cache", [], |row| row.get(0)).unwrap();
+        assert_eq!(cached, 1);
+    }
+
+    #[test]
+    fn test_bucket_stats_by_tag() {
+        let conn = test_conn();
+        insert_closed_trade(&conn, "T1", "WIN", 50.0, 1_767_603_600);
+        insert_closed_trade(&conn, "T2", "LOSS", -20.0, 1_767_605_400);
+        insert_closed_trade(&conn, "T3", "WIN", 10.0, 1_767_625_200);
+        conn.execute_batch(
+            "INSERT INTO tags (id, name, created_at) VALUES ('G1', 'breakout', 0), ('G2', 'news', 0);
+             INSERT INTO trade_tags (trade_id, tag_id, created_at) VALUES ('T1', 'G1', 0), ('T1', 'G2', 0), ('T2', 'G1', 0);",
+        )
+        .unwrap();
+
+        let stats = query_bucket_stats_from(
+            &conn,
+            "trades LEFT JOIN trade_tags ON trade_tags.trade_id = trades.id
+             LEFT JOIN tags ON tags.id = trade_tags.tag_id",
+            "COALESCE(tags.name, 'Untagged')",
+            None,
+            &StatsScope::default(),
+        )
+        .unwrap();
+        let by_name = |name: &str| stats.iter().find(|s| s.bucket == name).unwrap();
+
+        assert_eq!(by_name("breakout").trade_count, 2);
+        assert_eq!(by_name("breakout").total_pnl, 30.0);
+        assert_eq!(by_name("news").total_pnl, 50.0);
+        assert_eq!(by_name("Untagged").trade_count, 1);
+    }
+
+    #[test]
+    fn test_account_scope() {
+        let conn = test_conn();
+        conn.execute(
+            "INSERT INTO accounts (id, name, initial_capital, current_r_percent, created_at, updated_at)
+             VALUES ('A1', 'Paper', 1000, 0.01, 0, 0)",
+            [],
+        )
+        .unwrap();
+        insert_closed_trade(&conn, "T1", "WIN", 50.0, 1_767_603_600);
+        insert_closed_trade(&conn, "T2", "LOSS", -20.0, 1_767_690_000);
+        insert_closed_trade(&conn, "T3", "WIN", 200.0, 1_767_776_400);
+        conn.execute("UPDATE trades SET account_id = 'A1' WHERE id IN ('T1', 'T2')", []).unwrap();
+
+        let scope = &StatsScope::resolve(None, Some(1_767_690_000), None, Some("A1".to_string())).unwrap();
+        let daily = daily_pnl_series(&conn, scope).unwrap();
+        assert_eq!(daily.len(), 1);
+        assert_eq!(daily[0].1, -20.0);
+
+        // The account's own capital, plus only its trades before the window
+        assert_eq!(starting_equity(&conn, scope).unwrap(), 1_050.0);
+
+        let pooled = compute_dashboard_stats(&conn, &StatsScope::default()).unwrap();
+        assert_eq!(pooled.total_trades, 3);
+        let account = compute_dashboard_stats(&conn, &StatsScope { account_id: Some("A1".to_string()), ..Default::default() }).unwrap();
+        assert_eq!(account.total_trades, 2);
+        assert_eq!(account.total_pnl, 30.0);
+    }
 }
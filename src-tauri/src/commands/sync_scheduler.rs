@@ -1,12 +1,69 @@
 use tauri::State;
+use chrono::Utc;
+use crate::db::Database;
+use crate::commands::error::CommandError;
+use crate::models::SchedulerTaskStatus;
 use crate::sync::SyncScheduler;
+use crate::sync::scheduler::reset_sync_failures;
 
 /// Reload sync scheduler tasks
 #[tauri::command]
 pub async fn reload_sync_scheduler(
     scheduler: State<'_, SyncScheduler>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     println!("Reloading sync scheduler from command...");
-    scheduler.reload_tasks().await?;
+    scheduler.reload_tasks().await.map_err(CommandError::Database)
+}
+
+/// Pause auto-sync for one credential without touching its settings or other tasks.
+/// Lasts until resumed or the app restarts.
+#[tauri::command]
+pub async fn pause_auto_sync(
+    scheduler: State<'_, SyncScheduler>,
+    credential_id: String,
+) -> Result<(), CommandError> {
+    scheduler.pause(&credential_id).await;
+    Ok(())
+}
+
+/// Resume a paused credential's auto-sync from its next tick
+#[tauri::command]
+pub async fn resume_auto_sync(
+    scheduler: State<'_, SyncScheduler>,
+    credential_id: String,
+) -> Result<(), CommandError> {
+    scheduler.resume(&credential_id).await;
     Ok(())
 }
+
+/// Turn auto-sync back on after the circuit breaker turned it off, clearing the failure
+/// count and reason, and restart the scheduler tasks
+#[tauri::command]
+pub async fn reenable_auto_sync(
+    db: State<'_, Database>,
+    scheduler: State<'_, SyncScheduler>,
+    credential_id: String,
+) -> Result<(), CommandError> {
+    {
+        let conn = db.pool.get()?;
+        let updated = conn
+            .execute(
+                "UPDATE api_credentials SET auto_sync_enabled = 1, updated_at = ? WHERE id = ?",
+                rusqlite::params![Utc::now().timestamp(), &credential_id],
+            )?;
+        if updated == 0 {
+            return Err(CommandError::NotFound(format!("Credential {} not found", credential_id)));
+        }
+        reset_sync_failures(&conn, &credential_id)?;
+    }
+
+    scheduler.reload_tasks().await.map_err(CommandError::Database)
+}
+
+/// Every auto-sync task: running/paused, last run and result, next scheduled run
+#[tauri::command]
+pub async fn get_scheduler_status(
+    scheduler: State<'_, SyncScheduler>,
+) -> Result<Vec<SchedulerTaskStatus>, CommandError> {
+    Ok(scheduler.status().await)
+}
@@ -0,0 +1,113 @@
+use tauri::State;
+use crate::db::Database;
+use crate::commands::error::CommandError;
+use crate::models::{CreateTagInput, Tag};
+use chrono::Utc;
+
+fn map_row_to_tag(row: &rusqlite::Row) -> rusqlite::Result<Tag> {
+    Ok(Tag {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        color: row.get("color")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+#[tauri::command]
+pub async fn get_tags(db: State<'_, Database>) -> Result<Vec<Tag>, CommandError> {
+    let conn = db.pool.get()?;
+
+    let mut stmt = conn
+        .prepare("SELECT * FROM tags ORDER BY name COLLATE NOCASE")?;
+
+    let tags = stmt
+        .query_map([], map_row_to_tag)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(tags)
+}
+
+/// Create a tag, or return the existing one when the name is already taken (case-insensitive)
+#[tauri::command]
+pub async fn create_tag(
+    db: State<'_, Database>,
+    tag: CreateTagInput,
+) -> Result<Tag, CommandError> {
+    let name = tag.name.trim();
+    if name.is_empty() {
+        return Err(CommandError::Validation("Tag name cannot be empty".to_string()));
+    }
+
+    let conn = db.pool.get()?;
+
+    let id = format!("TAG-{}-{}", Utc::now().timestamp_millis(), uuid::Uuid::new_v4());
+    conn.execute(
+        "INSERT OR IGNORE INTO tags (id, name, color, created_at) VALUES (?, ?, ?, ?)",
+        rusqlite::params![id, name, tag.color, Utc::now().timestamp()],
+    )?;
+
+    conn.query_row("SELECT * FROM tags WHERE name = ?", [name], map_row_to_tag)
+        .map_err(CommandError::from)
+}
+
+/// Delete a tag and all of its trade assignments
+#[tauri::command]
+pub async fn delete_tag(
+    db: State<'_, Database>,
+    id: String,
+) -> Result<(), CommandError> {
+    let conn = db.pool.get()?;
+    conn.execute("DELETE FROM trade_tags WHERE tag_id = ?", [&id])?;
+    conn.execute("DELETE FROM tags WHERE id = ?", [&id])?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn assign_tag(
+    db: State<'_, Database>,
+    trade_id: String,
+    tag_id: String,
+) -> Result<(), CommandError> {
+    let conn = db.pool.get()?;
+    conn.execute(
+        "INSERT OR IGNORE INTO trade_tags (trade_id, tag_id, created_at) VALUES (?, ?, ?)",
+        rusqlite::params![trade_id, tag_id, Utc::now().timestamp()],
+    )?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn remove_tag(
+    db: State<'_, Database>,
+    trade_id: String,
+    tag_id: String,
+) -> Result<(), CommandError> {
+    let conn = db.pool.get()?;
+    conn.execute(
+        "DELETE FROM trade_tags WHERE trade_id = ? AND tag_id = ?",
+        rusqlite::params![trade_id, tag_id],
+    )?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_trade_tags(
+    db: State<'_, Database>,
+    trade_id: String,
+) -> Result<Vec<Tag>, CommandError> {
+    let conn = db.pool.get()?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.* FROM tags t
+             JOIN trade_tags tt ON tt.tag_id = t.id
+             WHERE tt.trade_id = ?
+             ORDER BY t.name COLLATE NOCASE",
+        )?;
+
+    let tags = stmt
+        .query_map([&trade_id], map_row_to_tag)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(tags)
+}
@@ -0,0 +1,146 @@
+//! Fixtures shared by the unit tests
+
+use rusqlite::Connection;
+use crate::db::migration_runner::MigrationRunner;
+use crate::models::Trade;
+
+/// In-memory database with every migration applied
+pub(crate) fn test_conn() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    MigrationRunner::new().run_pending_migrations(&conn, ":memory:").unwrap();
+    conn
+}
+
+/// An open, hand-entered BTC/USDT long on BitGet: entry 100, stop 90, quantity 1, 1R of 100.
+/// Tests override what they need with struct update syntax.
+pub(crate) fn trade(id: &str) -> Trade {
+    Trade {
+        id: id.to_string(),
+        pair: "BTC/USDT".to_string(),
+        exchange: "BitGet".to_string(),
+        analysis_date: 0,
+        trade_date: 0,
+        status: "OPEN".to_string(),
+        portfolio_value: 10_000.0,
+        r_percent: 0.01,
+        min_rr: 2.0,
+        planned_pe: 100.0,
+        planned_sl: 90.0,
+        leverage: 1,
+        planned_tps: "[]".to_string(),
+        planned_entries: None,
+        position_type: "LONG".to_string(),
+        one_r: 100.0,
+        margin: 100.0,
+        position_size: 100.0,
+        quantity: 1.0,
+        planned_weighted_rr: 2.0,
+        effective_pe: None,
+        effective_entries: None,
+        close_date: None,
+        exits: None,
+        effective_weighted_rr: None,
+        total_pnl: None,
+        pnl_in_r: None,
+        notes: String::new(),
+        execution_portfolio: None,
+        execution_r_percent: None,
+        execution_margin: None,
+        execution_position_size: None,
+        execution_quantity: None,
+        execution_one_r: None,
+        execution_potential_profit: None,
+        import_fingerprint: None,
+        import_source: "USER_CREATED".to_string(),
+        account_id: None,
+        grade: None,
+        pre_emotion: None,
+        post_emotion: None,
+        liquidated: false,
+        funding_pnl: None,
+        created_at: 0,
+        updated_at: 0,
+    }
+}
+
+/// Insert a live trade with every column the model has
+pub(crate) fn insert_trade(conn: &Connection, trade: &Trade) {
+    insert_trashed_trade(conn, trade, None);
+}
+
+/// Insert a trade already in the trash since `deleted_at`. Trashing it with an UPDATE
+/// instead would record a revision.
+pub(crate) fn insert_trashed_trade(conn: &Connection, trade: &Trade, deleted_at: Option<i64>) {
+    conn.execute(
+        "INSERT INTO trades (
+            id, pair, exchange, analysis_date, trade_date, status,
+            portfolio_value, r_percent, min_rr,
+            planned_pe, planned_sl, leverage, planned_tps, planned_entries,
+            position_type, one_r, margin, position_size, quantity, planned_weighted_rr,
+            effective_pe, effective_entries, close_date, exits,
+            effective_weighted_rr, total_pnl, pnl_in_r,
+            notes, execution_portfolio, execution_r_percent, execution_margin,
+            execution_position_size, execution_quantity, execution_one_r, execution_potential_profit,
+            import_fingerprint, import_source, account_id, grade, pre_emotion, post_emotion, liquidated,
+            created_at, updated_at, deleted_at
+        ) VALUES (
+            ?, ?, ?, ?, ?, ?,
+            ?, ?, ?,
+            ?, ?, ?, ?, ?,
+            ?, ?, ?, ?, ?, ?,
+            ?, ?, ?, ?,
+            ?, ?, ?,
+            ?, ?, ?, ?, ?, ?, ?, ?,
+            ?, ?, ?, ?, ?, ?, ?,
+            ?, ?, ?
+        )",
+        rusqlite::params![
+            trade.id,
+            trade.pair,
+            trade.exchange,
+            trade.analysis_date,
+            trade.trade_date,
+            trade.status,
+            trade.portfolio_value,
+            trade.r_percent,
+            trade.min_rr,
+            trade.planned_pe,
+            trade.planned_sl,
+            trade.leverage,
+            trade.planned_tps,
+            trade.planned_entries,
+            trade.position_type,
+            trade.one_r,
+            trade.margin,
+            trade.position_size,
+            trade.quantity,
+            trade.planned_weighted_rr,
+            trade.effective_pe,
+            trade.effective_entries,
+            trade.close_date,
+            trade.exits,
+            trade.effective_weighted_rr,
+            trade.total_pnl,
+            trade.pnl_in_r,
+            trade.notes,
+            trade.execution_portfolio,
+            trade.execution_r_percent,
+            trade.execution_margin,
+            trade.execution_position_size,
+            trade.execution_quantity,
+            trade.execution_one_r,
+            trade.execution_potential_profit,
+            trade.import_fingerprint,
+            trade.import_source,
+            trade.account_id,
+            trade.grade,
+            trade.pre_emotion,
+            trade.post_emotion,
+            trade.liquidated,
+            trade.created_at,
+            trade.updated_at,
+            deleted_at,
+        ],
+    )
+    .unwrap();
+}
@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+
+use tauri::State;
+use rusqlite::types::Value;
+use crate::db::Database;
+use crate::commands::error::CommandError;
+use crate::commands::trade_metrics::breakeven_threshold;
+use crate::commands::trades::{map_row_to_trade, TRADE_SELECT};
+use crate::models::{Trade, TradeCampaign, TradeLink};
+use chrono::Utc;
+
+/// Allowed trade_links.relation values (enforced by a CHECK constraint too)
+pub(crate) const TRADE_LINK_RELATIONS: &[&str] = &["RE_ENTRY", "HEDGE", "SCALE_IN"];
+
+fn map_row_to_trade_link(row: &rusqlite::Row) -> rusqlite::Result<TradeLink> {
+    Ok(TradeLink {
+        trade_id: row.get("trade_id")?,
+        related_trade_id: row.get("related_trade_id")?,
+        relation: row.get("relation")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+/// Link a follow-up trade (re-entry, hedge or scale-in) to the trade it follows up on.
+/// Two trades have at most one link: linking them again replaces it, either way round.
+#[tauri::command]
+pub async fn link_trades(
+    db: State<'_, Database>,
+    trade_id: String,
+    related_trade_id: String,
+    relation: String,
+) -> Result<TradeLink, CommandError> {
+    let relation = relation.trim().to_uppercase();
+    if !TRADE_LINK_RELATIONS.contains(&relation.as_str()) {
+        return Err(CommandError::Validation(format!(
+            "Unknown relation {}, expected one of {}",
+            relation,
+            TRADE_LINK_RELATIONS.join(", ")
+        )));
+    }
+    if trade_id == related_trade_id {
+        return Err(CommandError::Validation("A trade cannot be linked to itself".to_string()));
+    }
+
+    let mut conn = db.pool.get()?;
+    let tx = conn.transaction()?;
+
+    for id in [&trade_id, &related_trade_id] {
+        let exists: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM trades WHERE id = ? AND deleted_at IS NULL)",
+            [id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(CommandError::NotFound(format!("Trade {} not found", id)));
+        }
+    }
+
+    tx.execute(
+        "DELETE FROM trade_links WHERE trade_id = ?2 AND related_trade_id = ?1",
+        rusqlite::params![trade_id, related_trade_id],
+    )?;
+    tx.execute(
+        "INSERT INTO trade_links (trade_id, related_trade_id, relation, created_at) VALUES (?, ?, ?, ?)
+         ON CONFLICT(trade_id, related_trade_id) DO UPDATE SET relation = excluded.relation",
+        rusqlite::params![trade_id, related_trade_id, relation, Utc::now().timestamp()],
+    )?;
+
+    let link = tx.query_row(
+        "SELECT * FROM trade_links WHERE trade_id = ? AND related_trade_id = ?",
+        rusqlite::params![trade_id, related_trade_id],
+        map_row_to_trade_link,
+    )?;
+    tx.commit()?;
+
+    Ok(link)
+}
+
+/// Remove the link between two trades, whichever way round it was made
+#[tauri::command]
+pub async fn unlink_trades(
+    db: State<'_, Database>,
+    trade_id: String,
+    related_trade_id: String,
+) -> Result<(), CommandError> {
+    let conn = db.pool.get()?;
+    conn.execute(
+        "DELETE FROM trade_links
+         WHERE (trade_id = ?1 AND related_trade_id = ?2) OR (trade_id = ?2 AND related_trade_id = ?1)",
+        rusqlite::params![trade_id, related_trade_id],
+    )?;
+    Ok(())
+}
+
+/// Links from and to a trade, oldest first
+#[tauri::command]
+pub async fn get_trade_links(
+    db: State<'_, Database>,
+    trade_id: String,
+) -> Result<Vec<TradeLink>, CommandError> {
+    let conn = db.pool.get()?;
+
+    let mut stmt = conn
+        .prepare("SELECT * FROM trade_links WHERE trade_id = ?1 OR related_trade_id = ?1 ORDER BY created_at")?;
+
+    let links = stmt
+        .query_map([trade_id], map_row_to_trade_link)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(links)
+}
+
+/// Ids of the live trades reachable from trade ?1 through links, either way round
+const CAMPAIGN_CTE: &str = "WITH RECURSIVE campaign(id) AS (
+        SELECT id FROM trades WHERE id = ?1 AND deleted_at IS NULL
+        UNION
+        SELECT t.id FROM campaign c
+        JOIN trade_links l ON c.id IN (l.trade_id, l.related_trade_id)
+        JOIN trades t ON t.id = CASE WHEN l.trade_id = c.id THEN l.related_trade_id ELSE l.trade_id END
+        WHERE t.deleted_at IS NULL
+    )";
+
+/// The campaign a trade belongs to: every live trade reachable through links, with the
+/// combined outcome. A trade without links is a campaign of its own.
+#[tauri::command]
+pub async fn get_trade_campaign(
+    db: State<'_, Database>,
+    trade_id: String,
+) -> Result<TradeCampaign, CommandError> {
+    let conn = db.pool.get()?;
+    load_trade_campaign(&conn, &trade_id)
+}
+
+fn load_trade_campaign(conn: &rusqlite::Connection, trade_id: &str) -> Result<TradeCampaign, CommandError> {
+    let trades = conn
+        .prepare(&format!(
+            "{} {} WHERE id IN (SELECT id FROM campaign) ORDER BY trade_date, id",
+            CAMPAIGN_CTE, TRADE_SELECT
+        ))?
+        .query_map([trade_id], map_row_to_trade)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let links = conn
+        .prepare(&format!(
+            "{} SELECT * FROM trade_links
+             WHERE trade_id IN (SELECT id FROM campaign) AND related_trade_id IN (SELECT id FROM campaign)
+             ORDER BY created_at",
+            CAMPAIGN_CTE
+        ))?
+        .query_map([trade_id], map_row_to_trade_link)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    group_campaigns(trades, links, breakeven_threshold(conn)?)
+        .pop()
+        .ok_or_else(|| CommandError::NotFound(format!("Trade {} not found", trade_id)))
+}
+
+/// Group the live trades into campaigns, ordered by their first trade. `account_filter` is
+/// an "AND account_id = ?" fragment and its params (see StatsScope::account_filter); a link
+/// to a trade outside the filter or in the trash doesn't join anything.
+pub(crate) fn load_campaigns(
+    conn: &rusqlite::Connection,
+    account_filter: (String, Vec<Value>),
+) -> rusqlite::Result<Vec<TradeCampaign>> {
+    let (filter, params) = account_filter;
+    let trades = conn
+        .prepare(&format!("{} WHERE deleted_at IS NULL {} ORDER BY trade_date, id", TRADE_SELECT, filter))?
+        .query_map(rusqlite::params_from_iter(params.iter()), map_row_to_trade)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let links = conn
+        .prepare("SELECT * FROM trade_links ORDER BY created_at")?
+        .query_map([], map_row_to_trade_link)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(group_campaigns(trades, links, breakeven_threshold(conn)?))
+}
+
+/// Union-find over the links; `trades` must be sorted by trade_date
+fn group_campaigns(trades: Vec<Trade>, links: Vec<TradeLink>, breakeven_threshold: f64) -> Vec<TradeCampaign> {
+    let index: HashMap<String, usize> = trades.iter().enumerate().map(|(i, t)| (t.id.clone(), i)).collect();
+    let mut parent: Vec<usize> = (0..trades.len()).collect();
+
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    for link in &links {
+        if let (Some(&a), Some(&b)) = (index.get(&link.trade_id), index.get(&link.related_trade_id)) {
+            let (root_a, root_b) = (find(&mut parent, a), find(&mut parent, b));
+            // The earlier trade stays the root, so groups come out in trade_date order
+            parent[root_a.max(root_b)] = root_a.min(root_b);
+        }
+    }
+
+    let mut groups: Vec<Vec<Trade>> = Vec::new();
+    let mut group_of_root: HashMap<usize, usize> = HashMap::new();
+    for (i, trade) in trades.into_iter().enumerate() {
+        let root = find(&mut parent, i);
+        let group = *group_of_root.entry(root).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[group].push(trade);
+    }
+
+    let mut links_by_trade: HashMap<String, Vec<TradeLink>> = HashMap::new();
+    for link in links {
+        if index.contains_key(&link.related_trade_id) {
+            links_by_trade.entry(link.trade_id.clone()).or_default().push(link);
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|trades| {
+            let links = trades
+                .iter()
+                .flat_map(|t| links_by_trade.remove(&t.id).unwrap_or_default())
+                .collect();
+            build_campaign(trades, links, breakeven_threshold)
+        })
+        .collect()
+}
+
+/// A multi-trade campaign within the break-even threshold of zero overall is BE
+fn build_campaign(trades: Vec<Trade>, links: Vec<TradeLink>, breakeven_threshold: f64) -> TradeCampaign {
+    let closed = trades.iter().all(|t| matches!(t.status.as_str(), "WIN" | "LOSS" | "BE"));
+    let total_pnl: f64 = trades.iter().filter_map(|t| t.total_pnl).sum();
+    let r_values: Vec<f64> = trades.iter().filter_map(|t| t.pnl_in_r).collect();
+
+    let status = if !closed {
+        "OPEN".to_string()
+    } else if let [trade] = trades.as_slice() {
+        trade.status.clone()
+    } else if trades.iter().all(|t| t.status == "BE") || total_pnl.abs() <= breakeven_threshold {
+        "BE".to_string()
+    } else if total_pnl > 0.0 {
+        "WIN".to_string()
+    } else {
+        "LOSS".to_string()
+    };
+
+    TradeCampaign {
+        id: trades[0].id.clone(),
+        status,
+        total_pnl,
+        pnl_in_r: (!r_values.is_empty()).then(|| r_values.iter().sum()),
+        start_date: trades[0].trade_date,
+        end_date: if closed { trades.iter().filter_map(|t| t.close_date).max() } else { None },
+        trades,
+        links,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::{insert_trade, test_conn, trade};
+
+    fn insert_closed_trade(conn: &rusqlite::Connection, id: &str, status: &str, pnl: Option<f64>, trade_date: i64) {
+        insert_trade(conn, &Trade {
+            analysis_date: trade_date,
+            trade_date,
+            close_date: Some(trade_date + 3600),
+            status: status.to_string(),
+            total_pnl: pnl,
+            pnl_in_r: pnl.map(|pnl| pnl / 100.0),
+            ..trade(id)
+        });
+    }
+
+    #[test]
+    fn test_linked_trades_form_one_campaign() {
+        let conn = test_conn();
+
+        insert_closed_trade(&conn, "T1", "LOSS", Some(-100.0), 1_000);
+        insert_closed_trade(&conn, "T2", "WIN", Some(250.0), 2_000);
+        insert_closed_trade(&conn, "T3", "WIN", Some(50.0), 3_000);
+        insert_closed_trade(&conn, "T4", "OPEN", None, 4_000);
+        // T3 re-enters T2's setup, which re-entered T1's: links chain transitively
+        for (from, to) in [("T2", "T3"), ("T1", "T2")] {
+            conn.execute(
+                "INSERT INTO trade_links (trade_id, related_trade_id, relation, created_at) VALUES (?, ?, 'RE_ENTRY', 0)",
+                [from, to],
+            )
+            .unwrap();
+        }
+
+        let campaigns = load_campaigns(&conn, (String::new(), Vec::new())).unwrap();
+        assert_eq!(campaigns.len(), 2);
+
+        let chain = &campaigns[0];
+        assert_eq!(chain.id, "T1");
+        assert_eq!(chain.trades.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), vec!["T1", "T2", "T3"]);
+        assert_eq!(chain.links.len(), 2);
+        assert_eq!(chain.status, "WIN");
+        assert_eq!(chain.total_pnl, 200.0);
+        assert_eq!(chain.pnl_in_r, Some(2.0));
+        assert_eq!(chain.end_date, Some(3_000 + 3600));
+
+        assert_eq!(campaigns[1].status, "OPEN");
+        assert_eq!(campaigns[1].end_date, None);
+
+        // Trashing the middle trade breaks the chain
+        conn.execute("UPDATE trades SET deleted_at = 1 WHERE id = 'T2'", []).unwrap();
+        let campaigns = load_campaigns(&conn, (String::new(), Vec::new())).unwrap();
+        assert_eq!(campaigns.len(), 3);
+        assert_eq!(campaigns[0].status, "LOSS");
+        assert!(load_trade_campaign(&conn, "T2").is_err());
+    }
+
+    #[test]
+    fn test_trade_campaign_follows_links_from_the_trade() {
+        let conn = test_conn();
+
+        insert_closed_trade(&conn, "T1", "LOSS", Some(-100.0), 1_000);
+        insert_closed_trade(&conn, "T2", "WIN", Some(100.3), 2_000);
+        insert_closed_trade(&conn, "T3", "WIN", Some(50.0), 3_000);
+        insert_closed_trade(&conn, "T4", "WIN", Some(80.0), 4_000);
+        // T1 and T3 both follow up on T2, reached from T3 against the link direction
+        for (from, to) in [("T2", "T3"), ("T2", "T1")] {
+            conn.execute(
+                "INSERT INTO trade_links (trade_id, related_trade_id, relation, created_at) VALUES (?, ?, 'HEDGE', 0)",
+                [from, to],
+            )
+            .unwrap();
+        }
+
+        let campaign = load_trade_campaign(&conn, "T3").unwrap();
+        assert_eq!(campaign.trades.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), vec!["T1", "T2", "T3"]);
+        assert_eq!(campaign.links.len(), 2);
+        assert_eq!(load_trade_campaign(&conn, "T4").unwrap().trades.len(), 1);
+
+        // Netting within the break-even threshold of zero makes the campaign BE
+        conn.execute("UPDATE trades SET total_pnl = -0.2 WHERE id = 'T3'", []).unwrap();
+        assert_eq!(load_trade_campaign(&conn, "T1").unwrap().status, "BE");
+        conn.execute("UPDATE settings SET breakeven_threshold = 0 WHERE id = 1", []).unwrap();
+        assert_eq!(load_trade_campaign(&conn, "T1").unwrap().status, "WIN");
+    }
+}
@@ -0,0 +1,260 @@
+use tauri::State;
+use serde::{Deserialize, Serialize};
+use crate::db::Database;
+use crate::commands::error::CommandError;
+use crate::commands::trades::{map_row_to_trade, trade_filter_clause, TRADE_SELECT};
+use crate::models::{Trade, TradeFilters};
+use chrono::Utc;
+
+/// Values closer than this are treated as unchanged, so float noise doesn't rewrite trades
+const METRIC_EPSILON: f64 = 1e-9;
+
+/// A price and the share of the position (0-100) at it, as stored in the exits,
+/// effective_entries and planned_entries JSON columns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceLevel {
+    pub price: f64,
+    pub percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsRecalculation {
+    pub trades_checked: usize,
+    /// Trades where at least one value changed
+    pub trades_updated: usize,
+}
+
+/// Settings' break-even threshold
+pub(crate) fn breakeven_threshold(conn: &rusqlite::Connection) -> rusqlite::Result<f64> {
+    conn.query_row("SELECT breakeven_threshold FROM settings WHERE id = 1", [], |row| row.get(0))
+}
+
+/// WIN, LOSS or BE for a closed trade's PnL
+pub(crate) fn closed_status(total_pnl: f64, breakeven_threshold: f64) -> &'static str {
+    if total_pnl.abs() <= breakeven_threshold {
+        "BE"
+    } else if total_pnl > 0.0 {
+        "WIN"
+    } else {
+        "LOSS"
+    }
+}
+
+/// Levels from a JSON column, without the unfilled (zero price or share) ones
+pub(crate) fn parse_levels(json: Option<&str>) -> Vec<PriceLevel> {
+    json.and_then(|json| serde_json::from_str::<Vec<PriceLevel>>(json).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|level| level.price > 0.0 && level.percent > 0.0)
+        .collect()
+}
+
+/// Average price weighted by share; None without levels
+pub(crate) fn weighted_price(levels: &[PriceLevel]) -> Option<f64> {
+    let total: f64 = levels.iter().map(|l| l.percent).sum();
+    (total > 0.0).then(|| levels.iter().map(|l| l.price * l.percent).sum::<f64>() / total)
+}
+
+/// The price a trade was actually entered at: its filled entries, else effective_pe, else the plan
+pub(crate) fn effective_entry(trade: &Trade) -> f64 {
+    weighted_price(&parse_levels(trade.effective_entries.as_deref()))
+        .or(trade.effective_pe.filter(|pe| *pe > 0.0))
+        .unwrap_or(trade.planned_pe)
+}
+
+/// R multiple of the exits against the planned stop, weighted by their share of what was
+/// exited (as the trade detail page computes it). None without exits or stop distance.
+pub(crate) fn effective_rr(position_type: &str, entry: f64, stop: f64, exits: &[PriceLevel]) -> Option<f64> {
+    let total: f64 = exits.iter().map(|e| e.percent).sum();
+    let risk = if position_type == "SHORT" { stop - entry } else { entry - stop };
+    if total <= 0.0 || risk.abs() < METRIC_EPSILON {
+        return None;
+    }
+
+    Some(
+        exits
+            .iter()
+            .map(|exit| {
+                let gain = if position_type == "SHORT" { entry - exit.price } else { exit.price - entry };
+                gain / risk * exit.percent / total
+            })
+            .sum(),
+    )
+}
+
+/// Recompute the values derived from each trade's own data: pnl_in_r, effective_weighted_rr,
+/// the WIN/LOSS/BE status (with the current break-even threshold), margin and position_size.
+/// Use after changing settings or fixing imported data. Without filters every live trade is
+/// checked; paging fields are ignored. Open trades stay open.
+#[tauri::command]
+pub async fn recalculate_trade_metrics(
+    db: State<'_, Database>,
+    filters: Option<TradeFilters>,
+) -> Result<MetricsRecalculation, CommandError> {
+    let mut conn = db.pool.get()?;
+    recalculate(&mut conn, filters.as_ref()).map_err(CommandError::from)
+}
+
+fn recalculate(conn: &mut rusqlite::Connection, filters: Option<&TradeFilters>) -> rusqlite::Result<MetricsRecalculation> {
+    let threshold = breakeven_threshold(conn)?;
+    let tx = conn.transaction()?;
+
+    let trades = {
+        let (where_clause, params) = trade_filter_clause(filters);
+        let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let mut stmt = tx.prepare(&format!("{} WHERE {}", TRADE_SELECT, where_clause))?;
+        stmt.query_map(param_refs.as_slice(), map_row_to_trade)?
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let mut trades_updated = 0;
+    for trade in &trades {
+        if store_recalculated(&tx, trade, threshold)? {
+            trades_updated += 1;
+        }
+    }
+
+    tx.commit()?;
+    Ok(MetricsRecalculation { trades_checked: trades.len(), trades_updated })
+}
+
+/// Recompute one trade's derived values, e.g. after an import merged new executions into it
+pub(crate) fn recalculate_trade(
+    conn: &rusqlite::Connection,
+    trade_id: &str,
+    breakeven_threshold: f64,
+) -> rusqlite::Result<bool> {
+    let trade = conn.query_row(&format!("{} WHERE id = ?", TRADE_SELECT), [trade_id], map_row_to_trade)?;
+    store_recalculated(conn, &trade, breakeven_threshold)
+}
+
+/// Write the trade's recomputed values; false when they were already current
+fn store_recalculated(conn: &rusqlite::Connection, trade: &Trade, breakeven_threshold: f64) -> rusqlite::Result<bool> {
+    let updated = recalculated(trade, breakeven_threshold);
+    if !metrics_differ(trade, &updated) {
+        return Ok(false);
+    }
+
+    conn.execute(
+        "UPDATE trades SET pnl_in_r = ?, effective_weighted_rr = ?, status = ?, margin = ?,
+            position_size = ?, updated_at = ?
+         WHERE id = ?",
+        rusqlite::params![
+            updated.pnl_in_r,
+            updated.effective_weighted_rr,
+            updated.status,
+            updated.margin,
+            updated.position_size,
+            Utc::now().timestamp(),
+            trade.id,
+        ],
+    )?;
+    Ok(true)
+}
+
+/// The trade with its derived values recomputed; values that can't be derived (no 1R, no
+/// exits, no quantity) are kept
+fn recalculated(trade: &Trade, breakeven_threshold: f64) -> Trade {
+    let mut updated = trade.clone();
+
+    if let Some(pnl) = trade.total_pnl.filter(|_| trade.one_r > 0.0) {
+        updated.pnl_in_r = Some(pnl / trade.one_r);
+    }
+
+    let exits = parse_levels(trade.exits.as_deref());
+    if let Some(rr) = effective_rr(&trade.position_type, effective_entry(trade), trade.planned_sl, &exits) {
+        updated.effective_weighted_rr = Some(rr);
+    }
+
+    if matches!(trade.status.as_str(), "WIN" | "LOSS" | "BE")
+        && let Some(pnl) = trade.total_pnl
+    {
+        updated.status = closed_status(pnl, breakeven_threshold).to_string();
+    }
+
+    if trade.quantity > 0.0 && trade.planned_pe > 0.0 {
+        updated.position_size = trade.quantity * trade.planned_pe;
+        if trade.leverage > 0 {
+            updated.margin = updated.position_size / trade.leverage as f64;
+        }
+    }
+
+    updated
+}
+
+fn metrics_differ(a: &Trade, b: &Trade) -> bool {
+    let differ = |x: f64, y: f64| (x - y).abs() > METRIC_EPSILON;
+    let differ_opt = |x: Option<f64>, y: Option<f64>| match (x, y) {
+        (Some(x), Some(y)) => differ(x, y),
+        (x, y) => x.is_some() != y.is_some(),
+    };
+
+    a.status != b.status
+        || differ_opt(a.pnl_in_r, b.pnl_in_r)
+        || differ_opt(a.effective_weighted_rr, b.effective_weighted_rr)
+        || differ(a.margin, b.margin)
+        || differ(a.position_size, b.position_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::{insert_trade, test_conn, trade};
+
+    #[test]
+    fn test_effective_rr_weights_exits() {
+        let exits = vec![
+            PriceLevel { price: 120.0, percent: 50.0 },
+            PriceLevel { price: 90.0, percent: 50.0 },
+        ];
+        // Long from 100 with the stop at 90: +2R on half, -1R on the other half
+        assert!((effective_rr("LONG", 100.0, 90.0, &exits).unwrap() - 0.5).abs() < 1e-9);
+        // Short from 100 with the stop at 110: 120 is -2R, 90 is +1R
+        assert!((effective_rr("SHORT", 100.0, 110.0, &exits).unwrap() + 0.5).abs() < 1e-9);
+        assert_eq!(effective_rr("LONG", 100.0, 100.0, &exits), None);
+        assert_eq!(effective_rr("LONG", 100.0, 90.0, &[]), None);
+    }
+
+    #[test]
+    fn test_recalculate_fixes_derived_values() {
+        let mut conn = test_conn();
+        conn.execute("UPDATE settings SET breakeven_threshold = 5 WHERE id = 1", []).unwrap();
+
+        // Stale values everywhere: 1R is 100, quantity 2 at 100 with 5x leverage
+        for (id, status, pnl) in [("T1", "WIN", Some(3.0)), ("T2", "OPEN", None)] {
+            insert_trade(&conn, &Trade {
+                status: status.to_string(),
+                leverage: 5,
+                margin: 1.0,
+                position_size: 1.0,
+                quantity: 2.0,
+                exits: Some(r#"[{"price":120,"percent":100}]"#.to_string()),
+                total_pnl: pnl,
+                pnl_in_r: Some(9.0),
+                ..trade(id)
+            });
+        }
+
+        let result = recalculate(&mut conn, None).unwrap();
+        assert_eq!((result.trades_checked, result.trades_updated), (2, 2));
+
+        let (status, pnl_in_r, rr, margin, size): (String, f64, f64, f64, f64) = conn
+            .query_row(
+                "SELECT status, pnl_in_r, effective_weighted_rr, margin, position_size FROM trades WHERE id = 'T1'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+            )
+            .unwrap();
+        assert_eq!(status, "BE");
+        assert!((pnl_in_r - 0.03).abs() < 1e-9);
+        assert!((rr - 2.0).abs() < 1e-9);
+        assert!((margin - 40.0).abs() < 1e-9);
+        assert!((size - 200.0).abs() < 1e-9);
+
+        let status: String = conn.query_row("SELECT status FROM trades WHERE id = 'T2'", [], |row| row.get(0)).unwrap();
+        assert_eq!(status, "OPEN");
+
+        // Nothing left to change
+        assert_eq!(recalculate(&mut conn, None).unwrap().trades_updated, 0);
+    }
+}
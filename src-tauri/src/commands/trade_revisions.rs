@@ -0,0 +1,219 @@
+use tauri::State;
+use crate::commands::trades::map_row_to_trade;
+use crate::db::Database;
+use crate::commands::error::CommandError;
+use crate::models::{Trade, TradeRevision};
+use chrono::Utc;
+use rusqlite::OptionalExtension;
+
+/// Trade columns a revert writes back. Identity, timestamps and the trash state are left
+/// alone (restore_trade / delete_trade handle the latter).
+const REVERTIBLE_COLUMNS: &[&str] = &[
+    "pair", "exchange", "analysis_date", "trade_date", "close_date", "status",
+    "portfolio_value", "r_percent", "min_rr", "planned_pe", "planned_sl", "leverage",
+    "planned_tps", "planned_entries", "position_type", "one_r", "margin", "position_size",
+    "quantity", "planned_weighted_rr", "effective_pe", "effective_entries", "exits",
+    "effective_weighted_rr", "total_pnl", "pnl_in_r", "notes", "execution_portfolio",
+    "execution_r_percent", "execution_margin", "execution_position_size", "execution_quantity",
+    "execution_one_r", "execution_potential_profit", "import_fingerprint", "import_source",
+    "grade", "pre_emotion", "post_emotion", "liquidated",
+];
+
+fn map_row_to_revision(row: &rusqlite::Row) -> rusqlite::Result<TradeRevision> {
+    let before_data: String = row.get("before_data")?;
+    let after_data: String = row.get("after_data")?;
+    let before: serde_json::Value = serde_json::from_str(&before_data).unwrap_or_default();
+    let after: serde_json::Value = serde_json::from_str(&after_data).unwrap_or_default();
+
+    let changed_fields = match (before.as_object(), after.as_object()) {
+        (Some(b), Some(a)) => a
+            .iter()
+            .filter(|(key, value)| key.as_str() != "updated_at" && b.get(key.as_str()) != Some(*value))
+            .map(|(key, _)| key.clone())
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    Ok(TradeRevision {
+        id: row.get("id")?,
+        trade_id: row.get("trade_id")?,
+        before,
+        after,
+        changed_fields,
+        changed_at: row.get("changed_at")?,
+    })
+}
+
+/// Recorded edits of a trade, newest first
+#[tauri::command]
+pub async fn get_trade_history(
+    db: State<'_, Database>,
+    trade_id: String,
+) -> Result<Vec<TradeRevision>, CommandError> {
+    let conn = db.pool.get()?;
+
+    let mut stmt = conn
+        .prepare("SELECT * FROM trade_revisions WHERE trade_id = ? ORDER BY id DESC")?;
+
+    let revisions = stmt
+        .query_map([&trade_id], map_row_to_revision)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(revisions)
+}
+
+/// Put a trade back to how it was before the given revision. The revert is itself
+/// recorded as a new revision, so it can be undone the same way.
+#[tauri::command]
+pub async fn revert_trade_revision(
+    db: State<'_, Database>,
+    revision_id: i64,
+) -> Result<Trade, CommandError> {
+    let conn = db.pool.get()?;
+    revert_revision(&conn, revision_id)?;
+
+    conn.query_row(
+        "SELECT * FROM trades WHERE id = (SELECT trade_id FROM trade_revisions WHERE id = ?)",
+        [revision_id],
+        map_row_to_trade,
+    )
+    .map_err(CommandError::from)
+}
+
+fn revert_revision(conn: &rusqlite::Connection, revision_id: i64) -> Result<(), CommandError> {
+    let (trade_id, before_data): (String, String) = conn
+        .query_row(
+            "SELECT trade_id, before_data FROM trade_revisions WHERE id = ?",
+            [revision_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?
+        .ok_or_else(|| CommandError::NotFound(format!("Revision {} not found", revision_id)))?;
+
+    // Snapshots recorded before a column existed don't have its key; those columns keep
+    // their current value (json_type is NULL for a missing key, 'null' for a null value)
+    let mut updates: Vec<String> = REVERTIBLE_COLUMNS
+        .iter()
+        .map(|col| {
+            format!(
+                "{col} = CASE WHEN json_type(?1, '$.{col}') IS NOT NULL THEN json_extract(?1, '$.{col}') ELSE {col} END"
+            )
+        })
+        .collect();
+    // The account may have been deleted since; fall back to no account like ON DELETE SET NULL
+    updates.push(
+        "account_id = CASE WHEN json_type(?1, '$.account_id') IS NOT NULL
+            THEN (SELECT id FROM accounts WHERE id = json_extract(?1, '$.account_id')) ELSE account_id END"
+            .to_string(),
+    );
+    updates.push("updated_at = ?2".to_string());
+
+    let query = format!("UPDATE trades SET {} WHERE id = ?3", updates.join(", "));
+    let updated = conn.execute(&query, rusqlite::params![before_data, Utc::now().timestamp(), trade_id])?;
+
+    if updated == 0 {
+        return Err(CommandError::NotFound(format!("Trade {} no longer exists", trade_id)));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::{insert_trade, test_conn, trade};
+
+    #[test]
+    fn test_update_is_recorded_and_revertible() {
+        let conn = test_conn();
+
+        insert_trade(&conn, &Trade { planned_tps: r#"[{"price": 120}]"#.to_string(), notes: "original".to_string(), ..trade("T1") });
+
+        // Writes outside update_trade (sync, live mirror) are captured by the trigger too
+        conn.execute("UPDATE trades SET notes = 'overwritten', total_pnl = 50, updated_at = 1 WHERE id = 'T1'", [])
+            .unwrap();
+
+        let revision = conn
+            .query_row("SELECT * FROM trade_revisions WHERE trade_id = 'T1'", [], map_row_to_revision)
+            .unwrap();
+        assert_eq!(revision.changed_fields.len(), 2);
+        assert!(revision.changed_fields.contains(&"notes".to_string()));
+        assert!(revision.changed_fields.contains(&"total_pnl".to_string()));
+        assert_eq!(revision.before["notes"], "original");
+
+        revert_revision(&conn, revision.id).unwrap();
+
+        let (notes, total_pnl, planned_tps): (String, Option<f64>, String) = conn
+            .query_row("SELECT notes, total_pnl, planned_tps FROM trades WHERE id = 'T1'", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .unwrap();
+        assert_eq!(notes, "original");
+        assert_eq!(total_pnl, None);
+        assert_eq!(planned_tps, "[{\"price\": 120}]");
+
+        // The revert is a revision of its own
+        let revisions: i64 = conn
+            .query_row("SELECT COUNT(*) FROM trade_revisions WHERE trade_id = 'T1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(revisions, 2);
+    }
+
+    #[test]
+    fn test_revisions_cover_every_trade_column() {
+        let conn = test_conn();
+
+        let columns: std::collections::BTreeSet<String> = conn
+            .prepare("SELECT name FROM pragma_table_info('trades')")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        // The trigger snapshots every column, before and after
+        insert_trade(&conn, &trade("T1"));
+        conn.execute("UPDATE trades SET notes = 'edited' WHERE id = 'T1'", []).unwrap();
+        let revision = conn
+            .query_row("SELECT * FROM trade_revisions WHERE trade_id = 'T1'", [], map_row_to_revision)
+            .unwrap();
+        for snapshot in [&revision.before, &revision.after] {
+            let keys: std::collections::BTreeSet<String> = snapshot.as_object().unwrap().keys().cloned().collect();
+            assert_eq!(keys, columns, "trg_trades_update_revision is out of date");
+        }
+
+        // A revert writes back every column but the ones it handles on its own
+        let mut reverted: std::collections::BTreeSet<String> =
+            REVERTIBLE_COLUMNS.iter().map(|col| col.to_string()).collect();
+        reverted.extend(["id", "account_id", "created_at", "updated_at", "deleted_at"].map(String::from));
+        assert_eq!(reverted, columns, "REVERTIBLE_COLUMNS is out of date");
+    }
+
+    #[test]
+    fn test_revert_snapshot_from_before_newer_columns() {
+        let conn = test_conn();
+
+        insert_trade(&conn, &Trade { notes: "current".to_string(), grade: Some("A".to_string()), ..trade("T1") });
+
+        // Recorded before grades (021), emotions (022) and the liquidated flag (029) existed
+        let old_snapshot = serde_json::json!({ "id": "T1", "notes": "old", "total_pnl": null });
+        conn.execute(
+            "INSERT INTO trade_revisions (trade_id, before_data, after_data, changed_at) VALUES ('T1', ?, '{}', 0)",
+            [old_snapshot.to_string()],
+        )
+        .unwrap();
+        let revision_id = conn.last_insert_rowid();
+
+        revert_revision(&conn, revision_id).unwrap();
+
+        let (notes, grade, liquidated, pair): (String, Option<String>, bool, String) = conn
+            .query_row("SELECT notes, grade, liquidated, pair FROM trades WHERE id = 'T1'", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .unwrap();
+        assert_eq!(notes, "old");
+        assert_eq!(grade.as_deref(), Some("A"));
+        assert!(!liquidated);
+        assert_eq!(pair, "BTC/USDT");
+    }
+}
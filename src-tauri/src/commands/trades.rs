@@ -1,11 +1,26 @@
 use tauri::State;
 use crate::db::Database;
-use crate::models::{Trade, CreateTradeInput, TradeFilters};
+use crate::commands::error::CommandError;
+use crate::commands::trade_metrics::{breakeven_threshold, closed_status, effective_entry, effective_rr, PriceLevel};
+use crate::models::{BulkTradePatch, Trade, CreateTradeInput, TradeFilters};
 use chrono::Utc;
+use rusqlite::OptionalExtension;
+
+/// Allowed pre_emotion / post_emotion values (enforced by a CHECK constraint too)
+pub(crate) const TRADE_EMOTIONS: &[&str] = &[
+    "CALM", "CONFIDENT", "EXCITED", "ANXIOUS", "FEARFUL", "GREEDY", "FOMO", "FRUSTRATED",
+    "BORED", "TIRED", "SATISFIED", "REGRETFUL",
+];
+
+/// Columns the trade list can be sorted by (TradeFilters.sort_by)
+const SORTABLE_COLUMNS: &[&str] = &["trade_date", "close_date", "total_pnl", "pnl_in_r", "pair", "status"];
+
+/// Trade columns plus the computed funding_pnl read by map_row_to_trade
+pub(crate) const TRADE_SELECT: &str = "SELECT *, (SELECT SUM(amount) FROM funding_payments f WHERE f.trade_id = trades.id) AS funding_pnl FROM trades";
 
 /// Helper function to map a database row to a Trade struct using named columns.
 /// Named access is resilient to column order changes caused by ALTER TABLE migrations.
-fn map_row_to_trade(row: &rusqlite::Row) -> rusqlite::Result<Trade> {
+pub(crate) fn map_row_to_trade(row: &rusqlite::Row) -> rusqlite::Result<Trade> {
     Ok(Trade {
         id: row.get("id")?,
         pair: row.get("pair")?,
@@ -37,6 +52,12 @@ fn map_row_to_trade(row: &rusqlite::Row) -> rusqlite::Result<Trade> {
         notes: row.get("notes")?,
         import_fingerprint: row.get("import_fingerprint").ok(),
         import_source: row.get("import_source")?,
+        account_id: row.get("account_id").ok(),
+        grade: row.get("grade").ok(),
+        pre_emotion: row.get("pre_emotion").ok(),
+        post_emotion: row.get("post_emotion").ok(),
+        liquidated: row.get("liquidated").unwrap_or(false),
+        funding_pnl: row.get("funding_pnl").ok(),
         created_at: row.get("created_at")?,
         updated_at: row.get("updated_at")?,
         execution_portfolio: row.get("execution_portfolio").ok(),
@@ -53,19 +74,120 @@ fn map_row_to_trade(row: &rusqlite::Row) -> rusqlite::Result<Trade> {
 pub async fn get_trades(
     db: State<'_, Database>,
     filters: Option<TradeFilters>,
-) -> Result<Vec<Trade>, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+) -> Result<Vec<Trade>, CommandError> {
+    let conn = db.pool.get()?;
+
+    let order_clause = trade_order_clause(filters.as_ref())?;
+    let (where_clause, mut params) = trade_filter_clause(filters.as_ref());
+    let mut query = format!("{} WHERE {} ORDER BY {}", TRADE_SELECT, where_clause, order_clause);
+
+    if let Some(f) = &filters
+        && let (Some(page), Some(limit)) = (f.page, f.limit)
+    {
+        let offset = (page - 1) * limit;
+        query.push_str(" LIMIT ? OFFSET ?");
+        params.push(Box::new(limit));
+        params.push(Box::new(offset));
+    }
+
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+    let mut stmt = conn.prepare(&query)?;
+    let trades_iter = stmt.query_map(param_refs.as_slice(), map_row_to_trade)?;
+
+    let trades: Result<Vec<Trade>, _> = trades_iter.collect();
+    trades.map_err(CommandError::from)
+}
+
+/// Most results search_trades returns
+const SEARCH_LIMIT: i64 = 200;
+
+/// Live trades whose pair, notes or id contain the query (case-insensitive), newest first
+#[tauri::command]
+pub async fn search_trades(
+    db: State<'_, Database>,
+    query: String,
+) -> Result<Vec<Trade>, CommandError> {
+    let conn = db.pool.get()?;
+    search(&conn, &query).map_err(CommandError::from)
+}
+
+fn search(conn: &rusqlite::Connection, query: &str) -> rusqlite::Result<Vec<Trade>> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // The query is matched literally, so escape LIKE's wildcards
+    let pattern = format!(
+        "%{}%",
+        query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+    );
+    let sql = format!(
+        "{} WHERE deleted_at IS NULL
+            AND (pair LIKE ?1 ESCAPE '\\' OR notes LIKE ?1 ESCAPE '\\' OR id LIKE ?1 ESCAPE '\\')
+         ORDER BY trade_date DESC, id DESC
+         LIMIT ?2",
+        TRADE_SELECT
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    stmt.query_map(rusqlite::params![pattern, SEARCH_LIMIT], map_row_to_trade)?
+        .collect()
+}
+
+/// Number of trades get_trades would return for the filters without page/limit, so the
+/// frontend can render page controls
+#[tauri::command]
+pub async fn count_trades(
+    db: State<'_, Database>,
+    filters: Option<TradeFilters>,
+) -> Result<i64, CommandError> {
+    let conn = db.pool.get()?;
+
+    let (where_clause, params) = trade_filter_clause(filters.as_ref());
+    let query = format!("SELECT COUNT(*) FROM trades WHERE {}", where_clause);
 
-    let mut query = String::from("SELECT * FROM trades WHERE deleted_at IS NULL");
-    let mut conditions = Vec::new();
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+    conn.query_row(&query, param_refs.as_slice(), |row| row.get(0))
+        .map_err(CommandError::from)
+}
+
+/// ORDER BY clause (without the keyword) for the filters' sort, newest trades first by default.
+/// Trades without a value (e.g. open trades' close_date) come last either way, and the id
+/// keeps pages stable between equal values.
+fn trade_order_clause(filters: Option<&TradeFilters>) -> Result<String, CommandError> {
+    let column = filters.and_then(|f| f.sort_by.as_deref()).unwrap_or("trade_date");
+    if !SORTABLE_COLUMNS.contains(&column) {
+        return Err(CommandError::Validation(format!(
+            "Cannot sort by {}, expected one of {}",
+            column,
+            SORTABLE_COLUMNS.join(", ")
+        )));
+    }
+
+    let direction = match filters.and_then(|f| f.sort_dir.as_deref()).map(str::to_lowercase).as_deref() {
+        None | Some("desc") => "DESC",
+        Some("asc") => "ASC",
+        Some(other) => {
+            return Err(CommandError::Validation(format!("Sort direction must be asc or desc, got {}", other)));
+        }
+    };
+
+    Ok(format!("{} {} NULLS LAST, id {}", column, direction, direction))
+}
+
+/// WHERE clause (without the keyword) and its parameters selecting the live trades that
+/// match the filters. Paging fields are left to the caller.
+pub(crate) fn trade_filter_clause(filters: Option<&TradeFilters>) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+    let mut conditions = vec!["deleted_at IS NULL"];
     let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
-    if let Some(f) = &filters {
-        if let Some(status) = &f.status {
-            if status != "all" {
-                conditions.push("status = ?");
-                params.push(Box::new(status.clone()));
-            }
+    if let Some(f) = filters {
+        if let Some(status) = &f.status
+            && status != "all"
+        {
+            conditions.push("status = ?");
+            params.push(Box::new(status.clone()));
         }
         if let Some(pair) = &f.pair {
             conditions.push("pair LIKE ?");
@@ -79,44 +201,33 @@ pub async fn get_trades(
             conditions.push("trade_date <= ?");
             params.push(Box::new(end_date));
         }
-    }
-
-    if !conditions.is_empty() {
-        query.push_str(&format!(" AND {}", conditions.join(" AND ")));
-    }
-
-    query.push_str(" ORDER BY trade_date DESC");
-
-    if let Some(f) = &filters {
-        if let (Some(page), Some(limit)) = (f.page, f.limit) {
-            let offset = (page - 1) * limit;
-            query.push_str(" LIMIT ? OFFSET ?");
-            params.push(Box::new(limit));
-            params.push(Box::new(offset));
+        if let Some(tag) = &f.tag {
+            conditions.push(
+                "id IN (SELECT tt.trade_id FROM trade_tags tt JOIN tags t ON t.id = tt.tag_id WHERE t.name = ?)",
+            );
+            params.push(Box::new(tag.clone()));
+        }
+        if let Some(account_id) = &f.account_id {
+            conditions.push("account_id = ?");
+            params.push(Box::new(account_id.clone()));
         }
     }
 
-    let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
-    let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
-    let trades_iter = stmt.query_map(param_refs.as_slice(), map_row_to_trade)
-        .map_err(|e| e.to_string())?;
-
-    let trades: Result<Vec<Trade>, _> = trades_iter.collect();
-    trades.map_err(|e| e.to_string())
+    (conditions.join(" AND "), params)
 }
 
 #[tauri::command]
 pub async fn get_trade(
     db: State<'_, Database>,
     id: String,
-) -> Result<Trade, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+) -> Result<Trade, CommandError> {
+    let conn = db.pool.get()?;
 
     let trade = conn.query_row(
-        "SELECT * FROM trades WHERE id = ?",
+        &format!("{} WHERE id = ?", TRADE_SELECT),
         [&id],
         map_row_to_trade,
-    ).map_err(|e| e.to_string())?;
+    )?;
 
     Ok(trade)
 }
@@ -125,11 +236,11 @@ pub async fn get_trade(
 pub async fn create_trade(
     db: State<'_, Database>,
     trade: CreateTradeInput,
-) -> Result<Trade, String> {
+) -> Result<Trade, CommandError> {
     let id = {
-        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        let conn = db.pool.get()?;
 
-        let id = format!("TRADE-{}-{}", Utc::now().timestamp_millis(), uuid::Uuid::new_v4().to_string());
+        let id = format!("TRADE-{}-{}", Utc::now().timestamp_millis(), uuid::Uuid::new_v4());
         let now = Utc::now().timestamp();
 
         conn.execute(
@@ -139,17 +250,17 @@ pub async fn create_trade(
                 planned_tps, planned_entries, position_type, one_r, margin, position_size, quantity,
                 planned_weighted_rr, notes, execution_portfolio, execution_r_percent, execution_margin,
                 execution_position_size, execution_quantity, execution_one_r, execution_potential_profit,
-                import_source, created_at, updated_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                import_source, account_id, created_at, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             rusqlite::params![
                 id, trade.pair, trade.exchange, trade.analysis_date, trade.trade_date, trade.status,
                 trade.portfolio_value, trade.r_percent, trade.min_rr, trade.planned_pe, trade.planned_sl, trade.leverage,
                 trade.planned_tps, trade.planned_entries, trade.position_type, trade.one_r, trade.margin, trade.position_size, trade.quantity,
                 trade.planned_weighted_rr, trade.notes, trade.execution_portfolio, trade.execution_r_percent, trade.execution_margin,
                 trade.execution_position_size, trade.execution_quantity, trade.execution_one_r, trade.execution_potential_profit,
-                "USER_CREATED", now, now
+                "USER_CREATED", trade.account_id, now, now
             ],
-        ).map_err(|e| e.to_string())?;
+        )?;
 
         id
     };
@@ -161,43 +272,42 @@ pub async fn create_trade(
 pub async fn delete_trade(
     db: State<'_, Database>,
     id: String,
-) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+) -> Result<(), CommandError> {
+    let conn = db.pool.get()?;
     let now = Utc::now().timestamp();
     conn.execute(
         "UPDATE trades SET deleted_at = ? WHERE id = ?",
         rusqlite::params![now, &id]
-    ).map_err(|e| e.to_string())?;
+    )?;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn get_deleted_trades(
     db: State<'_, Database>,
-) -> Result<Vec<Trade>, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+) -> Result<Vec<Trade>, CommandError> {
+    let conn = db.pool.get()?;
 
     let mut stmt = conn.prepare(
         "SELECT * FROM trades WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC"
-    ).map_err(|e| e.to_string())?;
+    )?;
 
-    let trades_iter = stmt.query_map([], map_row_to_trade)
-        .map_err(|e| e.to_string())?;
+    let trades_iter = stmt.query_map([], map_row_to_trade)?;
 
     let trades: Result<Vec<Trade>, _> = trades_iter.collect();
-    trades.map_err(|e| e.to_string())
+    trades.map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub async fn restore_trade(
     db: State<'_, Database>,
     id: String,
-) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+) -> Result<(), CommandError> {
+    let conn = db.pool.get()?;
     conn.execute(
         "UPDATE trades SET deleted_at = NULL WHERE id = ?",
         [&id]
-    ).map_err(|e| e.to_string())?;
+    )?;
     Ok(())
 }
 
@@ -206,9 +316,9 @@ pub async fn update_trade(
     db: State<'_, Database>,
     id: String,
     trade_update: serde_json::Value,
-) -> Result<Trade, String> {
+) -> Result<Trade, CommandError> {
     {
-        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        let conn = db.pool.get()?;
 
         let now = Utc::now().timestamp();
 
@@ -322,6 +432,14 @@ pub async fn update_trade(
                 values.push(Box::new(val));
             }
         }
+        if let Some(v) = trade_update.get("account_id") {
+            if v.is_null() {
+                updates.push("account_id = NULL");
+            } else if let Some(val) = v.as_str() {
+                updates.push("account_id = ?");
+                values.push(Box::new(val.to_string()));
+            }
+        }
         if let Some(v) = trade_update.get("execution_potential_profit") {
             if v.is_null() {
                 updates.push("execution_potential_profit = NULL");
@@ -335,7 +453,230 @@ pub async fn update_trade(
         values.push(Box::new(id.clone()));
 
         let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
-        conn.execute(&query, params.as_slice()).map_err(|e| e.to_string())?;
+        conn.execute(&query, params.as_slice())?;
+    }
+
+    get_trade(db, id).await
+}
+
+/// Apply the same change to many trades at once, e.g. to a multi-selection in the trade
+/// list. Everything happens in one transaction. Trades in the trash and unknown ids are
+/// skipped; returns how many trades were updated.
+#[tauri::command]
+pub async fn bulk_update_trades(
+    db: State<'_, Database>,
+    ids: Vec<String>,
+    patch: BulkTradePatch,
+) -> Result<usize, CommandError> {
+    let patch = BulkTradePatch {
+        exchange: patch.exchange.map(|e| e.trim().to_string()),
+        ..patch
+    };
+    if patch.exchange.as_deref() == Some("") {
+        return Err(CommandError::Validation("Exchange cannot be empty".to_string()));
+    }
+    if patch.exchange.is_none()
+        && patch.notes.is_none()
+        && patch.add_tag_ids.is_empty()
+        && patch.remove_tag_ids.is_empty()
+    {
+        return Err(CommandError::Validation("Nothing to update".to_string()));
+    }
+
+    let mut conn = db.pool.get()?;
+    apply_bulk_patch(&mut conn, &ids, &patch).map_err(CommandError::from)
+}
+
+fn apply_bulk_patch(
+    conn: &mut rusqlite::Connection,
+    ids: &[String],
+    patch: &BulkTradePatch,
+) -> rusqlite::Result<usize> {
+    let tx = conn.transaction()?;
+    let now = Utc::now().timestamp();
+    let mut updated = 0;
+
+    for id in ids {
+        let exists: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM trades WHERE id = ? AND deleted_at IS NULL)",
+            [id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            continue;
+        }
+
+        // Only touch the row when a column changes, so tag-only edits leave no revision
+        if patch.exchange.is_some() || patch.notes.is_some() {
+            tx.execute(
+                "UPDATE trades SET exchange = COALESCE(?1, exchange), notes = COALESCE(?2, notes),
+                    updated_at = ?3
+                 WHERE id = ?4",
+                rusqlite::params![patch.exchange, patch.notes, now, id],
+            )?;
+        }
+        for tag_id in &patch.add_tag_ids {
+            tx.execute(
+                "INSERT OR IGNORE INTO trade_tags (trade_id, tag_id, created_at) VALUES (?, ?, ?)",
+                rusqlite::params![id, tag_id, now],
+            )?;
+        }
+        for tag_id in &patch.remove_tag_ids {
+            tx.execute(
+                "DELETE FROM trade_tags WHERE trade_id = ? AND tag_id = ?",
+                rusqlite::params![id, tag_id],
+            )?;
+        }
+        updated += 1;
+    }
+
+    tx.commit()?;
+    Ok(updated)
+}
+
+/// How far the exits may add up from 100% and still close the whole position
+const EXIT_PERCENT_TOLERANCE: f64 = 0.1;
+
+/// Close a trade from its exits: the PnL (net of `fees`), R, effective RR and WIN/LOSS/BE
+/// status are computed here, as the trade detail page does, and written in one update.
+/// Exits are {price, percent} with percents adding up to 100. An already closed trade is
+/// closed again with the new exits.
+#[tauri::command]
+pub async fn close_trade(
+    db: State<'_, Database>,
+    id: String,
+    exits: Vec<PriceLevel>,
+    close_date: i64,
+    fees: Option<f64>,
+) -> Result<Trade, CommandError> {
+    {
+        let conn = db.pool.get()?;
+        let trade = conn
+            .query_row(
+                &format!("{} WHERE id = ? AND deleted_at IS NULL", TRADE_SELECT),
+                [&id],
+                map_row_to_trade,
+            )
+            .optional()?
+            .ok_or_else(|| CommandError::NotFound(format!("Trade {} not found", id)))?;
+
+        if close_date < trade.trade_date {
+            return Err(CommandError::Validation("Close date cannot be before the trade date".to_string()));
+        }
+        let (total_pnl, rr) = closing_pnl(&trade, &exits, fees.unwrap_or(0.0)).map_err(CommandError::Validation)?;
+        let status = closed_status(total_pnl, breakeven_threshold(&conn)?);
+
+        conn.execute(
+            "UPDATE trades SET exits = ?, close_date = ?, total_pnl = ?, pnl_in_r = ?,
+                effective_weighted_rr = ?, status = ?, updated_at = ?
+             WHERE id = ?",
+            rusqlite::params![
+                serde_json::to_string(&exits)?,
+                close_date,
+                total_pnl,
+                total_pnl / trade.one_r,
+                rr,
+                status,
+                Utc::now().timestamp(),
+                &id,
+            ],
+        )?;
+    }
+
+    get_trade(db, id).await
+}
+
+/// Net PnL and effective RR of closing the whole trade at `exits`
+fn closing_pnl(trade: &Trade, exits: &[PriceLevel], fees: f64) -> Result<(f64, f64), String> {
+    if exits.is_empty() {
+        return Err("At least one exit is required".to_string());
+    }
+    let is_valid = |e: &PriceLevel| e.price > 0.0 && e.percent > 0.0 && e.percent <= 100.0;
+    if let Some(exit) = exits.iter().find(|e| !is_valid(e)) {
+        return Err(format!(
+            "Exit at {} for {}% is invalid: the price must be positive and the share between 0 and 100%",
+            exit.price, exit.percent
+        ));
+    }
+    let total_percent: f64 = exits.iter().map(|e| e.percent).sum();
+    if (total_percent - 100.0).abs() > EXIT_PERCENT_TOLERANCE {
+        return Err(format!("Exits must add up to 100% of the position, got {}%", total_percent));
+    }
+    if !fees.is_finite() || fees < 0.0 {
+        return Err(format!("Fees must be zero or more, got {}", fees));
+    }
+    if trade.one_r <= 0.0 {
+        return Err("The trade has no 1R to measure the result against".to_string());
+    }
+
+    let rr = effective_rr(&trade.position_type, effective_entry(trade), trade.planned_sl, exits)
+        .ok_or("The entry and stop loss are at the same price")?;
+    Ok((trade.one_r * rr - fees, rr))
+}
+
+/// Grade a trade's execution on review (A best to D worst), or clear the grade with None
+#[tauri::command]
+pub async fn set_trade_grade(
+    db: State<'_, Database>,
+    id: String,
+    grade: Option<String>,
+) -> Result<Trade, CommandError> {
+    let grade = grade.map(|g| g.trim().to_uppercase());
+    if let Some(g) = &grade
+        && !matches!(g.as_str(), "A" | "B" | "C" | "D")
+    {
+        return Err(CommandError::Validation(format!("Grade must be A, B, C or D, got {}", g)));
+    }
+
+    {
+        let conn = db.pool.get()?;
+        let updated = conn
+            .execute(
+                "UPDATE trades SET grade = ?, updated_at = ? WHERE id = ?",
+                rusqlite::params![grade, Utc::now().timestamp(), &id],
+            )?;
+
+        if updated == 0 {
+            return Err(CommandError::NotFound(format!("Trade {} not found", id)));
+        }
+    }
+
+    get_trade(db, id).await
+}
+
+/// Record how the user felt before entering and after closing a trade. Both are
+/// replaced; None clears an emotion.
+#[tauri::command]
+pub async fn set_trade_emotions(
+    db: State<'_, Database>,
+    id: String,
+    pre_emotion: Option<String>,
+    post_emotion: Option<String>,
+) -> Result<Trade, CommandError> {
+    let normalize = |emotion: Option<String>| -> Result<Option<String>, CommandError> {
+        match emotion.map(|e| e.trim().to_uppercase()) {
+            Some(e) if !TRADE_EMOTIONS.contains(&e.as_str()) => Err(CommandError::Validation(format!(
+                "Unknown emotion {}, expected one of {}",
+                e,
+                TRADE_EMOTIONS.join(", ")
+            ))),
+            e => Ok(e),
+        }
+    };
+    let pre_emotion = normalize(pre_emotion)?;
+    let post_emotion = normalize(post_emotion)?;
+
+    {
+        let conn = db.pool.get()?;
+        let updated = conn
+            .execute(
+                "UPDATE trades SET pre_emotion = ?, post_emotion = ?, updated_at = ? WHERE id = ?",
+                rusqlite::params![pre_emotion, post_emotion, Utc::now().timestamp(), &id],
+            )?;
+
+        if updated == 0 {
+            return Err(CommandError::NotFound(format!("Trade {} not found", id)));
+        }
     }
 
     get_trade(db, id).await
@@ -345,13 +686,13 @@ pub async fn update_trade(
 pub async fn duplicate_trade(
     db: State<'_, Database>,
     id: String,
-) -> Result<Trade, String> {
+) -> Result<Trade, CommandError> {
     let original = get_trade(db.clone(), id).await?;
 
     let new_id = {
-        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        let conn = db.pool.get()?;
 
-        let new_id = format!("TRADE-{}-{}", Utc::now().timestamp_millis(), uuid::Uuid::new_v4().to_string());
+        let new_id = format!("TRADE-{}-{}", Utc::now().timestamp_millis(), uuid::Uuid::new_v4());
         let now = Utc::now().timestamp();
 
         // Copy trade but reset to OPEN status and clear execution data
@@ -366,17 +707,17 @@ pub async fn duplicate_trade(
                 id, pair, exchange, analysis_date, trade_date, status,
                 portfolio_value, r_percent, min_rr, planned_pe, planned_sl, leverage,
                 planned_tps, planned_entries, position_type, one_r, margin, position_size, quantity,
-                planned_weighted_rr, notes, import_source, created_at, updated_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                planned_weighted_rr, notes, import_source, account_id, created_at, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             rusqlite::params![
                 new_id, original.pair, original.exchange, original.analysis_date, now, "OPEN",
                 original.portfolio_value, original.r_percent, original.min_rr,
                 original.planned_pe, original.planned_sl, original.leverage,
                 original.planned_tps, original.planned_entries, original.position_type, original.one_r,
                 original.margin, original.position_size, original.quantity,
-                original.planned_weighted_rr, notes, "USER_CREATED", now, now
+                original.planned_weighted_rr, notes, "USER_CREATED", original.account_id, now, now
             ],
-        ).map_err(|e| e.to_string())?;
+        )?;
 
         new_id
     };
@@ -387,9 +728,345 @@ pub async fn duplicate_trade(
 #[tauri::command]
 pub async fn delete_all_trades(
     db: State<'_, Database>,
-) -> Result<usize, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-    let count = conn.execute("DELETE FROM trades", [])
-        .map_err(|e| e.to_string())?;
+) -> Result<usize, CommandError> {
+    let conn = db.pool.get()?;
+    let count = conn.execute("DELETE FROM trades", [])?;
     Ok(count)
 }
+
+/// Permanently delete a trade from the trash, with its revisions and its tag and journal
+/// links. Only soft-deleted trades can be purged.
+#[tauri::command]
+pub async fn purge_trade(
+    db: State<'_, Database>,
+    id: String,
+) -> Result<(), CommandError> {
+    let mut conn = db.pool.get()?;
+    let count = purge_securely(&mut conn, |conn| purge_ids(conn, std::slice::from_ref(&id)))?;
+    if count == 0 {
+        return Err(CommandError::Validation(format!("Trade {} is not in the trash", id)));
+    }
+    Ok(())
+}
+
+/// Move the selected trades to the trash in one transaction. Returns how many were moved.
+#[tauri::command]
+pub async fn delete_trades(
+    db: State<'_, Database>,
+    ids: Vec<String>,
+) -> Result<usize, CommandError> {
+    let mut conn = db.pool.get()?;
+    let now = Utc::now().timestamp();
+    update_each(
+        &mut conn,
+        "UPDATE trades SET deleted_at = ?2 WHERE id = ?1 AND deleted_at IS NULL",
+        &ids,
+        &[&now],
+    )
+    .map_err(CommandError::from)
+}
+
+/// Take the selected trades out of the trash in one transaction. Returns how many were restored.
+#[tauri::command]
+pub async fn restore_trades(
+    db: State<'_, Database>,
+    ids: Vec<String>,
+) -> Result<usize, CommandError> {
+    let mut conn = db.pool.get()?;
+    update_each(
+        &mut conn,
+        "UPDATE trades SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+        &ids,
+        &[],
+    )
+    .map_err(CommandError::from)
+}
+
+/// Permanently delete the selected trades in one transaction. Like purge_trade only trashed
+/// trades are deleted; the others are skipped. Returns how many were deleted.
+#[tauri::command]
+pub async fn purge_trades(
+    db: State<'_, Database>,
+    ids: Vec<String>,
+) -> Result<usize, CommandError> {
+    let mut conn = db.pool.get()?;
+    purge_securely(&mut conn, |conn| purge_ids(conn, &ids)).map_err(CommandError::from)
+}
+
+/// Run `delete` (which removes trashed trades) so nothing of them stays readable in the
+/// database files: connections zero deleted content (secure_delete, see
+/// `Database::open_connection`) and the WAL, which still holds the old pages, is checkpointed
+/// and truncated. Revisions, tag and journal links go with the trade (ON DELETE CASCADE).
+fn purge_securely(
+    conn: &mut rusqlite::Connection,
+    delete: impl FnOnce(&mut rusqlite::Connection) -> rusqlite::Result<usize>,
+) -> rusqlite::Result<usize> {
+    let purged = delete(conn)?;
+    if purged > 0 {
+        // Best effort: a checkpoint blocked by a reader reports busy rather than failing
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+    }
+    Ok(purged)
+}
+
+/// Delete the trashed trades among `ids`
+fn purge_ids(conn: &mut rusqlite::Connection, ids: &[String]) -> rusqlite::Result<usize> {
+    update_each(conn, "DELETE FROM trades WHERE id = ?1 AND deleted_at IS NOT NULL", ids, &[])
+}
+
+/// Run `sql` for every id (bound to ?1, `extra` follows from ?2) inside one transaction,
+/// returning the rows changed
+fn update_each(
+    conn: &mut rusqlite::Connection,
+    sql: &str,
+    ids: &[String],
+    extra: &[&dyn rusqlite::ToSql],
+) -> rusqlite::Result<usize> {
+    let tx = conn.transaction()?;
+    let mut changed = 0;
+    {
+        let mut stmt = tx.prepare(sql)?;
+        for id in ids {
+            let mut params: Vec<&dyn rusqlite::ToSql> = vec![id];
+            params.extend_from_slice(extra);
+            changed += stmt.execute(params.as_slice())?;
+        }
+    }
+    tx.commit()?;
+    Ok(changed)
+}
+
+/// Permanently delete every trade in the trash
+#[tauri::command]
+pub async fn empty_trash(
+    db: State<'_, Database>,
+) -> Result<usize, CommandError> {
+    let mut conn = db.pool.get()?;
+    purge_securely(&mut conn, |conn| conn.execute("DELETE FROM trades WHERE deleted_at IS NOT NULL", []))
+        .map_err(CommandError::from)
+}
+
+/// Purge trades deleted more than settings.trash_retention_days ago (nothing when it is 0).
+/// Called once at startup.
+pub(crate) fn purge_expired_trash(conn: &mut rusqlite::Connection) -> Result<usize, String> {
+    let retention_days: i64 = conn
+        .query_row("SELECT trash_retention_days FROM settings WHERE id = 1", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if retention_days <= 0 {
+        return Ok(0);
+    }
+
+    let cutoff = Utc::now().timestamp() - retention_days * 24 * 60 * 60;
+    purge_securely(conn, |conn| {
+        conn.execute("DELETE FROM trades WHERE deleted_at IS NOT NULL AND deleted_at < ?", [cutoff])
+    })
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::{insert_trade, insert_trashed_trade, test_conn, trade};
+
+    #[test]
+    fn test_purge_expired_trash() {
+        let mut conn = test_conn();
+
+        let now = Utc::now().timestamp();
+        for (id, deleted_at) in [("T1", None), ("T2", Some(now - 40 * 86_400)), ("T3", Some(now - 86_400))] {
+            insert_trashed_trade(&conn, &trade(id), deleted_at);
+        }
+
+        // Retention off by default
+        assert_eq!(purge_expired_trash(&mut conn).unwrap(), 0);
+
+        conn.execute("UPDATE settings SET trash_retention_days = 30 WHERE id = 1", []).unwrap();
+        assert_eq!(purge_expired_trash(&mut conn).unwrap(), 1);
+
+        let remaining: Vec<String> = conn
+            .prepare("SELECT id FROM trades ORDER BY id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(remaining, vec!["T1", "T3"]);
+    }
+
+    #[test]
+    fn test_filter_clause_counts_live_matching_trades() {
+        let conn = test_conn();
+
+        for (id, status, deleted_at) in [("T1", "OPEN", None), ("T2", "WIN", None), ("T3", "OPEN", Some(1))] {
+            insert_trashed_trade(&conn, &Trade { status: status.to_string(), ..trade(id) }, deleted_at);
+        }
+
+        let count = |filters: Option<&TradeFilters>| -> i64 {
+            let (where_clause, params) = trade_filter_clause(filters);
+            let refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+            conn.query_row(&format!("SELECT COUNT(*) FROM trades WHERE {}", where_clause), refs.as_slice(), |row| row.get(0))
+                .unwrap()
+        };
+
+        assert_eq!(count(None), 2);
+        let filters = TradeFilters {
+            status: Some("OPEN".to_string()),
+            page: Some(1),
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(count(Some(&filters)), 1);
+    }
+
+    #[test]
+    fn test_order_clause_whitelists_columns() {
+        assert_eq!(trade_order_clause(None).unwrap(), "trade_date DESC NULLS LAST, id DESC");
+
+        let filters = TradeFilters {
+            sort_by: Some("total_pnl".to_string()),
+            sort_dir: Some("ASC".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(trade_order_clause(Some(&filters)).unwrap(), "total_pnl ASC NULLS LAST, id ASC");
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE t (id TEXT, total_pnl REAL); INSERT INTO t VALUES ('A', NULL), ('B', 5), ('C', -1);")
+            .unwrap();
+        let ids: Vec<String> = conn
+            .prepare(&format!("SELECT id FROM t ORDER BY {}", trade_order_clause(Some(&filters)).unwrap()))
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(ids, vec!["C", "B", "A"]);
+
+        let filters = TradeFilters {
+            sort_by: Some("notes; DROP TABLE trades".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(trade_order_clause(Some(&filters)), Err(CommandError::Validation(_))));
+
+        let filters = TradeFilters {
+            sort_dir: Some("sideways".to_string()),
+            ..Default::default()
+        };
+        assert!(trade_order_clause(Some(&filters)).is_err());
+    }
+
+    #[test]
+    fn test_search_matches_pair_notes_and_id() {
+        let conn = test_conn();
+
+        for (id, pair, notes, deleted_at) in [
+            ("TRADE-1", "BTC/USDT", "Breakout retest", None),
+            ("TRADE-2", "ETH/USDT", "Took 50% off early", None),
+            ("TRADE-3", "SOL/USDT", "breakout, deleted", Some(1)),
+        ] {
+            insert_trashed_trade(&conn, &Trade { pair: pair.to_string(), notes: notes.to_string(), ..trade(id) }, deleted_at);
+        }
+
+        let ids = |query: &str| -> Vec<String> { search(&conn, query).unwrap().into_iter().map(|t| t.id).collect() };
+
+        assert_eq!(ids("BREAKOUT"), vec!["TRADE-1"]);
+        assert_eq!(ids("eth"), vec!["TRADE-2"]);
+        assert_eq!(ids("trade-"), vec!["TRADE-2", "TRADE-1"]);
+        // Wildcards are literal
+        assert_eq!(ids("50%"), vec!["TRADE-2"]);
+        assert_eq!(ids("%"), vec!["TRADE-2"]);
+        assert!(ids("  ").is_empty());
+    }
+
+    #[test]
+    fn test_closing_pnl_validates_exits() {
+        let conn = test_conn();
+        // Long from 100, stop 90, 1R = 100
+        insert_trade(&conn, &trade("T1"));
+        let trade = conn
+            .query_row(&format!("{} WHERE id = 'T1'", TRADE_SELECT), [], map_row_to_trade)
+            .unwrap();
+        let exit = |price: f64, percent: f64| PriceLevel { price, percent };
+
+        // +2R on half, +1R on the other half, less 10 in fees
+        let (pnl, rr) = closing_pnl(&trade, &[exit(120.0, 50.0), exit(110.0, 50.0)], 10.0).unwrap();
+        assert!((rr - 1.5).abs() < 1e-9);
+        assert!((pnl - 140.0).abs() < 1e-9);
+
+        assert!(closing_pnl(&trade, &[], 0.0).is_err());
+        assert!(closing_pnl(&trade, &[exit(120.0, 60.0)], 0.0).is_err());
+        assert!(closing_pnl(&trade, &[exit(0.0, 100.0)], 0.0).is_err());
+        assert!(closing_pnl(&trade, &[exit(120.0, 100.0)], -1.0).is_err());
+        assert!(closing_pnl(&trade, &[exit(120.0, 33.33), exit(120.0, 66.67)], 0.0).is_ok());
+    }
+
+    #[test]
+    fn test_bulk_trash_restore_and_purge() {
+        let mut conn = test_conn();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+
+        for id in ["T1", "T2", "T3"] {
+            insert_trade(&conn, &trade(id));
+        }
+        let ids = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let trash = "UPDATE trades SET deleted_at = ?2 WHERE id = ?1 AND deleted_at IS NULL";
+
+        assert_eq!(update_each(&mut conn, trash, &ids(&["T1", "T2", "MISSING"]), &[&1_i64]).unwrap(), 2);
+        // Active trades are never purged
+        assert_eq!(purge_securely(&mut conn, |conn| purge_ids(conn, &ids(&["T1", "T3"]))).unwrap(), 1);
+        let revisions: i64 = conn
+            .query_row("SELECT COUNT(*) FROM trade_revisions WHERE trade_id = 'T1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(revisions, 0);
+
+        let restore = "UPDATE trades SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL";
+        assert_eq!(update_each(&mut conn, restore, &ids(&["T1", "T2"]), &[]).unwrap(), 1);
+
+        let active: i64 = conn
+            .query_row("SELECT COUNT(*) FROM trades WHERE deleted_at IS NULL", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(active, 2);
+    }
+
+    #[test]
+    fn test_bulk_update_trades() {
+        let mut conn = test_conn();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+
+        for (id, deleted_at) in [("T1", None), ("T2", None), ("T3", Some(1))] {
+            insert_trashed_trade(&conn, &Trade { notes: "old".to_string(), ..trade(id) }, deleted_at);
+        }
+        conn.execute("INSERT INTO tags (id, name, created_at) VALUES ('TAG1', 'breakout', 0)", [])
+            .unwrap();
+
+        let ids: Vec<String> = ["T1", "T2", "T3", "MISSING"].iter().map(|s| s.to_string()).collect();
+        let patch = BulkTradePatch {
+            exchange: Some("BloFin".to_string()),
+            add_tag_ids: vec!["TAG1".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(apply_bulk_patch(&mut conn, &ids, &patch).unwrap(), 2);
+
+        let rows: Vec<(String, String, String)> = conn
+            .prepare("SELECT id, exchange, notes FROM trades ORDER BY id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(rows[0], ("T1".to_string(), "BloFin".to_string(), "old".to_string()));
+        assert_eq!(rows[2].1, "BitGet");
+        let tagged: i64 = conn
+            .query_row("SELECT COUNT(*) FROM trade_tags WHERE tag_id = 'TAG1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(tagged, 2);
+
+        // A failing assignment rolls back the whole batch
+        let patch = BulkTradePatch {
+            notes: Some("new".to_string()),
+            add_tag_ids: vec!["NO-SUCH-TAG".to_string()],
+            ..Default::default()
+        };
+        assert!(apply_bulk_patch(&mut conn, &ids, &patch).is_err());
+        let notes: String = conn.query_row("SELECT notes FROM trades WHERE id = 'T1'", [], |row| row.get(0)).unwrap();
+        assert_eq!(notes, "old");
+    }
+}
@@ -1,21 +1,57 @@
 use rusqlite::{Connection, Result};
-use std::sync::Mutex;
+use std::path::PathBuf;
+use crate::db::encryption;
 use crate::db::migration_runner::MigrationRunner;
 use log;
+use std::time::Duration;
 
-pub struct Database {
-    pub conn: Mutex<Connection>,
+/// How long a statement waits for another connection's write lock before failing with
+/// "database is locked" (syncs and live mirroring write through their own connections)
+pub(crate) const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Upper bound on open connections; WAL allows concurrent readers, and writers queue on
+/// the busy timeout instead of on a single app-wide lock
+const POOL_MAX_SIZE: u32 = 8;
+
+/// How long a command waits for a free connection before giving up
+const POOL_CONNECTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Opens pooled connections through `Database::open_connection` so every one of them
+/// carries the encryption key and the app's pragmas
+#[derive(Debug)]
+pub struct ConnectionManager {
+    db_path: PathBuf,
 }
 
-impl Database {
-    pub fn new(db_path: &str) -> Result<Self> {
-        let conn = Connection::open(db_path)?;
+impl r2d2::ManageConnection for ConnectionManager {
+    type Connection = Connection;
+    type Error = rusqlite::Error;
 
-        // Enable foreign keys
-        conn.execute("PRAGMA foreign_keys = ON", [])?;
+    fn connect(&self) -> Result<Connection> {
+        Database::open_connection(&self.db_path)
+    }
 
-        // Enable WAL mode for better concurrency
-        conn.pragma_update(None, "journal_mode", "WAL")?;
+    fn is_valid(&self, conn: &mut Connection) -> Result<()> {
+        conn.execute_batch("SELECT 1")
+    }
+
+    fn has_broken(&self, _conn: &mut Connection) -> bool {
+        false
+    }
+}
+
+/// Shared handle to the journal database. Cloning is cheap and shares the pool, so
+/// background tasks can hold their own copy.
+#[derive(Clone)]
+pub struct Database {
+    pub pool: r2d2::Pool<ConnectionManager>,
+}
+
+impl Database {
+    pub fn new(db_path: &str) -> std::result::Result<Self, String> {
+        // Migrate on a dedicated connection first so a bad key or corrupt file fails
+        // immediately instead of after the pool's connection timeout
+        let conn = Self::open_connection(db_path).map_err(|e| e.to_string())?;
 
         // Run new migration system
         let runner = MigrationRunner::new();
@@ -24,11 +60,13 @@ impl Database {
         log::info!("=== Starting database migration check ===");
 
         // Get current version
-        let current_version = runner.get_current_version(&conn)?;
+        let current_version = runner.get_current_version(&conn).map_err(|e| e.to_string())?;
         log::info!("Current schema version: {:?}", current_version);
 
         // Run pending migrations
-        let applied = runner.run_pending_migrations(&conn, db_path)?;
+        let applied = runner
+            .run_pending_migrations(&conn, db_path)
+            .map_err(|e| e.to_string())?;
 
         if applied > 0 {
             log::info!("✅ Applied {} migrations successfully", applied);
@@ -37,17 +75,91 @@ impl Database {
         }
 
         // Verify migration integrity (checksums)
-        runner.verify_migrations(&conn)?;
+        runner.verify_migrations(&conn).map_err(|e| e.to_string())?;
 
         // Final version check
-        if let Some(version) = runner.get_current_version(&conn)? {
+        if let Some(version) = runner.get_current_version(&conn).map_err(|e| e.to_string())? {
             log::info!("Final schema version: {}", version);
         }
 
         log::info!("=== Migration check complete ===");
 
-        Ok(Database {
-            conn: Mutex::new(conn),
-        })
+        drop(conn);
+        Self::connect(db_path)
+    }
+
+    /// Open a pool on an existing database without running migrations
+    pub fn connect(db_path: impl Into<PathBuf>) -> std::result::Result<Self, String> {
+        let manager = ConnectionManager {
+            db_path: db_path.into(),
+        };
+        let pool = r2d2::Pool::builder()
+            .max_size(POOL_MAX_SIZE)
+            .min_idle(Some(1))
+            .connection_timeout(POOL_CONNECTION_TIMEOUT)
+            .build(manager)
+            .map_err(|e| e.to_string())?;
+
+        Ok(Database { pool })
+    }
+
+    /// Open a connection to the journal database with the app's pragmas. Use this for any
+    /// additional connection to the same file so it shares WAL mode and the busy timeout.
+    pub fn open_connection(db_path: impl AsRef<std::path::Path>) -> Result<Connection> {
+        let conn = Connection::open(db_path)?;
+
+        // Must come first on an encrypted database
+        encryption::apply_key(&conn)?;
+
+        // Enable foreign keys
+        conn.execute("PRAGMA foreign_keys = ON", [])?;
+
+        // WAL lets readers run alongside a writer; NORMAL sync is safe in WAL mode and
+        // avoids an fsync on every commit
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+
+        // Zero deleted content so purged trades don't linger in free pages. Set on every
+        // connection: toggling it around a purge would leak into pooled connections.
+        conn.pragma_update(None, "secure_delete", true)?;
+
+        // Wait for competing writers instead of failing immediately
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+
+        Ok(conn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_serves_concurrent_connections() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("pool.db");
+        let db = Database::new(db_path.to_str().unwrap()).unwrap();
+
+        // A long-held connection (e.g. an import) must not block another command
+        let writer = db.pool.get().unwrap();
+        writer
+            .execute("UPDATE settings SET initial_capital = 1234 WHERE id = 1", [])
+            .unwrap();
+
+        let reader = db.pool.get().unwrap();
+        let capital: f64 = reader
+            .query_row("SELECT initial_capital FROM settings WHERE id = 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(capital, 1234.0);
+
+        let foreign_keys: i32 = reader
+            .query_row("PRAGMA foreign_keys", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(foreign_keys, 1);
+
+        let secure_delete: i32 = reader
+            .query_row("PRAGMA secure_delete", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(secure_delete, 1);
     }
 }
@@ -0,0 +1,197 @@
+//! Opt-in SQLCipher encryption of the journal database (cargo feature `sqlcipher`).
+//!
+//! Enabling encryption stores the key in secure storage and leaves a marker next to the
+//! database. The plaintext file is encrypted at the next startup, before any connection
+//! is open, so nothing written between enabling and restarting is lost.
+
+use crate::api::credentials;
+use crate::commands::error::CommandError;
+use rusqlite::Connection;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Key of the open database, set once at startup when the file is encrypted
+static DATABASE_KEY: OnceLock<String> = OnceLock::new();
+
+/// Every plaintext SQLite file starts with this header, SQLCipher files look random
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Whether this build links SQLCipher
+pub fn is_supported() -> bool {
+    cfg!(feature = "sqlcipher")
+}
+
+/// Whether the database opened at startup is encrypted
+pub fn is_active() -> bool {
+    DATABASE_KEY.get().is_some()
+}
+
+/// True for an existing file that is not a plaintext SQLite database.
+/// Missing or empty files count as plaintext (a new database).
+pub fn is_encrypted_file(path: &Path) -> bool {
+    let mut header = [0u8; 16];
+    match std::fs::File::open(path).and_then(|mut file| file.read_exact(&mut header)) {
+        Ok(()) => &header != SQLITE_HEADER,
+        Err(_) => false,
+    }
+}
+
+/// Whether encryption was requested and runs at the next startup
+pub fn is_pending(db_path: &Path) -> bool {
+    pending_marker(db_path).exists()
+}
+
+/// Apply the database key to a freshly opened connection (no-op for a plaintext database).
+/// Must run before any other statement on the connection.
+pub fn apply_key(conn: &Connection) -> rusqlite::Result<()> {
+    if let Some(key) = DATABASE_KEY.get() {
+        conn.pragma_update(None, "key", key)?;
+    }
+    Ok(())
+}
+
+/// Startup step before the database is opened: run a requested encryption, then load the
+/// key from secure storage if the file is encrypted. Requires initialized secure storage.
+pub fn prepare_database(db_path: &Path) -> Result<(), String> {
+    if is_pending(db_path) {
+        match encrypt_in_place(db_path) {
+            Ok(()) => println!("✓ Database encrypted"),
+            Err(e) => {
+                eprintln!("Warning: Database encryption failed, keeping the plaintext database: {}", e);
+                if let Err(e) = credentials::delete_database_key() {
+                    eprintln!("Warning: Failed to remove unused database key: {}", e);
+                }
+            }
+        }
+        if let Err(e) = std::fs::remove_file(pending_marker(db_path)) {
+            eprintln!("Warning: Failed to remove encryption marker: {}", e);
+        }
+    }
+
+    if !is_encrypted_file(db_path) {
+        return Ok(());
+    }
+    if !is_supported() {
+        return Err("The database is encrypted, but this build has no SQLCipher support (cargo feature \"sqlcipher\")".to_string());
+    }
+
+    let key = credentials::retrieve_database_key()
+        .map_err(|e| format!("The database is encrypted, but its key could not be loaded from secure storage: {}", e))?;
+    DATABASE_KEY
+        .set(key)
+        .map_err(|_| "Database key already loaded".to_string())
+}
+
+/// Request encryption of the plaintext database at the next startup. Without a passphrase
+/// a random key is generated. Either way the key is kept in secure storage so the app can
+/// open the database unattended; the passphrase also opens the file in other SQLCipher tools.
+pub fn schedule_encryption(db_path: &Path, passphrase: Option<String>) -> Result<(), CommandError> {
+    if !is_supported() {
+        return Err(CommandError::Validation(
+            "This build has no SQLCipher support (cargo feature \"sqlcipher\")".to_string(),
+        ));
+    }
+    if is_active() || is_encrypted_file(db_path) {
+        return Err(CommandError::Validation("The database is already encrypted".to_string()));
+    }
+
+    let key = match passphrase {
+        Some(passphrase) if passphrase.chars().count() < MIN_PASSPHRASE_LEN => {
+            return Err(CommandError::Validation(format!(
+                "Passphrase must be at least {} characters",
+                MIN_PASSPHRASE_LEN
+            )));
+        }
+        Some(passphrase) => passphrase,
+        None => {
+            use rand::RngCore;
+            let mut bytes = [0u8; 32];
+            rand::thread_rng().fill_bytes(&mut bytes);
+            bytes.iter().map(|b| format!("{:02x}", b)).collect()
+        }
+    };
+
+    credentials::store_database_key(&key)?;
+    std::fs::write(pending_marker(db_path), b"")?;
+    Ok(())
+}
+
+/// Export the plaintext database into an encrypted copy, verify it and swap it in
+fn encrypt_in_place(db_path: &Path) -> Result<(), String> {
+    if is_encrypted_file(db_path) {
+        return Ok(());
+    }
+    let key = credentials::retrieve_database_key().map_err(|e| e.to_string())?;
+    let encrypted_path = sibling(db_path, ".encrypting");
+    let _ = std::fs::remove_file(&encrypted_path);
+
+    {
+        let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+        conn.execute(
+            "ATTACH DATABASE ?1 AS encrypted KEY ?2",
+            rusqlite::params![encrypted_path.to_string_lossy(), key],
+        )
+        .map_err(|e| e.to_string())?;
+        conn.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))
+            .map_err(|e| format!("sqlcipher_export failed: {}", e))?;
+        conn.execute("DETACH DATABASE encrypted", []).map_err(|e| e.to_string())?;
+    } // closing the last connection checkpoints the WAL into the plaintext file
+
+    {
+        let conn = Connection::open(&encrypted_path).map_err(|e| e.to_string())?;
+        conn.pragma_update(None, "key", &key).map_err(|e| e.to_string())?;
+        let integrity: String = conn
+            .pragma_query_value(None, "integrity_check", |row| row.get(0))
+            .map_err(|e| format!("Encrypted copy is unreadable: {}", e))?;
+        if integrity != "ok" {
+            let _ = std::fs::remove_file(&encrypted_path);
+            return Err(format!("Encrypted copy failed the integrity check: {}", integrity));
+        }
+    }
+
+    // Atomic replace, then drop journal files that belonged to the plaintext database
+    std::fs::rename(&encrypted_path, db_path).map_err(|e| e.to_string())?;
+    for suffix in ["-wal", "-shm"] {
+        let _ = std::fs::remove_file(sibling(db_path, suffix));
+    }
+
+    Ok(())
+}
+
+fn pending_marker(db_path: &Path) -> PathBuf {
+    sibling(db_path, ".encrypt-pending")
+}
+
+/// `trading_journal.db` + suffix, next to the database
+fn sibling(db_path: &Path, suffix: &str) -> PathBuf {
+    let mut name = db_path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_plaintext_header() {
+        let dir = std::env::temp_dir().join(format!("encryption-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let plain = dir.join("plain.db");
+        Connection::open(&plain).unwrap().execute_batch("CREATE TABLE x (id INTEGER)").unwrap();
+        assert!(!is_encrypted_file(&plain));
+
+        let random = dir.join("random.db");
+        std::fs::write(&random, [0x5a_u8; 4096]).unwrap();
+        assert!(is_encrypted_file(&random));
+
+        assert!(!is_encrypted_file(&dir.join("missing.db")));
+        assert_eq!(sibling(&plain, "-wal"), dir.join("plain.db-wal"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
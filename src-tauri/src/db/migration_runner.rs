@@ -1,4 +1,4 @@
-use rusqlite::{params, Connection, OptionalExtension, Result};
+use rusqlite::{params, Batch, Connection, OptionalExtension, Result};
 use sha2::{Digest, Sha256};
 use std::fs;
 use std::path::PathBuf;
@@ -55,6 +55,8 @@ pub struct Migration {
     pub version: u32,
     pub name: &'static str,
     pub sql: &'static str,
+    /// Reverses `sql`; migrations without one can only be undone by restoring a backup
+    pub down: Option<&'static str>,
 }
 
 impl Migration {
@@ -65,7 +67,17 @@ impl Migration {
     }
 
     pub fn new(version: u32, name: &'static str, sql: &'static str) -> Self {
-        Self { version, name, sql }
+        Self {
+            version,
+            name,
+            sql,
+            down: None,
+        }
+    }
+
+    pub fn with_down(mut self, down: &'static str) -> Self {
+        self.down = Some(down);
+        self
     }
 }
 
@@ -132,9 +144,224 @@ impl MigrationRunner {
                 "ensure_execution_columns",
                 include_str!("migrations/009_ensure_execution_columns.sql"),
             ),
+            Migration::new(
+                10,
+                "add_stats_cache",
+                include_str!("migrations/010_add_stats_cache.sql"),
+            )
+            .with_down(include_str!("migrations/010_add_stats_cache.down.sql")),
+            Migration::new(
+                11,
+                "add_risk_rules",
+                include_str!("migrations/011_add_risk_rules.sql"),
+            )
+            .with_down(include_str!("migrations/011_add_risk_rules.down.sql")),
+            Migration::new(
+                12,
+                "add_capital_transactions",
+                include_str!("migrations/012_add_capital_transactions.sql"),
+            )
+            .with_down(include_str!("migrations/012_add_capital_transactions.down.sql")),
+            Migration::new(
+                13,
+                "add_tags",
+                include_str!("migrations/013_add_tags.sql"),
+            )
+            .with_down(include_str!("migrations/013_add_tags.down.sql")),
+            Migration::new(
+                14,
+                "add_journal_entries",
+                include_str!("migrations/014_add_journal_entries.sql"),
+            )
+            .with_down(include_str!("migrations/014_add_journal_entries.down.sql")),
+            Migration::new(
+                15,
+                "add_accounts",
+                include_str!("migrations/015_add_accounts.sql"),
+            )
+            .with_down(include_str!("migrations/015_add_accounts.down.sql")),
+            Migration::new(
+                16,
+                "add_trash_retention",
+                include_str!("migrations/016_add_trash_retention.sql"),
+            )
+            .with_down(include_str!("migrations/016_add_trash_retention.down.sql")),
+            Migration::new(
+                17,
+                "add_trade_revisions",
+                include_str!("migrations/017_add_trade_revisions.sql"),
+            )
+            .with_down(include_str!("migrations/017_add_trade_revisions.down.sql")),
+            Migration::new(
+                18,
+                "add_hot_query_indices",
+                include_str!("migrations/018_add_hot_query_indices.sql"),
+            )
+            .with_down(include_str!("migrations/018_add_hot_query_indices.down.sql")),
+            Migration::new(
+                19,
+                "add_funding_payments",
+                include_str!("migrations/019_add_funding_payments.sql"),
+            )
+            .with_down(include_str!("migrations/019_add_funding_payments.down.sql")),
+            Migration::new(
+                20,
+                "add_planned_trades",
+                include_str!("migrations/020_add_planned_trades.sql"),
+            )
+            .with_down(include_str!("migrations/020_add_planned_trades.down.sql")),
+            Migration::new(
+                21,
+                "add_trade_grade",
+                include_str!("migrations/021_add_trade_grade.sql"),
+            )
+            .with_down(include_str!("migrations/021_add_trade_grade.down.sql")),
+            Migration::new(
+                22,
+                "add_trade_emotions",
+                include_str!("migrations/022_add_trade_emotions.sql"),
+            )
+            .with_down(include_str!("migrations/022_add_trade_emotions.down.sql")),
+            Migration::new(
+                23,
+                "add_auto_sync_lookback",
+                include_str!("migrations/023_add_auto_sync_lookback.sql"),
+            )
+            .with_down(include_str!("migrations/023_add_auto_sync_lookback.down.sql")),
+            Migration::new(
+                24,
+                "add_sync_symbol_filters",
+                include_str!("migrations/024_add_sync_symbol_filters.sql"),
+            )
+            .with_down(include_str!("migrations/024_add_sync_symbol_filters.down.sql")),
+            Migration::new(
+                25,
+                "add_sync_history_retention",
+                include_str!("migrations/025_add_sync_history_retention.sql"),
+            )
+            .with_down(include_str!("migrations/025_add_sync_history_retention.down.sql")),
+            Migration::new(
+                26,
+                "add_sync_on_startup",
+                include_str!("migrations/026_add_sync_on_startup.sql"),
+            )
+            .with_down(include_str!("migrations/026_add_sync_on_startup.down.sql")),
+            Migration::new(
+                27,
+                "add_sync_circuit_breaker",
+                include_str!("migrations/027_add_sync_circuit_breaker.sql"),
+            )
+            .with_down(include_str!("migrations/027_add_sync_circuit_breaker.down.sql")),
+            Migration::new(
+                28,
+                "add_live_mirror_events",
+                include_str!("migrations/028_add_live_mirror_events.sql"),
+            )
+            .with_down(include_str!("migrations/028_add_live_mirror_events.down.sql")),
+            Migration::new(
+                29,
+                "add_trade_liquidated",
+                include_str!("migrations/029_add_trade_liquidated.sql"),
+            )
+            .with_down(include_str!("migrations/029_add_trade_liquidated.down.sql")),
+            Migration::new(
+                30,
+                "add_mirror_stale_timeout",
+                include_str!("migrations/030_add_mirror_stale_timeout.sql"),
+            )
+            .with_down(include_str!("migrations/030_add_mirror_stale_timeout.down.sql")),
+            Migration::new(
+                31,
+                "add_price_alerts",
+                include_str!("migrations/031_add_price_alerts.sql"),
+            )
+            .with_down(include_str!("migrations/031_add_price_alerts.down.sql")),
+            Migration::new(
+                32,
+                "add_live_mirror_inflight",
+                include_str!("migrations/032_add_live_mirror_inflight.sql"),
+            )
+            .with_down(include_str!("migrations/032_add_live_mirror_inflight.down.sql")),
+            Migration::new(
+                33,
+                "add_live_mirror_positions",
+                include_str!("migrations/033_add_live_mirror_positions.sql"),
+            )
+            .with_down(include_str!("migrations/033_add_live_mirror_positions.down.sql")),
+            Migration::new(
+                34,
+                "add_app_lock",
+                include_str!("migrations/034_add_app_lock.sql"),
+            )
+            .with_down(include_str!("migrations/034_add_app_lock.down.sql")),
+            Migration::new(
+                35,
+                "add_default_credential",
+                include_str!("migrations/035_add_default_credential.sql"),
+            )
+            .with_down(include_str!("migrations/035_add_default_credential.down.sql")),
+            Migration::new(
+                36,
+                "add_biometric_unlock",
+                include_str!("migrations/036_add_biometric_unlock.sql"),
+            )
+            .with_down(include_str!("migrations/036_add_biometric_unlock.down.sql")),
+            Migration::new(
+                37,
+                "add_credential_access_log",
+                include_str!("migrations/037_add_credential_access_log.sql"),
+            )
+            .with_down(include_str!("migrations/037_add_credential_access_log.down.sql")),
+            Migration::new(
+                38,
+                "add_public_ip_history",
+                include_str!("migrations/038_add_public_ip_history.sql"),
+            )
+            .with_down(include_str!("migrations/038_add_public_ip_history.down.sql")),
+            Migration::new(
+                39,
+                "add_credential_storage_backend",
+                include_str!("migrations/039_add_credential_storage_backend.sql"),
+            )
+            .with_down(include_str!("migrations/039_add_credential_storage_backend.down.sql")),
+            Migration::new(
+                40,
+                "add_credential_rotations",
+                include_str!("migrations/040_add_credential_rotations.sql"),
+            )
+            .with_down(include_str!("migrations/040_add_credential_rotations.down.sql")),
+            Migration::new(
+                41,
+                "add_proxy_url",
+                include_str!("migrations/041_add_proxy_url.sql"),
+            )
+            .with_down(include_str!("migrations/041_add_proxy_url.down.sql")),
+            Migration::new(
+                42,
+                "add_credential_environment",
+                include_str!("migrations/042_add_credential_environment.sql"),
+            )
+            .with_down(include_str!("migrations/042_add_credential_environment.down.sql")),
+            Migration::new(
+                43,
+                "add_trade_links",
+                include_str!("migrations/043_add_trade_links.sql"),
+            )
+            .with_down(include_str!("migrations/043_add_trade_links.down.sql")),
+            Migration::new(
+                44,
+                "add_breakeven_threshold",
+                include_str!("migrations/044_add_breakeven_threshold.sql"),
+            )
+            .with_down(include_str!("migrations/044_add_breakeven_threshold.down.sql")),
         ]
     }
 
+    /// Highest schema version this build knows how to migrate to
+    pub fn latest_version(&self) -> u32 {
+        self.migrations.iter().map(|m| m.version).max().unwrap_or(0)
+    }
+
     pub fn run_pending_migrations(&self, conn: &Connection, db_path: &str) -> Result<usize> {
         // Check if this is a legacy database
         if !self.has_schema_migrations_table(conn)? {
@@ -166,7 +393,8 @@ impl MigrationRunner {
         let target_version = pending.last().unwrap().version;
 
         // Create backup before applying migrations
-        let backup_path = self.create_backup(db_path, target_version)?;
+        let backup_path =
+            self.create_backup(db_path, &format!("pre_migration_v{}", target_version))?;
         log_info!("Backup created: {}", backup_path.display());
 
         // Apply each migration
@@ -202,27 +430,7 @@ impl MigrationRunner {
         // Execute migration SQL - run each statement individually so we can
         // gracefully skip "duplicate column" errors (ALTER TABLE ADD COLUMN
         // on columns that already exist from a prior manual addition).
-        for raw_stmt in migration.sql.split(';') {
-            let stmt = raw_stmt.trim();
-            if stmt.is_empty() {
-                continue;
-            }
-            match tx.execute_batch(stmt) {
-                Ok(_) => {}
-                Err(e) => {
-                    let msg = e.to_string().to_lowercase();
-                    if msg.contains("duplicate column name") || msg.contains("already has a column") {
-                        log_warn!(
-                            "Skipping already-existing column in migration '{}': {}",
-                            migration.name,
-                            e
-                        );
-                    } else {
-                        return Err(e);
-                    }
-                }
-            }
-        }
+        execute_migration_sql(&tx, migration)?;
 
         // Record migration
         let now = current_timestamp();
@@ -255,6 +463,85 @@ impl MigrationRunner {
         Ok(())
     }
 
+    /// Undo applied migrations down to `target_version` using their `down` SQL, newest
+    /// first. A backup is taken before anything is reverted. Fails without changes if a
+    /// migration in the range has no `down`, in which case the pre-migration backup for
+    /// that release has to be restored instead.
+    pub fn rollback_to(&self, conn: &Connection, db_path: &str, target_version: u32) -> Result<usize> {
+        let current_version = match self.get_current_version(conn)? {
+            Some(v) if v > target_version => v,
+            _ => return Ok(0),
+        };
+
+        if current_version > self.latest_version() {
+            log_error!(
+                "Schema version {} is newer than this build ({})",
+                current_version,
+                self.latest_version()
+            );
+            return Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(format!(
+                    "Schema version {} is newer than this build knows how to roll back",
+                    current_version
+                )),
+            ));
+        }
+
+        let mut to_revert: Vec<&Migration> = self
+            .migrations
+            .iter()
+            .filter(|m| m.version > target_version && m.version <= current_version)
+            .collect();
+        to_revert.sort_by_key(|m| std::cmp::Reverse(m.version));
+
+        if let Some(missing) = to_revert.iter().find(|m| m.down.is_none()) {
+            log_error!(
+                "Migration {} ({}) has no down migration - restore a pre-migration backup instead",
+                missing.version,
+                missing.name
+            );
+            return Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(format!(
+                    "Migration {} ({}) cannot be rolled back, restore a backup instead",
+                    missing.version, missing.name
+                )),
+            ));
+        }
+
+        let backup_path =
+            self.create_backup(db_path, &format!("pre_rollback_v{}", current_version))?;
+        log_info!("Backup created: {}", backup_path.display());
+
+        let mut reverted = 0;
+        for migration in to_revert {
+            if let Err(e) = self.revert_migration(conn, migration) {
+                log_error!("❌ Rollback of migration {} failed: {}", migration.version, e);
+                log_error!("Backup available at: {}", backup_path.display());
+                return Err(e);
+            }
+            reverted += 1;
+            log_info!("↩️ Reverted migration {}: {}", migration.version, migration.name);
+        }
+
+        Ok(reverted)
+    }
+
+    fn revert_migration(&self, conn: &Connection, migration: &Migration) -> Result<()> {
+        let down = migration.down.ok_or(rusqlite::Error::InvalidQuery)?;
+
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(down)?;
+        tx.execute(
+            "DELETE FROM schema_migrations WHERE version = ?",
+            params![migration.version],
+        )?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
     pub fn verify_migrations(&self, conn: &Connection) -> Result<()> {
         // Get applied migrations
         let mut stmt = conn.prepare(
@@ -309,7 +596,7 @@ impl MigrationRunner {
         Ok(version)
     }
 
-    fn create_backup(&self, db_path: &str, target_version: u32) -> Result<PathBuf> {
+    fn create_backup(&self, db_path: &str, label: &str) -> Result<PathBuf> {
         // Get backup directory
         let db_path_buf = PathBuf::from(db_path);
         let db_dir = db_path_buf.parent().ok_or_else(|| {
@@ -329,12 +616,15 @@ impl MigrationRunner {
 
         // Generate backup filename
         let timestamp = current_timestamp();
-        let backup_name = format!("pre_migration_v{}_{}.db", target_version, timestamp);
+        let backup_name = format!("{}_{}.db", label, timestamp);
         let backup_path = backup_dir.join(&backup_name);
 
         // Create backup using rusqlite's backup API
+        // Backups of an encrypted database are encrypted with the same key
         let src = Connection::open(db_path)?;
+        crate::db::encryption::apply_key(&src)?;
         let mut dst = Connection::open(&backup_path)?;
+        crate::db::encryption::apply_key(&dst)?;
 
         {
             let backup = rusqlite::backup::Backup::new(&src, &mut dst)?;
@@ -531,6 +821,62 @@ impl MigrationRunner {
     }
 }
 
+/// Run migration SQL one statement at a time. SQLite's own parser (through `Batch`) finds
+/// where each statement ends, so semicolons in comments, string literals and trigger bodies
+/// are never split on.
+fn execute_migration_sql(conn: &Connection, migration: &Migration) -> Result<()> {
+    let mut rest = migration.sql;
+
+    loop {
+        let mut stmt = match Batch::new(conn, rest).next() {
+            Ok(Some(stmt)) => stmt,
+            Ok(None) => return Ok(()),
+            Err(e) if is_duplicate_column(&e) => {
+                // A statement SQLite refuses to prepare has no known end, but an ADD COLUMN
+                // has no semicolons of its own: resume after the next one
+                let Some(skipped) = skip_add_column(rest) else {
+                    return Err(e);
+                };
+                log_warn!(
+                    "Skipping already-existing column in migration '{}': {}",
+                    migration.name,
+                    e
+                );
+                rest = skipped;
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        // The prepared text runs from the statement's leading comments to its semicolon
+        let text = stmt.expanded_sql().unwrap_or_default();
+        let end = rest.find(&text).map(|start| start + text.len()).unwrap_or(rest.len());
+
+        let mut rows = stmt.raw_query();
+        while rows.next()?.is_some() {}
+
+        rest = &rest[end..];
+    }
+}
+
+fn is_duplicate_column(e: &rusqlite::Error) -> bool {
+    let msg = e.to_string().to_lowercase();
+    msg.contains("duplicate column name") || msg.contains("already has a column")
+}
+
+/// The SQL after the `ALTER TABLE ... ADD COLUMN` statement at the start of `sql` (past
+/// any comment lines before it); None if that isn't what comes next
+fn skip_add_column(sql: &str) -> Option<&str> {
+    let mut statement = sql.trim_start();
+    while let Some(comment) = statement.strip_prefix("--") {
+        statement = comment.split_once('\n').map_or("", |(_, after)| after).trim_start();
+    }
+    let end = statement.find(';').map(|i| i + 1).unwrap_or(statement.len());
+
+    let upper = statement[..end].to_uppercase();
+    (upper.starts_with("ALTER TABLE") && upper.contains("ADD")).then(|| &statement[end..])
+}
+
 fn current_timestamp() -> i64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -566,6 +912,100 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_rollback_and_reapply() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("rollback.db");
+        let db_path = db_path.to_str().unwrap();
+        let conn = Connection::open(db_path).unwrap();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+
+        let runner = MigrationRunner::new();
+        runner.run_pending_migrations(&conn, db_path).unwrap();
+        let latest = runner.latest_version();
+
+        let reverted = runner.rollback_to(&conn, db_path, 9).unwrap();
+        assert_eq!(reverted as u32, latest - 9);
+        assert_eq!(runner.get_current_version(&conn).unwrap(), Some(9));
+        assert!(!runner.column_exists(&conn, "trades", "account_id").unwrap());
+        let tables: i32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE name IN ('stats_cache', 'tags', 'accounts')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(tables, 0);
+
+        // Migration 9 has no down SQL, so nothing below it can be reverted
+        assert!(runner.rollback_to(&conn, db_path, 8).is_err());
+        assert_eq!(runner.get_current_version(&conn).unwrap(), Some(9));
+
+        // The down migrations leave a schema the up migrations apply to cleanly
+        let reapplied = runner.run_pending_migrations(&conn, db_path).unwrap();
+        assert_eq!(reapplied as u32, latest - 9);
+        assert!(runner.verify_migrations(&conn).is_ok());
+
+        let backups = fs::read_dir(dir.path().join("backups"))
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with("pre_rollback_"))
+            .count();
+        assert_eq!(backups, 1);
+    }
+
+    #[test]
+    fn test_hot_queries_use_indices() {
+        let conn = Connection::open_in_memory().unwrap();
+        let runner = MigrationRunner::new();
+        runner.run_pending_migrations(&conn, ":memory:").unwrap();
+
+        let plan = |sql: &str| -> String {
+            let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {}", sql)).unwrap();
+            stmt.query_map([], |row| row.get::<_, String>(3))
+                .unwrap()
+                .collect::<Result<Vec<_>>>()
+                .unwrap()
+                .join(" | ")
+        };
+
+        for sql in [
+            "SELECT id FROM trades WHERE import_fingerprint = 'fp'",
+            "SELECT id FROM trades WHERE import_fingerprint >= 'fp|' AND import_fingerprint < 'fp|' || char(1114111)",
+            "SELECT id FROM trades WHERE deleted_at IS NULL AND status = 'OPEN'",
+        ] {
+            let plan = plan(sql);
+            assert!(plan.contains("USING INDEX") || plan.contains("USING COVERING INDEX"), "{} -> {}", sql, plan);
+        }
+    }
+
+    #[test]
+    fn test_migration_sql_parsed_by_sqlite() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE a (x INTEGER, note TEXT);").unwrap();
+
+        let migration = Migration::new(
+            999,
+            "parse_check",
+            "-- comment; with a semicolon
+            ALTER TABLE a ADD COLUMN x INTEGER;
+            ALTER TABLE a ADD COLUMN y INTEGER; -- trailing; comment
+            CREATE TRIGGER t AFTER INSERT ON a
+            BEGIN
+                UPDATE a SET x = x + 1;
+                UPDATE a SET y = 2;
+            END;
+            INSERT INTO a (x, note) VALUES (1, 'a; b -- not a comment');",
+        );
+        execute_migration_sql(&conn, &migration).unwrap();
+
+        // The duplicate x was skipped, the trigger body kept whole and the literal untouched
+        let (x, y, note): (i64, i64, String) = conn
+            .query_row("SELECT x, y, note FROM a", [], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap();
+        assert_eq!((x, y, note.as_str()), (2, 2, "a; b -- not a comment"));
+    }
+
     #[test]
     fn test_fresh_install() {
         let conn = Connection::open_in_memory().unwrap();
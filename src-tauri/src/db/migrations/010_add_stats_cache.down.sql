@@ -0,0 +1,9 @@
+-- Rollback of migration 010: Materialized stats cache
+
+DROP TRIGGER IF EXISTS trg_trades_insert_stats_version;
+DROP TRIGGER IF EXISTS trg_trades_update_stats_version;
+DROP TRIGGER IF EXISTS trg_trades_delete_stats_version;
+DROP TRIGGER IF EXISTS trg_settings_update_stats_version;
+
+DROP TABLE IF EXISTS stats_data_version;
+DROP TABLE IF EXISTS stats_cache;
@@ -0,0 +1,41 @@
+-- Migration 010: Materialized stats cache
+-- Reason: Analytics screens re-ran a dozen full scans of trades on every navigation.
+--         Results are cached per command/arguments and invalidated through a data
+--         version bumped by triggers, so every writer (imports, sync, live mirror) is covered.
+-- Date: 2026-10-17
+-- Breaking: No
+
+CREATE TABLE IF NOT EXISTS stats_cache (
+    cache_key TEXT PRIMARY KEY,
+    data_version INTEGER NOT NULL,
+    payload TEXT NOT NULL,
+    computed_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS stats_data_version (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    version INTEGER NOT NULL DEFAULT 0
+);
+
+INSERT OR IGNORE INTO stats_data_version (id, version) VALUES (1, 0);
+
+CREATE TRIGGER IF NOT EXISTS trg_trades_insert_stats_version AFTER INSERT ON trades
+BEGIN
+    UPDATE stats_data_version SET version = version + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_trades_update_stats_version AFTER UPDATE ON trades
+BEGIN
+    UPDATE stats_data_version SET version = version + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_trades_delete_stats_version AFTER DELETE ON trades
+BEGIN
+    UPDATE stats_data_version SET version = version + 1 WHERE id = 1;
+END;
+
+-- initial_capital and current_r_percent feed into equity-based stats
+CREATE TRIGGER IF NOT EXISTS trg_settings_update_stats_version AFTER UPDATE ON settings
+BEGIN
+    UPDATE stats_data_version SET version = version + 1 WHERE id = 1;
+END;
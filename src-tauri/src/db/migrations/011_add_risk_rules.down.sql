@@ -0,0 +1,4 @@
+-- Rollback of migration 011: Risk throttle rules
+
+ALTER TABLE settings DROP COLUMN daily_loss_limit_r;
+ALTER TABLE settings DROP COLUMN max_consecutive_losses;
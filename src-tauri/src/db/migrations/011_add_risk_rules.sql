@@ -0,0 +1,10 @@
+-- Migration 011: Risk throttle rules
+-- Reason: get_risk_alerts needs persisted loss-streak and daily loss limits
+-- Date: 2026-10-17
+-- Breaking: No
+
+-- Consecutive closed losses before advising reduced size (0 disables the rule)
+ALTER TABLE settings ADD COLUMN max_consecutive_losses INTEGER NOT NULL DEFAULT 3;
+
+-- Realized loss in R within one day before advising to stop (0 disables the rule)
+ALTER TABLE settings ADD COLUMN daily_loss_limit_r REAL NOT NULL DEFAULT 3.0;
@@ -0,0 +1,9 @@
+-- Rollback of migration 012: Deposits/withdrawals ledger
+-- Recorded deposits and withdrawals are lost
+
+DROP TRIGGER IF EXISTS trg_capital_insert_stats_version;
+DROP TRIGGER IF EXISTS trg_capital_update_stats_version;
+DROP TRIGGER IF EXISTS trg_capital_delete_stats_version;
+
+DROP INDEX IF EXISTS idx_capital_transactions_date;
+DROP TABLE IF EXISTS capital_transactions;
@@ -0,0 +1,32 @@
+-- Migration 012: Deposits/withdrawals ledger
+-- Reason: Portfolio value was a static setting, and capital changes over time must not
+--         show up as trading returns on the equity curve
+-- Date: 2026-10-17
+-- Breaking: No
+
+CREATE TABLE IF NOT EXISTS capital_transactions (
+    id TEXT PRIMARY KEY,
+    transaction_type TEXT NOT NULL CHECK (transaction_type IN ('DEPOSIT', 'WITHDRAWAL')),
+    amount REAL NOT NULL CHECK (amount > 0),
+    transaction_date INTEGER NOT NULL,
+    notes TEXT,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_capital_transactions_date ON capital_transactions(transaction_date);
+
+-- Capital changes feed into the equity curve, so they invalidate the stats cache too
+CREATE TRIGGER IF NOT EXISTS trg_capital_insert_stats_version AFTER INSERT ON capital_transactions
+BEGIN
+    UPDATE stats_data_version SET version = version + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_capital_update_stats_version AFTER UPDATE ON capital_transactions
+BEGIN
+    UPDATE stats_data_version SET version = version + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_capital_delete_stats_version AFTER DELETE ON capital_transactions
+BEGIN
+    UPDATE stats_data_version SET version = version + 1 WHERE id = 1;
+END;
@@ -0,0 +1,10 @@
+-- Rollback of migration 013: Trade tags
+-- Tags and their assignments are lost
+
+DROP TRIGGER IF EXISTS trg_trade_tags_insert_stats_version;
+DROP TRIGGER IF EXISTS trg_trade_tags_delete_stats_version;
+DROP TRIGGER IF EXISTS trg_tags_update_stats_version;
+
+DROP INDEX IF EXISTS idx_trade_tags_tag;
+DROP TABLE IF EXISTS trade_tags;
+DROP TABLE IF EXISTS tags;
@@ -0,0 +1,38 @@
+-- Migration 013: Trade tags
+-- Reason: Categorize setups ("breakout", "news", "revenge") and analyze them
+-- Date: 2026-10-17
+-- Breaking: No
+
+CREATE TABLE IF NOT EXISTS tags (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    color TEXT,
+    created_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS trade_tags (
+    trade_id TEXT NOT NULL,
+    tag_id TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (trade_id, tag_id),
+    FOREIGN KEY (trade_id) REFERENCES trades(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_trade_tags_tag ON trade_tags(tag_id);
+
+-- Tag assignments feed into get_tag_stats, so they invalidate the stats cache too
+CREATE TRIGGER IF NOT EXISTS trg_trade_tags_insert_stats_version AFTER INSERT ON trade_tags
+BEGIN
+    UPDATE stats_data_version SET version = version + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_trade_tags_delete_stats_version AFTER DELETE ON trade_tags
+BEGIN
+    UPDATE stats_data_version SET version = version + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_tags_update_stats_version AFTER UPDATE ON tags
+BEGIN
+    UPDATE stats_data_version SET version = version + 1 WHERE id = 1;
+END;
@@ -0,0 +1,6 @@
+-- Rollback of migration 014: Daily journal entries
+-- Journal entries are lost
+
+DROP INDEX IF EXISTS idx_journal_entry_trades_trade;
+DROP TABLE IF EXISTS journal_entry_trades;
+DROP TABLE IF EXISTS journal_entries;
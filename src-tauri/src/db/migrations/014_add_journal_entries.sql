@@ -0,0 +1,24 @@
+-- Migration 014: Daily journal entries
+-- Reason: Journal the trading day (plan, review, mood), not just individual trades
+-- Date: 2026-10-17
+-- Breaking: No
+
+CREATE TABLE IF NOT EXISTS journal_entries (
+    id TEXT PRIMARY KEY,
+    entry_date TEXT NOT NULL UNIQUE, -- YYYY-MM-DD in the user's local time
+    pre_market_plan TEXT,
+    post_market_review TEXT,
+    mood INTEGER CHECK (mood BETWEEN 1 AND 5),
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS journal_entry_trades (
+    entry_id TEXT NOT NULL,
+    trade_id TEXT NOT NULL,
+    PRIMARY KEY (entry_id, trade_id),
+    FOREIGN KEY (entry_id) REFERENCES journal_entries(id) ON DELETE CASCADE,
+    FOREIGN KEY (trade_id) REFERENCES trades(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_journal_entry_trades_trade ON journal_entry_trades(trade_id);
@@ -0,0 +1,11 @@
+-- Rollback of migration 015: Multiple accounts/portfolios
+-- Trades and capital transactions fall back to the global settings
+
+DROP TRIGGER IF EXISTS trg_accounts_update_stats_version;
+
+ALTER TABLE capital_transactions DROP COLUMN account_id;
+
+DROP INDEX IF EXISTS idx_trades_account;
+ALTER TABLE trades DROP COLUMN account_id;
+
+DROP TABLE IF EXISTS accounts;
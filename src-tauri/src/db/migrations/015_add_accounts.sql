@@ -0,0 +1,29 @@
+-- Migration 015: Multiple accounts/portfolios
+-- Reason: Keep exchange accounts (and paper trading) separate instead of pooled.
+--         Trades and capital transactions without an account keep using the global settings.
+-- Date: 2026-10-17
+-- Breaking: No
+
+CREATE TABLE IF NOT EXISTS accounts (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    exchange TEXT,
+    is_paper INTEGER NOT NULL DEFAULT 0,
+    initial_capital REAL NOT NULL DEFAULT 10000.0,
+    current_r_percent REAL NOT NULL DEFAULT 0.02,
+    currency TEXT NOT NULL DEFAULT 'USD',
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+ALTER TABLE trades ADD COLUMN account_id TEXT REFERENCES accounts(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_trades_account ON trades(account_id);
+
+ALTER TABLE capital_transactions ADD COLUMN account_id TEXT REFERENCES accounts(id) ON DELETE SET NULL;
+
+-- Per-account capital settings feed into equity-based stats
+CREATE TRIGGER IF NOT EXISTS trg_accounts_update_stats_version AFTER UPDATE ON accounts
+BEGIN
+    UPDATE stats_data_version SET version = version + 1 WHERE id = 1;
+END;
@@ -0,0 +1,5 @@
+-- Rollback of migration 016: Trash retention
+
+DROP INDEX IF EXISTS idx_trades_deleted_at;
+
+ALTER TABLE settings DROP COLUMN trash_retention_days;
@@ -0,0 +1,9 @@
+-- Migration 016: Trash retention
+-- Reason: Soft-deleted trades stayed in the trash forever. Trades deleted longer
+--         than trash_retention_days ago are purged at startup (0 keeps them forever).
+-- Date: 2026-10-17
+-- Breaking: No
+
+ALTER TABLE settings ADD COLUMN trash_retention_days INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_trades_deleted_at ON trades(deleted_at);
@@ -0,0 +1,7 @@
+-- Rollback of migration 017: Trade revision history
+-- Recorded revisions are lost
+
+DROP TRIGGER IF EXISTS trg_trades_update_revision;
+
+DROP INDEX IF EXISTS idx_trade_revisions_trade;
+DROP TABLE IF EXISTS trade_revisions;
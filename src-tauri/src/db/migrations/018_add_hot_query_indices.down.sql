@@ -0,0 +1,5 @@
+-- Rollback of migration 018: Indices for hot queries
+-- The migration 001 indices are left in place
+
+CREATE INDEX IF NOT EXISTS idx_trades_deleted_at ON trades(deleted_at);
+DROP INDEX IF EXISTS idx_trades_deleted_status;
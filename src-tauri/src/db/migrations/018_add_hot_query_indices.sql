@@ -0,0 +1,15 @@
+-- Migration 018: Indices for hot queries
+-- Reason: Databases bootstrapped from a legacy schema never ran migration 001, so they
+--         can lack its trade indices, and every list filters on deleted_at and status.
+--         Imports look up fingerprints per row, which is a full scan without an index.
+-- Date: 2026-10-17
+-- Breaking: No
+
+-- Same names as migration 001, so these are no-ops where they already exist
+CREATE INDEX IF NOT EXISTS idx_trades_import_fingerprint ON trades(import_fingerprint);
+CREATE INDEX IF NOT EXISTS idx_trades_close_date ON trades(close_date DESC);
+CREATE INDEX IF NOT EXISTS idx_trades_trade_date ON trades(trade_date DESC);
+
+-- Covers deleted_at lookups on its own, so it replaces the single-column index
+CREATE INDEX IF NOT EXISTS idx_trades_deleted_status ON trades(deleted_at, status);
+DROP INDEX IF EXISTS idx_trades_deleted_at;
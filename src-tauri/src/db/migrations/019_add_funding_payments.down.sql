@@ -0,0 +1,10 @@
+-- Rollback of migration 019: Funding payments
+-- Synced funding payments are lost
+
+DROP TRIGGER IF EXISTS trg_funding_insert_stats_version;
+DROP TRIGGER IF EXISTS trg_funding_update_stats_version;
+DROP TRIGGER IF EXISTS trg_funding_delete_stats_version;
+
+DROP INDEX IF EXISTS idx_funding_payments_trade;
+DROP INDEX IF EXISTS idx_funding_payments_time;
+DROP TABLE IF EXISTS funding_payments;
@@ -0,0 +1,41 @@
+-- Migration 019: Funding payments
+-- Reason: Perpetual positions pay or receive funding that trade imports ignored, so
+--         net PnL was overstated for long-held positions. Payments are synced from the
+--         exchanges and linked to the trade that was open on the symbol at the time.
+-- Date: 2026-10-17
+-- Breaking: No
+
+CREATE TABLE IF NOT EXISTS funding_payments (
+    id TEXT PRIMARY KEY,
+    credential_id TEXT,
+    exchange TEXT NOT NULL,
+    exchange_bill_id TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    amount REAL NOT NULL, -- Positive when received, negative when paid
+    currency TEXT,
+    funding_time INTEGER NOT NULL, -- Unix seconds
+    trade_id TEXT,
+    created_at INTEGER NOT NULL,
+    UNIQUE (exchange, exchange_bill_id),
+    FOREIGN KEY (credential_id) REFERENCES api_credentials(id) ON DELETE SET NULL,
+    FOREIGN KEY (trade_id) REFERENCES trades(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_funding_payments_trade ON funding_payments(trade_id);
+CREATE INDEX IF NOT EXISTS idx_funding_payments_time ON funding_payments(funding_time);
+
+-- Funding feeds into net PnL stats, so it invalidates the stats cache too
+CREATE TRIGGER IF NOT EXISTS trg_funding_insert_stats_version AFTER INSERT ON funding_payments
+BEGIN
+    UPDATE stats_data_version SET version = version + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_funding_update_stats_version AFTER UPDATE ON funding_payments
+BEGIN
+    UPDATE stats_data_version SET version = version + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_funding_delete_stats_version AFTER DELETE ON funding_payments
+BEGIN
+    UPDATE stats_data_version SET version = version + 1 WHERE id = 1;
+END;
@@ -0,0 +1,5 @@
+-- Rollback of migration 020: Planned trades watchlist
+
+DROP INDEX IF EXISTS idx_planned_trades_converted;
+
+DROP TABLE IF EXISTS planned_trades;
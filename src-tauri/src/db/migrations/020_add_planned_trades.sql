@@ -0,0 +1,35 @@
+-- Migration 020: Planned trades watchlist
+-- Reason: Setups being watched were entered as fake OPEN trades, which skewed stats.
+--         Planned trades hold the plan until it is converted into a real trade.
+-- Date: 2026-10-17
+-- Breaking: No
+
+CREATE TABLE IF NOT EXISTS planned_trades (
+    id TEXT PRIMARY KEY,
+    pair TEXT NOT NULL,
+    exchange TEXT NOT NULL,
+    analysis_date INTEGER NOT NULL,
+    portfolio_value REAL NOT NULL,
+    r_percent REAL NOT NULL,
+    min_rr REAL NOT NULL,
+    planned_pe REAL NOT NULL,
+    planned_sl REAL NOT NULL,
+    leverage INTEGER NOT NULL,
+    planned_tps TEXT NOT NULL,
+    planned_entries TEXT,
+    position_type TEXT NOT NULL CHECK (position_type IN ('LONG', 'SHORT')),
+    one_r REAL NOT NULL,
+    margin REAL NOT NULL,
+    position_size REAL NOT NULL,
+    quantity REAL NOT NULL,
+    planned_weighted_rr REAL NOT NULL,
+    notes TEXT NOT NULL DEFAULT '',
+    account_id TEXT REFERENCES accounts(id) ON DELETE SET NULL,
+    -- Set once the plan has been taken (the trade keeps its own copy of the plan)
+    converted_trade_id TEXT REFERENCES trades(id) ON DELETE SET NULL,
+    converted_at INTEGER,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_planned_trades_converted ON planned_trades(converted_at);
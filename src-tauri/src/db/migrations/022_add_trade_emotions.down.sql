@@ -0,0 +1,53 @@
+-- Rollback of migration 022: Trade emotions
+-- Recorded emotions are lost
+
+DROP TRIGGER IF EXISTS trg_trades_update_revision;
+
+ALTER TABLE trades DROP COLUMN post_emotion;
+ALTER TABLE trades DROP COLUMN pre_emotion;
+
+CREATE TRIGGER IF NOT EXISTS trg_trades_update_revision AFTER UPDATE ON trades
+BEGIN
+    INSERT INTO trade_revisions (trade_id, before_data, after_data, changed_at)
+    VALUES (
+        NEW.id,
+        json_object(
+            'id', OLD.id, 'pair', OLD.pair, 'exchange', OLD.exchange,
+            'analysis_date', OLD.analysis_date, 'trade_date', OLD.trade_date, 'close_date', OLD.close_date,
+            'status', OLD.status, 'portfolio_value', OLD.portfolio_value, 'r_percent', OLD.r_percent,
+            'min_rr', OLD.min_rr, 'planned_pe', OLD.planned_pe, 'planned_sl', OLD.planned_sl,
+            'leverage', OLD.leverage, 'planned_tps', OLD.planned_tps, 'planned_entries', OLD.planned_entries,
+            'position_type', OLD.position_type, 'one_r', OLD.one_r, 'margin', OLD.margin,
+            'position_size', OLD.position_size, 'quantity', OLD.quantity, 'planned_weighted_rr', OLD.planned_weighted_rr,
+            'effective_pe', OLD.effective_pe, 'effective_entries', OLD.effective_entries, 'exits', OLD.exits,
+            'effective_weighted_rr', OLD.effective_weighted_rr, 'total_pnl', OLD.total_pnl, 'pnl_in_r', OLD.pnl_in_r,
+            'notes', OLD.notes, 'execution_portfolio', OLD.execution_portfolio, 'execution_r_percent', OLD.execution_r_percent,
+            'execution_margin', OLD.execution_margin, 'execution_position_size', OLD.execution_position_size, 'execution_quantity', OLD.execution_quantity,
+            'execution_one_r', OLD.execution_one_r, 'execution_potential_profit', OLD.execution_potential_profit, 'import_fingerprint', OLD.import_fingerprint,
+            'import_source', OLD.import_source, 'account_id', OLD.account_id, 'grade', OLD.grade,
+            'created_at', OLD.created_at, 'updated_at', OLD.updated_at, 'deleted_at', OLD.deleted_at
+        ),
+        json_object(
+            'id', NEW.id, 'pair', NEW.pair, 'exchange', NEW.exchange,
+            'analysis_date', NEW.analysis_date, 'trade_date', NEW.trade_date, 'close_date', NEW.close_date,
+            'status', NEW.status, 'portfolio_value', NEW.portfolio_value, 'r_percent', NEW.r_percent,
+            'min_rr', NEW.min_rr, 'planned_pe', NEW.planned_pe, 'planned_sl', NEW.planned_sl,
+            'leverage', NEW.leverage, 'planned_tps', NEW.planned_tps, 'planned_entries', NEW.planned_entries,
+            'position_type', NEW.position_type, 'one_r', NEW.one_r, 'margin', NEW.margin,
+            'position_size', NEW.position_size, 'quantity', NEW.quantity, 'planned_weighted_rr', NEW.planned_weighted_rr,
+            'effective_pe', NEW.effective_pe, 'effective_entries', NEW.effective_entries, 'exits', NEW.exits,
+            'effective_weighted_rr', NEW.effective_weighted_rr, 'total_pnl', NEW.total_pnl, 'pnl_in_r', NEW.pnl_in_r,
+            'notes', NEW.notes, 'execution_portfolio', NEW.execution_portfolio, 'execution_r_percent', NEW.execution_r_percent,
+            'execution_margin', NEW.execution_margin, 'execution_position_size', NEW.execution_position_size, 'execution_quantity', NEW.execution_quantity,
+            'execution_one_r', NEW.execution_one_r, 'execution_potential_profit', NEW.execution_potential_profit, 'import_fingerprint', NEW.import_fingerprint,
+            'import_source', NEW.import_source, 'account_id', NEW.account_id, 'grade', NEW.grade,
+            'created_at', NEW.created_at, 'updated_at', NEW.updated_at, 'deleted_at', NEW.deleted_at
+        ),
+        strftime('%s', 'now')
+    );
+    DELETE FROM trade_revisions
+    WHERE trade_id = NEW.id
+      AND id NOT IN (
+          SELECT id FROM trade_revisions WHERE trade_id = NEW.id ORDER BY id DESC LIMIT 50
+      );
+END;
@@ -0,0 +1,3 @@
+-- Rollback of migration 023: Auto-sync look-back window
+
+ALTER TABLE api_credentials DROP COLUMN auto_sync_lookback_days;
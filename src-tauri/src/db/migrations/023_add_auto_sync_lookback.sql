@@ -0,0 +1,7 @@
+-- Migration 023: Auto-sync look-back window
+-- Reason: Bound how far back automatic syncs fetch per credential (NULL = no bound).
+--         Manual syncs still fetch the full requested range.
+-- Date: 2026-10-17
+-- Breaking: No
+
+ALTER TABLE api_credentials ADD COLUMN auto_sync_lookback_days INTEGER CHECK (auto_sync_lookback_days > 0);
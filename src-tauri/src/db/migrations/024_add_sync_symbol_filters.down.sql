@@ -0,0 +1,3 @@
+-- Rollback of migration 024: Per-credential symbol filters for API syncs
+
+DROP TABLE IF EXISTS sync_symbol_filters;
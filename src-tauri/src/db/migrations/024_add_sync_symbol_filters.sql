@@ -0,0 +1,12 @@
+-- Migration 024: Per-credential symbol filters for API syncs
+-- Reason: Accounts shared with trading bots imported the bot's trades into the journal.
+--         INCLUDE rows restrict a credential's syncs to those symbols, EXCLUDE rows skip them.
+-- Date: 2026-10-17
+-- Breaking: No
+
+CREATE TABLE IF NOT EXISTS sync_symbol_filters (
+    credential_id TEXT NOT NULL REFERENCES api_credentials(id) ON DELETE CASCADE,
+    symbol TEXT NOT NULL,
+    mode TEXT NOT NULL CHECK (mode IN ('INCLUDE', 'EXCLUDE')),
+    PRIMARY KEY (credential_id, symbol)
+);
@@ -0,0 +1,6 @@
+-- Rollback of migration 025: Sync history retention
+
+DROP INDEX IF EXISTS idx_api_sync_history_credential_created;
+
+ALTER TABLE settings DROP COLUMN sync_history_max_entries;
+ALTER TABLE settings DROP COLUMN sync_history_retention_days;
@@ -0,0 +1,11 @@
+-- Migration 025: Sync history retention
+-- Reason: Hourly auto-sync made api_sync_history grow without bound. Each write now
+--         prunes entries older than sync_history_retention_days and keeps at most
+--         sync_history_max_entries per credential (0 disables either limit).
+-- Date: 2026-10-17
+-- Breaking: No
+
+ALTER TABLE settings ADD COLUMN sync_history_retention_days INTEGER NOT NULL DEFAULT 90;
+ALTER TABLE settings ADD COLUMN sync_history_max_entries INTEGER NOT NULL DEFAULT 1000;
+
+CREATE INDEX IF NOT EXISTS idx_api_sync_history_credential_created ON api_sync_history(credential_id, created_at);
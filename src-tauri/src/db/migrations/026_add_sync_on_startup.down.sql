@@ -0,0 +1,3 @@
+-- Rollback of migration 026: Catch-up sync on startup
+
+ALTER TABLE settings DROP COLUMN sync_on_startup;
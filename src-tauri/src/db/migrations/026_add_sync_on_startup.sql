@@ -0,0 +1,8 @@
+-- Migration 026: Catch-up sync on startup
+-- Reason: After the app was closed for a while, auto-sync credentials should sync
+--         shortly after launch rather than a full interval later. Turning it off
+--         makes the first auto-sync wait one interval instead.
+-- Date: 2026-10-17
+-- Breaking: No
+
+ALTER TABLE settings ADD COLUMN sync_on_startup INTEGER NOT NULL DEFAULT 1;
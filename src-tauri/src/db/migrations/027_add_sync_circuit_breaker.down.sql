@@ -0,0 +1,5 @@
+-- Rollback of migration 027: Auto-sync circuit breaker
+
+ALTER TABLE api_credentials DROP COLUMN auto_sync_disabled_at;
+ALTER TABLE api_credentials DROP COLUMN auto_sync_disabled_reason;
+ALTER TABLE api_credentials DROP COLUMN consecutive_sync_failures;
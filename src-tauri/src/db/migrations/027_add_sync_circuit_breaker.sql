@@ -0,0 +1,10 @@
+-- Migration 027: Auto-sync circuit breaker
+-- Reason: A revoked API key made the scheduler fail and notify on every tick forever.
+--         Consecutive failed auto-syncs are counted per credential and auto-sync is
+--         turned off past a threshold, keeping the reason until it is re-enabled.
+-- Date: 2026-10-17
+-- Breaking: No
+
+ALTER TABLE api_credentials ADD COLUMN consecutive_sync_failures INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE api_credentials ADD COLUMN auto_sync_disabled_reason TEXT;
+ALTER TABLE api_credentials ADD COLUMN auto_sync_disabled_at INTEGER;
@@ -0,0 +1,4 @@
+-- Rollback of migration 028: Live mirror event log
+
+DROP INDEX IF EXISTS idx_live_mirror_events_credential_created;
+DROP TABLE IF EXISTS live_mirror_events;
@@ -0,0 +1,20 @@
+-- Migration 028: Live mirror event log
+-- Reason: Nothing recorded what the live mirror did while nobody watched. Every opened,
+--         updated and closed position, TP/SL change and error is now logged per credential
+--         with a snippet of the exchange payload. Entries older than 30 days are pruned
+--         when mirroring starts.
+-- Date: 2026-10-17
+-- Breaking: No
+
+CREATE TABLE IF NOT EXISTS live_mirror_events (
+    id TEXT PRIMARY KEY NOT NULL,
+    credential_id TEXT NOT NULL REFERENCES api_credentials(id) ON DELETE CASCADE,
+    event_type TEXT NOT NULL CHECK (event_type IN ('OPENED', 'UPDATED', 'CLOSED', 'TP_SL', 'ERROR')),
+    pos_id TEXT,
+    trade_id TEXT,
+    message TEXT NOT NULL,
+    payload TEXT,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_live_mirror_events_credential_created ON live_mirror_events(credential_id, created_at);
@@ -0,0 +1,3 @@
+-- Rollback of migration 030: Live mirror staleness watchdog
+
+ALTER TABLE settings DROP COLUMN mirror_stale_timeout_secs;
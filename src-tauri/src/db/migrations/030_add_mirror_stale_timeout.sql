@@ -0,0 +1,8 @@
+-- Migration 030: Live mirror staleness watchdog
+-- Reason: Long-lived WebSocket connections stall silently. A mirror that receives no
+--         message (pongs included) for mirror_stale_timeout_secs is reconnected and the
+--         user notified. 0 disables the watchdog.
+-- Date: 2026-10-17
+-- Breaking: No
+
+ALTER TABLE settings ADD COLUMN mirror_stale_timeout_secs INTEGER NOT NULL DEFAULT 90;
@@ -0,0 +1,4 @@
+-- Rollback of migration 031: Price alerts
+
+DROP INDEX IF EXISTS idx_alerts_status;
+DROP TABLE IF EXISTS alerts;
@@ -0,0 +1,25 @@
+-- Migration 031: Price alerts
+-- Reason: Users want to be told when a symbol crosses a level without keeping a chart open.
+--         Active alerts are watched on the exchange's public ticker feed; a triggered alert
+--         raises a desktop notification and, when plan_position_type is set, adds a planned
+--         trade at the trigger price to the watchlist.
+-- Date: 2026-10-17
+-- Breaking: No
+
+CREATE TABLE IF NOT EXISTS alerts (
+    id TEXT PRIMARY KEY NOT NULL,
+    exchange TEXT NOT NULL DEFAULT 'bitget',
+    symbol TEXT NOT NULL,
+    condition TEXT NOT NULL CHECK (condition IN ('ABOVE', 'BELOW')),
+    target_price REAL NOT NULL,
+    note TEXT NOT NULL DEFAULT '',
+    plan_position_type TEXT CHECK (plan_position_type IN ('LONG', 'SHORT')),
+    status TEXT NOT NULL DEFAULT 'ACTIVE' CHECK (status IN ('ACTIVE', 'TRIGGERED')),
+    triggered_at INTEGER,
+    triggered_price REAL,
+    planned_trade_id TEXT REFERENCES planned_trades(id) ON DELETE SET NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_alerts_status ON alerts(status);
@@ -0,0 +1,4 @@
+-- Rollback of migration 032: Trades left open by a live mirror on exit
+
+DROP INDEX IF EXISTS idx_live_mirror_inflight_credential;
+DROP TABLE IF EXISTS live_mirror_inflight;
@@ -0,0 +1,18 @@
+-- Migration 032: Trades left open by a live mirror on exit
+-- Reason: Closing the app stopped mirroring without a trace, leaving OPEN trades whose
+--         positions may close while the app is off. On exit the mirror now records the
+--         trades it was tracking; when mirroring starts again their positions are checked
+--         over REST and the trades resumed or closed.
+-- Date: 2026-10-17
+-- Breaking: No
+
+CREATE TABLE IF NOT EXISTS live_mirror_inflight (
+    trade_id TEXT PRIMARY KEY NOT NULL REFERENCES trades(id) ON DELETE CASCADE,
+    credential_id TEXT NOT NULL REFERENCES api_credentials(id) ON DELETE CASCADE,
+    pos_id TEXT NOT NULL,
+    hold_side TEXT NOT NULL,
+    inst_id TEXT NOT NULL,
+    stopped_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_live_mirror_inflight_credential ON live_mirror_inflight(credential_id);
@@ -0,0 +1,3 @@
+-- Rollback of migration 033: Last known state of live-mirrored positions
+
+DROP TABLE IF EXISTS live_mirror_positions;
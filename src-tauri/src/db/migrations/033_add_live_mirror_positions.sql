@@ -0,0 +1,16 @@
+-- Migration 033: Last known state of live-mirrored positions
+-- Reason: Position events sent while a mirror is disconnected are lost, so a position
+--         closed meanwhile left its trade OPEN. The last state of each open position is
+--         now kept per credential; on every (re)connect the mirror diffs a REST snapshot
+--         against it and replays the Opened/Closed events it missed.
+-- Date: 2026-10-17
+-- Breaking: No
+
+CREATE TABLE IF NOT EXISTS live_mirror_positions (
+    credential_id TEXT NOT NULL REFERENCES api_credentials(id) ON DELETE CASCADE,
+    pos_id TEXT NOT NULL,
+    hold_side TEXT NOT NULL,
+    data TEXT NOT NULL, -- Position as last received, JSON
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (credential_id, pos_id, hold_side)
+);
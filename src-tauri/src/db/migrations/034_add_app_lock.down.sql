@@ -0,0 +1,3 @@
+-- Rollback of migration 034: Application lock
+
+DROP TABLE IF EXISTS app_lock;
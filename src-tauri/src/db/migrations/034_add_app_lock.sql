@@ -0,0 +1,17 @@
+-- Migration 034: Application lock
+-- Reason: The app holds the full trading history and exchange credentials. An optional
+--         master password (stored as an Argon2 hash) now has to be entered on launch
+--         before any command returns data, and the app locks itself again after
+--         auto_lock_minutes without activity (0 disables auto-lock).
+-- Date: 2026-10-17
+-- Breaking: No
+
+CREATE TABLE IF NOT EXISTS app_lock (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    password_hash TEXT, -- NULL while the lock is off
+    auto_lock_minutes INTEGER NOT NULL DEFAULT 15,
+    updated_at INTEGER NOT NULL
+);
+
+INSERT OR IGNORE INTO app_lock (id, password_hash, auto_lock_minutes, updated_at)
+VALUES (1, NULL, 15, strftime('%s', 'now'));
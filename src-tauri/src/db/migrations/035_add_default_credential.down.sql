@@ -0,0 +1,4 @@
+-- Rollback of migration 035: Default credential per exchange
+
+DROP INDEX IF EXISTS idx_api_credentials_default;
+ALTER TABLE api_credentials DROP COLUMN is_default;
@@ -0,0 +1,18 @@
+-- Migration 035: Default credential per exchange
+-- Reason: Quick actions (current positions, open orders, reconciliation) can run without the
+--         frontend picking a credential. At most one credential per exchange is the
+--         default; the oldest credential of each exchange starts as its default.
+-- Date: 2026-10-17
+-- Breaking: No
+
+ALTER TABLE api_credentials ADD COLUMN is_default INTEGER NOT NULL DEFAULT 0;
+
+UPDATE api_credentials SET is_default = 1
+WHERE id IN (
+    SELECT id FROM api_credentials AS c
+    WHERE created_at = (SELECT MIN(created_at) FROM api_credentials WHERE exchange = c.exchange)
+    GROUP BY exchange
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_api_credentials_default
+    ON api_credentials(exchange) WHERE is_default = 1;
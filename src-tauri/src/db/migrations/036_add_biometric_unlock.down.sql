@@ -0,0 +1,3 @@
+-- Rollback of migration 036: Biometric unlock for credential access
+
+ALTER TABLE settings DROP COLUMN require_biometric_unlock;
@@ -0,0 +1,8 @@
+-- Migration 036: Biometric unlock for credential access
+-- Reason: On macOS, reading exchange API keys from the keychain can require Touch ID (or
+--         the account password) so a stolen unlocked laptop can't silently sync. A
+--         successful prompt unlocks credential reads for a few minutes. Off by default.
+-- Date: 2026-10-17
+-- Breaking: No
+
+ALTER TABLE settings ADD COLUMN require_biometric_unlock INTEGER NOT NULL DEFAULT 0;
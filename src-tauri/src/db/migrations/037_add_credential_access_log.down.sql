@@ -0,0 +1,4 @@
+-- Rollback of migration 037: Credential access log
+
+DROP INDEX IF EXISTS idx_credential_access_log_credential_accessed;
+DROP TABLE IF EXISTS credential_access_log;
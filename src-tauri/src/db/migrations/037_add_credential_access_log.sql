@@ -0,0 +1,18 @@
+-- Migration 037: Credential access log
+-- Reason: Users could not tell when the app read their exchange API keys. Every keychain
+--         read of an API key, secret or passphrase is now logged with the feature that
+--         read it and whether the read succeeded. Entries are kept after the credential is
+--         deleted so the history stays auditable, and pruned after 90 days at startup.
+-- Date: 2026-10-17
+-- Breaking: No
+
+CREATE TABLE IF NOT EXISTS credential_access_log (
+    id TEXT PRIMARY KEY NOT NULL,
+    credential_id TEXT NOT NULL,
+    entry TEXT NOT NULL CHECK (entry IN ('api-key', 'api-secret', 'passphrase')),
+    accessed_by TEXT NOT NULL,
+    success INTEGER NOT NULL,
+    accessed_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_credential_access_log_credential_accessed ON credential_access_log(credential_id, accessed_at);
@@ -0,0 +1,3 @@
+-- Rollback of migration 038: Public IP history
+
+DROP TABLE IF EXISTS public_ip_history;
@@ -0,0 +1,13 @@
+-- Migration 038: Public IP history
+-- Reason: Exchanges recommend IP-whitelisted API keys, so users need the machine's public
+--         IP when creating one. Each lookup records the address with when it was first and
+--         last seen, so a key can be whitelisted for every address the machine recently
+--         had. Only the 10 most recently seen addresses are kept.
+-- Date: 2026-10-17
+-- Breaking: No
+
+CREATE TABLE IF NOT EXISTS public_ip_history (
+    ip TEXT PRIMARY KEY NOT NULL,
+    first_seen_at INTEGER NOT NULL,
+    last_seen_at INTEGER NOT NULL
+);
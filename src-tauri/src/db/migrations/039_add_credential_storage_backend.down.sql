@@ -0,0 +1,3 @@
+-- Rollback of migration 039: Credential storage backend
+
+ALTER TABLE api_credentials DROP COLUMN storage_backend;
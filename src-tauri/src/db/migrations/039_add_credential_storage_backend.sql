@@ -0,0 +1,10 @@
+-- Migration 039: Credential storage backend
+-- Reason: Without a usable keychain (Linux builds, a locked keychain) credentials could not
+--         be saved at all. Their secrets now fall back to the AES-GCM file store
+--         (credentials.enc); each credential records where its secrets went so the UI can
+--         say so. Existing credentials were all stored in the keychain.
+-- Date: 2026-10-17
+-- Breaking: No
+
+ALTER TABLE api_credentials ADD COLUMN storage_backend TEXT NOT NULL DEFAULT 'keychain'
+    CHECK (storage_backend IN ('keychain', 'encrypted_file'));
@@ -0,0 +1,4 @@
+-- Rollback of migration 040: Credential rotation history
+
+DROP INDEX IF EXISTS idx_credential_rotations_credential_rotated;
+DROP TABLE IF EXISTS credential_rotations;
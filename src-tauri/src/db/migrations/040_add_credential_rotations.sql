@@ -0,0 +1,19 @@
+-- Migration 040: Credential rotation history
+-- Reason: API keys can now be rotated in place with rotate_api_credentials. The new key is
+--         tested against the exchange before it replaces the old one, and every attempt is
+--         recorded with the previews of both keys and, when it failed, why. Entries are
+--         kept after the credential is deleted, like the credential access log.
+-- Date: 2026-10-17
+-- Breaking: No
+
+CREATE TABLE IF NOT EXISTS credential_rotations (
+    id TEXT PRIMARY KEY NOT NULL,
+    credential_id TEXT NOT NULL,
+    old_key_preview TEXT,
+    new_key_preview TEXT NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('success', 'failed')),
+    error_message TEXT,
+    rotated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_credential_rotations_credential_rotated ON credential_rotations(credential_id, rotated_at);
@@ -0,0 +1,3 @@
+-- Rollback of migration 041: Proxy for exchange traffic
+
+ALTER TABLE settings DROP COLUMN proxy_url;
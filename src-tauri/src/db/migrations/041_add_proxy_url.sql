@@ -0,0 +1,9 @@
+-- Migration 041: Proxy for exchange traffic
+-- Reason: Users behind corporate networks, or who need exchange traffic to leave from a
+--         specific region, could not sync. HTTP requests and WebSockets to the exchanges
+--         now go through the proxy set here (http://, socks5:// or socks5h://). NULL
+--         connects directly.
+-- Date: 2026-10-17
+-- Breaking: No
+
+ALTER TABLE settings ADD COLUMN proxy_url TEXT;
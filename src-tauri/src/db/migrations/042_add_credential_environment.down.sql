@@ -0,0 +1,4 @@
+-- Rollback of migration 042: Credential environment
+
+ALTER TABLE api_credentials DROP COLUMN base_url;
+ALTER TABLE api_credentials DROP COLUMN environment;
@@ -0,0 +1,11 @@
+-- Migration 042: Credential environment
+-- Reason: Demo (paper trading) accounts on BitGet and BloFin could not be connected, the
+--         clients always talking to the production servers. A credential now says which
+--         environment its key belongs to, and may replace the REST base URL (base_url).
+--         Existing credentials are production keys.
+-- Date: 2026-10-17
+-- Breaking: No
+
+ALTER TABLE api_credentials ADD COLUMN environment TEXT NOT NULL DEFAULT 'production'
+    CHECK (environment IN ('production', 'demo'));
+ALTER TABLE api_credentials ADD COLUMN base_url TEXT;
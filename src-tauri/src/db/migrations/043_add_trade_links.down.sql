@@ -0,0 +1,9 @@
+-- Rollback of migration 043: Linked trades
+-- Links between trades are lost
+
+DROP TRIGGER IF EXISTS trg_trade_links_insert_stats_version;
+DROP TRIGGER IF EXISTS trg_trade_links_update_stats_version;
+DROP TRIGGER IF EXISTS trg_trade_links_delete_stats_version;
+
+DROP INDEX IF EXISTS idx_trade_links_related;
+DROP TABLE IF EXISTS trade_links;
@@ -0,0 +1,35 @@
+-- Migration 043: Linked trades
+-- Reason: Re-entries, hedges and scale-ins taken across days were unrelated rows, so a
+--         setup traded in several goes could not be reviewed (or counted in stats) as one
+--         campaign. A link ties a follow-up trade to the trade it follows up on.
+-- Date: 2026-10-17
+-- Breaking: No
+
+CREATE TABLE IF NOT EXISTS trade_links (
+    trade_id TEXT NOT NULL,         -- The earlier trade
+    related_trade_id TEXT NOT NULL, -- The re-entry, hedge or scale-in
+    relation TEXT NOT NULL CHECK (relation IN ('RE_ENTRY', 'HEDGE', 'SCALE_IN')),
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (trade_id, related_trade_id),
+    CHECK (trade_id <> related_trade_id),
+    FOREIGN KEY (trade_id) REFERENCES trades(id) ON DELETE CASCADE,
+    FOREIGN KEY (related_trade_id) REFERENCES trades(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_trade_links_related ON trade_links(related_trade_id);
+
+-- Links decide how get_campaign_stats groups trades, so they invalidate the stats cache too
+CREATE TRIGGER IF NOT EXISTS trg_trade_links_insert_stats_version AFTER INSERT ON trade_links
+BEGIN
+    UPDATE stats_data_version SET version = version + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_trade_links_update_stats_version AFTER UPDATE ON trade_links
+BEGIN
+    UPDATE stats_data_version SET version = version + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_trade_links_delete_stats_version AFTER DELETE ON trade_links
+BEGIN
+    UPDATE stats_data_version SET version = version + 1 WHERE id = 1;
+END;
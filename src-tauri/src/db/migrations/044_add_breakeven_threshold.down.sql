@@ -0,0 +1,3 @@
+-- Rollback of migration 044: Break-even threshold
+
+ALTER TABLE settings DROP COLUMN breakeven_threshold;
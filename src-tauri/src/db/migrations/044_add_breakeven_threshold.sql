@@ -0,0 +1,10 @@
+-- Migration 044: Break-even threshold
+-- Reason: Closed trades within a fixed $0.50 (imports) or $1 (syncs, live mirror) of zero
+--         counted as break-even, too tight for large accounts and fee-heavy exchanges. The
+--         threshold is now one setting, used wherever a closed trade gets its WIN, LOSS or
+--         BE status.
+-- Date: 2026-10-17
+-- Breaking: No
+
+-- update_settings keeps it non-negative
+ALTER TABLE settings ADD COLUMN breakeven_threshold REAL DEFAULT 0.5;
@@ -1,4 +1,5 @@
 pub mod connection;
+pub mod encryption;
 pub mod migration_runner;
 pub mod migrations;
 
@@ -9,6 +9,154 @@ use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // While locked, everything but unlocking is refused before the command runs
+    let handler = commands::with_app_lock(tauri::generate_handler![
+        commands::get_settings,
+        commands::update_settings,
+        commands::get_trades,
+        commands::count_trades,
+        commands::search_trades,
+        commands::get_trade,
+        commands::create_trade,
+        commands::update_trade,
+        commands::close_trade,
+        commands::bulk_update_trades,
+        commands::delete_trade,
+        commands::get_deleted_trades,
+        commands::restore_trade,
+        commands::duplicate_trade,
+        commands::set_trade_grade,
+        commands::set_trade_emotions,
+        commands::purge_trade,
+        commands::empty_trash,
+        commands::delete_trades,
+        commands::restore_trades,
+        commands::purge_trades,
+        commands::link_trades,
+        commands::unlink_trades,
+        commands::get_trade_links,
+        commands::get_trade_campaign,
+        commands::recalculate_trade_metrics,
+        commands::get_trade_history,
+        commands::revert_trade_revision,
+        commands::get_all_trades_including_deleted,
+        commands::restore_all_trades,
+        commands::delete_all_trades,
+        commands::generate_demo_data,
+        commands::clear_demo_data,
+        commands::get_dashboard_stats,
+        commands::get_equity_curve,
+        commands::get_drawdown,
+        commands::get_advanced_stats,
+        commands::get_time_breakdown,
+        commands::get_source_breakdown,
+        commands::get_funding_stats,
+        commands::get_funding_payments,
+        commands::get_strategy_stats,
+        commands::get_tag_stats,
+        commands::get_grade_stats,
+        commands::get_emotion_stats,
+        commands::get_campaign_stats,
+        commands::get_pnl_calendar,
+        commands::get_kelly_suggestion,
+        commands::run_monte_carlo,
+        commands::get_risk_alerts,
+        commands::get_risk_of_ruin,
+        commands::get_accounts,
+        commands::get_account,
+        commands::create_account,
+        commands::update_account,
+        commands::delete_account,
+        commands::get_capital_transactions,
+        commands::create_capital_transaction,
+        commands::delete_capital_transaction,
+        commands::get_tags,
+        commands::create_tag,
+        commands::delete_tag,
+        commands::assign_tag,
+        commands::remove_tag,
+        commands::get_trade_tags,
+        commands::get_journal_entries,
+        commands::get_journal_entry,
+        commands::create_journal_entry,
+        commands::update_journal_entry,
+        commands::delete_journal_entry,
+        commands::link_journal_trade,
+        commands::unlink_journal_trade,
+        commands::link_journal_day_trades,
+        commands::get_journal_entry_trades,
+        commands::get_planned_trades,
+        commands::get_planned_trade,
+        commands::create_planned_trade,
+        commands::update_planned_trade,
+        commands::delete_planned_trade,
+        commands::convert_to_trade,
+        commands::preview_bitget_import,
+        commands::import_bitget_csv,
+        commands::delete_bitget_trades,
+        commands::preview_bitget_spot_import,
+        commands::import_bitget_spot_csv,
+        commands::preview_blofin_import,
+        commands::import_blofin_csv,
+        commands::delete_blofin_trades,
+        commands::preview_bingx_import,
+        commands::import_bingx_file,
+        commands::delete_bingx_trades,
+        commands::import_files,
+        commands::export_all_data,
+        commands::import_all_data,
+        commands::list_backups,
+        commands::restore_backup,
+        commands::rollback_database,
+        commands::restart_app,
+        commands::get_database_encryption_status,
+        commands::enable_database_encryption,
+        commands::check_database_health,
+        commands::save_api_credentials,
+        commands::list_api_credentials,
+        commands::test_api_credentials,
+        commands::rotate_api_credentials,
+        commands::get_credential_rotations,
+        commands::delete_api_credentials,
+        commands::set_default_api_credential,
+        commands::get_credential_access_log,
+        commands::get_public_ip,
+        commands::update_api_credentials_status,
+        commands::update_auto_sync_settings,
+        commands::get_sync_symbol_filters,
+        commands::set_sync_symbol_filters,
+        commands::get_sync_history,
+        commands::clear_sync_history,
+        commands::sync_exchange_trades,
+        commands::preview_exchange_sync,
+        commands::link_trade_to_fill,
+        commands::cancel_sync,
+        commands::reload_sync_scheduler,
+        commands::pause_auto_sync,
+        commands::resume_auto_sync,
+        commands::get_scheduler_status,
+        commands::reenable_auto_sync,
+        commands::fetch_current_positions,
+        commands::reconcile_positions,
+        commands::fetch_open_orders,
+        commands::start_live_mirroring,
+        commands::stop_live_mirroring,
+        commands::is_live_mirroring_active,
+        commands::get_mirror_health,
+        commands::toggle_live_mirroring,
+        commands::get_live_mirroring_status,
+        commands::get_live_mirror_events,
+        commands::get_price_alerts,
+        commands::create_price_alert,
+        commands::delete_price_alert,
+        commands::get_app_lock_status,
+        commands::unlock_app,
+        commands::lock_app,
+        commands::record_app_activity,
+        commands::set_app_lock_password,
+        commands::set_auto_lock_minutes,
+    ]);
+
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_notification::init())
@@ -26,6 +174,22 @@ pub fn run() {
             let db_path = app_dir.join("trading_journal.db");
             println!("Database path: {:?}", db_path);
 
+            // Initialize the legacy credential file store and move what it still holds to the
+            // keychain (including the key of an encrypted database)
+            api::credentials::init_storage(app_dir.clone())
+                .expect("Failed to initialize secure storage");
+            match api::credentials::migrate_legacy_storage() {
+                Ok(0) => {}
+                Ok(moved) => println!("Moved {} secret(s) from credentials.enc to the keychain", moved),
+                Err(e) => eprintln!("Warning: Failed to move legacy credentials to the keychain, will retry at next launch: {}", e),
+            }
+
+            // Run a requested encryption and load the key before the first connection opens
+            if let Err(e) = db::encryption::prepare_database(&db_path) {
+                eprintln!("❌ {}", e);
+                return Err(Box::new(std::io::Error::other(e)));
+            }
+
             // Initialize database
             let database = match db::Database::new(db_path.to_str().unwrap()) {
                 Ok(db) => db,
@@ -46,24 +210,65 @@ pub fn run() {
                     eprintln!("If the problem persists, please report this issue with");
                     eprintln!("the error message shown above.");
 
-                    return Err(Box::new(std::io::Error::new(
-                        std::io::ErrorKind::Other,
+                    return Err(Box::new(std::io::Error::other(
                         format!("Database initialization failed: {}", e),
                     )));
                 }
             };
 
+            // Apply the trash retention policy before anything reads trades
+            if let Ok(mut conn) = database.pool.get() {
+                match commands::purge_expired_trash(&mut conn) {
+                    Ok(0) => {}
+                    Ok(purged) => println!("Purged {} trade(s) past the trash retention period", purged),
+                    Err(e) => eprintln!("Warning: Failed to purge expired trash: {}", e),
+                }
+            }
+
+            // Audit trail of credential reads
+            if let Err(e) = api::credentials::init_access_log(database.clone()) {
+                eprintln!("Warning: Failed to initialize credential access log: {}", e);
+            }
+
+            // Touch ID before credential reads, when turned on in settings
+            if let Ok(conn) = database.pool.get() {
+                let required = conn
+                    .query_row("SELECT require_biometric_unlock FROM settings WHERE id = 1", [], |row| row.get::<_, i32>(0))
+                    .map(|value| value == 1)
+                    .unwrap_or(false);
+                if let Err(e) = api::biometric::set_required(required) {
+                    eprintln!("Warning: {}", e);
+                }
+            }
+
+            // Exchange traffic through the proxy set in settings
+            if let Ok(conn) = database.pool.get() {
+                let proxy_url: Option<String> = conn
+                    .query_row("SELECT proxy_url FROM settings WHERE id = 1", [], |row| row.get(0))
+                    .unwrap_or(None);
+                // An invalid stored proxy blocks exchange traffic rather than going direct
+                if let Err(e) = api::proxy::set_proxy(proxy_url.as_deref()) {
+                    eprintln!("ERROR: Exchange traffic is blocked until the proxy is fixed: {}", e);
+                }
+            }
+
+            // Locked from launch when a master password is set
+            let app_lock = database
+                .pool
+                .get()
+                .map_err(|e| e.to_string())
+                .and_then(|conn| commands::AppLock::load(&conn).map_err(|e| e.to_string()))
+                .map_err(|e| std::io::Error::other(format!("Failed to load app lock: {}", e)))?;
+            app.manage(app_lock);
+            tauri::async_runtime::spawn(commands::run_auto_lock(app.handle().clone()));
+
             // Store database in app state
             app.manage(database);
 
-            // Initialize secure credential storage
-            api::credentials::init_storage(app_dir.clone())
-                .expect("Failed to initialize secure storage");
-
             // Check feature flags after database initialization
             let db = app.state::<db::Database>();
             let (enable_position_monitor, enable_api_connections) = {
-                match db.conn.lock() {
+                match db.pool.get() {
                     Ok(conn) => {
                         let position_monitor: i32 = conn
                             .query_row(
@@ -91,6 +296,12 @@ pub fn run() {
             println!("Feature flags - Position Monitor: {}, API Connections: {}",
                      enable_position_monitor, enable_api_connections);
 
+            // Registry of running syncs so they can be cancelled
+            app.manage(sync::SyncCancellations::default());
+
+            // API key previews for the credential list, so it doesn't read the keychain each time
+            app.manage(api::credential_cache::CredentialCache::default());
+
             // Initialize sync scheduler
             let scheduler = sync::SyncScheduler::new(app.handle().clone());
 
@@ -113,62 +324,43 @@ pub fn run() {
                 tauri::async_runtime::spawn(async move {
                     mirror_manager_clone.stop_all().await;
                 });
+            } else {
+                // Resume mirroring for credentials that have it enabled
+                let mirror_manager_clone = mirror_manager.clone();
+                let app_handle = app.handle().clone();
+                let db = db.inner().clone();
+                tauri::async_runtime::spawn(async move {
+                    mirror_manager_clone.start_enabled(app_handle, db).await;
+                });
             }
 
             app.manage(mirror_manager);
 
+            // Watch active price alerts on the public ticker feed
+            let alert_manager = Arc::new(api::PriceAlertManager::new());
+            let alert_manager_clone = alert_manager.clone();
+            let app_handle = app.handle().clone();
+            let db = db.inner().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = alert_manager_clone.refresh(app_handle, db).await {
+                    eprintln!("Warning: Failed to start price alerts: {}", e);
+                }
+            });
+            app.manage(alert_manager);
+
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
-            commands::get_settings,
-            commands::update_settings,
-            commands::get_trades,
-            commands::get_trade,
-            commands::create_trade,
-            commands::update_trade,
-            commands::delete_trade,
-            commands::get_deleted_trades,
-            commands::restore_trade,
-            commands::duplicate_trade,
-            commands::get_all_trades_including_deleted,
-            commands::restore_all_trades,
-            commands::delete_all_trades,
-            commands::get_dashboard_stats,
-            commands::get_equity_curve,
-            commands::preview_bitget_import,
-            commands::import_bitget_csv,
-            commands::delete_bitget_trades,
-            commands::preview_blofin_import,
-            commands::import_blofin_csv,
-            commands::delete_blofin_trades,
-            commands::preview_bingx_import,
-            commands::import_bingx_file,
-            commands::delete_bingx_trades,
-            commands::export_all_data,
-            commands::import_all_data,
-            commands::save_api_credentials,
-            commands::list_api_credentials,
-            commands::test_api_credentials,
-            commands::delete_api_credentials,
-            commands::update_api_credentials_status,
-            commands::update_auto_sync_settings,
-            commands::get_sync_history,
-            commands::sync_exchange_trades,
-            commands::reload_sync_scheduler,
-            commands::fetch_current_positions,
-            commands::fetch_open_orders,
-            commands::start_live_mirroring,
-            commands::stop_live_mirroring,
-            commands::is_live_mirroring_active,
-            commands::toggle_live_mirroring,
-            commands::get_live_mirroring_status,
-        ])
+        .invoke_handler(handler)
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::CloseRequested { .. } = event {
-                // Cleanup live mirror connections on window close
+                // Cleanup live mirror and price alert connections on window close, recording open
+                // mirrored trades for the next start
                 let mirror_manager = window.state::<Arc<api::LiveMirrorManager>>();
+                let alert_manager = window.state::<Arc<api::PriceAlertManager>>();
+                let db = window.state::<db::Database>();
                 tauri::async_runtime::block_on(async {
-                    mirror_manager.stop_all().await;
+                    mirror_manager.shutdown(&db).await;
+                    alert_manager.stop().await;
                 });
             }
         })
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Account {
+    pub id: String,
+    pub name: String,
+    pub exchange: Option<String>,
+    pub is_paper: bool,
+    pub initial_capital: f64,
+    pub current_r_percent: f64,
+    pub currency: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateAccountInput {
+    pub name: String,
+    pub exchange: Option<String>,
+    pub is_paper: Option<bool>,
+    pub initial_capital: Option<f64>,
+    pub current_r_percent: Option<f64>,
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateAccountInput {
+    pub name: Option<String>,
+    pub exchange: Option<String>,
+    pub is_paper: Option<bool>,
+    pub initial_capital: Option<f64>,
+    pub current_r_percent: Option<f64>,
+    pub currency: Option<String>,
+}
@@ -1,4 +1,7 @@
 use serde::{Deserialize, Serialize};
+use crate::api::client::ApiKeyPermissions;
+use crate::api::credentials::Secret;
+use crate::models::Trade;
 
 /// API Credential model (for frontend communication)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,18 +11,23 @@ pub struct ApiCredential {
     pub label: String,
     #[serde(skip_serializing)]
     #[allow(dead_code)]
-    pub api_key: String, // Never send full key to frontend
+    pub api_key: Secret, // Never send full key to frontend
     pub api_key_preview: String, // Only last 4 chars
     #[serde(skip_serializing)]
     #[allow(dead_code)]
-    pub api_secret: String, // Never send to frontend
+    pub api_secret: Secret, // Never send to frontend
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub passphrase: Option<String>, // Never send to frontend
+    pub passphrase: Option<Secret>, // Never send to frontend
     pub is_active: bool,
     pub last_sync_timestamp: Option<i64>,
     pub auto_sync_enabled: bool,
     pub auto_sync_interval: i64, // Interval in seconds
+    pub auto_sync_lookback_days: Option<i64>, // None = automatic syncs are not bounded
     pub live_mirror_enabled: bool,
+    pub is_default: bool,
+    pub storage_backend: String, // "keychain", "encrypted_file"
+    pub environment: String, // "production", "demo"
+    pub base_url: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -46,7 +54,14 @@ impl ApiCredential {
             last_sync_timestamp: self.last_sync_timestamp,
             auto_sync_enabled: self.auto_sync_enabled,
             auto_sync_interval: self.auto_sync_interval,
+            auto_sync_lookback_days: self.auto_sync_lookback_days,
             live_mirror_enabled: self.live_mirror_enabled,
+            is_default: self.is_default,
+            storage_backend: self.storage_backend.clone(),
+            environment: self.environment.clone(),
+            base_url: self.base_url.clone(),
+            auto_sync_paused: false,
+            auto_sync_disabled_reason: None,
             created_at: self.created_at,
             updated_at: self.updated_at,
         }
@@ -64,24 +79,142 @@ pub struct ApiCredentialSafe {
     pub last_sync_timestamp: Option<i64>,
     pub auto_sync_enabled: bool,
     pub auto_sync_interval: i64, // Interval in seconds
+    /// Automatic syncs never fetch further back than this many days (None = no bound)
+    #[serde(default)]
+    pub auto_sync_lookback_days: Option<i64>,
     pub live_mirror_enabled: bool,
+    /// Used by quick actions that are not given a credential (one per exchange)
+    #[serde(default)]
+    pub is_default: bool,
+    /// Where the secrets are kept: "keychain", or "encrypted_file" when the keychain was
+    /// unavailable as they were saved
+    #[serde(default = "default_storage_backend")]
+    pub storage_backend: String,
+    /// "production", or "demo" for a key of the exchange's demo (paper trading) account
+    #[serde(default = "default_environment")]
+    pub environment: String,
+    /// Replaces the exchange's REST base URL (None = the environment's own)
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Auto-sync paused at runtime (pause_auto_sync); not persisted, cleared on restart
+    #[serde(default)]
+    pub auto_sync_paused: bool,
+    /// Why the circuit breaker turned auto-sync off, until it is re-enabled
+    #[serde(default)]
+    pub auto_sync_disabled_reason: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
+fn default_storage_backend() -> String {
+    "keychain".to_string()
+}
+
+fn default_environment() -> String {
+    "production".to_string()
+}
+
 /// Input for creating/updating API credentials
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiCredentialInput {
     pub id: Option<String>, // None for new, Some for update
     pub exchange: String,
     pub label: String,
-    pub api_key: String,
-    pub api_secret: String,
-    pub passphrase: Option<String>,
+    pub api_key: Secret,
+    pub api_secret: Secret,
+    pub passphrase: Option<Secret>,
     pub is_active: Option<bool>,
     pub auto_sync_enabled: Option<bool>,
     pub auto_sync_interval: Option<i64>,
+    #[serde(default)]
+    pub auto_sync_lookback_days: Option<i64>,
     pub live_mirror_enabled: Option<bool>,
+    /// "production" (default) or "demo"
+    #[serde(default)]
+    pub environment: Option<String>,
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+/// Outcome of testing API credentials
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialTestResult {
+    pub valid: bool,
+    /// None when the credentials are invalid or the exchange can't report them
+    pub permissions: Option<ApiKeyPermissions>,
+    /// Rights the key has but the journal doesn't need, or why permissions are unknown
+    pub warnings: Vec<String>,
+    pub diagnosis: CredentialDiagnosis,
+}
+
+/// What a credential test found step by step, so a key that doesn't work can be fixed
+/// without guessing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialDiagnosis {
+    /// The exchange's public server time endpoint answered
+    pub connectivity: DiagnosticCheck,
+    pub clock_skew: DiagnosticCheck,
+    /// The exchange accepted a signed request
+    pub signature: DiagnosticCheck,
+    /// The key can read and has no rights beyond that
+    pub permissions: DiagnosticCheck,
+    pub rate_limit: DiagnosticCheck,
+    /// Round trip of the server time request
+    pub latency_ms: Option<i64>,
+    /// Exchange clock minus local clock
+    pub clock_skew_ms: Option<i64>,
+    /// Requests the app could send the exchange right now without waiting
+    pub rate_limit_headroom: u32,
+}
+
+/// Outcome of one step of a credential test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticCheck {
+    pub status: String, // "ok", "warning", "failed", "skipped"
+    pub message: String,
+}
+
+impl DiagnosticCheck {
+    pub fn ok(message: impl Into<String>) -> Self {
+        Self { status: "ok".to_string(), message: message.into() }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self { status: "warning".to_string(), message: message.into() }
+    }
+
+    pub fn failed(message: impl Into<String>) -> Self {
+        Self { status: "failed".to_string(), message: message.into() }
+    }
+
+    pub fn skipped(message: impl Into<String>) -> Self {
+        Self { status: "skipped".to_string(), message: message.into() }
+    }
+}
+
+/// Entry of the credential access log: one keychain read of a credential's secret
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialAccess {
+    pub id: String,
+    pub credential_id: String,
+    pub entry: String, // "api-key", "api-secret", "passphrase"
+    /// App feature that read the secret, e.g. "sync" or "live_mirror"
+    pub accessed_by: String,
+    pub success: bool,
+    pub accessed_at: i64,
+}
+
+/// Entry of the rotation history: one attempt to swap a credential's key for a new one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialRotation {
+    pub id: String,
+    pub credential_id: String,
+    /// None when the old key could not be read
+    pub old_key_preview: Option<String>,
+    pub new_key_preview: String,
+    pub status: String, // "success", "failed"
+    pub error_message: Option<String>,
+    pub rotated_at: i64,
 }
 
 /// API Sync History record
@@ -118,4 +251,88 @@ pub struct SyncResult {
     pub duplicates: i32,
     pub errors: Vec<String>,
     pub total_pnl: Option<f64>,
+    /// New funding payments stored during the sync
+    #[serde(default)]
+    pub funding_imported: i32,
+    /// Imported trades that look like hand-entered ones, to offer linking
+    #[serde(default)]
+    pub link_suggestions: Vec<TradeLinkSuggestion>,
+}
+
+/// An imported trade matching a USER_CREATED trade (same pair and side, overlapping
+/// time, similar size)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeLinkSuggestion {
+    /// The hand-entered trade
+    pub trade_id: String,
+    /// The trade imported from the exchange's fills
+    pub fill_trade_id: String,
+}
+
+/// Symbols a credential's syncs import and its live mirror creates trades for. Symbols are compared without case or separators,
+/// so "BTC-USDT" and "btcusdt" match.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SymbolFilters {
+    /// When non-empty, only these symbols are imported
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Never imported, e.g. pairs traded by a bot on the same account
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+impl SymbolFilters {
+    pub fn normalize(symbol: &str) -> String {
+        symbol
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_uppercase())
+            .collect()
+    }
+
+    pub fn allows(&self, symbol: &str) -> bool {
+        let symbol = Self::normalize(symbol);
+        let listed = |list: &[String]| list.iter().any(|s| Self::normalize(s) == symbol);
+
+        (self.include.is_empty() || listed(&self.include)) && !listed(&self.exclude)
+    }
+}
+
+/// Auto-sync task state for one credential, returned to frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulerTaskStatus {
+    pub credential_id: String,
+    pub exchange: String,
+    pub label: String,
+    pub interval_secs: i64,
+    /// A sync (auto or manual) is in progress
+    pub running: bool,
+    pub paused: bool,
+    pub last_run: Option<i64>,
+    /// "SUCCESS", "FAILED" or "CANCELLED"; None until the first tick ran
+    pub last_result: Option<String>,
+    pub last_error: Option<String>,
+    pub last_imported: Option<i32>,
+    /// Approximate: a tick that runs late pushes the ones after it back
+    pub next_run: Option<i64>,
+}
+
+/// A fetched trade as a sync would import it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncPreviewTrade {
+    pub trade: Trade,
+    /// Already imported: a sync would skip it
+    pub is_duplicate: bool,
+    /// USER_CREATED trade the fills could be attached to instead
+    #[serde(default)]
+    pub matching_trade_id: Option<String>,
+}
+
+/// Dry-run of a sync, returned to frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncPreview {
+    pub trades: Vec<SyncPreviewTrade>,
+    pub new_trades: i32,
+    pub duplicates: i32,
+    pub errors: Vec<String>,
 }
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapitalTransaction {
+    pub id: String,
+    pub transaction_type: String, // DEPOSIT | WITHDRAWAL
+    pub amount: f64,              // Always positive; the type gives the direction
+    pub transaction_date: i64,
+    pub notes: Option<String>,
+    pub account_id: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCapitalTransactionInput {
+    pub transaction_type: String,
+    pub amount: f64,
+    pub transaction_date: i64,
+    pub notes: Option<String>,
+    pub account_id: Option<String>,
+}
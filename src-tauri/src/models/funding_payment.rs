@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundingPayment {
+    pub id: String,
+    pub credential_id: Option<String>,
+    pub exchange: String,
+    pub symbol: String,
+    pub amount: f64, // Positive when received, negative when paid
+    pub currency: Option<String>,
+    pub funding_time: i64,
+    pub trade_id: Option<String>, // Trade open on the symbol when the funding settled
+    pub created_at: i64,
+}
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub id: String,
+    pub entry_date: String, // YYYY-MM-DD
+    pub pre_market_plan: Option<String>,
+    pub post_market_review: Option<String>,
+    pub mood: Option<i32>, // 1 (bad) to 5 (great)
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateJournalEntryInput {
+    pub entry_date: String,
+    pub pre_market_plan: Option<String>,
+    pub post_market_review: Option<String>,
+    pub mood: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateJournalEntryInput {
+    pub pre_market_plan: Option<String>,
+    pub post_market_review: Option<String>,
+    pub mood: Option<i32>,
+}
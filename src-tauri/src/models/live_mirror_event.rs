@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// Entry of the live mirror event log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveMirrorEvent {
+    pub id: String,
+    pub credential_id: String,
+    pub event_type: String, // "OPENED", "UPDATED", "CLOSED", "TP_SL", "ERROR"
+    pub pos_id: Option<String>,
+    pub trade_id: Option<String>,
+    pub message: String,
+    /// Start of the exchange payload that caused the event
+    pub payload: Option<String>,
+    pub created_at: i64,
+}
@@ -1,7 +1,27 @@
+pub mod account;
 pub mod api_credential;
+pub mod capital_transaction;
+pub mod funding_payment;
+pub mod journal_entry;
+pub mod live_mirror_event;
+pub mod planned_trade;
+pub mod price_alert;
 pub mod settings;
+pub mod tag;
 pub mod trade;
+pub mod trade_link;
+pub mod trade_revision;
 
+pub use account::*;
 pub use api_credential::*;
+pub use capital_transaction::*;
+pub use funding_payment::*;
+pub use journal_entry::*;
+pub use live_mirror_event::*;
+pub use planned_trade::*;
+pub use price_alert::*;
 pub use settings::*;
+pub use tag::*;
 pub use trade::*;
+pub use trade_link::*;
+pub use trade_revision::*;
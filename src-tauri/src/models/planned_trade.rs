@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedTrade {
+    pub id: String,
+    pub pair: String,
+    pub exchange: String,
+    pub analysis_date: i64,
+
+    pub portfolio_value: f64,
+    pub r_percent: f64,
+    pub min_rr: f64,
+
+    pub planned_pe: f64,
+    pub planned_sl: f64,
+    pub leverage: i32,
+    pub planned_tps: String, // JSON
+    pub planned_entries: Option<String>, // JSON array of {price, percent}
+
+    pub position_type: String,
+    pub one_r: f64,
+    pub margin: f64,
+    pub position_size: f64,
+    pub quantity: f64,
+    pub planned_weighted_rr: f64,
+
+    pub notes: String,
+    pub account_id: Option<String>,
+
+    pub converted_trade_id: Option<String>, // None once the trade is deleted for good
+    pub converted_at: Option<i64>,          // None while still on the watchlist
+
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Used for both creating and editing a plan; editing replaces every field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedTradeInput {
+    pub pair: String,
+    pub exchange: String,
+    pub analysis_date: i64,
+
+    pub portfolio_value: f64,
+    pub r_percent: f64,
+    pub min_rr: f64,
+
+    pub planned_pe: f64,
+    pub planned_sl: f64,
+    pub leverage: i32,
+    pub planned_tps: String,
+    pub planned_entries: Option<String>,
+
+    pub position_type: String,
+    pub one_r: f64,
+    pub margin: f64,
+    pub position_size: f64,
+    pub quantity: f64,
+    pub planned_weighted_rr: f64,
+
+    pub notes: String,
+    pub account_id: Option<String>,
+}
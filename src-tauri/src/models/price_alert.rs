@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// Price level watched on an exchange's public ticker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceAlert {
+    pub id: String,
+    pub exchange: String,
+    pub symbol: String,
+    pub condition: String, // "ABOVE" or "BELOW"
+    pub target_price: f64,
+    pub note: String,
+    /// Side of the planned trade created when the alert triggers; None creates no plan
+    pub plan_position_type: Option<String>,
+    pub status: String, // "ACTIVE" or "TRIGGERED"
+    pub triggered_at: Option<i64>,
+    pub triggered_price: Option<f64>,
+    pub planned_trade_id: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceAlertInput {
+    pub symbol: String,
+    pub condition: String,
+    pub target_price: f64,
+    #[serde(default)]
+    pub note: String,
+    pub plan_position_type: Option<String>,
+}
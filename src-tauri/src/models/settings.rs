@@ -12,6 +12,37 @@ pub struct Settings {
     pub enable_position_monitor: bool,
     #[serde(default)]
     pub enable_api_connections: bool,
+    /// Consecutive losses before get_risk_alerts advises reduced size (0 disables)
+    #[serde(default = "default_max_consecutive_losses")]
+    pub max_consecutive_losses: i32,
+    /// Daily realized loss in R before get_risk_alerts advises stopping (0 disables)
+    #[serde(default = "default_daily_loss_limit_r")]
+    pub daily_loss_limit_r: f64,
+    /// Days a soft-deleted trade stays in the trash before it is purged at startup (0 keeps it)
+    #[serde(default)]
+    pub trash_retention_days: i32,
+    /// Days of API sync history kept when a new sync is recorded (0 keeps it all)
+    #[serde(default = "default_sync_history_retention_days")]
+    pub sync_history_retention_days: i32,
+    /// Most recent sync history entries kept per credential (0 keeps them all)
+    #[serde(default = "default_sync_history_max_entries")]
+    pub sync_history_max_entries: i32,
+    /// Run each auto-sync credential shortly after launch instead of one interval later
+    #[serde(default = "default_sync_on_startup")]
+    pub sync_on_startup: bool,
+    /// Reconnect a live mirror that received nothing for this many seconds (0 disables)
+    #[serde(default = "default_mirror_stale_timeout_secs")]
+    pub mirror_stale_timeout_secs: i32,
+    /// Ask for Touch ID before exchange API keys are read from the keychain (macOS)
+    #[serde(default)]
+    pub require_biometric_unlock: bool,
+    /// Proxy exchange traffic goes through, e.g. "socks5h://127.0.0.1:1080" (None connects
+    /// directly)
+    #[serde(default)]
+    pub proxy_url: Option<String>,
+    /// Closed trades whose PnL is within this many currency units of zero are break-even
+    #[serde(default = "default_breakeven_threshold")]
+    pub breakeven_threshold: f64,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -25,4 +56,43 @@ pub struct UpdateSettingsInput {
     pub currency: Option<String>,
     pub enable_position_monitor: Option<bool>,
     pub enable_api_connections: Option<bool>,
+    pub max_consecutive_losses: Option<i32>,
+    pub daily_loss_limit_r: Option<f64>,
+    pub trash_retention_days: Option<i32>,
+    pub sync_history_retention_days: Option<i32>,
+    pub sync_history_max_entries: Option<i32>,
+    pub sync_on_startup: Option<bool>,
+    pub mirror_stale_timeout_secs: Option<i32>,
+    pub require_biometric_unlock: Option<bool>,
+    /// An empty string removes the proxy
+    pub proxy_url: Option<String>,
+    pub breakeven_threshold: Option<f64>,
+}
+
+fn default_max_consecutive_losses() -> i32 {
+    3
+}
+
+fn default_daily_loss_limit_r() -> f64 {
+    3.0
+}
+
+fn default_sync_history_retention_days() -> i32 {
+    90
+}
+
+fn default_sync_history_max_entries() -> i32 {
+    1000
+}
+
+fn default_sync_on_startup() -> bool {
+    true
+}
+
+fn default_mirror_stale_timeout_secs() -> i32 {
+    90
+}
+
+fn default_breakeven_threshold() -> f64 {
+    0.5
 }
@@ -52,7 +52,24 @@ pub struct Trade {
 
     pub import_fingerprint: Option<String>,
     #[serde(default = "default_import_source")]
-    pub import_source: String, // USER_CREATED | API_IMPORT | CSV_IMPORT
+    pub import_source: String, // USER_CREATED | API_IMPORT | CSV_IMPORT | LIVE_MIRROR | POSITION_SYNC | DEMO
+
+    pub account_id: Option<String>, // None for trades not assigned to an account
+
+    #[serde(default)]
+    pub grade: Option<String>, // A | B | C | D, execution quality set on review
+    #[serde(default)]
+    pub pre_emotion: Option<String>, // See TRADE_EMOTIONS
+    #[serde(default)]
+    pub post_emotion: Option<String>,
+    /// Closed by liquidation (set by the live mirror)
+    #[serde(default)]
+    pub liquidated: bool,
+
+    /// Sum of linked funding payments (computed, not a trades column); net PnL is
+    /// total_pnl + funding_pnl
+    #[serde(default)]
+    pub funding_pnl: Option<f64>,
 
     pub created_at: i64,
     pub updated_at: i64,
@@ -92,14 +109,31 @@ pub struct CreateTradeInput {
     pub execution_quantity: Option<f64>,
     pub execution_one_r: Option<f64>,
     pub execution_potential_profit: Option<f64>,
+
+    pub account_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Changes bulk_update_trades applies to every selected trade; omitted fields are left alone
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BulkTradePatch {
+    pub exchange: Option<String>,
+    pub notes: Option<String>, // Replaces the existing notes
+    #[serde(default)]
+    pub add_tag_ids: Vec<String>,
+    #[serde(default)]
+    pub remove_tag_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TradeFilters {
     pub status: Option<String>,
     pub pair: Option<String>,
     pub start_date: Option<i64>,
     pub end_date: Option<i64>,
+    pub tag: Option<String>, // Tag name, case-insensitive
+    pub account_id: Option<String>,
     pub page: Option<i32>,
     pub limit: Option<i32>,
+    pub sort_by: Option<String>, // trade_date (default), close_date, total_pnl, pnl_in_r, pair or status
+    pub sort_dir: Option<String>, // "asc" or "desc" (default)
 }
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+use super::Trade;
+
+/// A follow-up trade tied to the trade it follows up on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeLink {
+    pub trade_id: String,
+    pub related_trade_id: String,
+    pub relation: String, // RE_ENTRY, HEDGE or SCALE_IN
+    pub created_at: i64,
+}
+
+/// Trades connected through links, reviewed and counted as one campaign
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeCampaign {
+    /// Id of the campaign's first trade
+    pub id: String,
+    pub trades: Vec<Trade>, // By trade_date
+    pub links: Vec<TradeLink>,
+    /// OPEN while any trade is open, otherwise WIN, LOSS or BE by the combined PnL
+    pub status: String,
+    pub total_pnl: f64,
+    /// Sum of the trades' pnl_in_r; None when no trade has one
+    pub pnl_in_r: Option<f64>,
+    pub start_date: i64,
+    /// Last close_date once every trade is closed
+    pub end_date: Option<i64>,
+}
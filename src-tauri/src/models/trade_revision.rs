@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// Snapshot pair recorded by the trades update trigger
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeRevision {
+    pub id: i64,
+    pub trade_id: String,
+    pub before: serde_json::Value,
+    pub after: serde_json::Value,
+    /// Columns whose value differs between the snapshots (updated_at is ignored)
+    pub changed_fields: Vec<String>,
+    pub changed_at: i64,
+}
@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::api::client::CancelFlag;
+
+/// Flags of the syncs currently running, per credential, so a sync started by the
+/// scheduler or the UI can be cancelled from another command
+#[derive(Default)]
+pub struct SyncCancellations {
+    running: Mutex<HashMap<String, Vec<CancelFlag>>>,
+}
+
+impl SyncCancellations {
+    /// Track a sync that is starting for the credential
+    pub fn register(&self, credential_id: &str) -> CancelFlag {
+        let flag: CancelFlag = Arc::new(AtomicBool::new(false));
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        running.entry(credential_id.to_string()).or_default().push(flag.clone());
+        flag
+    }
+
+    /// Stop tracking a sync once it has returned, cancelled or not
+    pub fn finish(&self, credential_id: &str, flag: &CancelFlag) {
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(flags) = running.get_mut(credential_id) {
+            flags.retain(|f| !Arc::ptr_eq(f, flag));
+            if flags.is_empty() {
+                running.remove(credential_id);
+            }
+        }
+    }
+
+    /// Ask every running sync of the credential to stop. Returns false when none is running.
+    pub fn cancel(&self, credential_id: &str) -> bool {
+        let running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        match running.get(credential_id) {
+            Some(flags) => {
+                for flag in flags {
+                    flag.store(true, Ordering::Relaxed);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_running_sync() {
+        let cancellations = SyncCancellations::default();
+        assert!(!cancellations.cancel("CRED"));
+
+        let manual = cancellations.register("CRED");
+        let auto = cancellations.register("CRED");
+        let other = cancellations.register("OTHER");
+
+        assert!(cancellations.cancel("CRED"));
+        assert!(manual.load(Ordering::Relaxed) && auto.load(Ordering::Relaxed));
+        assert!(!other.load(Ordering::Relaxed));
+
+        cancellations.finish("CRED", &manual);
+        cancellations.finish("CRED", &auto);
+        assert!(!cancellations.cancel("CRED"));
+    }
+}
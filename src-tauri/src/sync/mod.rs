@@ -1,3 +1,8 @@
+pub mod cancellation;
+pub mod position_builder;
+pub mod queue;
 pub mod scheduler;
 
+pub use cancellation::SyncCancellations;
+pub use queue::SyncQueue;
 pub use scheduler::SyncScheduler;
@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+
+use crate::api::{RawClosedPosition, RawTrade};
+use crate::models::SymbolFilters;
+
+/// How far apart a grouped position and the exchange's record of it may be timed
+const POSITION_MATCH_TOLERANCE_MS: i64 = 60_000;
+
+/// Fills that make up one journal trade
+#[derive(Debug, Clone)]
+pub struct FillGroup {
+    /// The group as a single fill: weighted average entry/exit prices, entry quantity,
+    /// summed PnL and fees
+    pub trade: RawTrade,
+    /// (price, qty) of each entry fill; empty for a lone exit fill
+    pub entries: Vec<(f64, f64)>,
+    /// (price, qty) of each exit fill
+    pub exits: Vec<(f64, f64)>,
+    /// The exit fills themselves, to recognise fills imported one by one before grouping
+    pub exit_fills: Vec<RawTrade>,
+}
+
+impl FillGroup {
+    /// An exit whose entries are outside the fetched range, imported on its own
+    fn lone(fill: RawTrade) -> Self {
+        Self {
+            trade: fill.clone(),
+            entries: Vec::new(),
+            exits: Vec::new(),
+            exit_fills: vec![fill],
+        }
+    }
+
+    /// Entries and exits were both seen and grouped into a position
+    pub fn is_position(&self) -> bool {
+        !self.entries.is_empty()
+    }
+
+    /// entries JSON: [{price, percent}] where percent is integer 0-100
+    pub fn entries_json(&self) -> String {
+        let total: f64 = self.entries.iter().map(|(_, qty)| qty).sum();
+        let entries: Vec<serde_json::Value> = self
+            .entries
+            .iter()
+            .map(|(price, qty)| {
+                let pct = if total > 0.0 { (qty / total * 100.0).round() as i64 } else { 0 };
+                serde_json::json!({"price": price, "percent": pct})
+            })
+            .collect();
+        serde_json::to_string(&entries).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    /// exits JSON: [{price, percent}] where percent is 0-100 of the entry quantity
+    pub fn exits_json(&self) -> String {
+        let total: f64 = self.entries.iter().map(|(_, qty)| qty).sum();
+        let exits: Vec<serde_json::Value> = self
+            .exits
+            .iter()
+            .map(|(price, qty)| {
+                let pct = if total > 0.0 { qty / total * 100.0 } else { 0.0 };
+                serde_json::json!({"price": price, "percent": pct})
+            })
+            .collect();
+        serde_json::to_string(&exits).unwrap_or_else(|_| "[]".to_string())
+    }
+}
+
+struct OpenPosition {
+    entries: Vec<RawTrade>,
+    exits: Vec<RawTrade>,
+    entry_qty: f64,
+    exit_qty: f64,
+}
+
+impl OpenPosition {
+    fn new(entry: RawTrade) -> Self {
+        Self {
+            entry_qty: entry.quantity,
+            entries: vec![entry],
+            exits: Vec::new(),
+            exit_qty: 0.0,
+        }
+    }
+
+    /// Fully closed when exit qty >= entry qty (with 0.1% tolerance)
+    fn is_closed(&self) -> bool {
+        self.entry_qty > 0.0 && self.exit_qty >= self.entry_qty * 0.999
+    }
+
+    fn finalize(self) -> FillGroup {
+        let weighted = |fills: &[RawTrade], qty: f64| {
+            if qty > 0.0 {
+                fills.iter().map(|f| f.entry_price * f.quantity).sum::<f64>() / qty
+            } else {
+                0.0
+            }
+        };
+        let first = &self.entries[0];
+        let all = || self.entries.iter().chain(&self.exits);
+
+        let trade = RawTrade {
+            exchange_trade_id: first.exchange_trade_id.clone(),
+            exchange_order_id: first.exchange_order_id.clone(),
+            symbol: first.symbol.clone(),
+            side: first.side.clone(),
+            position_side: first.position_side.clone(),
+            quantity: self.entry_qty,
+            entry_price: weighted(&self.entries, self.entry_qty),
+            exit_price: Some(weighted(&self.exits, self.exit_qty)),
+            pnl: all().map(|f| f.pnl).sum(),
+            fee: all().map(|f| f.fee).sum(),
+            leverage: all().find_map(|f| f.leverage),
+            timestamp: first.timestamp,
+            close_timestamp: self.exits.last().map(|f| f.timestamp),
+            reduce_only: None,
+            raw_json: format!("[{}]", all().map(|f| f.raw_json.as_str()).collect::<Vec<_>>().join(",")),
+        };
+
+        FillGroup {
+            trade,
+            entries: self.entries.iter().map(|f| (f.entry_price, f.quantity)).collect(),
+            exits: self.exits.iter().map(|f| (f.entry_price, f.quantity)).collect(),
+            exit_fills: self.exits,
+        }
+    }
+}
+
+fn opposite_side(position_side: &str) -> &'static str {
+    if position_side == "LONG" { "SHORT" } else { "LONG" }
+}
+
+/// Group API fills into positions, the way the BloFin/BingX CSV importers group orders:
+/// entries on a symbol and side average into one position until exits close it.
+///
+/// Nothing is dropped: exits whose entries fall before the fetched range, and exits of
+/// positions still open at the end of it, come back as lone fills. Entries of positions
+/// still open are left out until a later sync sees them closed.
+pub fn group_fills_into_positions(mut fills: Vec<RawTrade>) -> Vec<FillGroup> {
+    // Process chronologically so position grouping works correctly
+    fills.sort_by_key(|f| f.timestamp);
+
+    let mut open: HashMap<(String, String), OpenPosition> = HashMap::new();
+    let mut groups: Vec<FillGroup> = Vec::new();
+
+    for fill in fills {
+        let same = (fill.symbol.clone(), fill.position_side.clone());
+        let against = (fill.symbol.clone(), opposite_side(&fill.position_side).to_string());
+
+        // Hedge mode fills say whether they close; one-way fills close when they go
+        // against the open position, and carry the side of the order rather than the position
+        let closing = match fill.reduce_only {
+            Some(true) => [same.clone(), against.clone()].into_iter().find(|k| open.contains_key(k)),
+            Some(false) => None,
+            None => open.contains_key(&against).then_some(against),
+        };
+
+        match closing {
+            Some(key) => {
+                let pos = open.get_mut(&key).expect("key was found open");
+                pos.exit_qty += fill.quantity;
+                pos.exits.push(fill);
+
+                if pos.is_closed() {
+                    let pos = open.remove(&key).expect("key was found open");
+                    groups.push(pos.finalize());
+                }
+            }
+            // Closing fill with no matching open position
+            None if fill.reduce_only == Some(true) => groups.push(FillGroup::lone(fill)),
+            None => match open.get_mut(&same) {
+                Some(pos) => {
+                    // Add to existing open position (averaging in)
+                    pos.entry_qty += fill.quantity;
+                    pos.entries.push(fill);
+                }
+                None => {
+                    open.insert(same, OpenPosition::new(fill));
+                }
+            },
+        }
+    }
+
+    // Realized partial exits of positions still open are not lost
+    let mut still_open: Vec<OpenPosition> = open.into_values().collect();
+    still_open.sort_by_key(|pos| pos.entries[0].timestamp);
+    groups.extend(still_open.into_iter().flat_map(|pos| pos.exits).map(FillGroup::lone));
+
+    groups
+}
+
+/// "BTC-USDT-SWAP" from trade history and "BTC-USDT" from position history are one instrument
+fn same_instrument(a: &str, b: &str) -> bool {
+    let normalize = |s: &str| {
+        let s = SymbolFilters::normalize(s);
+        s.strip_suffix("SWAP").map(str::to_string).unwrap_or(s)
+    };
+    normalize(a) == normalize(b)
+}
+
+/// Take realized PnL from the exchange's position history for grouped positions, for
+/// exchanges whose fills carry none (BloFin). Each closed position is used at most once,
+/// matched on symbol, side when known, and close time; positions without a match keep
+/// the PnL summed from their fills.
+pub fn backfill_realized_pnl(groups: &mut [FillGroup], closed_positions: &[RawClosedPosition]) {
+    let mut used = vec![false; closed_positions.len()];
+
+    for group in groups.iter_mut().filter(|g| g.is_position()) {
+        let Some(closed_at) = group.trade.close_timestamp else {
+            continue;
+        };
+
+        let best = closed_positions
+            .iter()
+            .enumerate()
+            .filter(|(i, closed)| {
+                !used[*i]
+                    && same_instrument(&closed.symbol, &group.trade.symbol)
+                    && closed.position_side.as_ref().is_none_or(|side| *side == group.trade.position_side)
+                    && (closed.close_timestamp - closed_at).abs() <= POSITION_MATCH_TOLERANCE_MS
+                    && closed.open_timestamp <= group.trade.timestamp + POSITION_MATCH_TOLERANCE_MS
+            })
+            .min_by_key(|(_, closed)| (closed.close_timestamp - closed_at).abs());
+
+        if let Some((i, closed)) = best {
+            used[i] = true;
+            group.trade.pnl = closed.realized_pnl;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(clippy::too_many_arguments)]
+    fn fill(id: &str, side: &str, position_side: &str, reduce_only: Option<bool>, price: f64, qty: f64, pnl: f64, ts: i64) -> RawTrade {
+        RawTrade {
+            exchange_trade_id: id.to_string(),
+            exchange_order_id: format!("O{}", id),
+            symbol: "BTCUSDT".to_string(),
+            side: side.to_string(),
+            position_side: position_side.to_string(),
+            quantity: qty,
+            entry_price: price,
+            exit_price: None,
+            pnl,
+            fee: 1.0,
+            leverage: None,
+            timestamp: ts,
+            close_timestamp: None,
+            reduce_only,
+            raw_json: "{}".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_groups_scaled_position() {
+        let fills = vec![
+            fill("3", "sell", "LONG", Some(true), 110.0, 1.0, 10.0, 3),
+            fill("1", "buy", "LONG", Some(false), 100.0, 1.0, 0.0, 1),
+            fill("2", "buy", "LONG", Some(false), 90.0, 1.0, 0.0, 2),
+            fill("4", "sell", "LONG", Some(true), 120.0, 1.0, 25.0, 4),
+        ];
+
+        let groups = group_fills_into_positions(fills);
+        assert_eq!(groups.len(), 1);
+        let position = &groups[0];
+        assert!(position.is_position());
+        assert_eq!(position.trade.exchange_trade_id, "1");
+        assert_eq!(position.trade.quantity, 2.0);
+        assert_eq!(position.trade.entry_price, 95.0);
+        assert_eq!(position.trade.exit_price, Some(115.0));
+        assert_eq!(position.trade.pnl, 35.0);
+        assert_eq!(position.trade.fee, 4.0);
+        assert_eq!((position.trade.timestamp, position.trade.close_timestamp), (1, Some(4)));
+        assert_eq!(position.entries_json(), r#"[{"percent":50,"price":100.0},{"percent":50,"price":90.0}]"#);
+        assert_eq!(position.exit_fills.len(), 2);
+    }
+
+    #[test]
+    fn test_one_way_fills_close_against_open_position() {
+        // One-way mode: side-inferred position_side, no reduce flag
+        let fills = vec![
+            fill("1", "buy", "LONG", None, 100.0, 2.0, 0.0, 1),
+            fill("2", "sell", "SHORT", None, 105.0, 2.0, 10.0, 2),
+            fill("3", "sell", "SHORT", None, 104.0, 1.0, 0.0, 3),
+            fill("4", "buy", "LONG", None, 100.0, 1.0, 4.0, 4),
+        ];
+
+        let groups = group_fills_into_positions(fills);
+        assert_eq!(groups.len(), 2);
+        assert_eq!((groups[0].trade.position_side.as_str(), groups[0].trade.pnl), ("LONG", 10.0));
+        assert_eq!((groups[1].trade.position_side.as_str(), groups[1].trade.pnl), ("SHORT", 4.0));
+    }
+
+    #[test]
+    fn test_unmatched_exits_are_kept_as_lone_fills() {
+        let fills = vec![
+            // Entry before the fetched range
+            fill("1", "sell", "LONG", Some(true), 110.0, 1.0, 10.0, 1),
+            // Still open: the entry waits, the partial exit's PnL is kept
+            fill("2", "sell", "SHORT", Some(false), 100.0, 2.0, 0.0, 2),
+            fill("3", "buy", "SHORT", Some(true), 95.0, 1.0, 5.0, 3),
+        ];
+
+        let groups = group_fills_into_positions(fills);
+        let ids: Vec<&str> = groups.iter().map(|g| g.trade.exchange_trade_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "3"]);
+        assert!(groups.iter().all(|g| !g.is_position()));
+    }
+
+    #[test]
+    fn test_backfill_realized_pnl() {
+        let closed = |id: &str, side: Option<&str>, pnl: f64, close: i64| RawClosedPosition {
+            exchange_position_id: id.to_string(),
+            symbol: "BTC-USDT".to_string(),
+            position_side: side.map(str::to_string),
+            realized_pnl: pnl,
+            open_timestamp: 1_000,
+            close_timestamp: close,
+        };
+        // BloFin fills carry no PnL
+        let mut groups = group_fills_into_positions(vec![
+            fill("1", "buy", "LONG", Some(false), 100.0, 1.0, 0.0, 1_000),
+            fill("2", "sell", "LONG", Some(true), 110.0, 1.0, 0.0, 500_000),
+            fill("3", "sell", "SHORT", Some(false), 100.0, 1.0, 0.0, 600_000),
+            fill("4", "buy", "SHORT", Some(true), 90.0, 1.0, 0.0, 900_000),
+        ]);
+
+        backfill_realized_pnl(&mut groups, &[
+            closed("short", Some("SHORT"), 9.5, 900_500),
+            closed("other", Some("SHORT"), -3.0, 510_000),
+            closed("long", None, 9.8, 501_000),
+        ]);
+
+        let pnls: Vec<f64> = groups.iter().map(|g| g.trade.pnl).collect();
+        assert_eq!(pnls, vec![9.8, 9.5]);
+    }
+}
@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+/// One lock per credential so a manual sync and an auto-sync of the same credential
+/// never run at the same time. Waiters are served in arrival order.
+#[derive(Clone, Default)]
+pub struct SyncQueue {
+    locks: Arc<Mutex<HashMap<String, Arc<AsyncMutex<()>>>>>,
+}
+
+impl SyncQueue {
+    /// Wait until no other sync of the credential is running. The sync holds the
+    /// returned guard until it is done.
+    pub async fn acquire(&self, credential_id: &str) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+            locks.entry(credential_id.to_string()).or_default().clone()
+        };
+        lock.lock_owned().await
+    }
+
+    /// Whether a sync of the credential is running right now
+    pub fn is_busy(&self, credential_id: &str) -> bool {
+        let locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+        locks.get(credential_id).is_some_and(|lock| lock.try_lock().is_err())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_one_sync_per_credential() {
+        let queue = SyncQueue::default();
+
+        let running = queue.acquire("CRED").await;
+        assert!(queue.is_busy("CRED"));
+        // Other credentials are not blocked
+        let _other = queue.acquire("OTHER").await;
+
+        let waiting = {
+            let queue = queue.clone();
+            tokio::spawn(async move {
+                let _guard = queue.acquire("CRED").await;
+            })
+        };
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert!(!waiting.is_finished());
+
+        drop(running);
+        waiting.await.unwrap();
+        assert!(!queue.is_busy("CRED"));
+    }
+}
@@ -1,17 +1,95 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
+use rand::Rng;
 use tauri::{AppHandle, Manager};
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 
+use crate::api::error::ApiError;
+use crate::api::retry::backoff_delay;
+use crate::commands::app_lock::notification_body;
 use crate::db::Database;
-use crate::models::{SyncConfig, ApiCredentialSafe};
+use crate::models::{SyncConfig, ApiCredentialSafe, SchedulerTaskStatus};
+use crate::sync::{SyncCancellations, SyncQueue};
+
+/// Attempts per tick before a failed auto-sync is reported
+const SYNC_MAX_ATTEMPTS: u32 = 3;
+const SYNC_RETRY_BASE_DELAY: Duration = Duration::from_secs(30);
+const SYNC_RETRY_MAX_DELAY: Duration = Duration::from_secs(10 * 60);
+/// Upper bound on the random delay before a task's first tick
+const MAX_START_JITTER: Duration = Duration::from_secs(60);
+/// Failed ticks in a row after which auto-sync is turned off for the credential
+const CIRCUIT_BREAKER_THRESHOLD: i64 = 5;
+/// Time given to the app to finish launching before the catch-up sync
+const STARTUP_SYNC_DELAY: Duration = Duration::from_secs(15);
+
+/// Random delay before a task's first tick, so credentials sharing an interval don't
+/// all hit the database and the exchanges at once. Never longer than the interval itself.
+fn start_jitter(interval: Duration) -> Duration {
+    MAX_START_JITTER.min(interval).mul_f64(rand::thread_rng().gen_range(0.0..1.0))
+}
+
+/// Delay before a task's first tick. At launch (`startup_sync` is Some) the task either
+/// catches up shortly after startup or waits a full interval, as settings.sync_on_startup
+/// says. Reloads (None) tick right away.
+fn first_tick_delay(interval: Duration, startup_sync: Option<bool>) -> Duration {
+    let base = match startup_sync {
+        Some(true) => STARTUP_SYNC_DELAY,
+        Some(false) => interval,
+        None => Duration::ZERO,
+    };
+    base + start_jitter(interval)
+}
+
+/// Count a failed auto-sync tick. Returns true when this failure tripped the circuit
+/// breaker: auto-sync is then off for the credential, with `error` kept as the reason.
+pub(crate) fn record_sync_failure(
+    conn: &rusqlite::Connection,
+    credential_id: &str,
+    error: &str,
+    now: i64,
+) -> Result<bool, String> {
+    conn.execute(
+        "UPDATE api_credentials SET consecutive_sync_failures = consecutive_sync_failures + 1 WHERE id = ?",
+        [credential_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let tripped = conn
+        .execute(
+            "UPDATE api_credentials
+             SET auto_sync_enabled = 0, auto_sync_disabled_reason = ?, auto_sync_disabled_at = ?, updated_at = ?
+             WHERE id = ? AND auto_sync_enabled = 1 AND consecutive_sync_failures >= ?",
+            rusqlite::params![error, now, now, credential_id, CIRCUIT_BREAKER_THRESHOLD],
+        )
+        .map_err(|e| e.to_string())?;
+
+    Ok(tripped > 0)
+}
+
+/// Clear a credential's failure count and circuit breaker reason
+pub(crate) fn reset_sync_failures(conn: &rusqlite::Connection, credential_id: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE api_credentials
+         SET consecutive_sync_failures = 0, auto_sync_disabled_reason = NULL, auto_sync_disabled_at = NULL
+         WHERE id = ?",
+        [credential_id],
+    )?;
+    Ok(())
+}
 
 /// Background sync scheduler
 #[derive(Clone)]
 pub struct SyncScheduler {
     app_handle: AppHandle,
     tasks: Arc<RwLock<Vec<JoinHandle<()>>>>,
+    /// Credentials whose task keeps running but skips its ticks; kept across reloads
+    paused: Arc<RwLock<HashSet<String>>>,
+    /// Shared with manual syncs so each credential syncs one at a time
+    queue: SyncQueue,
+    /// Per-credential task state, replaced on reload
+    status: Arc<RwLock<HashMap<String, SchedulerTaskStatus>>>,
 }
 
 impl SyncScheduler {
@@ -20,15 +98,63 @@ impl SyncScheduler {
         Self {
             app_handle,
             tasks: Arc::new(RwLock::new(Vec::new())),
+            paused: Arc::new(RwLock::new(HashSet::new())),
+            queue: SyncQueue::default(),
+            status: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
+    /// Queue of syncs per credential, for syncs started outside the scheduler
+    pub fn queue(&self) -> &SyncQueue {
+        &self.queue
+    }
+
+    /// Skip a credential's auto-sync ticks until resumed, leaving other tasks alone
+    pub async fn pause(&self, credential_id: &str) {
+        self.paused.write().await.insert(credential_id.to_string());
+    }
+
+    /// Let a paused credential sync again from its next tick
+    pub async fn resume(&self, credential_id: &str) {
+        self.paused.write().await.remove(credential_id);
+    }
+
+    pub async fn is_paused(&self, credential_id: &str) -> bool {
+        self.paused.read().await.contains(credential_id)
+    }
+
+    /// State of every scheduled task, with running and paused read live
+    pub async fn status(&self) -> Vec<SchedulerTaskStatus> {
+        let paused = self.paused.read().await;
+        let mut statuses: Vec<SchedulerTaskStatus> = self
+            .status
+            .read()
+            .await
+            .values()
+            .map(|status| SchedulerTaskStatus {
+                running: self.queue.is_busy(&status.credential_id),
+                paused: paused.contains(&status.credential_id),
+                ..status.clone()
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.label.cmp(&b.label));
+        statuses
+    }
+
     /// Start the scheduler - scans for credentials and starts background tasks
     pub async fn start(&self) {
         println!("Starting background sync scheduler...");
 
+        let startup_sync = match self.check_sync_on_startup(&self.app_handle.state::<Database>()) {
+            Ok(enabled) => enabled,
+            Err(e) => {
+                eprintln!("Failed to read startup sync setting: {}", e);
+                true
+            }
+        };
+
         // Load credentials and start tasks
-        if let Err(e) = self.reload_tasks().await {
+        if let Err(e) = self.load_tasks(Some(startup_sync)).await {
             eprintln!("Failed to start sync scheduler: {}", e);
         }
     }
@@ -36,6 +162,12 @@ impl SyncScheduler {
     /// Reload all sync tasks (stop existing, start new ones)
     pub async fn reload_tasks(&self) -> Result<(), String> {
         println!("Reloading sync tasks...");
+        self.load_tasks(None).await
+    }
+
+    /// Replace the running tasks with one per auto-sync credential. `startup_sync` is set
+    /// only at launch, see first_tick_delay.
+    async fn load_tasks(&self, startup_sync: Option<bool>) -> Result<(), String> {
 
         // Stop all existing tasks
         self.stop_all_tasks().await;
@@ -57,7 +189,8 @@ impl SyncScheduler {
 
         // Start a task for each credential
         for cred in credentials {
-            self.start_task_for_credential(cred).await;
+            let offset = first_tick_delay(Duration::from_secs(cred.auto_sync_interval as u64), startup_sync);
+            self.start_task_for_credential(cred, offset).await;
         }
 
         Ok(())
@@ -65,7 +198,7 @@ impl SyncScheduler {
 
     /// Check if API connections feature is enabled in settings
     fn check_api_connections_feature(&self, db: &Database) -> Result<bool, String> {
-        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        let conn = db.pool.get().map_err(|e| e.to_string())?;
 
         let enabled: i32 = conn
             .query_row(
@@ -78,14 +211,30 @@ impl SyncScheduler {
         Ok(enabled == 1)
     }
 
+    /// Check whether auto-sync credentials should catch up right after launch
+    fn check_sync_on_startup(&self, db: &Database) -> Result<bool, String> {
+        let conn = db.pool.get().map_err(|e| e.to_string())?;
+
+        let enabled: i32 = conn
+            .query_row(
+                "SELECT sync_on_startup FROM settings WHERE id = 1",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+
+        Ok(enabled == 1)
+    }
+
     /// Get all credentials that have auto-sync enabled and are active
     fn get_auto_sync_credentials(&self, db: &Database) -> Result<Vec<ApiCredentialSafe>, String> {
-        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        let conn = db.pool.get().map_err(|e| e.to_string())?;
 
         let mut stmt = conn
             .prepare(
                 "SELECT id, exchange, label, api_key, is_active, last_sync_timestamp,
-                        auto_sync_enabled, auto_sync_interval, live_mirror_enabled, created_at, updated_at
+                        auto_sync_enabled, auto_sync_interval, live_mirror_enabled, created_at, updated_at,
+                        auto_sync_lookback_days, is_default, storage_backend, environment, base_url
                  FROM api_credentials
                  WHERE is_active = 1 AND auto_sync_enabled = 1
                  ORDER BY created_at DESC"
@@ -106,7 +255,14 @@ impl SyncScheduler {
                     last_sync_timestamp: row.get(5)?,
                     auto_sync_enabled: row.get::<_, i32>(6)? == 1,
                     auto_sync_interval: row.get(7)?,
+                    auto_sync_lookback_days: row.get(11)?,
                     live_mirror_enabled: row.get::<_, i32>(8)? == 1,
+                    is_default: row.get::<_, i32>(12)? == 1,
+                    storage_backend: row.get(13)?,
+                    environment: row.get(14)?,
+                    base_url: row.get(15)?,
+                    auto_sync_paused: false,
+                    auto_sync_disabled_reason: None,
                     created_at: row.get(9)?,
                     updated_at: row.get(10)?,
                 })
@@ -117,35 +273,114 @@ impl SyncScheduler {
     }
 
     /// Start a background task for a specific credential
-    async fn start_task_for_credential(&self, credential: ApiCredentialSafe) {
+    async fn start_task_for_credential(&self, credential: ApiCredentialSafe, offset: Duration) {
         let app_handle = self.app_handle.clone();
         let credential_id = credential.id.clone();
         let interval_secs = credential.auto_sync_interval;
         let exchange = credential.exchange.clone();
+        let paused = self.paused.clone();
+        let queue = self.queue.clone();
+        let status = self.status.clone();
+
+        let period = Duration::from_secs(interval_secs as u64);
+
+        status.write().await.insert(
+            credential_id.clone(),
+            SchedulerTaskStatus {
+                credential_id: credential_id.clone(),
+                exchange: exchange.clone(),
+                label: credential.label.clone(),
+                interval_secs,
+                running: false,
+                paused: false,
+                last_run: None,
+                last_result: None,
+                last_error: None,
+                last_imported: None,
+                next_run: Some(chrono::Utc::now().timestamp() + offset.as_secs() as i64),
+            },
+        );
 
         println!(
-            "Starting auto-sync task for {} ({}) - interval: {}s",
-            exchange, credential_id, interval_secs
+            "Starting auto-sync task for {} ({}) - interval: {}s, first tick in {:?}",
+            exchange, credential_id, interval_secs, offset
         );
 
         let handle = tokio::spawn(async move {
-            let mut interval = tokio::time::interval(Duration::from_secs(interval_secs as u64));
+            // Offset the first tick so tasks with the same interval stay out of step,
+            // and so launch either catches up or waits a full interval
+            let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + offset, period);
+            // Retries can outlast the interval; don't fire the missed ticks back to back
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
             loop {
                 interval.tick().await;
+                let tick_time = chrono::Utc::now().timestamp();
+
+                if paused.read().await.contains(&credential_id) {
+                    println!("Auto-sync paused for {} ({}) - skipping tick", exchange, credential_id);
+                    Self::record_tick(&status, &credential_id, tick_time, interval_secs, None).await;
+                    continue;
+                }
+
+                // A manual sync already covers this tick, don't queue a second one behind it
+                if queue.is_busy(&credential_id) {
+                    println!("Sync already running for {} ({}) - skipping tick", exchange, credential_id);
+                    Self::record_tick(&status, &credential_id, tick_time, interval_secs, None).await;
+                    continue;
+                }
 
                 println!("Auto-sync tick for {} ({})", exchange, credential_id);
 
                 // Perform sync
-                if let Err(e) = Self::perform_sync(&app_handle, &credential_id).await {
-                    eprintln!("Auto-sync failed for {}: {}", credential_id, e);
+                let result = Self::perform_sync_with_retry(&app_handle, &credential_id).await;
+                Self::record_tick(&status, &credential_id, tick_time, interval_secs, Some(&result)).await;
 
-                    // Send notification on error
-                    if let Err(ne) = Self::send_error_notification(&app_handle, &exchange, &e).await {
-                        eprintln!("Failed to send notification: {}", ne);
+                match result {
+                    // Cancelled from the UI on purpose, nothing to report
+                    Err(e) if e == ApiError::Cancelled.to_string() => {
+                        println!("Auto-sync cancelled for {}", credential_id);
+                    }
+                    Err(e) => {
+                        eprintln!("Auto-sync failed for {}: {}", credential_id, e);
+
+                        let tripped = Self::with_conn(&app_handle, |conn| {
+                            record_sync_failure(conn, &credential_id, &e, chrono::Utc::now().timestamp())
+                        })
+                        .unwrap_or_else(|ce| {
+                            eprintln!("Failed to record sync failure for {}: {}", credential_id, ce);
+                            false
+                        });
+
+                        // Send notification on error
+                        let notified = if tripped {
+                            Self::send_disabled_notification(&app_handle, &exchange, &e).await
+                        } else {
+                            Self::send_error_notification(&app_handle, &exchange, &e).await
+                        };
+                        if let Err(ne) = notified {
+                            eprintln!("Failed to send notification: {}", ne);
+                        }
+
+                        if tripped {
+                            eprintln!(
+                                "Auto-sync disabled for {} after {} consecutive failures",
+                                credential_id, CIRCUIT_BREAKER_THRESHOLD
+                            );
+                            if let Some(task) = status.write().await.get_mut(&credential_id) {
+                                task.next_run = None;
+                            }
+                            break;
+                        }
+                    }
+                    Ok(_) => {
+                        if let Err(e) = Self::with_conn(&app_handle, |conn| {
+                            reset_sync_failures(conn, &credential_id).map_err(|e| e.to_string())
+                        }) {
+                            eprintln!("Failed to reset sync failures for {}: {}", credential_id, e);
+                        }
+                        println!("Auto-sync completed successfully for {}", credential_id);
                     }
-                } else {
-                    println!("Auto-sync completed successfully for {}", credential_id);
                 }
             }
         });
@@ -155,13 +390,75 @@ impl SyncScheduler {
         tasks.push(handle);
     }
 
-    /// Perform a sync for a credential
-    async fn perform_sync(app_handle: &AppHandle, credential_id: &str) -> Result<(), String> {
+    fn with_conn<T>(
+        app_handle: &AppHandle,
+        f: impl FnOnce(&rusqlite::Connection) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let db = app_handle.state::<Database>();
+        let conn = db.pool.get().map_err(|e| e.to_string())?;
+        f(&conn)
+    }
+
+    /// Update a task's status after a tick. `result` is None when the tick was skipped.
+    async fn record_tick(
+        status: &RwLock<HashMap<String, SchedulerTaskStatus>>,
+        credential_id: &str,
+        tick_time: i64,
+        interval_secs: i64,
+        result: Option<&Result<i32, String>>,
+    ) {
+        let mut status = status.write().await;
+        let Some(task) = status.get_mut(credential_id) else {
+            return;
+        };
+
+        // Ticks are delayed, not bunched, when a sync outlasts the interval
+        task.next_run = Some((tick_time + interval_secs).max(chrono::Utc::now().timestamp()));
+
+        if let Some(result) = result {
+            task.last_run = Some(tick_time);
+            match result {
+                Ok(imported) => {
+                    task.last_result = Some("SUCCESS".to_string());
+                    task.last_error = None;
+                    task.last_imported = Some(*imported);
+                }
+                Err(e) => {
+                    let cancelled = *e == ApiError::Cancelled.to_string();
+                    task.last_result = Some(if cancelled { "CANCELLED" } else { "FAILED" }.to_string());
+                    task.last_error = (!cancelled).then(|| e.clone());
+                    task.last_imported = None;
+                }
+            }
+        }
+    }
+
+    /// Perform a sync, retrying failures with exponential backoff before giving up
+    async fn perform_sync_with_retry(app_handle: &AppHandle, credential_id: &str) -> Result<i32, String> {
+        let mut attempt = 0;
+        loop {
+            match Self::perform_sync(app_handle, credential_id).await {
+                Err(e) if e != ApiError::Cancelled.to_string() && attempt + 1 < SYNC_MAX_ATTEMPTS => {
+                    let delay = backoff_delay(attempt, SYNC_RETRY_BASE_DELAY, SYNC_RETRY_MAX_DELAY);
+                    eprintln!(
+                        "Auto-sync attempt {} failed for {}: {} - retrying in {:?}",
+                        attempt + 1, credential_id, e, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Perform a sync for a credential, returning how many trades were imported
+    async fn perform_sync(app_handle: &AppHandle, credential_id: &str) -> Result<i32, String> {
         let db = app_handle.state::<Database>();
 
         // Check if API connections feature is still enabled before syncing
-        let enabled = {
-            let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        let (enabled, last_sync_timestamp, lookback_days, reconcile) = {
+            let conn = db.pool.get().map_err(|e| e.to_string())?;
             let enabled: i32 = conn
                 .query_row(
                     "SELECT enable_api_connections FROM settings WHERE id = 1",
@@ -169,18 +466,32 @@ impl SyncScheduler {
                     |row| row.get(0),
                 )
                 .map_err(|e| e.to_string())?;
-            enabled
+            let (last_sync_timestamp, lookback_days, exchange, live_mirror_enabled): (Option<i64>, Option<i64>, String, i32) = conn
+                .query_row(
+                    "SELECT last_sync_timestamp, auto_sync_lookback_days, exchange, live_mirror_enabled FROM api_credentials WHERE id = ?",
+                    [credential_id],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+                )
+                .map_err(|e| e.to_string())?;
+            // Live mirroring already keeps OPEN trades in step with positions
+            let reconcile = live_mirror_enabled == 0 && crate::commands::positions::supports_positions(&exchange);
+            (enabled, last_sync_timestamp, lookback_days, reconcile)
         }; // conn is dropped here
 
         if enabled == 0 {
             println!("API connections feature is disabled - skipping sync for {}", credential_id);
-            return Ok(());
+            return Ok(0);
         }
 
         // Create sync config with auto_sync flag
         let config = SyncConfig {
             credential_id: credential_id.to_string(),
-            start_date: None, // Will use last_sync_timestamp for smart sync
+            // Only fetch what changed since the last sync, bounded by the look-back window
+            start_date: crate::commands::api_sync::auto_sync_start_time(
+                last_sync_timestamp,
+                lookback_days,
+                chrono::Utc::now().timestamp(),
+            ),
             end_date: None,   // Current time
             skip_duplicates: true,
             is_auto_sync: true,
@@ -189,8 +500,22 @@ impl SyncScheduler {
         // Call the sync command
         let result = crate::commands::sync_exchange_trades(
             db,
+            app_handle.state::<SyncCancellations>(),
+            app_handle.state::<SyncScheduler>(),
             config
-        ).await?;
+        ).await.map_err(|e| e.to_string())?;
+
+        // Open positions are secondary: a failure is logged but does not fail the sync
+        if reconcile {
+            match crate::commands::positions::reconcile_credential_positions(&app_handle.state::<Database>(), credential_id).await {
+                Ok(r) if !r.opened.is_empty() || !r.closed.is_empty() => println!(
+                    "Reconciled positions for {}: {} opened, {} closed",
+                    credential_id, r.opened.len(), r.closed.len()
+                ),
+                Ok(_) => {}
+                Err(e) => eprintln!("Position reconciliation failed for {}: {}", credential_id, e),
+            }
+        }
 
         // Only send notification if new trades were imported
         if result.imported > 0 {
@@ -201,7 +526,7 @@ impl SyncScheduler {
             ).await?;
         }
 
-        Ok(())
+        Ok(result.imported)
     }
 
     /// Send success notification
@@ -219,7 +544,7 @@ impl SyncScheduler {
             .notification()
             .builder()
             .title(title)
-            .body(&body)
+            .body(notification_body(app_handle, &body))
             .show()
             .map_err(|e| e.to_string())?;
 
@@ -241,7 +566,32 @@ impl SyncScheduler {
             .notification()
             .builder()
             .title(title)
-            .body(&body)
+            .body(notification_body(app_handle, &body))
+            .show()
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    /// Send notification that the circuit breaker turned auto-sync off
+    async fn send_disabled_notification(
+        app_handle: &AppHandle,
+        exchange: &str,
+        error: &str,
+    ) -> Result<(), String> {
+        use tauri_plugin_notification::NotificationExt;
+
+        let title = "Auto-Sync Disabled";
+        let body = format!(
+            "{} failed {} times in a row and auto-sync was turned off: {}",
+            exchange, CIRCUIT_BREAKER_THRESHOLD, error
+        );
+
+        app_handle
+            .notification()
+            .builder()
+            .title(title)
+            .body(notification_body(app_handle, &body))
             .show()
             .map_err(|e| e.to_string())?;
 
@@ -256,6 +606,7 @@ impl SyncScheduler {
         for task in tasks.drain(..) {
             task.abort();
         }
+        self.status.write().await.clear();
 
         println!("All sync tasks stopped");
     }
@@ -273,3 +624,66 @@ impl Drop for SyncScheduler {
         println!("SyncScheduler dropped");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::test_conn;
+
+    #[test]
+    fn test_start_jitter_bounds() {
+        for _ in 0..100 {
+            assert!(start_jitter(Duration::from_secs(3600)) < MAX_START_JITTER);
+            // Short intervals never wait longer than one period
+            assert!(start_jitter(Duration::from_secs(10)) < Duration::from_secs(10));
+        }
+        assert_eq!(start_jitter(Duration::ZERO), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_circuit_breaker_trips_after_threshold() {
+        let conn = test_conn();
+        conn.execute(
+            "INSERT INTO api_credentials (id, exchange, label, api_key, api_secret, auto_sync_enabled, created_at, updated_at)
+             VALUES ('C1', 'bitget', 'Main', 'key', 'secret', 1, 0, 0)",
+            [],
+        )
+        .unwrap();
+        let state = |conn: &rusqlite::Connection| -> (i32, i64, Option<String>) {
+            conn.query_row(
+                "SELECT auto_sync_enabled, consecutive_sync_failures, auto_sync_disabled_reason FROM api_credentials WHERE id = 'C1'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap()
+        };
+
+        // A success in between starts the count again
+        for _ in 0..CIRCUIT_BREAKER_THRESHOLD - 1 {
+            assert!(!record_sync_failure(&conn, "C1", "timeout", 10).unwrap());
+        }
+        reset_sync_failures(&conn, "C1").unwrap();
+        assert_eq!(state(&conn), (1, 0, None));
+
+        for _ in 0..CIRCUIT_BREAKER_THRESHOLD - 1 {
+            assert!(!record_sync_failure(&conn, "C1", "timeout", 10).unwrap());
+        }
+        assert!(record_sync_failure(&conn, "C1", "Invalid API key", 20).unwrap());
+        assert_eq!(state(&conn), (0, CIRCUIT_BREAKER_THRESHOLD, Some("Invalid API key".to_string())));
+
+        // Already off: later failures (e.g. manual syncs) don't trip it again
+        assert!(!record_sync_failure(&conn, "C1", "Invalid API key", 30).unwrap());
+    }
+
+    #[test]
+    fn test_first_tick_delay_at_launch() {
+        let hour = Duration::from_secs(3600);
+        for _ in 0..100 {
+            let catch_up = first_tick_delay(hour, Some(true));
+            assert!(catch_up >= STARTUP_SYNC_DELAY && catch_up < STARTUP_SYNC_DELAY + MAX_START_JITTER);
+            let wait = first_tick_delay(hour, Some(false));
+            assert!(wait >= hour && wait < hour + MAX_START_JITTER);
+            assert!(first_tick_delay(hour, None) < MAX_START_JITTER);
+        }
+    }
+}
@@ -12,8 +12,11 @@ import Help from './pages/Help';
 // import OpenOrders from './pages/OpenOrders';
 import Layout from './pages/Layout';
 import { AnonymousModeProvider } from './contexts/AnonymousModeContext';
+import { useUserActivity } from './hooks/useUserActivity';
 
 function App() {
+  useUserActivity();
+
   return (
     <>
       <Toaster position="top-right" richColors />
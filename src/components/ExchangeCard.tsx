@@ -8,7 +8,7 @@ import { Switch } from '@/components/ui/switch';
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from '@/components/ui/select';
 import { Label } from '@/components/ui/label';
 import { ApiCredentialSafe } from '@/lib/api';
-import { CheckCircle2, XCircle, Loader2, Trash2, Power, RefreshCw, Clock } from 'lucide-react';
+import { CheckCircle2, XCircle, Loader2, Trash2, Power, RefreshCw, Clock, FileLock2 } from 'lucide-react';
 import { useTranslation } from 'react-i18next';
 
 interface ExchangeCardProps {
@@ -133,6 +133,12 @@ export function ExchangeCard({
                   {t('api.inactive')}
                 </Badge>
               )}
+              {credential.storage_backend === 'encrypted_file' && (
+                <Badge variant="outline" className="gap-1" title={t('api.encryptedFileStorageHint')}>
+                  <FileLock2 className="h-3 w-3" />
+                  {t('api.encryptedFileStorage')}
+                </Badge>
+              )}
             </div>
             <CardDescription className="mt-1">{credential.label}</CardDescription>
           </div>
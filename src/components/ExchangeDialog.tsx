@@ -1,6 +1,7 @@
 'use client';
 
 import { useState } from 'react';
+import { toast } from 'sonner';
 import {
   Dialog,
   DialogContent,
@@ -37,6 +38,7 @@ export function ExchangeDialog({ open, onOpenChange, onSaved }: ExchangeDialogPr
   const [apiKey, setApiKey] = useState('');
   const [apiSecret, setApiSecret] = useState('');
   const [passphrase, setPassphrase] = useState('');
+  const [lookbackDays, setLookbackDays] = useState('');
   const [isTesting, setIsTesting] = useState(false);
   const [testResult, setTestResult] = useState<'success' | 'error' | null>(null);
   const [error, setError] = useState<string | null>(null);
@@ -47,6 +49,7 @@ export function ExchangeDialog({ open, onOpenChange, onSaved }: ExchangeDialogPr
     setApiKey('');
     setApiSecret('');
     setPassphrase('');
+    setLookbackDays('');
     setTestResult(null);
     setError(null);
   };
@@ -69,24 +72,29 @@ export function ExchangeDialog({ open, onOpenChange, onSaved }: ExchangeDialogPr
         api_secret: apiSecret,
         passphrase: passphrase || undefined,
         is_active: true,
+        auto_sync_lookback_days: lookbackDays ? parseInt(lookbackDays) : undefined,
       };
 
       const savedCred = await api.saveApiCredentials(input);
 
       // Then test them
-      const isValid = await api.testApiCredentials(savedCred.id);
+      const result = await api.testApiCredentials(savedCred.id);
 
-      if (isValid) {
+      if (result.valid) {
+        result.warnings.forEach(warning => toast.warning(warning, { duration: 15000 }));
         setTestResult('success');
         setTimeout(() => {
           onSaved();
           handleClose();
         }, 1000);
       } else {
+        const { connectivity, signature } = result.diagnosis;
         setTestResult('error');
-        setError(t('api.invalidCredentials'));
-        // Delete the credential since it failed
-        await api.deleteApiCredentials(savedCred.id);
+        setError(connectivity.status === 'failed' ? connectivity.message : signature.message || t('api.invalidCredentials'));
+        // Delete the credential since it failed; an unreachable exchange says nothing about the key
+        if (connectivity.status !== 'failed') {
+          await api.deleteApiCredentials(savedCred.id);
+        }
       }
     } catch (err) {
       setTestResult('error');
@@ -96,7 +104,8 @@ export function ExchangeDialog({ open, onOpenChange, onSaved }: ExchangeDialogPr
     }
   };
 
-  const isFormValid = apiKey && apiSecret && (exchange !== 'bitget' || passphrase);
+  const isFormValid =
+    apiKey && apiSecret && (exchange !== 'bitget' || passphrase) && (!lookbackDays || parseInt(lookbackDays) > 0);
 
   return (
     <Dialog open={open} onOpenChange={handleClose}>
@@ -173,6 +182,19 @@ export function ExchangeDialog({ open, onOpenChange, onSaved }: ExchangeDialogPr
             </div>
           )}
 
+          <div className="space-y-2">
+            <Label htmlFor="lookbackDays">{t('api.autoSyncLookbackDays')}</Label>
+            <Input
+              id="lookbackDays"
+              type="number"
+              min={1}
+              placeholder={t('api.autoSyncLookbackPlaceholder')}
+              value={lookbackDays}
+              onChange={(e) => setLookbackDays(e.target.value)}
+            />
+            <p className="text-xs text-muted-foreground">{t('api.autoSyncLookbackHint')}</p>
+          </div>
+
           {error && (
             <Alert variant="destructive">
               <AlertCircle className="h-4 w-4" />
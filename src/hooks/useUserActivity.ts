@@ -0,0 +1,26 @@
+import { useEffect } from 'react';
+import { api } from '../lib/api';
+
+const ACTIVITY_EVENTS = ['pointerdown', 'keydown', 'wheel'] as const;
+// Report at most this often; auto-lock works in minutes
+const REPORT_INTERVAL_MS = 30_000;
+
+// Tell the backend about keyboard and mouse input so auto-lock measures real inactivity,
+// not the polling done by open pages
+export function useUserActivity() {
+  useEffect(() => {
+    let lastReport = 0;
+    const report = () => {
+      const now = Date.now();
+      if (now - lastReport < REPORT_INTERVAL_MS) return;
+      lastReport = now;
+      // Refused while locked; unlocking is what counts then
+      api.recordAppActivity().catch(() => {});
+    };
+
+    ACTIVITY_EVENTS.forEach((event) => window.addEventListener(event, report, { passive: true }));
+    return () => {
+      ACTIVITY_EVENTS.forEach((event) => window.removeEventListener(event, report));
+    };
+  }, []);
+}
@@ -1,4 +1,4 @@
-import { invoke } from '@tauri-apps/api/core';
+import { invoke as tauriInvoke, type InvokeArgs } from '@tauri-apps/api/core';
 
 export interface Settings {
   id: number;
@@ -9,10 +9,26 @@ export interface Settings {
   currency: string;
   enable_position_monitor: boolean;
   enable_api_connections: boolean;
+  max_consecutive_losses: number;
+  daily_loss_limit_r: number;
+  trash_retention_days: number; // 0 keeps deleted trades forever
+  sync_history_retention_days: number; // 0 keeps sync history forever
+  sync_history_max_entries: number; // per credential, 0 for no limit
+  sync_on_startup: boolean; // auto-sync shortly after launch instead of one interval later
+  mirror_stale_timeout_secs: number; // reconnect a silent live mirror after this long, 0 disables
+  require_biometric_unlock: boolean; // Touch ID before exchange API keys are read (macOS)
+  proxy_url: string | null; // http://, socks5:// or socks5h:// proxy for exchange traffic; '' in updateSettings removes it
+  breakeven_threshold: number; // closed trades within this much PnL of zero are BE
   created_at: number;
   updated_at: number;
 }
 
+export type TradeGrade = 'A' | 'B' | 'C' | 'D';
+
+export type TradeEmotion =
+  | 'CALM' | 'CONFIDENT' | 'EXCITED' | 'ANXIOUS' | 'FEARFUL' | 'GREEDY'
+  | 'FOMO' | 'FRUSTRATED' | 'BORED' | 'TIRED' | 'SATISFIED' | 'REGRETFUL';
+
 export interface Trade {
   id: string;
   pair: string;
@@ -50,7 +66,13 @@ export interface Trade {
   execution_one_r?: number;
   execution_potential_profit?: number;
   import_fingerprint?: string;
-  import_source: string; // USER_CREATED | API_IMPORT | CSV_IMPORT | LIVE_MIRROR
+  import_source: string; // USER_CREATED | API_IMPORT | CSV_IMPORT | LIVE_MIRROR | POSITION_SYNC | DEMO
+  account_id?: string;
+  grade?: TradeGrade;
+  pre_emotion?: TradeEmotion;
+  post_emotion?: TradeEmotion;
+  liquidated?: boolean; // Closed by liquidation, set by the live mirror
+  funding_pnl?: number; // Sum of linked funding payments (positive = received)
   created_at: number;
   updated_at: number;
 }
@@ -60,8 +82,22 @@ export interface TradeFilters {
   pair?: string;
   start_date?: number;
   end_date?: number;
+  tag?: string;
+  account_id?: string;
   page?: number;
   limit?: number;
+  sort_by?: TradeSortColumn; // Default trade_date
+  sort_dir?: 'asc' | 'desc'; // Default desc
+}
+
+export type TradeSortColumn = 'trade_date' | 'close_date' | 'total_pnl' | 'pnl_in_r' | 'pair' | 'status';
+
+// Omitted fields are left alone on every selected trade
+export interface BulkTradePatch {
+  exchange?: string;
+  notes?: string; // Replaces the existing notes
+  add_tag_ids?: string[];
+  remove_tag_ids?: string[];
 }
 
 export interface CreateTradeInput {
@@ -92,8 +128,67 @@ export interface CreateTradeInput {
   execution_quantity?: number;
   execution_one_r?: number;
   execution_potential_profit?: number;
+  account_id?: string;
+}
+
+export interface MetricsRecalculation {
+  trades_checked: number;
+  trades_updated: number;
+}
+
+export interface TradeRevision {
+  id: number;
+  trade_id: string;
+  before: Partial<Trade>;
+  after: Partial<Trade>;
+  changed_fields: string[];
+  changed_at: number;
+}
+
+export type TradeLinkRelation = 'RE_ENTRY' | 'HEDGE' | 'SCALE_IN';
+
+export interface TradeLink {
+  trade_id: string; // The earlier trade
+  related_trade_id: string;
+  relation: TradeLinkRelation;
+  created_at: number;
+}
+
+// Trades connected through links
+export interface TradeCampaign {
+  id: string; // First trade's id
+  trades: Trade[];
+  links: TradeLink[];
+  status: 'OPEN' | 'WIN' | 'LOSS' | 'BE';
+  total_pnl: number;
+  pnl_in_r?: number;
+  start_date: number;
+  end_date?: number; // Once every trade is closed
+}
+
+export interface Account {
+  id: string;
+  name: string;
+  exchange: string | null;
+  is_paper: boolean;
+  initial_capital: number;
+  current_r_percent: number;
+  currency: string;
+  created_at: number;
+  updated_at: number;
+}
+
+export interface CreateAccountInput {
+  name: string;
+  exchange?: string;
+  is_paper?: boolean;
+  initial_capital?: number; // Defaults to the global settings
+  current_r_percent?: number;
+  currency?: string;
 }
 
+export type UpdateAccountInput = Partial<CreateAccountInput>;
+
 export interface DashboardStats {
   total_trades: number;
   wins: number;
@@ -108,6 +203,10 @@ export interface DashboardStats {
   avg_effective_rr: number;
   best_trade: number;
   worst_trade: number;
+  expectancy_r: number;
+  r_std_dev: number;
+  sqn: number;
+  r_trade_count: number;
 }
 
 export interface EquityCurvePoint {
@@ -115,6 +214,304 @@ export interface EquityCurvePoint {
   cumulative_pnl: number;
   daily_pnl: number;
   trade_count: number;
+  cumulative_value: number;
+  daily_value: number;
+  equity: number;
+}
+
+export interface Tag {
+  id: string;
+  name: string;
+  color: string | null;
+  created_at: number;
+}
+
+export interface PlannedTradeInput {
+  pair: string;
+  exchange: string;
+  analysis_date: number;
+  portfolio_value: number;
+  r_percent: number;
+  min_rr: number;
+  planned_pe: number;
+  planned_sl: number;
+  leverage: number;
+  planned_tps: string; // JSON
+  planned_entries?: string; // JSON array of {price, percent}
+  position_type: 'LONG' | 'SHORT';
+  one_r: number;
+  margin: number;
+  position_size: number;
+  quantity: number;
+  planned_weighted_rr: number;
+  notes: string;
+  account_id?: string;
+}
+
+export interface PlannedTrade extends PlannedTradeInput {
+  id: string;
+  converted_trade_id?: string;
+  converted_at?: number; // Unset while still on the watchlist
+  created_at: number;
+  updated_at: number;
+}
+
+export interface JournalEntry {
+  id: string;
+  entry_date: string;
+  pre_market_plan: string | null;
+  post_market_review: string | null;
+  mood: number | null;
+  created_at: number;
+  updated_at: number;
+}
+
+export interface CreateJournalEntryInput {
+  entry_date: string;
+  pre_market_plan?: string;
+  post_market_review?: string;
+  mood?: number;
+}
+
+export interface UpdateJournalEntryInput {
+  pre_market_plan?: string;
+  post_market_review?: string;
+  mood?: number;
+}
+
+export interface CapitalTransaction {
+  id: string;
+  transaction_type: 'DEPOSIT' | 'WITHDRAWAL';
+  amount: number;
+  transaction_date: number;
+  notes: string | null;
+  account_id: string | null;
+  created_at: number;
+}
+
+export interface CreateCapitalTransactionInput {
+  transaction_type: 'DEPOSIT' | 'WITHDRAWAL';
+  amount: number;
+  transaction_date: number;
+  notes?: string;
+  account_id?: string;
+}
+
+export type EquityCurveMode = 'usd' | 'r' | 'percent';
+
+export interface DrawdownPoint {
+  date: string;
+  equity: number;
+  peak: number;
+  drawdown: number;
+  drawdown_pct: number;
+}
+
+export interface DrawdownStats {
+  max_drawdown: number;
+  max_drawdown_pct: number;
+  max_drawdown_duration_days: number;
+  current_drawdown: number;
+  current_drawdown_pct: number;
+  curve: DrawdownPoint[];
+}
+
+export interface AdvancedStats {
+  sharpe_ratio: number;
+  sortino_ratio: number;
+  calmar_ratio: number;
+  annualized_return_pct: number;
+  annualized_volatility_pct: number;
+  max_drawdown_pct: number;
+  risk_free_rate: number;
+  days: number;
+}
+
+export interface BucketStats {
+  bucket: string;
+  trade_count: number;
+  wins: number;
+  losses: number;
+  win_rate: number;
+  total_pnl: number;
+  avg_pnl: number;
+}
+
+export interface TimeBreakdown {
+  by_weekday: BucketStats[];
+  by_hour: BucketStats[];
+}
+
+export interface EmotionStats {
+  emotion: TradeEmotion | 'Unrecorded';
+  trade_count: number;
+  wins: number;
+  losses: number;
+  win_rate: number;
+  total_pnl: number;
+  avg_r: number;
+  r_trade_count: number;
+}
+
+export interface CampaignStats {
+  campaign_count: number;
+  linked_campaign_count: number;
+  wins: number;
+  losses: number;
+  breakevens: number;
+  win_rate: number;
+  total_pnl: number;
+  avg_pnl: number;
+  avg_r: number;
+}
+
+export interface EmotionBreakdown {
+  pre_trade: EmotionStats[];
+  post_trade: EmotionStats[];
+}
+
+export interface FundingSymbolStats {
+  symbol: string;
+  net_funding: number;
+  payments: number;
+}
+
+export interface FundingStats {
+  total_paid: number;
+  total_received: number;
+  net_funding: number;
+  payments: number;
+  unlinked_payments: number;
+  gross_pnl: number;
+  net_pnl: number;
+  by_symbol: FundingSymbolStats[];
+}
+
+export interface SourceBreakdown {
+  by_exchange: BucketStats[];
+  by_import_source: BucketStats[];
+}
+
+export interface StrategyStats {
+  strategy: string;
+  trade_count: number;
+  wins: number;
+  losses: number;
+  win_rate: number;
+  total_pnl: number;
+  avg_pnl: number;
+  expectancy_r: number;
+  r_trade_count: number;
+}
+
+export interface CalendarDay {
+  date: string;
+  total_pnl: number;
+  trade_count: number;
+  wins: number;
+  losses: number;
+  win_rate: number;
+}
+
+export interface CalendarWeek {
+  week_start: string;
+  total_pnl: number;
+  trade_count: number;
+}
+
+export interface PnlCalendar {
+  year: number;
+  month: number;
+  days: CalendarDay[];
+  weeks: CalendarWeek[];
+  total_pnl: number;
+  trade_count: number;
+}
+
+export interface KellySuggestion {
+  trade_count: number;
+  win_rate: number;
+  avg_win_r: number;
+  avg_loss_r: number;
+  payoff_ratio: number;
+  kelly_fraction: number;
+  half_kelly_fraction: number;
+  suggested_r_percent: number;
+  current_r_percent: number;
+}
+
+export interface RiskAlert {
+  rule: 'loss_streak' | 'daily_loss_limit';
+  severity: 'warning' | 'stop';
+  message: string;
+  value: number;
+  limit: number;
+  suggested_r_percent: number | null;
+}
+
+export interface RiskOfRuinInput {
+  win_rate?: number;
+  avg_win_r?: number;
+  avg_loss_r?: number;
+  risk_per_trade?: number;
+  ruin_threshold?: number;
+  date_range?: string;
+  start_ts?: number;
+  end_ts?: number;
+  account_id?: string;
+}
+
+export interface RiskOfRuinResult {
+  win_rate: number;
+  avg_win_r: number;
+  avg_loss_r: number;
+  risk_per_trade: number;
+  ruin_threshold: number;
+  sample_size: number;
+  expectancy_r: number;
+  units_to_ruin: number;
+  risk_of_ruin_pct: number;
+}
+
+export interface DemoDataInput {
+  trades?: number; // Default 300, max 2000
+  months?: number; // Months of history ending now, default 6, max 36
+  seed?: number; // Same data on every run
+}
+
+export interface DemoDataSummary {
+  trades_created: number;
+  start_date: number;
+  end_date: number;
+}
+
+export interface MonteCarloInput {
+  simulations?: number;
+  trades_per_simulation?: number;
+  risk_per_trade?: number;
+  ruin_threshold?: number;
+  date_range?: string;
+  start_ts?: number;
+  end_ts?: number;
+  account_id?: string;
+  seed?: number;
+}
+
+export interface PercentilePath {
+  percentile: number;
+  equity: number[];
+}
+
+export interface MonteCarloResult {
+  simulations: number;
+  trades_per_simulation: number;
+  sample_size: number;
+  starting_equity: number;
+  risk_per_trade: number;
+  ruin_threshold: number;
+  percentile_paths: PercentilePath[];
+  risk_of_ruin_pct: number;
+  median_max_drawdown_pct: number;
 }
 
 export interface ImportPreview {
@@ -128,14 +525,28 @@ export interface ImportPreview {
   closing_time: string;
   total_fees: number;
   fingerprint: string;
+  is_open: boolean;
 }
 
 export interface ImportResult {
   imported: number;
+  updated: number;
   duplicates: number;
   errors: string[];
 }
 
+// Overrides the delimiter / decimal mark sniffed from a CSV; unset parts are still detected
+export interface CsvFormat {
+  delimiter?: ',' | ';' | '\t';
+  decimal_comma?: boolean; // "1.234,56" numbers
+}
+
+export interface FileImportResult {
+  file_path: string;
+  format: 'bitget_csv' | 'bitget_spot_csv' | 'blofin_csv' | 'bingx_xlsx' | null;
+  result: ImportResult;
+}
+
 export interface ApiCredentialSafe {
   id: string;
   exchange: string;
@@ -145,7 +556,15 @@ export interface ApiCredentialSafe {
   last_sync_timestamp?: number;
   auto_sync_enabled: boolean;
   auto_sync_interval: number; // Interval in seconds
+  auto_sync_lookback_days?: number; // Unset = automatic syncs are not bounded
   live_mirror_enabled: boolean;
+  is_default: boolean; // Used by quick actions given no credential, one per exchange
+  // 'encrypted_file' when the keychain was unavailable as the secrets were saved
+  storage_backend: 'keychain' | 'encrypted_file';
+  environment: CredentialEnvironment; // 'demo' for a key of the exchange's paper trading account
+  base_url: string | null; // Replaces the exchange's REST base URL
+  auto_sync_paused: boolean; // Paused until resumed or the app restarts
+  auto_sync_disabled_reason: string | null; // Set when repeated failures turned auto-sync off
   created_at: number;
   updated_at: number;
 }
@@ -160,9 +579,14 @@ export interface ApiCredentialInput {
   is_active?: boolean;
   auto_sync_enabled?: boolean;
   auto_sync_interval?: number;
+  auto_sync_lookback_days?: number;
   live_mirror_enabled?: boolean;
+  environment?: CredentialEnvironment; // Defaults to 'production'
+  base_url?: string; // Empty or unset = the environment's own
 }
 
+export type CredentialEnvironment = 'production' | 'demo';
+
 export interface ApiSyncHistory {
   id: string;
   credential_id: string;
@@ -185,11 +609,133 @@ export interface SyncConfig {
   is_auto_sync?: boolean;
 }
 
+export interface ApiKeyPermissions {
+  read: boolean;
+  trade: boolean;
+  withdraw: boolean | null; // null when the exchange doesn't report it
+  raw: string[];
+}
+
+export interface CredentialTestResult {
+  valid: boolean;
+  permissions: ApiKeyPermissions | null;
+  warnings: string[];
+  diagnosis: CredentialDiagnosis;
+}
+
+export interface DiagnosticCheck {
+  status: 'ok' | 'warning' | 'failed' | 'skipped';
+  message: string;
+}
+
+export interface CredentialDiagnosis {
+  connectivity: DiagnosticCheck;
+  clock_skew: DiagnosticCheck;
+  signature: DiagnosticCheck;
+  permissions: DiagnosticCheck;
+  rate_limit: DiagnosticCheck;
+  latency_ms: number | null;
+  clock_skew_ms: number | null; // Exchange clock minus local clock
+  rate_limit_headroom: number; // Requests the app could send right now without waiting
+}
+
+export interface CredentialAccess {
+  id: string;
+  credential_id: string;
+  entry: 'api-key' | 'api-secret' | 'passphrase';
+  accessed_by: string; // App feature that read it, e.g. 'sync' or 'live_mirror'
+  success: boolean;
+  accessed_at: number;
+}
+
+export interface CredentialRotation {
+  id: string;
+  credential_id: string;
+  old_key_preview: string | null; // null when the old key could not be read
+  new_key_preview: string;
+  status: 'success' | 'failed';
+  error_message: string | null;
+  rotated_at: number;
+}
+
+export interface PublicIpSighting {
+  ip: string;
+  first_seen_at: number;
+  last_seen_at: number;
+}
+
+export interface PublicIpInfo {
+  current: string;
+  recent: PublicIpSighting[]; // Most recent first, current included
+}
+
 export interface SyncResult {
   imported: number;
   duplicates: number;
   errors: string[];
   total_pnl?: number;
+  funding_imported: number;
+  link_suggestions: TradeLinkSuggestion[];
+}
+
+// An imported trade that looks like a hand-entered one
+export interface TradeLinkSuggestion {
+  trade_id: string;
+  fill_trade_id: string;
+}
+
+// Applied to syncs and live mirroring. Symbols are compared without case or separators:
+// "BTC-USDT" matches "btcusdt"
+export interface SymbolFilters {
+  // When non-empty, only these symbols are imported
+  include: string[];
+  exclude: string[];
+}
+
+export interface SchedulerTaskStatus {
+  credential_id: string;
+  exchange: string;
+  label: string;
+  interval_secs: number;
+  running: boolean;
+  paused: boolean;
+  last_run?: number;
+  last_result?: 'SUCCESS' | 'FAILED' | 'CANCELLED';
+  last_error?: string;
+  last_imported?: number;
+  next_run?: number;
+}
+
+export interface SyncPreviewTrade {
+  trade: Trade;
+  is_duplicate: boolean;
+  matching_trade_id?: string;
+}
+
+export interface SyncPreview {
+  trades: SyncPreviewTrade[];
+  new_trades: number;
+  duplicates: number;
+  errors: string[];
+}
+
+export interface FundingPayment {
+  id: string;
+  credential_id?: string;
+  exchange: string;
+  symbol: string;
+  amount: number; // Positive = received
+  currency: string;
+  funding_time: number;
+  trade_id?: string;
+  created_at: number;
+}
+
+export interface ReconcileResult {
+  // Trade IDs
+  opened: string[];
+  closed: string[];
+  unchanged: number;
 }
 
 export interface Position {
@@ -228,10 +774,45 @@ export interface OpenOrder {
 }
 
 export interface FetchOpenOrdersRequest {
-  credential_id: string;
+  credential_id?: string; // Omitted: the default credential of exchange
+  exchange?: string;
   symbol?: string;
 }
 
+export interface BackupInfo {
+  file_name: string;
+  size_bytes: number;
+  created_at: number;
+  schema_version: number | null;
+}
+
+export interface RestoreBackupResult {
+  restored_from: string;
+  safety_backup: string; // The database as it was before the restore
+  restart_required: boolean;
+}
+
+export interface DatabaseEncryptionStatus {
+  supported: boolean; // Build includes SQLCipher
+  encrypted: boolean;
+  pending: boolean; // Encryption runs at the next restart
+}
+
+export interface DatabaseHealth {
+  integrity_ok: boolean;
+  integrity_messages: string[];
+  foreign_key_violations: { table: string; rowid?: number; parent: string }[];
+  schema_version?: number;
+  size_bytes: number;
+  free_bytes: number;
+  trades_by_source: { import_source: string; active: number; deleted: number }[];
+  orphan_fingerprints: number; // Trashed imports that block re-import
+  duplicate_fingerprints: number;
+  last_backup_at?: number;
+  last_backup_age_days?: number;
+  warnings: string[];
+}
+
 export interface LiveMirrorStatus {
   credential_id: string;
   exchange: string;
@@ -239,7 +820,115 @@ export interface LiveMirrorStatus {
   enabled: boolean;
 }
 
+export interface LiveMirrorEvent {
+  id: string;
+  credential_id: string;
+  event_type: 'OPENED' | 'UPDATED' | 'CLOSED' | 'TP_SL' | 'ERROR';
+  pos_id: string | null;
+  trade_id: string | null;
+  message: string;
+  payload: string | null; // Start of the exchange payload
+  created_at: number;
+}
+
+export interface MirrorHealth {
+  credential_id: string;
+  connected: boolean;
+  last_message_at: number | null; // Unix seconds, pongs included
+  reconnect_count: number;
+  subscribed_channels: string[];
+  last_error: string | null;
+}
+
+/** Payload of the `live-positions-update` event, emitted at most once a second */
+export interface LivePositionsUpdate {
+  credential_id: string;
+  positions: {
+    pos_id: string;
+    trade_id: string | null;
+    pair: string;
+    hold_side: string;
+    unrealized_pnl: number;
+    market_price: number;
+  }[];
+}
+
+export interface PriceAlertInput {
+  symbol: string; // Bitget USDT-M futures symbol, e.g. BTCUSDT
+  condition: 'ABOVE' | 'BELOW';
+  target_price: number;
+  note?: string;
+  plan_position_type?: 'LONG' | 'SHORT' | null; // Adds a planned trade when set
+}
+
+/** Also the payload of the `price-alert-triggered` event */
+export interface PriceAlert extends Required<Omit<PriceAlertInput, 'plan_position_type'>> {
+  id: string;
+  exchange: string;
+  plan_position_type: 'LONG' | 'SHORT' | null;
+  status: 'ACTIVE' | 'TRIGGERED';
+  triggered_at: number | null;
+  triggered_price: number | null;
+  planned_trade_id: string | null;
+  created_at: number;
+  updated_at: number;
+}
+
+export interface AppLockStatus {
+  enabled: boolean; // A master password is set
+  locked: boolean; // Every other command is refused until unlocked
+  auto_lock_minutes: number; // 0 disables auto-lock; an `app-locked` event fires when it locks
+}
+
+export type CommandErrorKind =
+  | 'NotFound'
+  | 'Validation'
+  | 'RateLimited'
+  | 'Auth'
+  | 'Locked'
+  | 'Database'
+  | 'Network'
+  | 'Exchange'
+  | 'Cancelled'
+  | 'Internal';
+
+/** Error every command rejects with; `kind` tells what went wrong without reading the message */
+export class CommandError extends Error {
+  readonly kind: CommandErrorKind;
+
+  constructor(kind: CommandErrorKind, message: string) {
+    super(message);
+    this.kind = kind;
+    this.name = 'CommandError';
+  }
+
+  /** The message alone, so errors shown with String(error) or `${error}` read as before */
+  toString() {
+    return this.message;
+  }
+}
+
+const isCommandErrorPayload = (error: unknown): error is { kind: CommandErrorKind; message: string } =>
+  typeof error === 'object' && error !== null && 'kind' in error && 'message' in error;
+
+async function invoke<T>(command: string, args?: InvokeArgs): Promise<T> {
+  try {
+    return await tauriInvoke<T>(command, args);
+  } catch (error) {
+    throw isCommandErrorPayload(error) ? new CommandError(error.kind, error.message) : error;
+  }
+}
+
 // API functions
+/** Preset range ("week", "month", …) or explicit close-date bounds in unix seconds */
+export type StatsDateRange = string | { startTs?: number; endTs?: number };
+
+/** Stats commands pool all accounts unless `accountId` is given */
+const dateRangeArgs = (range?: StatsDateRange, accountId?: string) => ({
+  ...(typeof range === 'object' ? { startTs: range.startTs, endTs: range.endTs } : { dateRange: range }),
+  accountId,
+});
+
 export const api = {
   // Settings
   getSettings: () => invoke<Settings>('get_settings'),
@@ -247,42 +936,166 @@ export const api = {
 
   // Trades
   getTrades: (filters?: TradeFilters) => invoke<Trade[]>('get_trades', { filters }),
+  // Ignores page/limit; pages = Math.ceil(count / limit)
+  countTrades: (filters?: TradeFilters) => invoke<number>('count_trades', { filters }),
+  // Pair, notes or trade ID; newest 200 matches
+  searchTrades: (query: string) => invoke<Trade[]>('search_trades', { query }),
   getTrade: (id: string) => invoke<Trade>('get_trade', { id }),
   createTrade: (trade: CreateTradeInput) => invoke<Trade>('create_trade', { trade }),
   updateTrade: (id: string, tradeUpdate: Partial<Trade>) => invoke<Trade>('update_trade', { id, tradeUpdate }),
+  bulkUpdateTrades: (ids: string[], patch: BulkTradePatch) =>
+    invoke<number>('bulk_update_trades', { ids, patch }),
+  // Exit percents must add up to 100; PnL, R, effective RR and status are computed server-side
+  closeTrade: (id: string, exits: { price: number; percent: number }[], closeDate: number, fees?: number) =>
+    invoke<Trade>('close_trade', { id, exits, closeDate, fees }),
   deleteTrade: (id: string) => invoke<void>('delete_trade', { id }),
   getDeletedTrades: () => invoke<Trade[]>('get_deleted_trades'),
   restoreTrade: (id: string) => invoke<void>('restore_trade', { id }),
+  purgeTrade: (id: string) => invoke<void>('purge_trade', { id }),
+  deleteTrades: (ids: string[]) => invoke<number>('delete_trades', { ids }),
+  restoreTrades: (ids: string[]) => invoke<number>('restore_trades', { ids }),
+  purgeTrades: (ids: string[]) => invoke<number>('purge_trades', { ids }),
+  emptyTrash: () => invoke<number>('empty_trash'),
+  linkTrades: (tradeId: string, relatedTradeId: string, relation: TradeLinkRelation) =>
+    invoke<TradeLink>('link_trades', { tradeId, relatedTradeId, relation }),
+  unlinkTrades: (tradeId: string, relatedTradeId: string) =>
+    invoke<void>('unlink_trades', { tradeId, relatedTradeId }),
+  getTradeLinks: (tradeId: string) => invoke<TradeLink[]>('get_trade_links', { tradeId }),
+  getTradeCampaign: (tradeId: string) => invoke<TradeCampaign>('get_trade_campaign', { tradeId }),
+  // pnl_in_r, effective RR, WIN/LOSS/BE status, margin and position size; page/limit ignored
+  recalculateTradeMetrics: (filters?: TradeFilters) =>
+    invoke<MetricsRecalculation>('recalculate_trade_metrics', { filters }),
+  getTradeHistory: (tradeId: string) => invoke<TradeRevision[]>('get_trade_history', { tradeId }),
+  revertTradeRevision: (revisionId: number) =>
+    invoke<Trade>('revert_trade_revision', { revisionId }),
   duplicateTrade: (id: string) => invoke<Trade>('duplicate_trade', { id }),
+  setTradeGrade: (id: string, grade: TradeGrade | null) => invoke<Trade>('set_trade_grade', { id, grade }),
+  setTradeEmotions: (id: string, preEmotion: TradeEmotion | null, postEmotion: TradeEmotion | null) =>
+    invoke<Trade>('set_trade_emotions', { id, preEmotion, postEmotion }),
 
   // Debug commands
   getAllTradesIncludingDeleted: () => invoke<{ total: number; deleted: number; active: number }>('get_all_trades_including_deleted'),
   restoreAllTrades: () => invoke<number>('restore_all_trades'),
   deleteAllTrades: () => invoke<number>('delete_all_trades'),
 
+  // Demo data
+  generateDemoData: (input?: DemoDataInput) => invoke<DemoDataSummary>('generate_demo_data', { input }),
+  clearDemoData: () => invoke<number>('clear_demo_data'),
+
+  // Accounts
+  getAccounts: () => invoke<Account[]>('get_accounts'),
+  getAccount: (id: string) => invoke<Account>('get_account', { id }),
+  createAccount: (account: CreateAccountInput) => invoke<Account>('create_account', { account }),
+  updateAccount: (id: string, account: UpdateAccountInput) => invoke<Account>('update_account', { id, account }),
+  deleteAccount: (id: string) => invoke<void>('delete_account', { id }),
+
   // Stats
-  getDashboardStats: (dateRange?: string) => invoke<DashboardStats>('get_dashboard_stats', { date_range: dateRange }),
-  getEquityCurve: (dateRange?: string) => invoke<EquityCurvePoint[]>('get_equity_curve', { date_range: dateRange }),
+  getDashboardStats: (range?: StatsDateRange, accountId?: string) =>
+    invoke<DashboardStats>('get_dashboard_stats', dateRangeArgs(range, accountId)),
+  getEquityCurve: (range?: StatsDateRange, mode?: EquityCurveMode, accountId?: string) =>
+    invoke<EquityCurvePoint[]>('get_equity_curve', { ...dateRangeArgs(range, accountId), mode }),
+  getDrawdown: (range?: StatsDateRange, accountId?: string) =>
+    invoke<DrawdownStats>('get_drawdown', dateRangeArgs(range, accountId)),
+  getAdvancedStats: (range?: StatsDateRange, riskFreeRate?: number, accountId?: string) =>
+    invoke<AdvancedStats>('get_advanced_stats', { ...dateRangeArgs(range, accountId), riskFreeRate }),
+  getTimeBreakdown: (range?: StatsDateRange, utcOffsetMinutes?: number, accountId?: string) =>
+    invoke<TimeBreakdown>('get_time_breakdown', { ...dateRangeArgs(range, accountId), utcOffsetMinutes }),
+  getSourceBreakdown: (range?: StatsDateRange, accountId?: string) =>
+    invoke<SourceBreakdown>('get_source_breakdown', dateRangeArgs(range, accountId)),
+  getFundingStats: (range?: StatsDateRange, accountId?: string) =>
+    invoke<FundingStats>('get_funding_stats', dateRangeArgs(range, accountId)),
+  getFundingPayments: (tradeId?: string) =>
+    invoke<FundingPayment[]>('get_funding_payments', { tradeId }),
+  getStrategyStats: (range?: StatsDateRange, accountId?: string) =>
+    invoke<StrategyStats[]>('get_strategy_stats', dateRangeArgs(range, accountId)),
+  getTagStats: (range?: StatsDateRange, accountId?: string) =>
+    invoke<BucketStats[]>('get_tag_stats', dateRangeArgs(range, accountId)),
+  getGradeStats: (range?: StatsDateRange, accountId?: string) =>
+    invoke<BucketStats[]>('get_grade_stats', dateRangeArgs(range, accountId)),
+  getEmotionStats: (range?: StatsDateRange, accountId?: string) =>
+    invoke<EmotionBreakdown>('get_emotion_stats', dateRangeArgs(range, accountId)),
+  getCampaignStats: (range?: StatsDateRange, accountId?: string) =>
+    invoke<CampaignStats>('get_campaign_stats', dateRangeArgs(range, accountId)),
+  getPnlCalendar: (year: number, month: number, utcOffsetMinutes?: number, accountId?: string) =>
+    invoke<PnlCalendar>('get_pnl_calendar', { year, month, utcOffsetMinutes, accountId }),
+  getKellySuggestion: (range?: StatsDateRange, accountId?: string) =>
+    invoke<KellySuggestion>('get_kelly_suggestion', dateRangeArgs(range, accountId)),
+  runMonteCarlo: (input: MonteCarloInput) => invoke<MonteCarloResult>('run_monte_carlo', { input }),
+  getRiskAlerts: (utcOffsetMinutes?: number, notify?: boolean, accountId?: string) =>
+    invoke<RiskAlert[]>('get_risk_alerts', { utcOffsetMinutes, notify, accountId }),
+  getCapitalTransactions: (accountId?: string) =>
+    invoke<CapitalTransaction[]>('get_capital_transactions', { accountId }),
+  createCapitalTransaction: (transaction: CreateCapitalTransactionInput) =>
+    invoke<CapitalTransaction>('create_capital_transaction', { transaction }),
+  deleteCapitalTransaction: (id: string) => invoke<void>('delete_capital_transaction', { id }),
+  getTags: () => invoke<Tag[]>('get_tags'),
+  createTag: (name: string, color?: string) => invoke<Tag>('create_tag', { tag: { name, color } }),
+  deleteTag: (id: string) => invoke<void>('delete_tag', { id }),
+  assignTag: (tradeId: string, tagId: string) => invoke<void>('assign_tag', { tradeId, tagId }),
+  removeTag: (tradeId: string, tagId: string) => invoke<void>('remove_tag', { tradeId, tagId }),
+  getTradeTags: (tradeId: string) => invoke<Tag[]>('get_trade_tags', { tradeId }),
+  getJournalEntries: (startDate?: string, endDate?: string) =>
+    invoke<JournalEntry[]>('get_journal_entries', { startDate, endDate }),
+  getJournalEntry: (id: string) => invoke<JournalEntry>('get_journal_entry', { id }),
+  createJournalEntry: (entry: CreateJournalEntryInput) => invoke<JournalEntry>('create_journal_entry', { entry }),
+  updateJournalEntry: (id: string, entry: UpdateJournalEntryInput) =>
+    invoke<JournalEntry>('update_journal_entry', { id, entry }),
+  deleteJournalEntry: (id: string) => invoke<void>('delete_journal_entry', { id }),
+  linkJournalTrade: (entryId: string, tradeId: string) => invoke<void>('link_journal_trade', { entryId, tradeId }),
+  unlinkJournalTrade: (entryId: string, tradeId: string) =>
+    invoke<void>('unlink_journal_trade', { entryId, tradeId }),
+  linkJournalDayTrades: (entryId: string, utcOffsetMinutes?: number) =>
+    invoke<number>('link_journal_day_trades', { entryId, utcOffsetMinutes }),
+  getJournalEntryTrades: (entryId: string) => invoke<Trade[]>('get_journal_entry_trades', { entryId }),
+
+  // Planned trades (watchlist)
+  getPlannedTrades: (includeConverted?: boolean) =>
+    invoke<PlannedTrade[]>('get_planned_trades', { includeConverted }),
+  getPlannedTrade: (id: string) => invoke<PlannedTrade>('get_planned_trade', { id }),
+  createPlannedTrade: (plannedTrade: PlannedTradeInput) =>
+    invoke<PlannedTrade>('create_planned_trade', { plannedTrade }),
+  updatePlannedTrade: (id: string, plannedTrade: PlannedTradeInput) =>
+    invoke<PlannedTrade>('update_planned_trade', { id, plannedTrade }),
+  deletePlannedTrade: (id: string) => invoke<void>('delete_planned_trade', { id }),
+  convertToTrade: (id: string, tradeDate?: number) => invoke<Trade>('convert_to_trade', { id, tradeDate }),
+  getRiskOfRuin: (input: RiskOfRuinInput = {}) => invoke<RiskOfRuinResult>('get_risk_of_ruin', { input }),
 
   // Import/Export
-  previewBitgetImport: (csvContent: string, portfolio: number, rPercent: number) =>
-    invoke<ImportPreview[]>('preview_bitget_import', { csvContent, portfolio, rPercent }),
-  importBitgetCsv: (csvContent: string, portfolio: number, rPercent: number) =>
-    invoke<ImportResult>('import_bitget_csv', { csvContent, portfolio, rPercent }),
+  previewBitgetImport: (csvContent: string, portfolio: number, rPercent: number, csvFormat?: CsvFormat) =>
+    invoke<ImportPreview[]>('preview_bitget_import', { csvContent, portfolio, rPercent, csvFormat }),
+  importBitgetCsv: (csvContent: string, portfolio: number, rPercent: number, mergeExisting?: boolean, csvFormat?: CsvFormat) =>
+    invoke<ImportResult>('import_bitget_csv', { csvContent, portfolio, rPercent, mergeExisting, csvFormat }),
   deleteBitgetTrades: () => invoke<number>('delete_bitget_trades'),
-  previewBlofinImport: (csvContent: string, portfolio: number, rPercent: number) =>
-    invoke<ImportPreview[]>('preview_blofin_import', { csvContent, portfolio, rPercent }),
-  importBlofinCsv: (csvContent: string, portfolio: number, rPercent: number) =>
-    invoke<ImportResult>('import_blofin_csv', { csvContent, portfolio, rPercent }),
+  previewBitgetSpotImport: (csvContent: string, portfolio: number, rPercent: number, csvFormat?: CsvFormat) =>
+    invoke<ImportPreview[]>('preview_bitget_spot_import', { csvContent, portfolio, rPercent, csvFormat }),
+  importBitgetSpotCsv: (csvContent: string, portfolio: number, rPercent: number, mergeExisting?: boolean, csvFormat?: CsvFormat) =>
+    invoke<ImportResult>('import_bitget_spot_csv', { csvContent, portfolio, rPercent, mergeExisting, csvFormat }),
+  previewBlofinImport: (csvContent: string, portfolio: number, rPercent: number, includeOpen?: boolean, csvFormat?: CsvFormat) =>
+    invoke<ImportPreview[]>('preview_blofin_import', { csvContent, portfolio, rPercent, includeOpen, csvFormat }),
+  importBlofinCsv: (csvContent: string, portfolio: number, rPercent: number, mergeExisting?: boolean, includeOpen?: boolean, csvFormat?: CsvFormat) =>
+    invoke<ImportResult>('import_blofin_csv', { csvContent, portfolio, rPercent, mergeExisting, includeOpen, csvFormat }),
   deleteBlofinTrades: () => invoke<number>('delete_blofin_trades'),
   // BingX: sends file path (xlsx), not text content
-  previewBingxImport: (filePath: string, portfolio: number, rPercent: number) =>
-    invoke<ImportPreview[]>('preview_bingx_import', { filePath, portfolio, rPercent }),
-  importBingxFile: (filePath: string, portfolio: number, rPercent: number) =>
-    invoke<ImportResult>('import_bingx_file', { filePath, portfolio, rPercent }),
+  previewBingxImport: (filePath: string, portfolio: number, rPercent: number, includeOpen?: boolean) =>
+    invoke<ImportPreview[]>('preview_bingx_import', { filePath, portfolio, rPercent, includeOpen }),
+  importBingxFile: (filePath: string, portfolio: number, rPercent: number, mergeExisting?: boolean, includeOpen?: boolean) =>
+    invoke<ImportResult>('import_bingx_file', { filePath, portfolio, rPercent, mergeExisting, includeOpen }),
   deleteBingxTrades: () => invoke<number>('delete_bingx_trades'),
-  exportAllData: () => invoke<string>('export_all_data'),
-  importAllData: (jsonData: string) => invoke<[number, number]>('import_all_data', { jsonData }),
+  // Batch import: format is auto-detected per file
+  importFiles: (filePaths: string[], portfolio: number, rPercent: number, mergeExisting?: boolean, includeOpen?: boolean, csvFormat?: CsvFormat) =>
+    invoke<FileImportResult[]>('import_files', { filePaths, portfolio, rPercent, mergeExisting, includeOpen, csvFormat }),
+  // Encrypted with AES-256-GCM when a password is given; importing it needs the same password
+  exportAllData: (password?: string) => invoke<string>('export_all_data', { password }),
+  importAllData: (jsonData: string, password?: string) =>
+    invoke<[number, number]>('import_all_data', { jsonData, password }),
+  listBackups: () => invoke<BackupInfo[]>('list_backups'),
+  restoreBackup: (fileName: string) => invoke<RestoreBackupResult>('restore_backup', { fileName }),
+  rollbackDatabase: (targetVersion: number) => invoke<number>('rollback_database', { targetVersion }),
+  restartApp: () => invoke<void>('restart_app'),
+  getDatabaseEncryptionStatus: () => invoke<DatabaseEncryptionStatus>('get_database_encryption_status'),
+  enableDatabaseEncryption: (passphrase?: string) =>
+    invoke<DatabaseEncryptionStatus>('enable_database_encryption', { passphrase }),
+  checkDatabaseHealth: () => invoke<DatabaseHealth>('check_database_health'),
 
   // API Credentials
   saveApiCredentials: (input: ApiCredentialInput) =>
@@ -290,25 +1103,60 @@ export const api = {
   listApiCredentials: () =>
     invoke<ApiCredentialSafe[]>('list_api_credentials'),
   testApiCredentials: (credentialId: string) =>
-    invoke<boolean>('test_api_credentials', { credentialId }),
+    invoke<CredentialTestResult>('test_api_credentials', { credentialId }),
+  // Tests the new key first; the old one is kept unless the test passes
+  rotateApiCredentials: (credentialId: string, apiKey: string, apiSecret: string, passphrase?: string) =>
+    invoke<ApiCredentialSafe>('rotate_api_credentials', { credentialId, apiKey, apiSecret, passphrase }),
+  getCredentialRotations: (credentialId: string) =>
+    invoke<CredentialRotation[]>('get_credential_rotations', { credentialId }),
   deleteApiCredentials: (credentialId: string) =>
     invoke<void>('delete_api_credentials', { credentialId }),
+  setDefaultApiCredential: (credentialId: string) =>
+    invoke<void>('set_default_api_credential', { credentialId }),
+  getCredentialAccessLog: (credentialId?: string, limit?: number) =>
+    invoke<CredentialAccess[]>('get_credential_access_log', { credentialId, limit }),
+  // Public IP to whitelist on exchange API keys
+  getPublicIp: () => invoke<PublicIpInfo>('get_public_ip'),
   updateApiCredentialsStatus: (credentialId: string, isActive: boolean) =>
     invoke<void>('update_api_credentials_status', { credentialId, isActive }),
 
   // API Sync
   getSyncHistory: (credentialId: string) =>
     invoke<ApiSyncHistory[]>('get_sync_history', { credentialId }),
+  // Clears one credential's history, or all of it when credentialId is omitted
+  clearSyncHistory: (credentialId?: string) =>
+    invoke<number>('clear_sync_history', { credentialId }),
   syncExchangeTrades: (config: SyncConfig) =>
     invoke<SyncResult>('sync_exchange_trades', { config }),
+  // Fetches and maps trades without importing them
+  previewExchangeSync: (config: SyncConfig) =>
+    invoke<SyncPreview>('preview_exchange_sync', { config }),
+  // Moves the imported trade's fills and PnL onto the hand-entered trade and trashes the import
+  linkTradeToFill: (tradeId: string, fillTradeId: string) =>
+    invoke<Trade>('link_trade_to_fill', { tradeId, fillTradeId }),
+  // Resolves to false when no sync was running for the credential
+  cancelSync: (credentialId: string) => invoke<boolean>('cancel_sync', { credentialId }),
   updateAutoSyncSettings: (credentialId: string, autoSyncEnabled: boolean, autoSyncInterval: number) =>
     invoke<void>('update_auto_sync_settings', { credentialId, autoSyncEnabled, autoSyncInterval }),
+  getSyncSymbolFilters: (credentialId: string) =>
+    invoke<SymbolFilters>('get_sync_symbol_filters', { credentialId }),
+  setSyncSymbolFilters: (credentialId: string, filters: SymbolFilters) =>
+    invoke<SymbolFilters>('set_sync_symbol_filters', { credentialId, filters }),
   reloadSyncScheduler: () =>
     invoke<void>('reload_sync_scheduler'),
+  pauseAutoSync: (credentialId: string) => invoke<void>('pause_auto_sync', { credentialId }),
+  resumeAutoSync: (credentialId: string) => invoke<void>('resume_auto_sync', { credentialId }),
+  getSchedulerStatus: () => invoke<SchedulerTaskStatus[]>('get_scheduler_status'),
+  // Turns auto-sync back on after repeated failures disabled it
+  reenableAutoSync: (credentialId: string) => invoke<void>('reenable_auto_sync', { credentialId }),
 
   // Positions
-  fetchCurrentPositions: (credentialId: string) =>
-    invoke<Position[]>('fetch_current_positions', { credentialId }),
+  // Without a credential ID, the default credential of the exchange is used
+  fetchCurrentPositions: (credentialId?: string, exchange?: string) =>
+    invoke<Position[]>('fetch_current_positions', { credentialId, exchange }),
+  // Creates OPEN trades for untracked positions and closes reconciled trades whose position is gone
+  reconcilePositions: (credentialId?: string, exchange?: string) =>
+    invoke<ReconcileResult>('reconcile_positions', { credentialId, exchange }),
 
   // Open Orders
   fetchOpenOrders: (request: FetchOpenOrdersRequest) =>
@@ -323,6 +1171,27 @@ export const api = {
     invoke<boolean>('is_live_mirroring_active', { credentialId }),
   toggleLiveMirroring: (credentialId: string, enabled: boolean) =>
     invoke<void>('toggle_live_mirroring', { credentialId, enabled }),
+  getMirrorHealth: () =>
+    invoke<MirrorHealth[]>('get_mirror_health'),
   getLiveMirroringStatus: () =>
     invoke<LiveMirrorStatus[]>('get_live_mirroring_status'),
+  // Newest first; all credentials when credentialId is omitted
+  getLiveMirrorEvents: (credentialId?: string, limit?: number) =>
+    invoke<LiveMirrorEvent[]>('get_live_mirror_events', { credentialId, limit }),
+
+  // Price Alerts
+  getPriceAlerts: () => invoke<PriceAlert[]>('get_price_alerts'),
+  createPriceAlert: (alert: PriceAlertInput) => invoke<PriceAlert>('create_price_alert', { alert }),
+  deletePriceAlert: (id: string) => invoke<void>('delete_price_alert', { id }),
+
+  // App Lock
+  getAppLockStatus: () => invoke<AppLockStatus>('get_app_lock_status'),
+  unlockApp: (password: string) => invoke<AppLockStatus>('unlock_app', { password }),
+  lockApp: () => invoke<AppLockStatus>('lock_app'),
+  // Only user input counts toward auto-lock; other commands don't
+  recordAppActivity: () => invoke<void>('record_app_activity'),
+  // Pass newPassword null to remove the lock
+  setAppLockPassword: (currentPassword: string | null, newPassword: string | null) =>
+    invoke<AppLockStatus>('set_app_lock_password', { currentPassword, newPassword }),
+  setAutoLockMinutes: (minutes: number) => invoke<AppLockStatus>('set_auto_lock_minutes', { minutes }),
 };
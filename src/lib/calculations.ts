@@ -193,12 +193,15 @@ export function calculateEffectiveRR(
 }
 
 /**
- * Determine trade result based on exits
+ * Determine trade result based on exits; PnL within the settings' breakeven_threshold is BE
  */
-export function determineResult(totalExitPercent: number, totalPnL: number): 'OPEN' | 'WIN' | 'LOSS' | 'BE' {
+export function determineResult(
+  totalExitPercent: number,
+  totalPnL: number,
+  breakevenThreshold: number
+): 'OPEN' | 'WIN' | 'LOSS' | 'BE' {
   if (totalExitPercent === 0) return 'OPEN';
-  // Use $0.50 threshold for break-even classification (matches TradeDetail logic)
-  if (Math.abs(totalPnL) <= 0.5) return 'BE';
+  if (Math.abs(totalPnL) <= breakevenThreshold) return 'BE';
   if (totalPnL > 0) return 'WIN';
   return 'LOSS';
 }
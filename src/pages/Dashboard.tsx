@@ -71,6 +71,18 @@ export default function Dashboard() {
         ? closedTrades.reduce((sum, t) => sum + (t.effective_weighted_rr || 0), 0) / closedTrades.length
         : 0;
 
+      // Expectancy / SQN from R-multiples (same definition as the backend)
+      const rValues = closedTrades
+        .map(t => t.pnl_in_r)
+        .filter((r): r is number => r !== null && r !== undefined);
+      const expectancyR = rValues.length > 0
+        ? rValues.reduce((sum, r) => sum + r, 0) / rValues.length
+        : 0;
+      const rStdDev = rValues.length > 1
+        ? Math.sqrt(rValues.reduce((sum, r) => sum + (r - expectancyR) ** 2, 0) / (rValues.length - 1))
+        : 0;
+      const sqn = rStdDev > 0 ? Math.sqrt(Math.min(rValues.length, 100)) * expectancyR / rStdDev : 0;
+
       const filteredStats: DashboardStats = {
         total_trades: filteredTrades.length,
         wins,
@@ -85,6 +97,10 @@ export default function Dashboard() {
         avg_effective_rr: avgEffectiveRR,
         best_trade: bestTrade,
         worst_trade: worstTrade,
+        expectancy_r: expectancyR,
+        r_std_dev: rStdDev,
+        sqn,
+        r_trade_count: rValues.length,
       };
 
       // Filter equity curve by date
@@ -80,11 +80,15 @@ export default function Settings() {
   const handleTestCredentials = async (id: string) => {
     setTestingCredentialId(id);
     try {
-      const isValid = await api.testApiCredentials(id);
-      if (isValid) {
+      const result = await api.testApiCredentials(id);
+      if (result.valid) {
         toast.success(t('settings.connectionSuccessful'));
+        result.warnings.forEach(warning => toast.warning(warning, { duration: 15000 }));
       } else {
-        toast.error(t('settings.connectionFailed'));
+        const failed = Object.values(result.diagnosis).find(
+          check => typeof check === 'object' && check?.status === 'failed'
+        );
+        toast.error(failed ? `${t('settings.connectionFailed')}: ${failed.message}` : t('settings.connectionFailed'));
       }
     } catch (error) {
       console.error('Failed to test credentials:', error);
@@ -89,6 +89,9 @@ export default function TradeDetail() {
   const [executionQuantity, setExecutionQuantity] = useState(0);
   const [executionOneR, setExecutionOneR] = useState(0);
 
+  // Closed trades within this much PnL of zero are BE (from settings)
+  const [breakevenThreshold, setBreakevenThreshold] = useState(0.5);
+
   // Calculate current metrics (memoized) - must be before early returns
   const { executionMetrics, executionValid, validExits, totalExitPercent } = useMemo(() => {
     // Handle null trade case safely
@@ -196,6 +199,12 @@ export default function TradeDetail() {
     }
   }, [id]);
 
+  useEffect(() => {
+    api.getSettings()
+      .then((settings) => setBreakevenThreshold(settings.breakeven_threshold))
+      .catch((error) => console.error('Failed to load settings:', error));
+  }, []);
+
   const loadTrade = async (tradeId: string) => {
     try {
       const data = await api.getTrade(tradeId);
@@ -345,7 +354,7 @@ export default function TradeDetail() {
             effectiveRR = metrics.effectiveRR;
 
             // Determine status based on P&L
-            if (Math.abs(metrics.totalPnl) <= 0.5) {
+            if (Math.abs(metrics.totalPnl) <= breakevenThreshold) {
               // P&L is near zero - this is break-even
               newStatus = 'BE';
             } else if (metrics.totalPnl > 0) {